@@ -1,5 +1,6 @@
 mod connection;
 mod diff;
+mod input_request;
 mod mention;
 mod terminal;
 
@@ -7,6 +8,7 @@ use agent_settings::AgentSettings;
 use collections::HashSet;
 pub use connection::*;
 pub use diff::*;
+pub use input_request::*;
 use language::language_settings::FormatOnSave;
 pub use mention::*;
 use project::lsp_store::{FormatTrigger, LspFormatTarget};
@@ -47,9 +49,9 @@ pub struct UserMessage {
     pub indented: bool,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Checkpoint {
-    git_checkpoint: GitStoreCheckpoint,
+    pub git_checkpoint: GitStoreCheckpoint,
     pub show: bool,
 }
 
@@ -436,6 +438,11 @@ pub enum ToolCallStatus {
         options: Vec<acp::PermissionOption>,
         respond_tx: oneshot::Sender<acp::PermissionOptionId>,
     },
+    /// The tool call is running, and waiting for the user to fill in a form.
+    WaitingForInput {
+        request: Entity<InputRequest>,
+        respond_tx: oneshot::Sender<InputResponse>,
+    },
     /// The tool call is currently running.
     InProgress,
     /// The tool call completed successfully.
@@ -468,6 +475,7 @@ impl Display for ToolCallStatus {
             match self {
                 ToolCallStatus::Pending => "Pending",
                 ToolCallStatus::WaitingForConfirmation { .. } => "Waiting for confirmation",
+                ToolCallStatus::WaitingForInput { .. } => "Waiting for input",
                 ToolCallStatus::InProgress => "In Progress",
                 ToolCallStatus::Completed => "Completed",
                 ToolCallStatus::Failed => "Failed",
@@ -876,6 +884,7 @@ pub enum AcpThreadEvent {
     EntryUpdated(usize),
     EntriesRemoved(Range<usize>),
     ToolAuthorizationRequired,
+    ToolInputRequired,
     Retry(RetryStatus),
     Stopped,
     Error,
@@ -1656,6 +1665,44 @@ impl AcpThread {
         cx.emit(AcpThreadEvent::EntryUpdated(ix));
     }
 
+    /// Asks the user to fill in a form for a running tool call, resolving with their response,
+    /// or with [`InputResponse::Cancel`] if the tool call stops waiting for it first.
+    pub fn request_tool_call_input(
+        &mut self,
+        id: acp::ToolCallId,
+        request: InputRequest,
+        cx: &mut Context<Self>,
+    ) -> Result<BoxFuture<'static, InputResponse>> {
+        let (ix, call) = self.tool_call_mut(&id).context("Tool call not found")?;
+        let (tx, rx) = oneshot::channel();
+        call.status = ToolCallStatus::WaitingForInput {
+            request: cx.new(|_| request),
+            respond_tx: tx,
+        };
+        cx.emit(AcpThreadEvent::EntryUpdated(ix));
+        cx.emit(AcpThreadEvent::ToolInputRequired);
+
+        Ok(async { rx.await.unwrap_or(InputResponse::Cancel) }.boxed())
+    }
+
+    pub fn respond_to_tool_call_input(
+        &mut self,
+        id: acp::ToolCallId,
+        response: InputResponse,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((ix, call)) = self.tool_call_mut(&id) else {
+            return;
+        };
+        match mem::replace(&mut call.status, ToolCallStatus::InProgress) {
+            ToolCallStatus::WaitingForInput { respond_tx, .. } => {
+                respond_tx.send(response).ok();
+                cx.emit(AcpThreadEvent::EntryUpdated(ix));
+            }
+            status => call.status = status,
+        }
+    }
+
     pub fn first_tool_awaiting_confirmation(&self) -> Option<&ToolCall> {
         let mut first_tool_call = None;
 
@@ -1900,6 +1947,7 @@ impl AcpThread {
                     call.status,
                     ToolCallStatus::Pending
                         | ToolCallStatus::WaitingForConfirmation { .. }
+                        | ToolCallStatus::WaitingForInput { .. }
                         | ToolCallStatus::InProgress
                 );
 
@@ -2034,6 +2082,32 @@ impl AcpThread {
         })
     }
 
+    /// The checkpoints of the user messages, to carry them over to a fork of this thread.
+    pub fn checkpoints(&self) -> Vec<(UserMessageId, Checkpoint)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let message = entry.user_message()?;
+                Some((message.id.clone()?, message.checkpoint.clone()?))
+            })
+            .collect()
+    }
+
+    /// Gives the user messages with the given IDs their checkpoints, for threads that were forked
+    /// from another one.
+    pub fn set_checkpoints(
+        &mut self,
+        checkpoints: Vec<(UserMessageId, Checkpoint)>,
+        cx: &mut Context<Self>,
+    ) {
+        for (id, checkpoint) in checkpoints {
+            if let Some((ix, message)) = self.user_message_mut(&id) {
+                message.checkpoint = Some(checkpoint);
+                cx.emit(AcpThreadEvent::EntryUpdated(ix));
+            }
+        }
+    }
+
     fn last_user_message(&mut self) -> Option<(usize, &mut UserMessage)> {
         self.entries
             .iter_mut()
@@ -0,0 +1,292 @@
+use anyhow::{Context as _, Result, anyhow};
+use serde_json::{Map, Value};
+
+/// Structured input that a tool asks the user for while it runs, like a context server's
+/// elicitation request.
+#[derive(Debug)]
+pub struct InputRequest {
+    /// The name of whoever asks for the input, like the context server's.
+    pub requester: String,
+    pub message: String,
+    pub fields: Vec<InputField>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputField {
+    /// The key of the field's value in the response.
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub required: bool,
+    pub kind: InputFieldKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputFieldKind {
+    Text {
+        default: Option<String>,
+    },
+    Number {
+        integer: bool,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+        default: Option<f64>,
+    },
+    Boolean {
+        default: bool,
+    },
+    /// One of the given strings, shown with their labels.
+    Choice {
+        options: Vec<String>,
+        labels: Vec<String>,
+        default: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputResponse {
+    /// The user submitted these values, keyed by field name.
+    Accept(Map<String, Value>),
+    /// The user chose not to give the input.
+    Decline,
+    /// The user dismissed the request without choosing, like by canceling the turn.
+    Cancel,
+}
+
+impl InputRequest {
+    /// Reads the fields from a flat object schema, whose properties are strings, numbers,
+    /// booleans or string enums, as MCP elicitation requests use.
+    pub fn from_schema(requester: String, message: String, schema: &Value) -> Result<Self> {
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| {
+                required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let properties = match schema.get("properties") {
+            Some(properties) => properties
+                .as_object()
+                .context("The schema's properties must be an object")?,
+            None => &Map::new(),
+        };
+
+        let fields = properties
+            .iter()
+            .map(|(name, property)| {
+                Ok(InputField {
+                    name: name.clone(),
+                    title: string(property, "title"),
+                    description: string(property, "description"),
+                    required: required.contains(&name.as_str()),
+                    kind: InputFieldKind::from_schema(property)
+                        .with_context(|| format!("Unsupported schema for `{name}`"))?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            requester,
+            message,
+            fields,
+        })
+    }
+}
+
+impl InputFieldKind {
+    fn from_schema(property: &Value) -> Result<Self> {
+        let kind = property.get("type").and_then(Value::as_str);
+        if let Some(options) = property.get("enum").and_then(Value::as_array) {
+            let options = options
+                .iter()
+                .map(|option| option.as_str().map(ToString::to_string))
+                .collect::<Option<Vec<_>>>()
+                .context("Only string options are supported")?;
+            let labels = property
+                .get("enumNames")
+                .and_then(Value::as_array)
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter_map(|label| label.as_str().map(ToString::to_string))
+                        .collect::<Vec<_>>()
+                })
+                .filter(|labels| labels.len() == options.len())
+                .unwrap_or_else(|| options.clone());
+            return Ok(Self::Choice {
+                options,
+                labels,
+                default: string(property, "default"),
+            });
+        }
+
+        match kind {
+            Some("string") => Ok(Self::Text {
+                default: string(property, "default"),
+            }),
+            Some(kind @ ("number" | "integer")) => Ok(Self::Number {
+                integer: kind == "integer",
+                minimum: property.get("minimum").and_then(Value::as_f64),
+                maximum: property.get("maximum").and_then(Value::as_f64),
+                default: property.get("default").and_then(Value::as_f64),
+            }),
+            Some("boolean") => Ok(Self::Boolean {
+                default: property
+                    .get("default")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            }),
+            kind => Err(anyhow!("Unsupported type {kind:?}")),
+        }
+    }
+}
+
+impl InputField {
+    /// Converts what the user typed into a text or number field into the field's value, or
+    /// `None` if it was left empty.
+    pub fn parse_text(&self, text: &str) -> Result<Option<Value>> {
+        let text = text.trim();
+        if text.is_empty() {
+            anyhow::ensure!(!self.required, "{} is required", self.label());
+            return Ok(None);
+        }
+        match &self.kind {
+            InputFieldKind::Number {
+                integer,
+                minimum,
+                maximum,
+                ..
+            } => {
+                let number = if *integer {
+                    text.parse::<i64>()
+                        .map(Value::from)
+                        .map_err(|_| anyhow!("{} must be a whole number", self.label()))?
+                } else {
+                    text.parse::<f64>()
+                        .map(Value::from)
+                        .map_err(|_| anyhow!("{} must be a number", self.label()))?
+                };
+                let value = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = minimum {
+                    anyhow::ensure!(
+                        value >= *minimum,
+                        "{} must be at least {minimum}",
+                        self.label()
+                    );
+                }
+                if let Some(maximum) = maximum {
+                    anyhow::ensure!(
+                        value <= *maximum,
+                        "{} must be at most {maximum}",
+                        self.label()
+                    );
+                }
+                Ok(Some(number))
+            }
+            InputFieldKind::Text { .. }
+            | InputFieldKind::Boolean { .. }
+            | InputFieldKind::Choice { .. } => Ok(Some(Value::String(text.to_string()))),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+}
+
+fn string(property: &Value, key: &str) -> Option<String> {
+    property
+        .get(key)
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_input_request_from_schema() {
+        let request = InputRequest::from_schema(
+            "deploy-server".into(),
+            "Where should we deploy?".into(),
+            &json!({
+                "type": "object",
+                "properties": {
+                    "environment": {
+                        "type": "string",
+                        "enum": ["staging", "production"],
+                        "enumNames": ["Staging", "Production"]
+                    },
+                    "replicas": { "type": "integer", "minimum": 1, "title": "Replicas" },
+                    "dry_run": { "type": "boolean", "default": true },
+                    "note": { "type": "string", "description": "Shown in the changelog" }
+                },
+                "required": ["environment", "replicas"]
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            request.fields,
+            vec![
+                InputField {
+                    name: "environment".into(),
+                    title: None,
+                    description: None,
+                    required: true,
+                    kind: InputFieldKind::Choice {
+                        options: vec!["staging".into(), "production".into()],
+                        labels: vec!["Staging".into(), "Production".into()],
+                        default: None,
+                    },
+                },
+                InputField {
+                    name: "replicas".into(),
+                    title: Some("Replicas".into()),
+                    description: None,
+                    required: true,
+                    kind: InputFieldKind::Number {
+                        integer: true,
+                        minimum: Some(1.),
+                        maximum: None,
+                        default: None,
+                    },
+                },
+                InputField {
+                    name: "dry_run".into(),
+                    title: None,
+                    description: None,
+                    required: false,
+                    kind: InputFieldKind::Boolean { default: true },
+                },
+                InputField {
+                    name: "note".into(),
+                    title: None,
+                    description: Some("Shown in the changelog".into()),
+                    required: false,
+                    kind: InputFieldKind::Text { default: None },
+                },
+            ]
+        );
+
+        let replicas = &request.fields[1];
+        assert_eq!(replicas.parse_text(" 3 ").unwrap(), Some(json!(3)));
+        assert!(replicas.parse_text("").is_err());
+        assert!(replicas.parse_text("0").is_err());
+        assert!(replicas.parse_text("1.5").is_err());
+        assert_eq!(request.fields[3].parse_text("").unwrap(), None);
+
+        assert!(
+            InputRequest::from_schema(
+                "".into(),
+                "".into(),
+                &json!({ "properties": { "nested": { "type": "object" } } })
+            )
+            .is_err()
+        );
+    }
+}
@@ -48,6 +48,12 @@ pub enum MentionUri {
     Fetch {
         url: Url,
     },
+    /// A resource that a context server exposes, read with `resources/read`.
+    ContextServerResource {
+        server_id: String,
+        uri: Url,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -163,6 +169,25 @@ impl MentionUri {
                     Ok(Self::Directory {
                         abs_path: path.into(),
                     })
+                } else if path.starts_with("/agent/context-server-resource") {
+                    let mut server_id = None;
+                    let mut uri = None;
+                    let mut name = None;
+                    for (key, value) in url.query_pairs() {
+                        match key.as_ref() {
+                            "server" => server_id = Some(value.into_owned()),
+                            "uri" => uri = Some(Url::parse(&value)?),
+                            "name" => name = Some(value.into_owned()),
+                            _ => bail!("invalid query parameter"),
+                        }
+                    }
+                    let uri = uri.context("Missing uri for context server resource")?;
+                    Ok(Self::ContextServerResource {
+                        server_id: server_id
+                            .context("Missing server for context server resource")?,
+                        name: name.unwrap_or_else(|| uri.to_string()),
+                        uri,
+                    })
                 } else if path.starts_with("/agent/selection") {
                     let fragment = url.fragment().context("Missing fragment for selection")?;
                     let line_range = parse_line_range(fragment)?;
@@ -199,6 +224,7 @@ impl MentionUri {
                 ..
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
+            MentionUri::ContextServerResource { name, .. } => name.clone(),
         }
     }
 
@@ -216,6 +242,7 @@ impl MentionUri {
             MentionUri::Rule { .. } => IconName::Reader.path().into(),
             MentionUri::Selection { .. } => IconName::Reader.path().into(),
             MentionUri::Fetch { .. } => IconName::ToolWeb.path().into(),
+            MentionUri::ContextServerResource { .. } => IconName::Server.path().into(),
         }
     }
 
@@ -293,6 +320,19 @@ impl MentionUri {
                 url
             }
             MentionUri::Fetch { url } => url.clone(),
+            MentionUri::ContextServerResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///").unwrap();
+                url.set_path("/agent/context-server-resource");
+                url.query_pairs_mut()
+                    .append_pair("server", server_id)
+                    .append_pair("uri", uri.as_str())
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -471,6 +511,25 @@ mod tests {
         assert_eq!(parsed.to_uri().to_string(), rule_uri);
     }
 
+    #[test]
+    fn test_parse_context_server_resource_uri() {
+        let resource_uri = "zed:///agent/context-server-resource?server=github&uri=repo%3A%2F%2Fzed%2Fissues%2F42&name=Issue+42";
+        let parsed = MentionUri::parse(resource_uri, PathStyle::local()).unwrap();
+        match &parsed {
+            MentionUri::ContextServerResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                assert_eq!(server_id, "github");
+                assert_eq!(uri.as_str(), "repo://zed/issues/42");
+                assert_eq!(name, "Issue 42");
+            }
+            _ => panic!("Expected ContextServerResource variant"),
+        }
+        assert_eq!(parsed.to_uri().to_string(), resource_uri);
+    }
+
     #[test]
     fn test_parse_fetch_http_uri() {
         let http_uri = "http://example.com/path?query=value#fragment";
@@ -99,6 +99,7 @@ impl ActionLog {
                     unreviewed_edits,
                     snapshot: text_snapshot,
                     status,
+                    edited_for_agent: false,
                     version: buffer.read(cx).version(),
                     diff,
                     diff_update: diff_update_tx,
@@ -137,7 +138,12 @@ impl ActionLog {
         let Some(tracked_buffer) = self.tracked_buffers.get_mut(&buffer) else {
             return;
         };
-        tracked_buffer.schedule_diff_update(ChangeAuthor::User, cx);
+        let author = if tracked_buffer.edited_for_agent {
+            ChangeAuthor::Agent
+        } else {
+            ChangeAuthor::User
+        };
+        tracked_buffer.schedule_diff_update(author, cx);
     }
 
     fn handle_buffer_file_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
@@ -459,9 +465,18 @@ impl ActionLog {
         self.track_buffer_internal(buffer, true, cx);
     }
 
+    /// Mark a buffer as about to be edited on the agent's behalf by something else, like a
+    /// language server applying a rename. Its edits count as the agent's until
+    /// [`Self::buffer_edited`] is called.
+    pub fn will_edit_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let tracked_buffer = self.track_buffer_internal(buffer, false, cx);
+        tracked_buffer.edited_for_agent = true;
+    }
+
     /// Mark a buffer as edited by agent, so we can refresh it in the context
     pub fn buffer_edited(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
         let tracked_buffer = self.track_buffer_internal(buffer, false, cx);
+        tracked_buffer.edited_for_agent = false;
         if let TrackedBufferStatus::Deleted = tracked_buffer.status {
             tracked_buffer.status = TrackedBufferStatus::Modified;
         }
@@ -962,6 +977,8 @@ struct TrackedBuffer {
     diff_base: Rope,
     unreviewed_edits: Patch<u32>,
     status: TrackedBufferStatus,
+    /// Whether edits are being made on the agent's behalf, see [`ActionLog::will_edit_buffer`].
+    edited_for_agent: bool,
     version: clock::Global,
     diff: Entity<BufferDiff>,
     snapshot: text::BufferSnapshot,
@@ -1276,6 +1293,62 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test(iterations = 10)]
+    async fn test_edits_made_for_agent(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        // Edits made on the agent's behalf, like a language server's rename, arrive separately
+        // from the call to `buffer_edited`.
+        action_log.update(cx, |log, cx| log.will_edit_buffer(buffer.clone(), cx));
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(1, 0)..Point::new(1, 3), "DEF")], None, cx)
+        });
+        cx.run_until_parked();
+        action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+        cx.run_until_parked();
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx),
+            vec![(
+                buffer.clone(),
+                vec![HunkStatus {
+                    range: Point::new(1, 0)..Point::new(2, 0),
+                    diff_status: DiffHunkStatusKind::Modified,
+                    old_text: "def\n".into(),
+                }],
+            )]
+        );
+
+        // Once the agent is done, edits are the user's again.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(2, 0)..Point::new(2, 3), "GHI")], None, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx),
+            vec![(
+                buffer.clone(),
+                vec![HunkStatus {
+                    range: Point::new(1, 0)..Point::new(2, 0),
+                    diff_status: DiffHunkStatusKind::Modified,
+                    old_text: "def\n".into(),
+                }],
+            )]
+        );
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_creating_files(cx: &mut TestAppContext) {
         init_test(cx);
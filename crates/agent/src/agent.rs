@@ -1,6 +1,7 @@
 mod db;
 mod edit_agent;
 mod history_store;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -385,7 +386,9 @@ impl NativeAgent {
         Ok(())
     }
 
-    fn build_project_context(
+    /// Loads the context the system prompt gives the model about a project, like its worktrees
+    /// and their rules files, along with the user's default rules from `prompt_store`.
+    pub fn build_project_context(
         project: &Entity<Project>,
         prompt_store: Option<&Entity<PromptStore>>,
         cx: &mut App,
@@ -756,7 +759,7 @@ impl NativeAgent {
         id: acp::SessionId,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<AcpThread>>> {
-        let task = self.load_thread(id, cx);
+        let task = self.load_thread(id.clone(), cx);
         cx.spawn(async move |this, cx| {
             let thread = task.await?;
             let acp_thread =
@@ -766,10 +769,70 @@ impl NativeAgent {
                 NativeAgentConnection::handle_thread_events(events, acp_thread.downgrade(), cx)
             })?
             .await?;
+            let checkpoints =
+                thread.read_with(cx, |thread, _| thread.forked_checkpoints().to_vec())?;
+            acp_thread.update(cx, |acp_thread, cx| {
+                acp_thread.set_checkpoints(checkpoints, cx)
+            })?;
             Ok(acp_thread)
         })
     }
 
+    /// Saves a copy of a thread that ends before the given user message as a new thread, along
+    /// with the checkpoints of the messages it keeps and where it diverged from the original, so
+    /// the changes made in each can be compared.
+    pub fn fork_thread(
+        &mut self,
+        id: &acp::SessionId,
+        message_id: Option<UserMessageId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DbThreadMetadata>> {
+        let Some(session) = self.sessions.get(id) else {
+            return Task::ready(Err(anyhow!("Session not found")));
+        };
+        let checkpoints = session
+            .acp_thread
+            .upgrade()
+            .map(|acp_thread| acp_thread.read(cx).checkpoints())
+            .unwrap_or_default();
+        // Without a checkpoint from before the first message the fork leaves out, there's nothing
+        // to compare the original's changes against.
+        let base_checkpoint = match &message_id {
+            Some(message_id) => checkpoints
+                .iter()
+                .find(|(id, _)| id == message_id)
+                .map(|(_, checkpoint)| checkpoint.git_checkpoint.clone()),
+            None => None,
+        };
+        let is_comparable = message_id.is_none() || base_checkpoint.is_some();
+
+        let thread = session.thread.read(cx);
+        let title = thread.title();
+        let db_thread = match thread.fork(message_id.as_ref(), checkpoints, cx) {
+            Ok(db_thread) => db_thread,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let git_store = self.project.read(cx).git_store().clone();
+        let original_checkpoint =
+            is_comparable.then(|| git_store.update(cx, |git_store, cx| git_store.checkpoint(cx)));
+        let history = self.history.clone();
+        cx.spawn(async move |_, cx| {
+            let mut db_thread = db_thread.await;
+            if let Some(original_checkpoint) = original_checkpoint
+                && let Some(original_checkpoint) = original_checkpoint.await.log_err()
+            {
+                db_thread.fork_origin = Some(ForkOrigin {
+                    title,
+                    base_checkpoint: base_checkpoint.unwrap_or_else(|| original_checkpoint.clone()),
+                    original_checkpoint,
+                });
+            }
+            history
+                .update(cx, |history, cx| history.save_forked_thread(db_thread, cx))?
+                .await
+        })
+    }
+
     pub fn thread_summary(
         &mut self,
         id: acp::SessionId,
@@ -929,6 +992,16 @@ impl NativeAgentConnection {
         self.0.update(cx, |this, cx| this.load_thread(id, cx))
     }
 
+    pub fn fork_thread(
+        &self,
+        id: &acp::SessionId,
+        message_id: Option<UserMessageId>,
+        cx: &mut App,
+    ) -> Task<Result<DbThreadMetadata>> {
+        self.0
+            .update(cx, |this, cx| this.fork_thread(id, message_id, cx))
+    }
+
     fn run_turn(
         &self,
         session_id: acp::SessionId,
@@ -992,9 +1065,13 @@ impl NativeAgentConnection {
                                 options,
                                 response,
                             }) => {
+                                // Tool calls only ask for authorization when
+                                // `always_allow_tool_actions` is off, when a tool permission
+                                // rule requires confirmation regardless of it, or when a
+                                // context server asks to use the model while its tool runs.
                                 let outcome_task = acp_thread.update(cx, |thread, cx| {
                                     thread.request_tool_call_authorization(
-                                        tool_call, options, true, cx,
+                                        tool_call, options, false, cx,
                                     )
                                 })??;
                                 cx.background_spawn(async move {
@@ -1010,6 +1087,19 @@ impl NativeAgentConnection {
                                 })
                                 .detach();
                             }
+                            ThreadEvent::ToolCallInputRequest(ToolCallInputRequest {
+                                tool_call_id,
+                                request,
+                                response,
+                            }) => {
+                                let response_task = acp_thread.update(cx, |thread, cx| {
+                                    thread.request_tool_call_input(tool_call_id, request, cx)
+                                })??;
+                                cx.background_spawn(async move {
+                                    response.send(response_task.await).ok();
+                                })
+                                .detach();
+                            }
                             ThreadEvent::ToolCall(tool_call) => {
                                 acp_thread.update(cx, |thread, cx| {
                                     thread.upsert_tool_call(tool_call, cx)
@@ -1025,6 +1115,9 @@ impl NativeAgentConnection {
                                     thread.update_retry_status(status, cx)
                                 })?;
                             }
+                            ThreadEvent::Plan(plan) => {
+                                acp_thread.update(cx, |thread, cx| thread.update_plan(plan, cx))?;
+                            }
                             ThreadEvent::Stop(stop_reason) => {
                                 log::debug!("Assistant message complete: {:?}", stop_reason);
                                 return Ok(acp::PromptResponse::new(stop_reason));
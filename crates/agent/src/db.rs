@@ -8,6 +8,7 @@ use collections::{HashMap, IndexMap};
 use futures::{FutureExt, future::Shared};
 use gpui::{BackgroundExecutor, Global, Task};
 use indoc::indoc;
+use itertools::Itertools as _;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use sqlez::{
@@ -29,12 +30,61 @@ pub struct DbThreadMetadata {
     #[serde(alias = "summary")]
     pub title: SharedString,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub tags: Vec<SharedString>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A thread whose title or messages match a full-text search.
+#[derive(Debug, Clone)]
+pub struct DbThreadSearchMatch {
+    pub id: acp::SessionId,
+    /// The part of the thread that matched the query.
+    pub snippet: SharedString,
+}
+
+/// The formats threads can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadExportFormat {
+    /// A readable transcript, which can't be imported again.
+    Markdown,
+    /// The versioned format threads are saved in, which can be imported into another install.
+    Json,
+}
+
+impl ThreadExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ThreadExportFormat::Markdown => "md",
+            ThreadExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SerializedThread<'a> {
+    #[serde(flatten)]
+    thread: &'a DbThread,
+    version: &'static str,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DbThread {
     pub title: SharedString,
     pub messages: Vec<DbMessage>,
+    /// The summary sent in place of the older messages, if the thread was compacted.
+    #[serde(default)]
+    pub compaction: Option<crate::Compaction>,
+    /// The plan the model was working through, if it made one.
+    #[serde(default)]
+    pub plan: Vec<acp::PlanEntry>,
+    /// The checkpoints of the user messages a forked thread kept from the original.
+    #[serde(default)]
+    pub forked_checkpoints: Vec<(acp_thread::UserMessageId, acp_thread::Checkpoint)>,
+    /// Where a forked thread diverged from the thread it was forked from.
+    #[serde(default)]
+    pub fork_origin: Option<crate::ForkOrigin>,
     pub updated_at: DateTime<Utc>,
     #[serde(default)]
     pub detailed_summary: Option<SharedString>,
@@ -55,6 +105,43 @@ pub struct DbThread {
 impl DbThread {
     pub const VERSION: &'static str = "0.3.0";
 
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&SerializedThread {
+            thread: self,
+            version: Self::VERSION,
+        })?)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n", self.title);
+        for (ix, message) in self.messages.iter().enumerate() {
+            if ix > 0 {
+                markdown.push('\n');
+            }
+            markdown.push_str(&message.to_markdown());
+        }
+        markdown
+    }
+
+    pub fn export(&self, format: ThreadExportFormat) -> Result<String> {
+        match format {
+            ThreadExportFormat::Markdown => Ok(self.to_markdown()),
+            ThreadExportFormat::Json => Ok(serde_json::to_string_pretty(&SerializedThread {
+                thread: self,
+                version: Self::VERSION,
+            })?),
+        }
+    }
+
+    /// The text of the thread's messages, as indexed for full-text search.
+    fn searchable_content(&self) -> String {
+        self.messages
+            .iter()
+            .map(|message| message.to_markdown())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn from_json(json: &[u8]) -> Result<Self> {
         let saved_thread_json = serde_json::from_slice::<serde_json::Value>(json)?;
         match saved_thread_json.get("version") {
@@ -197,6 +284,10 @@ impl DbThread {
         Ok(Self {
             title: thread.summary,
             messages,
+            compaction: None,
+            plan: Vec::new(),
+            forked_checkpoints: Vec::new(),
+            fork_origin: None,
             updated_at: thread.updated_at,
             detailed_summary: match thread.detailed_summary_state {
                 crate::legacy_thread::DetailedSummaryState::NotGenerated
@@ -306,6 +397,25 @@ impl ThreadsDatabase {
         "})?()
         .map_err(|e| anyhow!("Failed to create threads table: {}", e))?;
 
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS thread_tags (
+                thread_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (thread_id, tag)
+            );
+            CREATE TABLE IF NOT EXISTS pinned_threads (
+                thread_id TEXT PRIMARY KEY
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS threads_fts USING fts5(
+                id UNINDEXED,
+                title,
+                content
+            );
+        "})?()
+        .map_err(|e| anyhow!("Failed to create thread search tables: {}", e))?;
+
+        Self::index_unindexed_threads(&connection)?;
+
         let db = Self {
             executor,
             connection: Arc::new(Mutex::new(connection)),
@@ -314,6 +424,42 @@ impl ThreadsDatabase {
         Ok(db)
     }
 
+    /// Adds the threads that were saved before the search index existed to it.
+    fn index_unindexed_threads(connection: &Connection) -> Result<()> {
+        let rows = connection.select::<(Arc<str>, DataType, Vec<u8>)>(indoc! {"
+            SELECT id, data_type, data FROM threads
+            WHERE id NOT IN (SELECT id FROM threads_fts)
+        "})?()?;
+        for (id, data_type, data) in rows {
+            match Self::decode_thread(data_type, data) {
+                Ok(thread) => Self::index_thread(connection, id, &thread)?,
+                Err(error) => log::error!("Failed to index thread {id}: {error:#}"),
+            }
+        }
+        Ok(())
+    }
+
+    fn index_thread(connection: &Connection, id: Arc<str>, thread: &DbThread) -> Result<()> {
+        connection.exec_bound::<Arc<str>>(indoc! {"
+            DELETE FROM threads_fts WHERE id = ?
+        "})?(id.clone())?;
+        connection.exec_bound::<(Arc<str>, String, String)>(indoc! {"
+            INSERT INTO threads_fts (id, title, content) VALUES (?, ?, ?)
+        "})?((id, thread.title.to_string(), thread.searchable_content()))?;
+        Ok(())
+    }
+
+    fn decode_thread(data_type: DataType, data: Vec<u8>) -> Result<DbThread> {
+        let json_data = match data_type {
+            DataType::Zstd => {
+                let decompressed = zstd::decode_all(&data[..])?;
+                String::from_utf8(decompressed)?
+            }
+            DataType::Json => String::from_utf8(data)?,
+        };
+        DbThread::from_json(json_data.as_bytes())
+    }
+
     fn save_thread_sync(
         connection: &Arc<Mutex<Connection>>,
         id: acp::SessionId,
@@ -321,19 +467,9 @@ impl ThreadsDatabase {
     ) -> Result<()> {
         const COMPRESSION_LEVEL: i32 = 3;
 
-        #[derive(Serialize)]
-        struct SerializedThread {
-            #[serde(flatten)]
-            thread: DbThread,
-            version: &'static str,
-        }
-
         let title = thread.title.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
-        let json_data = serde_json::to_string(&SerializedThread {
-            thread,
-            version: DbThread::VERSION,
-        })?;
+        let json_data = thread.to_json()?;
 
         let connection = connection.lock();
 
@@ -341,13 +477,17 @@ impl ThreadsDatabase {
         let data_type = DataType::Zstd;
         let data = compressed;
 
-        let mut insert = connection.exec_bound::<(Arc<str>, String, String, DataType, Vec<u8>)>(indoc! {"
-            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?)
-        "})?;
+        // The search index is updated in the same transaction, so that it never disagrees with
+        // the saved thread.
+        connection.with_savepoint("save_thread", || {
+            let mut insert = connection.exec_bound::<(Arc<str>, String, String, DataType, Vec<u8>)>(indoc! {"
+                INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?)
+            "})?;
 
-        insert((id.0, title, updated_at, data_type, data))?;
+            insert((id.0.clone(), title, updated_at, data_type, data))?;
 
-        Ok(())
+            Self::index_thread(&connection, id.0, &thread)
+        })
     }
 
     pub fn list_threads(&self) -> Task<Result<Vec<DbThreadMetadata>>> {
@@ -357,18 +497,33 @@ impl ThreadsDatabase {
             let connection = connection.lock();
 
             let mut select =
-                connection.select_bound::<(), (Arc<str>, String, String)>(indoc! {"
-                SELECT id, summary, updated_at FROM threads ORDER BY updated_at DESC
+                connection.select_bound::<(), (Arc<str>, String, String, bool)>(indoc! {"
+                SELECT id, summary, updated_at, id IN (SELECT thread_id FROM pinned_threads)
+                FROM threads ORDER BY updated_at DESC
             "})?;
 
             let rows = select(())?;
+
+            let mut tags_by_thread = HashMap::<Arc<str>, Vec<SharedString>>::default();
+            let tag_rows = connection.select::<(Arc<str>, String)>(indoc! {"
+                SELECT thread_id, tag FROM thread_tags ORDER BY tag
+            "})?()?;
+            for (thread_id, tag) in tag_rows {
+                tags_by_thread
+                    .entry(thread_id)
+                    .or_default()
+                    .push(tag.into());
+            }
+
             let mut threads = Vec::new();
 
-            for (id, summary, updated_at) in rows {
+            for (id, summary, updated_at, pinned) in rows {
                 threads.push(DbThreadMetadata {
+                    tags: tags_by_thread.remove(&id).unwrap_or_default(),
                     id: acp::SessionId::new(id),
                     title: summary.into(),
                     updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                    pinned,
                 });
             }
 
@@ -387,15 +542,7 @@ impl ThreadsDatabase {
 
             let rows = select(id.0)?;
             if let Some((data_type, data)) = rows.into_iter().next() {
-                let json_data = match data_type {
-                    DataType::Zstd => {
-                        let decompressed = zstd::decode_all(&data[..])?;
-                        String::from_utf8(decompressed)?
-                    }
-                    DataType::Json => String::from_utf8(data)?,
-                };
-                let thread = DbThread::from_json(json_data.as_bytes())?;
-                Ok(Some(thread))
+                Ok(Some(Self::decode_thread(data_type, data)?))
             } else {
                 Ok(None)
             }
@@ -416,7 +563,10 @@ impl ThreadsDatabase {
             let connection = connection.lock();
 
             let mut delete = connection.exec_bound::<Arc<str>>(indoc! {"
-                DELETE FROM threads WHERE id = ?
+                DELETE FROM threads WHERE id = ?1;
+                DELETE FROM threads_fts WHERE id = ?1;
+                DELETE FROM thread_tags WHERE thread_id = ?1;
+                DELETE FROM pinned_threads WHERE thread_id = ?1;
             "})?;
 
             delete(id.0)?;
@@ -432,7 +582,10 @@ impl ThreadsDatabase {
             let connection = connection.lock();
 
             let mut delete = connection.exec_bound::<()>(indoc! {"
-                DELETE FROM threads
+                DELETE FROM threads;
+                DELETE FROM threads_fts;
+                DELETE FROM thread_tags;
+                DELETE FROM pinned_threads;
             "})?;
 
             delete(())?;
@@ -440,4 +593,249 @@ impl ThreadsDatabase {
             Ok(())
         })
     }
+
+    /// Finds the threads whose title or messages contain all the words of `query`, best matches
+    /// first.
+    pub fn search_threads(&self, query: String) -> Task<Result<Vec<DbThreadSearchMatch>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let Some(query) = fts_query(&query) else {
+                return Ok(Vec::new());
+            };
+            let connection = connection.lock();
+
+            let mut select = connection.select_bound::<String, (Arc<str>, String)>(indoc! {"
+                SELECT id, snippet(threads_fts, 2, '', '', '…', 12) FROM threads_fts
+                WHERE threads_fts MATCH ?
+                ORDER BY rank
+                LIMIT 100
+            "})?;
+
+            Ok(select(query)?
+                .into_iter()
+                .map(|(id, snippet)| DbThreadSearchMatch {
+                    id: acp::SessionId::new(id),
+                    snippet: snippet.split_whitespace().join(" ").into(),
+                })
+                .collect())
+        })
+    }
+
+    pub fn set_thread_pinned(&self, id: acp::SessionId, pinned: bool) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let query = if pinned {
+                "INSERT OR IGNORE INTO pinned_threads (thread_id) VALUES (?)"
+            } else {
+                "DELETE FROM pinned_threads WHERE thread_id = ?"
+            };
+            connection.exec_bound::<Arc<str>>(query)?(id.0)?;
+            Ok(())
+        })
+    }
+
+    pub fn set_thread_tags(&self, id: acp::SessionId, tags: Vec<SharedString>) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            connection.with_savepoint("set_thread_tags", || {
+                connection.exec_bound::<Arc<str>>(indoc! {"
+                    DELETE FROM thread_tags WHERE thread_id = ?
+                "})?(id.0.clone())?;
+                let mut insert = connection.exec_bound::<(Arc<str>, String)>(indoc! {"
+                    INSERT OR IGNORE INTO thread_tags (thread_id, tag) VALUES (?, ?)
+                "})?;
+                for tag in &tags {
+                    insert((id.0.clone(), tag.to_string()))?;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Saves a thread exported as JSON, possibly from another install, as a new thread.
+    pub fn import_thread(&self, json: Vec<u8>) -> Task<Result<DbThreadMetadata>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let mut thread = DbThread::from_json(&json)?;
+            thread.updated_at = Utc::now();
+            Self::save_new_thread_sync(&connection, thread)
+        })
+    }
+
+    /// Saves a thread under a new ID, like a fork of another thread.
+    pub fn save_new_thread(&self, thread: DbThread) -> Task<Result<DbThreadMetadata>> {
+        let connection = self.connection.clone();
+
+        self.executor
+            .spawn(async move { Self::save_new_thread_sync(&connection, thread) })
+    }
+
+    fn save_new_thread_sync(
+        connection: &Arc<Mutex<Connection>>,
+        thread: DbThread,
+    ) -> Result<DbThreadMetadata> {
+        let metadata = DbThreadMetadata {
+            id: acp::SessionId::new(uuid::Uuid::new_v4().to_string()),
+            title: thread.title.clone(),
+            updated_at: thread.updated_at,
+            tags: Vec::new(),
+            pinned: false,
+        };
+        Self::save_thread_sync(connection, metadata.id.clone(), thread)?;
+        Ok(metadata)
+    }
+}
+
+/// Turns what the user typed into an FTS5 query matching threads that contain all of its words,
+/// or words starting with them, so that FTS5 syntax in the input isn't interpreted.
+fn fts_query(input: &str) -> Option<String> {
+    let query = input
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .join(" ");
+    (!query.is_empty()).then_some(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    fn thread(title: &str, text: &str) -> DbThread {
+        DbThread {
+            title: title.to_string().into(),
+            messages: vec![crate::Message::User(UserMessage {
+                id: UserMessageId::new(),
+                content: vec![UserMessageContent::Text(text.to_string())],
+            })],
+            compaction: None,
+            plan: Vec::new(),
+            forked_checkpoints: Vec::new(),
+            fork_origin: None,
+            updated_at: Utc::now(),
+            detailed_summary: None,
+            initial_project_snapshot: None,
+            cumulative_token_usage: Default::default(),
+            request_token_usage: HashMap::default(),
+            model: None,
+            completion_mode: None,
+            profile: None,
+        }
+    }
+
+    fn ids(matches: &[DbThreadSearchMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.id.0.as_ref()).collect()
+    }
+
+    #[gpui::test]
+    async fn test_search_tag_and_pin_threads(cx: &mut TestAppContext) {
+        let db = ThreadsDatabase::new(cx.executor()).unwrap();
+        let parser = acp::SessionId::new("parser");
+        let docs = acp::SessionId::new("docs");
+        db.save_thread(
+            parser.clone(),
+            thread("Fix the parser", "The tokenizer panics on emoji"),
+        )
+        .await
+        .unwrap();
+        db.save_thread(
+            docs.clone(),
+            thread(
+                "Write docs",
+                "Document how the tokenizer handles \"quotes\"",
+            ),
+        )
+        .await
+        .unwrap();
+
+        let matches = db.search_threads("tokeniz".into()).await.unwrap();
+        assert_eq!(matches.len(), 2);
+        let matches = db.search_threads("emoji panics".into()).await.unwrap();
+        assert_eq!(ids(&matches), ["parser"]);
+        assert!(matches[0].snippet.contains("emoji"));
+        let matches = db.search_threads("parser".into()).await.unwrap();
+        assert_eq!(ids(&matches), ["parser"]);
+        let matches = db.search_threads("\"quotes AND".into()).await.unwrap();
+        assert!(matches.is_empty());
+        let matches = db.search_threads("  ".into()).await.unwrap();
+        assert!(matches.is_empty());
+
+        // Saving a thread again replaces what's indexed for it.
+        db.save_thread(
+            parser.clone(),
+            thread("Fix the parser", "The lexer panics on emoji"),
+        )
+        .await
+        .unwrap();
+        let matches = db.search_threads("tokenizer".into()).await.unwrap();
+        assert_eq!(ids(&matches), ["docs"]);
+
+        db.set_thread_tags(parser.clone(), vec!["bug".into(), "lexer".into()])
+            .await
+            .unwrap();
+        db.set_thread_pinned(docs.clone(), true).await.unwrap();
+        let threads = db.list_threads().await.unwrap();
+        let parser_metadata = threads.iter().find(|t| t.id == parser).unwrap();
+        assert_eq!(parser_metadata.tags, ["bug", "lexer"]);
+        assert!(!parser_metadata.pinned);
+        let docs_metadata = threads.iter().find(|t| t.id == docs).unwrap();
+        assert!(docs_metadata.tags.is_empty());
+        assert!(docs_metadata.pinned);
+
+        db.set_thread_tags(parser.clone(), vec!["bug".into()])
+            .await
+            .unwrap();
+        db.set_thread_pinned(docs.clone(), false).await.unwrap();
+        let threads = db.list_threads().await.unwrap();
+        assert!(threads.iter().all(|t| !t.pinned));
+        assert_eq!(
+            threads.iter().find(|t| t.id == parser).unwrap().tags,
+            ["bug"]
+        );
+
+        db.delete_thread(parser.clone()).await.unwrap();
+        let matches = db.search_threads("emoji".into()).await.unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_thread_isnt_saved_if_indexing_fails(cx: &mut TestAppContext) {
+        let db = ThreadsDatabase::new(cx.executor()).unwrap();
+        db.connection.lock().exec("DROP TABLE threads_fts").unwrap()().unwrap();
+
+        let id = acp::SessionId::new("parser");
+        let result = db
+            .save_thread(
+                id.clone(),
+                thread("Fix the parser", "The tokenizer panics on emoji"),
+            )
+            .await;
+        assert!(result.is_err());
+        assert!(db.load_thread(id).await.unwrap().is_none());
+    }
+
+    #[gpui::test]
+    async fn test_export_and_import_thread(cx: &mut TestAppContext) {
+        let db = ThreadsDatabase::new(cx.executor()).unwrap();
+        let original = thread("Fix the parser", "The tokenizer panics on emoji");
+
+        let markdown = original.export(ThreadExportFormat::Markdown).unwrap();
+        assert!(markdown.starts_with("# Fix the parser\n"));
+        assert!(markdown.contains("The tokenizer panics on emoji"));
+
+        let json = original.export(ThreadExportFormat::Json).unwrap();
+        let imported = db.import_thread(json.into_bytes()).await.unwrap();
+        assert_eq!(imported.title, "Fix the parser");
+
+        let thread = db.load_thread(imported.id.clone()).await.unwrap().unwrap();
+        assert_eq!(thread.messages, original.messages);
+        let matches = db.search_threads("emoji".into()).await.unwrap();
+        assert_eq!(ids(&matches), [imported.id.0.as_ref()]);
+    }
 }
@@ -776,6 +776,7 @@ impl EditAgent {
             stop: Vec::new(),
             temperature: None,
             thinking_allowed: true,
+            max_output_tokens: None,
         };
 
         Ok(self.model.stream_completion_text(request, cx).await?.stream)
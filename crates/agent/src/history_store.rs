@@ -1,4 +1,4 @@
-use crate::{DbThread, DbThreadMetadata, ThreadsDatabase};
+use crate::{DbThread, DbThreadMetadata, DbThreadSearchMatch, ThreadExportFormat, ThreadsDatabase};
 use acp_thread::MentionUri;
 use agent_client_protocol as acp;
 use anyhow::{Context as _, Result, anyhow};
@@ -95,6 +95,20 @@ impl HistoryEntry {
             HistoryEntry::TextThread(text_thread) => &text_thread.title,
         }
     }
+
+    pub fn tags(&self) -> &[SharedString] {
+        match self {
+            HistoryEntry::AcpThread(thread) => &thread.tags,
+            HistoryEntry::TextThread(_) => &[],
+        }
+    }
+
+    pub fn pinned(&self) -> bool {
+        match self {
+            HistoryEntry::AcpThread(thread) => thread.pinned,
+            HistoryEntry::TextThread(_) => false,
+        }
+    }
 }
 
 /// Generic identifier for a history entry.
@@ -197,6 +211,90 @@ impl HistoryStore {
         })
     }
 
+    pub fn search_threads(
+        &self,
+        query: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DbThreadSearchMatch>>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.background_spawn(async move {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.search_threads(query).await
+        })
+    }
+
+    pub fn set_thread_pinned(
+        &mut self,
+        id: acp::SessionId,
+        pinned: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.set_thread_pinned(id, pinned).await?;
+            this.update(cx, |this, cx| this.reload(cx))
+        })
+    }
+
+    pub fn set_thread_tags(
+        &mut self,
+        id: acp::SessionId,
+        tags: Vec<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.set_thread_tags(id, tags).await?;
+            this.update(cx, |this, cx| this.reload(cx))
+        })
+    }
+
+    pub fn export_thread(
+        &mut self,
+        id: acp::SessionId,
+        format: ThreadExportFormat,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<String>> {
+        let load_thread = self.load_thread(id, cx);
+        cx.background_spawn(async move {
+            load_thread
+                .await?
+                .context("thread not found")?
+                .export(format)
+        })
+    }
+
+    pub fn import_thread(
+        &mut self,
+        json: Vec<u8>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DbThreadMetadata>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let metadata = database.import_thread(json).await?;
+            this.update(cx, |this, cx| this.reload(cx))?;
+            Ok(metadata)
+        })
+    }
+
+    /// Saves a fork of a thread as a new thread.
+    pub fn save_forked_thread(
+        &mut self,
+        thread: DbThread,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DbThreadMetadata>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let metadata = database.save_new_thread(thread).await?;
+            this.update(cx, |this, cx| this.reload(cx))?;
+            Ok(metadata)
+        })
+    }
+
     pub fn delete_text_thread(
         &mut self,
         path: Arc<Path>,
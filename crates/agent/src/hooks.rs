@@ -0,0 +1,303 @@
+use agent_client_protocol as acp;
+use agent_settings::AgentSettings;
+use anyhow::{Context as _, Result};
+use futures::{AsyncWriteExt as _, FutureExt as _};
+use gpui::{App, BackgroundExecutor, Entity, Task};
+use project::Project;
+use serde::Serialize;
+use settings::{AgentHookContent, AgentHookEvent, Settings as _};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+use util::shell::{ShellKind, get_system_shell};
+
+/// How long a hook may run when its settings don't say otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What hooks learn about the event they run for, besides what's common to all events.
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct HookInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<Arc<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_input: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+}
+
+/// The description of an event that hooks receive on stdin, as JSON.
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    event: AgentHookEvent,
+    session_id: &'a str,
+    cwd: Option<&'a Path>,
+    #[serde(flatten)]
+    input: &'a HookInput,
+}
+
+/// The hooks configured for an event, ready to run.
+pub(crate) struct Hooks {
+    hooks: Vec<AgentHookContent>,
+    session_id: Arc<str>,
+    cwd: Option<PathBuf>,
+    executor: BackgroundExecutor,
+}
+
+impl Hooks {
+    /// Returns the hooks that run for `event`, or `None` if there aren't any.
+    ///
+    /// For tool events, `tool` is the name and kind of the called tool. Hooks only run in local
+    /// projects, because they'd run on the wrong machine for remote ones.
+    pub fn for_event(
+        event: AgentHookEvent,
+        tool: Option<(&str, acp::ToolKind)>,
+        session_id: &acp::SessionId,
+        project: &Entity<Project>,
+        cx: &App,
+    ) -> Option<Self> {
+        let project = project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+
+        let hooks = AgentSettings::get_global(cx)
+            .hooks
+            .iter()
+            .filter(|hook| {
+                let runs_for_event = hook.event == event
+                    || (hook.event == AgentHookEvent::PostEdit
+                        && event == AgentHookEvent::PostToolCall
+                        && tool.is_some_and(|(_, kind)| edits_files(kind)));
+                let runs_for_tool = match (&hook.tools, tool) {
+                    (Some(tools), Some((tool_name, _))) => {
+                        tools.iter().any(|tool| tool.as_ref() == tool_name)
+                    }
+                    _ => true,
+                };
+                runs_for_event && runs_for_tool
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if hooks.is_empty() {
+            return None;
+        }
+
+        let cwd = project
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+        Some(Self {
+            hooks,
+            session_id: session_id.0.clone(),
+            cwd,
+            executor: cx.background_executor().clone(),
+        })
+    }
+
+    /// Runs the hooks one after another, sending each of them `input` on stdin.
+    ///
+    /// `post_edit` hooks are skipped when `input` describes a tool call that failed.
+    pub fn run(self, input: HookInput) -> Task<Vec<HookOutput>> {
+        let executor = self.executor.clone();
+        self.executor.spawn(async move {
+            let mut outputs = Vec::new();
+            for hook in &self.hooks {
+                if hook.event == AgentHookEvent::PostEdit && input.is_error == Some(true) {
+                    continue;
+                }
+                let payload = HookPayload {
+                    event: hook.event,
+                    session_id: &self.session_id,
+                    cwd: self.cwd.as_deref(),
+                    input: &input,
+                };
+                let timeout = hook.timeout.map_or(DEFAULT_TIMEOUT, Duration::from_secs);
+                let output = match run_hook(&hook.command, &payload, timeout, &executor).await {
+                    Ok(output) => output,
+                    Err(error) => HookOutput {
+                        command: hook.command.clone(),
+                        success: false,
+                        output: format!("{error:#}"),
+                    },
+                };
+                if !output.success {
+                    log::warn!("Agent hook {:?} failed: {}", output.command, output.output);
+                }
+                outputs.push(output);
+            }
+            outputs
+        })
+    }
+}
+
+/// Whether calls to tools of this kind change files, which makes them run `post_edit` hooks.
+fn edits_files(kind: acp::ToolKind) -> bool {
+    matches!(
+        kind,
+        acp::ToolKind::Edit | acp::ToolKind::Delete | acp::ToolKind::Move
+    )
+}
+
+/// What a hook printed, and whether it exited successfully.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct HookOutput {
+    pub command: String,
+    pub success: bool,
+    /// The hook's stdout followed by its stderr.
+    pub output: String,
+}
+
+impl HookOutput {
+    /// Describes the hooks that failed for the model, or returns `None` if all of them succeeded.
+    pub fn failures(outputs: &[HookOutput]) -> Option<String> {
+        let mut feedback = String::new();
+        for output in outputs.iter().filter(|output| !output.success) {
+            if !feedback.is_empty() {
+                feedback.push('\n');
+            }
+            writeln!(feedback, "The `{}` hook failed:", output.command).ok();
+            if !output.output.trim().is_empty() {
+                writeln!(feedback, "```\n{}\n```", output.output.trim()).ok();
+            }
+        }
+        (!feedback.is_empty()).then_some(feedback)
+    }
+
+    /// Collects what the hooks that succeeded printed, or returns `None` if they printed nothing.
+    pub fn successes(outputs: &[HookOutput]) -> Option<String> {
+        let mut context = String::new();
+        for output in outputs.iter().filter(|output| output.success) {
+            let text = output.output.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if !context.is_empty() {
+                context.push('\n');
+            }
+            writeln!(context, "Output of the `{}` hook:", output.command).ok();
+            writeln!(context, "```\n{text}\n```").ok();
+        }
+        (!context.is_empty()).then_some(context)
+    }
+}
+
+async fn run_hook(
+    command: &str,
+    payload: &HookPayload<'_>,
+    timeout: Duration,
+    executor: &BackgroundExecutor,
+) -> Result<HookOutput> {
+    let shell = get_system_shell();
+    let shell_kind = ShellKind::new(&shell, cfg!(windows));
+    let mut process = util::command::new_smol_command(&shell);
+    process
+        .args(shell_kind.args_for_shell(false, command.to_string()))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = payload.cwd {
+        process.current_dir(cwd);
+    }
+    let mut child = process
+        .spawn()
+        .with_context(|| format!("failed to run {command:?}"))?;
+
+    let input = serde_json::to_vec(payload)?;
+    let stdin = child.stdin.take();
+    // The input is written while the output is read, and both count towards the timeout, so that
+    // hooks that never read their input can't block the agent.
+    let write_input = async move {
+        if let Some(mut stdin) = stdin {
+            // Hooks that don't read their input may exit before it's written.
+            stdin.write_all(&input).await.ok();
+            stdin.close().await.ok();
+        }
+    };
+    let run = async move {
+        let (output, ()) = futures::join!(child.output(), write_input);
+        output
+    };
+
+    let output = futures::select_biased! {
+        output = run.fuse() => output?,
+        _ = executor.timer(timeout).fuse() => {
+            anyhow::bail!("timed out after {} seconds", timeout.as_secs());
+        }
+    };
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&stderr);
+    }
+    Ok(HookOutput {
+        command: command.to_string(),
+        success: output.status.success(),
+        output: text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_feedback() {
+        let outputs = [
+            HookOutput {
+                command: "cargo fmt".into(),
+                success: true,
+                output: "".into(),
+            },
+            HookOutput {
+                command: "cat CONTEXT.md".into(),
+                success: true,
+                output: "Use tabs\n".into(),
+            },
+            HookOutput {
+                command: "cargo clippy".into(),
+                success: false,
+                output: "error: unused variable\n".into(),
+            },
+        ];
+        assert_eq!(
+            HookOutput::failures(&outputs).as_deref(),
+            Some("The `cargo clippy` hook failed:\n```\nerror: unused variable\n```\n")
+        );
+        assert_eq!(
+            HookOutput::successes(&outputs).as_deref(),
+            Some("Output of the `cat CONTEXT.md` hook:\n```\nUse tabs\n```\n")
+        );
+        assert_eq!(HookOutput::failures(&outputs[..2]), None);
+        assert_eq!(HookOutput::successes(&outputs[2..]), None);
+    }
+
+    #[gpui::test]
+    async fn test_hook_that_ignores_stdin(cx: &mut gpui::TestAppContext) {
+        // An input larger than the pipe's buffer can't be written until the hook reads it.
+        let input = HookInput {
+            tool_output: Some("x".repeat(1024 * 1024)),
+            ..HookInput::default()
+        };
+        let payload = HookPayload {
+            event: AgentHookEvent::PostToolCall,
+            session_id: "session",
+            cwd: None,
+            input: &input,
+        };
+        let error = run_hook("sleep 60", &payload, Duration::from_secs(1), &cx.executor())
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "timed out after 1 seconds");
+    }
+}
@@ -1,7 +1,7 @@
 use super::*;
 use acp_thread::{AgentConnection, AgentModelGroupName, AgentModelList, UserMessageId};
 use agent_client_protocol::{self as acp};
-use agent_settings::AgentProfileId;
+use agent_settings::{AgentProfileId, AgentSettings, SUMMARIZE_THREAD_DETAILED_PROMPT};
 use anyhow::Result;
 use client::{Client, UserStore};
 use cloud_llm_client::CompletionIntent;
@@ -803,6 +803,538 @@ async fn test_tool_authorization(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_tool_permission_rules(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    fs.insert_file(
+        paths::settings_file(),
+        json!({
+            "agent": {
+                "default_profile": "test-profile",
+                "profiles": {
+                    "test-profile": {
+                        "name": "Test Profile",
+                        "tools": {
+                            EchoTool::name(): true,
+                        },
+                        "tool_permissions": [
+                            { "tool": EchoTool::name(), "pattern": "secret*", "action": "deny" },
+                            { "tool": EchoTool::name(), "pattern": "h*", "action": "ask" },
+                            { "tool": EchoTool::name(), "pattern": "hush*", "action": "deny" },
+                        ]
+                    }
+                }
+            }
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    cx.run_until_parked();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let echo = |id: &str, text: &str| {
+        LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+            id: id.into(),
+            name: EchoTool::name().into(),
+            raw_input: json!({ "text": text }).to_string(),
+            input: json!({ "text": text }),
+            is_input_complete: true,
+            thought_signature: None,
+        })
+    };
+    let last_tool_result = |fake_model: &FakeLanguageModel| {
+        let completion = fake_model.pending_completions().pop().unwrap();
+        let message = completion.messages.last().unwrap();
+        let [MessageContent::ToolResult(result)] = message.content.as_slice() else {
+            panic!("expected a single tool result, got {:?}", message.content);
+        };
+        result.clone()
+    };
+
+    // Calls matching a deny rule fail without asking.
+    fake_model.send_last_completion_stream_event(echo("tool_id_1", "secret plans"));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(result.is_error);
+    assert_eq!(
+        result.content,
+        "Permission to run tool denied by the profile's tool permission rules".into()
+    );
+
+    // Calls matching an ask rule wait for confirmation, even though the tool never asks itself.
+    // They can't be allowed for every input, since that would also allow the denied ones.
+    fake_model.send_last_completion_stream_event(echo("tool_id_2", "hello"));
+    fake_model.end_last_completion_stream();
+    let authorization = loop {
+        if let ThreadEvent::ToolCallAuthorization(authorization) =
+            events.next().await.unwrap().unwrap()
+        {
+            break authorization;
+        }
+    };
+    assert_eq!(
+        authorization
+            .options
+            .iter()
+            .map(|option| option.option_id.0.to_string())
+            .collect::<Vec<_>>(),
+        vec!["always_allow_subject", "allow", "deny"]
+    );
+    authorization
+        .response
+        .send(authorization.options[0].option_id.clone())
+        .unwrap();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(!result.is_error);
+    assert_eq!(result.content, "hello".into());
+
+    // Always allowing the call saves a rule ahead of the rule that asked.
+    cx.read(|cx| {
+        let profile =
+            &AgentSettings::get_global(cx).profiles[&AgentProfileId("test-profile".into())];
+        assert_eq!(
+            profile
+                .tool_permissions
+                .rules
+                .iter()
+                .map(|rule| (rule.pattern.as_deref(), rule.action))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("secret*"), settings::ToolPermissionAction::Deny),
+                (Some("hello"), settings::ToolPermissionAction::Allow),
+                (Some("h*"), settings::ToolPermissionAction::Ask),
+                (Some("hush*"), settings::ToolPermissionAction::Deny),
+            ]
+        );
+    });
+
+    fake_model.send_last_completion_stream_event(echo("tool_id_3", "hello"));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(!result.is_error);
+    assert_eq!(result.content, "hello".into());
+}
+
+#[gpui::test]
+async fn test_tool_permission_subjects(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    fs.insert_tree(
+        path!("/test"),
+        json!({
+            "src": { "lib.rs": "fn main() {}" },
+            "secrets": { "key": "hunter2" },
+        }),
+    )
+    .await;
+    fs.insert_file(
+        paths::settings_file(),
+        json!({
+            "agent": {
+                "default_profile": "test-profile",
+                "profiles": {
+                    "test-profile": {
+                        "name": "Test Profile",
+                        "tools": {
+                            CopyPathTool::name(): true,
+                            EditFileTool::name(): true,
+                            ReadFileTool::name(): true,
+                        },
+                        "tool_permissions": [
+                            { "tool": EditFileTool::name(), "pattern": "src/**", "action": "deny" },
+                            { "tool": EditFileTool::name(), "pattern": ".zed/**", "action": "allow" },
+                            { "tool": CopyPathTool::name(), "pattern": "secrets/**", "action": "deny" },
+                            { "tool": ReadFileTool::name(), "pattern": "src/**", "action": "allow" },
+                            { "tool": "*", "action": "ask" },
+                        ]
+                    }
+                }
+            }
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    cx.run_until_parked();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            let project = thread.project().clone();
+            let language_registry = project.read(cx).languages().clone();
+            let action_log = thread.action_log().clone();
+            thread.add_tool(CopyPathTool::new(project.clone()));
+            thread.add_tool(EditFileTool::new(
+                project.clone(),
+                cx.weak_entity(),
+                language_registry,
+                Templates::new(),
+            ));
+            thread.add_tool(ReadFileTool::new(cx.weak_entity(), project, action_log));
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let tool_use = |id: &str, name: &str, input: serde_json::Value| {
+        LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+            id: id.into(),
+            name: name.into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        })
+    };
+    let last_tool_result = |fake_model: &FakeLanguageModel| {
+        let completion = fake_model.pending_completions().pop().unwrap();
+        let message = completion.messages.last().unwrap();
+        let [MessageContent::ToolResult(result)] = message.content.as_slice() else {
+            panic!("expected a single tool result, got {:?}", message.content);
+        };
+        result.clone()
+    };
+    let denied_by_rules = "Permission to run tool denied by the profile's tool permission rules";
+
+    // Paths are matched relative to the worktree, even though tools are called with paths that
+    // start with the worktree's name.
+    fake_model.send_last_completion_stream_event(tool_use(
+        "tool_id_1",
+        EditFileTool::name(),
+        json!({
+            "display_description": "Edit main",
+            "path": "test/src/main.rs",
+            "mode": "edit",
+        }),
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(result.is_error);
+    assert_eq!(result.content, denied_by_rules.into());
+
+    // Both the source and the destination of a copy are checked.
+    fake_model.send_last_completion_stream_event(tool_use(
+        "tool_id_2",
+        CopyPathTool::name(),
+        json!({
+            "source_path": "test/secrets/key",
+            "destination_path": "test/public/key",
+        }),
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(result.is_error);
+    assert_eq!(result.content, denied_by_rules.into());
+
+    // Paths that leave a directory through `..` don't match the directory's rules.
+    fake_model.send_last_completion_stream_event(tool_use(
+        "tool_id_3",
+        ReadFileTool::name(),
+        json!({ "path": "src/../secrets/key" }),
+    ));
+    fake_model.end_last_completion_stream();
+    let authorization = loop {
+        if let ThreadEvent::ToolCallAuthorization(authorization) =
+            events.next().await.unwrap().unwrap()
+        {
+            break authorization;
+        }
+    };
+    authorization
+        .response
+        .send(acp::PermissionOptionId::new("deny"))
+        .unwrap();
+    cx.run_until_parked();
+    assert!(last_tool_result(fake_model).is_error);
+
+    fake_model.send_last_completion_stream_event(tool_use(
+        "tool_id_4",
+        ReadFileTool::name(),
+        json!({ "path": "test/src/lib.rs" }),
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(!result.is_error);
+
+    // Edits to settings ask for confirmation even when a rule allows them.
+    fake_model.send_last_completion_stream_event(tool_use(
+        "tool_id_5",
+        EditFileTool::name(),
+        json!({
+            "display_description": "Edit settings",
+            "path": "test/.zed/settings.json",
+            "mode": "create",
+        }),
+    ));
+    fake_model.end_last_completion_stream();
+    let authorization = loop {
+        if let ThreadEvent::ToolCallAuthorization(authorization) =
+            events.next().await.unwrap().unwrap()
+        {
+            break authorization;
+        }
+    };
+    assert_eq!(
+        authorization.tool_call.fields.title,
+        Some("Edit settings (local settings)".into())
+    );
+    assert_eq!(
+        authorization
+            .options
+            .iter()
+            .map(|option| option.option_id.0.to_string())
+            .collect::<Vec<_>>(),
+        vec!["allow", "deny"]
+    );
+    authorization
+        .response
+        .send(acp::PermissionOptionId::new("deny"))
+        .unwrap();
+    cx.run_until_parked();
+    assert!(last_tool_result(fake_model).is_error);
+}
+
+#[gpui::test]
+async fn test_git_commit_permission_covers_staged_paths(cx: &mut TestAppContext) {
+    use git::status::StatusCode;
+
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    fs.insert_tree(
+        path!("/test"),
+        json!({
+            ".git": {},
+            "src": { "main.rs": "fn main() {}" },
+            ".github": { "workflows": { "ci.yml": "on: push" } },
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/test/.git"));
+    fs.set_status_for_repo(
+        dot_git,
+        &[
+            ("src/main.rs", StatusCode::Modified.worktree()),
+            (".github/workflows/ci.yml", StatusCode::Added.index()),
+        ],
+    );
+    fs.insert_file(
+        paths::settings_file(),
+        json!({
+            "agent": {
+                "default_profile": "test-profile",
+                "profiles": {
+                    "test-profile": {
+                        "name": "Test Profile",
+                        "tools": {
+                            GitCommitTool::name(): true,
+                        },
+                        "tool_permissions": [
+                            { "tool": GitCommitTool::name(), "pattern": "src/**", "action": "allow" },
+                        ]
+                    }
+                }
+            }
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    cx.run_until_parked();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(GitCommitTool::new(thread.project().clone()));
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let commit = |id: &str| {
+        let input = json!({ "message": "Update main", "paths": ["test/src/main.rs"] });
+        LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+            id: id.into(),
+            name: GitCommitTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        })
+    };
+    let last_tool_result = |fake_model: &FakeLanguageModel| {
+        let completion = fake_model.pending_completions().pop().unwrap();
+        let message = completion.messages.last().unwrap();
+        let [MessageContent::ToolResult(result)] = message.content.as_slice() else {
+            panic!("expected a single tool result, got {:?}", message.content);
+        };
+        result.clone()
+    };
+
+    // The rule doesn't allow a commit that would also include a file staged outside of `src`.
+    fake_model.send_last_completion_stream_event(commit("tool_id_1"));
+    fake_model.end_last_completion_stream();
+    let authorization = loop {
+        if let ThreadEvent::ToolCallAuthorization(authorization) =
+            events.next().await.unwrap().unwrap()
+        {
+            break authorization;
+        }
+    };
+    authorization
+        .response
+        .send(acp::PermissionOptionId::new("deny"))
+        .unwrap();
+    cx.run_until_parked();
+    assert!(last_tool_result(fake_model).is_error);
+    fs.with_git_state(dot_git, false, |state| assert!(state.log.is_empty()))
+        .unwrap();
+
+    // Once only files in `src` would be committed, the rule allows the commit.
+    fs.set_status_for_repo(dot_git, &[("src/main.rs", StatusCode::Modified.worktree())]);
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(commit("tool_id_2"));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(!result.is_error, "{:?}", result.content);
+    fs.with_git_state(dot_git, false, |state| assert_eq!(state.log.len(), 1))
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_subagent(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    fs.insert_file(
+        paths::settings_file(),
+        json!({
+            "agent": {
+                "default_profile": "test-profile",
+                "profiles": {
+                    "test-profile": {
+                        "name": "Test Profile",
+                        "tools": {
+                            EchoTool::name(): true,
+                            SubagentTool::name(): true,
+                        }
+                    },
+                    "other-profile": {
+                        "name": "Other Profile",
+                        "tools": {
+                            NowTool::name(): true,
+                        }
+                    }
+                }
+            }
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    cx.run_until_parked();
+
+    let environment = Rc::new(cx.update(|cx| FakeThreadEnvironment {
+        handle: Rc::new(FakeTerminalHandle::new_never_exits(cx)),
+    }));
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.add_tool(SubagentTool::new(cx.weak_entity(), environment));
+            thread.send(UserMessageId::new(), ["Rename foo to bar"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        tool_names_for_completion(&completion),
+        vec![EchoTool::name(), SubagentTool::name()]
+    );
+
+    // The sub-agent can't switch to a profile that enables tools the parent doesn't have.
+    let input = json!({
+        "label": "Rename in src",
+        "task": "Rename foo to bar in src/",
+        "profile": "other-profile",
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: SubagentTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The sub-agent works on the task in its own thread, and can't delegate further.
+    let subagent_completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(subagent_completion.messages.len(), 2);
+    assert!(
+        subagent_completion.messages[1]
+            .string_contents()
+            .starts_with("Rename foo to bar in src/")
+    );
+    assert_eq!(
+        tool_names_for_completion(&subagent_completion),
+        Vec::<String>::new()
+    );
+    fake_model.send_last_completion_stream_text_chunk("Renamed 3 occurrences.");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // Its progress is streamed into the card of the tool call that delegated to it.
+    let mut progress = None;
+    while let Ok(Some(event)) = events.try_next() {
+        if let Ok(ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update))) =
+            event
+            && update.fields.content.is_some()
+        {
+            progress = Some(update.fields);
+        }
+    }
+    let progress = progress.unwrap();
+    assert_eq!(progress.title.as_deref(), Some("Rename in src"));
+    assert!(format!("{:?}", progress.content).contains("Renamed 3 occurrences."));
+
+    // The parent gets the sub-agent's summary as the tool result.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![MessageContent::ToolResult(LanguageModelToolResult {
+            tool_use_id: "tool_id_1".into(),
+            tool_name: SubagentTool::name().into(),
+            is_error: false,
+            content: "Renamed 3 occurrences.".into(),
+            output: Some("Renamed 3 occurrences.".into()),
+        })]
+    );
+}
+
 #[gpui::test]
 async fn test_tool_hallucination(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -1302,33 +1834,339 @@ async fn test_mcp_tools(cx: &mut TestAppContext) {
     fake_model.end_last_completion_stream();
     events.collect::<Vec<_>>().await;
 
-    // Send again after adding the echo tool, ensuring the name collision is resolved.
-    let events = thread.update(cx, |thread, cx| {
-        thread.add_tool(EchoTool);
-        thread.send(UserMessageId::new(), ["Go"], cx).unwrap()
+    // Send again after adding the echo tool, ensuring the name collision is resolved.
+    let events = thread.update(cx, |thread, cx| {
+        thread.add_tool(EchoTool);
+        thread.send(UserMessageId::new(), ["Go"], cx).unwrap()
+    });
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        tool_names_for_completion(&completion),
+        vec!["echo", "test_server_echo"]
+    );
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_2".into(),
+            name: "test_server_echo".into(),
+            raw_input: json!({"text": "mcp"}).to_string(),
+            input: json!({"text": "mcp"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_3".into(),
+            name: "echo".into(),
+            raw_input: json!({"text": "native"}).to_string(),
+            input: json!({"text": "native"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let (tool_call_params, tool_call_response) = mcp_tool_calls.next().await.unwrap();
+    assert_eq!(tool_call_params.name, "echo");
+    assert_eq!(tool_call_params.arguments, Some(json!({"text": "mcp"})));
+    tool_call_response
+        .send(context_server::types::CallToolResponse {
+            content: vec![context_server::types::ToolResponseContent::Text { text: "mcp".into() }],
+            is_error: None,
+            meta: None,
+            structured_content: None,
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    // Ensure the tool results were inserted with the correct names.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![
+            MessageContent::ToolResult(LanguageModelToolResult {
+                tool_use_id: "tool_3".into(),
+                tool_name: "echo".into(),
+                is_error: false,
+                content: "native".into(),
+                output: Some("native".into()),
+            },),
+            MessageContent::ToolResult(LanguageModelToolResult {
+                tool_use_id: "tool_2".into(),
+                tool_name: "test_server_echo".into(),
+                is_error: false,
+                content: "mcp".into(),
+                output: Some("mcp".into()),
+            },),
+        ]
+    );
+    fake_model.end_last_completion_stream();
+    events.collect::<Vec<_>>().await;
+}
+
+#[gpui::test]
+async fn test_mcp_sampling(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model,
+        thread,
+        context_server_store,
+        fs,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    fs.insert_file(
+        paths::settings_file(),
+        json!({
+            "agent": {
+                "always_allow_tool_actions": true,
+                "profiles": {
+                    "test": {
+                        "name": "Test Profile",
+                        "enable_all_context_servers": true,
+                        "tools": {}
+                    },
+                }
+            }
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    cx.run_until_parked();
+    thread.update(cx, |thread, cx| {
+        thread.set_profile(AgentProfileId("test".into()), cx)
+    });
+
+    let (fake_server, mut mcp_tool_calls) = setup_fake_context_server(
+        "test_server",
+        vec![context_server::types::Tool {
+            name: "echo".into(),
+            description: None,
+            input_schema: serde_json::to_value(EchoTool::input_schema(
+                LanguageModelToolSchemaFormat::JsonSchema,
+            ))
+            .unwrap(),
+            output_schema: None,
+            annotations: None,
+        }],
+        &context_server_store,
+        cx,
+    );
+
+    let sampling_request =
+        |progress_token: Option<serde_json::Value>| context_server::types::CreateMessageRequest {
+            messages: vec![context_server::types::SamplingMessage {
+                role: context_server::types::Role::User,
+                content: context_server::types::MessageContent::Text {
+                    text: "Summarize this".into(),
+                    annotations: None,
+                },
+            }],
+            model_preferences: None,
+            system_prompt: Some("Be brief".into()),
+            include_context: None,
+            temperature: None,
+            max_tokens: 100,
+            stop_sequences: None,
+            metadata: None,
+            meta: progress_token.map(|progress_token| {
+                collections::HashMap::from_iter([("progressToken".to_string(), progress_token)])
+            }),
+        };
+
+    // Outside of the server's own tool calls, sampling requests are refused.
+    cx.run_until_parked();
+    let error = fake_server
+        .request::<context_server::types::requests::CreateMessage>(sampling_request(None))
+        .await
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("only answers sampling requests while its agent runs"),
+        "{error}"
+    );
+
+    let mut events = thread.update(cx, |thread, cx| {
+        thread.send(UserMessageId::new(), ["Hey"], cx).unwrap()
+    });
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_1".into(),
+            name: "echo".into(),
+            raw_input: json!({"text": "test"}).to_string(),
+            input: json!({"text": "test"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // While the tool runs, the server asks to sample the model on behalf of the tool call, which
+    // it names by the call's progress token. Requests that don't name it are still refused.
+    let (tool_call_params, tool_call_response) = mcp_tool_calls.next().await.unwrap();
+    let progress_token = tool_call_params.meta.unwrap()["progressToken"].clone();
+    for progress_token in [None, Some(json!("another-call"))] {
+        let error = fake_server
+            .request::<context_server::types::requests::CreateMessage>(sampling_request(
+                progress_token,
+            ))
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("only answers sampling requests while its agent runs"),
+            "{error}"
+        );
+    }
+    let sampling = cx.executor().spawn(async move {
+        fake_server
+            .request::<context_server::types::requests::CreateMessage>(sampling_request(Some(
+                progress_token,
+            )))
+            .await
+    });
+    cx.run_until_parked();
+
+    // The user is asked to allow it, even though tool actions are always allowed.
+    let authorization = loop {
+        if let ThreadEvent::ToolCallAuthorization(authorization) =
+            events.next().await.unwrap().unwrap()
+        {
+            break authorization;
+        }
+    };
+    assert_eq!(
+        authorization.tool_call.fields.title.as_deref(),
+        Some("Allow `test_server` to send this prompt to Fake?")
+    );
+    authorization
+        .response
+        .send(acp::PermissionOptionId::new("allow"))
+        .unwrap();
+    cx.run_until_parked();
+
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(completion.max_output_tokens, Some(100));
+    assert_eq!(
+        completion.messages,
+        vec![
+            LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec!["Be brief".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Summarize this".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+        ]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Short summary");
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        language_model::TokenUsage {
+            input_tokens: 10,
+            output_tokens: 5,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let result = sampling.await.unwrap();
+    assert!(matches!(
+        result.content,
+        context_server::types::MessageContent::Text { text, .. } if text == "Short summary"
+    ));
+    let db_thread = thread.read_with(cx, |thread, cx| thread.to_db(cx)).await;
+    assert_eq!(db_thread.cumulative_token_usage.total_tokens(), 15);
+
+    tool_call_response
+        .send(context_server::types::CallToolResponse {
+            content: vec![context_server::types::ToolResponseContent::Text {
+                text: "Short summary".into(),
+            }],
+            is_error: None,
+            meta: None,
+            structured_content: None,
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Done!");
+    fake_model.end_last_completion_stream();
+    events.collect::<Vec<_>>().await;
+}
+
+#[gpui::test]
+async fn test_mcp_elicitation(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model,
+        thread,
+        context_server_store,
+        fs,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    fs.insert_file(
+        paths::settings_file(),
+        json!({
+            "agent": {
+                "always_allow_tool_actions": true,
+                "profiles": {
+                    "test": {
+                        "name": "Test Profile",
+                        "enable_all_context_servers": true,
+                        "tools": {}
+                    },
+                }
+            }
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    cx.run_until_parked();
+    thread.update(cx, |thread, cx| {
+        thread.set_profile(AgentProfileId("test".into()), cx)
+    });
+
+    let (fake_server, mut mcp_tool_calls) = setup_fake_context_server(
+        "test_server",
+        vec![context_server::types::Tool {
+            name: "echo".into(),
+            description: None,
+            input_schema: serde_json::to_value(EchoTool::input_schema(
+                LanguageModelToolSchemaFormat::JsonSchema,
+            ))
+            .unwrap(),
+            output_schema: None,
+            annotations: None,
+        }],
+        &context_server_store,
+        cx,
+    );
+
+    let mut events = thread.update(cx, |thread, cx| {
+        thread.send(UserMessageId::new(), ["Hey"], cx).unwrap()
     });
     cx.run_until_parked();
-    let completion = fake_model.pending_completions().pop().unwrap();
-    assert_eq!(
-        tool_names_for_completion(&completion),
-        vec!["echo", "test_server_echo"]
-    );
-    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
-        LanguageModelToolUse {
-            id: "tool_2".into(),
-            name: "test_server_echo".into(),
-            raw_input: json!({"text": "mcp"}).to_string(),
-            input: json!({"text": "mcp"}),
-            is_input_complete: true,
-            thought_signature: None,
-        },
-    ));
     fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
         LanguageModelToolUse {
-            id: "tool_3".into(),
+            id: "tool_1".into(),
             name: "echo".into(),
-            raw_input: json!({"text": "native"}).to_string(),
-            input: json!({"text": "native"}),
+            raw_input: json!({"text": "test"}).to_string(),
+            input: json!({"text": "test"}),
             is_input_complete: true,
             thought_signature: None,
         },
@@ -1336,40 +2174,97 @@ async fn test_mcp_tools(cx: &mut TestAppContext) {
     fake_model.end_last_completion_stream();
     cx.run_until_parked();
 
+    // While the tool runs, the server asks the user for input on behalf of the tool call, which it
+    // names by the call's progress token. Requests that don't name it are refused.
     let (tool_call_params, tool_call_response) = mcp_tool_calls.next().await.unwrap();
-    assert_eq!(tool_call_params.name, "echo");
-    assert_eq!(tool_call_params.arguments, Some(json!({"text": "mcp"})));
+    let progress_token = tool_call_params.meta.unwrap()["progressToken"].clone();
+    let elicit_request =
+        |message: &str, requested_schema, progress_token| context_server::types::ElicitRequest {
+            message: message.into(),
+            requested_schema,
+            meta: Some(collections::HashMap::from_iter([(
+                "progressToken".to_string(),
+                progress_token,
+            )])),
+        };
+    let branch_schema = json!({
+        "type": "object",
+        "properties": { "branch": { "type": "string" } },
+        "required": ["branch"]
+    });
+    let error = fake_server
+        .request::<context_server::types::requests::Elicit>(elicit_request(
+            "Which branch?",
+            branch_schema.clone(),
+            json!("another-call"),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("only answers elicitation requests while its agent runs"),
+        "{error}"
+    );
+    let elicitation = cx.executor().spawn({
+        let fake_server = fake_server.clone();
+        let request = elicit_request("Which branch?", branch_schema, progress_token.clone());
+        async move {
+            fake_server
+                .request::<context_server::types::requests::Elicit>(request)
+                .await
+        }
+    });
+    cx.run_until_parked();
+
+    let input_request = loop {
+        if let ThreadEvent::ToolCallInputRequest(input_request) =
+            events.next().await.unwrap().unwrap()
+        {
+            break input_request;
+        }
+    };
+    assert_eq!(input_request.tool_call_id, acp::ToolCallId::new("tool_1"));
+    assert_eq!(input_request.request.requester, "test_server");
+    assert_eq!(input_request.request.message, "Which branch?");
+    assert_eq!(input_request.request.fields[0].name, "branch");
+    let mut content = serde_json::Map::new();
+    content.insert("branch".into(), json!("main"));
+    input_request
+        .response
+        .send(acp_thread::InputResponse::Accept(content.clone()))
+        .unwrap();
+    cx.run_until_parked();
+
+    let result = elicitation.await.unwrap();
+    assert_eq!(result.action, context_server::types::ElicitAction::Accept);
+    assert_eq!(result.content, Some(content));
+
+    // Schemas that can't be shown as a form are rejected without asking.
+    let result = fake_server
+        .request::<context_server::types::requests::Elicit>(elicit_request(
+            "Which files?",
+            json!({
+                "type": "object",
+                "properties": { "files": { "type": "array" } }
+            }),
+            progress_token,
+        ))
+        .await;
+    assert!(result.is_err());
+
     tool_call_response
         .send(context_server::types::CallToolResponse {
-            content: vec![context_server::types::ToolResponseContent::Text { text: "mcp".into() }],
+            content: vec![context_server::types::ToolResponseContent::Text {
+                text: "On main".into(),
+            }],
             is_error: None,
             meta: None,
             structured_content: None,
         })
         .unwrap();
     cx.run_until_parked();
-
-    // Ensure the tool results were inserted with the correct names.
-    let completion = fake_model.pending_completions().pop().unwrap();
-    assert_eq!(
-        completion.messages.last().unwrap().content,
-        vec![
-            MessageContent::ToolResult(LanguageModelToolResult {
-                tool_use_id: "tool_3".into(),
-                tool_name: "echo".into(),
-                is_error: false,
-                content: "native".into(),
-                output: Some("native".into()),
-            },),
-            MessageContent::ToolResult(LanguageModelToolResult {
-                tool_use_id: "tool_2".into(),
-                tool_name: "test_server_echo".into(),
-                is_error: false,
-                content: "mcp".into(),
-                output: Some("mcp".into()),
-            },),
-        ]
-    );
+    fake_model.send_last_completion_stream_text_chunk("Done!");
     fake_model.end_last_completion_stream();
     events.collect::<Vec<_>>().await;
 }
@@ -1964,6 +2859,178 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
     assert_first_message_state(cx);
 }
 
+#[gpui::test]
+async fn test_fork_thread(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let mut message_ids = Vec::new();
+    for ix in 1..=2 {
+        let message_id = UserMessageId::new();
+        message_ids.push(message_id.clone());
+        thread
+            .update(cx, |thread, cx| {
+                thread.send(message_id, [format!("Message {ix}").as_str()], cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_text_chunk(format!("Message {ix} response"));
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            language_model::TokenUsage {
+                input_tokens: 32_000,
+                output_tokens: 16_000,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
+            },
+        ));
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+    }
+
+    let checkpoint = serde_json::from_value::<acp_thread::Checkpoint>(json!({
+        "git_checkpoint": { "checkpoints_by_work_dir_abs_path": {} },
+        "show": true,
+    }))
+    .unwrap();
+    let checkpoints = message_ids
+        .iter()
+        .map(|id| (id.clone(), checkpoint.clone()))
+        .collect::<Vec<_>>();
+
+    let fork = thread
+        .read_with(cx, |thread, cx| {
+            thread.fork(Some(&message_ids[1]), checkpoints.clone(), cx)
+        })
+        .unwrap()
+        .await;
+    assert_eq!(
+        fork.to_markdown(),
+        indoc! {"
+            # New Thread (fork)
+
+            ## User
+
+            Message 1
+
+            ## Assistant
+
+            Message 1 response
+        "}
+    );
+    assert!(fork.request_token_usage.contains_key(&message_ids[0]));
+    assert!(!fork.request_token_usage.contains_key(&message_ids[1]));
+    // The fork only keeps the checkpoints of the messages it kept, and saves them.
+    let fork = DbThread::from_json(fork.to_json().unwrap().as_bytes()).unwrap();
+    assert_eq!(
+        fork.forked_checkpoints
+            .iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![&message_ids[0]]
+    );
+
+    // Forking leaves the original thread as it was.
+    thread.read_with(cx, |thread, _| {
+        assert!(thread.to_markdown().ends_with("Message 2 response\n"));
+    });
+
+    let fork = thread
+        .read_with(cx, |thread, cx| thread.fork(None, checkpoints.clone(), cx))
+        .unwrap()
+        .await;
+    assert_eq!(fork.messages.len(), 4);
+    assert_eq!(fork.forked_checkpoints.len(), 2);
+
+    thread.read_with(cx, |thread, cx| {
+        assert!(
+            thread
+                .fork(Some(&UserMessageId::new()), Vec::new(), cx)
+                .is_err()
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_update_plan(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    thread.update(cx, |thread, cx| {
+        thread.add_tool(UpdatePlanTool::new(cx.weak_entity()))
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Fix the bug"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let input = json!({
+        "steps": [
+            {"content": "Find the bug", "status": "in_progress"},
+            {"content": "Fix it", "status": "pending"},
+        ]
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: UpdatePlanTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let MessageContent::ToolResult(result) = &completion.messages.last().unwrap().content[0] else {
+        panic!("expected a tool result");
+    };
+    assert_eq!(
+        result.content.to_str(),
+        Some("Updated the plan:\n\n- [ ] Find the bug (in progress)\n- [ ] Fix it\n")
+    );
+    let mut plans = Vec::new();
+    while let Ok(Some(event)) = events.try_next() {
+        if let Ok(ThreadEvent::Plan(plan)) = event {
+            plans.push(plan);
+        }
+    }
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0].entries.len(), 2);
+
+    fake_model.send_last_completion_stream_text_chunk("Done");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The model learns about the plan the user edited before its next request.
+    thread.update(cx, |thread, cx| {
+        let mut entries = thread.plan().to_vec();
+        entries.reverse();
+        entries[1].status = acp::PlanEntryStatus::Completed;
+        thread.edit_plan(entries, cx);
+    });
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Continue"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![MessageContent::Text(
+            "I changed the plan, follow this version of it:\n\n- [ ] Fix it\n- [x] Find the bug\n"
+                .into()
+        )]
+    );
+
+    let db_thread = thread.read_with(cx, |thread, cx| thread.to_db(cx)).await;
+    assert_eq!(db_thread.plan.len(), 2);
+    assert_eq!(db_thread.plan[0].content, "Fix it");
+}
+
 #[gpui::test]
 async fn test_title_generation(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -2090,6 +3157,157 @@ async fn test_building_request_with_pending_tools(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    for (prompt, reply) in [("One", "Uno"), ("Two", "Dos"), ("Three", "Tres")] {
+        thread
+            .update(cx, |thread, cx| {
+                thread.send(UserMessageId::new(), [prompt], cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_text_chunk(reply);
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+    }
+
+    let request_contents = |cx: &mut TestAppContext| {
+        thread
+            .read_with(cx, |thread, cx| {
+                thread.build_completion_request(CompletionIntent::UserPrompt, cx)
+            })
+            .unwrap()
+            .messages[1..]
+            .iter()
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        request_contents(cx),
+        vec!["One", "Uno", "Two", "Dos", "Three", "Tres"]
+    );
+
+    // Only the turns before the kept ones are summarized.
+    let compact = thread.update(cx, |thread, cx| {
+        assert!(thread.can_compact());
+        thread.compact(cx)
+    });
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| assert!(thread.is_compacting()));
+    let summarization_request = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        summarization_request
+            .messages
+            .iter()
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>(),
+        vec!["One", "Uno", SUMMARIZE_THREAD_DETAILED_PROMPT]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Counted to one.");
+    fake_model.end_last_completion_stream();
+    compact.await.unwrap();
+
+    let contents = request_contents(cx);
+    assert!(contents[0].contains("<compacted_context>"));
+    assert!(contents[0].contains("Counted to one."));
+    assert_eq!(contents[1..], ["Two", "Dos", "Three", "Tres"]);
+    thread.read_with(cx, |thread, _| {
+        assert!(!thread.is_compacting());
+        assert!(!thread.can_compact());
+    });
+
+    // Undoing the compaction sends every turn verbatim again.
+    thread.update(cx, |thread, cx| thread.undo_compaction(cx));
+    assert_eq!(
+        request_contents(cx),
+        vec!["One", "Uno", "Two", "Dos", "Three", "Tres"]
+    );
+}
+
+#[gpui::test]
+async fn test_compaction_within_turn(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["Go"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    for step in 1..=5 {
+        fake_model.send_last_completion_stream_text_chunk(format!("Step {step}"));
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+            LanguageModelToolUse {
+                id: format!("tool_{step}").into(),
+                name: EchoTool::name().into(),
+                raw_input: json!({"text": format!("Result {step}")}).to_string(),
+                input: json!({"text": format!("Result {step}")}),
+                is_input_complete: true,
+                thought_signature: None,
+            },
+        ));
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+    }
+    fake_model.send_last_completion_stream_text_chunk("Done");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // With a single turn, the older tool calls and results of that turn are summarized.
+    let compact = thread.update(cx, |thread, cx| {
+        assert!(thread.can_compact());
+        thread.compact(cx)
+    });
+    cx.run_until_parked();
+    let summarization_request = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        summarization_request
+            .messages
+            .iter()
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>(),
+        vec![
+            "Go",
+            "Step 1",
+            "Result 1",
+            "Step 2",
+            "Result 2",
+            SUMMARIZE_THREAD_DETAILED_PROMPT
+        ]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Ran two steps.");
+    fake_model.end_last_completion_stream();
+    compact.await.unwrap();
+
+    let request = thread
+        .read_with(cx, |thread, cx| {
+            thread.build_completion_request(CompletionIntent::UserPrompt, cx)
+        })
+        .unwrap();
+    let contents = request.messages[1..]
+        .iter()
+        .map(|message| message.string_contents())
+        .collect::<Vec<_>>();
+    assert!(contents[0].contains("Ran two steps."));
+    assert_eq!(
+        contents[1..],
+        [
+            "Step 3", "Result 3", "Step 4", "Result 4", "Step 5", "Result 5", "Done"
+        ]
+    );
+    // Every kept tool call is still sent along with its result.
+    assert_eq!(request.messages[2].role, Role::Assistant);
+    thread.read_with(cx, |thread, _| {
+        assert_eq!(thread.compaction().unwrap().summarized_turn_messages, 3);
+        assert!(!thread.can_compact());
+    });
+}
+
 #[gpui::test]
 async fn test_agent_connection(cx: &mut TestAppContext) {
     cx.update(settings::init);
@@ -2612,6 +3830,7 @@ async fn setup(cx: &mut TestAppContext, model: TestModel) -> ThreadTest {
                             ToolRequiringPermission::name(): true,
                             InfiniteTool::name(): true,
                             ThinkingTool::name(): true,
+                            UpdatePlanTool::name(): true,
                         }
                     }
                 }
@@ -2740,6 +3959,23 @@ fn setup_context_server(
     context_server::types::CallToolParams,
     oneshot::Sender<context_server::types::CallToolResponse>,
 )> {
+    setup_fake_context_server(name, tools, context_server_store, cx).1
+}
+
+/// Like [`setup_context_server`], also returning a handle for sending requests to the client as
+/// the server.
+fn setup_fake_context_server(
+    name: &'static str,
+    tools: Vec<context_server::types::Tool>,
+    context_server_store: &Entity<ContextServerStore>,
+    cx: &mut TestAppContext,
+) -> (
+    context_server::test::FakeServer,
+    mpsc::UnboundedReceiver<(
+        context_server::types::CallToolParams,
+        oneshot::Sender<context_server::types::CallToolResponse>,
+    )>,
+) {
     cx.update(|cx| {
         let mut settings = ProjectSettings::get_global(cx).clone();
         settings.context_servers.insert(
@@ -2758,7 +3994,9 @@ fn setup_context_server(
     });
 
     let (mcp_tool_calls_tx, mcp_tool_calls_rx) = mpsc::unbounded();
-    let fake_transport = context_server::test::create_fake_transport(name, cx.executor())
+    let fake_transport = context_server::test::create_fake_transport(name, cx.executor());
+    let fake_server = fake_transport.server();
+    let fake_transport = fake_transport
         .on_request::<context_server::types::requests::Initialize, _>(move |_params| async move {
             context_server::types::InitializeResponse {
                 protocol_version: context_server::types::ProtocolVersion(
@@ -2807,7 +4045,7 @@ fn setup_context_server(
         );
     });
     cx.run_until_parked();
-    mcp_tool_calls_rx
+    (fake_server, mcp_tool_calls_rx)
 }
 
 #[gpui::test]
@@ -24,6 +24,10 @@ impl AgentTool for EchoTool {
         acp::ToolKind::Other
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Text(input.text.clone())]
+    }
+
     fn initial_title(
         &self,
         _input: Result<Self::Input, serde_json::Value>,
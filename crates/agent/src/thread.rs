@@ -1,17 +1,21 @@
+use crate::hooks::{HookInput, HookOutput, Hooks};
 use crate::{
     ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
-    RestoreFileFromDiskTool, SaveFileTool, SystemPromptTemplate, Template, Templates, TerminalTool,
-    ThinkingTool, WebSearchTool,
+    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, FindReferencesTool,
+    GitBlameTool, GitCommitTool, GitDiffTool, GitLogTool, GitStatusTool, GoToDefinitionTool,
+    GrepTool, ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
+    RenameSymbolTool, RestoreFileFromDiskTool, SaveFileTool, SubagentTool, SystemPromptTemplate,
+    Template, Templates, TerminalTool, ThinkingTool, UpdatePlanTool, WebSearchTool,
+    WorkspaceSymbolsTool, plan_to_markdown,
 };
-use acp_thread::{MentionUri, UserMessageId};
+use acp_thread::{Checkpoint, MentionUri, UserMessageId};
 use action_log::ActionLog;
 
 use agent_client_protocol as acp;
 use agent_settings::{
-    AgentProfileId, AgentProfileSettings, AgentSettings, CompletionMode,
-    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT,
+    AgentProfile, AgentProfileId, AgentProfileSettings, AgentSettings, CompletionMode,
+    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT, ToolPermissionMatch,
+    ToolPermissionRule, split_command,
 };
 use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
@@ -37,16 +41,16 @@ use language_model::{
     LanguageModelToolUse, LanguageModelToolUseId, Role, SelectedModel, StopReason, TokenUsage,
     ZED_CLOUD_PROVIDER_ID,
 };
-use project::Project;
+use project::{Project, git_store::GitStoreCheckpoint};
 use prompt_store::ProjectContext;
 use schemars::{JsonSchema, Schema};
 use serde::{Deserialize, Serialize};
-use settings::{LanguageModelSelection, Settings, update_settings_file};
+use settings::{AgentHookEvent, LanguageModelSelection, Settings, ToolPermissionAction};
 use smol::stream::StreamExt;
 use std::{
     collections::BTreeMap,
     ops::RangeInclusive,
-    path::Path,
+    path::{Component, Path},
     rc::Rc,
     sync::Arc,
     time::{Duration, Instant},
@@ -57,6 +61,15 @@ use uuid::Uuid;
 
 const TOOL_CANCELED_MESSAGE: &str = "Tool canceled by user";
 pub const MAX_TOOL_NAME_LENGTH: usize = 64;
+/// The number of most recent user turns, with their tool calls and results, that compaction
+/// keeps verbatim.
+const COMPACTION_KEPT_TURNS: usize = 2;
+/// The number of most recent agent messages, with their tool calls and results, that compaction
+/// keeps verbatim when it summarizes the older part of the current turn.
+const COMPACTION_KEPT_AGENT_MESSAGES: usize = 4;
+/// How many times `turn_end` hooks can make the model continue a turn, so that a hook that keeps
+/// failing can't keep the agent running forever.
+const MAX_TURN_END_HOOK_CONTINUATIONS: usize = 3;
 
 /// The ID of the user prompt that initiated a request.
 ///
@@ -141,6 +154,50 @@ impl Message {
     }
 }
 
+/// A summary that stands in for the messages preceding `first_kept_message` in requests.
+///
+/// The summarized messages stay in the thread, so compaction can be undone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Compaction {
+    pub summary: String,
+    pub first_kept_message: UserMessageId,
+    /// How many messages of the turn starting at `first_kept_message` are summarized too, when
+    /// compaction cut into that turn to summarize its older tool calls and results.
+    #[serde(default)]
+    pub summarized_turn_messages: usize,
+}
+
+impl Compaction {
+    fn to_request(&self) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![
+                format!(
+                    "<compacted_context>\n\
+                    The earlier part of this conversation was summarized to fit in the context window:\n\n\
+                    {}\n\
+                    </compacted_context>",
+                    self.summary.trim()
+                )
+                .into(),
+            ],
+            cache: false,
+            reasoning_details: None,
+        }
+    }
+}
+
+/// Where a forked thread's conversation diverged from the thread it was forked from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkOrigin {
+    /// The title of the thread this one was forked from.
+    pub title: SharedString,
+    /// The state of the project before the first message the fork left out was sent.
+    pub base_checkpoint: GitStoreCheckpoint,
+    /// The state the original thread had left the project in when it was forked.
+    pub original_checkpoint: GitStoreCheckpoint,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserMessage {
     pub id: UserMessageId,
@@ -198,6 +255,7 @@ impl UserMessage {
         const OPEN_SELECTIONS_TAG: &str = "<selections>";
         const OPEN_THREADS_TAG: &str = "<threads>";
         const OPEN_FETCH_TAG: &str = "<fetched_urls>";
+        const OPEN_RESOURCES_TAG: &str = "<context_server_resources>";
         const OPEN_RULES_TAG: &str =
             "<rules>\nThe user has specified the following rules that should be applied:\n";
 
@@ -207,6 +265,7 @@ impl UserMessage {
         let mut selection_context = OPEN_SELECTIONS_TAG.to_string();
         let mut thread_context = OPEN_THREADS_TAG.to_string();
         let mut fetch_context = OPEN_FETCH_TAG.to_string();
+        let mut resource_context = OPEN_RESOURCES_TAG.to_string();
         let mut rules_context = OPEN_RULES_TAG.to_string();
 
         for chunk in &self.content {
@@ -289,6 +348,10 @@ impl UserMessage {
                         MentionUri::Fetch { url } => {
                             write!(&mut fetch_context, "\nFetch: {}\n\n{}", url, content).ok();
                         }
+                        MentionUri::ContextServerResource { uri, .. } => {
+                            write!(&mut resource_context, "\nResource: {}\n\n{}", uri, content)
+                                .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -342,6 +405,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(fetch_context));
         }
 
+        if resource_context.len() > OPEN_RESOURCES_TAG.len() {
+            resource_context.push_str("</context_server_resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(resource_context));
+        }
+
         if rules_context.len() > OPEN_RULES_TAG.len() {
             rules_context.push_str("</user_rules>\n");
             message
@@ -558,7 +628,9 @@ pub enum ThreadEvent {
     ToolCall(acp::ToolCall),
     ToolCallUpdate(acp_thread::ToolCallUpdate),
     ToolCallAuthorization(ToolCallAuthorization),
+    ToolCallInputRequest(ToolCallInputRequest),
     Retry(acp_thread::RetryStatus),
+    Plan(acp::Plan),
     Stop(acp::StopReason),
 }
 
@@ -577,6 +649,13 @@ pub struct ToolCallAuthorization {
     pub response: oneshot::Sender<acp::PermissionOptionId>,
 }
 
+#[derive(Debug)]
+pub struct ToolCallInputRequest {
+    pub tool_call_id: acp::ToolCallId,
+    pub request: acp_thread::InputRequest,
+    pub response: oneshot::Sender<acp_thread::InputResponse>,
+}
+
 #[derive(Debug, thiserror::Error)]
 enum CompletionError {
     #[error("max tokens")]
@@ -596,6 +675,15 @@ pub struct Thread {
     pending_summary_generation: Option<Shared<Task<Option<SharedString>>>>,
     summary: Option<SharedString>,
     messages: Vec<Message>,
+    compaction: Option<Compaction>,
+    is_compacting: bool,
+    /// The steps the model plans to work through, shown to the user as a checklist.
+    plan: Vec<acp::PlanEntry>,
+    /// Whether the user changed the plan since the model last saw it.
+    plan_edited: bool,
+    /// The checkpoints of the user messages this thread was forked with.
+    forked_checkpoints: Vec<(UserMessageId, Checkpoint)>,
+    fork_origin: Option<ForkOrigin>,
     user_store: Entity<UserStore>,
     completion_mode: CompletionMode,
     /// Holds the task that handles agent interaction until the end of the turn.
@@ -604,6 +692,7 @@ pub struct Thread {
     running_turn: Option<RunningTurn>,
     pending_message: Option<AgentMessage>,
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
+    allowed_tools: Option<HashSet<SharedString>>,
     tool_use_limit_reached: bool,
     request_token_usage: HashMap<UserMessageId, language_model::TokenUsage>,
     #[allow(unused)]
@@ -653,11 +742,18 @@ impl Thread {
             pending_summary_generation: None,
             summary: None,
             messages: Vec::new(),
+            compaction: None,
+            is_compacting: false,
+            plan: Vec::new(),
+            plan_edited: false,
+            forked_checkpoints: Vec::new(),
+            fork_origin: None,
             user_store: project.read(cx).user_store(),
             completion_mode: AgentSettings::get_global(cx).preferred_completion_mode,
             running_turn: None,
             pending_message: None,
             tools: BTreeMap::default(),
+            allowed_tools: None,
             tool_use_limit_reached: false,
             request_token_usage: HashMap::default(),
             cumulative_token_usage: TokenUsage::default(),
@@ -681,6 +777,25 @@ impl Thread {
         }
     }
 
+    /// Creates a thread for a sub-agent working on a subtask of `parent`. It shares the parent's
+    /// project, profile, model and action log, so its edits can be reviewed along with the parent's.
+    pub(crate) fn new_subagent(parent: &Thread, cx: &mut Context<Self>) -> Self {
+        let mut thread = Self::new(
+            parent.project.clone(),
+            parent.project_context.clone(),
+            parent.context_server_registry.clone(),
+            parent.templates.clone(),
+            parent.model.clone(),
+            cx,
+        );
+        // Sub-agents share the parent's profile, so they can't get tools or tool permissions
+        // that the parent doesn't have.
+        thread.profile_id = parent.profile_id.clone();
+        thread.completion_mode = parent.completion_mode;
+        thread.action_log = parent.action_log.clone();
+        thread
+    }
+
     pub fn id(&self) -> &acp::SessionId {
         &self.id
     }
@@ -716,6 +831,9 @@ impl Thread {
                 Message::Resume => {}
             }
         }
+        if !self.plan.is_empty() {
+            stream.send_plan(&self.plan);
+        }
         rx
     }
 
@@ -845,11 +963,18 @@ impl Thread {
             pending_summary_generation: None,
             summary: db_thread.detailed_summary,
             messages: db_thread.messages,
+            compaction: db_thread.compaction,
+            is_compacting: false,
+            plan: db_thread.plan,
+            plan_edited: false,
+            forked_checkpoints: db_thread.forked_checkpoints,
+            fork_origin: db_thread.fork_origin,
             user_store: project.read(cx).user_store(),
             completion_mode: db_thread.completion_mode.unwrap_or_default(),
             running_turn: None,
             pending_message: None,
             tools: BTreeMap::default(),
+            allowed_tools: None,
             tool_use_limit_reached: false,
             request_token_usage: db_thread.request_token_usage.clone(),
             cumulative_token_usage: db_thread.cumulative_token_usage,
@@ -874,6 +999,10 @@ impl Thread {
         let mut thread = DbThread {
             title: self.title(),
             messages: self.messages.clone(),
+            compaction: self.compaction.clone(),
+            plan: self.plan.clone(),
+            forked_checkpoints: self.forked_checkpoints.clone(),
+            fork_origin: self.fork_origin.clone(),
             updated_at: self.updated_at,
             detailed_summary: self.summary.clone(),
             initial_project_snapshot: None,
@@ -926,6 +1055,58 @@ impl Thread {
         self.messages.is_empty() && self.title.is_none()
     }
 
+    pub fn plan(&self) -> &[acp::PlanEntry] {
+        &self.plan
+    }
+
+    pub fn forked_checkpoints(&self) -> &[(UserMessageId, Checkpoint)] {
+        &self.forked_checkpoints
+    }
+
+    pub fn fork_origin(&self) -> Option<&ForkOrigin> {
+        self.fork_origin.as_ref()
+    }
+
+    /// Replaces the plan with one the model sent, and shows it to the user.
+    pub(crate) fn set_plan(&mut self, entries: Vec<acp::PlanEntry>, cx: &mut Context<Self>) {
+        self.plan = entries;
+        self.plan_edited = false;
+        if let Some(turn) = &self.running_turn {
+            turn.event_stream.send_plan(&self.plan);
+        }
+        cx.notify();
+    }
+
+    /// Replaces the plan with one the user edited. The model learns about the changes before its
+    /// next request.
+    pub fn edit_plan(&mut self, entries: Vec<acp::PlanEntry>, cx: &mut Context<Self>) {
+        self.plan = entries;
+        self.plan_edited = true;
+        cx.notify();
+    }
+
+    /// Tells the model about the changes the user made to the plan, if there are any.
+    fn send_plan_edits(&mut self, event_stream: &ThreadEventStream, cx: &mut Context<Self>) {
+        if !std::mem::take(&mut self.plan_edited) {
+            return;
+        }
+        let text = if self.plan.is_empty() {
+            "I removed the plan.".to_string()
+        } else {
+            format!(
+                "I changed the plan, follow this version of it:\n\n{}",
+                plan_to_markdown(&self.plan)
+            )
+        };
+        let message = UserMessage {
+            id: UserMessageId::new(),
+            content: vec![UserMessageContent::Text(text)],
+        };
+        event_stream.send_user_message(&message);
+        self.messages.push(Message::User(message));
+        cx.notify();
+    }
+
     pub fn model(&self) -> Option<&Arc<dyn LanguageModel>> {
         self.model.as_ref()
     }
@@ -999,6 +1180,13 @@ impl Thread {
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(FindReferencesTool::new(self.project.clone()));
+        self.add_tool(GitBlameTool::new(self.project.clone()));
+        self.add_tool(GitCommitTool::new(self.project.clone()));
+        self.add_tool(GitDiffTool::new(self.project.clone()));
+        self.add_tool(GitLogTool::new(self.project.clone()));
+        self.add_tool(GitStatusTool::new(self.project.clone()));
+        self.add_tool(GoToDefinitionTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
@@ -1009,11 +1197,18 @@ impl Thread {
             self.project.clone(),
             self.action_log.clone(),
         ));
+        self.add_tool(RenameSymbolTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
         self.add_tool(SaveFileTool::new(self.project.clone()));
         self.add_tool(RestoreFileFromDiskTool::new(self.project.clone()));
+        self.add_tool(SubagentTool::new(cx.weak_entity(), environment.clone()));
         self.add_tool(TerminalTool::new(self.project.clone(), environment));
         self.add_tool(ThinkingTool);
+        self.add_tool(UpdatePlanTool::new(cx.weak_entity()));
         self.add_tool(WebSearchTool);
+        self.add_tool(WorkspaceSymbolsTool::new(self.project.clone()));
     }
 
     pub fn add_tool<T: AgentTool>(&mut self, tool: T) {
@@ -1024,6 +1219,11 @@ impl Thread {
         self.tools.remove(name).is_some()
     }
 
+    /// Limits the tools offered to the model to the given ones, on top of the profile's settings.
+    pub fn set_allowed_tools(&mut self, allowed_tools: Option<HashSet<SharedString>>) {
+        self.allowed_tools = allowed_tools;
+    }
+
     pub fn profile(&self) -> &AgentProfileId {
         &self.profile_id
     }
@@ -1071,15 +1271,75 @@ impl Thread {
             match message {
                 Message::User(message) => {
                     self.request_token_usage.remove(&message.id);
+                    self.forked_checkpoints.retain(|(id, _)| id != &message.id);
                 }
                 Message::Agent(_) | Message::Resume => {}
             }
         }
+        if self.compaction_start_ix().is_none() {
+            self.compaction = None;
+        }
         self.clear_summary();
         cx.notify();
         Ok(())
     }
 
+    /// Returns a copy of this thread that ends before the given user message, or a copy of the
+    /// whole thread when no message is given, to continue the conversation differently in a new
+    /// thread. The copy keeps the checkpoints of the messages it keeps.
+    pub fn fork(
+        &self,
+        message_id: Option<&UserMessageId>,
+        checkpoints: Vec<(UserMessageId, Checkpoint)>,
+        cx: &App,
+    ) -> Result<Task<DbThread>> {
+        let end = match message_id {
+            Some(message_id) => self
+                .messages
+                .iter()
+                .position(
+                    |msg| matches!(msg, Message::User(UserMessage { id, .. }) if id == message_id),
+                )
+                .ok_or_else(|| anyhow!("Message not found"))?,
+            None => self.messages.len(),
+        };
+        let kept_message_ids = self.messages[..end]
+            .iter()
+            .filter_map(|message| match message {
+                Message::User(message) => Some(message.id.clone()),
+                Message::Agent(_) | Message::Resume => None,
+            })
+            .collect::<HashSet<_>>();
+        let keeps_compaction = self.compaction_start_ix().is_some_and(|ix| ix < end);
+
+        let db_thread = self.to_db(cx);
+        Ok(cx.background_spawn(async move {
+            let mut thread = db_thread.await;
+            thread.title = format!("{} (fork)", thread.title).into();
+            thread.messages.truncate(end);
+            thread
+                .request_token_usage
+                .retain(|id, _| kept_message_ids.contains(id));
+            thread.forked_checkpoints = checkpoints
+                .into_iter()
+                .filter(|(id, _)| kept_message_ids.contains(id))
+                .collect();
+            if !keeps_compaction {
+                thread.compaction = None;
+            }
+            thread.detailed_summary = None;
+            thread.updated_at = Utc::now();
+            thread
+        }))
+    }
+
+    /// Adds tokens spent on the thread's behalf outside of its own requests, like answering a
+    /// context server's sampling requests, to the thread's total usage.
+    pub(crate) fn add_token_usage(&mut self, usage: TokenUsage, cx: &mut Context<Self>) {
+        self.cumulative_token_usage = self.cumulative_token_usage + usage;
+        cx.notify();
+    }
+
     pub fn latest_request_token_usage(&self) -> Option<language_model::TokenUsage> {
         let last_user_message = self.last_user_message()?;
         let tokens = self.request_token_usage.get(&last_user_message.id)?;
@@ -1296,9 +1556,18 @@ impl Thread {
         event_stream: &ThreadEventStream,
         cx: &mut AsyncApp,
     ) -> Result<()> {
+        Self::run_thread_start_hooks(this, event_stream, cx).await?;
+
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
+        let mut turn_end_hook_continuations = 0;
         loop {
+            this.update(cx, |this, cx| this.send_plan_edits(event_stream, cx))?;
+            if this.read_with(cx, |this, cx| this.should_compact(cx))? {
+                let compact = this.update(cx, |this, cx| this.compact(cx))?;
+                compact.await.log_err();
+            }
+
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
 
@@ -1381,7 +1650,16 @@ impl Thread {
             } else if this.read_with(cx, |this, _| this.tool_use_limit_reached)? {
                 return Err(language_model::ToolUseLimitReachedError.into());
             } else if end_turn {
-                return Ok(());
+                if turn_end_hook_continuations < MAX_TURN_END_HOOK_CONTINUATIONS
+                    && let Some(feedback) = Self::run_turn_end_hooks(this, event_stream, cx).await?
+                {
+                    log::debug!("Continuing the turn to address hook feedback: {feedback}");
+                    turn_end_hook_continuations += 1;
+                    intent = CompletionIntent::UserPrompt;
+                    attempt = 0;
+                } else {
+                    return Ok(());
+                }
             } else {
                 intent = CompletionIntent::ToolResults;
                 attempt = 0;
@@ -1389,6 +1667,69 @@ impl Thread {
         }
     }
 
+    /// Runs the `thread_start` hooks before the first turn of a thread, adding what they print to
+    /// the user's first message.
+    async fn run_thread_start_hooks(
+        this: &WeakEntity<Self>,
+        event_stream: &ThreadEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let hooks = this.read_with(cx, |this, cx| {
+            let is_first_turn = matches!(this.messages.as_slice(), [Message::User(_)]);
+            is_first_turn
+                .then(|| this.hooks(AgentHookEvent::ThreadStart, None, cx))
+                .flatten()
+        })?;
+        let Some(hooks) = hooks else {
+            return Ok(());
+        };
+
+        let outputs = hooks.run(HookInput::default()).await;
+        if let Some(context) = HookOutput::successes(&outputs) {
+            this.update(cx, |this, cx| {
+                if let Some(Message::User(message)) = this.messages.first_mut() {
+                    let content = UserMessageContent::Text(context);
+                    event_stream.send_user_message(&UserMessage {
+                        id: message.id.clone(),
+                        content: vec![content.clone()],
+                    });
+                    message.content.push(content);
+                    cx.notify();
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Runs the `turn_end` hooks. When some of them fail, their output is added to the thread as
+    /// a user message and returned, so that the model continues its turn to address it.
+    async fn run_turn_end_hooks(
+        this: &WeakEntity<Self>,
+        event_stream: &ThreadEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<String>> {
+        let Some(hooks) =
+            this.read_with(cx, |this, cx| this.hooks(AgentHookEvent::TurnEnd, None, cx))?
+        else {
+            return Ok(None);
+        };
+
+        let outputs = hooks.run(HookInput::default()).await;
+        let Some(feedback) = HookOutput::failures(&outputs) else {
+            return Ok(None);
+        };
+        this.update(cx, |this, cx| {
+            let message = UserMessage {
+                id: UserMessageId::new(),
+                content: vec![UserMessageContent::Text(feedback.clone())],
+            };
+            event_stream.send_user_message(&message);
+            this.messages.push(Message::User(message));
+            cx.notify();
+        })?;
+        Ok(Some(feedback))
+    }
+
     fn handle_completion_error(
         &mut self,
         error: LanguageModelCompletionError,
@@ -1613,7 +1954,7 @@ impl Thread {
             event_stream.send_tool_call(
                 &tool_use.id,
                 &tool_use.name,
-                title,
+                title.clone(),
                 kind,
                 tool_use.input.clone(),
             );
@@ -1645,14 +1986,60 @@ impl Thread {
             }));
         };
 
+        let permission = self.tool_call_permission(&tool, &tool_use.input, cx);
+        if permission.action() == Some(ToolPermissionAction::Deny) {
+            let content = "Permission to run tool denied by the profile's tool permission rules";
+            return Some(Task::ready(LanguageModelToolResult {
+                content: LanguageModelToolResultContent::Text(Arc::from(content)),
+                tool_use_id: tool_use.id,
+                tool_name: tool_use.name,
+                is_error: true,
+                output: Some(content.into()),
+            }));
+        }
+
         let fs = self.project.read(cx).fs().clone();
         let tool_event_stream =
-            ToolCallEventStream::new(tool_use.id.clone(), event_stream.clone(), Some(fs));
+            ToolCallEventStream::new(tool_use.id.clone(), event_stream.clone(), Some(fs))
+                .with_permission(permission)
+                .with_thread(cx.weak_entity());
         tool_event_stream.update_fields(
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
         );
         let supports_images = self.model().is_some_and(|model| model.supports_images());
-        let tool_result = tool.run(tool_use.input, tool_event_stream, cx);
+        let hook_tool = Some((tool_use.name.as_ref(), tool.kind()));
+        let pre_tool_call = self.hooks(AgentHookEvent::PreToolCall, hook_tool, cx);
+        let post_tool_call = self.hooks(AgentHookEvent::PostToolCall, hook_tool, cx);
+        let hook_input = (pre_tool_call.is_some() || post_tool_call.is_some()).then(|| HookInput {
+            tool_name: Some(tool_use.name.clone()),
+            tool_input: Some(tool_use.input.clone()),
+            ..Default::default()
+        });
+        let tool_result = if tool_event_stream.must_ask() || pre_tool_call.is_some() {
+            let input = tool_use.input.clone();
+            let hook_input = hook_input.clone().unwrap_or_default();
+            cx.spawn(async move |_, cx| {
+                if let Some(pre_tool_call) = pre_tool_call
+                    && let Some(feedback) =
+                        HookOutput::failures(&pre_tool_call.run(hook_input).await)
+                {
+                    return Err(anyhow!("A hook blocked this tool call.\n\n{feedback}"));
+                }
+                let tool_event_stream = if tool_event_stream.must_ask() {
+                    // Ask before running tools that a rule requires confirmation for, even if the
+                    // tool itself wouldn't, and don't ask again once the tool runs.
+                    cx.update(|cx| tool_event_stream.authorize(title, cx))?
+                        .await?;
+                    tool_event_stream.preauthorized()
+                } else {
+                    tool_event_stream
+                };
+                cx.update(|cx| tool.run(input, tool_event_stream, cx))?
+                    .await
+            })
+        } else {
+            tool.run(tool_use.input, tool_event_stream, cx)
+        };
         log::debug!("Running tool {}", tool_use.name);
         Some(cx.foreground_executor().spawn(async move {
             let tool_result = tool_result.await.and_then(|output| {
@@ -1666,7 +2053,7 @@ impl Thread {
                 Ok(output)
             });
 
-            match tool_result {
+            let mut tool_result = match tool_result {
                 Ok(output) => LanguageModelToolResult {
                     tool_use_id: tool_use.id,
                     tool_name: tool_use.name,
@@ -1681,7 +2068,26 @@ impl Thread {
                     content: LanguageModelToolResultContent::Text(Arc::from(error.to_string())),
                     output: Some(error.to_string().into()),
                 },
+            };
+
+            // Hooks that fail after the tool ran tell the model what to fix, like lint errors in
+            // the files it just edited.
+            if let Some(post_tool_call) = post_tool_call
+                && let LanguageModelToolResultContent::Text(text) = &tool_result.content
+            {
+                let hook_input = HookInput {
+                    tool_output: Some(text.to_string()),
+                    is_error: Some(tool_result.is_error),
+                    ..hook_input.unwrap_or_default()
+                };
+                if let Some(feedback) = HookOutput::failures(&post_tool_call.run(hook_input).await)
+                {
+                    tool_result.content = LanguageModelToolResultContent::Text(
+                        format!("{text}\n\n{feedback}").into(),
+                    );
+                }
             }
+            tool_result
         }))
     }
 
@@ -1871,6 +2277,191 @@ impl Thread {
         self.pending_summary_generation = None;
     }
 
+    pub fn compaction(&self) -> Option<&Compaction> {
+        self.compaction.as_ref()
+    }
+
+    pub fn is_compacting(&self) -> bool {
+        self.is_compacting
+    }
+
+    /// Whether there are messages older than the ones compaction keeps that aren't summarized yet.
+    pub fn can_compact(&self) -> bool {
+        !self.is_compacting && self.compaction_boundary().is_some()
+    }
+
+    /// The index of the first message that is sent verbatim after the compaction summary.
+    fn compaction_start_ix(&self) -> Option<usize> {
+        let compaction = self.compaction.as_ref()?;
+        let turn_ix = self.messages.iter().position(|message| {
+            matches!(message, Message::User(UserMessage { id, .. }) if id == &compaction.first_kept_message)
+        })?;
+        Some(turn_ix + compaction.summarized_turn_messages).filter(|ix| *ix < self.messages.len())
+    }
+
+    /// Where a new compaction would start keeping messages from, as the index of that message, the
+    /// user message starting its turn, and how many messages of that turn precede it.
+    ///
+    /// This is the start of the oldest kept turn or, when there aren't enough older turns to
+    /// summarize, one of the current turn's agent messages, so that the older tool calls and
+    /// results of a long turn can be summarized too.
+    fn compaction_boundary(&self) -> Option<(usize, UserMessageId, usize)> {
+        let start_ix = self.compaction_start_ix().unwrap_or(0);
+        let turns = self
+            .messages
+            .iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(ix, message)| match message {
+                Message::User(message) => Some((ix, message)),
+                Message::Agent(_) | Message::Resume => None,
+            })
+            .take(COMPACTION_KEPT_TURNS)
+            .collect::<Vec<_>>();
+        if let Some((ix, message)) = turns.get(COMPACTION_KEPT_TURNS - 1)
+            && *ix > start_ix
+        {
+            return Some((*ix, message.id.clone(), 0));
+        }
+
+        let (turn_ix, message) = turns.first()?;
+        let ix = self
+            .messages
+            .iter()
+            .enumerate()
+            .skip(turn_ix + 1)
+            .rev()
+            .filter(|(_, message)| matches!(message, Message::Agent(_)))
+            .nth(COMPACTION_KEPT_AGENT_MESSAGES - 1)?
+            .0;
+        (ix > start_ix).then(|| (ix, message.id.clone(), ix - turn_ix))
+    }
+
+    /// The token usage of the most recent request, even if it was made before the last user
+    /// message was sent.
+    fn latest_context_usage(&self) -> Option<acp_thread::TokenUsage> {
+        let model = self.model.as_ref()?;
+        let usage = self
+            .messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                Message::User(message) => self.request_token_usage.get(&message.id),
+                Message::Agent(_) | Message::Resume => None,
+            })?;
+        Some(acp_thread::TokenUsage {
+            max_tokens: model.max_token_count_for_mode(self.completion_mode.into()),
+            used_tokens: usage.total_tokens(),
+        })
+    }
+
+    fn should_compact(&self, cx: &App) -> bool {
+        AgentSettings::get_global(cx).auto_compact
+            && self.can_compact()
+            && self
+                .latest_context_usage()
+                .is_some_and(|usage| usage.ratio() != acp_thread::TokenUsageRatio::Normal)
+    }
+
+    /// Summarizes every turn but the most recent ones, so that requests send the summary in
+    /// their place. Tool calls and results of the kept turns are sent verbatim. When there aren't
+    /// enough older turns, the older part of the current turn is summarized instead.
+    pub fn compact(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if self.is_compacting {
+            return Task::ready(Err(anyhow!("The thread is already being compacted")));
+        }
+        let Some(model) = self
+            .summarization_model
+            .clone()
+            .or_else(|| self.model.clone())
+        else {
+            return Task::ready(Err(anyhow!("No language model configured")));
+        };
+        let Some((boundary_ix, first_kept_message, summarized_turn_messages)) =
+            self.compaction_boundary()
+        else {
+            return Task::ready(Err(anyhow!("There are no older messages to compact")));
+        };
+
+        let mut request = LanguageModelRequest {
+            thread_id: Some(self.id.to_string()),
+            prompt_id: Some(self.prompt_id.to_string()),
+            intent: Some(CompletionIntent::ThreadContextSummarization),
+            temperature: AgentSettings::temperature_for_model(&model, cx),
+            ..Default::default()
+        };
+        request
+            .messages
+            .extend(self.compaction.iter().map(Compaction::to_request));
+        let start_ix = self.compaction_start_ix().unwrap_or(0);
+        for message in &self.messages[start_ix..boundary_ix] {
+            request.messages.extend(message.to_request());
+        }
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![SUMMARIZE_THREAD_DETAILED_PROMPT.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+
+        self.is_compacting = true;
+        cx.emit(CompactionUpdated);
+        cx.notify();
+        cx.spawn(async move |this, cx| {
+            let summarize = async {
+                let mut summary = String::new();
+                let mut events = model.stream_completion(request, cx).await?;
+                while let Some(event) = events.next().await {
+                    match event? {
+                        LanguageModelCompletionEvent::Text(text) => summary.push_str(&text),
+                        LanguageModelCompletionEvent::UsageUpdated { amount, limit } => {
+                            this.update(cx, |thread, cx| {
+                                thread.update_model_request_usage(amount, limit, cx);
+                            })?;
+                        }
+                        _ => {}
+                    }
+                }
+                anyhow::ensure!(
+                    !summary.trim().is_empty(),
+                    "The model returned an empty summary"
+                );
+                anyhow::Ok(summary)
+            };
+            let summary = summarize.await.context("failed to compact thread");
+            this.update(cx, |this, cx| {
+                this.is_compacting = false;
+                cx.emit(CompactionUpdated);
+                cx.notify();
+                let summary = summary?;
+                this.set_compaction(
+                    Some(Compaction {
+                        summary,
+                        first_kept_message,
+                        summarized_turn_messages,
+                    }),
+                    cx,
+                );
+                Ok(())
+            })?
+        })
+    }
+
+    /// Sends the summarized messages verbatim again.
+    pub fn undo_compaction(&mut self, cx: &mut Context<Self>) {
+        self.set_compaction(None, cx);
+    }
+
+    fn set_compaction(&mut self, compaction: Option<Compaction>, cx: &mut Context<Self>) {
+        self.compaction = compaction;
+        if self.compaction_start_ix().is_none() {
+            self.compaction = None;
+        }
+        self.updated_at = Utc::now();
+        cx.emit(CompactionUpdated);
+        cx.notify();
+    }
+
     fn last_user_message(&self) -> Option<&UserMessage> {
         self.messages
             .iter()
@@ -1967,6 +2558,7 @@ impl Thread {
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(model, cx),
             thinking_allowed: true,
+            max_output_tokens: None,
         };
 
         log::debug!("Completion request built successfully");
@@ -2038,9 +2630,76 @@ impl Thread {
             }
         }
 
+        if let Some(allowed_tools) = &self.allowed_tools {
+            tools.retain(|tool_name, _| allowed_tools.contains(tool_name));
+        }
+
         tools
     }
 
+    /// Decides how the current profile's tool permission rules treat a call of `tool`.
+    fn tool_call_permission(
+        &self,
+        tool: &Arc<dyn AnyAgentTool>,
+        input: &serde_json::Value,
+        cx: &App,
+    ) -> ToolCallPermission {
+        self.subjects_permission(
+            tool.name().as_ref().into(),
+            tool.permission_subjects(input),
+            cx,
+        )
+    }
+
+    /// Decides how the current profile's tool permission rules treat a call of the tool named
+    /// `tool_name` that affects `subjects`.
+    fn subjects_permission(
+        &self,
+        tool_name: Arc<str>,
+        subjects: Vec<PermissionSubject>,
+        cx: &App,
+    ) -> ToolCallPermission {
+        let project = self.project.read(cx);
+        let subjects = subjects
+            .into_iter()
+            .flat_map(|subject| match subject {
+                PermissionSubject::Path(path) => {
+                    let path = normalize_permission_path(&path);
+                    let path = project
+                        .find_project_path(&path, cx)
+                        .map(|project_path| project_path.path.as_unix_str().to_string())
+                        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+                    vec![path]
+                }
+                PermissionSubject::Command(command) => split_command(&command)
+                    .into_iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                PermissionSubject::Text(text) => vec![text],
+            })
+            .collect::<Vec<_>>();
+        let rule = AgentSettings::get_global(cx)
+            .profiles
+            .get(&self.profile_id)
+            .and_then(|profile| profile.tool_permissions.find_match(&tool_name, &subjects));
+        ToolCallPermission {
+            profile_id: self.profile_id.clone(),
+            tool_name,
+            subjects,
+            rule,
+        }
+    }
+
+    /// The hooks configured to run for `event`, if any.
+    fn hooks(
+        &self,
+        event: AgentHookEvent,
+        tool: Option<(&str, acp::ToolKind)>,
+        cx: &App,
+    ) -> Option<Hooks> {
+        Hooks::for_event(event, tool, &self.id, &self.project, cx)
+    }
+
     fn tool(&self, name: &str) -> Option<Arc<dyn AnyAgentTool>> {
         self.running_turn.as_ref()?.tools.get(name).cloned()
     }
@@ -2075,7 +2734,14 @@ impl Thread {
             cache: false,
             reasoning_details: None,
         }];
-        for message in &self.messages {
+        let start_ix = match self.compaction_start_ix() {
+            Some(start_ix) => {
+                messages.extend(self.compaction.iter().map(Compaction::to_request));
+                start_ix
+            }
+            None => 0,
+        };
+        for message in &self.messages[start_ix..] {
             messages.extend(message.to_request());
         }
 
@@ -2246,6 +2912,10 @@ pub struct TitleUpdated;
 
 impl EventEmitter<TitleUpdated> for Thread {}
 
+pub struct CompactionUpdated;
+
+impl EventEmitter<CompactionUpdated> for Thread {}
+
 pub trait AgentTool
 where
     Self: 'static + Sized,
@@ -2285,6 +2955,12 @@ where
         true
     }
 
+    /// The parts of the input that tool permission rules match their patterns against, like the
+    /// command run by the terminal tool or the path edited by the edit file tool.
+    fn permission_subjects(_input: &Self::Input) -> Vec<PermissionSubject> {
+        Vec::new()
+    }
+
     /// Runs the tool with the provided input.
     fn run(
         self: Arc<Self>,
@@ -2309,6 +2985,17 @@ where
     }
 }
 
+/// A part of a tool call's input that tool permission rules are matched against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PermissionSubject {
+    /// A path the tool reads or writes, matched relative to the worktree that contains it.
+    Path(String),
+    /// A shell command, which only matches a rule's pattern if each of its subcommands does.
+    Command(String),
+    /// Any other input, like a URL, matched as is.
+    Text(String),
+}
+
 pub struct Erased<T>(T);
 
 pub struct AgentToolOutput {
@@ -2325,6 +3012,9 @@ pub trait AnyAgentTool {
     fn supports_provider(&self, _provider: &LanguageModelProviderId) -> bool {
         true
     }
+    fn permission_subjects(&self, _input: &serde_json::Value) -> Vec<PermissionSubject> {
+        Vec::new()
+    }
     fn run(
         self: Arc<Self>,
         input: serde_json::Value,
@@ -2371,6 +3061,12 @@ where
         T::supports_provider(provider)
     }
 
+    fn permission_subjects(&self, input: &serde_json::Value) -> Vec<PermissionSubject> {
+        serde_json::from_value(input.clone())
+            .map(|input| T::permission_subjects(&input))
+            .unwrap_or_default()
+    }
+
     fn run(
         self: Arc<Self>,
         input: serde_json::Value,
@@ -2413,6 +3109,12 @@ impl ThreadEventStream {
             .ok();
     }
 
+    fn send_plan(&self, entries: &[acp::PlanEntry]) {
+        self.0
+            .unbounded_send(Ok(ThreadEvent::Plan(acp::Plan::new(entries.to_vec()))))
+            .ok();
+    }
+
     fn send_text(&self, text: &str) {
         self.0
             .unbounded_send(Ok(ThreadEvent::AgentText(text.to_string())))
@@ -2491,11 +3193,48 @@ impl ThreadEventStream {
     }
 }
 
+/// Collapses the `.` and `..` components of a path a tool was called with, so that a path like
+/// `src/../secrets` can't match a rule meant for `src`.
+fn normalize_permission_path(path: &str) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                Some(Component::ParentDir | Component::CurDir) | None => normalized.push(component),
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// How the thread's profile treats a tool call, and what an "always allow" answer saves to it.
+#[derive(Clone, Debug)]
+struct ToolCallPermission {
+    profile_id: AgentProfileId,
+    tool_name: Arc<str>,
+    subjects: Vec<String>,
+    rule: Option<ToolPermissionMatch>,
+}
+
+impl ToolCallPermission {
+    fn action(&self) -> Option<ToolPermissionAction> {
+        self.rule.map(|rule| rule.action)
+    }
+}
+
 #[derive(Clone)]
 pub struct ToolCallEventStream {
     tool_use_id: LanguageModelToolUseId,
     stream: ThreadEventStream,
     fs: Option<Arc<dyn Fs>>,
+    permission: Option<ToolCallPermission>,
+    thread: Option<WeakEntity<Thread>>,
 }
 
 impl ToolCallEventStream {
@@ -2517,7 +3256,44 @@ impl ToolCallEventStream {
             tool_use_id,
             stream,
             fs,
+            permission: None,
+            thread: None,
+        }
+    }
+
+    fn with_permission(mut self, permission: ToolCallPermission) -> Self {
+        self.permission = Some(permission);
+        self
+    }
+
+    fn with_thread(mut self, thread: WeakEntity<Thread>) -> Self {
+        self.thread = Some(thread);
+        self
+    }
+
+    /// The thread that the tool call belongs to.
+    pub(crate) fn thread(&self) -> Option<&WeakEntity<Thread>> {
+        self.thread.as_ref()
+    }
+
+    /// Returns a stream whose authorization requests succeed right away, for running a tool that
+    /// the user has already been asked about.
+    fn preauthorized(mut self) -> Self {
+        if let Some(rule) = self
+            .permission
+            .as_mut()
+            .and_then(|permission| permission.rule.as_mut())
+        {
+            rule.action = ToolPermissionAction::Allow;
         }
+        self
+    }
+
+    /// Whether a tool permission rule requires asking before the tool call runs.
+    fn must_ask(&self) -> bool {
+        self.permission
+            .as_ref()
+            .is_some_and(|permission| permission.action() == Some(ToolPermissionAction::Ask))
     }
 
     pub fn update_fields(&self, fields: acp::ToolCallUpdateFields) {
@@ -2538,11 +3314,9 @@ impl ToolCallEventStream {
             .ok();
     }
 
-    pub fn authorize(&self, title: impl Into<String>, cx: &mut App) -> Task<Result<()>> {
-        if agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions {
-            return Task::ready(Ok(()));
-        }
-
+    /// Asks the user to answer a sub-agent's authorization request in this tool call's card,
+    /// resolving once they have.
+    pub(crate) async fn forward_authorization(&self, authorization: ToolCallAuthorization) {
         let (response_tx, response_rx) = oneshot::channel();
         self.stream
             .0
@@ -2550,14 +3324,32 @@ impl ToolCallEventStream {
                 ToolCallAuthorization {
                     tool_call: acp::ToolCallUpdate::new(
                         self.tool_use_id.to_string(),
-                        acp::ToolCallUpdateFields::new().title(title.into()),
+                        authorization.tool_call.fields,
                     ),
+                    options: authorization.options,
+                    response: response_tx,
+                },
+            )))
+            .ok();
+        if let Ok(option_id) = response_rx.await {
+            authorization.response.send(option_id).ok();
+        }
+    }
+
+    /// Asks the user to allow something that the tool wants to do while it runs, beyond running
+    /// it, like a context server's request to use the model. It's asked every time, regardless
+    /// of the tool's permissions.
+    pub(crate) fn ask(
+        &self,
+        fields: acp::ToolCallUpdateFields,
+    ) -> impl Future<Output = Result<()>> + use<> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.stream
+            .0
+            .unbounded_send(Ok(ThreadEvent::ToolCallAuthorization(
+                ToolCallAuthorization {
+                    tool_call: acp::ToolCallUpdate::new(self.tool_use_id.to_string(), fields),
                     options: vec![
-                        acp::PermissionOption::new(
-                            acp::PermissionOptionId::new("always_allow"),
-                            "Always Allow",
-                            acp::PermissionOptionKind::AllowAlways,
-                        ),
                         acp::PermissionOption::new(
                             acp::PermissionOptionId::new("allow"),
                             "Allow",
@@ -2573,24 +3365,206 @@ impl ToolCallEventStream {
                 },
             )))
             .ok();
-        let fs = self.fs.clone();
-        cx.spawn(async move |cx| match response_rx.await?.0.as_ref() {
-            "always_allow" => {
-                if let Some(fs) = fs.clone() {
-                    cx.update(|cx| {
-                        update_settings_file(fs, cx, |settings, _| {
-                            settings
-                                .agent
-                                .get_or_insert_default()
-                                .set_always_allow_tool_actions(true);
-                        });
-                    })?;
+        async move {
+            let response = response_rx.await?;
+            anyhow::ensure!(response.0.as_ref() == "allow", "Denied by user");
+            Ok(())
+        }
+    }
+
+    /// Asks the user to fill in a form while the tool runs, resolving with their response.
+    pub(crate) fn request_input(
+        &self,
+        request: acp_thread::InputRequest,
+    ) -> impl Future<Output = acp_thread::InputResponse> + use<> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.stream
+            .0
+            .unbounded_send(Ok(ThreadEvent::ToolCallInputRequest(
+                ToolCallInputRequest {
+                    tool_call_id: acp::ToolCallId::new(self.tool_use_id.to_string()),
+                    request,
+                    response: response_tx,
+                },
+            )))
+            .ok();
+        async move {
+            response_rx
+                .await
+                .unwrap_or(acp_thread::InputResponse::Cancel)
+        }
+    }
+
+    /// Asks the user to fill in a sub-agent's form in this tool call's card, resolving once they
+    /// have.
+    pub(crate) async fn forward_input_request(&self, input_request: ToolCallInputRequest) {
+        let response = self.request_input(input_request.request).await;
+        input_request.response.send(response).ok();
+    }
+
+    pub fn authorize(&self, title: impl Into<String>, cx: &mut App) -> Task<Result<()>> {
+        self.request_authorization(title.into(), false, cx)
+    }
+
+    /// Like [`Self::authorize`], but checks the profile's tool permission rules against
+    /// `subjects` instead of the ones taken from the tool's input. Used by tools that only find
+    /// out what they affect once they run, like renaming a symbol across files.
+    pub fn authorize_subjects(
+        &self,
+        title: impl Into<String>,
+        subjects: Vec<PermissionSubject>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let thread = self.thread.as_ref().and_then(WeakEntity::upgrade);
+        let permission = self
+            .permission
+            .as_ref()
+            .zip(thread)
+            .map(|(permission, thread)| {
+                thread
+                    .read(cx)
+                    .subjects_permission(permission.tool_name.clone(), subjects, cx)
+            });
+        Self {
+            permission,
+            ..self.clone()
+        }
+        .request_authorization(title.into(), false, cx)
+    }
+
+    /// Like [`Self::authorize`], but asks even when a tool permission rule allows the call. Used
+    /// for edits that could change how Zed itself behaves, like edits to settings files.
+    pub fn authorize_sensitive(&self, title: impl Into<String>, cx: &mut App) -> Task<Result<()>> {
+        self.request_authorization(title.into(), true, cx)
+    }
+
+    fn request_authorization(
+        &self,
+        title: String,
+        sensitive: bool,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        match self
+            .permission
+            .as_ref()
+            .and_then(ToolCallPermission::action)
+        {
+            Some(ToolPermissionAction::Allow) if !sensitive => return Task::ready(Ok(())),
+            Some(ToolPermissionAction::Allow | ToolPermissionAction::Ask) => {}
+            Some(ToolPermissionAction::Deny) => {
+                return Task::ready(Err(anyhow!(
+                    "Permission to run tool denied by the profile's tool permission rules"
+                )));
+            }
+            None => {
+                if agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions {
+                    return Task::ready(Ok(()));
                 }
+            }
+        }
 
-                Ok(())
+        // Rules can't allow sensitive calls, so there's nothing to gain from saving one.
+        let mut options = Vec::new();
+        if !sensitive {
+            if let Some(permission) = self.permission.as_ref()
+                && let [subject] = permission.subjects.as_slice()
+            {
+                options.push(acp::PermissionOption::new(
+                    acp::PermissionOptionId::new("always_allow_subject"),
+                    format!(
+                        "Always Allow `{}`",
+                        util::truncate_and_trailoff(subject, 32)
+                    ),
+                    acp::PermissionOptionKind::AllowAlways,
+                ));
             }
-            "allow" => Ok(()),
-            _ => Err(anyhow!("Permission to run tool denied by user")),
+            // A rule allowing every call is saved ahead of the rule that asked, so it isn't
+            // offered when that would override a rule denying some of the tool's calls.
+            let overrides_deny_rule = self.permission.as_ref().is_some_and(|permission| {
+                permission.rule.is_some_and(|rule| {
+                    agent_settings::AgentSettings::get_global(cx)
+                        .profiles
+                        .get(&permission.profile_id)
+                        .is_some_and(|profile| {
+                            profile
+                                .tool_permissions
+                                .denies_from(&permission.tool_name, rule.rule_ix)
+                        })
+                })
+            });
+            if !overrides_deny_rule {
+                options.push(acp::PermissionOption::new(
+                    acp::PermissionOptionId::new("always_allow"),
+                    "Always Allow",
+                    acp::PermissionOptionKind::AllowAlways,
+                ));
+            }
+        }
+        options.extend([
+            acp::PermissionOption::new(
+                acp::PermissionOptionId::new("allow"),
+                "Allow",
+                acp::PermissionOptionKind::AllowOnce,
+            ),
+            acp::PermissionOption::new(
+                acp::PermissionOptionId::new("deny"),
+                "Deny",
+                acp::PermissionOptionKind::RejectOnce,
+            ),
+        ]);
+
+        let (response_tx, response_rx) = oneshot::channel();
+        self.stream
+            .0
+            .unbounded_send(Ok(ThreadEvent::ToolCallAuthorization(
+                ToolCallAuthorization {
+                    tool_call: acp::ToolCallUpdate::new(
+                        self.tool_use_id.to_string(),
+                        acp::ToolCallUpdateFields::new().title(title),
+                    ),
+                    options,
+                    response: response_tx,
+                },
+            )))
+            .ok();
+        let fs = self.fs.clone();
+        let permission = self.permission.clone();
+        cx.spawn(async move |cx| {
+            let response = response_rx.await?;
+            let always_allow_subject = match response.0.as_ref() {
+                "always_allow_subject" => true,
+                "always_allow" => false,
+                "allow" => return Ok(()),
+                _ => return Err(anyhow!("Permission to run tool denied by user")),
+            };
+            let (Some(fs), Some(permission)) = (fs, permission) else {
+                return Ok(());
+            };
+            let rule = match permission.subjects.as_slice() {
+                [subject] if always_allow_subject => ToolPermissionRule::for_subject(
+                    permission.tool_name,
+                    subject,
+                    ToolPermissionAction::Allow,
+                ),
+                _ => ToolPermissionRule::new(
+                    permission.tool_name,
+                    None,
+                    ToolPermissionAction::Allow,
+                )?,
+            };
+            // Place the rule ahead of the one that asked, so that it doesn't override any rule
+            // that takes precedence over it.
+            let rule_ix = permission.rule.map(|rule| rule.rule_ix);
+            cx.update(|cx| {
+                AgentProfile::insert_tool_permission_rule(
+                    permission.profile_id,
+                    rule_ix,
+                    rule,
+                    fs,
+                    cx,
+                )
+            })?;
+            Ok(())
         })
     }
 }
@@ -1,3 +1,4 @@
+mod code_navigation;
 mod context_server_registry;
 mod copy_path_tool;
 mod create_directory_tool;
@@ -6,18 +7,30 @@ mod diagnostics_tool;
 mod edit_file_tool;
 mod fetch_tool;
 mod find_path_tool;
+mod find_references_tool;
+mod git_blame_tool;
+mod git_commit_tool;
+mod git_diff_tool;
+mod git_log_tool;
+mod git_repository;
+mod git_status_tool;
+mod go_to_definition_tool;
 mod grep_tool;
 mod list_directory_tool;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
 mod read_file_tool;
+mod rename_symbol_tool;
 mod restore_file_from_disk_tool;
 mod save_file_tool;
+mod subagent_tool;
 
 mod terminal_tool;
 mod thinking_tool;
+mod update_plan_tool;
 mod web_search_tool;
+mod workspace_symbols_tool;
 
 use crate::AgentTool;
 use language_model::{LanguageModelRequestTool, LanguageModelToolSchemaFormat};
@@ -30,18 +43,29 @@ pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
 pub use fetch_tool::*;
 pub use find_path_tool::*;
+pub use find_references_tool::*;
+pub use git_blame_tool::*;
+pub use git_commit_tool::*;
+pub use git_diff_tool::*;
+pub use git_log_tool::*;
+pub use git_status_tool::*;
+pub use go_to_definition_tool::*;
 pub use grep_tool::*;
 pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use now_tool::*;
 pub use open_tool::*;
 pub use read_file_tool::*;
+pub use rename_symbol_tool::*;
 pub use restore_file_from_disk_tool::*;
 pub use save_file_tool::*;
+pub use subagent_tool::*;
 
 pub use terminal_tool::*;
 pub use thinking_tool::*;
+pub use update_plan_tool::*;
 pub use web_search_tool::*;
+pub use workspace_symbols_tool::*;
 
 macro_rules! tools {
     ($($tool:ty),* $(,)?) => {
@@ -88,15 +112,26 @@ tools! {
     EditFileTool,
     FetchTool,
     FindPathTool,
+    FindReferencesTool,
+    GitBlameTool,
+    GitCommitTool,
+    GitDiffTool,
+    GitLogTool,
+    GitStatusTool,
+    GoToDefinitionTool,
     GrepTool,
     ListDirectoryTool,
     MovePathTool,
     NowTool,
     OpenTool,
     ReadFileTool,
+    RenameSymbolTool,
     RestoreFileFromDiskTool,
     SaveFileTool,
+    SubagentTool,
     TerminalTool,
     ThinkingTool,
+    UpdatePlanTool,
     WebSearchTool,
+    WorkspaceSymbolsTool,
 }
@@ -0,0 +1,178 @@
+use crate::ToolCallEventStream;
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, AsyncApp, Entity, Task};
+use language::{Buffer, Location, Point, ToPoint as _};
+use project::{Project, ProjectPath, lsp_store::OpenLspBufferHandle};
+use std::{fmt::Write, path::PathBuf};
+use util::markdown::MarkdownInlineCode;
+
+/// How many locations the code navigation tools report at most.
+const MAX_LOCATIONS: usize = 100;
+
+/// Where a symbol named by the model appears in a buffer.
+pub(crate) struct SymbolPosition {
+    pub buffer: Entity<Buffer>,
+    pub point: Point,
+    /// Keeps the buffer registered with its language servers while they're queried about it.
+    _lsp_handle: OpenLspBufferHandle,
+}
+
+/// Opens the file at `path` and finds where `symbol` appears on its 1-based `line`.
+pub(crate) fn symbol_position(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    symbol: &str,
+    cx: &mut App,
+) -> Task<Result<SymbolPosition>> {
+    let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+        return Task::ready(Err(anyhow!("Path {path} not found in project")));
+    };
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let project = project.clone();
+    let path = path.to_string();
+    let symbol = symbol.to_string();
+    cx.spawn(async move |cx| {
+        let buffer = open_buffer.await?;
+        let lsp_handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        })?;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+        let row = line.saturating_sub(1);
+        if line == 0 || row > snapshot.max_point().row {
+            return Err(anyhow!(
+                "Line {line} is out of range, {path} has {} lines",
+                snapshot.max_point().row + 1
+            ));
+        }
+        let line_text = snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>();
+        let column = find_symbol(&line_text, &symbol).with_context(|| {
+            format!(
+                "{} doesn't appear on line {line} of {path}",
+                MarkdownInlineCode(&symbol)
+            )
+        })?;
+        Ok(SymbolPosition {
+            buffer,
+            point: Point::new(row, column as u32),
+            _lsp_handle: lsp_handle,
+        })
+    })
+}
+
+/// Returns the byte offset of the first occurrence of `symbol` in `line` that isn't part of a
+/// longer identifier, falling back to its first occurrence of any kind.
+fn find_symbol(line: &str, symbol: &str) -> Option<usize> {
+    if symbol.is_empty() {
+        return None;
+    }
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(symbol)
+        .map(|(ix, _)| ix)
+        .find(|&ix| {
+            let before = line[..ix].chars().next_back();
+            let after = line[ix + symbol.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .or_else(|| line.find(symbol))
+}
+
+/// A place in the code reported by a code navigation tool.
+pub(crate) struct CodeLocation {
+    /// The path shown to the model, starting with the name of the worktree's root directory.
+    pub path: PathBuf,
+    pub abs_path: Option<PathBuf>,
+    pub row: u32,
+    /// What's at the location, like the text of its line or the name of a symbol.
+    pub text: String,
+}
+
+impl CodeLocation {
+    pub fn from_location(location: &Location, project: &Entity<Project>, cx: &App) -> Option<Self> {
+        let buffer = location.buffer.read(cx);
+        let file = buffer.file()?;
+        let project_path = ProjectPath::from_file(file.as_ref(), cx);
+        let row = location.range.start.to_point(buffer).row;
+        let text = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        Some(Self {
+            path: file.full_path(cx),
+            abs_path: project.read(cx).absolute_path(&project_path, cx),
+            row,
+            text: text.trim().to_string(),
+        })
+    }
+
+    pub fn from_locations(
+        locations: &[Location],
+        project: &Entity<Project>,
+        cx: &AsyncApp,
+    ) -> Result<Vec<Self>> {
+        cx.update(|cx| {
+            locations
+                .iter()
+                .filter_map(|location| Self::from_location(location, project, cx))
+                .collect()
+        })
+    }
+}
+
+/// Shows the locations in the tool call and lists them for the model below the given heading.
+pub(crate) fn report_locations(
+    heading: &str,
+    mut locations: Vec<CodeLocation>,
+    event_stream: &ToolCallEventStream,
+) -> Result<String> {
+    locations.sort_by(|a, b| a.path.cmp(&b.path).then(a.row.cmp(&b.row)));
+    locations.dedup_by(|a, b| a.path == b.path && a.row == b.row);
+    let total = locations.len();
+    locations.truncate(MAX_LOCATIONS);
+
+    event_stream.update_fields(
+        acp::ToolCallUpdateFields::new().locations(
+            locations
+                .iter()
+                .filter_map(|location| {
+                    Some(acp::ToolCallLocation::new(location.abs_path.clone()?).line(location.row))
+                })
+                .collect::<Vec<_>>(),
+        ),
+    );
+
+    let mut output = format!("{heading}\n\n");
+    for location in &locations {
+        write!(output, "- {}:{}", location.path.display(), location.row + 1)?;
+        if location.text.is_empty() {
+            writeln!(output)?;
+        } else {
+            writeln!(output, ": {}", MarkdownInlineCode(&location.text))?;
+        }
+    }
+    if total > locations.len() {
+        writeln!(
+            output,
+            "\n{} more locations are not shown.",
+            total - locations.len()
+        )?;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_symbol() {
+        assert_eq!(find_symbol("let foo = foobar(foo);", "foo"), Some(4));
+        assert_eq!(find_symbol("let x = foobar(foo);", "foo"), Some(15));
+        assert_eq!(find_symbol("let x = foobar();", "foo"), Some(8));
+        assert_eq!(find_symbol("self.bar()", "bar"), Some(5));
+        assert_eq!(find_symbol("let x = 1;", "foo"), None);
+        assert_eq!(find_symbol("let x = 1;", ""), None);
+    }
+}
@@ -1,11 +1,26 @@
 use crate::{AgentToolOutput, AnyAgentTool, ToolCallEventStream};
-use agent_client_protocol::ToolKind;
-use anyhow::{Result, anyhow, bail};
+use agent_client_protocol::{self as acp, ToolKind};
+use anyhow::{Context as _, Result, anyhow, bail};
 use collections::{BTreeMap, HashMap};
-use context_server::{ContextServerId, client::NotificationSubscription};
+use context_server::{
+    ContextServerId,
+    client::NotificationSubscription,
+    types::{
+        CreateMessageRequest, CreateMessageResult, ElicitAction, ElicitRequest, ElicitResult,
+        ModelPreferences, ProgressToken,
+    },
+};
+use futures::{
+    FutureExt as _, StreamExt as _,
+    channel::{mpsc, oneshot},
+};
 use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task};
+use language_model::{
+    LanguageModel, LanguageModelCompletionEvent, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage, Role, StopReason, TokenUsage,
+};
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
-use std::sync::Arc;
+use std::{pin::pin, sync::Arc};
 use util::ResultExt;
 
 pub struct ContextServerPrompt {
@@ -250,7 +265,9 @@ impl ContextServerRegistry {
                         self.reload_tools_for_server(server_id.clone(), cx);
                         self.reload_prompts_for_server(server_id.clone(), cx);
                     }
-                    ContextServerStatus::Stopped | ContextServerStatus::Error(_) => {
+                    ContextServerStatus::Stopped
+                    | ContextServerStatus::Error(_)
+                    | ContextServerStatus::AuthRequired => {
                         if let Some(registered_server) = self.registered_servers.remove(server_id) {
                             if !registered_server.tools.is_empty() {
                                 cx.emit(ContextServerRegistryEvent::ToolsChanged);
@@ -331,9 +348,10 @@ impl AnyAgentTool for ContextServerTool {
             return Task::ready(Err(anyhow!("Context server not found")));
         };
         let tool_name = self.tool.name.clone();
-        let authorize = event_stream.authorize(self.initial_title(input.clone(), cx), cx);
+        let title = self.initial_title(input.clone(), cx);
+        let authorize = event_stream.authorize(title.clone(), cx);
 
-        cx.spawn(async move |_cx| {
+        cx.spawn(async move |cx| {
             authorize.await?;
 
             let Some(protocol) = server.client() else {
@@ -351,15 +369,62 @@ impl AnyAgentTool for ContextServerTool {
                 tool_name,
                 arguments
             );
-            let response = protocol
-                .request::<context_server::types::requests::CallTool>(
-                    context_server::types::CallToolParams {
-                        name: tool_name,
-                        arguments,
-                        meta: None,
+            // Answer the server's requests to sample the model or to ask the user for input while
+            // the tool runs, as they're made on behalf of the tool call. The server names the call
+            // they belong to by the progress token it was sent with.
+            let progress_token = ProgressToken::String(uuid::Uuid::new_v4().to_string());
+            let (sampling_tx, mut sampling_rx) = mpsc::unbounded();
+            let _sampling_subscription = protocol
+                .on_request_for_progress_token::<context_server::types::requests::CreateMessage>(
+                    &progress_token,
+                    move |request, cx| {
+                        let (response_tx, response_rx) = oneshot::channel();
+                        sampling_tx.unbounded_send((request, response_tx)).ok();
+                        cx.background_spawn(async move { response_rx.await? })
                     },
-                )
-                .await?;
+                )?;
+            let (elicitation_tx, mut elicitation_rx) = mpsc::unbounded();
+            let _elicitation_subscription = protocol
+                .on_request_for_progress_token::<context_server::types::requests::Elicit>(
+                    &progress_token,
+                    move |request, cx| {
+                        let (response_tx, response_rx) = oneshot::channel();
+                        elicitation_tx.unbounded_send((request, response_tx)).ok();
+                        cx.background_spawn(async move { response_rx.await? })
+                    },
+                )?;
+            let mut response = pin!(
+                protocol
+                    .request::<context_server::types::requests::CallTool>(
+                        context_server::types::CallToolParams {
+                            name: tool_name,
+                            arguments,
+                            meta: Some(HashMap::from_iter([(
+                                "progressToken".to_string(),
+                                serde_json::to_value(&progress_token)?,
+                            )])),
+                        },
+                    )
+                    .fuse()
+            );
+            let response = loop {
+                futures::select_biased! {
+                    response = response => break response?,
+                    (request, response_tx) = sampling_rx.select_next_some() => {
+                        let result = sample(&server.id(), request, &event_stream, cx).await;
+                        event_stream.update_fields(
+                            acp::ToolCallUpdateFields::new()
+                                .title(title.to_string())
+                                .content(Vec::new()),
+                        );
+                        response_tx.send(result).ok();
+                    }
+                    (request, response_tx) = elicitation_rx.select_next_some() => {
+                        let result = elicit(&server.id(), request, &event_stream).await;
+                        response_tx.send(result).ok();
+                    }
+                }
+            };
 
             let mut result = String::new();
             for content in response.content {
@@ -396,6 +461,213 @@ impl AnyAgentTool for ContextServerTool {
     }
 }
 
+/// Asks the user to fill in the form that a context server requests while its tool runs.
+async fn elicit(
+    server_id: &ContextServerId,
+    request: ElicitRequest,
+    event_stream: &ToolCallEventStream,
+) -> Result<ElicitResult> {
+    let request = acp_thread::InputRequest::from_schema(
+        server_id.to_string(),
+        request.message,
+        &request.requested_schema,
+    )?;
+    Ok(match event_stream.request_input(request).await {
+        acp_thread::InputResponse::Accept(content) => ElicitResult {
+            action: ElicitAction::Accept,
+            content: Some(content),
+        },
+        acp_thread::InputResponse::Decline => ElicitResult {
+            action: ElicitAction::Decline,
+            content: None,
+        },
+        acp_thread::InputResponse::Cancel => ElicitResult {
+            action: ElicitAction::Cancel,
+            content: None,
+        },
+    })
+}
+
+/// Answers a context server's request to sample the model, once the user allows it, and adds the
+/// tokens it took to the usage of the thread that the tool call belongs to.
+async fn sample(
+    server_id: &ContextServerId,
+    request: CreateMessageRequest,
+    event_stream: &ToolCallEventStream,
+    cx: &mut AsyncApp,
+) -> Result<CreateMessageResult> {
+    let thread = event_stream
+        .thread()
+        .context("Sampling is only available within an agent thread")?
+        .clone();
+    let model = thread
+        .read_with(cx, |thread, cx| {
+            sampling_model(request.model_preferences.as_ref(), thread.model(), cx)
+        })?
+        .context("No language model is configured")?;
+
+    event_stream
+        .ask(
+            acp::ToolCallUpdateFields::new()
+                .title(format!(
+                    "Allow `{server_id}` to send this prompt to {}?",
+                    model.name().0
+                ))
+                .content(vec![sampling_request_markdown(&request).into()]),
+        )
+        .await?;
+
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = request.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![system_prompt.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+    for message in request.messages {
+        let content = match message.content {
+            context_server::types::MessageContent::Text { text, .. } => text,
+            _ => {
+                log::warn!("Ignoring non-text content in sampling request from {server_id}");
+                continue;
+            }
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                context_server::types::Role::User => Role::User,
+                context_server::types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![content.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+    let request = LanguageModelRequest {
+        messages,
+        stop: request.stop_sequences.unwrap_or_default(),
+        temperature: request.temperature.map(|temperature| temperature as f32),
+        max_output_tokens: Some(request.max_tokens.into()),
+        ..Default::default()
+    };
+
+    let mut events = model.stream_completion(request, cx).await?;
+    let mut text = String::new();
+    let mut usage = TokenUsage::default();
+    let mut stop_reason = None;
+    while let Some(event) = events.next().await {
+        match event? {
+            LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+            LanguageModelCompletionEvent::UsageUpdate(update) => usage = update,
+            LanguageModelCompletionEvent::Stop(reason) => {
+                stop_reason = Some(match reason {
+                    StopReason::MaxTokens => "maxTokens",
+                    StopReason::EndTurn | StopReason::ToolUse | StopReason::Refusal => "endTurn",
+                })
+            }
+            _ => {}
+        }
+    }
+    thread.update(cx, |thread, cx| thread.add_token_usage(usage, cx))?;
+
+    Ok(CreateMessageResult {
+        role: context_server::types::Role::Assistant,
+        content: context_server::types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: stop_reason.map(ToString::to_string),
+    })
+}
+
+/// Describes everything that a sampling request would send to the model, so the user can review
+/// it before allowing it.
+fn sampling_request_markdown(request: &CreateMessageRequest) -> String {
+    let mut markdown = String::new();
+    if let Some(system_prompt) = &request.system_prompt {
+        markdown.push_str(&format!("**System prompt**\n\n{system_prompt}\n\n"));
+    }
+    for message in &request.messages {
+        let role = match message.role {
+            context_server::types::Role::User => "User",
+            context_server::types::Role::Assistant => "Assistant",
+        };
+        let content = match &message.content {
+            context_server::types::MessageContent::Text { text, .. } => text.as_str(),
+            context_server::types::MessageContent::Image { .. } => {
+                "_An image, which won't be sent_"
+            }
+            context_server::types::MessageContent::Audio { .. } => "_Audio, which won't be sent_",
+            context_server::types::MessageContent::Resource { .. } => {
+                "_A resource, which won't be sent_"
+            }
+        };
+        markdown.push_str(&format!("**{role}**\n\n{content}\n\n"));
+    }
+    let hints = request
+        .model_preferences
+        .as_ref()
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref())
+        .collect::<Vec<_>>();
+    if !hints.is_empty() {
+        markdown.push_str(&format!("**Model hints:** {}\n\n", hints.join(", ")));
+    }
+    markdown.push_str(&format!("**Max tokens:** {}", request.max_tokens));
+    markdown
+}
+
+/// Picks the model to answer a sampling request with: the first available model that one of the
+/// server's hints names, preferring the thread's provider. Without a match, it's the model for
+/// thread summaries if the server favors cost or speed over intelligence, or the thread's model.
+fn sampling_model(
+    preferences: Option<&ModelPreferences>,
+    thread_model: Option<&Arc<dyn LanguageModel>>,
+    cx: &App,
+) -> Option<Arc<dyn LanguageModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref());
+    for hint in hints {
+        let hint = hint.to_lowercase();
+        let matches = registry
+            .available_models(cx)
+            .filter(|model| {
+                model.id().0.to_lowercase().contains(&hint)
+                    || model.name().0.to_lowercase().contains(&hint)
+            })
+            .collect::<Vec<_>>();
+        let preferred = matches.iter().find(|model| {
+            thread_model
+                .is_some_and(|thread_model| thread_model.provider_id() == model.provider_id())
+        });
+        if let Some(model) = preferred.or(matches.first()) {
+            return Some(model.clone());
+        }
+    }
+
+    let prefers_cheaper_model = preferences.is_some_and(|preferences| {
+        let cost_or_speed = preferences
+            .cost_priority
+            .unwrap_or(0.)
+            .max(preferences.speed_priority.unwrap_or(0.));
+        cost_or_speed > preferences.intelligence_priority.unwrap_or(0.)
+    });
+    if prefers_cheaper_model && let Some(model) = registry.thread_summary_model() {
+        return Some(model.model);
+    }
+    thread_model
+        .cloned()
+        .or_else(|| registry.default_model().map(|model| model.model))
+}
+
 pub fn get_prompt(
     server_store: &Entity<ContextServerStore>,
     server_id: &ContextServerId,
@@ -1,4 +1,4 @@
-use crate::{AgentTool, ToolCallEventStream};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
 use agent_client_protocol::ToolKind;
 use anyhow::{Context as _, Result, anyhow};
 use gpui::{App, AppContext, Entity, Task};
@@ -58,6 +58,13 @@ impl AgentTool for CopyPathTool {
         ToolKind::Move
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![
+            PermissionSubject::Path(input.source_path.clone()),
+            PermissionSubject::Path(input.destination_path.clone()),
+        ]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use util::markdown::MarkdownInlineCode;
 
-use crate::{AgentTool, ToolCallEventStream};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
 
 /// Creates a new directory at the specified path within the project. Returns confirmation that the directory was created.
 ///
@@ -49,6 +49,10 @@ impl AgentTool for CreateDirectoryTool {
         ToolKind::Read
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Path(input.path.clone())]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
@@ -1,4 +1,4 @@
-use crate::{AgentTool, ToolCallEventStream};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
 use action_log::ActionLog;
 use agent_client_protocol::ToolKind;
 use anyhow::{Context as _, Result, anyhow};
@@ -52,6 +52,10 @@ impl AgentTool for DeletePathTool {
         ToolKind::Delete
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Path(input.path.clone())]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
@@ -1,5 +1,5 @@
 use crate::{
-    AgentTool, Templates, Thread, ToolCallEventStream,
+    AgentTool, PermissionSubject, Templates, Thread, ToolCallEventStream,
     edit_agent::{EditAgent, EditAgentOutput, EditAgentOutputEvent, EditFormat},
 };
 use acp_thread::Diff;
@@ -165,17 +165,25 @@ impl EditFileTool {
         }
 
         // If any path component matches the local settings folder, then this could affect
-        // the editor in ways beyond the project source, so prompt.
+        // the editor in ways beyond the project source, so prompt even if a tool permission
+        // rule allows the edit. The same goes for git's metadata, which can run hooks.
         let local_settings_folder = paths::local_settings_folder_name();
         let path = Path::new(&input.path);
         if path.components().any(|component| {
             component.as_os_str() == <_ as AsRef<OsStr>>::as_ref(&local_settings_folder)
         }) {
-            return event_stream.authorize(
+            return event_stream.authorize_sensitive(
                 format!("{} (local settings)", input.display_description),
                 cx,
             );
         }
+        if path
+            .components()
+            .any(|component| component.as_os_str() == OsStr::new(".git"))
+        {
+            return event_stream
+                .authorize_sensitive(format!("{} (git metadata)", input.display_description), cx);
+        }
 
         // It's also possible that the global config dir is configured to be inside the project,
         // so check for that edge case too.
@@ -183,7 +191,7 @@ impl EditFileTool {
         if let Ok(canonical_path) = std::fs::canonicalize(&input.path)
             && canonical_path.starts_with(paths::config_dir())
         {
-            return event_stream.authorize(
+            return event_stream.authorize_sensitive(
                 format!("{} (global settings)", input.display_description),
                 cx,
             );
@@ -219,6 +227,12 @@ impl AgentTool for EditFileTool {
         acp::ToolKind::Edit
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Path(
+            input.path.to_string_lossy().into_owned(),
+        )]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
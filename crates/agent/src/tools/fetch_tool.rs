@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 use ui::SharedString;
 use util::markdown::MarkdownEscaped;
 
-use crate::{AgentTool, ToolCallEventStream};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 enum ContentType {
@@ -126,6 +126,10 @@ impl AgentTool for FetchTool {
         acp::ToolKind::Fetch
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Text(input.url.clone())]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
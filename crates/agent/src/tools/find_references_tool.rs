@@ -0,0 +1,219 @@
+use super::code_navigation::{CodeLocation, report_locations, symbol_position};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Finds all the references to a symbol across the project, using the language server for its file.
+///
+/// Prefer this tool to searching with `grep` when you need every use of a specific function, type or variable, like before changing its signature, because it only reports the places that refer to that exact symbol.
+///
+/// <example>
+/// To find the uses of the `Config` struct defined on line 10 of `crates/app/src/config.rs`:
+/// {
+///     "path": "crates/app/src/config.rs",
+///     "line": 10,
+///     "symbol": "Config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    /// The path of a file where the symbol is defined or used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based number of a line of the file on which the symbol appears.
+    pub line: u32,
+    /// The symbol, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct FindReferencesTool {
+    project: Entity<Project>,
+}
+
+impl FindReferencesTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindReferencesTool {
+    type Input = FindReferencesToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "find_references"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Find references to {}", MarkdownInlineCode(&input.symbol)).into(),
+            Err(_) => "Find references".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let position = position.await?;
+            let references = project
+                .update(cx, |project, cx| {
+                    project.references(&position.buffer, position.point, cx)
+                })?
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No language server that can find references is running for {}",
+                        input.path
+                    )
+                })?;
+            let locations = CodeLocation::from_locations(&references, &project, cx)?;
+            if locations.is_empty() {
+                return Ok(format!(
+                    "No references found for {}",
+                    MarkdownInlineCode(&input.symbol)
+                ));
+            }
+            report_locations(
+                &format!("References to {}:", MarkdownInlineCode(&input.symbol)),
+                locations,
+                &event_stream,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_find_references(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "config.rs": "pub struct Config;\n",
+                    "main.rs": "fn main() {\n    let config = Config::default();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let (_buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/config.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>(
+            |params, _| async move {
+                let params = params.text_document_position;
+                assert_eq!(
+                    params.text_document.uri.to_file_path().unwrap(),
+                    Path::new(path!("/root/src/config.rs"))
+                );
+                assert_eq!(params.position, lsp::Position::new(0, 11));
+                Ok(Some(vec![
+                    lsp::Location::new(
+                        lsp::Uri::from_file_path(path!("/root/src/main.rs")).unwrap(),
+                        lsp::Range::new(lsp::Position::new(1, 17), lsp::Position::new(1, 23)),
+                    ),
+                    lsp::Location::new(
+                        lsp::Uri::from_file_path(path!("/root/src/config.rs")).unwrap(),
+                        lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 17)),
+                    ),
+                ]))
+            },
+        );
+
+        let tool = Arc::new(FindReferencesTool::new(project.clone()));
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.run(
+                FindReferencesToolInput {
+                    path: "root/src/config.rs".into(),
+                    line: 1,
+                    symbol: "Config".into(),
+                },
+                event_stream,
+                cx,
+            )
+        });
+
+        let output = task.await.unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "References to `Config`:\n\n- {}:1: `pub struct Config;`\n- {}:2: `let config = Config::default();`\n",
+                Path::new("root/src/config.rs").display(),
+                Path::new("root/src/main.rs").display()
+            )
+        );
+
+        let fields = event_rx.expect_update_fields().await;
+        let locations = fields.locations.unwrap();
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations[1].path, Path::new(path!("/root/src/main.rs")));
+        assert_eq!(locations[1].line, Some(1));
+    }
+}
@@ -0,0 +1,219 @@
+use super::git_repository::format_commit_date;
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use git::blame::BlameEntry;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, ops::Range, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Shows which commit last changed each line in a range of a file, like `git blame`.
+///
+/// Use this tool to find out when and why some code was written, then use `git_log` or `git_diff` to learn more.
+/// Prefer this tool to running `git blame` in the terminal.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitBlameToolInput {
+    /// The file to blame.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The first line to blame, 1-based. Defaults to the start of the file.
+    #[serde(default)]
+    pub start_line: Option<u32>,
+    /// The last line to blame, 1-based and inclusive. Defaults to the end of the file.
+    #[serde(default)]
+    pub end_line: Option<u32>,
+}
+
+pub struct GitBlameTool {
+    project: Entity<Project>,
+}
+
+impl GitBlameTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GitBlameTool {
+    type Input = GitBlameToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "git_blame"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Git blame".into();
+        };
+        let path = MarkdownInlineCode(&input.path);
+        match (input.start_line, input.end_line) {
+            (Some(start), Some(end)) => format!("Git blame of {path} ({start}-{end})"),
+            (Some(start), None) => format!("Git blame of {path} (from line {start})"),
+            (None, Some(end)) => format!("Git blame of {path} (up to line {end})"),
+            (None, None) => format!("Git blame of {path}"),
+        }
+        .into()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let project = self.project.read(cx);
+        let Some(project_path) = project.find_project_path(&input.path, cx) else {
+            return Task::ready(Err(anyhow!("Path {} not found in project", input.path)));
+        };
+        let abs_path = project.absolute_path(&project_path, cx);
+        let git_store = project.git_store().clone();
+        let open_buffer = self
+            .project
+            .update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+        cx.spawn(async move |cx| {
+            let buffer = open_buffer.await?;
+            let max_row = buffer.read_with(cx, |buffer, _| buffer.max_point().row)?;
+            let start_row = input.start_line.unwrap_or(1).max(1) - 1;
+            let end_row = input
+                .end_line
+                .map_or(max_row, |line| line.saturating_sub(1).min(max_row));
+            if start_row > end_row {
+                return Err(anyhow!(
+                    "The line range is empty, {} has {} lines",
+                    input.path,
+                    max_row + 1
+                ));
+            }
+
+            if let Some(abs_path) = abs_path {
+                event_stream.update_fields(
+                    acp::ToolCallUpdateFields::new()
+                        .locations(vec![acp::ToolCallLocation::new(abs_path).line(start_row)]),
+                );
+            }
+
+            let blame = git_store
+                .update(cx, |git_store, cx| {
+                    git_store.blame_buffer(&buffer, None, cx)
+                })?
+                .await?
+                .ok_or_else(|| anyhow!("{} isn't tracked by git", input.path))?;
+            Ok(format_blame(&blame.entries, start_row..end_row + 1))
+        })
+    }
+}
+
+/// Lists the commits that last changed the given rows, describing rows that aren't in any commit
+/// as uncommitted.
+fn format_blame(entries: &[BlameEntry], rows: Range<u32>) -> String {
+    let mut output = String::new();
+    let mut row = rows.start;
+    let write_uncommitted = |output: &mut String, range: Range<u32>| {
+        if !range.is_empty() {
+            writeln!(output, "{}: not committed yet", format_lines(&range)).ok();
+        }
+    };
+
+    for entry in entries {
+        let start = entry.range.start.max(rows.start);
+        let end = entry.range.end.min(rows.end);
+        if start >= end {
+            continue;
+        }
+        write_uncommitted(&mut output, row..start);
+        write!(
+            output,
+            "{}: {}",
+            format_lines(&(start..end)),
+            entry.sha.display_short()
+        )
+        .ok();
+        if let Some(time) = entry.author_time {
+            write!(output, " {}", format_commit_date(time)).ok();
+        }
+        if let Some(author) = &entry.author {
+            write!(output, " {author}").ok();
+        }
+        if let Some(summary) = &entry.summary {
+            write!(output, ": {summary}").ok();
+        }
+        writeln!(output).ok();
+        row = end;
+    }
+    write_uncommitted(&mut output, row..rows.end);
+    output
+}
+
+/// Formats 0-based rows as 1-based lines.
+fn format_lines(rows: &Range<u32>) -> String {
+    if rows.end - rows.start == 1 {
+        format!("Line {}", rows.start + 1)
+    } else {
+        format!("Lines {}-{}", rows.start + 1, rows.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::Oid;
+    use indoc::indoc;
+    use std::str::FromStr as _;
+
+    #[test]
+    fn test_format_blame() {
+        let entry = |sha: &str, range: Range<u32>, summary: &str| BlameEntry {
+            sha: Oid::from_str(sha).unwrap(),
+            range,
+            author: Some("Ada".to_string()),
+            author_time: Some(1_700_000_000),
+            summary: Some(summary.to_string()),
+            ..Default::default()
+        };
+        let entries = [
+            entry(
+                "1111111111111111111111111111111111111111",
+                0..2,
+                "Initial commit",
+            ),
+            entry(
+                "2222222222222222222222222222222222222222",
+                3..6,
+                "Add parser",
+            ),
+        ];
+
+        assert_eq!(
+            format_blame(&entries, 0..8),
+            indoc! {"
+                Lines 1-2: 1111111 2023-11-14 Ada: Initial commit
+                Line 3: not committed yet
+                Lines 4-6: 2222222 2023-11-14 Ada: Add parser
+                Lines 7-8: not committed yet
+            "}
+        );
+        assert_eq!(
+            format_blame(&entries, 1..4),
+            indoc! {"
+                Line 2: 1111111 2023-11-14 Ada: Initial commit
+                Line 3: not committed yet
+                Line 4: 2222222 2023-11-14 Ada: Add parser
+            "}
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use super::git_repository::{display_path, repository_for_path, short_sha};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use askpass::AskPassDelegate;
+use git::repository::CommitOptions;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Creates a git commit in the project, like `git commit`.
+///
+/// - Stages the given `paths` first, then commits everything that is staged.
+/// - Only commit when the user asked you to. Use `git_status` and `git_diff` first to check what will be committed.
+/// - Write a concise commit message in the style of the repository's existing commits, which you can see with `git_log`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitCommitToolInput {
+    /// The commit message. The first line is the subject of the commit.
+    pub message: String,
+    /// Files or directories to stage before committing. When empty, only the changes that are already staged are committed.
+    ///
+    /// These paths should never be absolute, and the first component
+    /// of each path should always be a root directory in a project.
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+pub struct GitCommitTool {
+    project: Entity<Project>,
+}
+
+impl GitCommitTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GitCommitTool {
+    type Input = GitCommitToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "git_commit"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Other
+    }
+
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        input
+            .paths
+            .iter()
+            .cloned()
+            .map(PermissionSubject::Path)
+            .collect()
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!(
+                "Commit {}",
+                MarkdownInlineCode(input.message.lines().next().unwrap_or_default())
+            )
+            .into(),
+            Err(_) => "Commit".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        if input.message.trim().is_empty() {
+            return Task::ready(Err(anyhow!("The commit message is empty")));
+        }
+
+        let resolved = match input
+            .paths
+            .iter()
+            .map(|path| repository_for_path(&self.project, Some(path), cx))
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(resolved) => resolved,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let repository = match resolved.first() {
+            Some((repository, _)) => repository.clone(),
+            None => match repository_for_path(&self.project, None, cx) {
+                Ok((repository, _)) => repository,
+                Err(error) => return Task::ready(Err(error)),
+            },
+        };
+        if resolved
+            .iter()
+            .any(|(path_repository, _)| path_repository != &repository)
+        {
+            return Task::ready(Err(anyhow!(
+                "The paths are in different git repositories, commit to each separately"
+            )));
+        }
+        let paths = resolved
+            .into_iter()
+            .filter_map(|(_, repo_path)| repo_path)
+            .collect::<Vec<_>>();
+
+        // Everything that's already staged is committed too, so the permission rules are checked
+        // against every file that ends up in the commit, not just the given paths.
+        let subjects = {
+            let project = self.project.read(cx);
+            let repository = repository.read(cx);
+            repository
+                .cached_status()
+                .filter(|entry| {
+                    entry.status.staging().has_staged()
+                        || (entry.status.has_changes()
+                            && paths.iter().any(|path| entry.repo_path.starts_with(path)))
+                })
+                .map(|entry| {
+                    PermissionSubject::Path(display_path(repository, &entry.repo_path, project, cx))
+                })
+                .collect()
+        };
+        let authorize = event_stream.authorize_subjects(
+            self.initial_title(Ok(input.clone()), cx),
+            subjects,
+            cx,
+        );
+        cx.spawn(async move |cx| {
+            authorize.await?;
+
+            if !paths.is_empty() {
+                repository
+                    .update(cx, |repository, cx| repository.stage_entries(paths, cx))?
+                    .await?;
+            }
+            // The agent can't answer prompts for credentials, like a passphrase for a signing key.
+            let askpass = AskPassDelegate::new(cx, |_, _, _| {});
+            repository
+                .update(cx, |repository, cx| {
+                    repository.commit(
+                        input.message.into(),
+                        None,
+                        CommitOptions::default(),
+                        askpass,
+                        cx,
+                    )
+                })?
+                .await??;
+
+            let commit = repository
+                .update(cx, |repository, _| repository.show("HEAD".to_string()))?
+                .await??;
+            Ok(format!(
+                "Created commit {}: {}",
+                short_sha(&commit.sha),
+                commit.message.lines().next().unwrap_or_default()
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::status::StatusCode;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_git_commit(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "src": { "main.rs": "fn main() {}" },
+                "README.md": "# Project",
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_status_for_repo(
+            dot_git,
+            &[
+                ("src/main.rs", StatusCode::Modified.worktree()),
+                ("README.md", StatusCode::Modified.worktree()),
+            ],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let tool = Arc::new(GitCommitTool::new(project));
+
+        assert_eq!(
+            GitCommitTool::permission_subjects(&GitCommitToolInput {
+                message: "Update main".into(),
+                paths: vec!["project/src/main.rs".into()],
+            }),
+            vec![PermissionSubject::Path("project/src/main.rs".into())]
+        );
+
+        let (event_stream, mut events) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.clone().run(
+                GitCommitToolInput {
+                    message: "Update main\n\nWith details".into(),
+                    paths: vec!["project/src/main.rs".into()],
+                },
+                event_stream,
+                cx,
+            )
+        });
+        let authorization = events.expect_authorization().await;
+        assert_eq!(
+            authorization.tool_call.fields.title,
+            Some("Commit `Update main`".into())
+        );
+        authorization
+            .response
+            .send(acp::PermissionOptionId::new("allow"))
+            .unwrap();
+        let output = task.await.unwrap();
+        assert!(output.starts_with("Created commit "), "{output}");
+        assert!(output.ends_with(": Update main"), "{output}");
+
+        fs.with_git_state(dot_git, false, |state| {
+            assert_eq!(
+                state
+                    .index_contents
+                    .get(&git::repository::repo_path("src/main.rs")),
+                Some(&"fn main() {}".to_string())
+            );
+            assert_ne!(
+                state
+                    .index_contents
+                    .get(&git::repository::repo_path("README.md")),
+                Some(&"# Project".to_string())
+            );
+            assert_eq!(state.log.len(), 1);
+            assert_eq!(state.log[0].subject.as_ref(), "Update main");
+        })
+        .unwrap();
+
+        // Declining the commit doesn't stage or commit anything.
+        let (event_stream, mut events) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.clone().run(
+                GitCommitToolInput {
+                    message: "Update readme".into(),
+                    paths: vec!["project/README.md".into()],
+                },
+                event_stream,
+                cx,
+            )
+        });
+        events
+            .expect_authorization()
+            .await
+            .response
+            .send(acp::PermissionOptionId::new("deny"))
+            .unwrap();
+        assert!(task.await.is_err());
+        fs.with_git_state(dot_git, false, |state| assert_eq!(state.log.len(), 1))
+            .unwrap();
+
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    GitCommitToolInput {
+                        message: " ".into(),
+                        paths: Vec::new(),
+                    },
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await;
+        assert!(output.is_err());
+    }
+}
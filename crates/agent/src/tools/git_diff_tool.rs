@@ -0,0 +1,199 @@
+use super::git_repository::repository_for_path;
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use git::repository::{DiffType, RepoPath};
+use gpui::{App, AppContext as _, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::{MarkdownCodeBlock, MarkdownInlineCode};
+
+/// How much of a diff is returned to the model, in bytes.
+const MAX_DIFF_LEN: usize = 40_000;
+
+/// Shows the uncommitted changes of a git repository in the project as a unified diff, like `git diff`.
+///
+/// By default, shows the changes since the last commit. Pass a `base` branch or commit to see everything that changed since the current branch diverged from it instead, for example to review the changes of a feature branch.
+///
+/// Prefer this tool to running `git diff` in the terminal.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitDiffToolInput {
+    /// A file or directory to limit the diff to. When omitted, shows the changes of the project's active repository.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// A branch or commit to compare against, like `main` or `origin/main`.
+    /// When omitted, compares against the last commit.
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Whether to only show the changes that are staged for the next commit. Ignored when `base` is set.
+    #[serde(default)]
+    pub staged: bool,
+}
+
+pub struct GitDiffTool {
+    project: Entity<Project>,
+}
+
+impl GitDiffTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GitDiffTool {
+    type Input = GitDiffToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "git_diff"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Git diff".into();
+        };
+        let mut title = match &input.base {
+            Some(base) => format!("Git diff against {}", MarkdownInlineCode(base)),
+            None if input.staged => "Git diff of staged changes".to_string(),
+            None => "Git diff".to_string(),
+        };
+        if let Some(path) = &input.path {
+            title.push_str(&format!(" in {}", MarkdownInlineCode(path)));
+        }
+        title.into()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let (repository, prefix) =
+            match repository_for_path(&self.project, input.path.as_deref(), cx) {
+                Ok(repository) => repository,
+                Err(error) => return Task::ready(Err(error)),
+            };
+        let diff_type = match input.base {
+            Some(base) => DiffType::MergeBaseToWorktree { base: base.into() },
+            None if input.staged => DiffType::HeadToIndex,
+            None => DiffType::HeadToWorktree,
+        };
+        let diff = repository.update(cx, |repository, cx| repository.diff(diff_type, cx));
+        cx.background_spawn(async move {
+            let mut diff = diff.await??;
+            if let Some(prefix) = prefix {
+                diff = filter_diff(&diff, &prefix);
+            }
+            if diff.trim().is_empty() {
+                return Ok("No changes".to_string());
+            }
+
+            let total_len = diff.len();
+            if total_len > MAX_DIFF_LEN {
+                let mut end = MAX_DIFF_LEN;
+                while !diff.is_char_boundary(end) {
+                    end -= 1;
+                }
+                diff.truncate(end);
+            }
+            let mut output = MarkdownCodeBlock {
+                tag: "diff",
+                text: &diff,
+            }
+            .to_string();
+            if total_len > diff.len() {
+                output.push_str(&format!(
+                    "\nThe diff was truncated to {} of its {total_len} bytes. Pass a `path` to see the rest.",
+                    diff.len()
+                ));
+            }
+            Ok(output)
+        })
+    }
+}
+
+/// Keeps the parts of a unified diff that change files under `prefix`.
+fn filter_diff(diff: &str, prefix: &RepoPath) -> String {
+    let prefix = prefix.as_unix_str();
+    let is_under_prefix = |path: &str| {
+        prefix.is_empty()
+            || path == prefix
+            || path
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+
+    let mut filtered = String::new();
+    let mut keep = false;
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            keep = header
+                .trim_end()
+                .split(' ')
+                .filter_map(|path| path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")))
+                .any(&is_under_prefix);
+        }
+        if keep {
+            filtered.push_str(line);
+        }
+    }
+    filtered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::repo_path;
+    use indoc::indoc;
+
+    #[test]
+    fn test_filter_diff() {
+        let diff = indoc! {"
+            diff --git a/src/main.rs b/src/main.rs
+            index 1111111..2222222 100644
+            --- a/src/main.rs
+            +++ b/src/main.rs
+            @@ -1 +1 @@
+            -fn main() {}
+            +fn main() { run() }
+            diff --git a/src/main_test.rs b/src/main_test.rs
+            new file mode 100644
+            --- /dev/null
+            +++ b/src/main_test.rs
+            @@ -0,0 +1 @@
+            +#[test] fn it_works() {}
+            diff --git a/README.md b/README.md
+            --- a/README.md
+            +++ b/README.md
+            @@ -1 +1 @@
+            -# App
+            +# My App
+        "};
+
+        let filtered = filter_diff(diff, &repo_path("src/main.rs"));
+        assert!(filtered.starts_with("diff --git a/src/main.rs b/src/main.rs\n"));
+        assert!(filtered.ends_with("+fn main() { run() }\n"));
+
+        let filtered = filter_diff(diff, &repo_path("src"));
+        assert!(filtered.contains("src/main.rs"));
+        assert!(filtered.contains("src/main_test.rs"));
+        assert!(!filtered.contains("README.md"));
+
+        assert_eq!(filter_diff(diff, &repo_path("")), diff);
+        assert_eq!(filter_diff(diff, &repo_path("docs")), "");
+    }
+}
@@ -0,0 +1,253 @@
+use super::git_repository::{format_commit_date, repository_for_path, short_sha};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+const COMMITS_PER_PAGE: usize = 20;
+
+/// Lists the commits of a git repository in the project, newest first, or only the commits that changed a given file.
+///
+/// - Prefer this tool to running `git log` in the terminal.
+/// - Results are paginated with 20 commits per page. Use the optional `offset` parameter to request older commits.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitLogToolInput {
+    /// A file to list the commits of. When omitted, lists the commits of the project's active repository.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// How many of the newest commits to skip.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+pub struct GitLogTool {
+    project: Entity<Project>,
+}
+
+impl GitLogTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+/// A commit as listed by [`GitLogTool`].
+struct LoggedCommit {
+    sha: SharedString,
+    timestamp: i64,
+    author_name: SharedString,
+    subject: SharedString,
+}
+
+impl AgentTool for GitLogTool {
+    type Input = GitLogToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "git_log"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input.ok().and_then(|input| input.path) {
+            Some(path) => format!("Git log of {}", MarkdownInlineCode(&path)).into(),
+            None => "Git log".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let (repository, path) = match repository_for_path(&self.project, input.path.as_deref(), cx)
+        {
+            Ok(repository) => repository,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let offset = input.offset;
+        cx.spawn(async move |cx| {
+            let commits = match path {
+                Some(path) => repository
+                    .update(cx, |repository, _| {
+                        repository.file_history_paginated(path, offset, Some(COMMITS_PER_PAGE))
+                    })?
+                    .await??
+                    .entries
+                    .into_iter()
+                    .map(|entry| LoggedCommit {
+                        sha: entry.sha,
+                        timestamp: entry.commit_timestamp,
+                        author_name: entry.author_name,
+                        subject: entry.subject,
+                    })
+                    .collect::<Vec<_>>(),
+                None => repository
+                    .update(cx, |repository, _| {
+                        repository.log(None, offset, Some(COMMITS_PER_PAGE))
+                    })?
+                    .await??
+                    .into_iter()
+                    .map(|entry| LoggedCommit {
+                        sha: entry.sha,
+                        timestamp: entry.commit_timestamp,
+                        author_name: entry.author_name,
+                        subject: entry.subject,
+                    })
+                    .collect::<Vec<_>>(),
+            };
+            if commits.is_empty() {
+                return Ok(if offset == 0 {
+                    "No commits found".to_string()
+                } else {
+                    format!("No commits found past the first {offset}")
+                });
+            }
+
+            let mut output = String::new();
+            for commit in &commits {
+                writeln!(
+                    output,
+                    "{} {} {}: {}",
+                    short_sha(&commit.sha),
+                    format_commit_date(commit.timestamp),
+                    commit.author_name,
+                    commit.subject
+                )?;
+            }
+            if commits.len() == COMMITS_PER_PAGE {
+                writeln!(
+                    output,
+                    "\nThere may be older commits. Pass an `offset` of {} to see them.",
+                    offset + COMMITS_PER_PAGE
+                )?;
+            }
+            Ok(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::log::LogEntry;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_git_log(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "src": { "main.rs": "fn main() {}" },
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.with_git_state(dot_git, true, |state| {
+            state.log = (0..COMMITS_PER_PAGE + 1)
+                .rev()
+                .map(|ix| LogEntry {
+                    sha: format!("{ix:02}{}", "0".repeat(38)).into(),
+                    parents: Vec::new(),
+                    subject: format!("Commit {ix}").into(),
+                    commit_timestamp: 1_700_000_000,
+                    author_name: "Ada".into(),
+                    author_email: "ada@example.com".into(),
+                    refs: Vec::new(),
+                })
+                .collect();
+        })
+        .unwrap();
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let tool = Arc::new(GitLogTool::new(project));
+        let run = |input: GitLogToolInput, cx: &mut TestAppContext| {
+            cx.update(|cx| tool.clone().run(input, ToolCallEventStream::test().0, cx))
+        };
+
+        let output = run(
+            GitLogToolInput {
+                path: None,
+                offset: 0,
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "2000000 2023-11-14 Ada: Commit 20");
+        assert_eq!(
+            lines[COMMITS_PER_PAGE - 1],
+            "0100000 2023-11-14 Ada: Commit 1"
+        );
+        assert_eq!(
+            lines[COMMITS_PER_PAGE + 1],
+            "There may be older commits. Pass an `offset` of 20 to see them."
+        );
+
+        let output = run(
+            GitLogToolInput {
+                path: None,
+                offset: COMMITS_PER_PAGE,
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, "0000000 2023-11-14 Ada: Commit 0\n");
+
+        let output = run(
+            GitLogToolInput {
+                path: None,
+                offset: 50,
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, "No commits found past the first 50");
+
+        assert!(
+            run(
+                GitLogToolInput {
+                    path: Some("project/missing.rs".into()),
+                    offset: 0,
+                },
+                cx,
+            )
+            .await
+            .is_err()
+        );
+    }
+}
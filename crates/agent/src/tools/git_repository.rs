@@ -0,0 +1,111 @@
+use anyhow::{Result, anyhow};
+use chrono::DateTime;
+use git::{
+    repository::RepoPath,
+    status::{FileStatus, StatusCode, UnmergedStatusCode},
+};
+use gpui::{App, Entity};
+use project::{Project, git_store::Repository};
+
+/// Finds the repository containing the project path `path`, along with the path relative to the
+/// repository's working directory. Without a path, returns the project's active repository.
+pub(crate) fn repository_for_path(
+    project: &Entity<Project>,
+    path: Option<&str>,
+    cx: &App,
+) -> Result<(Entity<Repository>, Option<RepoPath>)> {
+    let project = project.read(cx);
+    let git_store = project.git_store().read(cx);
+    match path {
+        Some(path) => {
+            let project_path = project
+                .find_project_path(path, cx)
+                .ok_or_else(|| anyhow!("Path {path} not found in project"))?;
+            let (repository, repo_path) = git_store
+                .repository_and_path_for_project_path(&project_path, cx)
+                .ok_or_else(|| anyhow!("{path} isn't in a git repository"))?;
+            Ok((repository, Some(repo_path)))
+        }
+        None => {
+            let repository = git_store
+                .active_repository()
+                .ok_or_else(|| anyhow!("The project doesn't have a git repository"))?;
+            Ok((repository, None))
+        }
+    }
+}
+
+/// The path the model should use to refer to a file of the repository, which starts with the
+/// name of a worktree's root directory when the file is in the project.
+pub(crate) fn display_path(
+    repository: &Repository,
+    repo_path: &RepoPath,
+    project: &Project,
+    cx: &App,
+) -> String {
+    repository
+        .repo_path_to_project_path(repo_path, cx)
+        .and_then(|project_path| {
+            let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+            Some(
+                worktree
+                    .read(cx)
+                    .full_path(&project_path.path)
+                    .display()
+                    .to_string(),
+            )
+        })
+        .unwrap_or_else(|| repo_path.as_unix_str().to_string())
+}
+
+/// Abbreviates a commit SHA like `git log --oneline` does.
+pub(crate) fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// Formats a commit timestamp as a date.
+pub(crate) fn format_commit_date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// The status of a file in the format of `git status --short`.
+pub(crate) fn short_status(status: FileStatus) -> String {
+    fn tracked_code(code: StatusCode) -> char {
+        match code {
+            StatusCode::Modified => 'M',
+            StatusCode::TypeChanged => 'T',
+            StatusCode::Added => 'A',
+            StatusCode::Deleted => 'D',
+            StatusCode::Renamed => 'R',
+            StatusCode::Copied => 'C',
+            StatusCode::Unmodified => ' ',
+        }
+    }
+
+    fn unmerged_code(code: UnmergedStatusCode) -> char {
+        match code {
+            UnmergedStatusCode::Added => 'A',
+            UnmergedStatusCode::Deleted => 'D',
+            UnmergedStatusCode::Updated => 'U',
+        }
+    }
+
+    match status {
+        FileStatus::Untracked => "??".to_string(),
+        FileStatus::Ignored => "!!".to_string(),
+        FileStatus::Unmerged(status) => [
+            unmerged_code(status.first_head),
+            unmerged_code(status.second_head),
+        ]
+        .into_iter()
+        .collect(),
+        FileStatus::Tracked(status) => [
+            tracked_code(status.index_status),
+            tracked_code(status.worktree_status),
+        ]
+        .into_iter()
+        .collect(),
+    }
+}
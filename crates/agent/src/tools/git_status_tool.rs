@@ -0,0 +1,223 @@
+use super::git_repository::{display_path, repository_for_path, short_sha, short_status};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Shows the checked-out branch and the changed files of a git repository in the project, like `git status --short`.
+///
+/// Prefer this tool to running `git status` in the terminal.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitStatusToolInput {
+    /// A path in the repository to show the status of, which also limits the status to the files under it.
+    /// When omitted, shows the status of the project's active repository.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+pub struct GitStatusTool {
+    project: Entity<Project>,
+}
+
+impl GitStatusTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GitStatusTool {
+    type Input = GitStatusToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "git_status"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input.ok().and_then(|input| input.path) {
+            Some(path) => format!("Git status of {}", MarkdownInlineCode(&path)).into(),
+            None => "Git status".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let (repository, prefix) =
+            match repository_for_path(&self.project, input.path.as_deref(), cx) {
+                Ok(repository) => repository,
+                Err(error) => return Task::ready(Err(error)),
+            };
+        let project = self.project.read(cx);
+        let repository = repository.read(cx);
+
+        let mut output = String::new();
+        match (&repository.branch, &repository.head_commit) {
+            (Some(branch), _) => writeln!(output, "On branch {}", branch.name()).ok(),
+            (None, Some(head)) => {
+                writeln!(output, "HEAD detached at {}", short_sha(&head.sha)).ok()
+            }
+            (None, None) => writeln!(output, "No commits yet").ok(),
+        };
+
+        let mut changes = String::new();
+        for entry in repository.cached_status() {
+            if prefix
+                .as_ref()
+                .is_some_and(|prefix| !entry.repo_path.starts_with(prefix))
+            {
+                continue;
+            }
+            writeln!(
+                changes,
+                "{} {}",
+                short_status(entry.status),
+                display_path(repository, &entry.repo_path, project, cx)
+            )
+            .ok();
+        }
+        if changes.is_empty() {
+            output.push_str("Nothing to commit, working tree clean\n");
+        } else {
+            output.push_str("\nChanged files, in the format of `git status --short`:\n\n");
+            output.push_str(&changes);
+        }
+        Task::ready(Ok(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::status::{FileStatus, StatusCode};
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_git_status(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "src": {
+                    "main.rs": "fn main() {}",
+                    "lib.rs": "pub fn run() {}",
+                },
+                "notes.txt": "todo",
+                "README.md": "# Project",
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_branch_name(dot_git, Some("main"));
+        fs.set_status_for_repo(
+            dot_git,
+            &[
+                ("src/main.rs", StatusCode::Modified.worktree()),
+                ("src/lib.rs", StatusCode::Modified.index()),
+                ("notes.txt", FileStatus::Untracked),
+            ],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let tool = Arc::new(GitStatusTool::new(project));
+
+        let output = cx
+            .update(|cx| {
+                tool.clone().run(
+                    GitStatusToolInput { path: None },
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            indoc! {"
+                On branch main
+
+                Changed files, in the format of `git status --short`:
+
+                ?? project/notes.txt
+                M  project/src/lib.rs
+                 M project/src/main.rs
+            "}
+        );
+
+        let output = cx
+            .update(|cx| {
+                tool.clone().run(
+                    GitStatusToolInput {
+                        path: Some("project/src".into()),
+                    },
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            indoc! {"
+                On branch main
+
+                Changed files, in the format of `git status --short`:
+
+                M  project/src/lib.rs
+                 M project/src/main.rs
+            "}
+        );
+
+        fs.set_status_for_repo(dot_git, &[]);
+        cx.run_until_parked();
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    GitStatusToolInput { path: None },
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            "On branch main\nNothing to commit, working tree clean\n"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use super::code_navigation::{CodeLocation, report_locations, symbol_position};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Finds where a symbol is defined, using the language server for its file.
+///
+/// Prefer this tool to searching with `grep` when you know where a symbol is used and want to find its definition, because it resolves the symbol the same way the compiler does and isn't fooled by symbols that share a name.
+///
+/// <example>
+/// To find the definition of `parse_config`, called on line 42 of `crates/app/src/main.rs`:
+/// {
+///     "path": "crates/app/src/main.rs",
+///     "line": 42,
+///     "symbol": "parse_config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoToDefinitionToolInput {
+    /// The path of a file where the symbol is used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based number of a line of the file on which the symbol appears.
+    pub line: u32,
+    /// The symbol, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct GoToDefinitionTool {
+    project: Entity<Project>,
+}
+
+impl GoToDefinitionTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GoToDefinitionTool {
+    type Input = GoToDefinitionToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "go_to_definition"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => {
+                format!("Go to definition of {}", MarkdownInlineCode(&input.symbol)).into()
+            }
+            Err(_) => "Go to definition".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let position = position.await?;
+            let definitions = project
+                .update(cx, |project, cx| {
+                    project.definitions(&position.buffer, position.point, cx)
+                })?
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No language server that can find definitions is running for {}",
+                        input.path
+                    )
+                })?;
+            let targets = definitions
+                .into_iter()
+                .map(|definition| definition.target)
+                .collect::<Vec<_>>();
+            let locations = CodeLocation::from_locations(&targets, &project, cx)?;
+            if locations.is_empty() {
+                return Ok(format!(
+                    "No definition found for {}",
+                    MarkdownInlineCode(&input.symbol)
+                ));
+            }
+            report_locations(
+                &format!("Definitions of {}:", MarkdownInlineCode(&input.symbol)),
+                locations,
+                &event_stream,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_go_to_definition(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "config.rs": "pub struct Config;\n",
+                    "main.rs": "fn main() {\n    let config = Config::default();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers =
+            language_registry.register_fake_lsp("Rust", language::FakeLspAdapter::default());
+
+        let (_buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::request::GotoDefinition, _, _>(
+            |params, _| async move {
+                let params = params.text_document_position_params;
+                assert_eq!(
+                    params.text_document.uri.to_file_path().unwrap(),
+                    Path::new(path!("/root/src/main.rs"))
+                );
+                assert_eq!(params.position, lsp::Position::new(1, 17));
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                    lsp::Location::new(
+                        lsp::Uri::from_file_path(path!("/root/src/config.rs")).unwrap(),
+                        lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 17)),
+                    ),
+                )))
+            },
+        );
+
+        let tool = Arc::new(GoToDefinitionTool::new(project.clone()));
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.run(
+                GoToDefinitionToolInput {
+                    path: "root/src/main.rs".into(),
+                    line: 2,
+                    symbol: "Config".into(),
+                },
+                event_stream,
+                cx,
+            )
+        });
+
+        let output = task.await.unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "Definitions of `Config`:\n\n- {}:1: `pub struct Config;`\n",
+                Path::new("root/src/config.rs").display()
+            )
+        );
+
+        let fields = event_rx.expect_update_fields().await;
+        let locations = fields.locations.unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].path, Path::new(path!("/root/src/config.rs")));
+        assert_eq!(locations[0].line, Some(0));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{AgentTool, ToolCallEventStream};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
 use agent_client_protocol::ToolKind;
 use anyhow::{Result, anyhow};
 use gpui::{App, Entity, SharedString, Task};
@@ -59,6 +59,10 @@ impl AgentTool for ListDirectoryTool {
         ToolKind::Read
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Path(input.path.clone())]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
@@ -1,4 +1,4 @@
-use crate::AgentTool;
+use crate::{AgentTool, PermissionSubject};
 use agent_client_protocol::ToolKind;
 use anyhow::{Context as _, Result};
 use gpui::{App, AppContext, Entity, SharedString, Task};
@@ -45,6 +45,10 @@ impl AgentTool for OpenTool {
         ToolKind::Execute
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Text(input.path_or_url.clone())]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
@@ -0,0 +1,481 @@
+use super::code_navigation::{CodeLocation, report_locations, symbol_position};
+use crate::{AgentTool, PermissionSubject, ToolCallEventStream};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use language::Point;
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Renames a symbol everywhere it's used across the project, using the language server for its file.
+///
+/// Prefer this tool to editing files one by one when renaming a function, type, variable or module, because the language server updates every reference to that exact symbol, including in files you haven't read, and leaves unrelated symbols with the same name alone.
+///
+/// <example>
+/// To rename the `Config` struct defined on line 10 of `crates/app/src/config.rs` to `AppConfig`:
+/// {
+///     "path": "crates/app/src/config.rs",
+///     "line": 10,
+///     "symbol": "Config",
+///     "new_name": "AppConfig"
+/// }
+/// </example>
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The path of a file where the symbol is defined or used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based number of a line of the file on which the symbol appears.
+    pub line: u32,
+    /// The symbol, exactly as it appears on that line.
+    pub symbol: String,
+    /// The new name of the symbol.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl RenameSymbolTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for RenameSymbolTool {
+    type Input = RenameSymbolToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "rename_symbol"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Path(input.path.clone())]
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!(
+                "Rename {} to {}",
+                MarkdownInlineCode(&input.symbol),
+                MarkdownInlineCode(&input.new_name)
+            )
+            .into(),
+            Err(_) => "Rename symbol".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let title = self.initial_title(Ok(input.clone()), cx);
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        cx.spawn(async move |cx| {
+            let position = position.await?;
+
+            // Language servers only say which files a rename changes by changing them, so ask
+            // for permission to edit every file that refers to the symbol beforehand.
+            let references = project
+                .update(cx, |project, cx| {
+                    project.references(&position.buffer, position.point, cx)
+                })?
+                .await?
+                .unwrap_or_default();
+            let mut buffers = vec![position.buffer.clone()];
+            for reference in references {
+                if !buffers.contains(&reference.buffer) {
+                    buffers.push(reference.buffer);
+                }
+            }
+            let authorize = cx.update(|cx| {
+                let subjects = buffers
+                    .iter()
+                    .filter_map(|buffer| {
+                        let path = buffer.read(cx).file()?.full_path(cx);
+                        Some(PermissionSubject::Path(path.to_string_lossy().into_owned()))
+                    })
+                    .collect();
+                event_stream.authorize_subjects(title, subjects, cx)
+            })?;
+            authorize.await?;
+
+            cx.update(|cx| {
+                action_log.update(cx, |action_log, cx| {
+                    for buffer in &buffers {
+                        action_log.will_edit_buffer(buffer.clone(), cx);
+                    }
+                })
+            })?;
+            let transaction = project
+                .update(cx, |project, cx| {
+                    project.perform_rename(
+                        position.buffer.clone(),
+                        position.point,
+                        input.new_name.clone(),
+                        cx,
+                    )
+                })?
+                .await;
+
+            // The language server may also change files that don't refer to the symbol, which
+            // the user wasn't asked about, so the whole rename is undone in that case.
+            let unauthorized_paths = cx.update(|cx| {
+                let Ok(transaction) = &transaction else {
+                    return Vec::new();
+                };
+                let unauthorized_paths = transaction
+                    .0
+                    .keys()
+                    .filter(|buffer| !buffers.contains(buffer))
+                    .map(|buffer| match buffer.read(cx).file() {
+                        Some(file) => file.full_path(cx).to_string_lossy().into_owned(),
+                        None => "an untitled buffer".to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                if !unauthorized_paths.is_empty() {
+                    for (buffer, transaction) in &transaction.0 {
+                        buffer.update(cx, |buffer, cx| {
+                            buffer.undo_transaction(transaction.id, cx);
+                        });
+                    }
+                }
+                unauthorized_paths
+            })?;
+            if !unauthorized_paths.is_empty() {
+                return Err(anyhow!(
+                    "The language server's rename of {} also changed {}, which weren't authorized, so it was undone",
+                    MarkdownInlineCode(&input.symbol),
+                    unauthorized_paths.join(", ")
+                ));
+            }
+
+            cx.update(|cx| {
+                action_log.update(cx, |action_log, cx| {
+                    let renamed_buffers = transaction
+                        .as_ref()
+                        .map(|transaction| transaction.0.keys().cloned().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    for buffer in buffers.iter().chain(&renamed_buffers) {
+                        action_log.buffer_edited(buffer.clone(), cx);
+                    }
+                })
+            })?;
+            let transaction = transaction?;
+            if transaction.0.is_empty() {
+                return Err(anyhow!(
+                    "The language server didn't rename {}",
+                    MarkdownInlineCode(&input.symbol)
+                ));
+            }
+
+            let locations = cx.update(|cx| {
+                let mut locations = Vec::new();
+                for (buffer, transaction) in &transaction.0 {
+                    let buffer = buffer.read(cx);
+                    let Some(file) = buffer.file() else {
+                        continue;
+                    };
+                    let path = file.full_path(cx);
+                    let abs_path = project
+                        .read(cx)
+                        .absolute_path(&ProjectPath::from_file(file.as_ref(), cx), cx);
+                    for edit in buffer.edits_since::<Point>(&transaction.start) {
+                        let row = edit.new.start.row;
+                        locations.push(CodeLocation {
+                            path: path.clone(),
+                            abs_path: abs_path.clone(),
+                            row,
+                            text: buffer
+                                .text_for_range(
+                                    Point::new(row, 0)..Point::new(row, buffer.line_len(row)),
+                                )
+                                .collect::<String>()
+                                .trim()
+                                .to_string(),
+                        });
+                    }
+                }
+                locations
+            })?;
+
+            report_locations(
+                &format!(
+                    "Renamed {} to {} in {} files:",
+                    MarkdownInlineCode(&input.symbol),
+                    MarkdownInlineCode(&input.new_name),
+                    transaction.0.len()
+                ),
+                locations,
+                &event_stream,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{collections::HashMap, path::Path};
+    use util::path;
+
+    async fn init_test(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Project>,
+        lsp::FakeLanguageServer,
+        project::lsp_store::OpenLspBufferHandle,
+    ) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "config.rs": "pub struct Config;\n",
+                    "main.rs": "fn main() {\n    let config = Config::default();\n}\n",
+                    "lib.rs": "mod config;\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let (_buffer, handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/config.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        (project, fake_language_server, handle)
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol(cx: &mut TestAppContext) {
+        let (project, fake_language_server, _handle) = init_test(cx).await;
+        let config_uri = lsp::Uri::from_file_path(path!("/root/src/config.rs")).unwrap();
+        let main_uri = lsp::Uri::from_file_path(path!("/root/src/main.rs")).unwrap();
+        let config_range = lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 17));
+        let main_range = lsp::Range::new(lsp::Position::new(1, 17), lsp::Position::new(1, 23));
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>({
+            let config_uri = config_uri.clone();
+            let main_uri = main_uri.clone();
+            move |_, _| {
+                let config_uri = config_uri.clone();
+                let main_uri = main_uri.clone();
+                async move {
+                    Ok(Some(vec![
+                        lsp::Location::new(config_uri, config_range),
+                        lsp::Location::new(main_uri, main_range),
+                    ]))
+                }
+            }
+        });
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>({
+            move |params, _| {
+                let config_uri = config_uri.clone();
+                let main_uri = main_uri.clone();
+                async move {
+                    assert_eq!(params.new_name, "AppConfig");
+                    assert_eq!(
+                        params.text_document_position.position,
+                        lsp::Position::new(0, 11)
+                    );
+                    Ok(Some(lsp::WorkspaceEdit::new(HashMap::from_iter([
+                        (
+                            config_uri,
+                            vec![lsp::TextEdit::new(config_range, "AppConfig".into())],
+                        ),
+                        (
+                            main_uri,
+                            vec![lsp::TextEdit::new(main_range, "AppConfig".into())],
+                        ),
+                    ]))))
+                }
+            }
+        });
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(RenameSymbolTool::new(project.clone(), action_log.clone()));
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.run(
+                RenameSymbolToolInput {
+                    path: "root/src/config.rs".into(),
+                    line: 1,
+                    symbol: "Config".into(),
+                    new_name: "AppConfig".into(),
+                },
+                event_stream,
+                cx,
+            )
+        });
+
+        let authorization = event_rx.expect_authorization().await;
+        assert_eq!(
+            authorization.tool_call.fields.title,
+            Some("Rename `Config` to `AppConfig`".into())
+        );
+        authorization
+            .response
+            .send(acp::PermissionOptionId::new("allow"))
+            .unwrap();
+
+        let output = task.await.unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "Renamed `Config` to `AppConfig` in 2 files:\n\n- {}:1: `pub struct AppConfig;`\n- {}:2: `let config = AppConfig::default();`\n",
+                Path::new("root/src/config.rs").display(),
+                Path::new("root/src/main.rs").display()
+            )
+        );
+
+        cx.run_until_parked();
+        let changed_paths = action_log.read_with(cx, |action_log, cx| {
+            action_log
+                .changed_buffers(cx)
+                .keys()
+                .map(|buffer| buffer.read(cx).file().unwrap().full_path(cx))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(changed_paths.len(), 2);
+        assert!(changed_paths.contains(&Path::new("root/src/config.rs").to_path_buf()));
+        assert!(changed_paths.contains(&Path::new("root/src/main.rs").to_path_buf()));
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_outside_references(cx: &mut TestAppContext) {
+        let (project, fake_language_server, _handle) = init_test(cx).await;
+        let config_uri = lsp::Uri::from_file_path(path!("/root/src/config.rs")).unwrap();
+        let lib_uri = lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap();
+        let config_range = lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 17));
+        let lib_range = lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 10));
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>({
+            let config_uri = config_uri.clone();
+            move |_, _| {
+                let config_uri = config_uri.clone();
+                async move { Ok(Some(vec![lsp::Location::new(config_uri, config_range)])) }
+            }
+        });
+        // The server also renames the module in a file it didn't report as a reference.
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>(move |_, _| {
+            let config_uri = config_uri.clone();
+            let lib_uri = lib_uri.clone();
+            async move {
+                Ok(Some(lsp::WorkspaceEdit::new(HashMap::from_iter([
+                    (
+                        config_uri,
+                        vec![lsp::TextEdit::new(config_range, "AppConfig".into())],
+                    ),
+                    (
+                        lib_uri,
+                        vec![lsp::TextEdit::new(lib_range, "app_config".into())],
+                    ),
+                ]))))
+            }
+        });
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(RenameSymbolTool::new(project.clone(), action_log.clone()));
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.run(
+                RenameSymbolToolInput {
+                    path: "root/src/config.rs".into(),
+                    line: 1,
+                    symbol: "Config".into(),
+                    new_name: "AppConfig".into(),
+                },
+                event_stream,
+                cx,
+            )
+        });
+        event_rx
+            .expect_authorization()
+            .await
+            .response
+            .send(acp::PermissionOptionId::new("allow"))
+            .unwrap();
+
+        let error = task.await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&Path::new("root/src/lib.rs").display().to_string()),
+            "{error}"
+        );
+        cx.run_until_parked();
+        for (path, text) in [
+            ("root/src/config.rs", "pub struct Config;\n"),
+            ("root/src/lib.rs", "mod config;\n"),
+        ] {
+            let buffer = project
+                .update(cx, |project, cx| {
+                    let project_path = project.find_project_path(path, cx).unwrap();
+                    project.open_buffer(project_path, cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), text);
+        }
+    }
+}
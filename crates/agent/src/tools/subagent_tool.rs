@@ -0,0 +1,255 @@
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result, anyhow};
+use futures::StreamExt as _;
+use gpui::{App, AppContext as _, SharedString, Task, WeakEntity};
+use language_model::{LanguageModel, LanguageModelRegistry, SelectedModel};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{rc::Rc, str::FromStr as _, sync::Arc};
+
+use crate::{
+    AgentTool, Thread, ThreadEnvironment, ThreadEvent, ToolCallEventStream, UserMessageId,
+};
+
+const SUBAGENT_INSTRUCTIONS: &str = "You are a sub-agent working on a task delegated by another agent, \
+which will only see your final message. When you are done, end your turn with a concise summary of \
+what you did, what you found, and anything that still needs attention.";
+
+/// Delegates a self-contained subtask to a sub-agent, which works on it in its own context window and reports back a summary.
+///
+/// Use this to keep large but separable pieces of work out of your own context, like applying the same change across many files or investigating a part of the codebase. You can delegate several independent subtasks at once to have them worked on in parallel, as long as they don't edit the same files.
+///
+/// The sub-agent doesn't see this conversation, so the task must include everything it needs to know: the goal, the relevant paths, any constraints, and what to report back.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SubagentToolInput {
+    /// A short label for the subtask, shown to the user while the sub-agent works on it.
+    pub label: String,
+    /// Complete instructions for the sub-agent.
+    pub task: String,
+    /// The model the sub-agent uses, as `provider/model`. Defaults to your own model.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// The names of the only tools the sub-agent may use, out of the ones available to you.
+    /// Defaults to all of them.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+}
+
+pub struct SubagentTool {
+    thread: WeakEntity<Thread>,
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl SubagentTool {
+    pub fn new(thread: WeakEntity<Thread>, environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self {
+            thread,
+            environment,
+        }
+    }
+}
+
+impl AgentTool for SubagentTool {
+    type Input = SubagentToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "subagent"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Other
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => input.label.into(),
+            Err(_) => "Delegate subtask".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String>> {
+        let model = match input.model.as_deref().map(|model| find_model(model, cx)) {
+            Some(Ok(model)) => Some(model),
+            Some(Err(error)) => return Task::ready(Err(error)),
+            None => None,
+        };
+
+        let subagent = match self.thread.update(cx, |thread, cx| {
+            cx.new(|cx| Thread::new_subagent(thread, cx))
+        }) {
+            Ok(subagent) => subagent,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let events = subagent.update(cx, |subagent, cx| {
+            subagent.add_default_tools(self.environment.clone(), cx);
+            // Keep sub-agents from delegating in turn.
+            subagent.remove_tool(Self::name());
+            if let Some(model) = model {
+                subagent.set_model(model, cx);
+            }
+            if let Some(tools) = input.tools {
+                subagent.set_allowed_tools(Some(tools.into_iter().map(Into::into).collect()));
+            }
+            let task = format!("{}\n\n{SUBAGENT_INSTRUCTIONS}", input.task);
+            subagent.send(UserMessageId::new(), [task.as_str()], cx)
+        });
+
+        let label = input.label;
+        cx.spawn(async move |_cx| {
+            // Dropping the sub-agent cancels it, so keep it alive until it's done.
+            let _subagent = subagent;
+            let mut events = events?;
+            let mut progress = SubagentProgress::default();
+            while let Some(event) = events.next().await {
+                match event? {
+                    ThreadEvent::AgentText(text) => progress.push_text(&text),
+                    ThreadEvent::ToolCall(tool_call) => {
+                        progress.push_tool_call(tool_call.tool_call_id, tool_call.title)
+                    }
+                    ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(
+                        update,
+                    )) => progress.update_tool_call(update),
+                    ThreadEvent::ToolCallAuthorization(mut authorization) => {
+                        if let Some(title) = authorization.tool_call.fields.title.take() {
+                            authorization.tool_call.fields.title =
+                                Some(format!("{label}: {title}"));
+                        }
+                        event_stream.forward_authorization(authorization).await;
+                    }
+                    ThreadEvent::ToolCallInputRequest(input_request) => {
+                        event_stream.forward_input_request(input_request).await;
+                    }
+                    ThreadEvent::Stop(acp::StopReason::EndTurn) => break,
+                    ThreadEvent::Stop(acp::StopReason::Cancelled) => {
+                        return Err(anyhow!("The sub-agent was canceled"));
+                    }
+                    ThreadEvent::Stop(reason) => {
+                        return Err(anyhow!("The sub-agent stopped early: {reason:?}"));
+                    }
+                    ThreadEvent::UserMessage(_)
+                    | ThreadEvent::AgentThinking(_)
+                    | ThreadEvent::ToolCallUpdate(_)
+                    | ThreadEvent::Retry(_)
+                    | ThreadEvent::Plan(_) => continue,
+                }
+                event_stream.update_fields(
+                    acp::ToolCallUpdateFields::new()
+                        .title(label.clone())
+                        .content(vec![progress.to_markdown().into()]),
+                );
+            }
+            Ok(progress.summary())
+        })
+    }
+}
+
+/// Looks up a model given as `provider/model` among the configured ones.
+fn find_model(model: &str, cx: &App) -> Result<Arc<dyn LanguageModel>> {
+    let selected = SelectedModel::from_str(model).map_err(|error| anyhow!(error))?;
+    let registry = LanguageModelRegistry::read_global(cx);
+    registry
+        .available_models(cx)
+        .find(|model| model.id() == selected.model && model.provider_id() == selected.provider)
+        .with_context(|| {
+            format!(
+                "There is no language model named {model}. Available models: {}",
+                registry
+                    .available_models(cx)
+                    .map(|model| format!("{}/{}", model.provider_id().0, model.id().0))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+enum SubagentStep {
+    Text(String),
+    ToolCall {
+        id: acp::ToolCallId,
+        title: String,
+        status: acp::ToolCallStatus,
+    },
+}
+
+/// What a sub-agent has done so far, as shown in the card of the tool call that delegated to it.
+#[derive(Default)]
+struct SubagentProgress {
+    steps: Vec<SubagentStep>,
+}
+
+impl SubagentProgress {
+    fn push_text(&mut self, text: &str) {
+        if let Some(SubagentStep::Text(last_text)) = self.steps.last_mut() {
+            last_text.push_str(text);
+        } else {
+            self.steps.push(SubagentStep::Text(text.to_string()));
+        }
+    }
+
+    fn push_tool_call(&mut self, id: acp::ToolCallId, title: String) {
+        self.steps.push(SubagentStep::ToolCall {
+            id,
+            title,
+            status: acp::ToolCallStatus::Pending,
+        });
+    }
+
+    fn update_tool_call(&mut self, update: acp::ToolCallUpdate) {
+        for step in &mut self.steps {
+            if let SubagentStep::ToolCall { id, title, status } = step
+                && *id == update.tool_call_id
+            {
+                if let Some(new_title) = update.fields.title {
+                    *title = new_title;
+                }
+                if let Some(new_status) = update.fields.status {
+                    *status = new_status;
+                }
+                return;
+            }
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for step in &self.steps {
+            match step {
+                SubagentStep::Text(text) => {
+                    if !markdown.is_empty() {
+                        markdown.push('\n');
+                    }
+                    markdown.push_str(text.trim());
+                    markdown.push_str("\n\n");
+                }
+                SubagentStep::ToolCall { title, status, .. } => {
+                    let title = title.lines().next().unwrap_or_default();
+                    let status = match status {
+                        acp::ToolCallStatus::Completed => "",
+                        acp::ToolCallStatus::Failed => " (failed)",
+                        _ => " (running)",
+                    };
+                    markdown.push_str(&format!("- {title}{status}\n"));
+                }
+            }
+        }
+        markdown.trim_end().to_string()
+    }
+
+    /// The text the sub-agent ended its turn with.
+    fn summary(&self) -> String {
+        match self.steps.last() {
+            Some(SubagentStep::Text(text)) if !text.trim().is_empty() => text.trim().to_string(),
+            _ => "The sub-agent finished without reporting a summary.".to_string(),
+        }
+    }
+}
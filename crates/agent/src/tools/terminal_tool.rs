@@ -13,7 +13,7 @@ use std::{
 };
 use util::markdown::MarkdownInlineCode;
 
-use crate::{AgentTool, ThreadEnvironment, ToolCallEventStream};
+use crate::{AgentTool, PermissionSubject, ThreadEnvironment, ToolCallEventStream};
 
 const COMMAND_OUTPUT_LIMIT: u64 = 16 * 1024;
 
@@ -66,6 +66,10 @@ impl AgentTool for TerminalTool {
         acp::ToolKind::Execute
     }
 
+    fn permission_subjects(input: &Self::Input) -> Vec<PermissionSubject> {
+        vec![PermissionSubject::Command(input.command.clone())]
+    }
+
     fn initial_title(
         &self,
         input: Result<Self::Input, serde_json::Value>,
@@ -0,0 +1,140 @@
+use crate::{AgentTool, Thread, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, SharedString, Task, WeakEntity};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+
+/// Creates or updates the plan for the current task, a checklist of steps that the user sees while you work.
+///
+/// - Use this tool for tasks that take several steps, to show the user your progress. Skip it for simple tasks.
+/// - Send the whole plan every time, including the steps that are already done. It replaces the previous plan.
+/// - Mark one step as `in_progress` before you start working on it, and mark it `completed` as soon as it's done.
+/// - The user may edit, reorder or remove steps while you work. When they do, you'll be told about the new plan; follow it from then on.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdatePlanToolInput {
+    /// The steps of the plan, in the order you'll work on them. Pass no steps to clear the plan.
+    pub steps: Vec<PlanStep>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PlanStep {
+    /// What the step is about, in a short sentence.
+    pub content: String,
+    pub status: PlanStepStatus,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+impl From<PlanStep> for acp::PlanEntry {
+    fn from(step: PlanStep) -> Self {
+        let status = match step.status {
+            PlanStepStatus::Pending => acp::PlanEntryStatus::Pending,
+            PlanStepStatus::InProgress => acp::PlanEntryStatus::InProgress,
+            PlanStepStatus::Completed => acp::PlanEntryStatus::Completed,
+        };
+        acp::PlanEntry::new(step.content, acp::PlanEntryPriority::Medium, status)
+    }
+}
+
+pub struct UpdatePlanTool {
+    thread: WeakEntity<Thread>,
+}
+
+impl UpdatePlanTool {
+    pub fn new(thread: WeakEntity<Thread>) -> Self {
+        Self { thread }
+    }
+}
+
+impl AgentTool for UpdatePlanTool {
+    type Input = UpdatePlanToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "update_plan"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Think
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) if input.steps.is_empty() => "Clear plan".into(),
+            _ => "Update plan".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let entries = input.steps.into_iter().map(Into::into).collect();
+        let plan = self.thread.update(cx, |thread, cx| {
+            thread.set_plan(entries, cx);
+            plan_to_markdown(thread.plan())
+        });
+        Task::ready(plan.map(|plan| {
+            if plan.is_empty() {
+                "Cleared the plan.".to_string()
+            } else {
+                format!("Updated the plan:\n\n{plan}")
+            }
+        }))
+    }
+}
+
+/// Formats a plan as a Markdown checklist.
+pub(crate) fn plan_to_markdown(entries: &[acp::PlanEntry]) -> String {
+    let mut markdown = String::new();
+    for entry in entries {
+        let (checkbox, suffix) = match entry.status {
+            acp::PlanEntryStatus::Completed => ("[x]", ""),
+            acp::PlanEntryStatus::InProgress => ("[ ]", " (in progress)"),
+            _ => ("[ ]", ""),
+        };
+        writeln!(markdown, "- {checkbox} {}{suffix}", entry.content).ok();
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_plan_to_markdown() {
+        let entries = [
+            ("Read the parser", acp::PlanEntryStatus::Completed),
+            ("Fix the bug", acp::PlanEntryStatus::InProgress),
+            ("Add a test", acp::PlanEntryStatus::Pending),
+        ]
+        .map(|(content, status)| {
+            acp::PlanEntry::new(content, acp::PlanEntryPriority::Medium, status)
+        });
+        assert_eq!(
+            plan_to_markdown(&entries),
+            indoc! {"
+                - [x] Read the parser
+                - [ ] Fix the bug (in progress)
+                - [ ] Add a test
+            "}
+        );
+        assert_eq!(plan_to_markdown(&[]), "");
+    }
+}
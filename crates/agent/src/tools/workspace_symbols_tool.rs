@@ -0,0 +1,216 @@
+use super::code_navigation::{CodeLocation, report_locations};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::{Project, Symbol, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Searches the symbols of the whole project by name, using the project's language servers.
+///
+/// Use this tool to find where a type, function or constant is defined when you know (part of) its name but not which file it's in. Language servers match symbol names fuzzily, so you don't need the exact name.
+///
+/// <example>
+/// To find the definition of a `ConfigLoader` type:
+/// {
+///     "query": "ConfigLoader"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceSymbolsToolInput {
+    /// The name of the symbols to search for.
+    pub query: String,
+}
+
+pub struct WorkspaceSymbolsTool {
+    project: Entity<Project>,
+}
+
+impl WorkspaceSymbolsTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for WorkspaceSymbolsTool {
+    type Input = WorkspaceSymbolsToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "workspace_symbols"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search symbols for {}", MarkdownInlineCode(&input.query)).into(),
+            Err(_) => "Search symbols".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let symbols = self
+            .project
+            .update(cx, |project, cx| project.symbols(&input.query, cx));
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let symbols = symbols.await?;
+            let locations = cx.update(|cx| {
+                symbols
+                    .iter()
+                    .filter_map(|symbol| symbol_location(symbol, &project, cx))
+                    .collect::<Vec<_>>()
+            })?;
+            if locations.is_empty() {
+                return Ok(format!(
+                    "No symbols found for {}",
+                    MarkdownInlineCode(&input.query)
+                ));
+            }
+            report_locations(
+                &format!("Symbols matching {}:", MarkdownInlineCode(&input.query)),
+                locations,
+                &event_stream,
+            )
+        })
+    }
+}
+
+fn symbol_location(symbol: &Symbol, project: &Entity<Project>, cx: &App) -> Option<CodeLocation> {
+    let (path, abs_path) = match &symbol.path {
+        SymbolLocation::InProject(project_path) => {
+            let project = project.read(cx);
+            let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+            (
+                worktree.read(cx).full_path(&project_path.path),
+                project.absolute_path(project_path, cx),
+            )
+        }
+        SymbolLocation::OutsideProject { abs_path, .. } => {
+            (abs_path.to_path_buf(), Some(abs_path.to_path_buf()))
+        }
+    };
+    Some(CodeLocation {
+        path,
+        abs_path,
+        row: symbol.range.start.0.row,
+        text: format!("{} ({:?})", symbol.label.text, symbol.kind),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_workspace_symbols(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "config.rs": "pub struct Config;\n",
+                    "main.rs": "fn main() {\n    let config = Config::default();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers =
+            language_registry.register_fake_lsp("Rust", language::FakeLspAdapter::default());
+
+        let (_buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(
+            |params, _| async move {
+                assert_eq!(params.query, "Conf");
+                #[allow(deprecated)]
+                let symbol = lsp::SymbolInformation {
+                    name: "Config".to_string(),
+                    kind: lsp::SymbolKind::STRUCT,
+                    tags: None,
+                    deprecated: None,
+                    container_name: None,
+                    location: lsp::Location::new(
+                        lsp::Uri::from_file_path(path!("/root/src/config.rs")).unwrap(),
+                        lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 17)),
+                    ),
+                };
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![symbol])))
+            },
+        );
+
+        let tool = Arc::new(WorkspaceSymbolsTool::new(project.clone()));
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.run(
+                WorkspaceSymbolsToolInput {
+                    query: "Conf".into(),
+                },
+                event_stream,
+                cx,
+            )
+        });
+
+        let output = task.await.unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "Symbols matching `Conf`:\n\n- {}:1: `Config ({:?})`\n",
+                Path::new("root/src/config.rs").display(),
+                lsp::SymbolKind::STRUCT
+            )
+        );
+
+        let fields = event_rx.expect_update_fields().await;
+        let locations = fields.locations.unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].path, Path::new(path!("/root/src/config.rs")));
+        assert_eq!(locations[0].line, Some(0));
+    }
+}
@@ -0,0 +1,160 @@
+mod run;
+
+use agent_settings::AgentProfileId;
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use gpui::{App, Application};
+use reqwest_client::ReqwestClient;
+use settings::SettingsStore;
+use std::{io::Read as _, path::PathBuf, sync::Arc};
+
+use crate::run::{PermissionPolicy, RunOptions, Transcript, run};
+
+/// Runs the agent on a project without a UI, and reports what it did as JSON.
+#[derive(Parser, Debug)]
+#[command(name = "agent_runner", disable_version_flag = true)]
+struct Args {
+    /// The prompt to send to the agent. Read from stdin if unspecified.
+    prompt: Option<String>,
+    /// The project to run the agent in.
+    #[arg(long, default_value = ".")]
+    project: PathBuf,
+    /// provider/model to use for the agent. Defaults to the model configured in the settings.
+    #[arg(long)]
+    model: Option<String>,
+    /// The agent profile to use, such as `write` or `ask`. Defaults to the profile configured
+    /// in the settings.
+    #[arg(long)]
+    profile: Option<String>,
+    /// How to answer tool calls that ask for permission.
+    #[arg(long, value_enum, default_value_t = PermissionPolicy::Deny)]
+    permissions: PermissionPolicy,
+    /// A settings file to use in place of the user's settings.
+    #[arg(long)]
+    settings: Option<PathBuf>,
+    /// Where to write the JSON transcript. Defaults to stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Where to also write the agent's changes, as a unified diff.
+    #[arg(long)]
+    diff: Option<PathBuf>,
+    /// Output current environment variables as JSON to stdout
+    #[arg(long, hide = true)]
+    printenv: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    // This prevents errors showing up in the logs, because
+    // project::environment::load_shell_environment() calls
+    // std::env::current_exe().unwrap() --printenv
+    if args.printenv {
+        util::shell_env::print_env();
+        return;
+    }
+
+    env_logger::init();
+
+    let options = match run_options(&args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error:#}");
+            std::process::exit(2);
+        }
+    };
+
+    let http_client = Arc::new(ReqwestClient::new());
+    let app = Application::headless().with_http_client(http_client);
+
+    app.run(move |cx| {
+        let app_state = Arc::new(headless_app::init("Zed Agent Runner", cx));
+        if let Err(error) = load_settings(args.settings.as_ref(), cx) {
+            eprintln!("{error:#}");
+            std::process::exit(2);
+        }
+
+        cx.spawn(async move |cx| {
+            let result = async {
+                let transcript = run(options, app_state, cx).await?;
+                write_transcript(&transcript, &args)?;
+                anyhow::Ok(transcript.succeeded())
+            }
+            .await;
+
+            let exit_code = match result {
+                Ok(true) => 0,
+                Ok(false) => 1,
+                Err(error) => {
+                    eprintln!("{error:#}");
+                    2
+                }
+            };
+            cx.update(|cx| cx.quit()).ok();
+            std::process::exit(exit_code);
+        })
+        .detach();
+    });
+}
+
+fn run_options(args: &Args) -> Result<RunOptions> {
+    let prompt = match &args.prompt {
+        Some(prompt) => prompt.clone(),
+        None => {
+            let mut prompt = String::new();
+            std::io::stdin()
+                .read_to_string(&mut prompt)
+                .context("Failed to read the prompt from stdin")?;
+            prompt
+        }
+    };
+    anyhow::ensure!(!prompt.trim().is_empty(), "The prompt is empty");
+
+    let project_path = args
+        .project
+        .canonicalize()
+        .with_context(|| format!("Failed to open project at {}", args.project.display()))?;
+
+    Ok(RunOptions {
+        project_path,
+        prompt,
+        model: args.model.clone(),
+        profile: args
+            .profile
+            .as_deref()
+            .map(|profile| AgentProfileId(profile.into())),
+        permissions: args.permissions,
+    })
+}
+
+/// Applies the settings file passed with `--settings`, or else the user's settings file if there
+/// is one.
+fn load_settings(path: Option<&PathBuf>, cx: &mut App) -> Result<()> {
+    let content = match path {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings from {}", path.display()))?,
+        None => match std::fs::read_to_string(paths::settings_file()) {
+            Ok(content) => content,
+            Err(_) => return Ok(()),
+        },
+    };
+    SettingsStore::update_global(cx, |store, cx| store.set_user_settings(&content, cx))
+        .result()
+        .context("Failed to parse settings")?;
+    Ok(())
+}
+
+fn write_transcript(transcript: &Transcript, args: &Args) -> Result<()> {
+    if let Some(path) = &args.diff {
+        std::fs::write(path, &transcript.diff)
+            .with_context(|| format!("Failed to write the diff to {}", path.display()))?;
+    }
+
+    let json = serde_json::to_string_pretty(transcript)?;
+    match &args.output {
+        Some(path) => std::fs::write(path, json)
+            .with_context(|| format!("Failed to write the transcript to {}", path.display()))?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
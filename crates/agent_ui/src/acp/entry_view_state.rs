@@ -18,7 +18,10 @@ use theme::ThemeSettings;
 use ui::{Context, TextSize};
 use workspace::Workspace;
 
-use crate::acp::message_editor::{MessageEditor, MessageEditorEvent};
+use crate::acp::{
+    input_request_form::InputRequestForm,
+    message_editor::{MessageEditor, MessageEditorEvent},
+};
 
 pub struct EntryViewState {
     workspace: WeakEntity<Workspace>,
@@ -118,6 +121,12 @@ impl EntryViewState {
                 let id = tool_call.id.clone();
                 let terminals = tool_call.terminals().cloned().collect::<Vec<_>>();
                 let diffs = tool_call.diffs().cloned().collect::<Vec<_>>();
+                let input_request = match &tool_call.status {
+                    acp_thread::ToolCallStatus::WaitingForInput { request, .. } => {
+                        Some(request.clone())
+                    }
+                    _ => None,
+                };
 
                 let views = if let Some(Entry::Content(views)) = self.entries.get_mut(index) {
                     views
@@ -170,6 +179,13 @@ impl EntryViewState {
                         element
                     });
                 }
+
+                if let Some(request) = input_request {
+                    views.entry(request.entity_id()).or_insert_with(|| {
+                        cx.new(|cx| InputRequestForm::new(request.clone(), window, cx))
+                            .into_any()
+                    });
+                }
             }
             AgentThreadEntry::AssistantMessage(message) => {
                 let entry = if let Some(Entry::AssistantMessage(entry)) =
@@ -296,6 +312,16 @@ impl Entry {
             .map(|entity| entity.downcast::<TerminalView>().unwrap())
     }
 
+    pub fn input_request_form(
+        &self,
+        request: &Entity<acp_thread::InputRequest>,
+    ) -> Option<Entity<InputRequestForm>> {
+        self.content_map()?
+            .get(&request.entity_id())
+            .cloned()
+            .map(|entity| entity.downcast::<InputRequestForm>().unwrap())
+    }
+
     pub fn scroll_handle_for_assistant_message_chunk(
         &self,
         chunk_ix: usize,
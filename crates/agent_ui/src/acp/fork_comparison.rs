@@ -0,0 +1,286 @@
+use agent::ForkOrigin;
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use git::repository::MissingCheckpoint;
+use gpui::{
+    App, AppContext as _, Entity, EventEmitter, FocusHandle, Focusable, SharedString, Task, Window,
+    prelude::*,
+};
+use language::{Buffer, Language};
+use project::{Project, git_store::GitStoreCheckpoint};
+use std::sync::Arc;
+use ui::{Divider, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{Item, Workspace};
+
+/// Shows the changes a forked thread made to the project next to the ones the thread it was
+/// forked from had made, both relative to where the two conversations diverged.
+pub struct ForkComparison {
+    title: SharedString,
+    origin: ForkOrigin,
+    project: Entity<Project>,
+    original_editor: Entity<Editor>,
+    fork_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    error: Option<SharedString>,
+    pending_task: Option<Task<()>>,
+}
+
+impl ForkComparison {
+    pub fn open(
+        title: SharedString,
+        origin: ForkOrigin,
+        workspace: Entity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let diff_language_task = workspace
+            .read(cx)
+            .app_state()
+            .languages
+            .language_for_name("Diff");
+        let project = workspace.read(cx).project().clone();
+
+        window.spawn(cx, async move |cx| {
+            let diff_language = diff_language_task.await.log_err();
+            workspace.update_in(cx, |workspace, window, cx| {
+                let comparison =
+                    cx.new(|cx| Self::new(title, origin, project, diff_language, window, cx));
+                workspace.add_item_to_active_pane(Box::new(comparison), None, true, window, cx);
+            })
+        })
+    }
+
+    fn new(
+        title: SharedString,
+        origin: ForkOrigin,
+        project: Entity<Project>,
+        diff_language: Option<Arc<Language>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut new_editor = |cx: &mut Context<Self>| {
+            let buffer = cx.new(|cx| {
+                let mut buffer = Buffer::local("", cx);
+                buffer.set_language(diff_language.clone(), cx);
+                buffer
+            });
+            cx.new(|cx| {
+                let mut editor = Editor::for_buffer(buffer, Some(project.clone()), window, cx);
+                editor.set_read_only(true);
+                editor
+            })
+        };
+        let original_editor = new_editor(cx);
+        let fork_editor = new_editor(cx);
+
+        let mut this = Self {
+            title,
+            origin,
+            project,
+            original_editor,
+            fork_editor,
+            focus_handle: cx.focus_handle(),
+            error: None,
+            pending_task: None,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    /// Diffs the original's changes and the fork's current changes against the fork point.
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let git_store = self.project.read(cx).git_store().clone();
+        let current_checkpoint = git_store.update(cx, |git_store, cx| git_store.checkpoint(cx));
+        let base_checkpoint = self.origin.base_checkpoint.clone();
+        let original_checkpoint = self.origin.original_checkpoint.clone();
+
+        self.pending_task = Some(cx.spawn(async move |this, cx| {
+            let diffs = async {
+                let current_checkpoint = current_checkpoint.await?;
+                let (original_diff, fork_diff) = git_store.update(cx, |git_store, cx| {
+                    (
+                        git_store.diff_checkpoints(
+                            base_checkpoint.clone(),
+                            original_checkpoint,
+                            cx,
+                        ),
+                        git_store.diff_checkpoints(base_checkpoint, current_checkpoint, cx),
+                    )
+                })?;
+                anyhow::Ok((original_diff.await?, fork_diff.await?))
+            }
+            .await;
+
+            this.update(cx, |this, cx| {
+                this.pending_task = None;
+                match diffs {
+                    Ok((original_diff, fork_diff)) => {
+                        this.error = None;
+                        set_text(&this.original_editor, original_diff, cx);
+                        set_text(&this.fork_editor, fork_diff, cx);
+                    }
+                    Err(error) if error.downcast_ref::<MissingCheckpoint>().is_some() => {
+                        this.error = Some(
+                            "Comparison isn't available anymore, because git has cleaned up the \
+                            project's state from when the threads diverged"
+                                .into(),
+                        );
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Puts the project back in the given state, then compares again.
+    fn restore(&mut self, checkpoint: GitStoreCheckpoint, cx: &mut Context<Self>) {
+        let git_store = self.project.read(cx).git_store().clone();
+        let restore = git_store.update(cx, |git_store, cx| {
+            git_store.restore_checkpoint(checkpoint, cx)
+        });
+
+        self.pending_task = Some(cx.spawn(async move |this, cx| {
+            let result = restore.await;
+            this.update(cx, |this, cx| match result {
+                Ok(()) => this.refresh(cx),
+                Err(error) => {
+                    this.pending_task = None;
+                    this.error = Some(format!("{error:#}").into());
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_busy = self.pending_task.is_some();
+
+        h_flex()
+            .p_2()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(self.title.clone()))
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("restore-fork-point", "Restore Fork Point")
+                            .label_size(LabelSize::Small)
+                            .disabled(is_busy)
+                            .tooltip(Tooltip::text(
+                                "Undo the changes made since the threads diverged",
+                            ))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.restore(this.origin.base_checkpoint.clone(), cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("restore-original", "Restore Original")
+                            .label_size(LabelSize::Small)
+                            .disabled(is_busy)
+                            .tooltip(Tooltip::text(
+                                "Replace the fork's changes with the original thread's",
+                            ))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.restore(this.origin.original_checkpoint.clone(), cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("refresh", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .disabled(is_busy)
+                            .tooltip(Tooltip::text("Compare Again"))
+                            .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+                    ),
+            )
+    }
+
+    fn render_side(
+        &self,
+        label: impl Into<SharedString>,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(label).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+}
+
+fn set_text(editor: &Entity<Editor>, text: String, cx: &mut App) {
+    if let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() {
+        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    }
+}
+
+impl EventEmitter<EditorEvent> for ForkComparison {}
+
+impl Focusable for ForkComparison {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ForkComparison {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        format!("Compare: {}", self.title).into()
+    }
+}
+
+impl Render for ForkComparison {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .items_start()
+                    .child(self.render_side(
+                        format!("Original: {}", self.origin.title),
+                        &self.original_editor,
+                        cx,
+                    ))
+                    .child(Divider::vertical())
+                    .child(self.render_side(
+                        format!("Fork: {}", self.title),
+                        &self.fork_editor,
+                        cx,
+                    )),
+            )
+    }
+}
@@ -0,0 +1,211 @@
+use acp_thread::{InputFieldKind, InputRequest};
+use gpui::{Entity, Render};
+use serde_json::{Map, Value};
+use ui::{Checkbox, ToggleState, prelude::*};
+use ui_input::InputField;
+
+/// The form for the input that a tool call asks the user for.
+pub struct InputRequestForm {
+    request: Entity<InputRequest>,
+    values: Vec<FieldValue>,
+    error: Option<SharedString>,
+}
+
+enum FieldValue {
+    Text(Entity<InputField>),
+    Boolean(bool),
+    Choice(Option<usize>),
+}
+
+impl InputRequestForm {
+    pub fn new(request: Entity<InputRequest>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let fields = request.read(cx).fields.clone();
+        let values = fields
+            .iter()
+            .enumerate()
+            .map(|(ix, field)| match &field.kind {
+                InputFieldKind::Text { default } => {
+                    FieldValue::Text(text_input(field.label(), default.clone(), ix, window, cx))
+                }
+                InputFieldKind::Number {
+                    integer, default, ..
+                } => FieldValue::Text(text_input(
+                    field.label(),
+                    default.map(|default| {
+                        if *integer {
+                            (default as i64).to_string()
+                        } else {
+                            default.to_string()
+                        }
+                    }),
+                    ix,
+                    window,
+                    cx,
+                )),
+                InputFieldKind::Boolean { default } => FieldValue::Boolean(*default),
+                InputFieldKind::Choice {
+                    options, default, ..
+                } => FieldValue::Choice(
+                    default
+                        .as_ref()
+                        .and_then(|default| options.iter().position(|option| option == default)),
+                ),
+            })
+            .collect();
+
+        Self {
+            request,
+            values,
+            error: None,
+        }
+    }
+
+    /// The values the user filled in, keyed by field name, or `None` if some of them are invalid,
+    /// in which case the form shows why.
+    pub fn submit(&mut self, cx: &mut Context<Self>) -> Option<Map<String, Value>> {
+        let result = self.values(cx);
+        self.error = result.as_ref().err().map(|error| error.to_string().into());
+        cx.notify();
+        result.ok()
+    }
+
+    fn values(&self, cx: &App) -> anyhow::Result<Map<String, Value>> {
+        let mut values = Map::new();
+        for (field, value) in self.request.read(cx).fields.iter().zip(&self.values) {
+            let value = match value {
+                FieldValue::Text(input) => field.parse_text(&input.read(cx).text(cx))?,
+                FieldValue::Boolean(checked) => Some(Value::Bool(*checked)),
+                FieldValue::Choice(selected) => match (&field.kind, selected) {
+                    (InputFieldKind::Choice { options, .. }, Some(ix)) => {
+                        options.get(*ix).cloned().map(Value::String)
+                    }
+                    _ => None,
+                },
+            };
+            match value {
+                Some(value) => {
+                    values.insert(field.name.clone(), value);
+                }
+                None => anyhow::ensure!(!field.required, "{} is required", field.label()),
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn text_input(
+    label: &str,
+    text: Option<String>,
+    tab_index: usize,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<InputField> {
+    let label = SharedString::from(label.to_string());
+    cx.new(|cx| {
+        let input = InputField::new(window, cx, "")
+            .label(label)
+            .tab_index(tab_index as isize)
+            .tab_stop(true);
+        if let Some(text) = text {
+            input.set_text(text, window, cx);
+        }
+        input
+    })
+}
+
+impl Render for InputRequestForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let request = self.request.read(cx);
+
+        v_flex()
+            .p_2()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::ToolHammer)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(format!("{} asks:", request.requester))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(Label::new(request.message.clone()).size(LabelSize::Small))
+            .children(request.fields.iter().zip(&self.values).enumerate().map(
+                |(ix, (field, value))| {
+                    let control = match value {
+                        FieldValue::Text(input) => input.clone().into_any_element(),
+                        FieldValue::Boolean(checked) => {
+                            Checkbox::new(("input-field", ix), (*checked).into())
+                                .label(field.label().to_string())
+                                .on_click(cx.listener(move |this, state, _window, cx| {
+                                    this.values[ix] =
+                                        FieldValue::Boolean(*state == ToggleState::Selected);
+                                    cx.notify();
+                                }))
+                                .into_any_element()
+                        }
+                        FieldValue::Choice(selected) => {
+                            self.render_choice(ix, field, *selected, cx)
+                        }
+                    };
+                    v_flex().gap_0p5().child(control).when_some(
+                        field.description.clone(),
+                        |this, description| {
+                            this.child(
+                                Label::new(description)
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                            )
+                        },
+                    )
+                },
+            ))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+    }
+}
+
+impl InputRequestForm {
+    fn render_choice(
+        &self,
+        ix: usize,
+        field: &acp_thread::InputField,
+        selected: Option<usize>,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let InputFieldKind::Choice { labels, .. } = &field.kind else {
+            return div().into_any_element();
+        };
+
+        v_flex()
+            .gap_1()
+            .child(Label::new(field.label().to_string()).size(LabelSize::Small))
+            .child(
+                h_flex()
+                    .flex_wrap()
+                    .gap_1()
+                    .children(labels.iter().enumerate().map(|(option_ix, label)| {
+                        Button::new(
+                            SharedString::from(format!("input-field-{ix}-{option_ix}")),
+                            label.clone(),
+                        )
+                        .style(ButtonStyle::Outlined)
+                        .label_size(LabelSize::Small)
+                        .toggle_state(selected == Some(option_ix))
+                        .on_click(cx.listener(
+                            move |this, _, _window, cx| {
+                                this.values[ix] = FieldValue::Choice(Some(option_ix));
+                                cx.notify();
+                            },
+                        ))
+                    })),
+            )
+            .into_any_element()
+    }
+}
@@ -71,6 +71,7 @@ impl PromptCompletionProviderDelegate for Entity<MessageEditor> {
                 PromptContextType::Thread,
                 PromptContextType::Fetch,
                 PromptContextType::Rules,
+                PromptContextType::Resource,
             ]);
         }
         supported
@@ -182,8 +183,9 @@ impl MessageEditor {
                 {
                     editor.update(cx, |editor, cx| {
                         let snapshot = editor.snapshot(window, cx);
-                        this.mention_set
-                            .update(cx, |mention_set, _cx| mention_set.remove_invalid(&snapshot));
+                        this.mention_set.update(cx, |mention_set, cx| {
+                            mention_set.remove_invalid(&snapshot, cx)
+                        });
 
                         let new_hints = this
                             .command_hint(snapshot.buffer())
@@ -438,7 +440,10 @@ impl MessageEditor {
                                     mention_image.format.mime_type(),
                                 )
                                 .uri(match uri {
-                                    MentionUri::File { .. } => Some(uri.to_uri().to_string()),
+                                    MentionUri::File { .. }
+                                    | MentionUri::ContextServerResource { .. } => {
+                                        Some(uri.to_uri().to_string())
+                                    }
                                     MentionUri::PastedImage => None,
                                     other => {
                                         debug_panic!(
@@ -474,9 +479,9 @@ impl MessageEditor {
         self.editor.update(cx, |editor, cx| {
             editor.clear(window, cx);
             editor.remove_creases(
-                self.mention_set.update(cx, |mention_set, _cx| {
+                self.mention_set.update(cx, |mention_set, cx| {
                     mention_set
-                        .clear()
+                        .clear(cx)
                         .map(|(crease_id, _)| crease_id)
                         .collect::<Vec<_>>()
                 }),
@@ -2179,6 +2184,8 @@ mod tests {
             id: acp::SessionId::new("thread-123"),
             title: "Previous Conversation".into(),
             updated_at: chrono::Utc::now(),
+            tags: Vec::new(),
+            pinned: false,
         };
 
         let message_editor = cx.update(|window, cx| {
@@ -2402,6 +2409,262 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_context_server_resource_mentions(cx: &mut TestAppContext) {
+        use context_server::{
+            ContextServer, ContextServerId,
+            types::{self, notifications::ResourcesUpdated, requests},
+        };
+        use parking_lot::Mutex;
+        use project::project_settings::{ContextServerSettings, ProjectSettings};
+        use settings::Settings as _;
+
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/project", json!({})).await;
+        let project = Project::test(fs, [Path::new(path!("/project"))], cx).await;
+
+        cx.update(|cx| {
+            let mut settings = ProjectSettings::get_global(cx).clone();
+            settings.context_servers.insert(
+                "docs".into(),
+                ContextServerSettings::Stdio {
+                    enabled: true,
+                    command: context_server::ContextServerCommand {
+                        path: "docs-server".into(),
+                        args: Vec::new(),
+                        env: None,
+                        timeout: None,
+                    },
+                },
+            );
+            ProjectSettings::override_global(settings, cx);
+        });
+
+        let pages = Arc::new(Mutex::new(collections::HashMap::from_iter([(
+            "docs://pages/intro".to_string(),
+            "Welcome".to_string(),
+        )])));
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+        let unsubscriptions = Arc::new(Mutex::new(Vec::new()));
+        let transport = context_server::test::create_fake_transport("docs", cx.executor());
+        let server = transport.server();
+        let transport = transport
+            .on_request::<requests::Initialize, _>(|_| async {
+                types::InitializeResponse {
+                    protocol_version: types::ProtocolVersion(
+                        types::LATEST_PROTOCOL_VERSION.to_string(),
+                    ),
+                    server_info: types::Implementation {
+                        name: "docs".into(),
+                        version: "1.0.0".into(),
+                    },
+                    capabilities: types::ServerCapabilities {
+                        resources: Some(types::ResourcesCapabilities {
+                            subscribe: Some(true),
+                            list_changed: None,
+                        }),
+                        ..Default::default()
+                    },
+                    meta: None,
+                }
+            })
+            .on_request::<requests::ResourcesList, _>(|_| async {
+                types::ResourcesListResponse {
+                    resources: vec![types::Resource {
+                        uri: "docs://readme".parse().unwrap(),
+                        name: "README".into(),
+                        description: None,
+                        mime_type: None,
+                    }],
+                    next_cursor: None,
+                    meta: None,
+                }
+            })
+            .on_request::<requests::ListResourceTemplates, _>(|_| async {
+                types::ListResourceTemplatesResponse {
+                    resource_templates: vec![types::ResourceTemplate {
+                        uri_template: "docs://pages/{page}".into(),
+                        name: "Page".into(),
+                        description: None,
+                        mime_type: None,
+                    }],
+                    next_cursor: None,
+                    meta: None,
+                }
+            })
+            .on_request::<requests::ResourcesRead, _>({
+                let pages = pages.clone();
+                move |params| {
+                    let text = pages.lock()[params.uri.as_str()].clone();
+                    async move {
+                        types::ResourcesReadResponse {
+                            contents: vec![types::ResourceContentsType::Text(
+                                types::TextResourceContents {
+                                    uri: params.uri,
+                                    mime_type: None,
+                                    text,
+                                },
+                            )],
+                            meta: None,
+                        }
+                    }
+                }
+            })
+            .on_request::<requests::ResourcesSubscribe, _>({
+                let subscriptions = subscriptions.clone();
+                move |params| {
+                    subscriptions.lock().push(params.uri.to_string());
+                    async { types::EmptyResponse::default() }
+                }
+            })
+            .on_request::<requests::ResourcesUnsubscribe, _>({
+                let unsubscriptions = unsubscriptions.clone();
+                move |params| {
+                    unsubscriptions.lock().push(params.uri.to_string());
+                    async { types::EmptyResponse::default() }
+                }
+            });
+        project.update(cx, |project, cx| {
+            project.context_server_store().update(cx, |store, cx| {
+                store.start_server(
+                    Arc::new(ContextServer::new(
+                        ContextServerId("docs".into()),
+                        Arc::new(transport),
+                    )),
+                    cx,
+                )
+            })
+        });
+        cx.run_until_parked();
+
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let text_thread_store = cx.new(|cx| TextThreadStore::fake(project.clone(), cx));
+        let history_store = cx.new(|cx| HistoryStore::new(text_thread_store, cx));
+
+        let (message_editor, editor) = workspace.update_in(cx, |workspace, window, cx| {
+            let workspace_handle = cx.weak_entity();
+            let message_editor = cx.new(|cx| {
+                MessageEditor::new(
+                    workspace_handle,
+                    project.downgrade(),
+                    history_store.clone(),
+                    None,
+                    Rc::new(RefCell::new(
+                        acp::PromptCapabilities::new().embedded_context(true),
+                    )),
+                    Default::default(),
+                    "Test Agent".into(),
+                    "Test",
+                    EditorMode::AutoHeight {
+                        max_lines: None,
+                        min_lines: 1,
+                    },
+                    window,
+                    cx,
+                )
+            });
+            workspace.active_pane().update(cx, |pane, cx| {
+                pane.add_item(
+                    Box::new(cx.new(|_| MessageEditorItem(message_editor.clone()))),
+                    true,
+                    true,
+                    None,
+                    window,
+                    cx,
+                );
+            });
+            message_editor.read(cx).focus_handle(cx).focus(window, cx);
+            let editor = message_editor.read(cx).editor().clone();
+            (message_editor, editor)
+        });
+
+        cx.simulate_input("Summarize @resource ");
+        editor.update(cx, |editor, _cx| {
+            assert!(editor.has_visible_completions_menu());
+            assert_eq!(current_completion_labels(editor), &["README", "Page"]);
+        });
+
+        // Picking a template leaves the user to fill in its variables.
+        editor.update_in(cx, |editor, window, cx| {
+            editor.context_menu_next(&editor::actions::ContextMenuNext, window, cx);
+            editor.confirm_completion(&editor::actions::ConfirmCompletion::default(), window, cx);
+        });
+        cx.run_until_parked();
+        cx.simulate_input("intro");
+        editor.update_in(cx, |editor, window, cx| {
+            assert_eq!(editor.text(cx), "Summarize @resource docs://pages/intro");
+            assert_eq!(current_completion_labels(editor), &["docs://pages/intro"]);
+            editor.confirm_completion(&editor::actions::ConfirmCompletion::default(), window, cx);
+        });
+        cx.run_until_parked();
+
+        let mention_uri = MentionUri::ContextServerResource {
+            server_id: "docs".into(),
+            uri: "docs://pages/intro".parse().unwrap(),
+            name: "docs://pages/intro".into(),
+        };
+        editor.read_with(cx, |editor, cx| {
+            assert_eq!(
+                editor.text(cx),
+                format!("Summarize {} ", mention_uri.as_link())
+            );
+        });
+        assert_eq!(
+            *subscriptions.lock(),
+            vec!["docs://pages/intro".to_string()]
+        );
+
+        let expected_content = |text: &str| {
+            vec![
+                "Summarize ".into(),
+                acp::ContentBlock::Resource(acp::EmbeddedResource::new(
+                    acp::EmbeddedResourceResource::TextResourceContents(
+                        acp::TextResourceContents::new(text, mention_uri.to_uri().to_string()),
+                    ),
+                )),
+            ]
+        };
+        let content = message_editor
+            .update(cx, |editor, cx| editor.contents(false, cx))
+            .await
+            .unwrap()
+            .0;
+        pretty_assertions::assert_eq!(content, expected_content("Welcome"));
+
+        // The mention picks up the resource's new contents when the server says it changed.
+        pages
+            .lock()
+            .insert("docs://pages/intro".into(), "Welcome back".into());
+        server
+            .notify::<ResourcesUpdated>(types::ResourcesUpdatedParams {
+                uri: "docs://pages/intro".into(),
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        let content = message_editor
+            .update(cx, |editor, cx| editor.contents(false, cx))
+            .await
+            .unwrap()
+            .0;
+        pretty_assertions::assert_eq!(content, expected_content("Welcome back"));
+
+        // The server stops sending updates once the mention is gone.
+        assert!(unsubscriptions.lock().is_empty());
+        message_editor.update_in(cx, |message_editor, window, cx| {
+            message_editor.clear(window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            *unsubscriptions.lock(),
+            vec!["docs://pages/intro".to_string()]
+        );
+    }
+
     #[gpui::test]
     async fn test_autoscroll_after_insert_selections(cx: &mut TestAppContext) {
         init_test(cx);
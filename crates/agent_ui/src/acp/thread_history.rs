@@ -1,6 +1,7 @@
 use crate::acp::AcpThreadView;
 use crate::{AgentPanel, RemoveHistory, RemoveSelectedThread};
-use agent::{HistoryEntry, HistoryStore};
+use agent::{DbThreadSearchMatch, HistoryEntry, HistoryStore};
+use agent_client_protocol as acp;
 use chrono::{Datelike as _, Local, NaiveDate, TimeDelta, Utc};
 use editor::{Editor, EditorEvent};
 use fuzzy::StringMatchCandidate;
@@ -12,9 +13,10 @@ use std::{fmt::Display, ops::Range};
 use text::Bias;
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
-    HighlightedLabel, IconButtonShape, ListItem, ListItemSpacing, Tab, Tooltip, WithScrollbar,
-    prelude::*,
+    Chip, HighlightedLabel, IconButtonShape, ListItem, ListItemSpacing, Tab, Tooltip,
+    WithScrollbar, prelude::*,
 };
+use util::ResultExt as _;
 
 pub struct AcpThreadHistory {
     pub(crate) history_store: Entity<HistoryStore>,
@@ -26,11 +28,14 @@ pub struct AcpThreadHistory {
     visible_items: Vec<ListItemType>,
     local_timezone: UtcOffset,
     confirming_delete_history: bool,
+    /// The thread whose tags are being edited, and the editor for them.
+    editing_tags: Option<(acp::SessionId, Entity<Editor>)>,
     _update_task: Task<()>,
     _subscriptions: Vec<gpui::Subscription>,
 }
 
 enum ListItemType {
+    PinnedSeparator,
     BucketSeparator(TimeBucket),
     Entry {
         entry: HistoryEntry,
@@ -39,6 +44,8 @@ enum ListItemType {
     SearchResult {
         entry: HistoryEntry,
         positions: Vec<usize>,
+        /// The part of the thread's messages that matched, for matches that aren't in the title.
+        snippet: Option<SharedString>,
     },
 }
 
@@ -100,6 +107,7 @@ impl AcpThreadHistory {
             .unwrap(),
             search_query: SharedString::default(),
             confirming_delete_history: false,
+            editing_tags: None,
             _subscriptions: vec![search_editor_subscription, history_store_subscription],
             _update_task: Task::ready(()),
         };
@@ -149,10 +157,20 @@ impl AcpThreadHistory {
 
     fn add_list_separators(&self, entries: Vec<HistoryEntry>, cx: &App) -> Task<Vec<ListItemType>> {
         cx.background_spawn(async move {
-            let mut items = Vec::with_capacity(entries.len() + 1);
+            let mut items = Vec::with_capacity(entries.len() + 2);
             let mut bucket = None;
             let today = Local::now().naive_local().date();
 
+            let (pinned, entries): (Vec<_>, Vec<_>) =
+                entries.into_iter().partition(|entry| entry.pinned());
+            if !pinned.is_empty() {
+                items.push(ListItemType::PinnedSeparator);
+                items.extend(pinned.into_iter().map(|entry| ListItemType::Entry {
+                    entry,
+                    format: EntryTimeFormat::DateAndTime,
+                }));
+            }
+
             for entry in entries.into_iter() {
                 let entry_date = entry
                     .updated_at()
@@ -178,12 +196,37 @@ impl AcpThreadHistory {
     fn filter_search_results(
         &self,
         entries: Vec<HistoryEntry>,
-        cx: &App,
+        cx: &mut Context<Self>,
     ) -> Task<Vec<ListItemType>> {
         let query = self.search_query.clone();
-        cx.background_spawn({
+
+        // Queries like `#bug` list the threads tagged with a tag starting with `bug`.
+        if let Some(tag) = query.strip_prefix('#') {
+            let tag = tag.trim().to_lowercase();
+            return Task::ready(
+                entries
+                    .into_iter()
+                    .filter(|entry| {
+                        entry
+                            .tags()
+                            .iter()
+                            .any(|entry_tag| entry_tag.to_lowercase().starts_with(&tag))
+                    })
+                    .map(|entry| ListItemType::SearchResult {
+                        entry,
+                        positions: Vec::new(),
+                        snippet: None,
+                    })
+                    .collect(),
+            );
+        }
+
+        let content_matches = self
+            .history_store
+            .update(cx, |store, cx| store.search_threads(query.to_string(), cx));
+        cx.spawn({
             let executor = cx.background_executor().clone();
-            async move {
+            async move |_, _| {
                 let mut candidates = Vec::with_capacity(entries.len());
 
                 for (idx, entry) in entries.iter().enumerate() {
@@ -203,13 +246,39 @@ impl AcpThreadHistory {
                 )
                 .await;
 
-                matches
+                let mut items = matches
                     .into_iter()
                     .map(|search_match| ListItemType::SearchResult {
                         entry: entries[search_match.candidate_id].clone(),
                         positions: search_match.positions,
+                        snippet: None,
                     })
-                    .collect()
+                    .collect::<Vec<_>>();
+
+                // Then list the threads whose messages match, unless their title did already.
+                let content_matches = content_matches.await.log_err().unwrap_or_default();
+                for DbThreadSearchMatch { id, snippet } in content_matches {
+                    let already_listed = items.iter().any(|item| {
+                        matches!(
+                            item.history_entry(),
+                            Some(HistoryEntry::AcpThread(thread)) if thread.id == id
+                        )
+                    });
+                    if already_listed {
+                        continue;
+                    }
+                    let entry = entries.iter().find(
+                        |entry| matches!(entry, HistoryEntry::AcpThread(thread) if thread.id == id),
+                    );
+                    if let Some(entry) = entry {
+                        items.push(ListItemType::SearchResult {
+                            entry: entry.clone(),
+                            positions: Vec::new(),
+                            snippet: Some(snippet),
+                        });
+                    }
+                }
+                items
             }
         })
     }
@@ -233,7 +302,7 @@ impl AcpThreadHistory {
         }
         while matches!(
             self.visible_items.get(index),
-            None | Some(ListItemType::BucketSeparator(..))
+            None | Some(ListItemType::BucketSeparator(..) | ListItemType::PinnedSeparator)
         ) {
             index = match bias {
                 Bias::Left => {
@@ -297,8 +366,21 @@ impl AcpThreadHistory {
         self.set_selected_index(self.visible_items.len() - 1, Bias::Left, cx);
     }
 
-    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
-        self.confirm_entry(self.selected_index, cx);
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_tags.is_some() {
+            self.save_tags(window, cx);
+        } else {
+            self.confirm_entry(self.selected_index, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_tags.take().is_some() {
+            self.search_editor.focus_handle(cx).focus(window, cx);
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
     }
 
     fn confirm_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
@@ -333,6 +415,52 @@ impl AcpThreadHistory {
         task.detach_and_log_err(cx);
     }
 
+    fn toggle_pinned(&mut self, visible_item_ix: usize, cx: &mut Context<Self>) {
+        let Some(HistoryEntry::AcpThread(thread)) = self.get_history_entry(visible_item_ix) else {
+            return;
+        };
+        let (id, pinned) = (thread.id.clone(), !thread.pinned);
+        self.history_store
+            .update(cx, |store, cx| store.set_thread_pinned(id, pinned, cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn edit_tags(&mut self, visible_item_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(HistoryEntry::AcpThread(thread)) = self.get_history_entry(visible_item_ix) else {
+            return;
+        };
+        let id = thread.id.clone();
+        let tags = thread.tags.join(", ");
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Comma-separated tags...", window, cx);
+            editor.set_text(tags, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window, cx);
+        self.editing_tags = Some((id, editor));
+        cx.notify();
+    }
+
+    fn save_tags(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((id, editor)) = self.editing_tags.take() else {
+            return;
+        };
+        let mut tags = Vec::<SharedString>::new();
+        for tag in editor.read(cx).text(cx).split(',') {
+            let tag = tag.trim();
+            if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+                tags.push(tag.to_string().into());
+            }
+        }
+        self.history_store
+            .update(cx, |store, cx| store.set_thread_tags(id, tags, cx))
+            .detach_and_log_err(cx);
+        self.search_editor.focus_handle(cx).focus(window, cx);
+        cx.notify();
+    }
+
     fn remove_history(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         self.history_store.update(cx, |store, cx| {
             store.delete_threads(cx).detach_and_log_err(cx)
@@ -369,15 +497,30 @@ impl AcpThreadHistory {
     fn render_list_item(&self, item: &ListItemType, ix: usize, cx: &Context<Self>) -> AnyElement {
         match item {
             ListItemType::Entry { entry, format } => self
-                .render_history_entry(entry, *format, ix, Vec::default(), cx)
+                .render_history_entry(entry, *format, ix, Vec::default(), None, cx)
                 .into_any(),
-            ListItemType::SearchResult { entry, positions } => self.render_history_entry(
+            ListItemType::SearchResult {
+                entry,
+                positions,
+                snippet,
+            } => self.render_history_entry(
                 entry,
                 EntryTimeFormat::DateAndTime,
                 ix,
                 positions.clone(),
+                snippet.clone(),
                 cx,
             ),
+            ListItemType::PinnedSeparator => div()
+                .px(DynamicSpacing::Base06.rems(cx))
+                .pt_2()
+                .pb_1()
+                .child(
+                    Label::new("Pinned")
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .into_any_element(),
             ListItemType::BucketSeparator(bucket) => div()
                 .px(DynamicSpacing::Base06.rems(cx))
                 .pt_2()
@@ -397,6 +540,7 @@ impl AcpThreadHistory {
         format: EntryTimeFormat,
         ix: usize,
         highlight_positions: Vec<usize>,
+        snippet: Option<SharedString>,
         cx: &Context<Self>,
     ) -> AnyElement {
         let selected = ix == self.selected_index;
@@ -418,6 +562,15 @@ impl AcpThreadHistory {
         let title = entry.title().clone();
         let full_date =
             EntryTimeFormat::DateAndTime.format_timestamp(timestamp, self.local_timezone);
+        // Content matches show what matched instead of the date.
+        let tooltip_meta = snippet.unwrap_or_else(|| full_date.into());
+        let is_agent_thread = matches!(entry, HistoryEntry::AcpThread(_));
+        let pinned = entry.pinned();
+        let tag_editor = self
+            .editing_tags
+            .as_ref()
+            .filter(|(id, _)| matches!(entry, HistoryEntry::AcpThread(thread) if &thread.id == id))
+            .map(|(_, editor)| editor.clone());
 
         h_flex()
             .w_full()
@@ -432,11 +585,32 @@ impl AcpThreadHistory {
                             .w_full()
                             .gap_2()
                             .justify_between()
-                            .child(
-                                HighlightedLabel::new(entry.title(), highlight_positions)
-                                    .size(LabelSize::Small)
-                                    .truncate(),
-                            )
+                            .map(|this| match tag_editor {
+                                Some(tag_editor) => this.child(
+                                    div()
+                                        .id("tag-editor")
+                                        .flex_1()
+                                        // Clicking the editor shouldn't open the thread.
+                                        .on_click(|_, _, cx| cx.stop_propagation())
+                                        .child(tag_editor),
+                                ),
+                                None => this.child(
+                                    h_flex()
+                                        .min_w_0()
+                                        .gap_1()
+                                        .child(
+                                            HighlightedLabel::new(
+                                                entry.title(),
+                                                highlight_positions,
+                                            )
+                                            .size(LabelSize::Small)
+                                            .truncate(),
+                                        )
+                                        .children(entry.tags().iter().map(|tag| {
+                                            Chip::new(tag.clone()).label_size(LabelSize::XSmall)
+                                        })),
+                                ),
+                            })
                             .child(
                                 Label::new(display_text)
                                     .color(Color::Muted)
@@ -444,7 +618,7 @@ impl AcpThreadHistory {
                             ),
                     )
                     .tooltip(move |_, cx| {
-                        Tooltip::with_meta(title.clone(), None, full_date.clone(), cx)
+                        Tooltip::with_meta(title.clone(), None, tooltip_meta.clone(), cx)
                     })
                     .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
                         if *is_hovered {
@@ -455,19 +629,59 @@ impl AcpThreadHistory {
 
                         cx.notify();
                     }))
-                    .end_slot::<IconButton>(if hovered {
+                    .end_slot::<Div>(if hovered {
                         Some(
-                            IconButton::new("delete", IconName::Trash)
-                                .shape(IconButtonShape::Square)
-                                .icon_size(IconSize::XSmall)
-                                .icon_color(Color::Muted)
-                                .tooltip(move |_window, cx| {
-                                    Tooltip::for_action("Delete", &RemoveSelectedThread, cx)
+                            h_flex()
+                                .when(is_agent_thread, |this| {
+                                    this.child(
+                                        IconButton::new(
+                                            "pin",
+                                            if pinned {
+                                                IconName::Unpin
+                                            } else {
+                                                IconName::Pin
+                                            },
+                                        )
+                                        .shape(IconButtonShape::Square)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon_color(Color::Muted)
+                                        .tooltip(Tooltip::text(if pinned {
+                                            "Unpin"
+                                        } else {
+                                            "Pin"
+                                        }))
+                                        .on_click(
+                                            cx.listener(move |this, _, _, cx| {
+                                                this.toggle_pinned(ix, cx);
+                                                cx.stop_propagation()
+                                            }),
+                                        ),
+                                    )
+                                    .child(
+                                        IconButton::new("tags", IconName::Hash)
+                                            .shape(IconButtonShape::Square)
+                                            .icon_size(IconSize::XSmall)
+                                            .icon_color(Color::Muted)
+                                            .tooltip(Tooltip::text("Edit Tags"))
+                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                this.edit_tags(ix, window, cx);
+                                                cx.stop_propagation()
+                                            })),
+                                    )
                                 })
-                                .on_click(cx.listener(move |this, _, _, cx| {
-                                    this.remove_thread(ix, cx);
-                                    cx.stop_propagation()
-                                })),
+                                .child(
+                                    IconButton::new("delete", IconName::Trash)
+                                        .shape(IconButtonShape::Square)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon_color(Color::Muted)
+                                        .tooltip(move |_window, cx| {
+                                            Tooltip::for_action("Delete", &RemoveSelectedThread, cx)
+                                        })
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.remove_thread(ix, cx);
+                                            cx.stop_propagation()
+                                        })),
+                                ),
                         )
                     } else {
                         None
@@ -497,6 +711,7 @@ impl Render for AcpThreadHistory {
             .on_action(cx.listener(Self::select_first))
             .on_action(cx.listener(Self::select_last))
             .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
             .on_action(cx.listener(Self::remove_selected_thread))
             .on_action(cx.listener(|this, _: &RemoveHistory, window, cx| {
                 this.remove_history(window, cx);
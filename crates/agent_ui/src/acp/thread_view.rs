@@ -1,7 +1,7 @@
 use acp_thread::{
     AcpThread, AcpThreadEvent, AgentThreadEntry, AssistantMessage, AssistantMessageChunk,
-    AuthRequired, LoadError, MentionUri, RetryStatus, ThreadStatus, ToolCall, ToolCallContent,
-    ToolCallStatus, UserMessageId,
+    AuthRequired, InputRequest, InputResponse, LoadError, MentionUri, RetryStatus, ThreadStatus,
+    ToolCall, ToolCallContent, ToolCallStatus, UserMessageId,
 };
 use acp_thread::{AgentConnection, Plan};
 use action_log::{ActionLog, ActionLogTelemetry};
@@ -61,15 +61,17 @@ use super::entry_view_state::EntryViewState;
 use crate::acp::AcpModelSelectorPopover;
 use crate::acp::ModeSelector;
 use crate::acp::entry_view_state::{EntryViewEvent, ViewEvent};
+use crate::acp::fork_comparison::ForkComparison;
 use crate::acp::message_editor::{MessageEditor, MessageEditorEvent};
 use crate::agent_diff::AgentDiff;
 use crate::profile_selector::{ProfileProvider, ProfileSelector};
 
 use crate::ui::{AgentNotification, AgentNotificationEvent, BurnModeTooltip, UsageCallout};
 use crate::{
-    AgentDiffPane, AgentPanel, AllowAlways, AllowOnce, ContinueThread, ContinueWithBurnMode,
-    CycleFavoriteModels, CycleModeSelector, ExpandMessageEditor, Follow, KeepAll, NewThread,
-    OpenAgentDiff, OpenHistory, RejectAll, RejectOnce, ToggleBurnMode, ToggleProfileSelector,
+    AgentDiffPane, AgentPanel, AllowAlways, AllowOnce, CompactThread, ContinueThread,
+    ContinueWithBurnMode, CycleFavoriteModels, CycleModeSelector, ExpandMessageEditor, Follow,
+    KeepAll, NewThread, OpenAgentDiff, OpenHistory, RejectAll, RejectOnce, ToggleBurnMode,
+    ToggleProfileSelector, UndoThreadCompaction,
 };
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -337,6 +339,7 @@ pub struct AcpThreadView {
     expanded_thinking_blocks: HashSet<(usize, usize)>,
     edits_expanded: bool,
     plan_expanded: bool,
+    editing_plan_entry: Option<(usize, Entity<Editor>)>,
     editor_expanded: bool,
     should_be_following: bool,
     editing_message: Option<usize>,
@@ -504,6 +507,7 @@ impl AcpThreadView {
             editing_message: None,
             edits_expanded: false,
             plan_expanded: false,
+            editing_plan_entry: None,
             prompt_capabilities,
             available_commands,
             editor_expanded: false,
@@ -723,6 +727,21 @@ impl AcpThreadView {
                             cx.observe(&action_log, |_, _, cx| cx.notify()),
                         ];
 
+                        let native_thread = thread
+                            .read(cx)
+                            .connection()
+                            .clone()
+                            .downcast::<agent::NativeAgentConnection>()
+                            .and_then(|connection| {
+                                connection.thread(thread.read(cx).session_id(), cx)
+                            });
+                        if let Some(native_thread) = native_thread {
+                            subscriptions.push(cx.subscribe(
+                                &native_thread,
+                                |_, _, _: &agent::CompactionUpdated, cx| cx.notify(),
+                            ));
+                        }
+
                         let title_editor =
                             if thread.update(cx, |thread, cx| thread.can_set_title(cx)) {
                                 let editor = cx.new(|cx| {
@@ -1525,6 +1544,9 @@ impl AcpThreadView {
             AcpThreadEvent::ToolAuthorizationRequired => {
                 self.notify_with_sound("Waiting for tool confirmation", IconName::Info, window, cx);
             }
+            AcpThreadEvent::ToolInputRequired => {
+                self.notify_with_sound("Waiting for your input", IconName::Info, window, cx);
+            }
             AcpThreadEvent::Retry(retry) => {
                 self.thread_retry_status = Some(retry.clone());
             }
@@ -2020,6 +2042,157 @@ impl AcpThreadView {
             .detach_and_log_err(cx);
     }
 
+    /// Opens a new thread with the conversation up to the end of the turn the entry is in.
+    fn fork_thread(&mut self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(thread), Some(connection)) = (self.thread(), self.as_native_connection(cx))
+        else {
+            return;
+        };
+        let thread = thread.read(cx);
+        let session_id = thread.session_id().clone();
+        let next_message_id = thread
+            .entries()
+            .iter()
+            .skip(entry_ix + 1)
+            .find_map(|entry| entry.user_message())
+            .and_then(|message| message.id.clone());
+        let fork = connection.fork_thread(&session_id, next_message_id, cx);
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let thread = fork.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.load_agent_thread(thread, window, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens the changes this forked thread made next to the ones the original thread made.
+    fn compare_with_original(
+        &self,
+        workspace: Entity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let Some(thread) = self.as_native_thread(cx) else {
+            return Task::ready(Ok(()));
+        };
+        let thread = thread.read(cx);
+        let title = thread.title();
+        let Some(origin) = thread.fork_origin().cloned() else {
+            return Task::ready(Ok(()));
+        };
+        ForkComparison::open(title, origin, workspace, window, cx)
+    }
+
+    /// Changes the plan of a native thread on the user's behalf, which the model is told about.
+    fn edit_plan(&mut self, edit: impl FnOnce(&mut Vec<acp::PlanEntry>), cx: &mut Context<Self>) {
+        let (Some(thread), Some(native_thread)) =
+            (self.thread().cloned(), self.as_native_thread(cx))
+        else {
+            return;
+        };
+        let mut entries = native_thread.read(cx).plan().to_vec();
+        edit(&mut entries);
+        thread.update(cx, |thread, cx| {
+            thread.update_plan(acp::Plan::new(entries.clone()), cx)
+        });
+        native_thread.update(cx, |thread, cx| thread.edit_plan(entries, cx));
+    }
+
+    fn cycle_plan_entry_status(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.edit_plan(
+            |entries| {
+                if let Some(entry) = entries.get_mut(index) {
+                    entry.status = match entry.status {
+                        acp::PlanEntryStatus::Pending => acp::PlanEntryStatus::InProgress,
+                        acp::PlanEntryStatus::InProgress => acp::PlanEntryStatus::Completed,
+                        _ => acp::PlanEntryStatus::Pending,
+                    };
+                }
+            },
+            cx,
+        );
+    }
+
+    fn move_plan_entry(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        self.edit_plan(
+            |entries| {
+                if from < entries.len() && to < entries.len() {
+                    let entry = entries.remove(from);
+                    entries.insert(to, entry);
+                }
+            },
+            cx,
+        );
+    }
+
+    fn remove_plan_entry(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.edit_plan(
+            |entries| {
+                if index < entries.len() {
+                    entries.remove(index);
+                }
+            },
+            cx,
+        );
+    }
+
+    fn start_editing_plan_entry(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(native_thread) = self.as_native_thread(cx) else {
+            return;
+        };
+        let Some(content) = native_thread
+            .read(cx)
+            .plan()
+            .get(index)
+            .map(|entry| entry.content.clone())
+        else {
+            return;
+        };
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(content, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window, cx);
+        self.editing_plan_entry = Some((index, editor));
+        cx.notify();
+    }
+
+    fn save_plan_entry(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((index, editor)) = self.editing_plan_entry.take() else {
+            return;
+        };
+        let content = editor.read(cx).text(cx).trim().to_string();
+        if !content.is_empty() {
+            self.edit_plan(
+                |entries| {
+                    if let Some(entry) = entries.get_mut(index) {
+                        entry.content = content;
+                    }
+                },
+                cx,
+            );
+        }
+        self.focus_handle(cx).focus(window, cx);
+        cx.notify();
+    }
+
+    fn cancel_editing_plan_entry(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_plan_entry.take().is_some() {
+            self.focus_handle(cx).focus(window, cx);
+            cx.notify();
+        }
+    }
+
     fn render_entry(
         &self,
         entry_ix: usize,
@@ -2329,6 +2502,7 @@ impl AcpThreadView {
             matches!(
                 tool_call.status,
                 ToolCallStatus::WaitingForConfirmation { .. }
+                    | ToolCallStatus::WaitingForInput { .. }
             )
         } else {
             false
@@ -2435,11 +2609,47 @@ impl AcpThreadView {
                             }
                         });
 
+                    let fork_thread =
+                        entity.read(cx).as_native_connection(cx).is_some().then(|| {
+                            ContextMenuEntry::new("Fork Thread from Here").handler({
+                                let entity = entity.clone();
+                                move |window, cx| {
+                                    entity.update(cx, |this, cx| {
+                                        this.fork_thread(entry_ix, window, cx);
+                                    });
+                                }
+                            })
+                        });
+
+                    let compare_with_original = entity
+                        .read(cx)
+                        .as_native_thread(cx)
+                        .is_some_and(|thread| thread.read(cx).fork_origin().is_some())
+                        .then(|| {
+                            ContextMenuEntry::new("Compare with Original").handler({
+                                let entity = entity.clone();
+                                let workspace = workspace.clone();
+                                move |window, cx| {
+                                    if let Some(workspace) = workspace.upgrade() {
+                                        entity
+                                            .update(cx, |this, cx| {
+                                                this.compare_with_original(workspace, window, cx)
+                                            })
+                                            .detach_and_log_err(cx);
+                                    }
+                                }
+                            })
+                        });
+
                     menu.when_some(focus, |menu, focus| menu.context(focus))
                         .action("Copy", Box::new(markdown::CopyAsMarkdown))
                         .separator()
                         .item(scroll_item)
                         .item(open_thread_as_markdown)
+                        .when_some(fork_thread, |menu, fork_thread| menu.item(fork_thread))
+                        .when_some(compare_with_original, |menu, compare_with_original| {
+                            menu.item(compare_with_original)
+                        })
                 })
             })
             .into_any_element()
@@ -2578,7 +2788,7 @@ impl AcpThreadView {
 
         let needs_confirmation = matches!(
             tool_call.status,
-            ToolCallStatus::WaitingForConfirmation { .. }
+            ToolCallStatus::WaitingForConfirmation { .. } | ToolCallStatus::WaitingForInput { .. }
         );
         let is_terminal_tool = matches!(tool_call.kind, acp::ToolKind::Execute);
         let is_edit =
@@ -2624,6 +2834,9 @@ impl AcpThreadView {
                             cx,
                         ))
                         .into_any(),
+                    ToolCallStatus::WaitingForInput { request, .. } => self
+                        .render_input_request(entry_ix, request, tool_call.id.clone(), cx)
+                        .into_any(),
                     ToolCallStatus::Pending | ToolCallStatus::InProgress
                         if is_edit
                             && tool_call.content.is_empty()
@@ -2786,9 +2999,73 @@ impl AcpThreadView {
                     )
                 }
             })
+            .when(tool_call.locations.len() > 1, |this| {
+                this.child(self.render_tool_call_locations(entry_ix, tool_call, cx))
+            })
             .children(tool_output_display)
     }
 
+    fn render_tool_call_locations(
+        &self,
+        entry_ix: usize,
+        tool_call: &ToolCall,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let path_style = project.path_style(cx);
+
+        v_flex()
+            .id(("tool-call-locations", entry_ix))
+            .mt_1()
+            .ml(rems(0.4))
+            .px_3p5()
+            .max_h_40()
+            .overflow_y_scroll()
+            .border_l_1()
+            .border_color(self.tool_card_border_color(cx))
+            .children(
+                tool_call
+                    .locations
+                    .iter()
+                    .enumerate()
+                    .map(|(location_ix, location)| {
+                        let path = project
+                            .find_project_path(&location.path, cx)
+                            .map(|project_path| project_path.path.display(path_style).to_string())
+                            .unwrap_or_else(|| location.path.to_string_lossy().to_string());
+                        let label = match location.line {
+                            Some(line) => format!("{path}:{}", line + 1),
+                            None => path,
+                        };
+                        let file_icon = FileIcons::get_icon(&location.path, cx)
+                            .map(Icon::from_path)
+                            .unwrap_or_else(|| Icon::new(IconName::File))
+                            .color(Color::Muted)
+                            .size(IconSize::XSmall);
+
+                        h_flex()
+                            .id(("tool-call-location", location_ix))
+                            .gap_1()
+                            .px_1()
+                            .rounded_xs()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(cx.theme().colors().element_hover))
+                            .child(file_icon)
+                            .child(
+                                Label::new(label)
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted)
+                                    .buffer_font(cx)
+                                    .truncate(),
+                            )
+                            .tooltip(Tooltip::text("Go to Location"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.open_tool_call_location(entry_ix, location_ix, window, cx);
+                            }))
+                    }),
+            )
+    }
+
     fn render_tool_call_label(
         &self,
         entry_ix: usize,
@@ -3123,6 +3400,83 @@ impl AcpThreadView {
             }))
     }
 
+    fn render_input_request(
+        &self,
+        entry_ix: usize,
+        request: &Entity<InputRequest>,
+        tool_call_id: acp::ToolCallId,
+        cx: &Context<Self>,
+    ) -> Div {
+        let form = self
+            .entry_view_state
+            .read(cx)
+            .entry(entry_ix)
+            .and_then(|entry| entry.input_request_form(request));
+
+        v_flex().w_full().children(form.clone()).child(
+            h_flex()
+                .p_1()
+                .gap_0p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(self.tool_card_border_color(cx))
+                .child(
+                    Button::new(("decline-input", entry_ix), "Decline")
+                        .icon(IconName::Close)
+                        .icon_color(Color::Error)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::XSmall)
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener({
+                            let tool_call_id = tool_call_id.clone();
+                            move |this, _, _window, cx| {
+                                this.respond_to_tool_call_input(
+                                    tool_call_id.clone(),
+                                    InputResponse::Decline,
+                                    cx,
+                                );
+                            }
+                        })),
+                )
+                .child(
+                    Button::new(("submit-input", entry_ix), "Submit")
+                        .icon(IconName::Check)
+                        .icon_color(Color::Success)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::XSmall)
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            let Some(values) = form
+                                .as_ref()
+                                .and_then(|form| form.update(cx, |form, cx| form.submit(cx)))
+                            else {
+                                return;
+                            };
+                            this.respond_to_tool_call_input(
+                                tool_call_id.clone(),
+                                InputResponse::Accept(values),
+                                cx,
+                            );
+                        })),
+                ),
+        )
+    }
+
+    fn respond_to_tool_call_input(
+        &mut self,
+        tool_call_id: acp::ToolCallId,
+        response: InputResponse,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.thread() else {
+            return;
+        };
+        thread.update(cx, |thread, cx| {
+            thread.respond_to_tool_call_input(tool_call_id, response, cx);
+        });
+        cx.notify();
+    }
+
     fn render_diff_loading(&self, cx: &Context<Self>) -> AnyElement {
         let bar = |n: u64, width_class: &str| {
             let bg_color = cx.theme().colors().element_active;
@@ -4277,60 +4631,148 @@ impl AcpThreadView {
         window: &mut Window,
         cx: &Context<Self>,
     ) -> impl IntoElement {
+        // Only the native agent keeps its plan in the thread, so only its plan can be edited.
+        let is_editable = self.as_native_connection(cx).is_some();
+        let entry_count = plan.entries.len();
+
         v_flex()
             .id("plan_items_list")
             .max_h_40()
             .overflow_y_scroll()
             .children(plan.entries.iter().enumerate().flat_map(|(index, entry)| {
+                let status_icon = match entry.status {
+                    acp::PlanEntryStatus::InProgress => Icon::new(IconName::TodoProgress)
+                        .size(IconSize::Small)
+                        .color(Color::Accent)
+                        .with_rotate_animation(2)
+                        .into_any_element(),
+                    acp::PlanEntryStatus::Completed => Icon::new(IconName::TodoComplete)
+                        .size(IconSize::Small)
+                        .color(Color::Success)
+                        .into_any_element(),
+                    acp::PlanEntryStatus::Pending | _ => Icon::new(IconName::TodoPending)
+                        .size(IconSize::Small)
+                        .color(Color::Muted)
+                        .into_any_element(),
+                };
+                let editor = self
+                    .editing_plan_entry
+                    .as_ref()
+                    .filter(|(editing_index, _)| *editing_index == index)
+                    .map(|(_, editor)| editor.clone());
+
                 let element = h_flex()
+                    .group("plan-entry")
                     .py_1()
                     .px_2()
                     .gap_2()
                     .justify_between()
                     .bg(cx.theme().colors().editor_background)
-                    .when(index < plan.entries.len() - 1, |parent| {
+                    .when(index < entry_count - 1, |parent| {
                         parent.border_color(cx.theme().colors().border).border_b_1()
                     })
                     .child(
                         h_flex()
                             .id(("plan_entry", index))
+                            .flex_1()
                             .gap_1p5()
                             .max_w_full()
                             .overflow_x_scroll()
                             .text_xs()
                             .text_color(cx.theme().colors().text_muted)
-                            .child(match entry.status {
-                                acp::PlanEntryStatus::InProgress => {
-                                    Icon::new(IconName::TodoProgress)
-                                        .size(IconSize::Small)
-                                        .color(Color::Accent)
-                                        .with_rotate_animation(2)
-                                        .into_any_element()
-                                }
-                                acp::PlanEntryStatus::Completed => {
-                                    Icon::new(IconName::TodoComplete)
-                                        .size(IconSize::Small)
-                                        .color(Color::Success)
-                                        .into_any_element()
-                                }
-                                acp::PlanEntryStatus::Pending | _ => {
-                                    Icon::new(IconName::TodoPending)
-                                        .size(IconSize::Small)
-                                        .color(Color::Muted)
-                                        .into_any_element()
+                            .map(|this| {
+                                if is_editable {
+                                    this.child(
+                                        div()
+                                            .id(("plan_entry_status", index))
+                                            .cursor_pointer()
+                                            .child(status_icon)
+                                            .tooltip(Tooltip::text("Change Status"))
+                                            .on_click(cx.listener(move |this, _, _, cx| {
+                                                this.cycle_plan_entry_status(index, cx);
+                                            })),
+                                    )
+                                } else {
+                                    this.child(status_icon)
                                 }
                             })
-                            .child(MarkdownElement::new(
-                                entry.content.clone(),
-                                plan_label_markdown_style(&entry.status, window, cx),
-                            )),
-                    );
+                            .map(|this| match editor {
+                                Some(editor) => this.child(
+                                    div()
+                                        .key_context("AgentPlanEntryEditor")
+                                        .on_action(cx.listener(
+                                            |this, _: &menu::Confirm, window, cx| {
+                                                this.save_plan_entry(window, cx);
+                                            },
+                                        ))
+                                        .on_action(cx.listener(
+                                            |this, _: &menu::Cancel, window, cx| {
+                                                this.cancel_editing_plan_entry(window, cx);
+                                            },
+                                        ))
+                                        .w_full()
+                                        .child(editor),
+                                ),
+                                None => this.child(MarkdownElement::new(
+                                    entry.content.clone(),
+                                    plan_label_markdown_style(&entry.status, window, cx),
+                                )),
+                            }),
+                    )
+                    .when(is_editable, |this| {
+                        this.child(self.render_plan_entry_controls(index, entry_count, cx))
+                    });
 
                 Some(element)
             }))
             .into_any_element()
     }
 
+    fn render_plan_entry_controls(
+        &self,
+        index: usize,
+        entry_count: usize,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        h_flex()
+            .gap_0p5()
+            .visible_on_hover("plan-entry")
+            .child(
+                IconButton::new(("plan_entry_move_up", index), IconName::ArrowUp)
+                    .icon_size(IconSize::XSmall)
+                    .disabled(index == 0)
+                    .tooltip(Tooltip::text("Move Up"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.move_plan_entry(index, index - 1, cx);
+                    })),
+            )
+            .child(
+                IconButton::new(("plan_entry_move_down", index), IconName::ArrowDown)
+                    .icon_size(IconSize::XSmall)
+                    .disabled(index + 1 == entry_count)
+                    .tooltip(Tooltip::text("Move Down"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.move_plan_entry(index, index + 1, cx);
+                    })),
+            )
+            .child(
+                IconButton::new(("plan_entry_edit", index), IconName::Pencil)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(Tooltip::text("Edit"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.start_editing_plan_entry(index, window, cx);
+                    })),
+            )
+            .child(
+                IconButton::new(("plan_entry_remove", index), IconName::Trash)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(Tooltip::text("Remove"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.remove_plan_entry(index, cx);
+                    })),
+            )
+    }
+
     fn render_edits_summary(
         &self,
         changed_buffers: &BTreeMap<Entity<Buffer>, Entity<BufferDiff>>,
@@ -4804,7 +5246,46 @@ impl AcpThreadView {
                         .size(LabelSize::Small)
                         .color(Color::Custom(cx.theme().colors().text_muted.opacity(0.5))),
                 )
-                .child(Label::new(max).size(LabelSize::Small).color(Color::Muted)),
+                .child(Label::new(max).size(LabelSize::Small).color(Color::Muted))
+                .children(self.render_compaction_indicator(cx)),
+        )
+    }
+
+    fn render_compaction_indicator(&self, cx: &App) -> Option<AnyElement> {
+        let thread = self.as_native_thread(cx)?;
+        let thread = thread.read(cx);
+        if thread.is_compacting() {
+            return Some(
+                Label::new("Compacting…")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .with_animation(
+                        "compacting-label",
+                        Animation::new(Duration::from_secs(2))
+                            .repeat()
+                            .with_easing(pulsating_between(0.3, 0.8)),
+                        |label, delta| label.alpha(delta),
+                    )
+                    .into_any_element(),
+            );
+        }
+        thread.compaction()?;
+        Some(
+            IconButton::new("undo-thread-compaction", IconName::ListCollapse)
+                .icon_size(IconSize::XSmall)
+                .icon_color(Color::Muted)
+                .tooltip(|_window, cx| {
+                    Tooltip::with_meta(
+                        "Older Turns Summarized",
+                        Some(&UndoThreadCompaction),
+                        "Click to send them verbatim again",
+                        cx,
+                    )
+                })
+                .on_click(|_, window, cx| {
+                    window.dispatch_action(UndoThreadCompaction.boxed_clone(), cx)
+                })
+                .into_any_element(),
         )
     }
 
@@ -4830,6 +5311,32 @@ impl AcpThreadView {
         });
     }
 
+    fn compact_thread(&mut self, _: &CompactThread, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(thread) = self.as_native_thread(cx) else {
+            return;
+        };
+        let compact = thread.update(cx, |thread, cx| thread.compact(cx));
+        cx.spawn(async move |this, cx| {
+            if let Err(error) = compact.await {
+                this.update(cx, |this, cx| this.handle_thread_error(error, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn undo_thread_compaction(
+        &mut self,
+        _: &UndoThreadCompaction,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.as_native_thread(cx) else {
+            return;
+        };
+        thread.update(cx, |thread, cx| thread.undo_compaction(cx));
+    }
+
     fn keep_all(&mut self, _: &KeepAll, _window: &mut Window, cx: &mut Context<Self>) {
         let Some(thread) = self.thread() else {
             return;
@@ -5160,6 +5667,8 @@ impl AcpThreadView {
                                     id,
                                     title: name.into(),
                                     updated_at: Default::default(),
+                                    tags: Vec::new(),
+                                    pinned: false,
                                 },
                                 window,
                                 cx,
@@ -5190,6 +5699,11 @@ impl AcpThreadView {
                 MentionUri::Fetch { url } => {
                     cx.open_url(url.as_str());
                 }
+                MentionUri::ContextServerResource { uri, .. } => {
+                    if matches!(uri.scheme(), "http" | "https") {
+                        cx.open_url(uri.as_str());
+                    }
+                }
             })
         } else {
             cx.open_url(&url);
@@ -5739,10 +6253,19 @@ impl AcpThreadView {
                     .is_some_and(|model| model.supports_burn_mode())
         });
 
-        let description = if burn_mode_available {
-            "To continue, start a new thread from a summary or turn Burn Mode on."
-        } else {
-            "To continue, start a new thread from a summary."
+        let can_compact = self
+            .as_native_thread(cx)
+            .is_some_and(|thread| thread.read(cx).can_compact());
+
+        let description = match (can_compact, burn_mode_available) {
+            (true, true) => {
+                "To continue, compact the older turns, start a new thread from a summary or turn Burn Mode on."
+            }
+            (true, false) => {
+                "To continue, compact the older turns or start a new thread from a summary."
+            }
+            (false, true) => "To continue, start a new thread from a summary or turn Burn Mode on.",
+            (false, false) => "To continue, start a new thread from a summary.",
         };
 
         Some(
@@ -5754,6 +6277,15 @@ impl AcpThreadView {
                 .actions_slot(
                     h_flex()
                         .gap_0p5()
+                        .when(can_compact, |this| {
+                            this.child(
+                                Button::new("compact-thread", "Compact")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.compact_thread(&CompactThread, window, cx);
+                                    })),
+                            )
+                        })
                         .child(
                             Button::new("start-new-thread", "Start New Thread")
                                 .label_size(LabelSize::Small)
@@ -6355,6 +6887,8 @@ impl Render for AcpThreadView {
             .size_full()
             .key_context("AcpThread")
             .on_action(cx.listener(Self::toggle_burn_mode))
+            .on_action(cx.listener(Self::compact_thread))
+            .on_action(cx.listener(Self::undo_thread_compaction))
             .on_action(cx.listener(Self::keep_all))
             .on_action(cx.listener(Self::reject_all))
             .on_action(cx.listener(Self::allow_always))
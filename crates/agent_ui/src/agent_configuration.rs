@@ -40,7 +40,7 @@ use ui::{
     WithScrollbar, prelude::*,
 };
 use util::ResultExt as _;
-use workspace::{Workspace, create_and_open_local_file};
+use workspace::{Workspace, create_and_open_local_file, notifications::DetachAndPromptErr as _};
 use zed_actions::{ExtensionCategoryFilter, OpenBrowser};
 
 pub(crate) use configure_context_server_modal::ConfigureContextServerModal;
@@ -99,6 +99,8 @@ impl AgentConfiguration {
 
         cx.subscribe(&context_server_store, |_, _, _, cx| cx.notify())
             .detach();
+        cx.observe(&context_server_store, |_, _, cx| cx.notify())
+            .detach();
 
         let mut this = Self {
             fs,
@@ -679,6 +681,14 @@ impl AgentConfiguration {
             .configuration_for_server(&context_server_id);
 
         let is_running = matches!(server_status, ContextServerStatus::Running);
+        let requires_authentication = matches!(server_status, ContextServerStatus::AuthRequired);
+        let (is_authenticating, is_signed_in) = {
+            let store = self.context_server_store.read(cx);
+            (
+                store.is_authenticating(&context_server_id),
+                store.is_signed_in(&context_server_id),
+            )
+        };
         let item_id = SharedString::from(context_server_id.0.clone());
         // Servers without a configuration can only be provided by extensions.
         let provided_by_extension = server_configuration.as_ref().is_none_or(|config| {
@@ -736,6 +746,10 @@ impl AgentConfiguration {
                 Indicator::dot().color(Color::Muted).into_any_element(),
                 "Server is stopped.",
             ),
+            ContextServerStatus::AuthRequired => (
+                Indicator::dot().color(Color::Warning).into_any_element(),
+                "Server requires authentication.",
+            ),
         };
         let is_remote = server_configuration
             .as_ref()
@@ -755,6 +769,7 @@ impl AgentConfiguration {
                 let language_registry = self.language_registry.clone();
                 let workspace = self.workspace.clone();
                 let context_server_registry = self.context_server_registry.clone();
+                let context_server_store = self.context_server_store.clone();
 
                 move |window, cx| {
                     Some(ContextMenu::build(window, cx, |menu, _window, _cx| {
@@ -801,6 +816,22 @@ impl AgentConfiguration {
                                 .ok();
                             }
                         }))
+                        .when(is_signed_in, |this| this.entry("Sign Out", None, {
+                            let context_server_id = context_server_id.clone();
+                            let context_server_store = context_server_store.clone();
+                            move |window, cx| {
+                                context_server_store
+                                    .update(cx, |store, cx| {
+                                        store.sign_out_server(&context_server_id, cx)
+                                    })
+                                    .detach_and_prompt_err(
+                                        "Failed to sign out of the MCP server",
+                                        window,
+                                        cx,
+                                        |error, _, _| Some(error.to_string()),
+                                    );
+                            }
+                        }))
                         .separator()
                         .entry("Uninstall", None, {
                             let fs = fs.clone();
@@ -856,6 +887,65 @@ impl AgentConfiguration {
                 }
             });
 
+        let authentication_prompt = requires_authentication.then(|| {
+            let context_server_store = self.context_server_store.clone();
+            let context_server_id = context_server_id.clone();
+            h_flex()
+                .gap_2()
+                .pr_4()
+                .justify_between()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Icon::new(IconName::LockOutlined)
+                                .size(IconSize::XSmall)
+                                .color(Color::Warning),
+                        )
+                        .child(
+                            Label::new(if is_authenticating {
+                                "Waiting for you to sign in in your browser…"
+                            } else {
+                                "Sign in to use this server."
+                            })
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                        ),
+                )
+                .map(|this| {
+                    if is_authenticating {
+                        this.child(
+                            Button::new("cancel-context-server-authentication", "Cancel")
+                                .style(ButtonStyle::Outlined)
+                                .label_size(LabelSize::Small)
+                                .on_click(move |_, _, cx| {
+                                    context_server_store.update(cx, |store, cx| {
+                                        store.cancel_authentication(&context_server_id, cx)
+                                    });
+                                }),
+                        )
+                    } else {
+                        this.child(
+                            Button::new("authenticate-context-server", "Authenticate")
+                                .style(ButtonStyle::Outlined)
+                                .label_size(LabelSize::Small)
+                                .on_click(move |_, window, cx| {
+                                    context_server_store
+                                        .update(cx, |store, cx| {
+                                            store.authenticate_server(&context_server_id, cx)
+                                        })
+                                        .detach_and_prompt_err(
+                                            "Failed to authenticate with the MCP server",
+                                            window,
+                                            cx,
+                                            |error, _, _| Some(error.to_string()),
+                                        );
+                                }),
+                        )
+                    }
+                })
+        });
+
         v_flex()
             .id(item_id.clone())
             .child(
@@ -955,6 +1045,7 @@ impl AgentConfiguration {
                         ),
                     ),
             )
+            .children(authentication_prompt)
             .map(|parent| {
                 if let Some(error) = error {
                     return parent.child(
@@ -877,7 +877,9 @@ fn wait_for_context_server(
     let subscription = cx.subscribe(context_server_store, move |_, event, _cx| match event {
         project::context_server_store::Event::ServerStatusChanged { server_id, status } => {
             match status {
-                ContextServerStatus::Running => {
+                // Servers that require the user to sign in are configured, and can be signed in
+                // to from the server list.
+                ContextServerStatus::Running | ContextServerStatus::AuthRequired => {
                     if server_id == &context_server_id
                         && let Some(tx) = tx.lock().unwrap().take()
                     {
@@ -5,7 +5,7 @@ use agent_settings::{AgentProfileId, AgentProfileSettings};
 use fs::Fs;
 use gpui::{App, Context, DismissEvent, Entity, EventEmitter, Focusable, Task, WeakEntity, Window};
 use picker::{Picker, PickerDelegate};
-use settings::{AgentProfileContent, ContextServerPresetContent, update_settings_file};
+use settings::update_settings_file;
 use ui::{ListItem, ListItemSpacing, prelude::*};
 use util::ResultExt as _;
 
@@ -296,26 +296,7 @@ impl PickerDelegate for ToolPickerDelegate {
                     .get_or_insert_default();
                 let profile = profiles
                     .entry(profile_id.0)
-                    .or_insert_with(|| AgentProfileContent {
-                        name: default_profile.name.into(),
-                        tools: default_profile.tools,
-                        enable_all_context_servers: Some(
-                            default_profile.enable_all_context_servers,
-                        ),
-                        context_servers: default_profile
-                            .context_servers
-                            .into_iter()
-                            .map(|(server_id, preset)| {
-                                (
-                                    server_id,
-                                    ContextServerPresetContent {
-                                        tools: preset.tools,
-                                    },
-                                )
-                            })
-                            .collect(),
-                        default_model: default_profile.default_model.clone(),
-                    });
+                    .or_insert_with(|| default_profile.to_content());
 
                 if let Some(server_id) = server_id {
                     let preset = profile.context_servers.entry(server_id).or_default();
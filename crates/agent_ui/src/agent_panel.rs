@@ -1,7 +1,9 @@
 use std::{ops::Range, path::Path, rc::Rc, sync::Arc, time::Duration};
 
 use acp_thread::AcpThread;
-use agent::{ContextServerRegistry, DbThreadMetadata, HistoryEntry, HistoryStore};
+use agent::{
+    ContextServerRegistry, DbThreadMetadata, HistoryEntry, HistoryStore, ThreadExportFormat,
+};
 use agent_servers::AgentServer;
 use db::kvp::{Dismissable, KEY_VALUE_STORE};
 use project::{
@@ -18,9 +20,10 @@ use zed_actions::agent::{OpenClaudeCodeOnboardingModal, ReauthenticateAgent};
 use crate::ManageProfiles;
 use crate::ui::{AcpOnboardingModal, ClaudeCodeOnboardingModal};
 use crate::{
-    AddContextServer, AgentDiffPane, Follow, InlineAssistant, NewTextThread, NewThread,
-    OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell,
-    ToggleNavigationMenu, ToggleNewThreadMenu, ToggleOptionsMenu,
+    AddContextServer, AgentDiffPane, ExportActiveThreadAsJson, ExportActiveThreadAsMarkdown,
+    Follow, ImportThreads, InlineAssistant, NewTextThread, NewThread, OpenActiveThreadAsMarkdown,
+    OpenAgentDiff, OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell, ToggleNavigationMenu,
+    ToggleNewThreadMenu, ToggleOptionsMenu,
     acp::AcpThreadView,
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
     slash_command::SlashCommandCompletionProvider,
@@ -34,7 +37,7 @@ use crate::{
 use crate::{ExternalAgent, NewExternalAgentThread, NewNativeAgentThreadFromSummary};
 use agent_settings::AgentSettings;
 use ai_onboarding::AgentPanelOnboarding;
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use assistant_slash_command::SlashCommandWorkingSet;
 use assistant_text_thread::{TextThread, TextThreadEvent, TextThreadSummary};
 use client::{UserStore, zed_urls};
@@ -45,8 +48,8 @@ use extension_host::ExtensionStore;
 use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt, AnyElement, App, AsyncWindowContext, Corner, DismissEvent,
-    Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, KeyContext, Pixels, Subscription,
-    Task, UpdateGlobal, WeakEntity, prelude::*, pulsating_between,
+    Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, KeyContext, PathPromptOptions,
+    Pixels, Subscription, Task, UpdateGlobal, WeakEntity, prelude::*, pulsating_between,
 };
 use language::LanguageRegistry;
 use language_model::{ConfigurationError, LanguageModelRegistry};
@@ -64,6 +67,7 @@ use util::ResultExt as _;
 use workspace::{
     CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr as _,
 };
 use zed_actions::{
     DecreaseBufferFontSize, IncreaseBufferFontSize, ResetBufferFontSize,
@@ -1195,6 +1199,78 @@ impl AgentPanel {
         }
     }
 
+    fn export_active_thread(
+        &mut self,
+        format: ThreadExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self
+            .active_thread_view()
+            .and_then(|thread_view| thread_view.read(cx).thread())
+        else {
+            return;
+        };
+        let thread = thread.read(cx);
+        let session_id = thread.session_id().clone();
+        let file_name = format!(
+            "{}.{}",
+            thread.title().replace(['/', '\\', ':'], "-"),
+            format.extension()
+        );
+        let export = self
+            .history_store
+            .update(cx, |store, cx| store.export_thread(session_id, format, cx));
+        let path = cx.prompt_for_new_path(util::paths::home_dir(), Some(&file_name));
+        let fs = self.fs.clone();
+        cx.spawn_in(window, async move |_, _| {
+            let contents = export.await?;
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            fs.atomic_write(path, contents).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+    }
+
+    fn import_threads(&mut self, _: &ImportThreads, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: true,
+            prompt: Some("Import".into()),
+        });
+        let fs = self.fs.clone();
+        let history_store = self.history_store.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(paths) = paths.await?? else {
+                return Ok(());
+            };
+            let mut imported_thread = None;
+            for path in paths {
+                let json = fs.load_bytes(&path).await?;
+                let import = history_store.update(cx, |store, cx| store.import_thread(json, cx))?;
+                imported_thread = Some(
+                    import
+                        .await
+                        .with_context(|| format!("failed to import {}", path.display()))?,
+                );
+            }
+            // Open the last imported thread, the others are in the history.
+            if let Some(thread) = imported_thread {
+                this.update_in(cx, |this, window, cx| {
+                    this.load_agent_thread(thread, window, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to import threads", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1804,6 +1880,10 @@ impl AgentPanel {
             }
             _ => false,
         };
+        // Only threads of Zed's agent are saved in the history, so only they can be exported.
+        let is_native_thread = thread_view
+            .as_ref()
+            .is_some_and(|thread_view| thread_view.read(cx).as_native_thread(cx).is_some());
 
         PopoverMenu::new("agent-options-menu")
             .trigger_with_tooltip(
@@ -1893,6 +1973,16 @@ impl AgentPanel {
                                             );
                                         }
                                     })
+                                    .when(is_native_thread, |menu| {
+                                        menu.action(
+                                            "Export as Markdown…",
+                                            Box::new(ExportActiveThreadAsMarkdown),
+                                        )
+                                        .action(
+                                            "Export as JSON…",
+                                            Box::new(ExportActiveThreadAsJson),
+                                        )
+                                    })
                                     .separator();
                             }
                         }
@@ -1910,6 +2000,7 @@ impl AgentPanel {
                             )
                             .action("Add Custom Server…", Box::new(AddContextServer))
                             .separator()
+                            .action("Import Threads…", Box::new(ImportThreads))
                             .action("Rules", Box::new(OpenRulesLibrary::default()))
                             .action("Profiles", Box::new(ManageProfiles::default()))
                             .action("Settings", Box::new(OpenSettings))
@@ -2612,6 +2703,17 @@ impl Render for AgentPanel {
                 this.open_configuration(window, cx);
             }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(
+                cx.listener(|this, _: &ExportActiveThreadAsMarkdown, window, cx| {
+                    this.export_active_thread(ThreadExportFormat::Markdown, window, cx);
+                }),
+            )
+            .on_action(
+                cx.listener(|this, _: &ExportActiveThreadAsJson, window, cx| {
+                    this.export_active_thread(ThreadExportFormat::Json, window, cx);
+                }),
+            )
+            .on_action(cx.listener(Self::import_threads))
             .on_action(cx.listener(Self::deploy_rules_library))
             .on_action(cx.listener(Self::go_back))
             .on_action(cx.listener(Self::toggle_navigation_menu))
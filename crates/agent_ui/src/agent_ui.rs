@@ -97,6 +97,12 @@ actions!(
         FocusRight,
         /// Opens the active thread as a markdown file.
         OpenActiveThreadAsMarkdown,
+        /// Exports the active thread to a Markdown file.
+        ExportActiveThreadAsMarkdown,
+        /// Exports the active thread to a JSON file, which can be imported into another install.
+        ExportActiveThreadAsJson,
+        /// Imports threads that were exported as JSON.
+        ImportThreads,
         /// Opens the agent diff view to review changes.
         OpenAgentDiff,
         /// Keeps the current suggestion or change.
@@ -125,6 +131,10 @@ actions!(
         ContinueWithBurnMode,
         /// Toggles burn mode for faster responses.
         ToggleBurnMode,
+        /// Summarizes the older turns of the thread to free up context window space.
+        CompactThread,
+        /// Sends the turns summarized by the last compaction verbatim again.
+        UndoThreadCompaction,
     ]
 );
 
@@ -477,6 +487,8 @@ mod tests {
             expand_terminal_card: true,
             use_modifier_to_send: true,
             message_editor_min_lines: 1,
+            auto_compact: true,
+            hooks: vec![],
         };
 
         cx.update(|cx| {
@@ -547,6 +547,7 @@ impl CodegenAlternative {
                 temperature,
                 messages,
                 thinking_allowed: false,
+                max_output_tokens: None,
             }
         }))
     }
@@ -626,6 +627,7 @@ impl CodegenAlternative {
                 temperature,
                 messages: vec![request_message],
                 thinking_allowed: false,
+                max_output_tokens: None,
             }
         }))
     }
@@ -7,6 +7,7 @@ use std::sync::atomic::AtomicBool;
 use acp_thread::MentionUri;
 use agent::{HistoryEntry, HistoryStore};
 use anyhow::Result;
+use context_server::{ContextServerId, protocol::ServerCapability};
 use editor::{
     CompletionProvider, Editor, ExcerptId, code_context_menus::COMPLETION_MENU_MAX_WIDTH,
 };
@@ -24,6 +25,7 @@ use prompt_store::{PromptStore, UserPromptId};
 use rope::Point;
 use text::{Anchor, ToPoint as _};
 use ui::prelude::*;
+use url::Url;
 use util::ResultExt as _;
 use util::paths::PathStyle;
 use util::rel_path::RelPath;
@@ -55,6 +57,7 @@ pub(crate) enum PromptContextType {
     Fetch,
     Thread,
     Rules,
+    Resource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -92,6 +95,7 @@ impl TryFrom<&str> for PromptContextType {
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
+            "resource" => Ok(Self::Resource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -105,6 +109,7 @@ impl PromptContextType {
             Self::Fetch => "fetch",
             Self::Thread => "thread",
             Self::Rules => "rule",
+            Self::Resource => "resource",
         }
     }
 
@@ -115,6 +120,7 @@ impl PromptContextType {
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
             Self::Rules => "Rules",
+            Self::Resource => "Resources",
         }
     }
 
@@ -125,6 +131,7 @@ impl PromptContextType {
             Self::Fetch => IconName::ToolWeb,
             Self::Thread => IconName::Thread,
             Self::Rules => IconName::Reader,
+            Self::Resource => IconName::Server,
         }
     }
 }
@@ -136,6 +143,7 @@ pub(crate) enum Match {
     RecentThread(HistoryEntry),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    Resource(ResourceContextEntry),
     Entry(EntryMatch),
 }
 
@@ -149,6 +157,7 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Rules(_) => 1.,
             Match::Fetch(_) => 1.,
+            Match::Resource(_) => 1.,
         }
     }
 }
@@ -164,6 +173,22 @@ pub struct RulesContextEntry {
     pub title: SharedString,
 }
 
+#[derive(Debug, Clone)]
+pub struct ResourceContextEntry {
+    pub server_id: ContextServerId,
+    pub name: SharedString,
+    pub description: Option<SharedString>,
+    pub kind: ResourceContextKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum ResourceContextKind {
+    /// A resource the server listed, or an expansion of one of its templates.
+    Resource(Url),
+    /// A template, which the user fills in after its literal prefix.
+    Template { prefix: String },
+}
+
 #[derive(Debug, Clone)]
 pub struct AvailableCommand {
     pub name: Arc<str>,
@@ -499,6 +524,66 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_resource(
+        resource: ResourceContextEntry,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        let documentation = resource
+            .description
+            .map(CompletionDocumentation::MultiLinePlainText);
+        match resource.kind {
+            ResourceContextKind::Template { prefix } => Completion {
+                replace_range: source_range,
+                new_text: format!("@{} {}", PromptContextType::Resource.keyword(), prefix),
+                label: CodeLabel::plain(resource.name.to_string(), None),
+                documentation,
+                insert_text_mode: None,
+                source: project::CompletionSource::Custom,
+                match_start: None,
+                snippet_deduplication_key: None,
+                icon_path: Some(PromptContextType::Resource.icon().path().into()),
+                // Keep the menu open while the user fills in the rest of the template.
+                confirm: Some(Arc::new(|_, _, _| true)),
+            },
+            ResourceContextKind::Resource(uri) => {
+                let mention_uri = MentionUri::ContextServerResource {
+                    server_id: resource.server_id.0.to_string(),
+                    uri,
+                    name: resource.name.to_string(),
+                };
+                let new_text = format!("{} ", mention_uri.as_link());
+                let new_text_len = new_text.len();
+                let icon_path = mention_uri.icon_path(cx);
+                Completion {
+                    replace_range: source_range.clone(),
+                    new_text,
+                    label: CodeLabel::plain(resource.name.to_string(), None),
+                    documentation,
+                    insert_text_mode: None,
+                    source: project::CompletionSource::Custom,
+                    match_start: None,
+                    snippet_deduplication_key: None,
+                    icon_path: Some(icon_path),
+                    confirm: Some(confirm_completion_callback(
+                        resource.name,
+                        source_range.start,
+                        new_text_len - 1,
+                        mention_uri,
+                        source,
+                        editor,
+                        mention_set,
+                        workspace,
+                    )),
+                }
+            }
+        }
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -671,6 +756,18 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
                 }
             }
 
+            Some(PromptContextType::Resource) => {
+                let search_resources_task =
+                    search_resources(query, cancellation_flag, &workspace, cx);
+                cx.background_spawn(async move {
+                    search_resources_task
+                        .await
+                        .into_iter()
+                        .map(Match::Resource)
+                        .collect()
+                })
+            }
+
             None if query.is_empty() => {
                 let mut matches = self.recent_context_picker_entries(&workspace, cx);
 
@@ -852,6 +949,27 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             entries.push(PromptContextEntry::Mode(PromptContextType::Fetch));
         }
 
+        let has_resources = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .context_server_store()
+            .read(cx)
+            .running_servers()
+            .iter()
+            .any(|server| {
+                server
+                    .client()
+                    .is_some_and(|client| client.capable(ServerCapability::Resources))
+            });
+        if has_resources
+            && self
+                .source
+                .supports_context(PromptContextType::Resource, cx)
+        {
+            entries.push(PromptContextEntry::Mode(PromptContextType::Resource));
+        }
+
         entries
     }
 }
@@ -1080,6 +1198,16 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                     cx,
                                 ),
 
+                                Match::Resource(resource) => Some(Self::completion_for_resource(
+                                    resource,
+                                    source_range.clone(),
+                                    source.clone(),
+                                    editor.clone(),
+                                    mention_set.clone(),
+                                    workspace.clone(),
+                                    cx,
+                                )),
+
                                 Match::Entry(EntryMatch { entry, .. }) => {
                                     Self::completion_for_entry(
                                         entry,
@@ -1595,6 +1723,102 @@ pub(crate) fn search_rules(
     })
 }
 
+/// Lists the resources and resource templates of the running context servers that match the
+/// query. Templates that the query is an expansion of are offered as that resource.
+pub(crate) fn search_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    workspace: &Entity<Workspace>,
+    cx: &mut App,
+) -> Task<Vec<ResourceContextEntry>> {
+    let context_server_store = workspace.read(cx).project().read(cx).context_server_store();
+    let lists = context_server_store.update(cx, |store, cx| {
+        store
+            .running_servers()
+            .into_iter()
+            .map(|server| {
+                let server_id = server.id();
+                (server_id.clone(), store.resources(&server_id, cx))
+            })
+            .collect::<Vec<_>>()
+    });
+    let executor = cx.background_executor().clone();
+    cx.spawn(async move |_| {
+        let mut entries = Vec::new();
+        for (server_id, lists) in lists {
+            let Some(lists) = lists.await.log_err() else {
+                continue;
+            };
+            entries.extend(lists.resources.iter().map(|resource| ResourceContextEntry {
+                server_id: server_id.clone(),
+                name: resource.name.clone().into(),
+                description: resource.description.clone().map(Into::into),
+                kind: ResourceContextKind::Resource(resource.uri.clone()),
+            }));
+            for template in &lists.templates {
+                let kind = if template.matches(&query)
+                    && let Ok(uri) = Url::parse(&query)
+                {
+                    ResourceContextKind::Resource(uri)
+                } else {
+                    ResourceContextKind::Template {
+                        prefix: template.prefix().to_string(),
+                    }
+                };
+                entries.push(ResourceContextEntry {
+                    server_id: server_id.clone(),
+                    name: match &kind {
+                        ResourceContextKind::Resource(uri) => uri.to_string().into(),
+                        ResourceContextKind::Template { .. } => template.name.clone().into(),
+                    },
+                    description: Some(
+                        template
+                            .description
+                            .as_ref()
+                            .unwrap_or(&template.uri_template)
+                            .clone()
+                            .into(),
+                    ),
+                    kind,
+                });
+            }
+        }
+
+        if query.is_empty() {
+            return entries;
+        }
+
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                let text = match &entry.kind {
+                    ResourceContextKind::Resource(uri) => format!("{} {}", entry.name, uri),
+                    ResourceContextKind::Template { prefix } => {
+                        format!("{} {}", entry.name, prefix)
+                    }
+                };
+                StringMatchCandidate::new(id, &text)
+            })
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidates,
+            &query,
+            false,
+            true,
+            100,
+            &cancellation_flag,
+            executor,
+        )
+        .await;
+
+        matches
+            .into_iter()
+            .map(|mat| entries[mat.candidate_id].clone())
+            .collect()
+    })
+}
+
 pub struct SymbolMatch {
     pub symbol: Symbol,
 }
@@ -5,6 +5,15 @@ use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
 use assistant_slash_commands::codeblock_fence_for_path;
 use collections::{HashMap, HashSet};
+use context_server::{
+    ContextServerId,
+    client::NotificationSubscription,
+    protocol::InitializedContextServerProtocol,
+    types::{
+        Notification as _, ResourceContentsType, ResourcesUpdatedParams,
+        notifications::ResourcesUpdated,
+    },
+};
 use editor::{
     Anchor, Editor, EditorSnapshot, ExcerptId, FoldPlaceholder, ToOffset,
     display_map::{Crease, CreaseId, CreaseMetadata, FoldId},
@@ -36,6 +45,7 @@ use std::{
 };
 use text::OffsetRangeExt;
 use ui::{ButtonLike, Disclosure, TintColor, Toggleable, prelude::*};
+use url::Url;
 use util::{ResultExt, debug_panic, rel_path::RelPath};
 use workspace::{Workspace, notifications::NotifyResultExt as _};
 
@@ -62,6 +72,16 @@ pub struct MentionSet {
     history_store: Entity<HistoryStore>,
     prompt_store: Option<Entity<PromptStore>>,
     mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
+    resource_subscriptions: HashMap<CreaseId, ResourceSubscription>,
+}
+
+/// Keeps a mentioned resource up to date while it's mentioned.
+struct ResourceSubscription {
+    server_id: ContextServerId,
+    uri: Url,
+    /// Whether the server was asked to send updates about the resource.
+    subscribed: bool,
+    _notification_subscription: NotificationSubscription,
 }
 
 impl MentionSet {
@@ -75,6 +95,7 @@ impl MentionSet {
             history_store,
             prompt_store,
             mentions: HashMap::default(),
+            resource_subscriptions: HashMap::default(),
         }
     }
 
@@ -105,10 +126,10 @@ impl MentionSet {
         })
     }
 
-    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot) {
+    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot, cx: &mut Context<Self>) {
         for (crease_id, crease) in snapshot.crease_snapshot.creases() {
             if !crease.range().start.is_valid(snapshot.buffer_snapshot()) {
-                self.mentions.remove(&crease_id);
+                self.remove_mention(&crease_id, cx);
             }
         }
     }
@@ -117,8 +138,9 @@ impl MentionSet {
         self.mentions.insert(crease_id, (uri, task));
     }
 
-    pub fn remove_mention(&mut self, crease_id: &CreaseId) {
+    pub fn remove_mention(&mut self, crease_id: &CreaseId, cx: &mut Context<Self>) {
         self.mentions.remove(crease_id);
+        self.unsubscribe_from_resource(crease_id, cx);
     }
 
     pub fn creases(&self) -> HashSet<CreaseId> {
@@ -129,11 +151,35 @@ impl MentionSet {
         self.mentions.values().map(|(uri, _)| uri.clone()).collect()
     }
 
-    pub fn set_mentions(&mut self, mentions: HashMap<CreaseId, (MentionUri, MentionTask)>) {
+    pub fn set_mentions(
+        &mut self,
+        mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
+        cx: &mut Context<Self>,
+    ) {
         self.mentions = mentions;
+        let removed_crease_ids = self
+            .resource_subscriptions
+            .keys()
+            .filter(|crease_id| !self.mentions.contains_key(crease_id))
+            .copied()
+            .collect::<Vec<_>>();
+        for crease_id in removed_crease_ids {
+            self.unsubscribe_from_resource(&crease_id, cx);
+        }
     }
 
-    pub fn clear(&mut self) -> impl Iterator<Item = (CreaseId, (MentionUri, MentionTask))> {
+    pub fn clear(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> impl Iterator<Item = (CreaseId, (MentionUri, MentionTask))> + use<'_> {
+        let crease_ids = self
+            .resource_subscriptions
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        for crease_id in crease_ids {
+            self.unsubscribe_from_resource(&crease_id, cx);
+        }
         self.mentions.drain()
     }
 
@@ -229,6 +275,21 @@ impl MentionSet {
                 ..
             } => self.confirm_mention_for_symbol(abs_path, line_range, cx),
             MentionUri::Rule { id, .. } => self.confirm_mention_for_rule(id, cx),
+            MentionUri::ContextServerResource { server_id, uri, .. } => {
+                self.subscribe_to_resource(
+                    crease_id,
+                    ContextServerId(server_id.as_str().into()),
+                    uri.clone(),
+                    supports_images,
+                    cx,
+                );
+                self.confirm_mention_for_resource(
+                    ContextServerId(server_id.into()),
+                    uri,
+                    supports_images,
+                    cx,
+                )
+            }
             MentionUri::PastedImage => {
                 debug_panic!("pasted image URI should not be included in completions");
                 Task::ready(Err(anyhow!(
@@ -255,7 +316,7 @@ impl MentionSet {
                         // Remove mention
                         editor.edit([(start_anchor..end_anchor, "")], cx);
                     });
-                    this.mentions.remove(&crease_id);
+                    this.remove_mention(&crease_id, cx);
                 })
                 .ok();
             }
@@ -387,6 +448,203 @@ impl MentionSet {
         })
     }
 
+    fn confirm_mention_for_resource(
+        &self,
+        server_id: ContextServerId,
+        uri: Url,
+        supports_images: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let Some(client) = self.context_server_client(&server_id, cx) else {
+            return Task::ready(Err(anyhow!("Context server {server_id} is not running")));
+        };
+        cx.spawn(async move |_, _| {
+            let response = client
+                .request::<context_server::types::requests::ResourcesRead>(
+                    context_server::types::ResourcesReadParams {
+                        uri: uri.clone(),
+                        meta: None,
+                    },
+                )
+                .await?;
+
+            let mut content = String::new();
+            let mut image = None;
+            for contents in response.contents {
+                match contents {
+                    ResourceContentsType::Text(text) => {
+                        if !content.is_empty() {
+                            content.push_str("\n\n");
+                        }
+                        content.push_str(&text.text);
+                    }
+                    ResourceContentsType::Blob(blob) => {
+                        if let Some(format) = blob
+                            .mime_type
+                            .as_deref()
+                            .and_then(ImageFormat::from_mime_type)
+                        {
+                            image.get_or_insert(MentionImage {
+                                data: blob.blob.into(),
+                                format,
+                            });
+                        }
+                    }
+                }
+            }
+
+            if !content.is_empty() {
+                Ok(Mention::Text {
+                    content,
+                    tracked_buffers: Vec::new(),
+                })
+            } else if let Some(image) = image {
+                if !supports_images {
+                    return Err(anyhow!("This model does not support images yet"));
+                }
+                Ok(Mention::Image(image))
+            } else {
+                Err(anyhow!("{uri} has no contents that can be attached"))
+            }
+        })
+    }
+
+    /// Re-reads the resource whenever its server says that it changed, so that the message
+    /// includes its latest contents.
+    fn subscribe_to_resource(
+        &mut self,
+        crease_id: CreaseId,
+        server_id: ContextServerId,
+        uri: Url,
+        supports_images: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(client) = self.context_server_client(&server_id, cx) else {
+            return;
+        };
+
+        let this = cx.weak_entity();
+        let subscription = client.on_notification(
+            ResourcesUpdated::METHOD,
+            Box::new({
+                let uri = uri.clone();
+                move |params, cx| {
+                    let Some(params) =
+                        serde_json::from_value::<ResourcesUpdatedParams>(params).log_err()
+                    else {
+                        return;
+                    };
+                    if params.uri != uri.as_str() {
+                        return;
+                    }
+                    let this = this.clone();
+                    cx.spawn(async move |cx| {
+                        this.update(cx, |this, cx| {
+                            this.refresh_resource_mention(crease_id, supports_images, cx)
+                        })
+                    })
+                    .detach();
+                }
+            }),
+        );
+
+        let supports_subscriptions = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        self.resource_subscriptions.insert(
+            crease_id,
+            ResourceSubscription {
+                server_id,
+                uri: uri.clone(),
+                subscribed: supports_subscriptions,
+                _notification_subscription: subscription,
+            },
+        );
+        if supports_subscriptions {
+            cx.spawn(async move |_, _| {
+                client
+                    .request::<context_server::types::requests::ResourcesSubscribe>(
+                        context_server::types::ResourcesSubscribeParams { uri, meta: None },
+                    )
+                    .await
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    /// Stops refreshing the resource mentioned by the crease, and tells its server to stop
+    /// sending updates about it unless another mention of the same resource still needs them.
+    fn unsubscribe_from_resource(&mut self, crease_id: &CreaseId, cx: &mut Context<Self>) {
+        let Some(subscription) = self.resource_subscriptions.remove(crease_id) else {
+            return;
+        };
+        let still_needed = self.resource_subscriptions.values().any(|other| {
+            other.subscribed
+                && other.server_id == subscription.server_id
+                && other.uri == subscription.uri
+        });
+        if !subscription.subscribed || still_needed {
+            return;
+        }
+        let Some(client) = self.context_server_client(&subscription.server_id, cx) else {
+            return;
+        };
+        cx.spawn(async move |_, _| {
+            client
+                .request::<context_server::types::requests::ResourcesUnsubscribe>(
+                    context_server::types::ResourcesUnsubscribeParams {
+                        uri: subscription.uri,
+                        meta: None,
+                    },
+                )
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn refresh_resource_mention(
+        &mut self,
+        crease_id: CreaseId,
+        supports_images: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((MentionUri::ContextServerResource { server_id, uri, .. }, _)) =
+            self.mentions.get(&crease_id)
+        else {
+            return;
+        };
+        let task = self.confirm_mention_for_resource(
+            ContextServerId(server_id.as_str().into()),
+            uri.clone(),
+            supports_images,
+            cx,
+        );
+        let task = cx
+            .spawn(async move |_, _| task.await.map_err(|e| e.to_string()))
+            .shared();
+        if let Some((_, mention_task)) = self.mentions.get_mut(&crease_id) {
+            *mention_task = task;
+        }
+    }
+
+    fn context_server_client(
+        &self,
+        server_id: &ContextServerId,
+        cx: &App,
+    ) -> Option<Arc<InitializedContextServerProtocol>> {
+        self.project
+            .upgrade()?
+            .read(cx)
+            .context_server_store()
+            .read(cx)
+            .get_running_server(server_id)?
+            .client()
+    }
+
     pub fn confirm_mention_for_selection(
         &mut self,
         source_range: Range<text::Anchor>,
@@ -660,8 +918,8 @@ pub(crate) fn paste_images_as_context(
                     })
                     .ok();
                 mention_set
-                    .update(cx, |mention_set, _cx| {
-                        mention_set.remove_mention(&crease_id)
+                    .update(cx, |mention_set, cx| {
+                        mention_set.remove_mention(&crease_id, cx)
                     })
                     .ok();
             }
@@ -273,6 +273,7 @@ impl TerminalInlineAssistant {
                 stop: Vec::new(),
                 temperature,
                 thinking_allowed: false,
+                max_output_tokens: None,
             }
         }))
     }
@@ -2297,6 +2297,7 @@ impl TextThread {
             stop: Vec::new(),
             temperature: model.and_then(|model| AgentSettings::temperature_for_model(model, cx)),
             thinking_allowed: true,
+            max_output_tokens: None,
         };
         for message in self.messages(cx) {
             if message.status != MessageStatus::Done {
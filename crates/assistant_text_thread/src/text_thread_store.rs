@@ -869,7 +869,9 @@ impl TextThreadStore {
                             cx,
                         );
                     }
-                    ContextServerStatus::Stopped | ContextServerStatus::Error(_) => {
+                    ContextServerStatus::Stopped
+                    | ContextServerStatus::Error(_)
+                    | ContextServerStatus::AuthRequired => {
                         if let Some(slash_command_ids) =
                             self.context_server_slash_command_ids.remove(server_id)
                         {
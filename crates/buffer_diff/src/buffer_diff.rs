@@ -615,6 +615,109 @@ impl BufferDiffInner {
         Some(new_index_text)
     }
 
+    /// Returns the new index text after staging or unstaging individual lines
+    /// of this diff's hunks. Rows in `buffer_rows` select added lines, and rows
+    /// in `base_text_rows` select deleted lines. Lines outside of hunks are ignored.
+    fn stage_or_unstage_lines_impl(
+        &self,
+        unstaged_diff: &Self,
+        stage: bool,
+        buffer_rows: &[Range<u32>],
+        base_text_rows: &[Range<u32>],
+        buffer: &text::BufferSnapshot,
+    ) -> Option<Rope> {
+        let is_selected = |rows: &[Range<u32>], row: u32| rows.iter().any(|r| r.contains(&row));
+        let mut added_rows = Vec::new();
+        let mut deleted_rows = Vec::new();
+        for hunk in self.hunks.iter() {
+            let hunk_buffer_rows = point_range_to_rows(hunk.buffer_range.to_point(buffer));
+            added_rows.extend(hunk_buffer_rows.filter(|row| is_selected(buffer_rows, *row)));
+            let hunk_base_rows =
+                point_range_to_rows(hunk.diff_base_byte_range.to_point(&self.base_text));
+            deleted_rows.extend(hunk_base_rows.filter(|row| is_selected(base_text_rows, *row)));
+        }
+        if added_rows.is_empty() && deleted_rows.is_empty() {
+            return None;
+        }
+
+        let head_text = self.base_text.text();
+        let index_text = unstaged_diff.base_text.text();
+        let buffer_text = buffer.text();
+        let head_lines = head_text.split_inclusive('\n').collect::<Vec<_>>();
+        let index_lines = index_text.split_inclusive('\n').collect::<Vec<_>>();
+        let buffer_lines = buffer_text.split_inclusive('\n').collect::<Vec<_>>();
+
+        // Row edits from the buffer to the index, and from HEAD to the index.
+        let buffer_to_index = unstaged_diff
+            .hunks
+            .iter()
+            .map(|hunk| {
+                (
+                    point_range_to_rows(hunk.buffer_range.to_point(buffer)),
+                    point_range_to_rows(
+                        hunk.diff_base_byte_range.to_point(&unstaged_diff.base_text),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        let head_to_index = if deleted_rows.is_empty() {
+            Vec::new()
+        } else {
+            language::line_diff(&head_text, &index_text)
+        };
+
+        let mut deleted_index_rows = Vec::new();
+        // Lines to insert into the index, keyed by the index row they precede.
+        // Lines from HEAD are placed before lines from the buffer at the same row.
+        let mut insertions = Vec::new();
+        for row in added_rows {
+            match (stage, map_row(row, &buffer_to_index)) {
+                (true, Err(index_rows)) => {
+                    if let Some(line) = buffer_lines.get(row as usize) {
+                        insertions.push((index_rows.end, 1, row, *line));
+                    }
+                }
+                (false, Ok(index_row)) => deleted_index_rows.push(index_row),
+                _ => {}
+            }
+        }
+        for row in deleted_rows {
+            match (stage, map_row(row, &head_to_index)) {
+                (true, Ok(index_row)) => deleted_index_rows.push(index_row),
+                (false, Err(index_rows)) => {
+                    if let Some(line) = head_lines.get(row as usize) {
+                        insertions.push((index_rows.start, 0, row, *line));
+                    }
+                }
+                _ => {}
+            }
+        }
+        insertions.sort_unstable();
+
+        let mut new_index_text = String::with_capacity(index_text.len());
+        let mut push_line = |line: &str| {
+            if !new_index_text.is_empty() && !new_index_text.ends_with('\n') {
+                new_index_text.push('\n');
+            }
+            new_index_text.push_str(line);
+        };
+        let mut insertions = insertions.into_iter().peekable();
+        for (index_row, line) in index_lines.into_iter().enumerate() {
+            while let Some((_, _, _, inserted_line)) =
+                insertions.next_if(|(row, ..)| *row as usize <= index_row)
+            {
+                push_line(inserted_line);
+            }
+            if !deleted_index_rows.contains(&(index_row as u32)) {
+                push_line(line);
+            }
+        }
+        for (_, _, _, inserted_line) in insertions {
+            push_line(inserted_line);
+        }
+        Some(Rope::from(new_index_text.as_str()))
+    }
+
     fn hunks_intersecting_range<'a>(
         &'a self,
         range: Range<Anchor>,
@@ -844,6 +947,32 @@ impl BufferDiffInner {
     }
 }
 
+/// Converts a range of points covering whole lines into the rows it spans.
+fn point_range_to_rows(range: Range<Point>) -> Range<u32> {
+    let end_row = if range.end.column > 0 {
+        range.end.row + 1
+    } else {
+        range.end.row
+    };
+    range.start.row..end_row.max(range.start.row)
+}
+
+/// Maps a row through a sorted list of row edits. Returns the corresponding
+/// row if it is unchanged, or the rows that replaced it otherwise.
+fn map_row(row: u32, edits: &[(Range<u32>, Range<u32>)]) -> Result<u32, Range<u32>> {
+    let mut delta = 0i64;
+    for (old_rows, new_rows) in edits {
+        if row < old_rows.start {
+            break;
+        }
+        if old_rows.contains(&row) {
+            return Err(new_rows.clone());
+        }
+        delta = new_rows.end as i64 - old_rows.end as i64;
+    }
+    Ok((row as i64 + delta) as u32)
+}
+
 fn build_diff_options(
     file: Option<&Arc<dyn File>>,
     language: Option<LanguageName>,
@@ -1193,6 +1322,31 @@ impl BufferDiff {
         }
     }
 
+    /// Stages or unstages only the given lines of this diff's hunks, leaving the
+    /// rest of each hunk as it is in the index. Rows in `buffer_rows` select added
+    /// lines, and rows in `base_text_rows` select deleted lines.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        buffer_rows: &[Range<u32>],
+        base_text_rows: &[Range<u32>],
+        buffer: &text::BufferSnapshot,
+        cx: &mut Context<Self>,
+    ) -> Option<Rope> {
+        let new_index_text = self.inner.stage_or_unstage_lines_impl(
+            &self.secondary_diff.as_ref()?.read(cx).inner,
+            stage,
+            buffer_rows,
+            base_text_rows,
+            buffer,
+        )?;
+
+        cx.emit(BufferDiffEvent::HunksStagedOrUnstaged(Some(
+            new_index_text.clone(),
+        )));
+        Some(new_index_text)
+    }
+
     pub fn range_to_hunk_range(
         &self,
         range: Range<Anchor>,
@@ -2154,6 +2308,117 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_stage_and_unstage_lines(cx: &mut TestAppContext) {
+        let head_text = "
+            one
+            two
+            three
+            four
+            five
+        "
+        .unindent();
+        let buffer_text = "
+            one
+            TWO-A
+            TWO-B
+            three
+            five
+            SIX
+        "
+        .unindent();
+        let buffer = Buffer::new(
+            ReplicaId::LOCAL,
+            BufferId::new(1).unwrap(),
+            buffer_text.clone(),
+        );
+
+        let build_diff = |index_text: String, cx: &mut TestAppContext| {
+            let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), index_text, cx);
+            let uncommitted = BufferDiffSnapshot::new_sync(buffer.clone(), head_text.clone(), cx);
+            let unstaged_diff = cx.new(|cx| {
+                let mut diff = BufferDiff::new(&buffer, cx);
+                diff.set_snapshot(unstaged, &buffer, cx);
+                diff
+            });
+            cx.new(|cx| {
+                let mut diff = BufferDiff::new(&buffer, cx);
+                diff.set_snapshot(uncommitted, &buffer, cx);
+                diff.set_secondary_diff(unstaged_diff);
+                diff
+            })
+        };
+
+        // Stage one added line of the modified hunk, the deleted line of the
+        // second hunk and the added line of the third hunk.
+        let uncommitted_diff = build_diff(head_text.clone(), cx);
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(true, &[1..2, 5..6], &[3..4], &buffer, cx)
+                .unwrap()
+                .to_string()
+        });
+        assert_eq!(
+            new_index_text,
+            "
+            one
+            two
+            TWO-A
+            three
+            five
+            SIX
+            "
+            .unindent()
+        );
+
+        // Rows outside of any hunk don't change the index.
+        let uncommitted_diff = build_diff(new_index_text.clone(), cx);
+        uncommitted_diff.update(cx, |diff, cx| {
+            assert!(
+                diff.stage_or_unstage_lines(true, &[0..1, 3..4], &[0..1], &buffer, cx)
+                    .is_none()
+            );
+        });
+
+        // Unstaging lines restores them from HEAD.
+        let index_text = uncommitted_diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(false, &[5..6], &[3..4], &buffer, cx)
+                .unwrap()
+                .to_string()
+        });
+        assert_eq!(
+            index_text,
+            "
+            one
+            two
+            TWO-A
+            three
+            four
+            five
+            "
+            .unindent()
+        );
+
+        // Staging the rest of the modified hunk yields the buffer's lines.
+        let uncommitted_diff = build_diff(index_text, cx);
+        let index_text = uncommitted_diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(true, &[1..3], &[1..2], &buffer, cx)
+                .unwrap()
+                .to_string()
+        });
+        assert_eq!(
+            index_text,
+            "
+            one
+            TWO-A
+            TWO-B
+            three
+            four
+            five
+            "
+            .unindent()
+        );
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_compare(cx: &mut TestAppContext) {
         let base_text = "
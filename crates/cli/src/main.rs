@@ -119,6 +119,10 @@ struct Args {
     /// Pairs of file paths to diff. Can be specified multiple times.
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
+    /// Resolves a three-way merge, writing the result to OUTPUT_PATH. Can be used as a
+    /// `git mergetool` together with `--wait`.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 4, value_names = ["BASE_PATH", "LOCAL_PATH", "REMOTE_PATH", "OUTPUT_PATH"])]
+    merge: Vec<String>,
     /// Uninstall Zed from user system
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
@@ -473,6 +477,7 @@ fn main() -> Result<()> {
     let mut paths = vec![];
     let mut urls = vec![];
     let mut diff_paths = vec![];
+    let mut merge_paths = vec![];
     let mut stdin_tmp_file: Option<fs::File> = None;
     let mut anonymous_fd_tmp_files = vec![];
 
@@ -483,6 +488,15 @@ fn main() -> Result<()> {
         ]);
     }
 
+    for paths in args.merge.chunks(4) {
+        merge_paths.push([
+            parse_path_with_position(&paths[0])?,
+            parse_path_with_position(&paths[1])?,
+            parse_path_with_position(&paths[2])?,
+            parse_path_with_position(&paths[3])?,
+        ]);
+    }
+
     #[cfg(target_os = "windows")]
     let wsl = args.wsl.as_ref();
     #[cfg(not(target_os = "windows"))]
@@ -538,6 +552,7 @@ fn main() -> Result<()> {
                     paths,
                     urls,
                     diff_paths,
+                    merge_paths,
                     wsl,
                     wait: args.wait,
                     open_new_workspace,
@@ -1011,6 +1011,10 @@ impl Database {
                         stash_entries: Vec::new(),
                         remote_upstream_url: db_repository_entry.remote_upstream_url.clone(),
                         remote_origin_url: db_repository_entry.remote_origin_url.clone(),
+                        rebase_status: None,
+                        sequencer_command: None,
+                        submodules: Vec::new(),
+                        bisect_status: None,
                     });
                 }
             }
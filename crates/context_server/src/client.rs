@@ -1,4 +1,4 @@
-use anyhow::{Context as _, Result, anyhow};
+use anyhow::{Context as _, Result};
 use collections::HashMap;
 use futures::{FutureExt, StreamExt, channel::oneshot, future, select};
 use gpui::{AppContext as _, AsyncApp, BackgroundExecutor, Task};
@@ -37,7 +37,7 @@ pub const INTERNAL_ERROR: i32 = -32603;
 
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
-type RequestHandler = Box<dyn Send + FnMut(RequestId, &RawValue, AsyncApp)>;
+pub(crate) type RequestHandler = Box<dyn Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -52,6 +52,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
+    request_handlers: Arc<Mutex<RequestHandlerSet>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -101,7 +102,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -114,7 +114,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -138,8 +137,9 @@ struct AnyNotification<'a> {
     params: Option<Value>,
 }
 
+/// An error that the other end of the connection answered a request with.
 #[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct Error {
+pub struct Error {
     pub message: String,
     pub code: i32,
 }
@@ -195,12 +195,13 @@ impl Client {
         let subscription_set = Arc::new(Mutex::new(NotificationSubscriptionSet::default()));
         let response_handlers =
             Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
-        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(RequestHandlerSet::default()));
 
         let receive_input_task = cx.spawn({
             let subscription_set = subscription_set.clone();
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
             let transport = transport.clone();
             async move |cx| {
                 Self::handle_input(
@@ -208,6 +209,7 @@ impl Client {
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -237,6 +239,7 @@ impl Client {
         Ok(Self {
             server_id,
             subscription_set,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -252,14 +255,16 @@ impl Client {
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches
+    /// them to the appropriate handlers. It processes requests (which are answered
+    /// by registered handlers), responses (which are matched to pending requests)
+    /// and notifications (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
-        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        request_handlers: Arc<Mutex<RequestHandlerSet>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -267,14 +272,20 @@ impl Client {
         while let Some(message) = receiver.next().await {
             log::trace!("recv: {}", &message);
             if let Ok(request) = serde_json::from_str::<AnyRequest>(&message) {
-                let mut request_handlers = request_handlers.lock();
-                if let Some(handler) = request_handlers.get_mut(request.method) {
-                    handler(
-                        request.id,
-                        request.params.unwrap_or(RawValue::NULL),
-                        cx.clone(),
-                    );
-                }
+                let params = request
+                    .params
+                    .and_then(|params| serde_json::from_str(params.get()).log_err())
+                    .unwrap_or(Value::Null);
+                let response = request_handlers.lock().handle(request.method, params, cx);
+                let method = request.method.to_string();
+                let id = request.id;
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    Self::respond(id, method, response, outbound_tx)
+                        .log_err()
+                        .await
+                })
+                .detach();
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
                     && let Some(handler) = handlers.remove(&response.id)
@@ -297,6 +308,35 @@ impl Client {
         Ok(())
     }
 
+    /// Sends the response to a request from the server, once its handler is done with it.
+    async fn respond(
+        id: RequestId,
+        method: String,
+        response: Option<Task<Result<Value>>>,
+        outbound_tx: channel::Sender<String>,
+    ) -> anyhow::Result<()> {
+        let value = match response {
+            Some(response) => match response.await {
+                Ok(result) => CspResult::Ok(Some(result)),
+                Err(error) => CspResult::Error(Some(Error {
+                    message: format!("{error:#}"),
+                    code: INTERNAL_ERROR,
+                })),
+            },
+            None => CspResult::Error(Some(Error {
+                message: format!("Method not found: {method}"),
+                code: METHOD_NOT_FOUND,
+            })),
+        };
+        let response = serde_json::to_string(&Response {
+            jsonrpc: JSON_RPC_VERSION,
+            id,
+            value,
+        })?;
+        outbound_tx.send(response).await?;
+        Ok(())
+    }
+
     /// Handles the stderr output from the context server.
     /// Continuously reads and logs any error messages from the server.
     async fn handle_err(transport: Arc<dyn Transport>) -> anyhow::Result<()> {
@@ -412,7 +452,7 @@ impl Client {
                     Ok(response) => {
                         let parsed: AnyResponse = serde_json::from_str(&response)?;
                         if let Some(error) = parsed.error {
-                            Err(anyhow!(error.message))
+                            Err(error.into())
                         } else if let Some(result) = parsed.result {
                             Ok(serde_json::from_str(result.get())?)
                         } else {
@@ -465,6 +505,42 @@ impl Client {
             set: self.subscription_set.clone(),
         }
     }
+
+    /// Registers a handler for requests that the server sends to the client. Its
+    /// result is sent back to the server as the response. When several handlers
+    /// are registered for a method, the most recently registered one answers.
+    /// Requests that carry a progress token for which a handler was registered
+    /// with [`Self::on_request_for_progress_token`] are answered by that handler instead.
+    #[must_use]
+    pub fn on_request(
+        &self,
+        method: &'static str,
+        f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) -> RequestSubscription {
+        RequestSubscription {
+            id: self.request_handlers.lock().add_handler(method, None, f),
+            set: self.request_handlers.clone(),
+        }
+    }
+
+    /// Registers a handler that only answers the requests whose `_meta.progressToken`
+    /// is `progress_token`, i.e. the requests the server makes on behalf of the
+    /// client request that was sent with that token.
+    #[must_use]
+    pub fn on_request_for_progress_token(
+        &self,
+        method: &'static str,
+        progress_token: Value,
+        f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) -> RequestSubscription {
+        RequestSubscription {
+            id: self
+                .request_handlers
+                .lock()
+                .add_handler(method, Some(progress_token), f),
+            set: self.request_handlers.clone(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -478,6 +554,14 @@ impl std::fmt::Display for RequestCanceled {
     }
 }
 
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 impl fmt::Display for ContextServerId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -562,3 +646,62 @@ impl Drop for NotificationSubscription {
         });
     }
 }
+
+#[derive(Default)]
+struct RequestHandlerSet {
+    next_id: usize,
+    handlers: Vec<(usize, &'static str, Option<Value>, RequestHandler)>,
+}
+
+impl RequestHandlerSet {
+    fn add_handler(
+        &mut self,
+        method: &'static str,
+        progress_token: Option<Value>,
+        handler: RequestHandler,
+    ) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.handlers.push((id, method, progress_token, handler));
+        id
+    }
+
+    fn handle(
+        &mut self,
+        method: &str,
+        params: Value,
+        cx: &mut AsyncApp,
+    ) -> Option<Task<Result<Value>>> {
+        let progress_token = params
+            .get("_meta")
+            .and_then(|meta| meta.get("progressToken"))
+            .cloned();
+        let find_handler = |progress_token: Option<&Value>| {
+            self.handlers
+                .iter()
+                .rposition(|(_, probe_method, probe_token, _)| {
+                    method == *probe_method && probe_token.as_ref() == progress_token
+                })
+        };
+        let index = progress_token
+            .as_ref()
+            .and_then(|progress_token| find_handler(Some(progress_token)))
+            .or_else(|| find_handler(None))?;
+        let (_, _, _, handler) = &mut self.handlers[index];
+        Some(handler(params, cx.clone()))
+    }
+}
+
+pub struct RequestSubscription {
+    id: usize,
+    set: Arc<Mutex<RequestHandlerSet>>,
+}
+
+impl Drop for RequestSubscription {
+    fn drop(&mut self) {
+        self.set
+            .lock()
+            .handlers
+            .retain(|(id, _, _, _)| *id != self.id);
+    }
+}
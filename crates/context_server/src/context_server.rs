@@ -1,5 +1,6 @@
 pub mod client;
 pub mod listener;
+pub mod oauth;
 pub mod protocol;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
@@ -13,13 +14,15 @@ use std::sync::Arc;
 use std::{fmt::Display, path::PathBuf};
 
 use anyhow::Result;
-use client::Client;
-use gpui::AsyncApp;
-use parking_lot::RwLock;
+use client::{Client, RequestSubscription};
+use gpui::{AsyncApp, Task};
+use parking_lot::{Mutex, RwLock};
 pub use settings::ContextServerCommand;
 use url::Url;
 
+use crate::oauth::OAuthSession;
 use crate::transport::HttpTransport;
+use crate::types::Request as _;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ContextServerId(pub Arc<str>);
@@ -32,6 +35,7 @@ impl Display for ContextServerId {
 
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
+    Http(Arc<HttpTransport>),
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
@@ -39,6 +43,8 @@ pub struct ContextServer {
     id: ContextServerId,
     client: RwLock<Option<Arc<crate::protocol::InitializedContextServerProtocol>>>,
     configuration: ContextServerTransport,
+    roots: Arc<RwLock<Vec<types::Root>>>,
+    roots_subscription: Mutex<Option<RequestSubscription>>,
 }
 
 impl ContextServer {
@@ -50,6 +56,8 @@ impl ContextServer {
         Self {
             id,
             client: RwLock::new(None),
+            roots: Default::default(),
+            roots_subscription: Default::default(),
             configuration: ContextServerTransport::Stdio(
                 command,
                 working_directory.map(|directory| directory.to_path_buf()),
@@ -67,19 +75,25 @@ impl ContextServer {
         let transport = match endpoint.scheme() {
             "http" | "https" => {
                 log::info!("Using HTTP transport for {}", endpoint);
-                let transport =
-                    HttpTransport::new(http_client, endpoint.to_string(), headers, executor);
-                Arc::new(transport) as _
+                HttpTransport::new(http_client, endpoint.to_string(), headers, executor)
             }
             _ => anyhow::bail!("unsupported MCP url scheme {}", endpoint.scheme()),
         };
-        Ok(Self::new(id, transport))
+        Ok(Self {
+            id,
+            client: RwLock::new(None),
+            roots: Default::default(),
+            roots_subscription: Default::default(),
+            configuration: ContextServerTransport::Http(Arc::new(transport)),
+        })
     }
 
     pub fn new(id: ContextServerId, transport: Arc<dyn crate::transport::Transport>) -> Self {
         Self {
             id,
             client: RwLock::new(None),
+            roots: Default::default(),
+            roots_subscription: Default::default(),
             configuration: ContextServerTransport::Custom(transport),
         }
     }
@@ -93,7 +107,58 @@ impl ContextServer {
     }
 
     pub async fn start(&self, cx: &AsyncApp) -> Result<()> {
-        self.initialize(self.new_client(cx)?).await
+        let client = self.new_client(cx)?;
+        // Answer `roots/list` before initializing, as the server may ask for them right away.
+        let roots = self.roots.clone();
+        *self.roots_subscription.lock() = Some(client.on_request(
+            types::requests::ListRoots::METHOD,
+            Box::new(move |_, _| {
+                let response = types::ListRootsResponse {
+                    roots: roots.read().clone(),
+                    meta: None,
+                };
+                Task::ready(serde_json::to_value(response).map_err(Into::into))
+            }),
+        ));
+        let result = self.initialize(client).await;
+        // The transport fails every request once the server rejects one, so report why.
+        if result.is_err()
+            && let ContextServerTransport::Http(transport) = &self.configuration
+            && let Some(authorization_required) = transport.authorization_required()
+        {
+            return Err(authorization_required.into());
+        }
+        result
+    }
+
+    /// Signs the requests to an HTTP server with the session's access token.
+    pub fn set_oauth_session(&self, session: Option<Arc<OAuthSession>>) {
+        if let ContextServerTransport::Http(transport) = &self.configuration {
+            transport.set_oauth_session(session);
+        }
+    }
+
+    pub fn oauth_session(&self) -> Option<Arc<OAuthSession>> {
+        match &self.configuration {
+            ContextServerTransport::Http(transport) => transport.oauth_session(),
+            ContextServerTransport::Stdio(..) | ContextServerTransport::Custom(_) => None,
+        }
+    }
+
+    /// Sets the roots that the server is told it can operate on, notifying it if they changed
+    /// while it's running.
+    pub fn set_roots(&self, roots: Vec<types::Root>) -> Result<()> {
+        {
+            let mut current_roots = self.roots.write();
+            if *current_roots == roots {
+                return Ok(());
+            }
+            *current_roots = roots;
+        }
+        if let Some(client) = self.client() {
+            client.notify::<types::notifications::RootsListChanged>(())?;
+        }
+        Ok(())
     }
 
     fn new_client(&self, cx: &AsyncApp) -> Result<Client> {
@@ -109,6 +174,13 @@ impl ContextServer {
                 working_directory,
                 cx.clone(),
             )?,
+            ContextServerTransport::Http(transport) => Client::new(
+                client::ContextServerId(self.id.0.clone()),
+                self.id().0,
+                transport.clone(),
+                None,
+                cx.clone(),
+            )?,
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
@@ -143,6 +215,13 @@ impl ContextServer {
         if let Some(protocol) = client.take() {
             drop(protocol);
         }
+        self.roots_subscription.lock().take();
         Ok(())
     }
 }
+
+/// Returns an unguessable, URL-safe token, e.g. for PKCE verifiers or bearer tokens.
+pub(crate) fn random_token() -> String {
+    use base64::prelude::*;
+    BASE64_URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
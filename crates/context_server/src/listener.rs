@@ -1,5 +1,5 @@
 use ::serde::{Deserialize, Serialize};
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use collections::HashMap;
 use futures::{
     AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, FutureExt,
@@ -16,8 +16,10 @@ use smol::stream::StreamExt;
 use std::{
     any::TypeId,
     cell::RefCell,
+    io::Read as _,
     path::{Path, PathBuf},
     rc::Rc,
+    sync::Arc,
 };
 use util::ResultExt;
 
@@ -30,13 +32,41 @@ use crate::{
     },
 };
 
+/// The path that [`McpServer::serve_http`] accepts requests on.
+const HTTP_PATH: &str = "/mcp";
+
+/// The largest request body that [`McpServer::serve_http`] accepts, in bytes.
+const MAX_HTTP_BODY_LEN: usize = 4 * 1024 * 1024;
+
 pub struct McpServer {
     socket_path: PathBuf,
     tools: Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
     handlers: Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
+    http: Option<HttpListener>,
     _server_task: Task<()>,
 }
 
+/// An HTTP endpoint serving the same tools and handlers as an [`McpServer`]'s socket, using the
+/// Streamable HTTP transport without server-initiated streams.
+#[derive(Clone, Debug)]
+pub struct HttpEndpoint {
+    pub url: String,
+    /// The token that clients must send as `Authorization: Bearer <token>`.
+    pub token: String,
+}
+
+struct HttpListener {
+    server: Arc<tiny_http::Server>,
+    endpoint: HttpEndpoint,
+    _task: Task<()>,
+}
+
+impl Drop for HttpListener {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
 struct RegisteredTool {
     tool: Tool,
     handler: ToolHandler,
@@ -79,6 +109,7 @@ impl McpServer {
                 _server_task: server_task,
                 tools,
                 handlers,
+                http: None,
             })
         })
     }
@@ -214,36 +245,209 @@ impl McpServer {
                     continue;
                 };
 
-                if request.method == CallTool::METHOD {
-                    Self::handle_call_tool(request_id, request.params, &tools, &outgoing_tx, cx)
-                        .await;
-                } else if request.method == ListTools::METHOD {
-                    Self::handle_list_tools(request.id.unwrap(), &tools, &outgoing_tx);
-                } else if let Some(handler) = handlers.borrow().get(&request.method.as_ref()) {
-                    let outgoing_tx = outgoing_tx.clone();
+                Self::handle_message(request_id, request, &tools, &handlers, &outgoing_tx, cx)
+                    .await;
+            }
+        })
+        .detach();
+    }
 
-                    if let Some(task) = cx
-                        .update(|cx| handler(request_id, request.params, cx))
-                        .log_err()
-                    {
-                        cx.spawn(async move |_| {
-                            let response = task.await;
-                            outgoing_tx.unbounded_send(response).ok();
-                        })
-                        .detach();
+    /// Starts serving over HTTP on a random local port, if it isn't already, and returns the
+    /// endpoint that clients can connect to.
+    pub fn serve_http(&mut self, cx: &mut AsyncApp) -> Result<HttpEndpoint> {
+        if let Some(http) = &self.http {
+            return Ok(http.endpoint.clone());
+        }
+
+        let server = Arc::new(
+            tiny_http::Server::http("127.0.0.1:0")
+                .map_err(|error| anyhow!("creating mcp http server: {error}"))?,
+        );
+        let endpoint = HttpEndpoint {
+            url: format!(
+                "http://127.0.0.1:{}{HTTP_PATH}",
+                server.server_addr().port()
+            ),
+            token: crate::random_token(),
+        };
+
+        // tiny_http only offers blocking reads, so requests are received on a dedicated thread
+        // and handled on the foreground like the socket's. Requests are checked before their body
+        // is read, so that unauthorized clients can't make the server buffer anything.
+        let (requests_tx, mut requests_rx) = unbounded();
+        std::thread::Builder::new()
+            .name("mcp-http".into())
+            .spawn({
+                let server = server.clone();
+                let authorization = format!("Bearer {}", endpoint.token);
+                move || {
+                    for mut request in server.incoming_requests() {
+                        if let Some(status) = Self::check_http_request(&request, &authorization) {
+                            request
+                                .respond(tiny_http::Response::empty(status))
+                                .log_err();
+                            continue;
+                        }
+                        let mut body = String::new();
+                        if let Err(error) = request
+                            .as_reader()
+                            .take(MAX_HTTP_BODY_LEN as u64 + 1)
+                            .read_to_string(&mut body)
+                        {
+                            log::error!("failed to read mcp http request: {error}");
+                            request.respond(tiny_http::Response::empty(400)).log_err();
+                            continue;
+                        }
+                        if body.len() > MAX_HTTP_BODY_LEN {
+                            request.respond(tiny_http::Response::empty(413)).log_err();
+                            continue;
+                        }
+                        if requests_tx.unbounded_send((request, body)).is_err() {
+                            break;
+                        }
                     }
-                } else {
-                    Self::send_err(
-                        request_id,
-                        format!("unhandled method {}", request.method),
-                        &outgoing_tx,
-                    );
+                }
+            })
+            .context("spawning mcp http thread")?;
+
+        let task = cx.spawn({
+            let tools = self.tools.clone();
+            let handlers = self.handlers.clone();
+            async move |cx| {
+                while let Some((request, body)) = requests_rx.next().await {
+                    Self::serve_http_request(request, body, &tools, &handlers, cx).await;
                 }
             }
+        });
+
+        self.http = Some(HttpListener {
+            server,
+            endpoint: endpoint.clone(),
+            _task: task,
+        });
+        Ok(endpoint)
+    }
+
+    /// Returns the status to reject an HTTP request with, or `None` if its body should be read
+    /// and handled.
+    fn check_http_request(request: &tiny_http::Request, authorization: &str) -> Option<u16> {
+        let path = request.url().split('?').next().unwrap_or_default();
+        let is_authorized = request.headers().iter().any(|header| {
+            header.field.equiv("Authorization") && header.value.as_str() == authorization
+        });
+        if !is_authorized {
+            Some(401)
+        } else if path != HTTP_PATH {
+            Some(404)
+        } else if *request.method() != tiny_http::Method::Post {
+            Some(405)
+        } else if request
+            .body_length()
+            .is_some_and(|length| length > MAX_HTTP_BODY_LEN)
+        {
+            Some(413)
+        } else {
+            None
+        }
+    }
+
+    async fn serve_http_request(
+        request: tiny_http::Request,
+        body: String,
+        tools: &Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
+        handlers: &Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
+        cx: &mut AsyncApp,
+    ) {
+        log::trace!("recv: {}", &body);
+        let message = match serde_json::from_str::<RawRequest>(&body) {
+            Ok(message) => message,
+            Err(error) => {
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "error": {
+                        "code": -32700,
+                        "message": format!("Failed to parse: {error}"),
+                    },
+                });
+                Self::respond_http(request, 400, Some(response.to_string()), cx);
+                return;
+            }
+        };
+        let Some(request_id) = message.id.clone() else {
+            Self::respond_http(request, 202, None, cx);
+            return;
+        };
+
+        let (outgoing_tx, mut outgoing_rx) = unbounded();
+        Self::handle_message(request_id, message, tools, handlers, &outgoing_tx, cx).await;
+        drop(outgoing_tx);
+
+        cx.spawn(async move |cx| match outgoing_rx.next().await {
+            Some(response) => {
+                log::trace!("send: {}", &response);
+                Self::respond_http(request, 200, Some(response), cx);
+            }
+            None => Self::respond_http(request, 500, None, cx),
         })
         .detach();
     }
 
+    fn respond_http(request: tiny_http::Request, status: u16, body: Option<String>, cx: &AsyncApp) {
+        cx.background_spawn(async move {
+            let result = match body {
+                Some(body) => {
+                    let content_type = tiny_http::Header::from_bytes(
+                        &b"Content-Type"[..],
+                        &b"application/json"[..],
+                    )
+                    .expect("valid header");
+                    request.respond(
+                        tiny_http::Response::from_string(body)
+                            .with_status_code(status)
+                            .with_header(content_type),
+                    )
+                }
+                None => request.respond(tiny_http::Response::empty(status)),
+            };
+            result.log_err();
+        })
+        .detach();
+    }
+
+    async fn handle_message(
+        request_id: RequestId,
+        request: RawRequest,
+        tools: &Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
+        handlers: &Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
+        outgoing_tx: &UnboundedSender<String>,
+        cx: &mut AsyncApp,
+    ) {
+        if request.method == CallTool::METHOD {
+            Self::handle_call_tool(request_id, request.params, tools, outgoing_tx, cx).await;
+        } else if request.method == ListTools::METHOD {
+            Self::handle_list_tools(request_id, tools, outgoing_tx);
+        } else if let Some(handler) = handlers.borrow().get(&request.method.as_ref()) {
+            let outgoing_tx = outgoing_tx.clone();
+
+            if let Some(task) = cx
+                .update(|cx| handler(request_id, request.params, cx))
+                .log_err()
+            {
+                cx.spawn(async move |_| {
+                    let response = task.await;
+                    outgoing_tx.unbounded_send(response).ok();
+                })
+                .detach();
+            }
+        } else {
+            Self::send_err(
+                request_id,
+                format!("unhandled method {}", request.method),
+                outgoing_tx,
+            );
+        }
+    }
+
     fn handle_list_tools(
         request_id: RequestId,
         tools: &Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
@@ -438,3 +642,67 @@ struct RawRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     params: Option<Box<serde_json::value::RawValue>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::io::{Read as _, Write as _};
+
+    #[gpui::test]
+    async fn test_http_requests_are_checked_before_reading_body(cx: &mut TestAppContext) {
+        let mut server = McpServer::new(&cx.to_async()).await.unwrap();
+        let endpoint = server.serve_http(&mut cx.to_async()).unwrap();
+        let address = endpoint
+            .url
+            .strip_prefix("http://")
+            .and_then(|url| url.strip_suffix(HTTP_PATH))
+            .unwrap()
+            .to_string();
+        let authorization = format!("Authorization: Bearer {}\r\n", endpoint.token);
+
+        let status = |request: String| {
+            let mut stream = std::net::TcpStream::connect(&address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+                .split_whitespace()
+                .nth(1)
+                .and_then(|status| status.parse::<u16>().ok())
+                .unwrap()
+        };
+
+        assert_eq!(
+            status(format!(
+                "POST {HTTP_PATH} HTTP/1.1\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{{}}"
+            )),
+            401
+        );
+        assert_eq!(
+            status(format!(
+                "POST {HTTP_PATH} HTTP/1.1\r\nConnection: close\r\nAuthorization: Bearer wrong\r\nContent-Length: 2\r\n\r\n{{}}"
+            )),
+            401
+        );
+        assert_eq!(
+            status(format!(
+                "POST /other HTTP/1.1\r\nConnection: close\r\n{authorization}Content-Length: 2\r\n\r\n{{}}"
+            )),
+            404
+        );
+        assert_eq!(
+            status(format!(
+                "GET {HTTP_PATH} HTTP/1.1\r\nConnection: close\r\n{authorization}\r\n"
+            )),
+            405
+        );
+        assert_eq!(
+            status(format!(
+                "POST {HTTP_PATH} HTTP/1.1\r\nConnection: close\r\n{authorization}Content-Length: {}\r\n\r\n",
+                MAX_HTTP_BODY_LEN + 1
+            )),
+            413
+        );
+    }
+}
@@ -0,0 +1,631 @@
+//! OAuth 2.1 authorization for HTTP context servers, as described by the MCP spec: the server's
+//! authorization server is discovered from its metadata, Zed registers itself as a client, and
+//! the user approves access in their browser, with PKCE protecting the authorization code.
+
+use anyhow::{Context as _, Result, anyhow};
+use base64::prelude::*;
+use futures::{AsyncReadExt as _, FutureExt as _, lock::Mutex as AsyncMutex};
+use gpui::BackgroundExecutor;
+use http_client::{AsyncBody, HttpClient, Method, Request};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest as _, Sha256};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use url::Url;
+use util::ResultExt as _;
+
+const CLIENT_NAME: &str = "Zed";
+const CALLBACK_PATH: &str = "/callback";
+/// How long to wait for the user to approve access in their browser.
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// How often to check whether the browser was redirected back to the callback server.
+const CALLBACK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Access tokens are refreshed this long before they expire, so that requests don't race them.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// The error that a server responds with until the user authorizes Zed to access it.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("The server requires authorization")]
+pub struct AuthorizationRequired {
+    /// The server's `WWW-Authenticate` header, which can point to its metadata.
+    pub www_authenticate: Option<String>,
+}
+
+/// The tokens for a server, along with what's needed to refresh them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OAuthCredentials {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub token_endpoint: String,
+    /// The server the tokens are for, as sent in the `resource` parameter.
+    pub resource: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<SystemTime>,
+}
+
+impl OAuthCredentials {
+    fn is_expiring(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= SystemTime::now() + EXPIRY_MARGIN)
+    }
+
+    async fn refresh(&self, http_client: &Arc<dyn HttpClient>) -> Result<Self> {
+        let refresh_token = self
+            .refresh_token
+            .as_deref()
+            .context("The access token expired and can't be refreshed")?;
+        ensure_secure_endpoint("token", &self.token_endpoint)?;
+        let mut params = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", self.client_id.as_str()),
+            ("resource", self.resource.as_str()),
+        ];
+        if let Some(client_secret) = &self.client_secret {
+            params.push(("client_secret", client_secret.as_str()));
+        }
+        let response = request_token(http_client, &self.token_endpoint, &params).await?;
+        Ok(Self {
+            access_token: response.access_token,
+            refresh_token: response
+                .refresh_token
+                .or_else(|| self.refresh_token.clone()),
+            expires_at: expires_at(response.expires_in),
+            ..self.clone()
+        })
+    }
+}
+
+/// Signs a server's requests with the user's access token, refreshing it as needed.
+pub struct OAuthSession {
+    http_client: Arc<dyn HttpClient>,
+    credentials: Mutex<OAuthCredentials>,
+    refreshing: AsyncMutex<()>,
+    on_refresh: Box<dyn Fn(OAuthCredentials) + Send + Sync>,
+}
+
+impl OAuthSession {
+    /// Creates a session that calls `on_refresh` with the new credentials whenever the tokens
+    /// are refreshed, so that they can be saved.
+    pub fn new(
+        http_client: Arc<dyn HttpClient>,
+        credentials: OAuthCredentials,
+        on_refresh: impl Fn(OAuthCredentials) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            http_client,
+            credentials: Mutex::new(credentials),
+            refreshing: AsyncMutex::new(()),
+            on_refresh: Box::new(on_refresh),
+        }
+    }
+
+    /// The current access token, without refreshing it.
+    pub fn current_access_token(&self) -> String {
+        self.credentials.lock().access_token.clone()
+    }
+
+    /// The access token to send, refreshed first if it's about to expire.
+    pub async fn access_token(&self) -> Result<String> {
+        let credentials = self.credentials.lock().clone();
+        if credentials.is_expiring() {
+            self.refresh(&credentials.access_token).await
+        } else {
+            Ok(credentials.access_token)
+        }
+    }
+
+    /// Replaces an access token that the server rejected, unless another request already did.
+    pub async fn refresh(&self, rejected_access_token: &str) -> Result<String> {
+        let _refreshing = self.refreshing.lock().await;
+        let credentials = self.credentials.lock().clone();
+        if credentials.access_token != rejected_access_token {
+            return Ok(credentials.access_token);
+        }
+
+        let credentials = credentials.refresh(&self.http_client).await?;
+        let access_token = credentials.access_token.clone();
+        *self.credentials.lock() = credentials.clone();
+        (self.on_refresh)(credentials);
+        Ok(access_token)
+    }
+}
+
+/// Lets the user authorize Zed to access the server at `server_url`, by opening the
+/// authorization page with `open_url` and waiting for their browser to be redirected back to a
+/// local callback server.
+///
+/// Dropping the returned future cancels the authorization and stops the callback server.
+pub async fn authorize(
+    http_client: Arc<dyn HttpClient>,
+    server_url: &Url,
+    www_authenticate: Option<&str>,
+    open_url: impl FnOnce(&Url),
+    executor: BackgroundExecutor,
+) -> Result<OAuthCredentials> {
+    let server = tiny_http::Server::http("127.0.0.1:0")
+        .map_err(|error| anyhow!("Failed to start the sign-in callback server: {error}"))?;
+    let redirect_uri = format!(
+        "http://127.0.0.1:{}{CALLBACK_PATH}",
+        server.server_addr().port()
+    );
+
+    let authorization =
+        begin_authorization(&http_client, server_url, www_authenticate, &redirect_uri).await?;
+    open_url(&authorization.url);
+
+    let callback_query = futures::select_biased! {
+        query = wait_for_callback(&server, &authorization.state, &executor).fuse() => query?,
+        _ = executor.timer(CALLBACK_TIMEOUT).fuse() => {
+            anyhow::bail!("Didn't receive the sign-in callback")
+        }
+    };
+
+    authorization.complete(&http_client, &callback_query).await
+}
+
+/// Waits for the browser to be redirected to the callback server with the authorization's
+/// `state`, returning the query it was redirected with. Other requests, like ones left over from
+/// an earlier sign-in, are answered with an error and ignored.
+async fn wait_for_callback(
+    server: &tiny_http::Server,
+    state: &str,
+    executor: &BackgroundExecutor,
+) -> Result<String> {
+    loop {
+        let Some(request) = server.try_recv()? else {
+            executor.timer(CALLBACK_POLL_INTERVAL).await;
+            continue;
+        };
+        let Some(url) = Url::parse(&format!("http://127.0.0.1{}", request.url()))
+            .ok()
+            .filter(|url| url.path() == CALLBACK_PATH)
+        else {
+            request.respond(tiny_http::Response::empty(404)).log_err();
+            continue;
+        };
+        let callback_state = url
+            .query_pairs()
+            .find_map(|(key, value)| (key == "state").then_some(value));
+        if callback_state.as_deref() != Some(state) {
+            request
+                .respond(
+                    tiny_http::Response::from_string(
+                        "This sign-in link doesn't match the one Zed opened.",
+                    )
+                    .with_status_code(400),
+                )
+                .log_err();
+            continue;
+        }
+
+        request
+            .respond(tiny_http::Response::from_string(
+                "Zed received your authorization. You can close this window.",
+            ))
+            .log_err();
+        return Ok(url.query().unwrap_or_default().to_string());
+    }
+}
+
+/// An authorization that's waiting for the user to approve it in their browser.
+pub struct PendingAuthorization {
+    /// The authorization page to open in the browser.
+    pub url: Url,
+    client: RegisteredClient,
+    token_endpoint: String,
+    resource: String,
+    redirect_uri: String,
+    code_verifier: String,
+    state: String,
+}
+
+/// Discovers the authorization server of the server at `server_url`, registers Zed as a client
+/// that's redirected to `redirect_uri`, and prepares the authorization page's URL.
+pub async fn begin_authorization(
+    http_client: &Arc<dyn HttpClient>,
+    server_url: &Url,
+    www_authenticate: Option<&str>,
+    redirect_uri: &str,
+) -> Result<PendingAuthorization> {
+    let mut resource = server_url.clone();
+    resource.set_fragment(None);
+    let resource = resource.to_string();
+
+    let resource_metadata_url = www_authenticate
+        .and_then(|header| challenge_param(header, "resource_metadata"))
+        .and_then(|url| Url::parse(&url).log_err());
+    let resource_metadata_urls = match resource_metadata_url {
+        Some(url) => vec![url],
+        None => well_known_urls(server_url, "oauth-protected-resource"),
+    };
+    let resource_metadata =
+        fetch_first::<ProtectedResourceMetadata>(http_client, resource_metadata_urls).await;
+
+    // Servers without resource metadata are their own authorization server.
+    let issuer = match resource_metadata
+        .as_ref()
+        .and_then(|metadata| metadata.authorization_servers.first())
+    {
+        Some(issuer) => Url::parse(issuer).context("Invalid authorization server URL")?,
+        None => Url::parse(&server_url.origin().ascii_serialization())?,
+    };
+    let metadata_urls = well_known_urls(&issuer, "oauth-authorization-server")
+        .into_iter()
+        .chain(well_known_urls(&issuer, "openid-configuration"))
+        .collect();
+    let metadata =
+        match fetch_first::<AuthorizationServerMetadata>(http_client, metadata_urls).await {
+            Some(metadata) => metadata,
+            // Servers without metadata use the default endpoints, which must support PKCE.
+            None => AuthorizationServerMetadata {
+                authorization_endpoint: issuer.join("/authorize")?.to_string(),
+                token_endpoint: issuer.join("/token")?.to_string(),
+                registration_endpoint: Some(issuer.join("/register")?.to_string()),
+                code_challenge_methods_supported: vec!["S256".into()],
+            },
+        };
+    anyhow::ensure!(
+        metadata
+            .code_challenge_methods_supported
+            .iter()
+            .any(|method| method == "S256"),
+        "The authorization server doesn't support PKCE with S256, so Zed can't sign in securely"
+    );
+
+    let registration_endpoint = metadata
+        .registration_endpoint
+        .context("The authorization server doesn't support client registration")?;
+    let mut url = ensure_secure_endpoint("authorization", &metadata.authorization_endpoint)?;
+    ensure_secure_endpoint("token", &metadata.token_endpoint)?;
+    ensure_secure_endpoint("registration", &registration_endpoint)?;
+    let client = register_client(http_client, &registration_endpoint, redirect_uri).await?;
+
+    let code_verifier = crate::random_token();
+    let code_challenge = BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+    let state = crate::random_token();
+    let scope = www_authenticate
+        .and_then(|header| challenge_param(header, "scope"))
+        .or_else(|| {
+            resource_metadata
+                .map(|metadata| metadata.scopes_supported.join(" "))
+                .filter(|scope| !scope.is_empty())
+        });
+
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &client.client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("code_challenge", &code_challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("state", &state)
+        .append_pair("resource", &resource);
+    if let Some(scope) = scope {
+        url.query_pairs_mut().append_pair("scope", &scope);
+    }
+
+    Ok(PendingAuthorization {
+        url,
+        client,
+        token_endpoint: metadata.token_endpoint,
+        resource,
+        redirect_uri: redirect_uri.to_string(),
+        code_verifier,
+        state,
+    })
+}
+
+impl PendingAuthorization {
+    /// Exchanges the authorization code from the query of the URL that the browser was
+    /// redirected to for tokens.
+    pub async fn complete(
+        self,
+        http_client: &Arc<dyn HttpClient>,
+        callback_query: &str,
+    ) -> Result<OAuthCredentials> {
+        #[derive(Deserialize)]
+        struct CallbackParams {
+            code: Option<String>,
+            state: Option<String>,
+            error: Option<String>,
+            error_description: Option<String>,
+        }
+
+        let params: CallbackParams = serde_urlencoded::from_str(callback_query)
+            .context("Failed to parse the sign-in callback")?;
+        if let Some(error) = params.error {
+            anyhow::bail!(
+                "Authorization failed: {}",
+                params.error_description.unwrap_or(error)
+            );
+        }
+        anyhow::ensure!(
+            params.state.as_deref() == Some(self.state.as_str()),
+            "The sign-in callback doesn't match the authorization request"
+        );
+        let code = params
+            .code
+            .context("The sign-in callback is missing the authorization code")?;
+
+        let mut token_params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("client_id", self.client.client_id.as_str()),
+            ("code_verifier", self.code_verifier.as_str()),
+            ("resource", self.resource.as_str()),
+        ];
+        if let Some(client_secret) = &self.client.client_secret {
+            token_params.push(("client_secret", client_secret.as_str()));
+        }
+        let response = request_token(http_client, &self.token_endpoint, &token_params).await?;
+
+        Ok(OAuthCredentials {
+            client_id: self.client.client_id,
+            client_secret: self.client.client_secret,
+            token_endpoint: self.token_endpoint,
+            resource: self.resource,
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: expires_at(response.expires_in),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ProtectedResourceMetadata {
+    authorization_servers: Vec<String>,
+    #[serde(default)]
+    scopes_supported: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AuthorizationServerMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    registration_endpoint: Option<String>,
+    #[serde(default)]
+    code_challenge_methods_supported: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RegisteredClient {
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+async fn register_client(
+    http_client: &Arc<dyn HttpClient>,
+    registration_endpoint: &str,
+    redirect_uri: &str,
+) -> Result<RegisteredClient> {
+    let body = serde_json::json!({
+        "client_name": CLIENT_NAME,
+        "redirect_uris": [redirect_uri],
+        "grant_types": ["authorization_code", "refresh_token"],
+        "response_types": ["code"],
+        "token_endpoint_auth_method": "none",
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(registration_endpoint)
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json")
+        .body(AsyncBody::from(body.to_string()))?;
+    send_json(http_client, request)
+        .await
+        .context("Failed to register with the authorization server")
+}
+
+async fn request_token(
+    http_client: &Arc<dyn HttpClient>,
+    token_endpoint: &str,
+    params: &[(&str, &str)],
+) -> Result<TokenResponse> {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(token_endpoint)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("Accept", "application/json")
+        .body(AsyncBody::from(serde_urlencoded::to_string(params)?))?;
+    send_json(http_client, request)
+        .await
+        .context("Failed to request an access token")
+}
+
+/// Parses one of the authorization server's endpoints, which must use HTTPS unless it's on the
+/// local machine, since codes, tokens and client secrets are sent to them.
+fn ensure_secure_endpoint(name: &str, endpoint: &str) -> Result<Url> {
+    let url = Url::parse(endpoint).with_context(|| format!("Invalid {name} endpoint"))?;
+    let is_loopback = match url.host() {
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        Some(url::Host::Ipv4(address)) => address.is_loopback(),
+        Some(url::Host::Ipv6(address)) => address.is_loopback(),
+        None => false,
+    };
+    anyhow::ensure!(
+        url.scheme() == "https" || (url.scheme() == "http" && is_loopback),
+        "The authorization server's {name} endpoint {url} doesn't use HTTPS"
+    );
+    Ok(url)
+}
+
+/// Fetches the first of `urls` that responds with the expected metadata.
+async fn fetch_first<T: DeserializeOwned>(
+    http_client: &Arc<dyn HttpClient>,
+    urls: Vec<Url>,
+) -> Option<T> {
+    for url in urls {
+        match get_json(http_client, &url).await {
+            Ok(metadata) => return Some(metadata),
+            Err(error) => log::debug!("No OAuth metadata at {url}: {error:#}"),
+        }
+    }
+    None
+}
+
+async fn get_json<T: DeserializeOwned>(http_client: &Arc<dyn HttpClient>, url: &Url) -> Result<T> {
+    let request = Request::builder()
+        .uri(url.as_str())
+        .header("Accept", "application/json")
+        .body(AsyncBody::empty())?;
+    send_json(http_client, request).await
+}
+
+async fn send_json<T: DeserializeOwned>(
+    http_client: &Arc<dyn HttpClient>,
+    request: Request<AsyncBody>,
+) -> Result<T> {
+    let mut response = http_client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "HTTP {}: {body}",
+        response.status()
+    );
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// The metadata URLs for `url`, with the well-known path inserted before its path, then at its
+/// origin's root.
+fn well_known_urls(url: &Url, name: &str) -> Vec<Url> {
+    let origin = url.origin().ascii_serialization();
+    let path = url.path().trim_end_matches('/');
+    let mut urls = Vec::new();
+    if !path.is_empty() {
+        urls.extend(Url::parse(&format!("{origin}/.well-known/{name}{path}")).ok());
+    }
+    urls.extend(Url::parse(&format!("{origin}/.well-known/{name}")).ok());
+    urls
+}
+
+/// Reads a parameter of a `WWW-Authenticate: Bearer` challenge.
+fn challenge_param(header: &str, name: &str) -> Option<String> {
+    let params = header.trim().strip_prefix("Bearer").unwrap_or(header);
+    params.split(',').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+fn expires_at(expires_in: Option<u64>) -> Option<SystemTime> {
+    expires_in.map(|expires_in| SystemTime::now() + Duration::from_secs(expires_in))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::FakeAuthorizationServer;
+    use gpui::TestAppContext;
+    use std::io::{Read as _, Write as _};
+
+    #[gpui::test]
+    async fn test_authorize_through_local_callback(cx: &mut TestAppContext) {
+        let authorization_server = FakeAuthorizationServer::default();
+        let http_client: Arc<dyn HttpClient> = authorization_server.http_client();
+        let server_url = Url::parse(FakeAuthorizationServer::MCP_URL).unwrap();
+
+        // Send requests to the callback server from another thread, as a browser would.
+        let (statuses_tx, statuses_rx) = std::sync::mpsc::channel();
+        let open_url = {
+            let authorization_server = authorization_server.clone();
+            move |authorization_url: &Url| {
+                let callback_query = authorization_server.approve(authorization_url);
+                let redirect_uri = authorization_url
+                    .query_pairs()
+                    .find_map(|(key, value)| (key == "redirect_uri").then(|| value.into_owned()))
+                    .unwrap();
+                let address = redirect_uri
+                    .strip_prefix("http://")
+                    .and_then(|uri| uri.strip_suffix(CALLBACK_PATH))
+                    .unwrap()
+                    .to_string();
+                std::thread::spawn(move || {
+                    let statuses = [
+                        "/favicon.ico".to_string(),
+                        format!("{CALLBACK_PATH}?code=stolen&state=forged"),
+                        format!("{CALLBACK_PATH}?{callback_query}"),
+                    ]
+                    .map(|path| get_status(&address, &path));
+                    statuses_tx.send(statuses).unwrap();
+                });
+            }
+        };
+        let authorize = cx.executor().spawn({
+            let http_client = http_client.clone();
+            let executor = cx.executor();
+            async move { authorize(http_client, &server_url, None, open_url, executor).await }
+        });
+
+        let statuses = loop {
+            cx.executor().advance_clock(CALLBACK_POLL_INTERVAL);
+            cx.run_until_parked();
+            if let Ok(statuses) = statuses_rx.try_recv() {
+                break statuses;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        };
+        // A callback with another state doesn't end the sign-in, so the real one still can.
+        assert_eq!(statuses, [404, 400, 200]);
+
+        let credentials = authorize.await.unwrap();
+        assert_eq!(credentials.client_id, "fake-client");
+        assert_eq!(credentials.resource, FakeAuthorizationServer::MCP_URL);
+        assert!(credentials.refresh_token.is_some());
+        assert_eq!(authorization_server.grant_types(), ["authorization_code"]);
+    }
+
+    #[test]
+    fn test_ensure_secure_endpoint() {
+        for endpoint in [
+            "https://auth.example.com/token",
+            "http://localhost:8080/token",
+            "http://127.0.0.1:8080/token",
+            "http://[::1]:8080/token",
+        ] {
+            assert!(
+                ensure_secure_endpoint("token", endpoint).is_ok(),
+                "{endpoint}"
+            );
+        }
+        for endpoint in [
+            "http://auth.example.com/token",
+            "http://localhost.example.com/token",
+            "http://10.0.0.1/token",
+            "ftp://auth.example.com/token",
+            "javascript:alert(1)",
+            "not a url",
+        ] {
+            assert!(
+                ensure_secure_endpoint("token", endpoint).is_err(),
+                "{endpoint}"
+            );
+        }
+    }
+
+    fn get_status(address: &str, path: &str) -> u16 {
+        let mut stream = std::net::TcpStream::connect(address).unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nConnection: close\r\n\r\n").as_bytes())
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap()
+    }
+}
@@ -7,12 +7,12 @@
 
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use futures::channel::oneshot;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use serde_json::Value;
 
-use crate::client::{Client, NotificationSubscription};
+use crate::client::{Client, NotificationSubscription, RequestHandler, RequestSubscription};
 use crate::types::{self, Notification, Request};
 
 pub struct ModelContextProtocol {
@@ -39,8 +39,14 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
-                roots: None,
+                // Sampling and elicitation requests are answered by whoever handles them at the
+                // time, such as the agent while it runs one of the server's tools, and are
+                // refused with an explanation otherwise.
+                sampling: Some(serde_json::json!({})),
+                roots: Some(types::RootsCapabilities {
+                    list_changed: Some(true),
+                }),
+                elicitation: Some(serde_json::json!({})),
             },
             meta: None,
             client_info,
@@ -59,10 +65,23 @@ impl ModelContextProtocol {
 
         log::trace!("mcp server info {:?}", response.server_info);
 
-        let initialized_protocol = InitializedContextServerProtocol {
+        let mut initialized_protocol = InitializedContextServerProtocol {
             inner: self.inner,
             initialize: response,
+            _fallback_request_handlers: Vec::new(),
         };
+        initialized_protocol._fallback_request_handlers = vec![
+            initialized_protocol.on_request::<types::requests::CreateMessage>(|_, _| {
+                Task::ready(Err(anyhow!(
+                    "Zed only answers sampling requests while its agent runs one of this server's tools"
+                )))
+            }),
+            initialized_protocol.on_request::<types::requests::Elicit>(|_, _| {
+                Task::ready(Err(anyhow!(
+                    "Zed only answers elicitation requests while its agent runs one of this server's tools"
+                )))
+            }),
+        ];
 
         initialized_protocol.notify::<types::notifications::Initialized>(())?;
 
@@ -73,6 +92,9 @@ impl ModelContextProtocol {
 pub struct InitializedContextServerProtocol {
     inner: Client,
     pub initialize: types::InitializeResponse,
+    /// Refuse the requests that the client advertises support for while nothing else handles
+    /// them.
+    _fallback_request_handlers: Vec<RequestSubscription>,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -122,4 +144,40 @@ impl InitializedContextServerProtocol {
     ) -> NotificationSubscription {
         self.inner.on_notification(method, f)
     }
+
+    /// Answers the server's requests of type `T` with `f`, until the returned subscription is
+    /// dropped.
+    pub fn on_request<T: Request>(
+        &self,
+        f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) -> RequestSubscription {
+        self.inner
+            .on_request(T::METHOD, Self::request_handler::<T>(f))
+    }
+
+    /// Answers the server's requests of type `T` that it makes on behalf of the request sent
+    /// with `progress_token`, until the returned subscription is dropped.
+    pub fn on_request_for_progress_token<T: Request>(
+        &self,
+        progress_token: &types::ProgressToken,
+        f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) -> Result<RequestSubscription> {
+        Ok(self.inner.on_request_for_progress_token(
+            T::METHOD,
+            serde_json::to_value(progress_token)?,
+            Self::request_handler::<T>(f),
+        ))
+    }
+
+    fn request_handler<T: Request>(
+        mut f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) -> RequestHandler {
+        Box::new(move |params, cx| {
+            let response = match serde_json::from_value(params) {
+                Ok(params) => f(params, cx.clone()),
+                Err(error) => return Task::ready(Err(error.into())),
+            };
+            cx.spawn(async move |_| Ok(serde_json::to_value(response.await?)?))
+        })
+    }
 }
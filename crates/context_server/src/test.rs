@@ -1,8 +1,21 @@
 use anyhow::Context as _;
-use collections::HashMap;
-use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
+use base64::prelude::*;
+use collections::{HashMap, HashSet};
+use futures::{
+    AsyncReadExt as _, FutureExt, Stream, StreamExt as _, channel::oneshot, future::BoxFuture,
+    lock::Mutex,
+};
 use gpui::BackgroundExecutor;
-use std::{pin::Pin, sync::Arc};
+use http_client::{AsyncBody, FakeHttpClient, HttpClientWithUrl, Request, Response};
+use sha2::{Digest as _, Sha256};
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering::SeqCst},
+    },
+};
+use url::Url;
 
 use crate::{
     transport::Transport,
@@ -39,22 +52,94 @@ pub struct FakeTransport {
         &'static str,
         Arc<dyn Send + Sync + Fn(serde_json::Value) -> BoxFuture<'static, serde_json::Value>>,
     >,
+    notification_handlers: HashMap<&'static str, Arc<dyn Send + Sync + Fn(serde_json::Value)>>,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    server: FakeServer,
     executor: BackgroundExecutor,
 }
 
+/// Sends requests to the client from a [`FakeTransport`], as a server would.
+#[derive(Clone)]
+pub struct FakeServer {
+    tx: futures::channel::mpsc::UnboundedSender<String>,
+    next_id: Arc<AtomicU64>,
+    pending_requests: Arc<parking_lot::Mutex<HashMap<u64, oneshot::Sender<serde_json::Value>>>>,
+}
+
+impl FakeServer {
+    pub async fn request<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> anyhow::Result<T::Response> {
+        let id = self.next_id.fetch_add(1, SeqCst);
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending_requests.lock().insert(id, response_tx);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": format!("server-{id}"),
+            "method": T::METHOD,
+            "params": params,
+        });
+        self.tx
+            .unbounded_send(request.to_string())
+            .context("sending a message")?;
+
+        let mut response = response_rx.await?;
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("{}", error["message"]);
+        }
+        Ok(serde_json::from_value(response["result"].take())?)
+    }
+
+    pub fn notify<T: crate::types::Notification>(&self, params: T::Params) -> anyhow::Result<()> {
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": T::METHOD,
+            "params": params,
+        });
+        self.tx
+            .unbounded_send(notification.to_string())
+            .context("sending a message")
+    }
+
+    fn handle_response(&self, response: serde_json::Value) -> bool {
+        let Some(id) = response
+            .get("id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| id.strip_prefix("server-"))
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            return false;
+        };
+        if let Some(response_tx) = self.pending_requests.lock().remove(&id) {
+            response_tx.send(response).ok();
+        }
+        true
+    }
+}
+
 impl FakeTransport {
     pub fn new(executor: BackgroundExecutor) -> Self {
         let (tx, rx) = futures::channel::mpsc::unbounded();
         Self {
             request_handlers: Default::default(),
+            notification_handlers: Default::default(),
+            server: FakeServer {
+                tx: tx.clone(),
+                next_id: Default::default(),
+                pending_requests: Default::default(),
+            },
             tx,
             rx: Arc::new(Mutex::new(rx)),
             executor,
         }
     }
 
+    pub fn server(&self) -> FakeServer {
+        self.server.clone()
+    }
+
     pub fn on_request<T, Fut>(
         mut self,
         handler: impl 'static + Send + Sync + Fn(T::Params) -> Fut,
@@ -78,6 +163,23 @@ impl FakeTransport {
         );
         self
     }
+
+    pub fn on_notification<T: crate::types::Notification>(
+        mut self,
+        handler: impl 'static + Send + Sync + Fn(T::Params),
+    ) -> Self {
+        self.notification_handlers.insert(
+            T::METHOD,
+            Arc::new(move |value| {
+                let params = value
+                    .get("params")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                handler(serde_json::from_value(params).expect("Invalid parameters received"));
+            }),
+        );
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -88,19 +190,39 @@ impl Transport for FakeTransport {
 
             if let Some(method) = msg.get("method") {
                 let method = method.as_str().expect("Invalid method received");
-                if let Some(handler) = self.request_handlers.get(method) {
-                    let payload = handler(msg).await;
+                if msg.get("id").is_none() {
+                    if let Some(handler) = self.notification_handlers.get(method) {
+                        handler(msg);
+                    }
+                } else if let Some(handler) = self.request_handlers.get(method) {
+                    // Answer in the background, so that the client's other messages, like its
+                    // responses to the server's requests, get through in the meantime.
+                    let payload = handler(msg);
+                    let tx = self.tx.clone();
+                    self.executor
+                        .spawn(async move {
+                            let response = serde_json::json!({
+                                "jsonrpc": "2.0",
+                                "id": id,
+                                "result": payload.await
+                            });
+                            tx.unbounded_send(response.to_string()).ok();
+                        })
+                        .detach();
+                } else {
+                    log::debug!("No handler registered for MCP request '{method}'");
                     let response = serde_json::json!({
                         "jsonrpc": "2.0",
                         "id": id,
-                        "result": payload
+                        "error": {
+                            "code": crate::client::METHOD_NOT_FOUND,
+                            "message": format!("Method not found: {method}"),
+                        }
                     });
-                    self.tx
-                        .unbounded_send(response.to_string())
-                        .context("sending a message")?;
-                } else {
-                    log::debug!("No handler registered for MCP request '{method}'");
+                    self.tx.unbounded_send(response.to_string()).ok();
                 }
+            } else if !self.server.handle_response(msg) {
+                log::debug!("Unexpected MCP message '{message}'");
             }
         }
         Ok(())
@@ -128,3 +250,191 @@ impl Transport for FakeTransport {
         Box::pin(futures::stream::empty())
     }
 }
+
+/// An OAuth authorization server for tests, which registers any client and approves every
+/// authorization request, along with the MCP server at [`Self::MCP_URL`] that it protects.
+#[derive(Clone, Default)]
+pub struct FakeAuthorizationServer(Arc<parking_lot::Mutex<FakeAuthorizationState>>);
+
+#[derive(Default)]
+struct FakeAuthorizationState {
+    code_challenges: HashMap<String, String>,
+    access_tokens: HashSet<String>,
+    refresh_tokens: HashSet<String>,
+    next_id: usize,
+    grant_types: Vec<String>,
+}
+
+impl FakeAuthorizationServer {
+    pub const MCP_URL: &'static str = "https://mcp.example.com/mcp";
+    const ISSUER: &'static str = "https://auth.example.com";
+    const RESOURCE_METADATA_URL: &'static str =
+        "https://mcp.example.com/.well-known/oauth-protected-resource/mcp";
+
+    /// A client for the authorization server and the MCP server.
+    pub fn http_client(&self) -> Arc<HttpClientWithUrl> {
+        let this = self.clone();
+        FakeHttpClient::create(move |request| {
+            let this = this.clone();
+            async move { this.handle_request(request).await }
+        })
+    }
+
+    /// Approves the authorization request that the browser was sent to, returning the query
+    /// that it's redirected back with.
+    pub fn approve(&self, authorization_url: &Url) -> String {
+        let params = authorization_url
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<_, _>>();
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["resource"], Self::MCP_URL);
+
+        let mut state = self.0.lock();
+        let code = state.new_token("code");
+        state
+            .code_challenges
+            .insert(code.clone(), params["code_challenge"].clone());
+        serde_urlencoded::to_string([("code", code.as_str()), ("state", params["state"].as_str())])
+            .unwrap()
+    }
+
+    /// Revokes the access tokens handed out so far, so that clients have to refresh them.
+    pub fn revoke_access_tokens(&self) {
+        self.0.lock().access_tokens.clear();
+    }
+
+    /// The grant types of the token requests that were made, in order.
+    pub fn grant_types(&self) -> Vec<String> {
+        self.0.lock().grant_types.clone()
+    }
+
+    async fn handle_request(
+        &self,
+        mut request: Request<AsyncBody>,
+    ) -> anyhow::Result<Response<AsyncBody>> {
+        let url = request.uri().to_string();
+        let mut body = String::new();
+        request.body_mut().read_to_string(&mut body).await?;
+
+        let metadata = if url == Self::RESOURCE_METADATA_URL {
+            serde_json::json!({
+                "resource": Self::MCP_URL,
+                "authorization_servers": [Self::ISSUER],
+            })
+        } else if url == format!("{}/.well-known/oauth-authorization-server", Self::ISSUER) {
+            serde_json::json!({
+                "issuer": Self::ISSUER,
+                "authorization_endpoint": format!("{}/authorize", Self::ISSUER),
+                "token_endpoint": format!("{}/token", Self::ISSUER),
+                "registration_endpoint": format!("{}/register", Self::ISSUER),
+                "code_challenge_methods_supported": ["S256"],
+            })
+        } else if url == format!("{}/register", Self::ISSUER) {
+            let registration: serde_json::Value = serde_json::from_str(&body)?;
+            assert_eq!(registration["token_endpoint_auth_method"], "none");
+            serde_json::json!({ "client_id": "fake-client" })
+        } else if url == format!("{}/token", Self::ISSUER) {
+            return Ok(self.handle_token_request(&body));
+        } else if url == Self::MCP_URL {
+            return Ok(self.handle_mcp_request(&request, &body));
+        } else {
+            return Ok(Response::builder().status(404).body(AsyncBody::empty())?);
+        };
+        Ok(json_response(200, &metadata))
+    }
+
+    fn handle_token_request(&self, body: &str) -> Response<AsyncBody> {
+        let params = serde_urlencoded::from_str::<HashMap<String, String>>(body).unwrap();
+        let mut state = self.0.lock();
+        state.grant_types.push(params["grant_type"].clone());
+        assert_eq!(params["client_id"], "fake-client");
+
+        let is_valid = match params["grant_type"].as_str() {
+            "authorization_code" => {
+                state
+                    .code_challenges
+                    .remove(&params["code"])
+                    .is_some_and(|code_challenge| {
+                        let code_verifier = params["code_verifier"].as_bytes();
+                        code_challenge
+                            == BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier))
+                    })
+            }
+            "refresh_token" => state.refresh_tokens.contains(&params["refresh_token"]),
+            _ => false,
+        };
+        if !is_valid {
+            return json_response(400, &serde_json::json!({ "error": "invalid_grant" }));
+        }
+
+        let access_token = state.new_token("access");
+        let refresh_token = state.new_token("refresh");
+        state.access_tokens.insert(access_token.clone());
+        state.refresh_tokens.insert(refresh_token.clone());
+        json_response(
+            200,
+            &serde_json::json!({
+                "access_token": access_token,
+                "token_type": "Bearer",
+                "refresh_token": refresh_token,
+                "expires_in": 3600,
+            }),
+        )
+    }
+
+    fn handle_mcp_request(&self, request: &Request<AsyncBody>, body: &str) -> Response<AsyncBody> {
+        let access_token = request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if !access_token.is_some_and(|token| self.0.lock().access_tokens.contains(token)) {
+            return Response::builder()
+                .status(401)
+                .header(
+                    "WWW-Authenticate",
+                    format!(
+                        "Bearer resource_metadata=\"{}\"",
+                        Self::RESOURCE_METADATA_URL
+                    ),
+                )
+                .body(AsyncBody::empty())
+                .unwrap();
+        }
+
+        let message: serde_json::Value = serde_json::from_str(body).unwrap();
+        let Some(id) = message.get("id") else {
+            return Response::builder()
+                .status(202)
+                .body(AsyncBody::empty())
+                .unwrap();
+        };
+        let result = match message["method"].as_str() {
+            Some("initialize") => {
+                serde_json::to_value(create_initialize_response("fake-server".into())).unwrap()
+            }
+            Some("tools/list") => serde_json::json!({ "tools": [] }),
+            _ => serde_json::json!({}),
+        };
+        json_response(
+            200,
+            &serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        )
+    }
+}
+
+impl FakeAuthorizationState {
+    fn new_token(&mut self, kind: &str) -> String {
+        self.next_id += 1;
+        format!("{kind}-{}", self.next_id)
+    }
+}
+
+fn json_response(status: u16, body: &serde_json::Value) -> Response<AsyncBody> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(AsyncBody::from(body.to_string()))
+        .unwrap()
+}
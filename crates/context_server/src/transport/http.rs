@@ -3,11 +3,12 @@ use async_trait::async_trait;
 use collections::HashMap;
 use futures::{Stream, StreamExt};
 use gpui::BackgroundExecutor;
-use http_client::{AsyncBody, HttpClient, Request, Response, http::Method};
+use http_client::{AsyncBody, HttpClient, Request, Response, StatusCode, http::Method};
 use parking_lot::Mutex as SyncMutex;
 use smol::channel;
 use std::{pin::Pin, sync::Arc};
 
+use crate::oauth::{AuthorizationRequired, OAuthSession};
 use crate::transport::Transport;
 
 // Constants from MCP spec
@@ -27,6 +28,8 @@ pub struct HttpTransport {
     error_rx: channel::Receiver<String>,
     // Authentication headers to include in requests
     headers: HashMap<String, String>,
+    oauth_session: SyncMutex<Option<Arc<OAuthSession>>>,
+    authorization_required: SyncMutex<Option<AuthorizationRequired>>,
 }
 
 impl HttpTransport {
@@ -49,14 +52,31 @@ impl HttpTransport {
             error_tx,
             error_rx,
             headers,
+            oauth_session: SyncMutex::new(None),
+            authorization_required: SyncMutex::new(None),
         }
     }
 
-    /// Send a message and handle the response based on content type
-    async fn send_message(&self, message: String) -> Result<()> {
-        let is_notification =
-            !message.contains("\"id\":") || message.contains("notifications/initialized");
+    /// Signs requests with the session's access token.
+    pub fn set_oauth_session(&self, session: Option<Arc<OAuthSession>>) {
+        *self.oauth_session.lock() = session;
+        self.authorization_required.lock().take();
+    }
+
+    pub fn oauth_session(&self) -> Option<Arc<OAuthSession>> {
+        self.oauth_session.lock().clone()
+    }
+
+    /// Returns why the server last rejected a request, if it requires the user to authorize Zed.
+    pub fn authorization_required(&self) -> Option<AuthorizationRequired> {
+        self.authorization_required.lock().clone()
+    }
 
+    fn build_request(
+        &self,
+        message: String,
+        access_token: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
         let mut request_builder = Request::builder()
             .method(Method::POST)
             .uri(&self.endpoint)
@@ -70,13 +90,85 @@ impl HttpTransport {
             request_builder = request_builder.header(key.as_str(), value.as_str());
         }
 
+        if let Some(access_token) = access_token {
+            request_builder =
+                request_builder.header("Authorization", format!("Bearer {access_token}"));
+        }
+
         // Add session ID if we have one (except for initialize)
         if let Some(ref session_id) = *self.session_id.lock() {
             request_builder = request_builder.header(HEADER_SESSION_ID, session_id.as_str());
         }
 
-        let request = request_builder.body(AsyncBody::from(message.into_bytes()))?;
-        let mut response = self.http_client.send(request).await?;
+        Ok(request_builder.body(AsyncBody::from(message.into_bytes()))?)
+    }
+
+    /// Sends the request, refreshing the access token and retrying once if the server rejects
+    /// it, and failing with [`AuthorizationRequired`] if it still does.
+    async fn send_authorized(&self, message: String) -> Result<Response<AsyncBody>> {
+        let oauth_session = self.oauth_session.lock().clone();
+        let access_token = match &oauth_session {
+            Some(session) => match session.access_token().await {
+                Ok(access_token) => Some(access_token),
+                Err(error) => {
+                    log::warn!(
+                        "Failed to refresh the access token for {}: {error:#}",
+                        self.endpoint
+                    );
+                    return Err(self.require_authorization(None).into());
+                }
+            },
+            None => None,
+        };
+
+        let mut response = self
+            .http_client
+            .send(self.build_request(message.clone(), access_token.as_deref())?)
+            .await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        if let Some((session, access_token)) = oauth_session.zip(access_token) {
+            match session.refresh(&access_token).await {
+                Ok(access_token) => {
+                    response = self
+                        .http_client
+                        .send(self.build_request(message, Some(&access_token))?)
+                        .await?;
+                    if response.status() != StatusCode::UNAUTHORIZED {
+                        return Ok(response);
+                    }
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Failed to refresh the access token for {}: {error:#}",
+                        self.endpoint
+                    );
+                }
+            }
+        }
+
+        let www_authenticate = response
+            .headers()
+            .get("WWW-Authenticate")
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+        Err(self.require_authorization(www_authenticate).into())
+    }
+
+    fn require_authorization(&self, www_authenticate: Option<String>) -> AuthorizationRequired {
+        let error = AuthorizationRequired { www_authenticate };
+        *self.authorization_required.lock() = Some(error.clone());
+        error
+    }
+
+    /// Send a message and handle the response based on content type
+    async fn send_message(&self, message: String) -> Result<()> {
+        let is_notification =
+            !message.contains("\"id\":") || message.contains("notifications/initialized");
+
+        let mut response = self.send_authorized(message).await?;
 
         // Handle different response types based on status and content-type
         match response.status() {
@@ -233,6 +325,11 @@ impl Drop for HttpTransport {
         let endpoint = self.endpoint.clone();
         let session_id = self.session_id.lock().clone();
         let headers = self.headers.clone();
+        let access_token = self
+            .oauth_session
+            .lock()
+            .as_ref()
+            .map(|session| session.current_access_token());
 
         if let Some(session_id) = session_id {
             self.executor
@@ -246,6 +343,10 @@ impl Drop for HttpTransport {
                     for (key, value) in headers {
                         request_builder = request_builder.header(key.as_str(), value.as_str());
                     }
+                    if let Some(access_token) = access_token {
+                        request_builder = request_builder
+                            .header("Authorization", format!("Bearer {access_token}"));
+                    }
 
                     let request = request_builder.body(AsyncBody::empty());
 
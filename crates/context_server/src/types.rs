@@ -34,13 +34,13 @@ pub mod requests {
         "resources/unsubscribe",
         ResourcesUnsubscribe,
         ResourcesUnsubscribeParams,
-        ()
+        EmptyResponse
     );
     request!(
         "resources/subscribe",
         ResourcesSubscribe,
         ResourcesSubscribeParams,
-        ()
+        EmptyResponse
     );
     request!(
         "resources/read",
@@ -48,7 +48,12 @@ pub mod requests {
         ResourcesReadParams,
         ResourcesReadResponse
     );
-    request!("resources/list", ResourcesList, (), ResourcesListResponse);
+    request!(
+        "resources/list",
+        ResourcesList,
+        PaginatedParams,
+        ResourcesListResponse
+    );
     request!(
         "logging/setLevel",
         LoggingSetLevel,
@@ -73,10 +78,17 @@ pub mod requests {
     request!(
         "resources/templates/list",
         ListResourceTemplates,
-        (),
+        PaginatedParams,
         ListResourceTemplatesResponse
     );
     request!("roots/list", ListRoots, (), ListRootsResponse);
+    request!(
+        "sampling/createMessage",
+        CreateMessage,
+        CreateMessageRequest,
+        CreateMessageResult
+    );
+    request!("elicitation/create", Elicit, ElicitRequest, ElicitResult);
 }
 
 pub trait Request {
@@ -161,6 +173,17 @@ pub struct CallToolParams {
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// The parameters of list requests whose results can span several pages.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedParams {
+    /// The `next_cursor` of the previous page, if this isn't a request for the first one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, serde_json::Value>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourcesUnsubscribeParams {
@@ -277,6 +300,13 @@ pub enum ResourceContentsType {
     Blob(BlobResourceContents),
 }
 
+/// The result of requests that only acknowledge they succeeded.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmptyResponse {
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, serde_json::Value>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourcesListResponse {
@@ -311,9 +341,11 @@ pub struct CreateMessageRequest {
     pub stop_sequences: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMessageResult {
     pub role: Role,
@@ -323,6 +355,32 @@ pub struct CreateMessageResult {
     pub stop_reason: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitRequest {
+    pub message: String,
+    /// A flat object schema whose properties are strings, numbers, booleans, or string enums.
+    pub requested_schema: serde_json::Value,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitResult {
+    pub action: ElicitAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    Accept,
+    Decline,
+    Cancel,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PromptMessage {
@@ -446,6 +504,8 @@ pub struct ClientCapabilities {
     pub sampling: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roots: Option<RootsCapabilities>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<serde_json::Value>,
 }
 
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -583,6 +643,66 @@ pub struct ResourceTemplate {
     pub mime_type: Option<String>,
 }
 
+impl ResourceTemplate {
+    /// The literal part of the template before its first variable, like `logs://` for
+    /// `logs://{date}/{service}`.
+    pub fn prefix(&self) -> &str {
+        self.uri_template
+            .split('{')
+            .next()
+            .unwrap_or(&self.uri_template)
+    }
+
+    /// Whether `uri` is an expansion of this template. Each variable stands for one or more
+    /// characters, which only span a `/` for reserved expansions like `{+path}`.
+    pub fn matches(&self, uri: &str) -> bool {
+        fn matches_parts(parts: &[UriTemplatePart], uri: &str) -> bool {
+            match parts.split_first() {
+                None => uri.is_empty(),
+                Some((UriTemplatePart::Literal(literal), rest)) => uri
+                    .strip_prefix(literal)
+                    .is_some_and(|uri| matches_parts(rest, uri)),
+                Some((UriTemplatePart::Variable { reserved }, rest)) => {
+                    for (ix, char) in uri.char_indices() {
+                        if char == '/' && !reserved {
+                            break;
+                        }
+                        if matches_parts(rest, &uri[ix + char.len_utf8()..]) {
+                            return true;
+                        }
+                    }
+                    false
+                }
+            }
+        }
+
+        let mut parts = Vec::new();
+        let mut template = self.uri_template.as_str();
+        while let Some(start) = template.find('{') {
+            let Some(end) = template[start..].find('}') else {
+                return false;
+            };
+            if start > 0 {
+                parts.push(UriTemplatePart::Literal(&template[..start]));
+            }
+            let expression = &template[start + 1..start + end];
+            parts.push(UriTemplatePart::Variable {
+                reserved: expression.starts_with(['+', '#']),
+            });
+            template = &template[start + end + 1..];
+        }
+        if !template.is_empty() {
+            parts.push(UriTemplatePart::Literal(template));
+        }
+        matches_parts(&parts, uri)
+    }
+}
+
+enum UriTemplatePart<'a> {
+    Literal(&'a str),
+    Variable { reserved: bool },
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LoggingLevel {
@@ -749,7 +869,7 @@ pub struct ListRootsResponse {
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Root {
     pub uri: Url,
@@ -19955,6 +19955,127 @@ impl Editor {
         None
     }
 
+    pub fn stage_selected_lines(
+        &mut self,
+        _: &::git::StageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stage_or_unstage_selected_lines(true, cx);
+    }
+
+    pub fn unstage_selected_lines(
+        &mut self,
+        _: &::git::UnstageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stage_or_unstage_selected_lines(false, cx);
+    }
+
+    pub fn stage_or_unstage_selected_lines(&mut self, stage: bool, cx: &mut Context<Self>) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        let task = self.save_buffers_for_ranges_if_needed(&ranges, cx);
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |this, cx| {
+                let rows_by_buffer = this.selected_diff_rows(&ranges, cx);
+                for (buffer_id, (buffer_rows, base_text_rows)) in rows_by_buffer {
+                    this.do_stage_or_unstage_lines(
+                        stage,
+                        buffer_id,
+                        &buffer_rows,
+                        &base_text_rows,
+                        cx,
+                    );
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Returns the buffer rows and diff base rows covered by the given ranges,
+    /// grouped by buffer. The deleted lines of collapsed hunks are included
+    /// whenever a range touches the hunk, since they can't be selected.
+    fn selected_diff_rows(
+        &self,
+        ranges: &[Range<Anchor>],
+        cx: &App,
+    ) -> HashMap<BufferId, (Vec<Range<u32>>, Vec<Range<u32>>)> {
+        let multibuffer = self.buffer.read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        let mut rows_by_buffer: HashMap<BufferId, (Vec<Range<u32>>, Vec<Range<u32>>)> =
+            HashMap::default();
+        for range in ranges {
+            let is_empty = range.start == range.end;
+            for (buffer, buffer_range, excerpt_id, deleted_hunk_anchor) in
+                snapshot.range_to_buffer_ranges_with_deleted_hunks(range.clone())
+            {
+                if buffer_range.is_empty() && !is_empty {
+                    continue;
+                }
+                let Some(buffer_id) = snapshot.buffer_id_for_excerpt(excerpt_id) else {
+                    continue;
+                };
+                let start = buffer.offset_to_point(buffer_range.start.0);
+                let end = buffer.offset_to_point(buffer_range.end.0);
+                let end_row = if end.column == 0 && end.row > start.row {
+                    end.row
+                } else {
+                    end.row + 1
+                };
+                let (buffer_rows, base_text_rows) = rows_by_buffer.entry(buffer_id).or_default();
+                if deleted_hunk_anchor.is_some() {
+                    base_text_rows.push(start.row..end_row);
+                } else {
+                    buffer_rows.push(start.row..end_row);
+                }
+            }
+        }
+
+        if !multibuffer.all_diff_hunks_expanded() {
+            for hunk in self.diff_hunks_in_ranges(ranges, &snapshot) {
+                if multibuffer.has_expanded_diff_hunks_in_ranges(&[hunk.multi_buffer_range()], cx) {
+                    continue;
+                }
+                let Some(diff) = snapshot.diff_for_buffer_id(hunk.buffer_id) else {
+                    continue;
+                };
+                let start = diff
+                    .base_text()
+                    .offset_to_point(hunk.diff_base_byte_range.start.0);
+                let end = diff
+                    .base_text()
+                    .offset_to_point(hunk.diff_base_byte_range.end.0);
+                let end_row = if end.column > 0 { end.row + 1 } else { end.row };
+                rows_by_buffer
+                    .entry(hunk.buffer_id)
+                    .or_default()
+                    .1
+                    .push(start.row..end_row);
+            }
+        }
+        rows_by_buffer
+    }
+
+    fn do_stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        buffer_id: BufferId,
+        buffer_rows: &[Range<u32>],
+        base_text_rows: &[Range<u32>],
+        cx: &mut App,
+    ) -> Option<()> {
+        let project = self.project()?;
+        let buffer = project.read(cx).buffer_for_id(buffer_id, cx)?;
+        let diff = self.buffer.read(cx).diff_for(buffer_id)?;
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(stage, buffer_rows, base_text_rows, &buffer_snapshot, cx)
+        });
+        None
+    }
+
     pub fn expand_selected_diff_hunks(&mut self, cx: &mut Context<Self>) {
         let ranges: Vec<_> = self
             .selections
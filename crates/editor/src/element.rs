@@ -498,6 +498,8 @@ impl EditorElement {
         register_action(editor, window, Editor::toggle_staged_selected_diff_hunks);
         register_action(editor, window, Editor::stage_and_next);
         register_action(editor, window, Editor::unstage_and_next);
+        register_action(editor, window, Editor::stage_selected_lines);
+        register_action(editor, window, Editor::unstage_selected_lines);
         register_action(editor, window, Editor::expand_all_diff_hunks);
         register_action(editor, window, Editor::collapse_all_diff_hunks);
         register_action(editor, window, Editor::go_to_previous_change);
@@ -0,0 +1,336 @@
+//! A built-in MCP server that lets external agents and scripts drive a workspace.
+//!
+//! Each workspace can start its own server with [`StartServer`], which copies a configuration
+//! for connecting to it over stdio (through `zed --nc`) or HTTP to the clipboard. Every tool is
+//! refused unless all of the workspace's worktrees are trusted, and edits made through the server
+//! are tracked in an [`ActionLog`] so the user can keep or reject them.
+
+mod tools;
+
+use action_log::ActionLog;
+use anyhow::{Context as _, Result};
+use context_server::{
+    listener::{HttpEndpoint, McpServer},
+    types::{
+        Implementation, InitializeResponse, LATEST_PROTOCOL_VERSION, ProtocolVersion,
+        ServerCapabilities, ToolsCapabilities, VERSION_2024_11_05, requests,
+    },
+};
+use gpui::{
+    App, AppContext as _, ClipboardItem, Context, Entity, Task, Window, WindowHandle, actions,
+};
+use project::{Project, trusted_worktrees::TrustedWorktrees};
+use release_channel::AppVersion;
+use serde_json::json;
+use workspace::{
+    Toast, Workspace,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+pub use tools::*;
+
+actions!(
+    editor_mcp_server,
+    [
+        /// Starts an MCP server for this workspace and copies its configuration to the clipboard.
+        StartServer,
+        /// Stops this workspace's MCP server.
+        StopServer,
+        /// Keeps the edits made through this workspace's MCP server.
+        KeepEdits,
+        /// Rejects the edits made through this workspace's MCP server.
+        RejectEdits,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(EditorMcpServer::register).detach();
+}
+
+pub struct EditorMcpServer {
+    workspace: WindowHandle<Workspace>,
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+    state: ServerState,
+}
+
+enum ServerState {
+    Stopped,
+    Starting(Task<()>),
+    Running {
+        server: McpServer,
+        endpoint: HttpEndpoint,
+    },
+}
+
+struct EditorMcpServerNotification;
+struct ReviewEditsNotification;
+
+impl EditorMcpServer {
+    fn register(
+        workspace: &mut Workspace,
+        window: Option<&mut Window>,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(window) = window else {
+            return;
+        };
+        let Some(workspace_handle) = window.window_handle().downcast::<Workspace>() else {
+            return;
+        };
+        let project = workspace.project().clone();
+        let server = cx.new(|cx| Self::new(workspace_handle, project, cx));
+
+        workspace
+            .register_action({
+                let server = server.clone();
+                move |_, _: &StartServer, _, cx| server.update(cx, |server, cx| server.start(cx))
+            })
+            .register_action({
+                let server = server.clone();
+                move |_, _: &StopServer, _, cx| server.update(cx, |server, cx| server.stop(cx))
+            })
+            .register_action({
+                let server = server.clone();
+                move |workspace, _: &KeepEdits, _, cx| {
+                    server.update(cx, |server, cx| {
+                        server
+                            .action_log
+                            .update(cx, |action_log, cx| action_log.keep_all_edits(None, cx))
+                    });
+                    workspace.dismiss_notification(
+                        &NotificationId::unique::<ReviewEditsNotification>(),
+                        cx,
+                    );
+                }
+            })
+            .register_action(move |workspace, _: &RejectEdits, _, cx| {
+                server.update(cx, |server, cx| {
+                    server
+                        .action_log
+                        .update(cx, |action_log, cx| action_log.reject_all_edits(None, cx))
+                        .detach()
+                });
+                workspace
+                    .dismiss_notification(&NotificationId::unique::<ReviewEditsNotification>(), cx);
+            });
+    }
+
+    pub fn new(
+        workspace: WindowHandle<Workspace>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            workspace,
+            action_log: cx.new(|_| ActionLog::new(project.clone())),
+            project,
+            state: ServerState::Stopped,
+        }
+    }
+
+    pub fn action_log(&self) -> &Entity<ActionLog> {
+        &self.action_log
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, ServerState::Running { .. })
+    }
+
+    pub fn start(&mut self, cx: &mut Context<Self>) {
+        match self.state {
+            ServerState::Running { .. } => {
+                self.copy_configuration(cx);
+                return;
+            }
+            ServerState::Starting(_) => return,
+            ServerState::Stopped => {}
+        }
+
+        let tools = self.tools();
+        self.state = ServerState::Starting(cx.spawn(async move |this, cx| {
+            let result = async {
+                let mut server = McpServer::new(cx).await?;
+                server.handle_request::<requests::Initialize>(|params, cx| {
+                    let protocol_version = if params.protocol_version.0 == VERSION_2024_11_05 {
+                        params.protocol_version
+                    } else {
+                        ProtocolVersion(LATEST_PROTOCOL_VERSION.into())
+                    };
+                    Task::ready(Ok(InitializeResponse {
+                        protocol_version,
+                        capabilities: ServerCapabilities {
+                            tools: Some(ToolsCapabilities {
+                                list_changed: Some(false),
+                            }),
+                            ..ServerCapabilities::default()
+                        },
+                        server_info: Implementation {
+                            name: "zed".into(),
+                            version: AppVersion::global(cx).to_string(),
+                        },
+                        meta: None,
+                    }))
+                });
+                tools.add_to(&mut server);
+                let endpoint = server.serve_http(cx)?;
+                anyhow::Ok((server, endpoint))
+            }
+            .await;
+
+            this.update(cx, |this, cx| match result {
+                Ok((server, endpoint)) => {
+                    this.state = ServerState::Running { server, endpoint };
+                    this.copy_configuration(cx);
+                }
+                Err(error) => {
+                    this.state = ServerState::Stopped;
+                    this.show_toast(format!("Failed to start the MCP server: {error:#}"), cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        if matches!(self.state, ServerState::Stopped) {
+            return;
+        }
+        self.state = ServerState::Stopped;
+        self.show_toast("Stopped the MCP server".into(), cx);
+    }
+
+    fn tools(&self) -> EditorTools {
+        EditorTools {
+            workspace: self.workspace,
+            action_log: self.action_log.clone(),
+        }
+    }
+
+    /// Returns the configuration that MCP clients can use to connect to the running server.
+    pub fn configuration(&self) -> Option<serde_json::Value> {
+        let ServerState::Running { server, endpoint } = &self.state else {
+            return None;
+        };
+
+        let mut servers = serde_json::Map::new();
+        if cfg!(not(target_os = "windows"))
+            && let Ok(zed_path) = std::env::current_exe()
+        {
+            servers.insert(
+                "zed".into(),
+                json!({
+                    "command": zed_path,
+                    "args": ["--nc", server.socket_path()],
+                }),
+            );
+        }
+        servers.insert(
+            "zed-http".into(),
+            json!({
+                "type": "http",
+                "url": endpoint.url,
+                "headers": { "Authorization": format!("Bearer {}", endpoint.token) },
+            }),
+        );
+        Some(json!({ "mcpServers": servers }))
+    }
+
+    fn copy_configuration(&self, cx: &mut Context<Self>) {
+        let Some(configuration) = self.configuration() else {
+            return;
+        };
+        let Ok(configuration) = serde_json::to_string_pretty(&configuration) else {
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(configuration));
+
+        if ensure_trusted(&self.project, cx).is_ok() {
+            self.show_toast(
+                "Copied the MCP server's configuration to the clipboard".into(),
+                cx,
+            );
+        } else {
+            self.show_toast(
+                "Copied the MCP server's configuration to the clipboard. \
+                Its tools won't run until you trust this workspace"
+                    .into(),
+                cx,
+            );
+        }
+    }
+
+    fn show_toast(&self, message: String, cx: &mut App) {
+        // Deferred because the actions that start and stop the server run while the workspace
+        // is being updated.
+        let workspace = self.workspace;
+        cx.defer(move |cx| {
+            workspace
+                .update(cx, |workspace, _, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<EditorMcpServerNotification>(),
+                            message,
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                })
+                .ok();
+        });
+    }
+}
+
+/// Returns an error unless all the visible worktrees of `project` are trusted.
+fn ensure_trusted(project: &Entity<Project>, cx: &mut App) -> Result<()> {
+    let trusted_worktrees = TrustedWorktrees::try_get_global(cx)
+        .context("Workspace trust isn't available, so the workspace's tools can't be used.")?;
+    let worktree_ids = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).id())
+        .collect::<Vec<_>>();
+    let is_trusted = trusted_worktrees.update(cx, |trusted_worktrees, cx| {
+        worktree_ids
+            .into_iter()
+            .all(|worktree_id| trusted_worktrees.can_trust(worktree_id, cx))
+    });
+    anyhow::ensure!(
+        is_trusted,
+        "This workspace isn't trusted. The user needs to trust it in Zed before its tools can be used."
+    );
+    Ok(())
+}
+
+fn show_review_notification(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    workspace.show_notification(
+        NotificationId::unique::<ReviewEditsNotification>(),
+        cx,
+        |cx| {
+            cx.new(|cx| {
+                MessageNotification::new("An external agent edited files in this workspace.", cx)
+                    .primary_message("Keep")
+                    .primary_on_click(|window, cx| {
+                        window.dispatch_action(Box::new(KeepEdits), cx);
+                    })
+                    .secondary_message("Reject")
+                    .secondary_on_click(|window, cx| {
+                        window.dispatch_action(Box::new(RejectEdits), cx);
+                    })
+            })
+        },
+    );
+}
+
+fn project_path_for(
+    workspace: &Workspace,
+    path: &str,
+    cx: &mut App,
+) -> Result<project::ProjectPath> {
+    ensure_trusted(workspace.project(), cx)?;
+    workspace
+        .project()
+        .read(cx)
+        .find_project_path(path, cx)
+        .with_context(|| format!("Could not find path {path} in the workspace"))
+}
@@ -0,0 +1,766 @@
+use std::fmt::Write as _;
+
+use action_log::ActionLog;
+use anyhow::{Context as _, Result, anyhow};
+use collections::BTreeMap;
+use context_server::{
+    listener::{McpServer, McpServerTool, ToolResponse},
+    types::{ToolAnnotations, ToolResponseContent},
+};
+use editor::Editor;
+use gpui::{AsyncApp, Entity, WindowHandle};
+use language::{DiagnosticSeverity, OffsetRangeExt as _, Point};
+use project::DiagnosticSummary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use task::TaskContext;
+use workspace::Workspace;
+
+use crate::{ensure_trusted, project_path_for, show_review_notification};
+
+pub(crate) struct EditorTools {
+    pub(crate) workspace: WindowHandle<Workspace>,
+    pub(crate) action_log: Entity<ActionLog>,
+}
+
+impl EditorTools {
+    pub(crate) fn add_to(self, server: &mut McpServer) {
+        server.add_tool(OpenFileTool {
+            workspace: self.workspace,
+        });
+        server.add_tool(GetDiagnosticsTool {
+            workspace: self.workspace,
+        });
+        server.add_tool(GetSelectionTool {
+            workspace: self.workspace,
+        });
+        server.add_tool(RunTaskTool {
+            workspace: self.workspace,
+        });
+        server.add_tool(ApplyEditTool {
+            workspace: self.workspace,
+            action_log: self.action_log,
+        });
+    }
+}
+
+fn text_response(text: impl Into<String>) -> ToolResponse<()> {
+    ToolResponse {
+        content: vec![ToolResponseContent::Text { text: text.into() }],
+        structured_content: (),
+    }
+}
+
+fn read_only_annotations(title: &str) -> ToolAnnotations {
+    ToolAnnotations {
+        title: Some(title.into()),
+        read_only_hint: Some(true),
+        destructive_hint: None,
+        idempotent_hint: Some(true),
+        open_world_hint: Some(false),
+    }
+}
+
+/// Opens a file from the workspace in the editor, optionally moving the cursor to a line.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OpenFileInput {
+    /// The path of the file, either absolute or starting with the name of one of the workspace's root directories.
+    pub path: String,
+    /// The 1-based line to move the cursor to.
+    pub line: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct OpenFileTool {
+    workspace: WindowHandle<Workspace>,
+}
+
+impl McpServerTool for OpenFileTool {
+    type Input = OpenFileInput;
+    type Output = ();
+
+    const NAME: &'static str = "open_file";
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations {
+            title: Some("Open file".into()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        }
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        let open_task = self.workspace.update(cx, |workspace, window, cx| {
+            let project_path = project_path_for(workspace, &input.path, cx)?;
+            anyhow::Ok(workspace.open_path(project_path, None, true, window, cx))
+        })??;
+        let item = open_task.await?;
+
+        if let Some(line) = input.line {
+            self.workspace.update(cx, |_, window, cx| {
+                if let Some(editor) = item.act_as::<Editor>(cx) {
+                    editor.update(cx, |editor, cx| {
+                        let point = Point::new(line.saturating_sub(1), 0);
+                        editor.go_to_singleton_buffer_point(point, window, cx);
+                    });
+                }
+            })?;
+        }
+
+        Ok(text_response(format!("Opened {}", input.path)))
+    }
+}
+
+/// Gets the errors and warnings reported by language servers for a file, or a summary of them for the whole workspace.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetDiagnosticsInput {
+    /// The path of the file, either absolute or starting with the name of one of the workspace's root directories. If omitted, returns the error and warning counts of every file.
+    pub path: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct GetDiagnosticsTool {
+    workspace: WindowHandle<Workspace>,
+}
+
+impl McpServerTool for GetDiagnosticsTool {
+    type Input = GetDiagnosticsInput;
+    type Output = ();
+
+    const NAME: &'static str = "get_diagnostics";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Get diagnostics")
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        match input.path {
+            Some(path) if !path.is_empty() => {
+                let open_buffer = self.workspace.update(cx, |workspace, _, cx| {
+                    let project_path = project_path_for(workspace, &path, cx)?;
+                    anyhow::Ok(
+                        workspace
+                            .project()
+                            .update(cx, |project, cx| project.open_buffer(project_path, cx)),
+                    )
+                })??;
+                let buffer = open_buffer.await?;
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+
+                let mut output = String::new();
+                for (_, group) in snapshot.diagnostic_groups(None) {
+                    let entry = &group.entries[group.primary_ix];
+                    let range = entry.range.to_point(&snapshot);
+                    let severity = match entry.diagnostic.severity {
+                        DiagnosticSeverity::ERROR => "error",
+                        DiagnosticSeverity::WARNING => "warning",
+                        _ => continue,
+                    };
+                    writeln!(
+                        output,
+                        "{severity} at line {}: {}",
+                        range.start.row + 1,
+                        entry.diagnostic.message
+                    )?;
+                }
+
+                if output.is_empty() {
+                    Ok(text_response("File doesn't have errors or warnings"))
+                } else {
+                    Ok(text_response(output))
+                }
+            }
+            _ => {
+                let output = self.workspace.update(cx, |workspace, _, cx| {
+                    let project = workspace.project().clone();
+                    ensure_trusted(&project, cx)?;
+
+                    let project = project.read(cx);
+                    let mut summaries = BTreeMap::<_, DiagnosticSummary>::default();
+                    for (project_path, _, summary) in
+                        project.lsp_store().read(cx).diagnostic_summaries(false, cx)
+                    {
+                        let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+                        else {
+                            continue;
+                        };
+                        let path = worktree.read(cx).absolutize(&project_path.path);
+                        let total = summaries.entry(path).or_default();
+                        total.error_count += summary.error_count;
+                        total.warning_count += summary.warning_count;
+                    }
+
+                    let mut output = String::new();
+                    for (path, summary) in summaries {
+                        if summary.error_count > 0 || summary.warning_count > 0 {
+                            writeln!(
+                                output,
+                                "{}: {} error(s), {} warning(s)",
+                                path.display(),
+                                summary.error_count,
+                                summary.warning_count
+                            )?;
+                        }
+                    }
+                    anyhow::Ok(output)
+                })??;
+
+                if output.is_empty() {
+                    Ok(text_response(
+                        "No errors or warnings found in the workspace",
+                    ))
+                } else {
+                    Ok(text_response(output))
+                }
+            }
+        }
+    }
+}
+
+/// Gets the text selected in the editor that's focused in the workspace, along with the path of its file.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetSelectionInput {}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetSelectionOutput {
+    /// The path of the file being edited, if it's been saved.
+    pub path: Option<String>,
+    pub selections: Vec<SelectionOutput>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SelectionOutput {
+    pub start: PositionOutput,
+    pub end: PositionOutput,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PositionOutput {
+    /// The 1-based line.
+    pub line: u32,
+    /// The 0-based column, in bytes.
+    pub column: u32,
+}
+
+impl From<Point> for PositionOutput {
+    fn from(point: Point) -> Self {
+        Self {
+            line: point.row + 1,
+            column: point.column,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GetSelectionTool {
+    workspace: WindowHandle<Workspace>,
+}
+
+impl McpServerTool for GetSelectionTool {
+    type Input = GetSelectionInput;
+    type Output = GetSelectionOutput;
+
+    const NAME: &'static str = "get_selection";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Get selection")
+    }
+
+    async fn run(
+        &self,
+        _: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<GetSelectionOutput>> {
+        let output = self.workspace.update(cx, |workspace, _, cx| {
+            ensure_trusted(workspace.project(), cx)?;
+            let editor = workspace
+                .active_item(cx)
+                .and_then(|item| item.act_as::<Editor>(cx))
+                .context("No editor is focused")?;
+
+            editor.update(cx, |editor, cx| {
+                let buffer = editor
+                    .buffer()
+                    .read(cx)
+                    .as_singleton()
+                    .context("The focused editor doesn't edit a single file")?;
+                let selections = editor.selections.all::<Point>(&editor.display_snapshot(cx));
+
+                let buffer = buffer.read(cx);
+                anyhow::Ok(GetSelectionOutput {
+                    path: buffer
+                        .file()
+                        .map(|file| file.full_path(cx).to_string_lossy().into_owned()),
+                    selections: selections
+                        .into_iter()
+                        .map(|selection| SelectionOutput {
+                            text: buffer
+                                .text_for_range(selection.start..selection.end)
+                                .collect(),
+                            start: selection.start.into(),
+                            end: selection.end.into(),
+                        })
+                        .collect(),
+                })
+            })
+        })??;
+
+        Ok(ToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&output)?,
+            }],
+            structured_content: output,
+        })
+    }
+}
+
+/// Runs one of the workspace's tasks in a terminal and waits for it to finish.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RunTaskInput {
+    /// The label of the task, as shown in Zed's task picker.
+    pub label: String,
+}
+
+#[derive(Clone)]
+pub struct RunTaskTool {
+    workspace: WindowHandle<Workspace>,
+}
+
+impl McpServerTool for RunTaskTool {
+    type Input = RunTaskInput;
+    type Output = ();
+
+    const NAME: &'static str = "run_task";
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations {
+            title: Some("Run task".into()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(true),
+        }
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        let task_contexts = self.workspace.update(cx, |workspace, window, cx| {
+            ensure_trusted(workspace.project(), cx)?;
+            anyhow::Ok(tasks_ui::task_contexts(workspace, window, cx))
+        })??;
+        let task_contexts = task_contexts.await;
+
+        let list_tasks = self.workspace.update(cx, |workspace, _, cx| {
+            let task_inventory = workspace
+                .project()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+                .context("Tasks aren't available in this workspace")?;
+            let language = task_contexts
+                .location()
+                .and_then(|location| location.buffer.read(cx).language_at(location.range.start));
+            anyhow::Ok(task_inventory.read(cx).list_tasks(
+                task_contexts.file(cx),
+                language,
+                task_contexts.worktree(),
+                cx,
+            ))
+        })??;
+        let tasks = list_tasks.await;
+
+        let Some((task_source_kind, task_template)) = tasks
+            .iter()
+            .find(|(_, task_template)| task_template.label == input.label)
+            .cloned()
+        else {
+            let labels = tasks
+                .iter()
+                .map(|(_, task_template)| task_template.label.as_str())
+                .collect::<Vec<_>>();
+            anyhow::bail!(
+                "No task is labeled {:?}. Available tasks: {}",
+                input.label,
+                labels.join(", ")
+            );
+        };
+
+        let exit_status = self.workspace.update(cx, |workspace, window, cx| {
+            let default_context = TaskContext::default();
+            let resolved_task = task_template
+                .resolve_task(
+                    &task_source_kind.to_id_base(),
+                    task_contexts.active_context().unwrap_or(&default_context),
+                )
+                .with_context(|| format!("Failed to resolve task {:?}", input.label))?;
+            let spawn_in_terminal = resolved_task.resolved.clone();
+
+            if let Some(task_inventory) = workspace
+                .project()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+            {
+                task_inventory.update(cx, |task_inventory, _| {
+                    task_inventory.task_scheduled(task_source_kind, resolved_task);
+                });
+            }
+            anyhow::Ok(workspace.spawn_in_terminal(spawn_in_terminal, window, cx))
+        })??;
+
+        match exit_status.await {
+            Some(Ok(status)) if status.success() => {
+                Ok(text_response(format!("Task {:?} succeeded", input.label)))
+            }
+            Some(Ok(status)) => match status.code() {
+                Some(code) => Err(anyhow!(
+                    "Task {:?} failed with exit code {code}",
+                    input.label
+                )),
+                None => Err(anyhow!("Task {:?} was terminated", input.label)),
+            },
+            Some(Err(error)) => Err(error.context(format!("Task {:?} failed", input.label))),
+            None => Err(anyhow!("Task {:?} was canceled", input.label)),
+        }
+    }
+}
+
+/// Replaces text in a file of the workspace. The edit isn't saved, and the user reviews it before keeping or rejecting it.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApplyEditInput {
+    /// The path of the file, either absolute or starting with the name of one of the workspace's root directories.
+    pub path: String,
+    /// The text to replace, which must appear exactly once in the file.
+    pub old_text: String,
+    /// The text to replace it with.
+    pub new_text: String,
+}
+
+#[derive(Clone)]
+pub struct ApplyEditTool {
+    workspace: WindowHandle<Workspace>,
+    action_log: Entity<ActionLog>,
+}
+
+impl McpServerTool for ApplyEditTool {
+    type Input = ApplyEditInput;
+    type Output = ();
+
+    const NAME: &'static str = "apply_edit";
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations {
+            title: Some("Apply edit".into()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        }
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        anyhow::ensure!(!input.old_text.is_empty(), "old_text can't be empty");
+
+        let (project_path, open_buffer) = self.workspace.update(cx, |workspace, _, cx| {
+            let project_path = project_path_for(workspace, &input.path, cx)?;
+            let open_buffer = workspace.project().update(cx, |project, cx| {
+                project.open_buffer(project_path.clone(), cx)
+            });
+            anyhow::Ok((project_path, open_buffer))
+        })??;
+        let buffer = open_buffer.await?;
+
+        self.workspace.update(cx, |workspace, window, cx| {
+            let text = buffer.read(cx).text();
+            let mut matches = text.match_indices(&input.old_text).map(|(ix, _)| ix);
+            let start = matches
+                .next()
+                .with_context(|| format!("old_text wasn't found in {}", input.path))?;
+            anyhow::ensure!(
+                matches.next().is_none(),
+                "old_text appears more than once in {}. Include more of the surrounding text to make it unique",
+                input.path
+            );
+            let end = start + input.old_text.len();
+
+            self.action_log
+                .update(cx, |action_log, cx| action_log.buffer_read(buffer.clone(), cx));
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit([(start..end, input.new_text.as_str())], None, cx)
+            });
+            self.action_log
+                .update(cx, |action_log, cx| action_log.buffer_edited(buffer.clone(), cx));
+
+            workspace
+                .open_path_preview(project_path, None, false, true, true, window, cx)
+                .detach_and_log_err(cx);
+            show_review_notification(workspace, cx);
+            anyhow::Ok(())
+        })??;
+
+        Ok(text_response(format!(
+            "Edited {}. The user will review the edit before keeping it",
+            input.path
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use collections::{HashMap, HashSet};
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Diagnostic, DiagnosticEntry, LanguageServerId, PointUtf16, Unclipped};
+    use project::{
+        FakeFs, Project,
+        trusted_worktrees::{self, PathTrust, TrustedWorktrees},
+    };
+    use serde_json::json;
+    use std::path::PathBuf;
+    use util::path;
+    use workspace::AppState;
+
+    use super::*;
+
+    const MAIN_RS: &str = "fn main() {\n    println!(\"hello\");\n}\n";
+
+    async fn init_test(cx: &mut TestAppContext) -> (Entity<Project>, WindowHandle<Workspace>) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            trusted_worktrees::init(HashMap::default(), None, None, cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "main.rs": MAIN_RS }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        (project, workspace)
+    }
+
+    fn trust_worktrees(project: &Entity<Project>, cx: &mut TestAppContext) {
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let trusted_worktrees = cx.update(|cx| TrustedWorktrees::try_get_global(cx).unwrap());
+        trusted_worktrees.update(cx, |trusted_worktrees, cx| {
+            trusted_worktrees.trust(
+                HashSet::from_iter([PathTrust::Worktree(worktree_id)]),
+                None,
+                cx,
+            )
+        });
+    }
+
+    fn response_text<T>(response: &ToolResponse<T>) -> &str {
+        match response.content.as_slice() {
+            [ToolResponseContent::Text { text }] => text,
+            content => panic!("expected a single text response, got {content:?}"),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_open_file_and_get_selection(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        let open_file = OpenFileTool { workspace };
+        let get_selection = GetSelectionTool { workspace };
+        let input = || OpenFileInput {
+            path: "project/main.rs".into(),
+            line: Some(2),
+        };
+
+        let error = open_file
+            .run(input(), &mut cx.to_async())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("isn't trusted"), "{error}");
+
+        trust_worktrees(&project, cx);
+        let response = open_file.run(input(), &mut cx.to_async()).await.unwrap();
+        assert_eq!(response_text(&response), "Opened project/main.rs");
+        cx.run_until_parked();
+
+        let output = get_selection
+            .run(GetSelectionInput {}, &mut cx.to_async())
+            .await
+            .unwrap()
+            .structured_content;
+        assert_eq!(output.path.as_deref(), Some("project/main.rs"));
+        let [selection] = output.selections.as_slice() else {
+            panic!("expected a single selection, got {:?}", output.selections);
+        };
+        assert_eq!((selection.start.line, selection.start.column), (2, 0));
+        assert_eq!((selection.end.line, selection.end.column), (2, 0));
+        assert_eq!(selection.text, "");
+
+        let error = open_file
+            .run(
+                OpenFileInput {
+                    path: "other/main.rs".into(),
+                    line: None,
+                },
+                &mut cx.to_async(),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Could not find path"), "{error}");
+    }
+
+    #[gpui::test]
+    async fn test_get_diagnostics(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        let tool = GetDiagnosticsTool { workspace };
+        trust_worktrees(&project, cx);
+
+        let response = tool
+            .run(GetDiagnosticsInput { path: None }, &mut cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(
+            response_text(&response),
+            "No errors or warnings found in the workspace"
+        );
+
+        project.update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store
+                    .update_diagnostic_entries(
+                        LanguageServerId(0),
+                        PathBuf::from(path!("/project/main.rs")),
+                        None,
+                        None,
+                        vec![
+                            DiagnosticEntry {
+                                range: Unclipped(PointUtf16::new(1, 4))
+                                    ..Unclipped(PointUtf16::new(1, 12)),
+                                diagnostic: Diagnostic {
+                                    severity: DiagnosticSeverity::ERROR,
+                                    message: "cannot find macro".to_string(),
+                                    group_id: 0,
+                                    is_primary: true,
+                                    ..Diagnostic::default()
+                                },
+                            },
+                            DiagnosticEntry {
+                                range: Unclipped(PointUtf16::new(0, 3))
+                                    ..Unclipped(PointUtf16::new(0, 7)),
+                                diagnostic: Diagnostic {
+                                    severity: DiagnosticSeverity::HINT,
+                                    message: "consider renaming".to_string(),
+                                    group_id: 1,
+                                    is_primary: true,
+                                    ..Diagnostic::default()
+                                },
+                            },
+                        ],
+                        cx,
+                    )
+                    .unwrap();
+            });
+        });
+
+        let response = tool
+            .run(GetDiagnosticsInput { path: None }, &mut cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(
+            response_text(&response),
+            format!("{}: 1 error(s), 0 warning(s)\n", path!("/project/main.rs"))
+        );
+
+        let response = tool
+            .run(
+                GetDiagnosticsInput {
+                    path: Some("project/main.rs".into()),
+                },
+                &mut cx.to_async(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response_text(&response),
+            "error at line 2: cannot find macro\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_run_task(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        let tool = RunTaskTool { workspace };
+        let input = || RunTaskInput {
+            label: "deploy".into(),
+        };
+
+        let error = tool.run(input(), &mut cx.to_async()).await.unwrap_err();
+        assert!(error.to_string().contains("isn't trusted"), "{error}");
+
+        trust_worktrees(&project, cx);
+        let error = tool.run(input(), &mut cx.to_async()).await.unwrap_err();
+        assert!(
+            error.to_string().contains("No task is labeled \"deploy\""),
+            "{error}"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_apply_edit_requires_trust_and_review(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = ApplyEditTool {
+            workspace,
+            action_log: action_log.clone(),
+        };
+        let input = || ApplyEditInput {
+            path: "project/main.rs".into(),
+            old_text: "hello".into(),
+            new_text: "goodbye".into(),
+        };
+
+        let error = tool.run(input(), &mut cx.to_async()).await.unwrap_err();
+        assert!(error.to_string().contains("isn't trusted"), "{error}");
+        assert!(action_log.read_with(cx, |action_log, cx| {
+            action_log.changed_buffers(cx).is_empty()
+        }));
+
+        trust_worktrees(&project, cx);
+        tool.run(input(), &mut cx.to_async()).await.unwrap();
+        cx.run_until_parked();
+        let buffer = action_log.read_with(cx, |action_log, cx| {
+            action_log
+                .changed_buffers(cx)
+                .into_keys()
+                .next()
+                .expect("the edit should be tracked for review")
+        });
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn main() {\n    println!(\"goodbye\");\n}\n"
+        );
+
+        let error = tool
+            .run(
+                ApplyEditInput {
+                    path: "project/main.rs".into(),
+                    old_text: "missing".into(),
+                    new_text: "".into(),
+                },
+                &mut cx.to_async(),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("wasn't found"), "{error}");
+
+        action_log
+            .update(cx, |action_log, cx| action_log.reject_all_edits(None, cx))
+            .await;
+        cx.run_until_parked();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), MAIN_RS);
+    }
+}
@@ -328,9 +328,20 @@ impl ExampleContext {
                         "{}Bug: Tool confirmation should not be required in eval",
                         log_prefix
                     ),
+                    ThreadEvent::ToolCallInputRequest(request) => {
+                        println!(
+                            "{log_prefix} Declined input request from {}: {}",
+                            request.request.requester, request.request.message
+                        );
+                        request
+                            .response
+                            .send(acp_thread::InputResponse::Decline)
+                            .ok();
+                    }
                     ThreadEvent::Retry(status) => {
                         println!("{log_prefix} Got retry: {status:?}");
                     }
+                    ThreadEvent::Plan(_) => {}
                     ThreadEvent::Stop(stop_reason) => match stop_reason {
                         acp::StopReason::EndTurn => {}
                         acp::StopReason::MaxTokens => {
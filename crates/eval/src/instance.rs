@@ -560,6 +560,7 @@ impl ExampleInstance {
                 tool_choice: None,
                 stop: Vec::new(),
                 thinking_allowed: true,
+                max_output_tokens: None,
             };
 
             let model = model.clone();
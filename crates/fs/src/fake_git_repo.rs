@@ -4,15 +4,21 @@ use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid, RunHook,
+    bisect::{BisectMark, BisectStatus},
     blame::Blame,
+    log::{LogEntry, LogSearch},
+    rebase::{RebaseStatus, RebaseTodoAction, RebaseTodoEntry},
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        AskPassDelegate, Branch, CommitDetails, CommitOptions, ConflictStages, FetchOptions,
+        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        SequencerCommand, SequencerControl, SequencerOutcome, Tag, Worktree,
     },
+    signing::CommitSignature,
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
         UnmergedStatus,
     },
+    submodule::{Submodule, SubmoduleStatus},
 };
 use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task, TaskLabel};
 use ignore::gitignore::GitignoreBuilder;
@@ -45,6 +51,7 @@ pub struct FakeGitRepositoryState {
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
+    pub conflict_stages: HashMap<RepoPath, ConflictStages>,
     // everything in commit contents is in oids
     pub merge_base_contents: HashMap<RepoPath, Oid>,
     pub oids: HashMap<Oid, String>,
@@ -55,6 +62,15 @@ pub struct FakeGitRepositoryState {
     pub remotes: HashMap<String, String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// Commits returned by `rebase_todo`, regardless of the requested upstream.
+    pub rebase_todo: Vec<RebaseTodoEntry>,
+    pub rebase: Option<RebaseStatus>,
+    /// The repository-wide log, newest commit first.
+    pub log: Vec<LogEntry>,
+    /// Tags, most recently created first.
+    pub tags: Vec<Tag>,
+    pub submodules: Vec<Submodule>,
+    pub bisect: Option<BisectStatus>,
 }
 
 impl FakeGitRepositoryState {
@@ -63,6 +79,7 @@ impl FakeGitRepositoryState {
             event_emitter,
             head_contents: Default::default(),
             index_contents: Default::default(),
+            conflict_stages: Default::default(),
             unmerged_paths: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
@@ -72,8 +89,35 @@ impl FakeGitRepositoryState {
             merge_base_contents: Default::default(),
             oids: Default::default(),
             remotes: HashMap::default(),
+            rebase_todo: Vec::new(),
+            rebase: None,
+            log: Vec::new(),
+            tags: Vec::new(),
+            submodules: Vec::new(),
+            bisect: None,
         }
     }
+
+    /// Applies rebase commands until one that stops the rebase, clearing the rebase once all
+    /// commands have been applied.
+    fn advance_rebase(&mut self) {
+        let Some(rebase) = self.rebase.as_mut() else {
+            return;
+        };
+        rebase.stopped_sha = None;
+        while !rebase.remaining.is_empty() {
+            let entry = rebase.remaining.remove(0);
+            let stops = entry.action == RebaseTodoAction::Edit;
+            if stops {
+                rebase.stopped_sha = Some(entry.sha.clone());
+            }
+            rebase.done.push(entry);
+            if stops {
+                return;
+            }
+        }
+        self.rebase = None;
+    }
 }
 
 impl FakeGitRepository {
@@ -96,6 +140,16 @@ impl FakeGitRepository {
 impl GitRepository for FakeGitRepository {
     fn reload_index(&self) {}
 
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        self.with_state_async(false, move |state| {
+            state
+                .conflict_stages
+                .get(&path)
+                .cloned()
+                .with_context(|| format!("{} is not conflicted", path.as_unix_str()))
+        })
+    }
+
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let fut = self.with_state_async(false, move |state| {
             state
@@ -206,14 +260,30 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>> {
-        async {
-            Ok(CommitDetails {
-                sha: commit.into(),
-                message: "initial commit".into(),
-                ..Default::default()
+        self.with_state_async(false, move |state| {
+            let entry = state
+                .log
+                .iter()
+                .find(|entry| commit == "HEAD" || entry.sha.starts_with(commit.as_str()));
+            Ok(match entry {
+                Some(entry) => CommitDetails {
+                    sha: entry.sha.clone(),
+                    message: entry.subject.clone(),
+                    commit_timestamp: entry.commit_timestamp,
+                    author_email: entry.author_email.clone(),
+                    author_name: entry.author_name.clone(),
+                },
+                None => CommitDetails {
+                    sha: commit.into(),
+                    message: "initial commit".into(),
+                    ..Default::default()
+                },
             })
-        }
-        .boxed()
+        })
+    }
+
+    fn commit_signature(&self, _commit: String) -> BoxFuture<'_, Result<Option<CommitSignature>>> {
+        async { Ok(None) }.boxed()
     }
 
     fn reset(
@@ -495,6 +565,35 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn log(
+        &self,
+        search: Option<LogSearch>,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .log
+                .iter()
+                .skip_while(|entry| match &search {
+                    Some(LogSearch::Sha(sha)) => !entry.sha.starts_with(sha.as_str()),
+                    _ => false,
+                })
+                .filter(|entry| match &search {
+                    Some(LogSearch::Author(author)) => {
+                        entry.author_name.contains(author.as_str())
+                            || entry.author_email.contains(author.as_str())
+                    }
+                    Some(LogSearch::Message(message)) => entry.subject.contains(message.as_str()),
+                    _ => true,
+                })
+                .skip(skip)
+                .take(limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect())
+        })
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -577,13 +676,35 @@ impl GitRepository for FakeGitRepository {
 
     fn commit(
         &self,
-        _message: gpui::SharedString,
+        message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
         _options: CommitOptions,
         _askpass: AskPassDelegate,
         _env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>> {
-        async { Ok(()) }.boxed()
+        let sha = Oid::random(&mut self.executor.rng()).to_string();
+        // Record the commit in the log, so that tests can check what was committed.
+        self.with_state_async(false, move |state| {
+            let parents = state.log.first().map(|parent| parent.sha.clone());
+            state.log.insert(
+                0,
+                LogEntry {
+                    sha: sha.into(),
+                    parents: parents.into_iter().collect(),
+                    subject: message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                        .into(),
+                    commit_timestamp: 0,
+                    author_name: SharedString::default(),
+                    author_email: SharedString::default(),
+                    refs: Vec::new(),
+                },
+            );
+            Ok(())
+        })
     }
 
     fn run_hook(
@@ -628,6 +749,97 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn rebase_status(&self) -> BoxFuture<'_, Result<Option<RebaseStatus>>> {
+        self.with_state_async(false, |state| Ok(state.rebase.clone()))
+    }
+
+    fn rebase_todo(&self, _upstream: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        self.with_state_async(false, |state| Ok(state.rebase_todo.clone()))
+    }
+
+    fn rebase_start(
+        &self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.rebase.is_some() {
+                bail!("a rebase is already in progress");
+            }
+            state.rebase = Some(RebaseStatus {
+                head_name: state.current_branch_name.clone().map(Into::into),
+                onto: Some(upstream.into()),
+                remaining: todo,
+                interactive: true,
+                ..Default::default()
+            });
+            state.advance_rebase();
+            Ok(())
+        })
+    }
+
+    fn rebase_edit_todo(&self, todo: Vec<RebaseTodoEntry>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let rebase = state.rebase.as_mut().context("no rebase in progress")?;
+            rebase.remaining = todo;
+            Ok(())
+        })
+    }
+
+    fn rebase_continue(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            if state.rebase.is_none() {
+                bail!("no rebase in progress");
+            }
+            state.advance_rebase();
+            Ok(())
+        })
+    }
+
+    fn rebase_skip(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            let rebase = state.rebase.as_mut().context("no rebase in progress")?;
+            rebase.done.pop();
+            state.advance_rebase();
+            Ok(())
+        })
+    }
+
+    fn rebase_abort(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            state.rebase.take().context("no rebase in progress")?;
+            Ok(())
+        })
+    }
+
+    fn cherry_pick(
+        &self,
+        _commits: Vec<String>,
+        _no_commit: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        unimplemented!()
+    }
+
+    fn revert(
+        &self,
+        _commits: Vec<String>,
+        _no_commit: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        unimplemented!()
+    }
+
+    fn sequencer_control(
+        &self,
+        _command: SequencerCommand,
+        _control: SequencerControl,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        unimplemented!()
+    }
+
     fn get_all_remotes(&self) -> BoxFuture<'_, Result<Vec<Remote>>> {
         self.with_state_async(false, move |state| {
             let remotes = state
@@ -736,6 +948,184 @@ impl GitRepository for FakeGitRepository {
             Ok(())
         })
     }
+
+    fn tags(&self, points_at: Option<String>) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .tags
+                .iter()
+                .filter(|tag| points_at.as_ref().is_none_or(|sha| tag.sha.as_ref() == sha))
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name.as_ref() == name) {
+                bail!("tag '{name}' already exists");
+            }
+            let target = target.unwrap_or_else(|| "HEAD".into());
+            let sha = state.refs.get(&target).cloned().unwrap_or(target);
+            state.tags.insert(
+                0,
+                Tag {
+                    name: name.into(),
+                    sha: sha.into(),
+                    message: message.map(|message| {
+                        message
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string()
+                            .into()
+                    }),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let Some(ix) = state.tags.iter().position(|tag| tag.name.as_ref() == name) else {
+                bail!("no such tag: {name}");
+            };
+            state.tags.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn push_tag(
+        &self,
+        _name: String,
+        _remote_name: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        self.with_state_async(false, move |state| Ok(state.submodules.clone()))
+    }
+
+    fn init_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(false, move |state| {
+            for path in paths {
+                if !state
+                    .submodules
+                    .iter()
+                    .any(|submodule| submodule.path == path)
+                {
+                    bail!("no submodule at {path:?}");
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.init_submodules(paths, env)
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        self.with_state_async(true, move |state| {
+            for submodule in &mut state.submodules {
+                if paths.is_empty() || paths.contains(&submodule.path) {
+                    submodule.checked_out_sha = Some(submodule.recorded_sha.clone());
+                    submodule.status = SubmoduleStatus::UpToDate;
+                }
+            }
+            Ok(git::repository::RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn bisect_status(&self) -> BoxFuture<'_, Result<Option<BisectStatus>>> {
+        self.with_state_async(false, move |state| Ok(state.bisect.clone()))
+    }
+
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.bisect.is_some() {
+                bail!("already bisecting");
+            }
+            state.bisect = Some(BisectStatus {
+                start: state.current_branch_name.clone().map(SharedString::from),
+                bad: Some(bad.into()),
+                good: good.into_iter().map(SharedString::from).collect(),
+                ..Default::default()
+            });
+            Ok(())
+        })
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let Some(bisect) = state.bisect.as_mut() else {
+                bail!("not bisecting");
+            };
+            let commit = commit
+                .map(SharedString::from)
+                .or_else(|| bisect.current.clone())
+                .context("no commit to mark")?;
+            match mark {
+                BisectMark::Good => bisect.good.push(commit),
+                BisectMark::Bad => bisect.bad = Some(commit),
+                BisectMark::Skip => bisect.skipped.push(commit),
+            }
+            Ok(())
+        })
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state.bisect = None;
+            Ok(())
+        })
+    }
+
+    fn bisect_run(
+        &self,
+        _command: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<String>> {
+        unimplemented!()
+    }
 }
 
 #[cfg(test)]
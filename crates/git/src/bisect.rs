@@ -0,0 +1,199 @@
+use anyhow::Result;
+use gpui::SharedString;
+use std::path::Path;
+
+pub const BISECT_START: &str = "BISECT_START";
+pub const BISECT_LOG: &str = "BISECT_LOG";
+pub const BISECT_TERMS: &str = "BISECT_TERMS";
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum BisectMark {
+    /// The commit does not have the bug.
+    Good,
+    /// The commit has the bug.
+    Bad,
+    /// The commit can't be tested, so another one nearby should be tested instead.
+    Skip,
+}
+
+impl BisectMark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BisectMark::Good => "good",
+            BisectMark::Bad => "bad",
+            BisectMark::Skip => "skip",
+        }
+    }
+}
+
+/// How much of the history is left to search.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct BisectRemaining {
+    /// The number of commits that may still be the first bad commit.
+    pub commits: u32,
+    /// The approximate number of commits left to test.
+    pub steps: u32,
+}
+
+/// The state of a bisect that is currently in progress.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct BisectStatus {
+    /// The branch or commit that was checked out when the bisect started, restored on reset.
+    pub start: Option<SharedString>,
+    /// The newest commit known to be bad.
+    pub bad: Option<SharedString>,
+    /// The commits known to be good.
+    pub good: Vec<SharedString>,
+    /// The commits that were skipped.
+    pub skipped: Vec<SharedString>,
+    /// The commit that is checked out for testing.
+    pub current: Option<SharedString>,
+    /// The part of the history left to search, once both a good and a bad commit are known.
+    pub remaining: Option<BisectRemaining>,
+    /// The first bad commit, once the bisect has found it.
+    pub first_bad: Option<SharedString>,
+}
+
+impl BisectStatus {
+    /// Reads the state of an in-progress bisect from a repository's git directory.
+    ///
+    /// The checked-out commit and the remaining range depend on the commit graph and are left
+    /// for the caller to fill in.
+    pub fn load(git_dir: &Path) -> Result<Option<Self>> {
+        let Ok(start) = std::fs::read_to_string(git_dir.join(BISECT_START)) else {
+            return Ok(None);
+        };
+        let start = start.trim();
+
+        let log = std::fs::read_to_string(git_dir.join(BISECT_LOG)).unwrap_or_default();
+        let terms = std::fs::read_to_string(git_dir.join(BISECT_TERMS)).unwrap_or_default();
+        let mut terms = terms.lines();
+        let bad_term = terms.next().unwrap_or("bad");
+        let good_term = terms.next().unwrap_or("good");
+
+        let mut status = Self::parse_log(&log, bad_term, good_term);
+        status.start = (!start.is_empty()).then(|| start.to_string().into());
+        Ok(Some(status))
+    }
+
+    /// Parses the marks recorded in `BISECT_LOG`, using the given terms for bad and good commits.
+    fn parse_log(log: &str, bad_term: &str, good_term: &str) -> Self {
+        let mut status = Self::default();
+        for line in log.lines() {
+            if let Some(first_bad) = line.strip_prefix("# first bad commit: [") {
+                status.first_bad = first_bad
+                    .split_once(']')
+                    .map(|(sha, _)| sha.to_string().into());
+                continue;
+            }
+            let Some(mark) = line.strip_prefix("git bisect ") else {
+                continue;
+            };
+            let Some((term, sha)) = mark.split_once(' ') else {
+                continue;
+            };
+            let sha = SharedString::from(sha.trim().to_string());
+            if term == bad_term {
+                status.bad = Some(sha);
+            } else if term == good_term {
+                status.good.push(sha);
+            } else if term == "skip" {
+                status.skipped.push(sha);
+            }
+        }
+        status
+    }
+}
+
+/// Parses the output of `git rev-list --bisect-vars`.
+pub fn parse_bisect_vars(output: &str) -> Option<BisectRemaining> {
+    let mut commits = None;
+    let mut steps = None;
+    for line in output.lines() {
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        match name {
+            "bisect_all" => commits = value.parse().ok(),
+            "bisect_steps" => steps = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(BisectRemaining {
+        commits: commits?,
+        steps: steps?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_load_bisect_status() {
+        let git_dir = tempfile::tempdir().unwrap();
+        assert_eq!(BisectStatus::load(git_dir.path()).unwrap(), None);
+
+        std::fs::write(git_dir.path().join(BISECT_START), "main\n").unwrap();
+        std::fs::write(
+            git_dir.path().join(BISECT_LOG),
+            "\
+git bisect start 'main' 'v1.0'
+# bad: [2222222] Break things
+git bisect bad 2222222
+# good: [1111111] Release 1.0
+git bisect good 1111111
+# skip: [3333333] Does not build
+git bisect skip 3333333
+# first bad commit: [4444444] Really break things
+",
+        )
+        .unwrap();
+
+        let status = BisectStatus::load(git_dir.path()).unwrap().unwrap();
+        assert_eq!(
+            status,
+            BisectStatus {
+                start: Some("main".into()),
+                bad: Some("2222222".into()),
+                good: vec!["1111111".into()],
+                skipped: vec!["3333333".into()],
+                current: None,
+                remaining: None,
+                first_bad: Some("4444444".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_log_with_custom_terms() {
+        let status = BisectStatus::parse_log(
+            "git bisect new 2222222\ngit bisect old 1111111\ngit bisect old 0000000\n",
+            "new",
+            "old",
+        );
+        assert_eq!(status.bad, Some("2222222".into()));
+        assert_eq!(status.good, vec!["1111111".into(), "0000000".into()]);
+    }
+
+    #[test]
+    fn test_parse_bisect_vars() {
+        let output = "\
+bisect_rev='5555555555555555555555555555555555555555'
+bisect_nr=3
+bisect_good=1
+bisect_bad=2
+bisect_all=7
+bisect_steps=2
+";
+        assert_eq!(
+            parse_bisect_vars(output),
+            Some(BisectRemaining {
+                commits: 7,
+                steps: 2
+            })
+        );
+        assert_eq!(parse_bisect_vars(""), None);
+    }
+}
@@ -0,0 +1,152 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use collections::HashMap;
+use http_client::HttpClient;
+use parking_lot::Mutex;
+use url::Url;
+
+use crate::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequestDetails, PullRequestState, RemoteUrl, ReviewComment,
+};
+
+/// A hosting provider that serves pull requests from memory, for tests.
+///
+/// It handles remotes on its base URL's host, like `https://git.example.com/owner/repo.git`.
+pub struct FakeGitHostingProvider {
+    base_url: Url,
+    state: Mutex<FakeGitHostingProviderState>,
+}
+
+#[derive(Default)]
+struct FakeGitHostingProviderState {
+    pull_requests: Vec<PullRequestDetails>,
+    review_comments: HashMap<u32, Vec<ReviewComment>>,
+}
+
+impl FakeGitHostingProvider {
+    pub fn new(base_url: &str) -> Arc<Self> {
+        Arc::new(Self {
+            base_url: Url::parse(base_url).unwrap(),
+            state: Default::default(),
+        })
+    }
+
+    pub fn set_pull_requests(&self, pull_requests: Vec<PullRequestDetails>) {
+        self.state.lock().pull_requests = pull_requests;
+    }
+
+    pub fn set_review_comments(&self, number: u32, comments: Vec<ReviewComment>) {
+        self.state.lock().review_comments.insert(number, comments);
+    }
+
+    /// Builds an open pull request on this provider with the given number, title and branch.
+    pub fn pull_request(&self, number: u32, title: &str, head_branch: &str) -> PullRequestDetails {
+        PullRequestDetails {
+            number,
+            title: title.to_string().into(),
+            url: self
+                .base_url
+                .join(&format!("owner/repo/pull/{number}"))
+                .unwrap(),
+            author: "octocat".into(),
+            state: PullRequestState::Open,
+            head_branch: head_branch.to_string().into(),
+            head_sha: format!("{number:040x}").into(),
+            checks: None,
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostingProvider for FakeGitHostingProvider {
+    fn name(&self) -> String {
+        "Fake".to_string()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        self.base_url
+            .join(&format!(
+                "{}/{}/commit/{}",
+                remote.owner, remote.repo, params.sha
+            ))
+            .unwrap()
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        self.base_url
+            .join(&format!(
+                "{}/{}/blob/{}/{}",
+                remote.owner, remote.repo, params.sha, params.path
+            ))
+            .unwrap()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        format!("L{line}")
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        format!("L{start_line}-L{end_line}")
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let url = RemoteUrl::from_str(url).ok()?;
+        if url.host_str()? != self.base_url.host_str()? {
+            return None;
+        }
+        let mut path_segments = url.path_segments()?;
+        let owner = path_segments.next()?;
+        let repo = path_segments.next()?.trim_end_matches(".git");
+        Some(ParsedGitRemote {
+            owner: owner.into(),
+            repo: repo.into(),
+        })
+    }
+
+    fn supports_pull_requests(&self) -> bool {
+        true
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        Some(format!("refs/pull/{number}/head"))
+    }
+
+    async fn list_pull_requests(
+        &self,
+        _remote: &ParsedGitRemote,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<PullRequestDetails>> {
+        Ok(self.state.lock().pull_requests.clone())
+    }
+
+    async fn pull_request_review_comments(
+        &self,
+        _remote: &ParsedGitRemote,
+        number: u32,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewComment>> {
+        Ok(self
+            .state
+            .lock()
+            .review_comments
+            .get(&number)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
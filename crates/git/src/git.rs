@@ -1,10 +1,17 @@
+pub mod bisect;
 pub mod blame;
 pub mod commit;
+#[cfg(any(test, feature = "test-support"))]
+pub mod fake_hosting_provider;
 mod hosting_provider;
+pub mod log;
+pub mod rebase;
 mod remote;
 pub mod repository;
+pub mod signing;
 pub mod stash;
 pub mod status;
+pub mod submodule;
 
 pub use crate::hosting_provider::*;
 pub use crate::remote::*;
@@ -37,6 +44,10 @@ actions!(
         StageAndNext,
         /// Unstages the current hunk and moves to the next one.
         UnstageAndNext,
+        /// Stages only the selected lines of the hunks intersecting the selection.
+        StageSelectedLines,
+        /// Unstages only the selected lines of the hunks intersecting the selection.
+        UnstageSelectedLines,
         /// Restores the selected hunks to their original state.
         #[action(deprecated_aliases = ["editor::RevertSelectedHunks"])]
         Restore,
@@ -77,6 +88,28 @@ actions!(
         Pull,
         /// Pulls changes from the remote repository with rebase.
         PullRebase,
+        /// Opens the todo list editor to interactively rebase the current branch.
+        InteractiveRebase,
+        /// Continues the rebase in progress after resolving conflicts or editing a commit.
+        RebaseContinue,
+        /// Skips the commit the rebase in progress stopped at.
+        RebaseSkip,
+        /// Aborts the rebase in progress and restores the original branch.
+        RebaseAbort,
+        /// Continues the cherry-pick or revert in progress after resolving conflicts.
+        SequencerContinue,
+        /// Skips the commit the cherry-pick or revert in progress stopped at.
+        SequencerSkip,
+        /// Aborts the cherry-pick or revert in progress and restores the original branch.
+        SequencerAbort,
+        /// Opens the bisect view to find the commit that introduced a bug.
+        Bisect,
+        /// Opens the three-way merge editor for the conflicted file in the active editor.
+        OpenMergeEditor,
+        /// Lists the open pull requests of the repository and checks out the selected one.
+        CheckoutPullRequest,
+        /// Shows or hides the review comments of the current branch's pull request in the active editor.
+        ToggleReviewComments,
         /// Fetches changes from the remote repository.
         Fetch,
         /// Fetches changes from a specific remote.
@@ -87,6 +120,8 @@ actions!(
         Amend,
         /// Enable the --signoff option.
         Signoff,
+        /// Enable the --gpg-sign option, signing commits even when `commit.gpgsign` is not set.
+        GpgSign,
         /// Cancels the current git operation.
         Cancel,
         /// Expands the commit message editor.
@@ -97,6 +132,8 @@ actions!(
         Init,
         /// Opens all modified files in the editor.
         OpenModifiedFiles,
+        /// Opens the repository-wide commit log.
+        CommitLog,
         /// Clones a repository.
         Clone,
         /// Adds a file to .gitignore.
@@ -125,6 +162,36 @@ pub struct RestoreFile {
     pub skip_prompt: bool,
 }
 
+/// Applies the changes introduced by existing commits onto the current branch.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = git)]
+#[serde(deny_unknown_fields)]
+pub struct CherryPick {
+    /// The commits to cherry-pick, either single revisions or ranges such as `main~3..main`.
+    ///
+    /// Default: the commit shown in the active commit view.
+    #[serde(default)]
+    pub commits: Vec<String>,
+    /// Whether to leave the changes in the working tree and index instead of committing them.
+    #[serde(default)]
+    pub no_commit: bool,
+}
+
+/// Creates commits that undo the changes introduced by existing commits.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = git)]
+#[serde(deny_unknown_fields)]
+pub struct Revert {
+    /// The commits to revert, either single revisions or ranges such as `main~3..main`.
+    ///
+    /// Default: the commit shown in the active commit view.
+    #[serde(default)]
+    pub commits: Vec<String>,
+    /// Whether to leave the changes in the working tree and index instead of committing them.
+    #[serde(default)]
+    pub no_commit: bool,
+}
+
 /// The length of a Git short SHA.
 pub const SHORT_SHA_LENGTH: usize = 7;
 
@@ -17,6 +17,70 @@ pub struct PullRequest {
     pub url: Url,
 }
 
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum PullRequestState {
+    Open,
+    Draft,
+    Merged,
+    Closed,
+}
+
+impl PullRequestState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PullRequestState::Open => "Open",
+            PullRequestState::Draft => "Draft",
+            PullRequestState::Merged => "Merged",
+            PullRequestState::Closed => "Closed",
+        }
+    }
+}
+
+/// The combined result of the checks that ran on a pull request's head commit.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum PullRequestChecks {
+    Pending,
+    Passing,
+    Failing,
+}
+
+impl PullRequestChecks {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PullRequestChecks::Pending => "Checks pending",
+            PullRequestChecks::Passing => "Checks passing",
+            PullRequestChecks::Failing => "Checks failing",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PullRequestDetails {
+    pub number: u32,
+    pub title: SharedString,
+    pub url: Url,
+    pub author: SharedString,
+    pub state: PullRequestState,
+    /// The name of the branch the pull request was opened from.
+    pub head_branch: SharedString,
+    pub head_sha: SharedString,
+    /// The status of the checks on the head commit, when the provider reports them.
+    pub checks: Option<PullRequestChecks>,
+}
+
+/// A review comment left on a line of a pull request's diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub path: RepoPath,
+    /// The zero-based line the comment is attached to in the pull request's head commit, or
+    /// `None` if later changes made the comment outdated.
+    pub row: Option<u32>,
+    pub author: SharedString,
+    pub body: SharedString,
+    pub url: Option<Url>,
+}
+
 #[derive(Clone)]
 pub struct GitRemote {
     pub host: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
@@ -50,6 +114,47 @@ impl GitRemote {
             .ok()
             .flatten()
     }
+
+    pub fn host_supports_pull_requests(&self) -> bool {
+        self.host.supports_pull_requests()
+    }
+
+    pub async fn pull_requests(
+        &self,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<PullRequestDetails>> {
+        self.host
+            .list_pull_requests(&self.parsed_remote(), client)
+            .await
+    }
+
+    pub async fn pull_request_for_branch(
+        &self,
+        head_owner: &str,
+        branch: &str,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequestDetails>> {
+        self.host
+            .pull_request_for_branch(&self.parsed_remote(), head_owner, branch, client)
+            .await
+    }
+
+    pub async fn review_comments(
+        &self,
+        pull_request: u32,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewComment>> {
+        self.host
+            .pull_request_review_comments(&self.parsed_remote(), pull_request, client)
+            .await
+    }
+
+    fn parsed_remote(&self) -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: self.owner.as_ref().into(),
+            repo: self.repo.as_ref().into(),
+        }
+    }
 }
 
 pub struct BuildCommitPermalinkParams<'a> {
@@ -134,6 +239,51 @@ pub trait GitHostingProvider {
     ) -> Result<Option<Url>> {
         Ok(None)
     }
+
+    /// Returns whether this provider can list pull requests and their review comments.
+    fn supports_pull_requests(&self) -> bool {
+        false
+    }
+
+    /// Returns the ref that the head of the given pull request can be fetched from, including
+    /// pull requests opened from forks.
+    fn pull_request_ref(&self, _number: u32) -> Option<String> {
+        None
+    }
+
+    /// Returns the open pull requests of the repository, without their checks.
+    async fn list_pull_requests(
+        &self,
+        _remote: &ParsedGitRemote,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<PullRequestDetails>> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the open pull request opened from the given branch of `head_owner`'s repository,
+    /// which differs from the remote's owner when the branch was pushed to a fork.
+    async fn pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        _head_owner: &str,
+        branch: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequestDetails>> {
+        Ok(self
+            .list_pull_requests(remote, http_client)
+            .await?
+            .into_iter()
+            .find(|pull_request| pull_request.head_branch.as_ref() == branch))
+    }
+
+    async fn pull_request_review_comments(
+        &self,
+        _remote: &ParsedGitRemote,
+        _number: u32,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewComment>> {
+        Ok(Vec::new())
+    }
 }
 
 #[derive(Default, Deref, DerefMut)]
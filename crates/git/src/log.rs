@@ -0,0 +1,306 @@
+use gpui::SharedString;
+
+/// A commit in the repository-wide log.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    pub parents: Vec<SharedString>,
+    pub subject: SharedString,
+    pub commit_timestamp: i64,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// The refs pointing at this commit.
+    pub refs: Vec<LogRef>,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum LogRefKind {
+    Head,
+    LocalBranch,
+    RemoteBranch,
+    Tag,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogRef {
+    pub kind: LogRefKind,
+    /// The ref's short name, e.g. `main`, `origin/main` or `v1.0`.
+    pub name: SharedString,
+}
+
+/// Restricts the log to commits matching a query.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum LogSearch {
+    /// Commits whose author name or email matches the pattern.
+    Author(String),
+    /// Commits whose message matches the pattern.
+    Message(String),
+    /// The history starting at the given commit.
+    Sha(String),
+}
+
+/// Parses the output of `git log --decorate=full --format=%D` for a single commit.
+pub fn parse_decorations(decorations: &str) -> Vec<LogRef> {
+    let mut refs = Vec::new();
+    for decoration in decorations.split(", ") {
+        let decoration = decoration.trim();
+        let decoration = match decoration.strip_prefix("HEAD -> ") {
+            Some(branch) => {
+                refs.push(LogRef {
+                    kind: LogRefKind::Head,
+                    name: "HEAD".into(),
+                });
+                branch
+            }
+            None => decoration,
+        };
+
+        let (kind, name) = if decoration == "HEAD" {
+            (LogRefKind::Head, decoration)
+        } else if let Some(tag) = decoration.strip_prefix("tag: ") {
+            (
+                LogRefKind::Tag,
+                tag.strip_prefix("refs/tags/").unwrap_or(tag),
+            )
+        } else if let Some(branch) = decoration.strip_prefix("refs/heads/") {
+            (LogRefKind::LocalBranch, branch)
+        } else if let Some(branch) = decoration.strip_prefix("refs/remotes/") {
+            // Symbolic refs such as `origin/HEAD` only duplicate the branch they point at.
+            if branch.ends_with("/HEAD") {
+                continue;
+            }
+            (LogRefKind::RemoteBranch, branch)
+        } else {
+            continue;
+        };
+        refs.push(LogRef {
+            kind,
+            name: name.to_string().into(),
+        });
+    }
+    refs
+}
+
+/// How a single row of the commit graph is drawn.
+///
+/// Lanes are columns of the graph; each line segment connects a lane at one edge of the row to a
+/// lane at its vertical middle, where the row's commit is drawn.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphRow {
+    /// The lane containing this row's commit.
+    pub lane: usize,
+    /// Segments from the top of the row to its middle, as `(top_lane, middle_lane)` pairs.
+    pub upper: Vec<(usize, usize)>,
+    /// Segments from the middle of the row to its bottom, as `(middle_lane, bottom_lane)` pairs.
+    pub lower: Vec<(usize, usize)>,
+    /// The number of lanes the row spans.
+    pub width: usize,
+}
+
+/// Assigns log entries to lanes, one row at a time, so that the graph can grow as more pages of
+/// the log are loaded.
+///
+/// Entries must be pushed in topological order, children before their parents. Every parent
+/// must be pushed eventually, since its lane stays open until it is, so filtered logs can't be
+/// laid out.
+#[derive(Clone, Debug, Default)]
+pub struct GraphLayout {
+    /// The commit each lane is waiting for, continuing downwards from the last row.
+    lanes: Vec<Option<SharedString>>,
+    rows: Vec<GraphRow>,
+}
+
+impl GraphLayout {
+    pub fn rows(&self) -> &[GraphRow] {
+        &self.rows
+    }
+
+    pub fn push(&mut self, entry: &LogEntry) {
+        let lane = self
+            .lanes
+            .iter()
+            .position(|expected| expected.as_ref() == Some(&entry.sha))
+            .unwrap_or_else(|| self.free_lane());
+
+        let mut upper = Vec::new();
+        let mut continuing = Vec::new();
+        for (ix, expected) in self.lanes.iter_mut().enumerate() {
+            match expected {
+                Some(sha) if *sha == entry.sha => {
+                    upper.push((ix, lane));
+                    *expected = None;
+                }
+                Some(_) => {
+                    upper.push((ix, ix));
+                    continuing.push(ix);
+                }
+                None => {}
+            }
+        }
+        let width_above = self.lanes.len();
+
+        let mut lower = continuing
+            .into_iter()
+            .map(|ix| (ix, ix))
+            .collect::<Vec<_>>();
+        for (ix, parent) in entry.parents.iter().enumerate() {
+            let target = match self
+                .lanes
+                .iter()
+                .position(|expected| expected.as_ref() == Some(parent))
+            {
+                // Pull the first parent into this commit's lane, so that a line of first
+                // parents stays in the leftmost lane it reaches.
+                Some(existing) if ix == 0 && existing > lane && self.lanes[lane].is_none() => {
+                    self.lanes.swap(existing, lane);
+                    for segment in &mut lower {
+                        if *segment == (existing, existing) {
+                            *segment = (existing, lane);
+                        }
+                    }
+                    lane
+                }
+                Some(existing) => existing,
+                None => {
+                    let target = if ix == 0 && self.lanes[lane].is_none() {
+                        lane
+                    } else {
+                        self.free_lane()
+                    };
+                    self.lanes[target] = Some(parent.clone());
+                    target
+                }
+            };
+            lower.push((lane, target));
+        }
+
+        let width = width_above.max(self.lanes.len());
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+
+        self.rows.push(GraphRow {
+            lane,
+            upper,
+            lower,
+            width,
+        });
+    }
+
+    /// Returns the leftmost lane that isn't waiting for a commit, adding one if necessary.
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(ix) => ix,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parents: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            subject: SharedString::default(),
+            commit_timestamp: 0,
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+            refs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_decorations() {
+        assert_eq!(
+            parse_decorations(
+                "HEAD -> refs/heads/main, tag: refs/tags/v1.0, refs/remotes/origin/main, refs/remotes/origin/HEAD, refs/stash"
+            ),
+            vec![
+                LogRef {
+                    kind: LogRefKind::Head,
+                    name: "HEAD".into()
+                },
+                LogRef {
+                    kind: LogRefKind::LocalBranch,
+                    name: "main".into()
+                },
+                LogRef {
+                    kind: LogRefKind::Tag,
+                    name: "v1.0".into()
+                },
+                LogRef {
+                    kind: LogRefKind::RemoteBranch,
+                    name: "origin/main".into()
+                },
+            ]
+        );
+        assert_eq!(parse_decorations(""), Vec::new());
+    }
+
+    #[test]
+    fn test_graph_layout() {
+        // * e (merge of d and c)
+        // |\
+        // | * d
+        // * | c
+        // |/
+        // * b
+        // * a
+        let mut layout = GraphLayout::default();
+        for entry in [
+            entry("e", &["c", "d"]),
+            entry("d", &["b"]),
+            entry("c", &["b"]),
+            entry("b", &["a"]),
+            entry("a", &[]),
+        ] {
+            layout.push(&entry);
+        }
+
+        assert_eq!(
+            layout.rows(),
+            &[
+                GraphRow {
+                    lane: 0,
+                    upper: vec![],
+                    lower: vec![(0, 0), (0, 1)],
+                    width: 2,
+                },
+                GraphRow {
+                    lane: 1,
+                    upper: vec![(0, 0), (1, 1)],
+                    lower: vec![(0, 0), (1, 1)],
+                    width: 2,
+                },
+                GraphRow {
+                    lane: 0,
+                    upper: vec![(0, 0), (1, 1)],
+                    lower: vec![(1, 0), (0, 0)],
+                    width: 2,
+                },
+                GraphRow {
+                    lane: 0,
+                    upper: vec![(0, 0)],
+                    lower: vec![(0, 0)],
+                    width: 1,
+                },
+                GraphRow {
+                    lane: 0,
+                    upper: vec![(0, 0)],
+                    lower: vec![],
+                    width: 1,
+                },
+            ]
+        );
+    }
+}
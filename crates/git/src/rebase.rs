@@ -0,0 +1,494 @@
+use anyhow::{Context as _, Result, anyhow};
+use gpui::SharedString;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub const REBASE_MERGE_DIR: &str = "rebase-merge";
+pub const REBASE_APPLY_DIR: &str = "rebase-apply";
+pub const REBASE_TODO_FILE: &str = "git-rebase-todo";
+/// Prefix of the files in the git directory that hold the replacement messages of reworded
+/// commits, followed by the commit's SHA.
+pub const REWORD_MESSAGE_PREFIX: &str = "ZED_REWORD_MSG_";
+const REWORD_EXEC_PREFIX: &str =
+    "exec git commit --amend --only --allow-empty --no-verify --cleanup=strip -F ";
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RebaseTodoAction {
+    /// Use the commit as-is.
+    Pick,
+    /// Use the commit, but edit its message.
+    Reword,
+    /// Use the commit, but stop for amending.
+    Edit,
+    /// Meld the commit into the previous one, combining both messages.
+    Squash,
+    /// Meld the commit into the previous one, keeping only the previous message.
+    Fixup,
+    /// Remove the commit.
+    Drop,
+}
+
+impl RebaseTodoAction {
+    pub const ALL: [RebaseTodoAction; 6] = [
+        RebaseTodoAction::Pick,
+        RebaseTodoAction::Reword,
+        RebaseTodoAction::Edit,
+        RebaseTodoAction::Squash,
+        RebaseTodoAction::Fixup,
+        RebaseTodoAction::Drop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseTodoAction::Pick => "pick",
+            RebaseTodoAction::Reword => "reword",
+            RebaseTodoAction::Edit => "edit",
+            RebaseTodoAction::Squash => "squash",
+            RebaseTodoAction::Fixup => "fixup",
+            RebaseTodoAction::Drop => "drop",
+        }
+    }
+
+    /// Returns the action following this one, wrapping around after [`RebaseTodoAction::Drop`].
+    pub fn next(&self) -> Self {
+        let ix = Self::ALL
+            .iter()
+            .position(|action| action == self)
+            .unwrap_or(0);
+        Self::ALL[(ix + 1) % Self::ALL.len()]
+    }
+
+    /// Whether this action melds the commit into the one preceding it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, RebaseTodoAction::Squash | RebaseTodoAction::Fixup)
+    }
+}
+
+impl FromStr for RebaseTodoAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "p" | "pick" => Ok(RebaseTodoAction::Pick),
+            "r" | "reword" => Ok(RebaseTodoAction::Reword),
+            "e" | "edit" => Ok(RebaseTodoAction::Edit),
+            "s" | "squash" => Ok(RebaseTodoAction::Squash),
+            "f" | "fixup" => Ok(RebaseTodoAction::Fixup),
+            "d" | "drop" => Ok(RebaseTodoAction::Drop),
+            _ => Err(anyhow!("unsupported rebase todo command: {s:?}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseTodoAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// Replacement message for [`RebaseTodoAction::Reword`] entries.
+    ///
+    /// When `None`, the original commit message is kept.
+    pub message: Option<SharedString>,
+}
+
+impl RebaseTodoEntry {
+    pub fn pick(sha: impl Into<SharedString>, subject: impl Into<SharedString>) -> Self {
+        Self {
+            action: RebaseTodoAction::Pick,
+            sha: sha.into(),
+            subject: subject.into(),
+            message: None,
+        }
+    }
+
+    /// Checks that the entry can be written into a todo script as a single command. Its commit
+    /// must be a hexadecimal SHA, since it's also used in file names, and its subject must be a
+    /// single line, so that it can't add commands like `exec` to the script.
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            (4..=64).contains(&self.sha.len()) && self.sha.chars().all(|c| c.is_ascii_hexdigit()),
+            "invalid commit in rebase todo: {:?}",
+            self.sha
+        );
+        anyhow::ensure!(
+            !self.subject.contains(['\n', '\r']),
+            "invalid subject in rebase todo: {:?}",
+            self.subject
+        );
+        Ok(())
+    }
+}
+
+/// The commit commands of a `git-rebase-todo` (or `done`) file.
+///
+/// Lines that are not commit commands (`exec`, `break`, `label`, ...) and comments are skipped,
+/// except that a `pick` followed by the `exec` written by [`todo_script`] to reword it is read
+/// back as a [`RebaseTodoAction::Reword`] entry. Its message is left for the caller to load.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct RebaseTodo {
+    pub entries: Vec<RebaseTodoEntry>,
+}
+
+impl FromStr for RebaseTodo {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut entries = Vec::new();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(previous) = entries.last_mut()
+                && is_reword_exec(line, previous)
+            {
+                previous.action = RebaseTodoAction::Reword;
+                continue;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let Some(action) = parts.next().and_then(|command| command.parse().ok()) else {
+                log::debug!("skipping rebase todo line {line:?}");
+                continue;
+            };
+            let sha = parts
+                .next()
+                .with_context(|| format!("missing commit in rebase todo line {line:?}"))?;
+            let subject = parts.next().unwrap_or_default();
+            entries.push(RebaseTodoEntry {
+                action,
+                sha: sha.to_string().into(),
+                subject: subject.to_string().into(),
+                message: None,
+            });
+        }
+        Ok(Self { entries })
+    }
+}
+
+/// The state of a rebase that is currently in progress.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct RebaseStatus {
+    /// The branch being rebased, or `None` when rebasing a detached HEAD.
+    pub head_name: Option<SharedString>,
+    /// The commit the branch is being rebased onto.
+    pub onto: Option<SharedString>,
+    /// The commit on which the rebase stopped, e.g. due to a conflict or an `edit` command.
+    pub stopped_sha: Option<SharedString>,
+    /// Commands that have already been applied.
+    pub done: Vec<RebaseTodoEntry>,
+    /// Commands that remain to be applied.
+    pub remaining: Vec<RebaseTodoEntry>,
+    /// Whether the rebase is interactive, and so has an editable todo list.
+    pub interactive: bool,
+}
+
+impl RebaseStatus {
+    /// Reads the state of an in-progress rebase from a repository's git directory.
+    pub fn load(git_dir: &Path) -> Result<Option<Self>> {
+        let read = |dir: &Path, name: &str| -> Option<String> {
+            std::fs::read_to_string(dir.join(name))
+                .ok()
+                .map(|content| content.trim().to_string())
+                .filter(|content| !content.is_empty())
+        };
+
+        let merge_dir = git_dir.join(REBASE_MERGE_DIR);
+        if merge_dir.is_dir() {
+            let mut remaining = read(&merge_dir, REBASE_TODO_FILE)
+                .map(|todo| todo.parse::<RebaseTodo>())
+                .transpose()?
+                .unwrap_or_default()
+                .entries;
+            for entry in &mut remaining {
+                if entry.action == RebaseTodoAction::Reword && entry.validate().is_ok() {
+                    entry.message = std::fs::read_to_string(reword_message_path(git_dir, entry))
+                        .ok()
+                        .map(SharedString::from);
+                }
+            }
+            let done = read(&merge_dir, "done")
+                .map(|done| done.parse::<RebaseTodo>())
+                .transpose()?
+                .unwrap_or_default()
+                .entries;
+            return Ok(Some(Self {
+                head_name: read(&merge_dir, "head-name").map(parse_head_name),
+                onto: read(&merge_dir, "onto").map(SharedString::from),
+                stopped_sha: read(&merge_dir, "stopped-sha").map(SharedString::from),
+                done,
+                remaining,
+                interactive: merge_dir.join("interactive").exists(),
+            }));
+        }
+
+        let apply_dir = git_dir.join(REBASE_APPLY_DIR);
+        if apply_dir.is_dir() && !apply_dir.join("applying").exists() {
+            return Ok(Some(Self {
+                head_name: read(&apply_dir, "head-name").map(parse_head_name),
+                onto: read(&apply_dir, "onto").map(SharedString::from),
+                ..Default::default()
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// The position of the current step and the total number of steps, both counting commit commands only.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.done.len(), self.done.len() + self.remaining.len())
+    }
+}
+
+fn parse_head_name(head_name: String) -> SharedString {
+    match head_name.strip_prefix("refs/heads/") {
+        Some(branch) => branch.to_string().into(),
+        None => head_name.into(),
+    }
+}
+
+/// The file holding the replacement message of a reworded entry.
+pub fn reword_message_path(git_dir: &Path, entry: &RebaseTodoEntry) -> PathBuf {
+    git_dir.join(format!("{REWORD_MESSAGE_PREFIX}{}", entry.sha))
+}
+
+fn is_reword_exec(line: &str, entry: &RebaseTodoEntry) -> bool {
+    entry.action == RebaseTodoAction::Pick
+        && line.starts_with(REWORD_EXEC_PREFIX)
+        && line.contains(&format!("{REWORD_MESSAGE_PREFIX}{}", entry.sha))
+}
+
+/// Renders a todo list in the format git expects in `git-rebase-todo`.
+///
+/// Reworded entries are replaced by a `pick` followed by an `exec` that amends the commit with
+/// the message stored at the path returned by `message_path`.
+pub fn todo_script(
+    entries: &[RebaseTodoEntry],
+    mut message_path: impl FnMut(&RebaseTodoEntry) -> String,
+) -> String {
+    let mut script = String::new();
+    for entry in entries {
+        match (entry.action, entry.message.as_ref()) {
+            (RebaseTodoAction::Reword, Some(_)) => {
+                let path = shell_quote(&message_path(entry));
+                script.push_str(&format!("pick {} {}\n", entry.sha, entry.subject));
+                script.push_str(&format!("{REWORD_EXEC_PREFIX}{path} && rm -f {path}\n"));
+            }
+            (action, _) => {
+                script.push_str(&format!(
+                    "{} {} {}\n",
+                    action.as_str(),
+                    entry.sha,
+                    entry.subject
+                ));
+            }
+        }
+    }
+    script
+}
+
+/// Renders `entries` in place of the commit commands of the todo script `current`.
+///
+/// The script's other lines (`exec`, `break`, `update-ref`, ...) are kept after the commit they
+/// followed, or at the top if they came before any commit. The `exec` lines that reword the
+/// script's commits are rendered from `entries` again instead.
+pub fn edit_todo_script(
+    current: &str,
+    entries: &[RebaseTodoEntry],
+    mut message_path: impl FnMut(&RebaseTodoEntry) -> String,
+) -> String {
+    let mut leading_lines = Vec::new();
+    let mut following_lines = Vec::<(RebaseTodoEntry, Vec<&str>)>::new();
+    for line in current.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Ok(todo) = line.parse::<RebaseTodo>()
+            && let Some(entry) = todo.entries.into_iter().next()
+        {
+            following_lines.push((entry, Vec::new()));
+        } else if let Some((entry, lines)) = following_lines.last_mut() {
+            if !is_reword_exec(line, entry) {
+                lines.push(line);
+            }
+        } else {
+            leading_lines.push(line);
+        }
+    }
+
+    let mut script = String::new();
+    for line in leading_lines {
+        script.push_str(line);
+        script.push('\n');
+    }
+    for entry in entries {
+        script.push_str(&todo_script(std::slice::from_ref(entry), &mut message_path));
+        let position = following_lines
+            .iter()
+            .position(|(current_entry, _)| current_entry.sha == entry.sha);
+        if let Some(position) = position {
+            for line in following_lines.remove(position).1 {
+                script.push_str(line);
+                script.push('\n');
+            }
+        }
+    }
+    // Keep the lines that followed commits which are no longer in the list at the end.
+    for line in following_lines.into_iter().flat_map(|(_, lines)| lines) {
+        script.push_str(line);
+        script.push('\n');
+    }
+    script
+}
+
+/// Quotes a string so that it is passed through `sh` as a single argument.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_rebase_todo() {
+        let todo = "\
+pick 1a2b3c4 First commit
+r 5d6e7f8 Second commit
+exec cargo test
+f 9a0b1c2 fixup! First commit
+
+# Rebase 0123456..9a0b1c2 onto 0123456 (3 commands)
+# Commands:
+# p, pick <commit> = use commit
+";
+        let todo = todo.parse::<RebaseTodo>().unwrap();
+        assert_eq!(
+            todo.entries
+                .iter()
+                .map(|entry| (entry.action, entry.sha.as_ref(), entry.subject.as_ref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (RebaseTodoAction::Pick, "1a2b3c4", "First commit"),
+                (RebaseTodoAction::Reword, "5d6e7f8", "Second commit"),
+                (RebaseTodoAction::Fixup, "9a0b1c2", "fixup! First commit"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_todo_script() {
+        let entries = vec![
+            RebaseTodoEntry::pick("1a2b3c4", "First commit"),
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Reword,
+                sha: "5d6e7f8".into(),
+                subject: "Second commit".into(),
+                message: Some("Better message".into()),
+            },
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Drop,
+                sha: "9a0b1c2".into(),
+                subject: "Third commit".into(),
+                message: None,
+            },
+        ];
+        let script = todo_script(&entries, |entry| format!("/tmp/it's {}", entry.sha));
+        assert_eq!(
+            script,
+            "\
+pick 1a2b3c4 First commit
+pick 5d6e7f8 Second commit
+exec git commit --amend --only --allow-empty --no-verify --cleanup=strip -F '/tmp/it'\\''s 5d6e7f8' && rm -f '/tmp/it'\\''s 5d6e7f8'
+drop 9a0b1c2 Third commit
+"
+        );
+    }
+
+    #[test]
+    fn test_edit_todo_script() {
+        let current = "\
+exec cargo test
+pick 1a2b3c4 First commit
+exec git commit --amend --only --allow-empty --no-verify --cleanup=strip -F '/git/ZED_REWORD_MSG_1a2b3c4' && rm -f '/git/ZED_REWORD_MSG_1a2b3c4'
+pick 5d6e7f8 Second commit
+break
+update-ref refs/heads/feature
+";
+        let mut entries = current.parse::<RebaseTodo>().unwrap().entries;
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.action, entry.sha.as_ref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (RebaseTodoAction::Reword, "1a2b3c4"),
+                (RebaseTodoAction::Pick, "5d6e7f8"),
+            ]
+        );
+
+        entries[0].message = Some("Better first".into());
+        entries.swap(0, 1);
+        let script = edit_todo_script(current, &entries, |entry| {
+            format!("/git/ZED_REWORD_MSG_{}", entry.sha)
+        });
+        assert_eq!(
+            script,
+            "\
+exec cargo test
+pick 5d6e7f8 Second commit
+break
+update-ref refs/heads/feature
+pick 1a2b3c4 First commit
+exec git commit --amend --only --allow-empty --no-verify --cleanup=strip -F '/git/ZED_REWORD_MSG_1a2b3c4' && rm -f '/git/ZED_REWORD_MSG_1a2b3c4'
+"
+        );
+    }
+
+    #[test]
+    fn test_validate_rebase_todo_entry() {
+        assert!(
+            RebaseTodoEntry::pick("1a2b3c4", "First commit")
+                .validate()
+                .is_ok()
+        );
+        for (sha, subject) in [
+            ("1a2b3c4", "First commit\nexec touch pwned"),
+            ("1a2b3c4", "First commit\rexec touch pwned"),
+            ("/../../pwned", "First commit"),
+            ("abc", "First commit"),
+            ("", "First commit"),
+        ] {
+            assert!(RebaseTodoEntry::pick(sha, subject).validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_load_rebase_status() {
+        let git_dir = tempfile::tempdir().unwrap();
+        assert_eq!(RebaseStatus::load(git_dir.path()).unwrap(), None);
+
+        let merge_dir = git_dir.path().join(REBASE_MERGE_DIR);
+        std::fs::create_dir(&merge_dir).unwrap();
+        std::fs::write(merge_dir.join("head-name"), "refs/heads/feature\n").unwrap();
+        std::fs::write(merge_dir.join("onto"), "0123456789\n").unwrap();
+        std::fs::write(merge_dir.join("interactive"), "").unwrap();
+        std::fs::write(merge_dir.join("done"), "pick 1a2b3c4 First commit\n").unwrap();
+        std::fs::write(
+            merge_dir.join(REBASE_TODO_FILE),
+            "squash 5d6e7f8 Second commit\ndrop 9a0b1c2 Third commit\n",
+        )
+        .unwrap();
+
+        let status = RebaseStatus::load(git_dir.path()).unwrap().unwrap();
+        assert_eq!(status.head_name, Some("feature".into()));
+        assert_eq!(status.onto, Some("0123456789".into()));
+        assert_eq!(status.stopped_sha, None);
+        assert!(status.interactive);
+        assert_eq!(status.progress(), (1, 3));
+        assert_eq!(status.remaining[0].action, RebaseTodoAction::Squash);
+    }
+}
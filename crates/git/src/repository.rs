@@ -1,6 +1,13 @@
+use crate::bisect::{BisectMark, BisectStatus, parse_bisect_vars};
 use crate::commit::parse_git_diff_name_status;
+use crate::log::{LogEntry, LogSearch, parse_decorations};
+use crate::rebase::{
+    REBASE_MERGE_DIR, REBASE_TODO_FILE, REWORD_MESSAGE_PREFIX, RebaseStatus, RebaseTodoEntry,
+};
+use crate::signing::{CommitSignature, GpgAskPassWrapper};
 use crate::stash::GitStash;
 use crate::status::{DiffTreeType, GitStatus, StatusCode, TreeDiff};
+use crate::submodule::{GITMODULES, Submodule, parse_submodules, status_paths};
 use crate::{Oid, RunHook, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow, bail};
 use collections::HashMap;
@@ -12,7 +19,7 @@ use gpui::{AppContext as _, AsyncApp, BackgroundExecutor, SharedString, Task};
 use parking_lot::Mutex;
 use rope::Rope;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use smol::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use text::LineEnding;
 
@@ -150,6 +157,9 @@ impl Upstream {
 pub struct CommitOptions {
     pub amend: bool,
     pub signoff: bool,
+    /// Sign the commit even if `commit.gpgsign` is not set. The format and key come from
+    /// `gpg.format` and `user.signingkey`.
+    pub sign: bool,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -207,6 +217,16 @@ pub struct CommitSummary {
     pub has_parent: bool,
 }
 
+/// The versions of a conflicted file recorded in the index while a merge is in progress.
+///
+/// A side is `None` when the file does not exist on it, e.g. when both sides added the file.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ConflictStages {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
 pub struct CommitDetails {
     pub sha: SharedString,
@@ -255,6 +275,21 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points at, peeled through annotated tags.
+    pub sha: SharedString,
+    /// The subject of the tag's message, for annotated tags.
+    pub message: Option<SharedString>,
+}
+
+impl Tag {
+    pub fn is_annotated(&self) -> bool {
+        self.message.is_some()
+    }
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -264,41 +299,131 @@ pub enum ResetMode {
     Mixed,
 }
 
+/// A command that replays existing commits onto HEAD through git's sequencer.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum SequencerCommand {
+    /// Apply the changes introduced by the commits (`git cherry-pick`).
+    CherryPick,
+    /// Apply the inverse of the changes introduced by the commits (`git revert`).
+    Revert,
+}
+
+impl SequencerCommand {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SequencerCommand::CherryPick => "cherry-pick",
+            SequencerCommand::Revert => "revert",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum SequencerControl {
+    Continue,
+    Skip,
+    Abort,
+}
+
+impl SequencerControl {
+    fn flag(&self) -> &'static str {
+        match self {
+            SequencerControl::Continue => "--continue",
+            SequencerControl::Skip => "--skip",
+            SequencerControl::Abort => "--abort",
+        }
+    }
+}
+
+/// How a cherry-pick or revert ended.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum SequencerOutcome {
+    /// All commits were applied.
+    Completed,
+    /// The command stopped midway, leaving conflicts in the working tree to be resolved.
+    Conflicted,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum FetchOptions {
     All,
     Remote(Remote),
+    /// Fetches a refspec that the remote's configured refspecs don't cover, like the head of a
+    /// pull request.
+    Refspec {
+        remote: Remote,
+        refspec: SharedString,
+    },
 }
 
 impl FetchOptions {
-    pub fn to_proto(&self) -> Option<String> {
+    pub fn to_proto(&self) -> (Option<String>, Option<String>) {
         match self {
-            FetchOptions::All => None,
-            FetchOptions::Remote(remote) => Some(remote.clone().name.into()),
+            FetchOptions::All => (None, None),
+            FetchOptions::Remote(remote) => (Some(remote.name.to_string()), None),
+            FetchOptions::Refspec { remote, refspec } => {
+                (Some(remote.name.to_string()), Some(refspec.to_string()))
+            }
         }
     }
 
-    pub fn from_proto(remote_name: Option<String>) -> Self {
-        match remote_name {
-            Some(name) => FetchOptions::Remote(Remote { name: name.into() }),
-            None => FetchOptions::All,
+    pub fn from_proto(remote_name: Option<String>, refspec: Option<String>) -> Result<Self> {
+        let options = match (remote_name, refspec) {
+            (Some(name), Some(refspec)) => FetchOptions::Refspec {
+                remote: Remote { name: name.into() },
+                refspec: refspec.into(),
+            },
+            (Some(name), None) => FetchOptions::Remote(Remote { name: name.into() }),
+            (None, _) => FetchOptions::All,
+        };
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Remote names and refspecs can come from remote peers, so don't let them be parsed as
+    /// options like `--upload-pack`.
+    fn validate(&self) -> Result<()> {
+        match self {
+            FetchOptions::All => {}
+            FetchOptions::Remote(remote) => {
+                anyhow::ensure!(
+                    !remote.name.starts_with('-'),
+                    "Invalid remote name {:?}",
+                    remote.name
+                );
+            }
+            FetchOptions::Refspec { remote, refspec } => {
+                anyhow::ensure!(
+                    !remote.name.starts_with('-'),
+                    "Invalid remote name {:?}",
+                    remote.name
+                );
+                anyhow::ensure!(!refspec.starts_with('-'), "Invalid refspec {refspec:?}");
+            }
         }
+        Ok(())
     }
 
     pub fn name(&self) -> SharedString {
         match self {
             Self::All => "Fetch all remotes".into(),
-            Self::Remote(remote) => remote.name.clone(),
+            Self::Remote(remote) | Self::Refspec { remote, .. } => remote.name.clone(),
+        }
+    }
+
+    fn args(&self) -> Vec<String> {
+        match self {
+            FetchOptions::All => vec!["--all".into()],
+            FetchOptions::Remote(remote) => vec![remote.name.to_string()],
+            FetchOptions::Refspec { remote, refspec } => {
+                vec![remote.name.to_string(), refspec.to_string()]
+            }
         }
     }
 }
 
 impl std::fmt::Display for FetchOptions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            FetchOptions::All => write!(f, "--all"),
-            FetchOptions::Remote(remote) => write!(f, "{}", remote.name),
-        }
+        write!(f, "{}", self.args().join(" "))
     }
 }
 
@@ -418,6 +543,10 @@ pub trait GitRepository: Send + Sync {
     ///
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
+
+    /// Returns the base, ours and theirs versions of a conflicted path from the index.
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>>;
+
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
     fn set_index_text(
@@ -487,6 +616,11 @@ pub trait GitRepository: Send + Sync {
 
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
+    /// Verifies the signature of `commit`, returning `None` if it isn't signed.
+    ///
+    /// This runs gpg or ssh-keygen, so it is kept separate from [`GitRepository::show`].
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<Option<CommitSignature>>>;
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(
         &self,
@@ -502,6 +636,15 @@ pub trait GitRepository: Send + Sync {
         limit: Option<usize>,
     ) -> BoxFuture<'_, Result<FileHistory>>;
 
+    /// Returns a page of the repository-wide log in topological order, covering all local
+    /// branches, remote branches and tags.
+    fn log(
+        &self,
+        search: Option<LogSearch>,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> BoxFuture<'_, Result<Vec<LogEntry>>>;
+
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
     /// worktree's gitdir within the main repository (typically `.git/worktrees/<name>`).
     fn path(&self) -> PathBuf;
@@ -598,6 +741,56 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
 
+    /// Returns the state of the rebase in progress, if any.
+    fn rebase_status(&self) -> BoxFuture<'_, Result<Option<RebaseStatus>>>;
+
+    /// Returns the commits that a rebase of HEAD onto `upstream` would replay, as a list of picks.
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Starts an interactive rebase of HEAD onto `upstream`, applying the given todo list.
+    fn rebase_start(
+        &self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Replaces the remaining commands of the rebase in progress.
+    fn rebase_edit_todo(&self, todo: Vec<RebaseTodoEntry>) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_continue(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_skip(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the given commits, or ranges of commits, onto HEAD.
+    ///
+    /// When `no_commit` is set, the changes are left in the index and working tree instead of
+    /// being committed.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>>;
+
+    /// Reverts the given commits, or ranges of commits, on top of HEAD.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>>;
+
+    /// Continues, skips the current commit of, or aborts a cherry-pick or revert that stopped midway.
+    fn sequencer_control(
+        &self,
+        command: SequencerCommand,
+        control: SequencerControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>>;
+
     fn get_push_remote(&self, branch: String) -> BoxFuture<'_, Result<Option<Remote>>>;
 
     fn get_branch_remote(&self, branch: String) -> BoxFuture<'_, Result<Option<Remote>>>;
@@ -608,6 +801,98 @@ pub trait GitRepository: Send + Sync {
 
     fn create_remote(&self, name: String, url: String) -> BoxFuture<'_, Result<()>>;
 
+    /// Returns the repository's tags, most recently created first, or only those pointing at the
+    /// commit `points_at` when it's given.
+    fn tags(&self, points_at: Option<String>) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    /// Creates a tag pointing at `target`, or at HEAD when `target` is `None`.
+    ///
+    /// The tag is annotated when a message is given, and lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>>;
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    /// Returns the repository's submodules, with the commits recorded for and checked out in each.
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>>;
+
+    /// Registers the given submodules, or all of them when `paths` is empty, using the URLs
+    /// from `.gitmodules`.
+    fn init_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Updates the registered URLs of the given submodules, or all of them when `paths` is empty,
+    /// to match `.gitmodules`.
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Checks out the recorded commit in the given submodules, or all of them when `paths` is
+    /// empty, initializing and cloning them as needed.
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    /// Returns the state of the bisect in progress, if any.
+    fn bisect_status(&self) -> BoxFuture<'_, Result<Option<BisectStatus>>>;
+
+    /// Starts bisecting between a bad commit and the given good commits.
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Marks the given commit, or the checked-out one when `commit` is `None`, and checks out
+    /// the next commit to test.
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Ends the bisect and checks out the commit it started from.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Runs `git bisect run` with the given shell command, which must exit with 0 for good
+    /// commits, 125 for commits to skip and any other code below 128 for bad ones.
+    ///
+    /// Returns the output of the run. Dropping the future stops the run.
+    fn bisect_run(
+        &self,
+        command: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<String>>;
+
     /// returns a list of remote branches that contain HEAD
     fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>>;
 
@@ -640,6 +925,10 @@ pub trait GitRepository: Send + Sync {
 pub enum DiffType {
     HeadToIndex,
     HeadToWorktree,
+    /// The changes in the working tree since the point where `HEAD` branched off `base`.
+    MergeBaseToWorktree {
+        base: SharedString,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
@@ -689,6 +978,22 @@ impl RealGitRepository {
             .map(Path::to_path_buf)
     }
 
+    /// Returns the program git will sign a commit with, if the commit is going to be signed
+    /// with an OpenPGP key.
+    fn openpgp_signing_program(&self, sign: bool) -> Option<String> {
+        let config = self.repository.lock().config().ok()?.snapshot().ok()?;
+        let sign = sign || config.get_bool("commit.gpgsign").unwrap_or(false);
+        let format = config.get_str("gpg.format").unwrap_or("openpgp");
+        if !sign || format != "openpgp" {
+            return None;
+        }
+        let program = config
+            .get_str("gpg.openpgp.program")
+            .or_else(|_| config.get_str("gpg.program"))
+            .unwrap_or("gpg");
+        Some(program.to_string())
+    }
+
     async fn any_git_binary_help_output(&self) -> SharedString {
         if let Some(output) = self.any_git_binary_help_output.lock().clone() {
             return output;
@@ -709,13 +1014,71 @@ impl RealGitRepository {
         *self.any_git_binary_help_output.lock() = Some(output.clone());
         output
     }
+
+    fn run_rebase_command(
+        &self,
+        flag: &'static str,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(rebase_envs(&env))
+                    .run(&["rebase", flag])
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn run_sequencer_command(
+        &self,
+        command: SequencerCommand,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'static, Result<SequencerOutcome>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                anyhow::ensure!(!commits.is_empty(), "no commits to {}", command.as_str());
+                for commit in &commits {
+                    ensure_revision(commit)?;
+                }
+                let git = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(rebase_envs(&env));
+                let mut args = vec![command.as_str().to_string()];
+                if no_commit {
+                    args.push("--no-commit".into());
+                } else if command == SequencerCommand::Revert {
+                    args.push("--no-edit".into());
+                }
+                args.extend(commits);
+                sequencer_outcome(&git, git.run(&args).await).await
+            })
+            .boxed()
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GitRepositoryCheckpoint {
     pub commit_sha: Oid,
 }
 
+/// A checkpoint's commit is no longer in the repository.
+///
+/// No ref points at checkpoints, so `git gc` prunes the ones that are kept around for long.
+#[derive(Error, Debug)]
+#[error("checkpoint {commit_sha} is no longer in the repository")]
+pub struct MissingCheckpoint {
+    pub commit_sha: Oid,
+}
+
 #[derive(Debug)]
 pub struct GitCommitter {
     pub name: Option<String>,
@@ -815,6 +1178,31 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<Option<CommitSignature>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                ensure_revision(&commit)?;
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&[
+                        "--no-optional-locks",
+                        "show",
+                        "--no-patch",
+                        "--format=%G?%x00%GS%x00%GK",
+                        &commit,
+                    ])
+                    .await?;
+                let fields = output.split('\0').collect::<Vec<_>>();
+                let [status, signer, key] = fields.as_slice() else {
+                    bail!("unexpected git-show output for {commit:?}: {output:?}")
+                };
+                Ok(CommitSignature::parse(status, signer, key))
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
@@ -1050,6 +1438,39 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        const STAGE_BASE: i32 = 1;
+        const STAGE_OURS: i32 = 2;
+        const STAGE_THEIRS: i32 = 3;
+
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let mut index = repo.index()?;
+                index.read(false)?;
+                let load_stage = |stage| -> Result<Option<String>> {
+                    let Some(entry) = index.get_path(path.as_std_path(), stage) else {
+                        return Ok(None);
+                    };
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(String::from_utf8(content)?))
+                };
+                let stages = ConflictStages {
+                    base: load_stage(STAGE_BASE)?,
+                    ours: load_stage(STAGE_OURS)?,
+                    theirs: load_stage(STAGE_THEIRS)?,
+                };
+                anyhow::ensure!(
+                    stages.ours.is_some() || stages.theirs.is_some(),
+                    "{} is not conflicted",
+                    path.as_unix_str()
+                );
+                Ok(stages)
+            })
+            .boxed()
+    }
+
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>> {
         let repo = self.repository.clone();
         self.executor
@@ -1630,14 +2051,104 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn log(
+        &self,
+        search: Option<LogSearch>,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let mut args = vec![
+                    "--no-optional-locks".to_string(),
+                    "log".to_string(),
+                    "--topo-order".to_string(),
+                    "--decorate=full".to_string(),
+                    "--format=%H%x00%P%x00%D%x00%s%x00%at%x00%an%x00%ae".to_string(),
+                ];
+                if skip > 0 {
+                    args.push(format!("--skip={skip}"));
+                }
+                if let Some(limit) = limit {
+                    args.push(format!("--max-count={limit}"));
+                }
+                match search {
+                    // The SHA can come from a remote peer, so don't let it be parsed as an option.
+                    Some(LogSearch::Sha(sha)) => {
+                        args.push("--end-of-options".into());
+                        args.push(sha);
+                    }
+                    search => {
+                        match search {
+                            Some(LogSearch::Author(pattern)) => {
+                                args.push("--regexp-ignore-case".into());
+                                args.push(format!("--author={pattern}"));
+                            }
+                            Some(LogSearch::Message(pattern)) => {
+                                args.push("--regexp-ignore-case".into());
+                                args.push(format!("--grep={pattern}"));
+                            }
+                            _ => {}
+                        }
+                        args.extend(
+                            ["--branches", "--remotes", "--tags", "HEAD"].map(String::from),
+                        );
+                    }
+                }
+                args.push("--".into());
+
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let output = git.run(&args).await?;
+                Ok(output
+                    .lines()
+                    .filter_map(|line| {
+                        let mut fields = line.split('\0');
+                        let sha = fields.next()?;
+                        let parents = fields.next()?;
+                        let decorations = fields.next()?;
+                        let subject = fields.next()?;
+                        let commit_timestamp = fields.next()?.parse().unwrap_or(0);
+                        let author_name = fields.next()?;
+                        let author_email = fields.next()?;
+                        Some(LogEntry {
+                            sha: sha.to_string().into(),
+                            parents: parents
+                                .split_whitespace()
+                                .map(|parent| parent.to_string().into())
+                                .collect(),
+                            subject: subject.to_string().into(),
+                            commit_timestamp,
+                            author_name: author_name.to_string().into(),
+                            author_email: author_email.to_string().into(),
+                            refs: parse_decorations(decorations),
+                        })
+                    })
+                    .collect())
+            })
+            .boxed()
+    }
+
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
         self.executor
             .spawn(async move {
                 let args = match diff {
-                    DiffType::HeadToIndex => Some("--staged"),
-                    DiffType::HeadToWorktree => None,
+                    DiffType::HeadToIndex => vec!["--staged".to_string()],
+                    DiffType::HeadToWorktree => Vec::new(),
+                    DiffType::MergeBaseToWorktree { base } => {
+                        // The base can come from the agent or a remote peer, so don't let it be
+                        // parsed as an option like `--output`.
+                        anyhow::ensure!(!base.starts_with('-'), "Invalid git ref {base:?}");
+                        vec![
+                            "--merge-base".to_string(),
+                            base.to_string(),
+                            "--".to_string(),
+                        ]
+                    }
                 };
 
                 let output = new_smol_command(&git_binary_path)
@@ -1845,13 +2356,32 @@ impl GitRepository for RealGitRepository {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
         let executor = self.executor.clone();
+        // gpg-agent's pinentry can't prompt for a passphrase when Zed isn't started from a
+        // terminal, so OpenPGP signing goes through askpass instead. SSH signing already
+        // uses `SSH_ASKPASS`.
+        let openpgp_program = if cfg!(target_os = "windows") {
+            None
+        } else {
+            self.openpgp_signing_program(options.sign)
+        };
         // Note: Do not spawn this command on the background thread, it might pop open the credential helper
         // which we want to block on.
         async move {
             let mut cmd = new_smol_command(git_binary_path);
-            cmd.current_dir(&working_directory?)
-                .envs(env.iter())
-                .args(["commit", "--quiet", "-m"])
+            cmd.current_dir(&working_directory?).envs(env.iter());
+
+            let gpg_wrapper = match openpgp_program {
+                Some(program) => Some(GpgAskPassWrapper::new(&program).await?),
+                None => None,
+            };
+            if let Some(gpg_wrapper) = &gpg_wrapper {
+                cmd.arg("-c").arg(format!(
+                    "gpg.openpgp.program={}",
+                    gpg_wrapper.script_path().display()
+                ));
+            }
+
+            cmd.args(["commit", "--quiet", "-m"])
                 .arg(&message.to_string())
                 .arg("--cleanup=strip")
                 .arg("--no-verify")
@@ -1866,11 +2396,16 @@ impl GitRepository for RealGitRepository {
                 cmd.arg("--signoff");
             }
 
+            if options.sign {
+                cmd.arg("--gpg-sign");
+            }
+
             if let Some((name, email)) = name_and_email {
                 cmd.arg("--author").arg(&format!("{name} <{email}>"));
             }
 
             run_git_command(env, ask_pass, cmd, &executor).await?;
+            drop(gpg_wrapper);
 
             Ok(())
         }
@@ -1959,18 +2494,20 @@ impl GitRepository for RealGitRepository {
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
         let working_directory = self.working_directory();
-        let remote_name = format!("{}", fetch_options);
+        let fetch_args = fetch_options.args();
         let git_binary_path = self.system_git_binary_path.clone();
         let executor = cx.background_executor().clone();
         // Note: Do not spawn this command on the background thread, it might pop open the credential helper
         // which we want to block on.
         async move {
             let git_binary_path = git_binary_path.context("git not found on $PATH, can't fetch")?;
+            fetch_options.validate()?;
             let mut command = new_smol_command(git_binary_path);
             command
                 .envs(env.iter())
                 .current_dir(&working_directory?)
-                .args(["fetch", &remote_name])
+                .arg("fetch")
+                .args(&fetch_args)
                 .stdout(smol::process::Stdio::piped())
                 .stderr(smol::process::Stdio::piped());
 
@@ -1979,26 +2516,174 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
-    fn get_push_remote(&self, branch: String) -> BoxFuture<'_, Result<Option<Remote>>> {
+    fn rebase_status(&self) -> BoxFuture<'_, Result<Option<RebaseStatus>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { RebaseStatus::load(&git_dir) })
+            .boxed()
+    }
+
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-                let output = new_smol_command(&git_binary_path)
-                    .current_dir(&working_directory)
-                    .args(["rev-parse", "--abbrev-ref"])
-                    .arg(format!("{branch}@{{push}}"))
-                    .output()
+                ensure_revision(&upstream)?;
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let output = git
+                    .run(&[
+                        "--no-optional-locks",
+                        "log",
+                        "--reverse",
+                        "--no-merges",
+                        "--format=%H%x00%s",
+                        &format!("{upstream}..HEAD"),
+                    ])
                     .await?;
-                if !output.status.success() {
-                    return Ok(None);
-                }
-                let remote_name = String::from_utf8_lossy(&output.stdout)
-                    .split('/')
-                    .next()
-                    .map(|name| Remote {
-                        name: name.trim().to_string().into(),
+                Ok(output
+                    .lines()
+                    .filter_map(|line| {
+                        let (sha, subject) = line.split_once('\0')?;
+                        Some(RebaseTodoEntry::pick(sha.to_string(), subject.to_string()))
+                    })
+                    .collect())
+            })
+            .boxed()
+    }
+
+    fn rebase_start(
+        &self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let git_dir = self.path();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                anyhow::ensure!(!todo.is_empty(), "nothing to rebase");
+                ensure_revision(&upstream)?;
+                let script = write_rebase_todo_script(&git_dir, &todo, None).await?;
+                let todo_path = git_dir.join("ZED_REBASE_TODO");
+                smol::fs::write(&todo_path, script).await?;
+
+                // Git runs the sequence editor with the path of its own todo file appended,
+                // so copying over it makes git pick up our todo list instead.
+                let mut envs = rebase_envs(&env);
+                envs.insert(
+                    "GIT_SEQUENCE_EDITOR".into(),
+                    format!(
+                        "cp {}",
+                        crate::rebase::shell_quote(&todo_path.to_string_lossy())
+                    ),
+                );
+                let result = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(envs)
+                    .run(&["rebase", "--interactive", &upstream])
+                    .await;
+                smol::fs::remove_file(&todo_path).await.log_err();
+                result?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn rebase_edit_todo(&self, todo: Vec<RebaseTodoEntry>) -> BoxFuture<'_, Result<()>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let rebase_dir = git_dir.join(REBASE_MERGE_DIR);
+                anyhow::ensure!(rebase_dir.is_dir(), "no interactive rebase in progress");
+                let todo_path = rebase_dir.join(REBASE_TODO_FILE);
+                let current_script = smol::fs::read_to_string(&todo_path)
+                    .await
+                    .unwrap_or_default();
+                let script =
+                    write_rebase_todo_script(&git_dir, &todo, Some(&current_script)).await?;
+                smol::fs::write(&todo_path, script).await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn rebase_continue(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.run_rebase_command("--continue", env)
+    }
+
+    fn rebase_skip(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.run_rebase_command("--skip", env)
+    }
+
+    fn rebase_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_dir = self.path();
+        let abort = self.run_rebase_command("--abort", env);
+        async move {
+            abort.await?;
+            remove_reword_messages(&git_dir).await;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        self.run_sequencer_command(SequencerCommand::CherryPick, commits, no_commit, env)
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        self.run_sequencer_command(SequencerCommand::Revert, commits, no_commit, env)
+    }
+
+    fn sequencer_control(
+        &self,
+        command: SequencerCommand,
+        control: SequencerControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(rebase_envs(&env));
+                sequencer_outcome(&git, git.run(&[command.as_str(), control.flag()]).await).await
+            })
+            .boxed()
+    }
+
+    fn get_push_remote(&self, branch: String) -> BoxFuture<'_, Result<Option<Remote>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["rev-parse", "--abbrev-ref"])
+                    .arg(format!("{branch}@{{push}}"))
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Ok(None);
+                }
+                let remote_name = String::from_utf8_lossy(&output.stdout)
+                    .split('/')
+                    .next()
+                    .map(|name| Remote {
+                        name: name.trim().to_string().into(),
                     });
 
                 Ok(remote_name)
@@ -2088,315 +2773,680 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>> {
+    fn tags(&self, points_at: Option<String>) -> BoxFuture<'_, Result<Vec<Tag>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-                let git_cmd = async |args: &[&str]| -> Result<String> {
-                    let output = new_smol_command(&git_binary_path)
-                        .current_dir(&working_directory)
-                        .args(args)
-                        .output()
-                        .await?;
-                    anyhow::ensure!(
-                        output.status.success(),
-                        String::from_utf8_lossy(&output.stderr).to_string()
-                    );
-                    Ok(String::from_utf8(output.stdout)?)
-                };
-
-                let head = git_cmd(&["rev-parse", "HEAD"])
-                    .await
-                    .context("Failed to get HEAD")?
-                    .trim()
-                    .to_owned();
-
-                let mut remote_branches = vec![];
-                let mut add_if_matching = async |remote_head: &str| {
-                    if let Ok(merge_base) = git_cmd(&["merge-base", &head, remote_head]).await
-                        && merge_base.trim() == head
-                        && let Some(s) = remote_head.strip_prefix("refs/remotes/")
-                    {
-                        remote_branches.push(s.to_owned().into());
-                    }
-                };
-
-                // check the main branch of each remote
-                let remotes = git_cmd(&["remote"])
-                    .await
-                    .context("Failed to get remotes")?;
-                for remote in remotes.lines() {
-                    if let Ok(remote_head) =
-                        git_cmd(&["symbolic-ref", &format!("refs/remotes/{remote}/HEAD")]).await
-                    {
-                        add_if_matching(remote_head.trim()).await;
-                    }
-                }
-
-                // ... and the remote branch that the checked-out one is tracking
-                if let Ok(remote_head) =
-                    git_cmd(&["rev-parse", "--symbolic-full-name", "@{u}"]).await
-                {
-                    add_if_matching(remote_head.trim()).await;
+                let mut args = vec![
+                    "--no-optional-locks".to_string(),
+                    "for-each-ref".to_string(),
+                    "--sort=-creatordate".to_string(),
+                    "--format=%(refname:strip=2)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(contents:subject)".to_string(),
+                ];
+                if let Some(points_at) = points_at {
+                    // Annotated tags are matched through the commit they point at.
+                    args.push(format!("--points-at={points_at}"));
                 }
-
-                Ok(remote_branches)
+                args.push("refs/tags".to_string());
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&args)
+                    .await?;
+                Ok(parse_tags(&output))
             })
             .boxed()
     }
 
-    fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>> {
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
         let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-                let mut git = GitBinary::new(git_binary_path, working_directory.clone(), executor)
-                    .envs(checkpoint_author_envs());
-                git.with_temp_index(async |git| {
-                    let head_sha = git.run(&["rev-parse", "HEAD"]).await.ok();
-                    let mut excludes = exclude_files(git).await?;
-
-                    git.run(&["add", "--all"]).await?;
-                    let tree = git.run(&["write-tree"]).await?;
-                    let checkpoint_sha = if let Some(head_sha) = head_sha.as_deref() {
-                        git.run(&["commit-tree", &tree, "-p", head_sha, "-m", "Checkpoint"])
-                            .await?
-                    } else {
-                        git.run(&["commit-tree", &tree, "-m", "Checkpoint"]).await?
-                    };
-
-                    excludes.restore_original().await?;
-
-                    Ok(GitRepositoryCheckpoint {
-                        commit_sha: checkpoint_sha.parse()?,
-                    })
-                })
-                .await
+                let mut args = vec!["tag".to_string()];
+                if let Some(message) = message {
+                    args.extend(["--annotate".to_string(), "--message".to_string(), message]);
+                }
+                args.push("--".to_string());
+                args.push(name);
+                args.extend(target);
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(&args)
+                    .await?;
+                Ok(())
             })
             .boxed()
     }
 
-    fn restore_checkpoint(&self, checkpoint: GitRepositoryCheckpoint) -> BoxFuture<'_, Result<()>> {
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
-
         let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-
-                let git = GitBinary::new(git_binary_path, working_directory, executor);
-                git.run(&[
-                    "restore",
-                    "--source",
-                    &checkpoint.commit_sha.to_string(),
-                    "--worktree",
-                    ".",
-                ])
-                .await?;
-
-                // TODO: We don't track binary and large files anymore,
-                //       so the following call would delete them.
-                //       Implement an alternative way to track files added by agent.
-                //
-                // git.with_temp_index(async move |git| {
-                //     git.run(&["read-tree", &checkpoint.commit_sha.to_string()])
-                //         .await?;
-                //     git.run(&["clean", "-d", "--force"]).await
-                // })
-                // .await?;
-
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["tag", "--delete", "--", &name])
+                    .await?;
                 Ok(())
             })
             .boxed()
     }
 
-    fn compare_checkpoints(
+    fn push_tag(
         &self,
-        left: GitRepositoryCheckpoint,
-        right: GitRepositoryCheckpoint,
-    ) -> BoxFuture<'_, Result<bool>> {
+        name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
         let working_directory = self.working_directory();
-        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        // Note: Do not spawn this command on the background thread, it might pop open the credential helper
+        // which we want to block on.
+        async move {
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't push")?;
+            let working_directory = working_directory?;
+            // Remote names can come from remote peers, so don't let them be parsed as options.
+            anyhow::ensure!(
+                !remote_name.starts_with('-'),
+                "Invalid remote name {remote_name:?}"
+            );
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory)
+                .args(["push"])
+                .arg(remote_name)
+                .arg(format!("refs/tags/{name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
 
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
         let executor = self.executor.clone();
         self.executor
             .spawn(async move {
                 let working_directory = working_directory?;
+                if !working_directory.join(GITMODULES).exists() {
+                    return Ok(Vec::new());
+                }
                 let git = GitBinary::new(git_binary_path, working_directory, executor);
-                let result = git
+                let status = git
+                    .run(&["--no-optional-locks", "submodule", "status"])
+                    .await?;
+                let paths = status_paths(&status)?;
+                if paths.is_empty() {
+                    return Ok(Vec::new());
+                }
+                // The index records the superproject's commit for each submodule, which is
+                // cheaper to read than running `git submodule status --cached`.
+                let mut args = vec![
+                    "--no-optional-locks",
+                    "--literal-pathspecs",
+                    "ls-files",
+                    "--stage",
+                    "-z",
+                    "--",
+                ];
+                args.extend(paths);
+                let index = git.run(&args).await?;
+                // `git config` fails when there are no matching entries.
+                let gitmodules = git
                     .run(&[
-                        "diff-tree",
-                        "--quiet",
-                        &left.commit_sha.to_string(),
-                        &right.commit_sha.to_string(),
+                        "config",
+                        "--null",
+                        "--file",
+                        GITMODULES,
+                        "--get-regexp",
+                        r"^submodule\..*\.(path|url)$",
                     ])
-                    .await;
-                match result {
-                    Ok(_) => Ok(true),
-                    Err(error) => {
-                        if let Some(GitBinaryCommandError { status, .. }) =
-                            error.downcast_ref::<GitBinaryCommandError>()
-                            && status.code() == Some(1)
-                        {
-                            return Ok(false);
-                        }
-
-                        Err(error)
-                    }
-                }
+                    .await
+                    .unwrap_or_default();
+                parse_submodules(&status, &index, &gitmodules)
             })
             .boxed()
     }
 
-    fn diff_checkpoints(
+    fn init_submodules(
         &self,
-        base_checkpoint: GitRepositoryCheckpoint,
-        target_checkpoint: GitRepositoryCheckpoint,
-    ) -> BoxFuture<'_, Result<String>> {
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
-
         let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-                let git = GitBinary::new(git_binary_path, working_directory, executor);
-                git.run(&[
-                    "diff",
-                    "--find-renames",
-                    "--patch",
-                    &base_checkpoint.commit_sha.to_string(),
-                    &target_checkpoint.commit_sha.to_string(),
-                ])
-                .await
+                let mut args = vec![
+                    "submodule".to_string(),
+                    "init".to_string(),
+                    "--".to_string(),
+                ];
+                args.extend(paths.iter().map(|path| path.as_unix_str().to_string()));
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(&args)
+                    .await?;
+                Ok(())
             })
             .boxed()
     }
 
-    fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>> {
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
-
         let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                let mut args = vec![
+                    "submodule".to_string(),
+                    "sync".to_string(),
+                    "--".to_string(),
+                ];
+                args.extend(paths.iter().map(|path| path.as_unix_str().to_string()));
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(&args)
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
 
-                if let Ok(output) = git
-                    .run(&["symbolic-ref", "refs/remotes/upstream/HEAD"])
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        // Note: Do not spawn this command on the background thread, it might pop open the credential helper
+        // which we want to block on.
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't update submodules")?;
+            let working_directory = working_directory?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory)
+                .args(["submodule", "update", "--init", "--"])
+                .args(paths.iter().map(|path| path.as_unix_str()))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
+    fn bisect_status(&self) -> BoxFuture<'_, Result<Option<BisectStatus>>> {
+        let git_dir = self.path();
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let Some(mut status) = BisectStatus::load(&git_dir)? else {
+                    return Ok(None);
+                };
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                status.current = git
+                    .run(&["--no-optional-locks", "rev-parse", "HEAD"])
                     .await
+                    .log_err()
+                    .map(SharedString::from);
+                if let Some(bad) = &status.bad
+                    && !status.good.is_empty()
+                    && status.first_bad.is_none()
                 {
-                    let output = output
-                        .strip_prefix("refs/remotes/upstream/")
-                        .map(|s| SharedString::from(s.to_owned()));
-                    return Ok(output);
-                }
-
-                if let Ok(output) = git.run(&["symbolic-ref", "refs/remotes/origin/HEAD"]).await {
-                    return Ok(output
-                        .strip_prefix("refs/remotes/origin/")
-                        .map(|s| SharedString::from(s.to_owned())));
+                    let mut args = vec!["--no-optional-locks", "rev-list", "--bisect-vars"];
+                    args.push(bad.as_ref());
+                    args.push("--not");
+                    args.extend(status.good.iter().map(|sha| sha.as_ref()));
+                    status.remaining = parse_bisect_vars(&git.run(&args).await?);
                 }
+                Ok(Some(status))
+            })
+            .boxed()
+    }
 
-                if let Ok(default_branch) = git.run(&["config", "init.defaultBranch"]).await {
-                    if git.run(&["rev-parse", &default_branch]).await.is_ok() {
-                        return Ok(Some(default_branch.into()));
-                    }
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                ensure_revision(&bad)?;
+                for good in &good {
+                    ensure_revision(good)?;
                 }
+                let mut args = vec!["bisect".to_string(), "start".to_string(), bad];
+                args.extend(good);
+                args.push("--".to_string());
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(&args)
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
 
-                if git.run(&["rev-parse", "master"]).await.is_ok() {
-                    return Ok(Some("master".into()));
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                if let Some(commit) = &commit {
+                    ensure_revision(commit)?;
                 }
+                let mut args = vec!["bisect".to_string(), mark.as_str().to_string()];
+                args.extend(commit);
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(&args)
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
 
-                Ok(None)
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(&["bisect", "reset"])
+                    .await?;
+                Ok(())
             })
             .boxed()
     }
 
-    fn run_hook(
+    fn bisect_run(
         &self,
-        hook: RunHook,
+        command: String,
         env: Arc<HashMap<String, String>>,
-    ) -> BoxFuture<'_, Result<()>> {
+    ) -> BoxFuture<'_, Result<String>> {
         let working_directory = self.working_directory();
-        let repository = self.repository.clone();
         let git_binary_path = self.any_git_binary_path.clone();
         let executor = self.executor.clone();
-        let help_output = self.any_git_binary_help_output();
+        self.executor
+            .spawn(async move {
+                // The run can take a long time, so it is stopped when the caller drops it.
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .build_command(["bisect", "run", "sh", "-c", command.as_str()])
+                    .kill_on_drop(true)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    GitBinaryCommandError {
+                        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                        status: output.status,
+                    }
+                );
+                Ok(String::from_utf8(output.stdout)?)
+            })
+            .boxed()
+    }
 
-        // Note: Do not spawn these commands on the background thread, as this causes some git hooks to hang.
-        async move {
-            let working_directory = working_directory?;
-            if !help_output
-                .await
-                .lines()
-                .any(|line| line.trim().starts_with("hook "))
-            {
-                let hook_abs_path = repository.lock().path().join("hooks").join(hook.as_str());
-                if hook_abs_path.is_file() {
-                    let output = new_smol_command(&hook_abs_path)
-                        .envs(env.iter())
+    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git_cmd = async |args: &[&str]| -> Result<String> {
+                    let output = new_smol_command(&git_binary_path)
                         .current_dir(&working_directory)
+                        .args(args)
                         .output()
                         .await?;
+                    anyhow::ensure!(
+                        output.status.success(),
+                        String::from_utf8_lossy(&output.stderr).to_string()
+                    );
+                    Ok(String::from_utf8(output.stdout)?)
+                };
 
-                    if !output.status.success() {
-                        return Err(GitBinaryCommandError {
-                            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-                            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-                            status: output.status,
-                        }
-                        .into());
+                let head = git_cmd(&["rev-parse", "HEAD"])
+                    .await
+                    .context("Failed to get HEAD")?
+                    .trim()
+                    .to_owned();
+
+                let mut remote_branches = vec![];
+                let mut add_if_matching = async |remote_head: &str| {
+                    if let Ok(merge_base) = git_cmd(&["merge-base", &head, remote_head]).await
+                        && merge_base.trim() == head
+                        && let Some(s) = remote_head.strip_prefix("refs/remotes/")
+                    {
+                        remote_branches.push(s.to_owned().into());
+                    }
+                };
+
+                // check the main branch of each remote
+                let remotes = git_cmd(&["remote"])
+                    .await
+                    .context("Failed to get remotes")?;
+                for remote in remotes.lines() {
+                    if let Ok(remote_head) =
+                        git_cmd(&["symbolic-ref", &format!("refs/remotes/{remote}/HEAD")]).await
+                    {
+                        add_if_matching(remote_head.trim()).await;
                     }
                 }
 
-                return Ok(());
-            }
+                // ... and the remote branch that the checked-out one is tracking
+                if let Ok(remote_head) =
+                    git_cmd(&["rev-parse", "--symbolic-full-name", "@{u}"]).await
+                {
+                    add_if_matching(remote_head.trim()).await;
+                }
 
-            let git = GitBinary::new(git_binary_path, working_directory, executor)
-                .envs(HashMap::clone(&env));
-            git.run(&["hook", "run", "--ignore-missing", hook.as_str()])
-                .await?;
-            Ok(())
-        }
-        .boxed()
+                Ok(remote_branches)
+            })
+            .boxed()
     }
-}
-
-fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
-    let mut args = vec![
-        OsString::from("--no-optional-locks"),
-        OsString::from("status"),
-        OsString::from("--porcelain=v1"),
-        OsString::from("--untracked-files=all"),
-        OsString::from("--no-renames"),
-        OsString::from("-z"),
-    ];
-    args.extend(
-        path_prefixes
-            .iter()
-            .map(|path_prefix| path_prefix.as_std_path().into()),
-    );
-    args.extend(path_prefixes.iter().map(|path_prefix| {
-        if path_prefix.is_empty() {
-            Path::new(".").into()
-        } else {
-            path_prefix.as_std_path().into()
-        }
-    }));
-    args
-}
 
-/// Temporarily git-ignore commonly ignored files and files over 2MB
-async fn exclude_files(git: &GitBinary) -> Result<GitExcludeOverride> {
-    const MAX_SIZE: u64 = 2 * 1024 * 1024; // 2 MB
-    let mut excludes = git.with_exclude_overrides().await?;
+    fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut git = GitBinary::new(git_binary_path, working_directory.clone(), executor)
+                    .envs(checkpoint_author_envs());
+                git.with_temp_index(async |git| {
+                    let head_sha = git.run(&["rev-parse", "HEAD"]).await.ok();
+                    let mut excludes = exclude_files(git).await?;
+
+                    git.run(&["add", "--all"]).await?;
+                    let tree = git.run(&["write-tree"]).await?;
+                    let checkpoint_sha = if let Some(head_sha) = head_sha.as_deref() {
+                        git.run(&["commit-tree", &tree, "-p", head_sha, "-m", "Checkpoint"])
+                            .await?
+                    } else {
+                        git.run(&["commit-tree", &tree, "-m", "Checkpoint"]).await?
+                    };
+
+                    excludes.restore_original().await?;
+
+                    Ok(GitRepositoryCheckpoint {
+                        commit_sha: checkpoint_sha.parse()?,
+                    })
+                })
+                .await
+            })
+            .boxed()
+    }
+
+    fn restore_checkpoint(&self, checkpoint: GitRepositoryCheckpoint) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                ensure_checkpoint_exists(&git, &checkpoint).await?;
+                git.run(&[
+                    "restore",
+                    "--source",
+                    &checkpoint.commit_sha.to_string(),
+                    "--worktree",
+                    ".",
+                ])
+                .await?;
+
+                // TODO: We don't track binary and large files anymore,
+                //       so the following call would delete them.
+                //       Implement an alternative way to track files added by agent.
+                //
+                // git.with_temp_index(async move |git| {
+                //     git.run(&["read-tree", &checkpoint.commit_sha.to_string()])
+                //         .await?;
+                //     git.run(&["clean", "-d", "--force"]).await
+                // })
+                // .await?;
+
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn compare_checkpoints(
+        &self,
+        left: GitRepositoryCheckpoint,
+        right: GitRepositoryCheckpoint,
+    ) -> BoxFuture<'_, Result<bool>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                ensure_checkpoint_exists(&git, &left).await?;
+                ensure_checkpoint_exists(&git, &right).await?;
+                let result = git
+                    .run(&[
+                        "diff-tree",
+                        "--quiet",
+                        &left.commit_sha.to_string(),
+                        &right.commit_sha.to_string(),
+                    ])
+                    .await;
+                match result {
+                    Ok(_) => Ok(true),
+                    Err(error) => {
+                        if let Some(GitBinaryCommandError { status, .. }) =
+                            error.downcast_ref::<GitBinaryCommandError>()
+                            && status.code() == Some(1)
+                        {
+                            return Ok(false);
+                        }
+
+                        Err(error)
+                    }
+                }
+            })
+            .boxed()
+    }
+
+    fn diff_checkpoints(
+        &self,
+        base_checkpoint: GitRepositoryCheckpoint,
+        target_checkpoint: GitRepositoryCheckpoint,
+    ) -> BoxFuture<'_, Result<String>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                ensure_checkpoint_exists(&git, &base_checkpoint).await?;
+                ensure_checkpoint_exists(&git, &target_checkpoint).await?;
+                git.run(&[
+                    "diff",
+                    "--find-renames",
+                    "--patch",
+                    &base_checkpoint.commit_sha.to_string(),
+                    &target_checkpoint.commit_sha.to_string(),
+                ])
+                .await
+            })
+            .boxed()
+    }
+
+    fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+
+                if let Ok(output) = git
+                    .run(&["symbolic-ref", "refs/remotes/upstream/HEAD"])
+                    .await
+                {
+                    let output = output
+                        .strip_prefix("refs/remotes/upstream/")
+                        .map(|s| SharedString::from(s.to_owned()));
+                    return Ok(output);
+                }
+
+                if let Ok(output) = git.run(&["symbolic-ref", "refs/remotes/origin/HEAD"]).await {
+                    return Ok(output
+                        .strip_prefix("refs/remotes/origin/")
+                        .map(|s| SharedString::from(s.to_owned())));
+                }
+
+                if let Ok(default_branch) = git.run(&["config", "init.defaultBranch"]).await {
+                    if git.run(&["rev-parse", &default_branch]).await.is_ok() {
+                        return Ok(Some(default_branch.into()));
+                    }
+                }
+
+                if git.run(&["rev-parse", "master"]).await.is_ok() {
+                    return Ok(Some("master".into()));
+                }
+
+                Ok(None)
+            })
+            .boxed()
+    }
+
+    fn run_hook(
+        &self,
+        hook: RunHook,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let repository = self.repository.clone();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        let help_output = self.any_git_binary_help_output();
+
+        // Note: Do not spawn these commands on the background thread, as this causes some git hooks to hang.
+        async move {
+            let working_directory = working_directory?;
+            if !help_output
+                .await
+                .lines()
+                .any(|line| line.trim().starts_with("hook "))
+            {
+                let hook_abs_path = repository.lock().path().join("hooks").join(hook.as_str());
+                if hook_abs_path.is_file() {
+                    let output = new_smol_command(&hook_abs_path)
+                        .envs(env.iter())
+                        .current_dir(&working_directory)
+                        .output()
+                        .await?;
+
+                    if !output.status.success() {
+                        return Err(GitBinaryCommandError {
+                            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                            status: output.status,
+                        }
+                        .into());
+                    }
+                }
+
+                return Ok(());
+            }
+
+            let git = GitBinary::new(git_binary_path, working_directory, executor)
+                .envs(HashMap::clone(&env));
+            git.run(&["hook", "run", "--ignore-missing", hook.as_str()])
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
+    let mut args = vec![
+        OsString::from("--no-optional-locks"),
+        OsString::from("status"),
+        OsString::from("--porcelain=v1"),
+        OsString::from("--untracked-files=all"),
+        OsString::from("--no-renames"),
+        OsString::from("-z"),
+    ];
+    args.extend(
+        path_prefixes
+            .iter()
+            .map(|path_prefix| path_prefix.as_std_path().into()),
+    );
+    args.extend(path_prefixes.iter().map(|path_prefix| {
+        if path_prefix.is_empty() {
+            Path::new(".").into()
+        } else {
+            path_prefix.as_std_path().into()
+        }
+    }));
+    args
+}
+
+/// Temporarily git-ignore commonly ignored files and files over 2MB
+async fn exclude_files(git: &GitBinary) -> Result<GitExcludeOverride> {
+    const MAX_SIZE: u64 = 2 * 1024 * 1024; // 2 MB
+    let mut excludes = git.with_exclude_overrides().await?;
     excludes
         .add_excludes(include_str!("./checkpoint.gitignore"))
         .await?;
@@ -2799,39 +3849,865 @@ fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     }))
 }
 
-fn checkpoint_author_envs() -> HashMap<String, String> {
-    HashMap::from_iter([
-        ("GIT_AUTHOR_NAME".to_string(), "Zed".to_string()),
-        ("GIT_AUTHOR_EMAIL".to_string(), "hi@zed.dev".to_string()),
-        ("GIT_COMMITTER_NAME".to_string(), "Zed".to_string()),
-        ("GIT_COMMITTER_EMAIL".to_string(), "hi@zed.dev".to_string()),
-    ])
-}
+/// Returns an error if git would parse `revision` as an option rather than a revision. Revisions
+/// can come from remote peers, which mustn't be able to pass options like `--exec`.
+fn ensure_revision(revision: &str) -> Result<()> {
+    anyhow::ensure!(
+        !revision.starts_with('-'),
+        "Invalid git revision {revision:?}"
+    );
+    Ok(())
+}
+
+/// Distinguishes a cherry-pick or revert that stopped on conflicts from one that failed outright.
+async fn sequencer_outcome(git: &GitBinary, result: Result<String>) -> Result<SequencerOutcome> {
+    match result {
+        Ok(_) => Ok(SequencerOutcome::Completed),
+        Err(error) => {
+            let conflicted = git
+                .run(&["diff", "--name-only", "--diff-filter=U"])
+                .await
+                .is_ok_and(|output| !output.trim().is_empty());
+            if conflicted {
+                Ok(SequencerOutcome::Conflicted)
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Parses the output of `git for-each-ref refs/tags` using the format requested by
+/// [`GitRepository::tags`].
+fn parse_tags(output: &str) -> Vec<Tag> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let name = fields.next()?;
+            let object_type = fields.next()?;
+            let object = fields.next()?;
+            let peeled = fields.next()?;
+            let subject = fields.next().unwrap_or_default();
+            let (sha, message) = if object_type == "tag" {
+                (peeled, Some(subject.to_string().into()))
+            } else {
+                (object, None)
+            };
+            Some(Tag {
+                name: name.to_string().into(),
+                sha: sha.to_string().into(),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Environment for rebase commands, which must never block on an interactive editor.
+fn rebase_envs(env: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut envs = env.clone();
+    envs.insert("GIT_EDITOR".into(), "true".into());
+    envs
+}
+
+/// Writes the replacement messages of reworded entries into the git directory, and returns
+/// the todo script referring to them. When `current_script` is given, the returned script keeps
+/// its lines that aren't commit commands.
+async fn write_rebase_todo_script(
+    git_dir: &Path,
+    todo: &[RebaseTodoEntry],
+    current_script: Option<&str>,
+) -> Result<String> {
+    for entry in todo {
+        entry.validate()?;
+    }
+    for entry in todo {
+        if let Some(message) = entry.message.as_ref() {
+            smol::fs::write(
+                crate::rebase::reword_message_path(git_dir, entry),
+                message.as_bytes(),
+            )
+            .await?;
+        }
+    }
+    let message_path = |entry: &RebaseTodoEntry| {
+        crate::rebase::reword_message_path(git_dir, entry)
+            .to_string_lossy()
+            .into_owned()
+    };
+    Ok(match current_script {
+        Some(current_script) => crate::rebase::edit_todo_script(current_script, todo, message_path),
+        None => crate::rebase::todo_script(todo, message_path),
+    })
+}
+
+async fn remove_reword_messages(git_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(git_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(REWORD_MESSAGE_PREFIX)
+        {
+            smol::fs::remove_file(entry.path()).await.log_err();
+        }
+    }
+}
+
+async fn ensure_checkpoint_exists(
+    git: &GitBinary,
+    checkpoint: &GitRepositoryCheckpoint,
+) -> Result<()> {
+    let object = format!("{}^{{commit}}", checkpoint.commit_sha);
+    if git.run(&["cat-file", "-e", &object]).await.is_err() {
+        return Err(MissingCheckpoint {
+            commit_sha: checkpoint.commit_sha,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn checkpoint_author_envs() -> HashMap<String, String> {
+    HashMap::from_iter([
+        ("GIT_AUTHOR_NAME".to_string(), "Zed".to_string()),
+        ("GIT_AUTHOR_EMAIL".to_string(), "hi@zed.dev".to_string()),
+        ("GIT_COMMITTER_NAME".to_string(), "Zed".to_string()),
+        ("GIT_COMMITTER_EMAIL".to_string(), "hi@zed.dev".to_string()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::submodule::SubmoduleStatus;
+    use gpui::TestAppContext;
+
+    fn disable_git_global_config() {
+        unsafe {
+            std::env::set_var("GIT_CONFIG_GLOBAL", "");
+            std::env::set_var("GIT_CONFIG_SYSTEM", "");
+        }
+    }
+
+    #[gpui::test]
+    async fn test_checkpoint_basic(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified before checkpoint")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file_before_checkpoint"), "1")
+            .await
+            .unwrap();
+        let checkpoint = repo.checkpoint().await.unwrap();
+
+        // Ensure the user can't see any branches after creating a checkpoint.
+        assert_eq!(repo.branches().await.unwrap().len(), 1);
+
+        smol::fs::write(&file_path, "modified after checkpoint")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Commit after checkpoint".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::remove_file(repo_dir.path().join("new_file_before_checkpoint"))
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file_after_checkpoint"), "2")
+            .await
+            .unwrap();
+
+        // Ensure checkpoint stays alive even after a Git GC.
+        repo.gc().await.unwrap();
+        repo.restore_checkpoint(checkpoint.clone()).await.unwrap();
+
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "modified before checkpoint"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("new_file_before_checkpoint"))
+                .await
+                .unwrap(),
+            "1"
+        );
+        // See TODO above
+        // assert_eq!(
+        //     smol::fs::read_to_string(repo_dir.path().join("new_file_after_checkpoint"))
+        //         .await
+        //         .ok(),
+        //     None
+        // );
+    }
+
+    #[gpui::test]
+    async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        smol::fs::write(repo_dir.path().join("foo"), "foo")
+            .await
+            .unwrap();
+        let checkpoint_sha = repo.checkpoint().await.unwrap();
+
+        // Ensure the user can't see any branches after creating a checkpoint.
+        assert_eq!(repo.branches().await.unwrap().len(), 1);
+
+        smol::fs::write(repo_dir.path().join("foo"), "bar")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("baz"), "qux")
+            .await
+            .unwrap();
+        repo.restore_checkpoint(checkpoint_sha).await.unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("foo"))
+                .await
+                .unwrap(),
+            "foo"
+        );
+        // See TODOs above
+        // assert_eq!(
+        //     smol::fs::read_to_string(repo_dir.path().join("baz"))
+        //         .await
+        //         .ok(),
+        //     None
+        // );
+    }
+
+    #[gpui::test]
+    async fn test_compare_checkpoints(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        smol::fs::write(repo_dir.path().join("file1"), "content1")
+            .await
+            .unwrap();
+        let checkpoint1 = repo.checkpoint().await.unwrap();
+
+        smol::fs::write(repo_dir.path().join("file2"), "content2")
+            .await
+            .unwrap();
+        let checkpoint2 = repo.checkpoint().await.unwrap();
+
+        assert!(
+            !repo
+                .compare_checkpoints(checkpoint1, checkpoint2.clone())
+                .await
+                .unwrap()
+        );
+
+        let checkpoint3 = repo.checkpoint().await.unwrap();
+        assert!(
+            repo.compare_checkpoints(checkpoint2, checkpoint3)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_pruned_checkpoint(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        smol::fs::write(repo_dir.path().join("file1"), "content1")
+            .await
+            .unwrap();
+        let checkpoint1 = repo.checkpoint().await.unwrap();
+        smol::fs::write(repo_dir.path().join("file2"), "content2")
+            .await
+            .unwrap();
+        let checkpoint2 = repo.checkpoint().await.unwrap();
+        assert!(
+            repo.diff_checkpoints(checkpoint1.clone(), checkpoint2.clone())
+                .await
+                .unwrap()
+                .contains("content2")
+        );
+
+        // Nothing refers to checkpoints, so git removes them when it cleans up.
+        let status = std::process::Command::new("git")
+            .args(["gc", "--prune=now", "--quiet"])
+            .current_dir(repo_dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let error = repo
+            .diff_checkpoints(checkpoint1, checkpoint2.clone())
+            .await
+            .unwrap_err();
+        assert!(
+            error.downcast_ref::<MissingCheckpoint>().is_some(),
+            "{error}"
+        );
+        let error = repo.restore_checkpoint(checkpoint2).await.unwrap_err();
+        assert!(
+            error.downcast_ref::<MissingCheckpoint>().is_some(),
+            "{error}"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_checkpoint_exclude_binary_files(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        let text_path = repo_dir.path().join("main.rs");
+        let bin_path = repo_dir.path().join("binary.o");
+
+        git2::Repository::init(repo_dir.path()).unwrap();
+
+        smol::fs::write(&text_path, "fn main() {}").await.unwrap();
+
+        smol::fs::write(&bin_path, "some binary file here")
+            .await
+            .unwrap();
+
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        // initial commit
+        repo.stage_paths(vec![repo_path("main.rs")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        let checkpoint = repo.checkpoint().await.unwrap();
+
+        smol::fs::write(&text_path, "fn main() { println!(\"Modified\"); }")
+            .await
+            .unwrap();
+        smol::fs::write(&bin_path, "Modified binary file")
+            .await
+            .unwrap();
+
+        repo.restore_checkpoint(checkpoint).await.unwrap();
+
+        // Text files should be restored to checkpoint state,
+        // but binaries should not (they aren't tracked)
+        assert_eq!(
+            smol::fs::read_to_string(&text_path).await.unwrap(),
+            "fn main() {}"
+        );
+
+        assert_eq!(
+            smol::fs::read_to_string(&bin_path).await.unwrap(),
+            "Modified binary file"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (ix, message) in ["First", "Second", "Third"].into_iter().enumerate() {
+            smol::fs::write(repo_dir.path().join("file"), ix.to_string())
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
+        let mut todo = repo.rebase_todo("HEAD~2".into()).await.unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Second", "Third"]
+        );
+
+        todo[0].action = crate::rebase::RebaseTodoAction::Reword;
+        todo[0].message = Some("Second, reworded".into());
+        todo[1].action = crate::rebase::RebaseTodoAction::Edit;
+        repo.rebase_start("HEAD~2".into(), todo, env.clone())
+            .await
+            .unwrap();
+
+        // The rebase stops at the commit marked for editing.
+        let status = repo.rebase_status().await.unwrap().unwrap();
+        assert!(status.interactive);
+        assert!(status.remaining.is_empty());
+        assert_eq!(status.progress(), (2, 2));
+
+        repo.rebase_continue(env.clone()).await.unwrap();
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
+        assert_eq!(
+            repo.show("HEAD~1".into()).await.unwrap().message.trim(),
+            "Second, reworded"
+        );
+        assert_eq!(
+            repo.show("HEAD".into()).await.unwrap().message.trim(),
+            "Third"
+        );
+
+        // Upstreams that git would parse as options are rejected.
+        assert!(repo.rebase_todo("--exec=true".into()).await.is_err());
+        let todo = repo.rebase_todo("HEAD~1".into()).await.unwrap();
+        assert!(
+            repo.rebase_start("--exec=true".into(), todo, env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
+
+        // Entries that could add commands to the todo script, or write messages outside the git
+        // directory, are rejected before anything runs.
+        let mut todo = repo.rebase_todo("HEAD~1".into()).await.unwrap();
+        let head = repo.show("HEAD".into()).await.unwrap().sha;
+        todo[0].subject = "Third\nexec touch pwned".into();
+        assert!(
+            repo.rebase_start("HEAD~1".into(), todo.clone(), env.clone())
+                .await
+                .is_err()
+        );
+        todo[0].subject = "Third".into();
+        todo[0].sha = "/../../pwned".into();
+        todo[0].action = crate::rebase::RebaseTodoAction::Reword;
+        todo[0].message = Some("Pwned".into());
+        assert!(
+            repo.rebase_start("HEAD~1".into(), todo, env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
+        assert_eq!(repo.show("HEAD".into()).await.unwrap().sha, head);
+        assert!(!repo_dir.path().join("pwned").exists());
+    }
+
+    #[gpui::test]
+    async fn test_edit_rebase_todo(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (ix, message) in ["First", "Second", "Third"].into_iter().enumerate() {
+            smol::fs::write(repo_dir.path().join("file"), ix.to_string())
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut todo = repo.rebase_todo("HEAD~2".into()).await.unwrap();
+        todo[0].action = crate::rebase::RebaseTodoAction::Edit;
+        todo[1].action = crate::rebase::RebaseTodoAction::Reword;
+        todo[1].message = Some("Third, reworded".into());
+        repo.rebase_start("HEAD~2".into(), todo, env.clone())
+            .await
+            .unwrap();
+
+        // While the rebase is stopped, the user adds their own command to the todo list.
+        let todo_path = repo_dir
+            .path()
+            .join(".git")
+            .join(REBASE_MERGE_DIR)
+            .join(REBASE_TODO_FILE);
+        let mut script = smol::fs::read_to_string(&todo_path).await.unwrap();
+        script.push_str("exec touch exec-ran\n");
+        smol::fs::write(&todo_path, script).await.unwrap();
+
+        // The scheduled reword is read back with its message, and editing the remaining todo
+        // keeps both it and the user's command.
+        let status = repo.rebase_status().await.unwrap().unwrap();
+        assert_eq!(status.progress(), (1, 2));
+        assert_eq!(
+            status.remaining[0].action,
+            crate::rebase::RebaseTodoAction::Reword
+        );
+        assert_eq!(
+            status.remaining[0].message.as_deref(),
+            Some("Third, reworded")
+        );
+        repo.rebase_edit_todo(status.remaining).await.unwrap();
+
+        repo.rebase_continue(env.clone()).await.unwrap();
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
+        assert_eq!(
+            repo.show("HEAD".into()).await.unwrap().message.trim(),
+            "Third, reworded"
+        );
+        assert!(repo_dir.path().join("exec-ran").exists());
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_and_revert(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let file_path = repo_dir.path().join("file");
+
+        let commit = async |contents: &str, message: &str| {
+            smol::fs::write(&file_path, contents).await.unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.to_string().into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+            repo.show("HEAD".into()).await.unwrap().sha
+        };
+
+        commit("one", "First").await;
+        let second = commit("two", "Second").await;
+
+        let outcome = repo
+            .revert(vec![second.to_string()], false, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(outcome, SequencerOutcome::Completed);
+        assert_eq!(smol::fs::read_to_string(&file_path).await.unwrap(), "one");
+        assert!(
+            repo.show("HEAD".into())
+                .await
+                .unwrap()
+                .message
+                .starts_with("Revert \"Second\"")
+        );
+
+        commit("three", "Third").await;
+
+        // Re-applying "Second" conflicts with the contents written by "Third".
+        let outcome = repo
+            .cherry_pick(vec![second.to_string()], false, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(outcome, SequencerOutcome::Conflicted);
+
+        let outcome = repo
+            .sequencer_control(
+                SequencerCommand::CherryPick,
+                SequencerControl::Abort,
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, SequencerOutcome::Completed);
+        assert_eq!(smol::fs::read_to_string(&file_path).await.unwrap(), "three");
+
+        // Ranges are passed through to git, and conflicts are reported with `--no-commit` too.
+        let outcome = repo
+            .cherry_pick(vec!["HEAD~2..HEAD~1".into()], true, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(outcome, SequencerOutcome::Conflicted);
+
+        assert!(
+            repo.cherry_pick(Vec::new(), false, env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.cherry_pick(vec!["--quit".into()], false, env.clone())
+                .await
+                .is_err()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_tags(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        smol::fs::write(repo_dir.path().join("file"), "one")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "First".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let head = repo.show("HEAD".into()).await.unwrap().sha;
+
+        repo.create_tag("v1.0".into(), None, None, env.clone())
+            .await
+            .unwrap();
+        repo.create_tag(
+            "v1.1".into(),
+            Some("HEAD".into()),
+            Some("Release 1.1\n\nWith notes".into()),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        let mut tags = repo.tags(None).await.unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            tags,
+            vec![
+                Tag {
+                    name: "v1.0".into(),
+                    sha: head.clone(),
+                    message: None,
+                },
+                Tag {
+                    name: "v1.1".into(),
+                    sha: head.clone(),
+                    message: Some("Release 1.1".into()),
+                },
+            ]
+        );
+
+        // Annotated tags are matched by the commit they point at.
+        smol::fs::write(repo_dir.path().join("file"), "two")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Second".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let second = repo.show("HEAD".into()).await.unwrap().sha;
+        assert_eq!(repo.tags(Some(head.to_string())).await.unwrap().len(), 2);
+        assert!(
+            repo.tags(Some(second.to_string()))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(
+            repo.create_tag("v1.0".into(), None, None, env.clone())
+                .await
+                .is_err()
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use gpui::TestAppContext;
+        repo.delete_tag("v1.0".into()).await.unwrap();
+        let tags = repo.tags(None).await.unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "v1.1");
+        assert!(tags[0].is_annotated());
 
-    fn disable_git_global_config() {
-        unsafe {
-            std::env::set_var("GIT_CONFIG_GLOBAL", "");
-            std::env::set_var("GIT_CONFIG_SYSTEM", "");
-        }
+        // Names that look like options are treated as tag names.
+        assert!(repo.delete_tag("--no-column".into()).await.is_err());
+        assert_eq!(repo.tags(None).await.unwrap().len(), 1);
     }
 
+    // The signing wrapper is a POSIX shell script, so it isn't used on Windows.
+    #[cfg(not(target_os = "windows"))]
     #[gpui::test]
-    async fn test_checkpoint_basic(cx: &mut TestAppContext) {
+    async fn test_signed_commit(cx: &mut TestAppContext) {
         disable_git_global_config();
 
         cx.executor().allow_parking();
 
-        let repo_dir = tempfile::tempdir().unwrap();
-
-        git2::Repository::init(repo_dir.path()).unwrap();
-        let file_path = repo_dir.path().join("file");
-        smol::fs::write(&file_path, "initial").await.unwrap();
+        // A stand-in for gpg that records its arguments, signs anything and trusts every
+        // signature.
+        let gpg_dir = tempfile::tempdir().unwrap();
+        let gpg_log = gpg_dir.path().join("gpg.log");
+        let gpg_path = gpg_dir.path().join("gpg");
+        smol::fs::write(
+            &gpg_path,
+            format!(
+                r#"#!/bin/sh
+echo "$@" >> '{}'
+cat > /dev/null
+case "$*" in
+*--verify*)
+    echo "[GNUPG:] GOODSIG 0123456789ABCDEF Zed <hi@zed.dev>"
+    echo "[GNUPG:] TRUST_ULTIMATE 0 pgp"
+    ;;
+*)
+    printf '\n[GNUPG:] SIG_CREATED D 1 8 00 0 0\n' >&2
+    printf -- '-----BEGIN PGP SIGNATURE-----\n\nc2lnbmVk\n-----END PGP SIGNATURE-----\n'
+    ;;
+esac
+"#,
+                gpg_log.display()
+            ),
+        )
+        .await
+        .unwrap();
+        util::fs::make_file_executable(&gpg_path).await.unwrap();
 
+        let repo_dir = tempfile::tempdir().unwrap();
+        let mut config = git2::Repository::init(repo_dir.path())
+            .unwrap()
+            .config()
+            .unwrap();
+        config
+            .set_str("gpg.program", gpg_path.to_str().unwrap())
+            .unwrap();
+        config.set_str("user.signingkey", "TEST").unwrap();
         let repo = RealGitRepository::new(
             &repo_dir.path().join(".git"),
             None,
@@ -2839,79 +4715,73 @@ mod tests {
             cx.executor(),
         )
         .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
 
-        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+        smol::fs::write(repo_dir.path().join("file"), "one")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], env.clone())
             .await
             .unwrap();
         repo.commit(
-            "Initial commit".into(),
+            "Unsigned".into(),
             None,
             CommitOptions::default(),
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
-            Arc::new(checkpoint_author_envs()),
+            env.clone(),
         )
         .await
         .unwrap();
+        assert_eq!(repo.commit_signature("HEAD".into()).await.unwrap(), None);
+        assert!(!gpg_log.exists());
 
-        smol::fs::write(&file_path, "modified before checkpoint")
-            .await
-            .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file_before_checkpoint"), "1")
-            .await
-            .unwrap();
-        let checkpoint = repo.checkpoint().await.unwrap();
-
-        // Ensure the user can't see any branches after creating a checkpoint.
-        assert_eq!(repo.branches().await.unwrap().len(), 1);
-
-        smol::fs::write(&file_path, "modified after checkpoint")
+        smol::fs::write(repo_dir.path().join("file"), "two")
             .await
             .unwrap();
-        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+        repo.stage_paths(vec![repo_path("file")], env.clone())
             .await
             .unwrap();
         repo.commit(
-            "Commit after checkpoint".into(),
+            "Signed".into(),
             None,
-            CommitOptions::default(),
+            CommitOptions {
+                sign: true,
+                ..CommitOptions::default()
+            },
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
-            Arc::new(checkpoint_author_envs()),
+            env.clone(),
         )
         .await
         .unwrap();
 
-        smol::fs::remove_file(repo_dir.path().join("new_file_before_checkpoint"))
-            .await
-            .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file_after_checkpoint"), "2")
+        // `commit.gpgsign` isn't set, so the commit is only signed because of `--gpg-sign`.
+        let raw_commit = GitBinary::new("git".into(), repo_dir.path().to_path_buf(), cx.executor())
+            .run(["cat-file", "commit", "HEAD"])
             .await
             .unwrap();
+        assert!(raw_commit.contains("gpgsig -----BEGIN PGP SIGNATURE-----"));
 
-        // Ensure checkpoint stays alive even after a Git GC.
-        repo.gc().await.unwrap();
-        repo.restore_checkpoint(checkpoint.clone()).await.unwrap();
-
-        assert_eq!(
-            smol::fs::read_to_string(&file_path).await.unwrap(),
-            "modified before checkpoint"
+        // Only the wrapper passed through `gpg.openpgp.program` adds these arguments.
+        let gpg_calls = smol::fs::read_to_string(&gpg_log).await.unwrap();
+        let sign_call = gpg_calls.lines().next().unwrap();
+        assert!(
+            sign_call.starts_with("--batch --pinentry-mode error ") && sign_call.ends_with(" TEST"),
+            "unexpected gpg call: {sign_call:?}"
         );
+
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("new_file_before_checkpoint"))
-                .await
-                .unwrap(),
-            "1"
+            repo.commit_signature("HEAD".into()).await.unwrap(),
+            Some(CommitSignature {
+                status: crate::signing::SignatureStatus::Good,
+                signer: Some("Zed <hi@zed.dev>".into()),
+                key: Some("0123456789ABCDEF".into()),
+            })
         );
-        // See TODO above
-        // assert_eq!(
-        //     smol::fs::read_to_string(repo_dir.path().join("new_file_after_checkpoint"))
-        //         .await
-        //         .ok(),
-        //     None
-        // );
+        assert!(repo.commit_signature("--help".into()).await.is_err());
     }
 
     #[gpui::test]
-    async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
+    async fn test_log_search_by_sha(cx: &mut TestAppContext) {
         disable_git_global_config();
 
         cx.executor().allow_parking();
@@ -2925,39 +4795,57 @@ mod tests {
             cx.executor(),
         )
         .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
 
-        smol::fs::write(repo_dir.path().join("foo"), "foo")
+        for message in ["First", "Second"] {
+            smol::fs::write(repo_dir.path().join("file"), message)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
             .await
             .unwrap();
-        let checkpoint_sha = repo.checkpoint().await.unwrap();
-
-        // Ensure the user can't see any branches after creating a checkpoint.
-        assert_eq!(repo.branches().await.unwrap().len(), 1);
+        }
+        let head = repo.show("HEAD".into()).await.unwrap().sha;
 
-        smol::fs::write(repo_dir.path().join("foo"), "bar")
+        let entries = repo
+            .log(Some(LogSearch::Sha(head[..7].to_string())), 0, None)
             .await
             .unwrap();
-        smol::fs::write(repo_dir.path().join("baz"), "qux")
-            .await
-            .unwrap();
-        repo.restore_checkpoint(checkpoint_sha).await.unwrap();
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("foo"))
-                .await
-                .unwrap(),
-            "foo"
+            entries
+                .iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["Second", "First"]
         );
-        // See TODOs above
-        // assert_eq!(
-        //     smol::fs::read_to_string(repo_dir.path().join("baz"))
-        //         .await
-        //         .ok(),
-        //     None
-        // );
+
+        let output_path = repo_dir.path().join("output");
+        assert!(
+            repo.log(
+                Some(LogSearch::Sha(format!(
+                    "--output={}",
+                    output_path.display()
+                ))),
+                0,
+                None
+            )
+            .await
+            .is_err()
+        );
+        assert!(!output_path.exists());
     }
 
     #[gpui::test]
-    async fn test_compare_checkpoints(cx: &mut TestAppContext) {
+    async fn test_diff_against_base(cx: &mut TestAppContext) {
         disable_git_global_config();
 
         cx.executor().allow_parking();
@@ -2971,50 +4859,94 @@ mod tests {
             cx.executor(),
         )
         .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
 
-        smol::fs::write(repo_dir.path().join("file1"), "content1")
+        smol::fs::write(repo_dir.path().join("file"), "one\n")
             .await
             .unwrap();
-        let checkpoint1 = repo.checkpoint().await.unwrap();
-
-        smol::fs::write(repo_dir.path().join("file2"), "content2")
+        repo.stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "First".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        smol::fs::write(repo_dir.path().join("file"), "two\n")
             .await
             .unwrap();
-        let checkpoint2 = repo.checkpoint().await.unwrap();
 
-        assert!(
-            !repo
-                .compare_checkpoints(checkpoint1, checkpoint2.clone())
-                .await
-                .unwrap()
-        );
+        let diff = repo
+            .diff(DiffType::MergeBaseToWorktree {
+                base: "HEAD".into(),
+            })
+            .await
+            .unwrap();
+        assert!(diff.contains("-one\n+two\n"), "unexpected diff: {diff}");
 
-        let checkpoint3 = repo.checkpoint().await.unwrap();
+        let output_path = repo_dir.path().join("output");
         assert!(
-            repo.compare_checkpoints(checkpoint2, checkpoint3)
-                .await
-                .unwrap()
+            repo.diff(DiffType::MergeBaseToWorktree {
+                base: format!("--output={}", output_path.display()).into(),
+            })
+            .await
+            .is_err()
         );
+        assert!(!output_path.exists());
     }
 
     #[gpui::test]
-    async fn test_checkpoint_exclude_binary_files(cx: &mut TestAppContext) {
+    async fn test_submodules(cx: &mut TestAppContext) {
         disable_git_global_config();
 
         cx.executor().allow_parking();
 
-        let repo_dir = tempfile::tempdir().unwrap();
-        let text_path = repo_dir.path().join("main.rs");
-        let bin_path = repo_dir.path().join("binary.o");
-
-        git2::Repository::init(repo_dir.path()).unwrap();
-
-        smol::fs::write(&text_path, "fn main() {}").await.unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let commit = async |repo: &RealGitRepository, message: &str| {
+            repo.commit(
+                message.to_string().into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+            repo.show("HEAD".into()).await.unwrap().sha
+        };
 
-        smol::fs::write(&bin_path, "some binary file here")
+        let sub_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(sub_dir.path()).unwrap();
+        let sub_repo = RealGitRepository::new(
+            &sub_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        smol::fs::write(sub_dir.path().join("file"), "one")
+            .await
+            .unwrap();
+        sub_repo
+            .stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        let first_sha = commit(&sub_repo, "First").await;
+        smol::fs::write(sub_dir.path().join("file"), "two")
+            .await
+            .unwrap();
+        sub_repo
+            .stage_paths(vec![repo_path("file")], env.clone())
             .await
             .unwrap();
+        let second_sha = commit(&sub_repo, "Second").await;
 
+        let repo_dir = tempfile::tempdir().unwrap();
+        let git2_repo = git2::Repository::init(repo_dir.path()).unwrap();
         let repo = RealGitRepository::new(
             &repo_dir.path().join(".git"),
             None,
@@ -3022,42 +4954,209 @@ mod tests {
             cx.executor(),
         )
         .unwrap();
+        assert_eq!(repo.submodules().await.unwrap(), Vec::new());
 
-        // initial commit
-        repo.stage_paths(vec![repo_path("main.rs")], Arc::new(HashMap::default()))
-            .await
+        let url = sub_dir.path().to_str().unwrap();
+        let mut submodule = git2_repo
+            .submodule(url, Path::new("libs/sub"), true)
             .unwrap();
-        repo.commit(
-            "Initial commit".into(),
-            None,
-            CommitOptions::default(),
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        commit(&repo, "Add submodule").await;
+
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(
+            submodules,
+            vec![Submodule {
+                name: "libs/sub".into(),
+                path: repo_path("libs/sub"),
+                url: Some(url.to_string().into()),
+                recorded_sha: second_sha.clone(),
+                checked_out_sha: Some(second_sha.clone()),
+                status: SubmoduleStatus::UpToDate,
+            }]
+        );
+
+        // Check out a different commit in the submodule.
+        git2::Repository::open(repo_dir.path().join("libs/sub"))
+            .unwrap()
+            .set_head_detached(git2::Oid::from_str(&first_sha).unwrap())
+            .unwrap();
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(submodules[0].status, SubmoduleStatus::OutOfDate);
+        assert_eq!(submodules[0].recorded_sha, second_sha);
+        assert_eq!(submodules[0].checked_out_sha, Some(first_sha));
+
+        repo.sync_submodules(Vec::new(), env.clone()).await.unwrap();
+        // Skip the askpass session, since updating doesn't need to fetch anything.
+        let mut update_env = checkpoint_author_envs();
+        update_env.insert("GIT_ASKPASS".into(), "true".into());
+        repo.update_submodules(
+            vec![repo_path("libs/sub")],
             AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
-            Arc::new(checkpoint_author_envs()),
+            Arc::new(update_env),
+            cx.to_async(),
         )
         .await
         .unwrap();
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(submodules[0].status, SubmoduleStatus::UpToDate);
+        assert_eq!(submodules[0].checked_out_sha, Some(second_sha));
+    }
 
-        let checkpoint = repo.checkpoint().await.unwrap();
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        disable_git_global_config();
 
-        smol::fs::write(&text_path, "fn main() { println!(\"Modified\"); }")
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        assert_eq!(repo.bisect_status().await.unwrap(), None);
+
+        let env = Arc::new(checkpoint_author_envs());
+        let mut shas = Vec::new();
+        for value in 1..=5 {
+            smol::fs::write(repo_dir.path().join("value"), value.to_string())
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("value")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Set value to {value}").into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
             .await
             .unwrap();
-        smol::fs::write(&bin_path, "Modified binary file")
+            shas.push(repo.show("HEAD".into()).await.unwrap().sha);
+        }
+
+        assert!(
+            repo.bisect_start(
+                shas[4].to_string(),
+                vec!["--no-checkout".into()],
+                env.clone()
+            )
+            .await
+            .is_err()
+        );
+        assert_eq!(repo.bisect_status().await.unwrap(), None);
+
+        repo.bisect_start(shas[4].to_string(), vec![shas[0].to_string()], env.clone())
+            .await
+            .unwrap();
+        let status = repo.bisect_status().await.unwrap().unwrap();
+        assert_eq!(status.bad, Some(shas[4].clone()));
+        assert_eq!(status.good, vec![shas[0].clone()]);
+        assert_eq!(status.remaining.unwrap().commits, 4);
+        assert!(status.current.is_some());
+        assert_eq!(status.first_bad, None);
+
+        // Options aren't accepted in place of commits.
+        assert!(
+            repo.bisect_mark(BisectMark::Good, Some("--term-good=x".into()), env.clone())
+                .await
+                .is_err()
+        );
+
+        // Values of 3 and above are bad.
+        repo.bisect_run(r#"test "$(cat value)" -lt 3"#.into(), env.clone())
             .await
             .unwrap();
+        let status = repo.bisect_status().await.unwrap().unwrap();
+        assert_eq!(status.first_bad, Some(shas[2].clone()));
 
-        repo.restore_checkpoint(checkpoint).await.unwrap();
+        repo.bisect_reset(env.clone()).await.unwrap();
+        assert_eq!(repo.bisect_status().await.unwrap(), None);
+        assert_eq!(repo.show("HEAD".into()).await.unwrap().sha, shas[4]);
+    }
 
-        // Text files should be restored to checkpoint state,
-        // but binaries should not (they aren't tracked)
+    #[gpui::test]
+    async fn test_load_conflict_stages(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        let env = Arc::new(checkpoint_author_envs());
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo_dir.path())
+                .envs(env.iter())
+                .output()
+                .unwrap();
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let write =
+            |contents: &str| std::fs::write(repo_dir.path().join("file"), contents).unwrap();
+
+        write("base\n");
+        git(&["add", "file"]);
+        git(&["commit", "-m", "Base"]);
+        let base_branch = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        git(&["checkout", "-b", "theirs"]);
+        write("theirs\n");
+        git(&["commit", "-am", "Theirs"]);
+        git(&["checkout", base_branch.trim()]);
+        write("ours\n");
+        git(&["commit", "-am", "Ours"]);
+
+        assert!(repo.load_conflict_stages(repo_path("file")).await.is_err());
+
+        git(&["merge", "theirs"]);
         assert_eq!(
-            smol::fs::read_to_string(&text_path).await.unwrap(),
-            "fn main() {}"
+            repo.load_conflict_stages(repo_path("file")).await.unwrap(),
+            ConflictStages {
+                base: Some("base\n".into()),
+                ours: Some("ours\n".into()),
+                theirs: Some("theirs\n".into()),
+            }
         );
+    }
 
+    #[test]
+    fn test_fetch_options_from_proto() {
         assert_eq!(
-            smol::fs::read_to_string(&bin_path).await.unwrap(),
-            "Modified binary file"
+            FetchOptions::from_proto(Some("origin".into()), Some("pull/1/head".into())).unwrap(),
+            FetchOptions::Refspec {
+                remote: Remote {
+                    name: "origin".into()
+                },
+                refspec: "pull/1/head".into(),
+            }
+        );
+        assert_eq!(
+            FetchOptions::from_proto(None, None).unwrap(),
+            FetchOptions::All
+        );
+        assert!(FetchOptions::from_proto(Some("--upload-pack=touch pwned".into()), None).is_err());
+        assert!(
+            FetchOptions::from_proto(
+                Some("origin".into()),
+                Some("--upload-pack=touch pwned".into())
+            )
+            .is_err()
         );
     }
 
@@ -0,0 +1,170 @@
+use anyhow::{Context as _, Result};
+use gpui::SharedString;
+use std::path::{Path, PathBuf};
+use util::shell::ShellKind;
+
+/// The result of verifying a commit's signature, as reported by git's `%G?` placeholder.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// A good signature from a trusted key.
+    Good,
+    /// A good signature from a key whose validity is unknown.
+    UnknownValidity,
+    /// A good signature that has expired.
+    ExpiredSignature,
+    /// A good signature made by a key that has since expired.
+    ExpiredKey,
+    /// A good signature made by a key that has since been revoked.
+    RevokedKey,
+    /// A signature that does not match the commit.
+    Bad,
+    /// A signature that can't be checked, usually because the key is missing.
+    CannotCheck,
+}
+
+impl SignatureStatus {
+    fn parse(code: &str) -> Option<Self> {
+        match code {
+            "G" => Some(SignatureStatus::Good),
+            "U" => Some(SignatureStatus::UnknownValidity),
+            "X" => Some(SignatureStatus::ExpiredSignature),
+            "Y" => Some(SignatureStatus::ExpiredKey),
+            "R" => Some(SignatureStatus::RevokedKey),
+            "B" => Some(SignatureStatus::Bad),
+            "E" => Some(SignatureStatus::CannotCheck),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignatureStatus::Good => "Verified",
+            SignatureStatus::UnknownValidity => "Signed by an untrusted key",
+            SignatureStatus::ExpiredSignature => "Signature expired",
+            SignatureStatus::ExpiredKey => "Signed by an expired key",
+            SignatureStatus::RevokedKey => "Signed by a revoked key",
+            SignatureStatus::Bad => "Bad signature",
+            SignatureStatus::CannotCheck => "Unverified signature",
+        }
+    }
+
+    /// Whether the signature is good and was made by a key that is still valid.
+    pub fn is_verified(&self) -> bool {
+        *self == SignatureStatus::Good
+    }
+
+    /// Whether the signature shows that the commit can't be trusted.
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, SignatureStatus::Bad | SignatureStatus::RevokedKey)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    /// The name of the signer, as recorded in the key.
+    pub signer: Option<SharedString>,
+    /// The fingerprint or ID of the key used to sign the commit.
+    pub key: Option<SharedString>,
+}
+
+impl CommitSignature {
+    /// Builds a signature from the `%G?`, `%GS` and `%GK` placeholders, returning `None` for
+    /// unsigned commits.
+    pub fn parse(status: &str, signer: &str, key: &str) -> Option<Self> {
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| SharedString::from(value.to_string()))
+        };
+        Some(Self {
+            status: SignatureStatus::parse(status.trim())?,
+            signer: non_empty(signer),
+            key: non_empty(key),
+        })
+    }
+}
+
+/// A stand-in for the OpenPGP signing program that asks for the key's passphrase through
+/// `GIT_ASKPASS`, for when gpg-agent doesn't have it cached and can't show a pinentry itself.
+///
+/// The script keeps the commit payload so it can first try signing without a prompt.
+pub struct GpgAskPassWrapper {
+    _temp_dir: tempfile::TempDir,
+    script_path: PathBuf,
+}
+
+impl GpgAskPassWrapper {
+    pub async fn new(gpg_program: &str) -> Result<Self> {
+        let temp_dir = tempfile::Builder::new().prefix("zed-gpg").tempdir()?;
+        let script_path = temp_dir.path().join("gpg.sh");
+        smol::fs::write(&script_path, gpg_wrapper_script(gpg_program)?)
+            .await
+            .with_context(|| format!("creating gpg wrapper at {script_path:?}"))?;
+        util::fs::make_file_executable(&script_path)
+            .await
+            .with_context(|| format!("marking gpg wrapper executable at {script_path:?}"))?;
+        Ok(Self {
+            _temp_dir: temp_dir,
+            script_path,
+        })
+    }
+
+    pub fn script_path(&self) -> &Path {
+        &self.script_path
+    }
+}
+
+fn gpg_wrapper_script(gpg_program: &str) -> Result<String> {
+    let gpg = ShellKind::Posix
+        .try_quote(gpg_program)
+        .context("Failed to shell-escape gpg program")?;
+    Ok(format!(
+        r#"#!/bin/sh
+payload=$(mktemp) || exit 1
+trap 'rm -f "$payload"' EXIT
+cat > "$payload"
+{gpg} --batch --pinentry-mode error "$@" < "$payload" && exit 0
+[ -n "$GIT_ASKPASS" ] || exit 1
+passphrase=$("$GIT_ASKPASS" "Enter the passphrase for your GPG signing key:") || exit 1
+{gpg} --batch --pinentry-mode loopback --passphrase-fd 3 "$@" < "$payload" 3<<EOF
+$passphrase
+EOF
+"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_commit_signature() {
+        assert_eq!(CommitSignature::parse("N", "", ""), None);
+        assert_eq!(CommitSignature::parse("", "", ""), None);
+        assert_eq!(
+            CommitSignature::parse("G", "Jane Doe <jane@example.com>", "ABCDEF0123456789"),
+            Some(CommitSignature {
+                status: SignatureStatus::Good,
+                signer: Some("Jane Doe <jane@example.com>".into()),
+                key: Some("ABCDEF0123456789".into()),
+            })
+        );
+        assert_eq!(
+            CommitSignature::parse("E", "", "SHA256:abc"),
+            Some(CommitSignature {
+                status: SignatureStatus::CannotCheck,
+                signer: None,
+                key: Some("SHA256:abc".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_gpg_wrapper_script_quotes_program() {
+        let script = gpg_wrapper_script("/opt/GnuPG Tools/gpg").unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("'/opt/GnuPG Tools/gpg' --batch --pinentry-mode error"));
+        assert!(script.contains("'/opt/GnuPG Tools/gpg' --batch --pinentry-mode loopback"));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::repository::RepoPath;
+use anyhow::{Result, anyhow};
+use collections::HashMap;
+use gpui::SharedString;
+
+pub const GITMODULES: &str = ".gitmodules";
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SubmoduleStatus {
+    /// The submodule has not been initialized, so nothing is checked out.
+    Uninitialized,
+    /// The checked-out commit matches the one recorded in the superproject.
+    UpToDate,
+    /// The checked-out commit differs from the one recorded in the superproject.
+    OutOfDate,
+    /// The submodule has merge conflicts in the superproject.
+    Conflicted,
+}
+
+impl SubmoduleStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SubmoduleStatus::Uninitialized => "Not initialized",
+            SubmoduleStatus::UpToDate => "Up to date",
+            SubmoduleStatus::OutOfDate => "Checked out commit differs",
+            SubmoduleStatus::Conflicted => "Conflicted",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Submodule {
+    /// The name of the submodule in `.gitmodules`.
+    pub name: SharedString,
+    pub path: RepoPath,
+    pub url: Option<SharedString>,
+    /// The commit recorded for the submodule in the superproject's index.
+    pub recorded_sha: SharedString,
+    /// The commit checked out in the submodule, if it is initialized.
+    pub checked_out_sha: Option<SharedString>,
+    pub status: SubmoduleStatus,
+}
+
+/// A single line of `git submodule status` output.
+#[derive(Debug, PartialEq, Eq)]
+struct StatusLine<'a> {
+    status: SubmoduleStatus,
+    sha: &'a str,
+    path: &'a str,
+}
+
+fn parse_status_line(line: &str) -> Result<StatusLine<'_>> {
+    let mut chars = line.chars();
+    let status = match chars.next() {
+        Some(' ') => SubmoduleStatus::UpToDate,
+        Some('-') => SubmoduleStatus::Uninitialized,
+        Some('+') => SubmoduleStatus::OutOfDate,
+        Some('U') => SubmoduleStatus::Conflicted,
+        _ => return Err(anyhow!("unexpected submodule status line: {line:?}")),
+    };
+    let (sha, rest) = chars
+        .as_str()
+        .split_once(' ')
+        .ok_or_else(|| anyhow!("unexpected submodule status line: {line:?}"))?;
+    // Initialized submodules are followed by the output of `git describe` in parentheses.
+    let path = match rest.rsplit_once(" (") {
+        Some((path, describe)) if describe.ends_with(')') => path,
+        _ => rest,
+    };
+    Ok(StatusLine { status, sha, path })
+}
+
+/// Parses the output of `git config --null --file .gitmodules --get-regexp`, returning the
+/// name and URL of each submodule keyed by its path.
+fn parse_gitmodules(config: &str) -> HashMap<String, (String, Option<String>)> {
+    let mut paths = HashMap::default();
+    let mut urls = HashMap::default();
+    for record in config.split('\0') {
+        let Some((key, value)) = record.split_once('\n') else {
+            continue;
+        };
+        let Some((name, field)) = key
+            .strip_prefix("submodule.")
+            .and_then(|key| key.rsplit_once('.'))
+        else {
+            continue;
+        };
+        match field {
+            "path" => {
+                paths.insert(name.to_string(), value.to_string());
+            }
+            "url" => {
+                urls.insert(name.to_string(), value.to_string());
+            }
+            _ => {}
+        }
+    }
+    paths
+        .into_iter()
+        .map(|(name, path)| {
+            let url = urls.remove(&name);
+            (path, (name, url))
+        })
+        .collect()
+}
+
+/// Returns the paths of the submodules in the output of `git submodule status`.
+pub fn status_paths(status: &str) -> Result<Vec<&str>> {
+    status
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_status_line(line).map(|line| line.path))
+        .collect()
+}
+
+/// Parses the output of `git ls-files --stage -z`, returning the commit that the index records
+/// for each submodule keyed by its path. Conflicted submodules, which only have entries for the
+/// merge stages, are left out.
+fn parse_recorded_shas(index: &str) -> HashMap<&str, &str> {
+    index
+        .split('\0')
+        .filter_map(|record| {
+            let (info, path) = record.split_once('\t')?;
+            let mut info = info.split(' ');
+            let (mode, sha, stage) = (info.next()?, info.next()?, info.next()?);
+            (mode == "160000" && stage == "0").then_some((path, sha))
+        })
+        .collect()
+}
+
+/// Combines the output of `git submodule status`, the index entries of the submodules from
+/// `git ls-files --stage -z` and the submodule entries of `.gitmodules` into the list of
+/// submodules.
+pub fn parse_submodules(status: &str, index: &str, gitmodules: &str) -> Result<Vec<Submodule>> {
+    let recorded_shas = parse_recorded_shas(index);
+    let mut gitmodules = parse_gitmodules(gitmodules);
+
+    status
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let StatusLine { status, sha, path } = parse_status_line(line)?;
+            let recorded_sha = recorded_shas.get(path).copied().unwrap_or(sha);
+            let checked_out_sha = match status {
+                SubmoduleStatus::Uninitialized | SubmoduleStatus::Conflicted => None,
+                SubmoduleStatus::UpToDate | SubmoduleStatus::OutOfDate => Some(sha),
+            };
+            let (name, url) = gitmodules
+                .remove(path)
+                .unwrap_or_else(|| (path.to_string(), None));
+            Ok(Submodule {
+                name: name.into(),
+                path: RepoPath::new(path)?,
+                url: url.map(SharedString::from),
+                recorded_sha: recorded_sha.to_string().into(),
+                checked_out_sha: checked_out_sha.map(|sha| sha.to_string().into()),
+                status,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::repo_path;
+
+    #[test]
+    fn test_parse_submodules() {
+        let status = "\
+ 1111111111111111111111111111111111111111 libs/up-to-date (v1.0.0)
++2222222222222222222222222222222222222222 libs/out of date (v2.0.0-3-g2222222)
+-3333333333333333333333333333333333333333 libs/uninitialized
+U0000000000000000000000000000000000000000 libs/conflicted
+";
+        let index = "\
+160000 1111111111111111111111111111111111111111 0\tlibs/up-to-date\0\
+160000 4444444444444444444444444444444444444444 0\tlibs/out of date\0\
+160000 3333333333333333333333333333333333333333 0\tlibs/uninitialized\0\
+160000 5555555555555555555555555555555555555555 2\tlibs/conflicted\0\
+160000 6666666666666666666666666666666666666666 3\tlibs/conflicted\0";
+        let gitmodules = "submodule.up-to-date.path\nlibs/up-to-date\0\
+submodule.up-to-date.url\nhttps://example.com/up-to-date.git\0\
+submodule.libs.out.path\nlibs/out of date\0\
+submodule.libs.out.url\n../out.git\0\
+submodule.uninitialized.path\nlibs/uninitialized\0";
+
+        let submodules = parse_submodules(status, index, gitmodules).unwrap();
+        assert_eq!(
+            submodules,
+            vec![
+                Submodule {
+                    name: "up-to-date".into(),
+                    path: repo_path("libs/up-to-date"),
+                    url: Some("https://example.com/up-to-date.git".into()),
+                    recorded_sha: "1111111111111111111111111111111111111111".into(),
+                    checked_out_sha: Some("1111111111111111111111111111111111111111".into()),
+                    status: SubmoduleStatus::UpToDate,
+                },
+                Submodule {
+                    name: "libs.out".into(),
+                    path: repo_path("libs/out of date"),
+                    url: Some("../out.git".into()),
+                    recorded_sha: "4444444444444444444444444444444444444444".into(),
+                    checked_out_sha: Some("2222222222222222222222222222222222222222".into()),
+                    status: SubmoduleStatus::OutOfDate,
+                },
+                Submodule {
+                    name: "uninitialized".into(),
+                    path: repo_path("libs/uninitialized"),
+                    url: None,
+                    recorded_sha: "3333333333333333333333333333333333333333".into(),
+                    checked_out_sha: None,
+                    status: SubmoduleStatus::Uninitialized,
+                },
+                Submodule {
+                    name: "libs/conflicted".into(),
+                    path: repo_path("libs/conflicted"),
+                    url: None,
+                    recorded_sha: "0000000000000000000000000000000000000000".into(),
+                    checked_out_sha: None,
+                    status: SubmoduleStatus::Conflicted,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_submodules_rejects_unknown_status() {
+        assert!(parse_submodules("?1111 foo\n", "", "").is_err());
+    }
+}
@@ -8,11 +8,14 @@ use gpui::SharedString;
 use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
 use regex::Regex;
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use url::Url;
+use util::ResultExt as _;
 
 use git::{
     BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    PullRequest, RemoteUrl,
+    PullRequest, PullRequestChecks, PullRequestDetails, PullRequestState, RemoteUrl, ReviewComment,
+    repository::RepoPath,
 };
 
 use crate::get_host_from_git_remote_url;
@@ -61,6 +64,114 @@ struct User {
     pub avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct PullRequestResponse {
+    number: u32,
+    title: String,
+    html_url: String,
+    user: Option<Login>,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    merged_at: Option<String>,
+    head: PullRequestHead,
+}
+
+#[derive(Debug, Deserialize)]
+struct Login {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestHead {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+}
+
+impl PullRequestResponse {
+    fn into_details(self) -> Result<PullRequestDetails> {
+        let state = if self.merged_at.is_some() {
+            PullRequestState::Merged
+        } else if self.state == "closed" {
+            PullRequestState::Closed
+        } else if self.draft {
+            PullRequestState::Draft
+        } else {
+            PullRequestState::Open
+        };
+        Ok(PullRequestDetails {
+            number: self.number,
+            title: self.title.into(),
+            url: Url::parse(&self.html_url)?,
+            author: self.user.map(|user| user.login).unwrap_or_default().into(),
+            state,
+            head_branch: self.head.ref_name.into(),
+            head_sha: self.head.sha.into(),
+            checks: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRunsResponse {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+fn combine_check_runs(check_runs: &[CheckRun]) -> Option<PullRequestChecks> {
+    if check_runs.is_empty() {
+        return None;
+    }
+    let failed = check_runs.iter().any(|run| {
+        matches!(
+            run.conclusion.as_deref(),
+            Some("failure" | "timed_out" | "cancelled" | "action_required")
+        )
+    });
+    if failed {
+        Some(PullRequestChecks::Failing)
+    } else if check_runs.iter().any(|run| run.status != "completed") {
+        Some(PullRequestChecks::Pending)
+    } else {
+        Some(PullRequestChecks::Passing)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewCommentResponse {
+    id: u64,
+    path: String,
+    line: Option<u32>,
+    side: Option<String>,
+    body: String,
+    user: Option<Login>,
+    html_url: String,
+}
+
+impl ReviewCommentResponse {
+    fn into_comment(self) -> Result<ReviewComment> {
+        // Comments on removed lines refer to the base of the pull request, not its head.
+        let row = self
+            .line
+            .filter(|_| self.side.as_deref() != Some("LEFT"))
+            .map(|line| line.saturating_sub(1));
+        Ok(ReviewComment {
+            id: self.id,
+            path: RepoPath::new(&self.path)?,
+            row,
+            author: self.user.map(|user| user.login).unwrap_or_default().into(),
+            body: self.body.into(),
+            url: Url::parse(&self.html_url).ok(),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct Github {
     name: String,
@@ -105,10 +216,24 @@ impl Github {
         commit: &str,
         client: &Arc<dyn HttpClient>,
     ) -> Result<Option<User>> {
+        self.fetch_api::<CommitDetails>(
+            &format!("repos/{repo_owner}/{repo}/commits/{commit}"),
+            client,
+        )
+        .await
+        .map(|commit| commit.author)
+        .context("failed to fetch GitHub commit details")
+    }
+
+    async fn fetch_api<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<T> {
         let Some(host) = self.base_url.host_str() else {
             bail!("failed to get host from github base url");
         };
-        let url = format!("https://api.{host}/repos/{repo_owner}/{repo}/commits/{commit}");
+        let url = format!("https://api.{host}/{path}");
 
         let mut request = Request::get(&url)
             .header("Content-Type", "application/json")
@@ -121,7 +246,7 @@ impl Github {
         let mut response = client
             .send(request.body(AsyncBody::default())?)
             .await
-            .with_context(|| format!("error fetching GitHub commit details at {:?}", url))?;
+            .with_context(|| format!("error fetching GitHub API at {:?}", url))?;
 
         let mut body = Vec::new();
         response.body_mut().read_to_end(&mut body).await?;
@@ -136,9 +261,24 @@ impl Github {
 
         let body_str = std::str::from_utf8(&body)?;
 
-        serde_json::from_str::<CommitDetails>(body_str)
-            .map(|commit| commit.author)
-            .context("failed to deserialize GitHub commit details")
+        serde_json::from_str::<T>(body_str)
+            .with_context(|| format!("failed to deserialize GitHub API response from {url:?}"))
+    }
+
+    async fn fetch_checks(
+        &self,
+        remote: &ParsedGitRemote,
+        sha: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequestChecks>> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let response = self
+            .fetch_api::<CheckRunsResponse>(
+                &format!("repos/{owner}/{repo}/commits/{sha}/check-runs?per_page=100"),
+                client,
+            )
+            .await?;
+        Ok(combine_check_runs(&response.check_runs))
     }
 }
 
@@ -255,6 +395,76 @@ impl GitHostingProvider for Github {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_requests(&self) -> bool {
+        true
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        Some(format!("refs/pull/{number}/head"))
+    }
+
+    async fn list_pull_requests(
+        &self,
+        remote: &ParsedGitRemote,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<PullRequestDetails>> {
+        let ParsedGitRemote { owner, repo } = remote;
+        self.fetch_api::<Vec<PullRequestResponse>>(
+            &format!("repos/{owner}/{repo}/pulls?state=open&per_page=100"),
+            &http_client,
+        )
+        .await?
+        .into_iter()
+        .map(PullRequestResponse::into_details)
+        .collect()
+    }
+
+    async fn pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        head_owner: &str,
+        branch: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequestDetails>> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let head = urlencoding::encode(&format!("{head_owner}:{branch}")).into_owned();
+        let Some(pull_request) = self
+            .fetch_api::<Vec<PullRequestResponse>>(
+                &format!("repos/{owner}/{repo}/pulls?state=open&head={head}&per_page=1"),
+                &http_client,
+            )
+            .await?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+        let mut pull_request = pull_request.into_details()?;
+        pull_request.checks = self
+            .fetch_checks(remote, &pull_request.head_sha, &http_client)
+            .await
+            .log_err()
+            .flatten();
+        Ok(Some(pull_request))
+    }
+
+    async fn pull_request_review_comments(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewComment>> {
+        let ParsedGitRemote { owner, repo } = remote;
+        self.fetch_api::<Vec<ReviewCommentResponse>>(
+            &format!("repos/{owner}/{repo}/pulls/{number}/comments?per_page=100"),
+            &http_client,
+        )
+        .await?
+        .into_iter()
+        .map(ReviewCommentResponse::into_comment)
+        .collect()
+    }
 }
 
 #[cfg(test)]
@@ -508,6 +718,127 @@ mod tests {
         assert_eq!(github.extract_pull_request(&remote, message), None);
     }
 
+    #[test]
+    fn test_parse_pull_requests() {
+        let response = indoc! {r#"
+            [
+                {
+                    "number": 42,
+                    "title": "Add a feature",
+                    "html_url": "https://github.com/zed-industries/zed/pull/42",
+                    "user": { "login": "octocat" },
+                    "state": "open",
+                    "draft": true,
+                    "merged_at": null,
+                    "head": { "ref": "feature", "sha": "abc123" }
+                },
+                {
+                    "number": 7,
+                    "title": "Fix a bug",
+                    "html_url": "https://github.com/zed-industries/zed/pull/7",
+                    "user": null,
+                    "state": "closed",
+                    "merged_at": "2024-01-01T00:00:00Z",
+                    "head": { "ref": "fix", "sha": "def456" }
+                }
+            ]
+        "#};
+        let pull_requests = serde_json::from_str::<Vec<PullRequestResponse>>(response)
+            .unwrap()
+            .into_iter()
+            .map(PullRequestResponse::into_details)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            pull_requests,
+            vec![
+                PullRequestDetails {
+                    number: 42,
+                    title: "Add a feature".into(),
+                    url: Url::parse("https://github.com/zed-industries/zed/pull/42").unwrap(),
+                    author: "octocat".into(),
+                    state: PullRequestState::Draft,
+                    head_branch: "feature".into(),
+                    head_sha: "abc123".into(),
+                    checks: None,
+                },
+                PullRequestDetails {
+                    number: 7,
+                    title: "Fix a bug".into(),
+                    url: Url::parse("https://github.com/zed-industries/zed/pull/7").unwrap(),
+                    author: "".into(),
+                    state: PullRequestState::Merged,
+                    head_branch: "fix".into(),
+                    head_sha: "def456".into(),
+                    checks: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combine_check_runs() {
+        let run = |status: &str, conclusion: Option<&str>| CheckRun {
+            status: status.into(),
+            conclusion: conclusion.map(Into::into),
+        };
+
+        assert_eq!(combine_check_runs(&[]), None);
+        assert_eq!(
+            combine_check_runs(&[
+                run("completed", Some("success")),
+                run("completed", Some("skipped"))
+            ]),
+            Some(PullRequestChecks::Passing)
+        );
+        assert_eq!(
+            combine_check_runs(&[run("completed", Some("success")), run("in_progress", None)]),
+            Some(PullRequestChecks::Pending)
+        );
+        assert_eq!(
+            combine_check_runs(&[run("in_progress", None), run("completed", Some("failure"))]),
+            Some(PullRequestChecks::Failing)
+        );
+    }
+
+    #[test]
+    fn test_parse_review_comments() {
+        let response = indoc! {r#"
+            [
+                {
+                    "id": 1,
+                    "path": "src/main.rs",
+                    "line": 10,
+                    "side": "RIGHT",
+                    "body": "Consider renaming this.",
+                    "user": { "login": "reviewer" },
+                    "html_url": "https://github.com/zed-industries/zed/pull/42#discussion_r1"
+                },
+                {
+                    "id": 2,
+                    "path": "src/lib.rs",
+                    "line": null,
+                    "side": "RIGHT",
+                    "body": "Outdated",
+                    "user": { "login": "reviewer" },
+                    "html_url": "https://github.com/zed-industries/zed/pull/42#discussion_r2"
+                }
+            ]
+        "#};
+        let comments = serde_json::from_str::<Vec<ReviewCommentResponse>>(response)
+            .unwrap()
+            .into_iter()
+            .map(ReviewCommentResponse::into_comment)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(comments[0].path, repo_path("src/main.rs"));
+        assert_eq!(comments[0].row, Some(9));
+        assert_eq!(comments[0].author, SharedString::from("reviewer"));
+        assert_eq!(comments[1].row, None);
+    }
+
     /// Regression test for issue #39875
     #[test]
     fn test_git_permalink_url_escaping() {
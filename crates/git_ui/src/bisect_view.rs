@@ -0,0 +1,545 @@
+use editor::Editor;
+use git::{
+    bisect::{BisectMark, BisectStatus},
+    rebase::shell_quote,
+};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity, Window,
+};
+use project::{
+    Project,
+    git_store::{Repository, RepositoryEvent},
+};
+use task::{ResolvedTask, TaskContext};
+use ui::{ListItem, Tooltip, prelude::*};
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+use crate::{commit_view::CommitView, git_panel::GitPanel};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::Bisect, window, cx| {
+        BisectView::toggle(workspace, window, cx);
+    });
+}
+
+/// Drives `git bisect` for the active repository: starting a bisect, marking the checked-out
+/// commit, and running a task against each candidate until the first bad commit is found.
+pub struct BisectView {
+    workspace: WeakEntity<Workspace>,
+    repository: Entity<Repository>,
+    bad_editor: Entity<Editor>,
+    good_editor: Entity<Editor>,
+    tasks: Vec<ResolvedTask>,
+    selected_task: Option<usize>,
+    /// Tasks are only run on the machine that owns the repository.
+    can_run_tasks: bool,
+    /// The `git bisect run` in progress, which is stopped when this is dropped.
+    bisect_run: Option<Task<()>>,
+    output: Option<SharedString>,
+    /// The first bad commit that was last opened, so it is only opened once.
+    opened_first_bad: Option<SharedString>,
+    focus_handle: FocusHandle,
+    _load_tasks: Task<()>,
+    _subscription: Subscription,
+}
+
+impl BisectView {
+    pub fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(panel) = workspace.panel::<GitPanel>(cx) else {
+            return;
+        };
+        let Some(repository) = panel.read(cx).active_repository.clone() else {
+            return;
+        };
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            BisectView::new(project, repository, workspace_handle, window, cx)
+        });
+    }
+
+    fn new(
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let bad_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bad commit (defaults to HEAD)", window, cx);
+            editor
+        });
+        let good_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Good commits, separated by spaces", window, cx);
+            editor
+        });
+
+        let subscription = cx.subscribe_in(&repository, window, |this, _, event, window, cx| {
+            if matches!(event, RepositoryEvent::BisectStatusChanged) {
+                this.open_first_bad(window, cx);
+                cx.notify();
+            }
+        });
+        let opened_first_bad = repository
+            .read(cx)
+            .bisect
+            .as_ref()
+            .and_then(|bisect| bisect.first_bad.clone());
+
+        let can_run_tasks = project.read(cx).is_local();
+        let load_tasks = if can_run_tasks {
+            Self::load_tasks(project, repository.clone(), cx)
+        } else {
+            Task::ready(())
+        };
+
+        Self {
+            workspace,
+            _load_tasks: load_tasks,
+            repository,
+            bad_editor,
+            good_editor,
+            tasks: Vec::new(),
+            selected_task: None,
+            can_run_tasks,
+            bisect_run: None,
+            output: None,
+            opened_first_bad,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    /// Resolves the project's task templates to run from the root of the repository.
+    fn load_tasks(
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let work_directory = repository.read(cx).work_directory_abs_path.clone();
+        let project = project.read(cx);
+        let worktree_id = project
+            .find_worktree(&work_directory, cx)
+            .map(|(worktree, _)| worktree.read(cx).id());
+        let Some(inventory) = project.task_store().read(cx).task_inventory() else {
+            return Task::ready(());
+        };
+        let templates = inventory.read(cx).list_tasks(None, None, worktree_id, cx);
+        let task_context = TaskContext {
+            cwd: Some(work_directory.to_path_buf()),
+            ..TaskContext::default()
+        };
+
+        cx.spawn(async move |this, cx| {
+            let tasks = templates
+                .await
+                .into_iter()
+                .filter_map(|(kind, template)| {
+                    template.resolve_task(&kind.to_id_base(), &task_context)
+                })
+                .collect::<Vec<_>>();
+            this.update(cx, |this, cx| {
+                this.selected_task = (!tasks.is_empty()).then_some(0);
+                this.tasks = tasks;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn bisect<'a>(&self, cx: &'a App) -> Option<&'a BisectStatus> {
+        self.repository.read(cx).bisect.as_ref()
+    }
+
+    fn open_first_bad(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(first_bad) = self.bisect(cx).and_then(|bisect| bisect.first_bad.clone()) else {
+            return;
+        };
+        if self.opened_first_bad.as_ref() == Some(&first_bad) {
+            return;
+        }
+        self.opened_first_bad = Some(first_bad.clone());
+        CommitView::open(
+            first_bad.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let bad = self.bad_editor.read(cx).text(cx).trim().to_string();
+        let bad = if bad.is_empty() { "HEAD".into() } else { bad };
+        let good = self
+            .good_editor
+            .read(cx)
+            .text(cx)
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_start(bad, good));
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err("Failed to start bisect", window, cx, |_, _, _| None);
+    }
+
+    fn mark(&mut self, mark: BisectMark, window: &mut Window, cx: &mut Context<Self>) {
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_mark(mark, None));
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err("Failed to mark commit", window, cx, |_, _, _| None);
+    }
+
+    fn reset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.output = None;
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_reset());
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err("Failed to reset bisect", window, cx, |_, _, _| None);
+    }
+
+    fn run(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(task) = self.selected_task.and_then(|ix| self.tasks.get(ix)) else {
+            return;
+        };
+        let Some(command) = bisect_run_command(task) else {
+            return;
+        };
+        let env = task.resolved.env.clone();
+
+        self.output = None;
+        let run = self
+            .repository
+            .update(cx, |repository, cx| repository.bisect_run(command, env, cx));
+        self.bisect_run = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = run.await;
+            this.update_in(cx, |this, window, cx| {
+                this.bisect_run = None;
+                let output = match result {
+                    Ok(output) => output,
+                    Err(error) => format!("Failed to run bisect: {error:#}"),
+                };
+                this.output = Some(output.trim().to_string().into());
+                this.open_first_bad(window, cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn stop_run(&mut self, cx: &mut Context<Self>) {
+        self.bisect_run = None;
+        self.output = Some("Stopped the bisect run".into());
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_start(&self, cx: &Context<Self>) -> impl IntoElement {
+        v_flex()
+            .px_3()
+            .py_2()
+            .gap_2()
+            .child(
+                Label::new("Mark a commit that has the bug and at least one that does not.")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.bad_editor.clone())
+            .child(self.good_editor.clone())
+            .child(
+                h_flex().justify_end().child(
+                    Button::new("start-bisect", "Start Bisect")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|this, _, window, cx| this.start(window, cx))),
+                ),
+            )
+    }
+
+    fn render_status(&self, bisect: &BisectStatus, cx: &Context<Self>) -> impl IntoElement {
+        let short = |sha: &SharedString| -> SharedString {
+            sha.chars()
+                .take(git::SHORT_SHA_LENGTH)
+                .collect::<String>()
+                .into()
+        };
+        let list = |shas: &[SharedString]| -> SharedString {
+            if shas.is_empty() {
+                "none".into()
+            } else {
+                shas.iter().map(short).collect::<Vec<_>>().join(", ").into()
+            }
+        };
+        let row = |label: &'static str, value: SharedString| {
+            h_flex()
+                .gap_2()
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                .child(Label::new(value).size(LabelSize::Small).buffer_font(cx))
+        };
+
+        let remaining: SharedString = match (&bisect.first_bad, bisect.remaining) {
+            (Some(_), _) => "Found the first bad commit".into(),
+            (None, Some(remaining)) => format!(
+                "{} commits left to search (roughly {} steps)",
+                remaining.commits, remaining.steps
+            )
+            .into(),
+            (None, None) => "Mark a good and a bad commit to narrow the range".into(),
+        };
+        let candidate = self
+            .repository
+            .read(cx)
+            .head_commit
+            .as_ref()
+            .filter(|commit| Some(&commit.sha) == bisect.current.as_ref())
+            .map(|commit| {
+                let subject = commit
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                SharedString::from(format!("{} {subject}", short(&commit.sha)))
+            })
+            .or_else(|| bisect.current.as_ref().map(short))
+            .unwrap_or_else(|| "unknown".into());
+
+        v_flex()
+            .px_3()
+            .py_2()
+            .gap_1()
+            .child(Label::new(remaining).size(LabelSize::Small))
+            .when_some(bisect.start.clone(), |this, start| {
+                this.child(row("Started from", start))
+            })
+            .child(row(
+                "Bad",
+                bisect.bad.as_ref().map(short).unwrap_or("none".into()),
+            ))
+            .child(row("Good", list(&bisect.good)))
+            .when(!bisect.skipped.is_empty(), |this| {
+                this.child(row("Skipped", list(&bisect.skipped)))
+            })
+            .map(|this| match bisect.first_bad.clone() {
+                Some(first_bad) => this.child(
+                    h_flex()
+                        .gap_2()
+                        .child(row("First bad commit", short(&first_bad)))
+                        .child(
+                            Button::new("open-first-bad", "View Commit")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.opened_first_bad = None;
+                                    this.open_first_bad(window, cx);
+                                })),
+                        ),
+                ),
+                None => this.child(row("Testing", candidate)),
+            })
+    }
+
+    fn render_tasks(&self, cx: &Context<Self>) -> impl IntoElement {
+        v_flex()
+            .px_1()
+            .py_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                div().px_2().child(
+                    Label::new("Run a task on each commit (exit code 0 is good, 125 skips)")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .when(self.tasks.is_empty(), |this| {
+                let message = if self.can_run_tasks {
+                    "No tasks are defined for this project"
+                } else {
+                    "Tasks can only be run in local projects"
+                };
+                this.child(
+                    div().px_2().child(
+                        Label::new(message)
+                            .size(LabelSize::Small)
+                            .color(Color::Placeholder),
+                    ),
+                )
+            })
+            .children(self.tasks.iter().enumerate().map(|(ix, task)| {
+                ListItem::new(("bisect-task", ix))
+                    .toggle_state(self.selected_task == Some(ix))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(task.resolved_label.clone()).size(LabelSize::Small))
+                            .child(
+                                Label::new(task.resolved.command_label.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                    )
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.selected_task = Some(ix);
+                        cx.notify();
+                    }))
+            }))
+    }
+}
+
+/// Builds the shell command `git bisect run` executes for a task, running it from the task's
+/// working directory.
+fn bisect_run_command(task: &ResolvedTask) -> Option<String> {
+    let mut command = String::new();
+    if let Some(cwd) = &task.resolved.cwd {
+        command.push_str(&format!("cd {} && ", shell_quote(&cwd.to_string_lossy())));
+    }
+    command.push_str(task.resolved.command.as_deref()?);
+    for arg in &task.resolved.args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    Some(command)
+}
+
+impl EventEmitter<DismissEvent> for BisectView {}
+impl ModalView for BisectView {}
+impl Focusable for BisectView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for BisectView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let bisect = self.bisect(cx).cloned();
+        let searching = bisect
+            .as_ref()
+            .is_some_and(|bisect| bisect.first_bad.is_none());
+        let is_running = self.bisect_run.is_some();
+        let can_run = searching
+            && !is_running
+            && bisect
+                .as_ref()
+                .is_some_and(|bisect| bisect.remaining.is_some())
+            && self.selected_task.is_some();
+
+        v_flex()
+            .key_context("BisectView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::MagnifyingGlass).size(IconSize::XSmall))
+                    .child(Headline::new("Git Bisect").size(HeadlineSize::XSmall)),
+            )
+            .map(|this| match &bisect {
+                None => this.child(self.render_start(cx)),
+                Some(bisect) => this
+                    .child(self.render_status(bisect, cx))
+                    .when(searching, |this| this.child(self.render_tasks(cx))),
+            })
+            .children(self.output.clone().map(|output| {
+                div()
+                    .id("bisect-output")
+                    .px_3()
+                    .py_1()
+                    .max_h(rems(8.))
+                    .overflow_y_scroll()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(output)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+            }))
+            .when_some(bisect, |this, _| {
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_2()
+                        .gap_1()
+                        .justify_end()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Button::new("bisect-reset", "Reset")
+                                .tooltip(Tooltip::text(
+                                    "End the bisect and check out where it started",
+                                ))
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.reset(window, cx)),
+                                ),
+                        )
+                        .when(searching, |this| {
+                            this.child(
+                                Button::new("bisect-skip", "Skip")
+                                    .disabled(is_running)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.mark(BisectMark::Skip, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("bisect-good", "Good")
+                                    .disabled(is_running)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.mark(BisectMark::Good, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("bisect-bad", "Bad")
+                                    .disabled(is_running)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.mark(BisectMark::Bad, window, cx)
+                                    })),
+                            )
+                            .map(|this| {
+                                if is_running {
+                                    this.child(
+                                        Button::new("bisect-stop", "Stop Run")
+                                            .style(ButtonStyle::Filled)
+                                            .tooltip(Tooltip::text(
+                                                "Stop the task and keep the commits marked so far",
+                                            ))
+                                            .on_click(
+                                                cx.listener(|this, _, _, cx| this.stop_run(cx)),
+                                            ),
+                                    )
+                                } else {
+                                    this.child(
+                                        Button::new("bisect-run", "Run Task")
+                                            .style(ButtonStyle::Filled)
+                                            .disabled(!can_run)
+                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                this.run(window, cx)
+                                            })),
+                                    )
+                                }
+                            })
+                        }),
+                )
+            })
+    }
+}
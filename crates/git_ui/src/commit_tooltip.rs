@@ -14,7 +14,7 @@ use settings::Settings;
 use std::hash::Hash;
 use theme::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
-use ui::{Avatar, Divider, IconButtonShape, prelude::*, tooltip_container};
+use ui::{Avatar, Chip, Divider, IconButtonShape, prelude::*, tooltip_container};
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
@@ -127,6 +127,8 @@ pub struct CommitTooltip {
     markdown: Entity<Markdown>,
     repository: Entity<Repository>,
     workspace: WeakEntity<Workspace>,
+    /// Names of the tags pointing at the commit.
+    tags: Vec<SharedString>,
 }
 
 impl CommitTooltip {
@@ -178,12 +180,25 @@ impl CommitTooltip {
                 cx,
             )
         });
+        let tags = repository.update(cx, |repository, _| {
+            repository.tags(Some(commit.sha.to_string()))
+        });
+        cx.spawn(async move |this, cx| {
+            let tags = tags.await??;
+            this.update(cx, |this, cx| {
+                this.tags = tags.into_iter().map(|tag| tag.name).collect();
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+
         Self {
             commit,
             repository,
             workspace,
             scroll_handle: ScrollHandle::new(),
             markdown,
+            tags: Vec::new(),
         }
     }
 }
@@ -295,7 +310,11 @@ impl Render for CommitTooltip {
                                 .pt_1p5()
                                 .border_t_1()
                                 .border_color(cx.theme().colors().border_variant)
-                                .child(absolute_timestamp)
+                                .child(h_flex().gap_1().child(absolute_timestamp).children(
+                                    self.tags.iter().map(|tag| {
+                                        Chip::new(tag.clone()).label_color(Color::Warning)
+                                    }),
+                                ))
                                 .child(
                                     h_flex()
                                         .gap_1p5()
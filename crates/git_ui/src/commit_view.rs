@@ -3,14 +3,15 @@ use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::display_map::{BlockPlacement, BlockProperties, BlockStyle};
 use editor::{Editor, EditorEvent, ExcerptRange, MultiBuffer, multibuffer_context_lines};
 use git::repository::{CommitDetails, CommitDiff, RepoPath};
+use git::signing::CommitSignature;
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, ParsedGitRemote,
     parse_git_remote_url,
 };
 use gpui::{
-    AnyElement, App, AppContext as _, AsyncApp, AsyncWindowContext, Context, Element, Entity,
-    EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement,
-    PromptLevel, Render, Styled, Task, WeakEntity, Window, actions,
+    Action as _, AnyElement, App, AppContext as _, AsyncApp, AsyncWindowContext, Context, Corner,
+    Element, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement,
+    ParentElement, PromptLevel, Render, Styled, Task, WeakEntity, Window, actions,
 };
 use language::{
     Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
@@ -24,7 +25,7 @@ use std::{
     sync::Arc,
 };
 use theme::ActiveTheme;
-use ui::{DiffStat, Tooltip, prelude::*};
+use ui::{Chip, ContextMenu, DiffStat, PopoverMenu, Tooltip, prelude::*};
 use util::{ResultExt, paths::PathStyle, rel_path::RelPath, truncate_and_trailoff};
 use workspace::item::TabTooltipContent;
 use workspace::{
@@ -63,6 +64,9 @@ pub struct CommitView {
     multibuffer: Entity<MultiBuffer>,
     repository: Entity<Repository>,
     remote: Option<GitRemote>,
+    /// Names of the tags pointing at this commit.
+    tags: Vec<SharedString>,
+    signature: Option<CommitSignature>,
 }
 
 struct GitBlob {
@@ -288,6 +292,32 @@ impl CommitView {
         })
         .detach();
 
+        if stash.is_none() {
+            let tags = repository.update(cx, |repository, _| {
+                repository.tags(Some(commit.sha.to_string()))
+            });
+            cx.spawn(async move |this, cx| {
+                let tags = tags.await??;
+                this.update(cx, |this, cx| {
+                    this.tags = tags.into_iter().map(|tag| tag.name).collect();
+                    cx.notify();
+                })
+            })
+            .detach_and_log_err(cx);
+
+            let signature = repository.update(cx, |repository, _| {
+                repository.commit_signature(commit.sha.to_string())
+            });
+            cx.spawn(async move |this, cx| {
+                let signature = signature.await??;
+                this.update(cx, |this, cx| {
+                    this.signature = signature;
+                    cx.notify();
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+
         let snapshot = repository.read(cx).snapshot();
         let remote_url = snapshot
             .remote_upstream_url
@@ -310,6 +340,8 @@ impl CommitView {
             stash,
             repository,
             remote,
+            tags: Vec::new(),
+            signature: None,
         }
     }
 
@@ -460,7 +492,11 @@ impl CommitView {
                                             .size(LabelSize::Small)
                                             .truncate()
                                             .buffer_font(cx),
-                                    ),
+                                    )
+                                    .children(self.tags.iter().map(|tag| {
+                                        Chip::new(tag.clone()).label_color(Color::Warning)
+                                    }))
+                                    .children(self.signature.as_ref().map(render_signature_status)),
                             )
                             .child(
                                 h_flex()
@@ -478,22 +514,119 @@ impl CommitView {
                                     .children(commit_diff_stat),
                             ),
                     )
-                    .children(remote_info.map(|(provider_name, url)| {
-                        let icon = match provider_name.as_str() {
-                            "GitHub" => IconName::Github,
-                            _ => IconName::Link,
-                        };
-
-                        Button::new("view_on_provider", format!("View on {}", provider_name))
-                            .icon(icon)
-                            .icon_color(Color::Muted)
-                            .icon_size(IconSize::Small)
-                            .icon_position(IconPosition::Start)
-                            .on_click(move |_, _, cx| cx.open_url(&url))
-                    })),
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when(self.stash.is_none(), |this| {
+                                let sha = commit.sha.to_string();
+                                let focus_handle = self.editor.focus_handle(cx);
+                                this.child(
+                                    Button::new("cherry_pick", "Cherry-pick")
+                                        .icon(IconName::GitBranchAlt)
+                                        .icon_color(Color::Muted)
+                                        .icon_size(IconSize::Small)
+                                        .icon_position(IconPosition::Start)
+                                        .tooltip(Tooltip::text(
+                                            "Apply this commit onto the current branch",
+                                        ))
+                                        .on_click({
+                                            let sha = sha.clone();
+                                            move |_, window, cx| {
+                                                window.dispatch_action(
+                                                    git::CherryPick {
+                                                        commits: vec![sha.clone()],
+                                                        no_commit: false,
+                                                    }
+                                                    .boxed_clone(),
+                                                    cx,
+                                                )
+                                            }
+                                        }),
+                                )
+                                .child(
+                                    Button::new("revert", "Revert")
+                                        .icon(IconName::Undo)
+                                        .icon_color(Color::Muted)
+                                        .icon_size(IconSize::Small)
+                                        .icon_position(IconPosition::Start)
+                                        .tooltip(Tooltip::text(
+                                            "Create a commit undoing this commit",
+                                        ))
+                                        .on_click({
+                                            let sha = sha.clone();
+                                            move |_, window, cx| {
+                                                window.dispatch_action(
+                                                    git::Revert {
+                                                        commits: vec![sha.clone()],
+                                                        no_commit: false,
+                                                    }
+                                                    .boxed_clone(),
+                                                    cx,
+                                                )
+                                            }
+                                        }),
+                                )
+                                .child(
+                                    PopoverMenu::new("apply_commit_menu")
+                                        .trigger(
+                                            IconButton::new(
+                                                "apply_commit_menu_trigger",
+                                                IconName::Ellipsis,
+                                            )
+                                            .icon_size(IconSize::Small)
+                                            .icon_color(Color::Muted),
+                                        )
+                                        .menu(move |window, cx| {
+                                            let sha = sha.clone();
+                                            let focus_handle = focus_handle.clone();
+                                            Some(ContextMenu::build(window, cx, |menu, _, _| {
+                                                menu.context(focus_handle)
+                                                    .action(
+                                                        "Cherry-pick Without Committing",
+                                                        git::CherryPick {
+                                                            commits: vec![sha.clone()],
+                                                            no_commit: true,
+                                                        }
+                                                        .boxed_clone(),
+                                                    )
+                                                    .action(
+                                                        "Revert Without Committing",
+                                                        git::Revert {
+                                                            commits: vec![sha],
+                                                            no_commit: true,
+                                                        }
+                                                        .boxed_clone(),
+                                                    )
+                                            }))
+                                        })
+                                        .anchor(Corner::TopRight),
+                                )
+                            })
+                            .children(remote_info.map(|(provider_name, url)| {
+                                let icon = match provider_name.as_str() {
+                                    "GitHub" => IconName::Github,
+                                    _ => IconName::Link,
+                                };
+
+                                Button::new(
+                                    "view_on_provider",
+                                    format!("View on {}", provider_name),
+                                )
+                                .icon(icon)
+                                .icon_color(Color::Muted)
+                                .icon_size(IconSize::Small)
+                                .icon_position(IconPosition::Start)
+                                .on_click(move |_, _, cx| cx.open_url(&url))
+                            })),
+                    ),
             )
     }
 
+    /// The SHA of the commit being shown, unless it is a stash entry.
+    pub(crate) fn commit_sha(&self) -> Option<&SharedString> {
+        self.stash.is_none().then_some(&self.commit.sha)
+    }
+
     fn apply_stash(workspace: &mut Workspace, window: &mut Window, cx: &mut App) {
         Self::stash_action(
             workspace,
@@ -989,6 +1122,38 @@ impl ToolbarItemView for CommitViewToolbar {
     }
 }
 
+/// Renders whether a commit's signature could be verified, with the signer and key in a tooltip.
+fn render_signature_status(signature: &CommitSignature) -> impl IntoElement {
+    let status = signature.status;
+    let (icon, color) = if status.is_verified() {
+        (IconName::ShieldCheck, Color::Success)
+    } else if status.is_invalid() {
+        (IconName::XCircle, Color::Error)
+    } else {
+        (IconName::Warning, Color::Warning)
+    };
+    let details = signature
+        .signer
+        .iter()
+        .map(|signer| signer.to_string())
+        .chain(signature.key.iter().map(|key| format!("Key {key}")))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    h_flex()
+        .id("commit-signature")
+        .gap_0p5()
+        .child(Icon::new(icon).size(IconSize::Small).color(color))
+        .child(
+            Label::new(status.label())
+                .size(LabelSize::Small)
+                .color(color),
+        )
+        .when(!details.is_empty(), |this| {
+            this.tooltip(Tooltip::text(details))
+        })
+}
+
 fn stash_matches_index(sha: &str, stash_index: usize, repo: &Repository) -> bool {
     repo.stash_entries
         .entries
@@ -3,18 +3,20 @@ use futures::Future;
 use git::repository::{FileHistory, FileHistoryEntry, RepoPath};
 use git::{GitHostingProviderRegistry, GitRemote, parse_git_remote_url};
 use gpui::{
-    AnyElement, AnyEntity, App, Asset, Context, Entity, EventEmitter, FocusHandle, Focusable,
-    IntoElement, Render, ScrollStrategy, Task, UniformListScrollHandle, WeakEntity, Window,
-    actions, uniform_list,
+    Action as _, AnyElement, AnyEntity, App, Asset, ClickEvent, Context, Corner, DismissEvent,
+    Entity, EventEmitter, FocusHandle, Focusable, IntoElement, MouseDownEvent, Pixels, Point,
+    Render, ScrollStrategy, Subscription, Task, UniformListScrollHandle, WeakEntity, Window,
+    actions, anchored, deferred, uniform_list,
 };
 use project::{
     Project, ProjectPath,
     git_store::{GitStore, Repository},
 };
 use std::any::{Any, TypeId};
+use std::ops::RangeInclusive;
 
 use time::OffsetDateTime;
-use ui::{Avatar, Chip, Divider, ListItem, WithScrollbar, prelude::*};
+use ui::{Avatar, Chip, ContextMenu, Divider, ListItem, WithScrollbar, prelude::*};
 use util::ResultExt;
 use workspace::{
     Item, Workspace,
@@ -42,6 +44,9 @@ pub struct FileHistoryView {
     workspace: WeakEntity<Workspace>,
     remote: Option<GitRemote>,
     selected_entry: Option<usize>,
+    /// The other end of the selected range of entries, when one was extended with shift-click.
+    selection_anchor: Option<usize>,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     scroll_handle: UniformListScrollHandle,
     focus_handle: FocusHandle,
     loading_more: bool,
@@ -139,6 +144,8 @@ impl FileHistoryView {
             workspace,
             remote,
             selected_entry: None,
+            selection_anchor: None,
+            context_menu: None,
             scroll_handle,
             focus_handle,
             loading_more: false,
@@ -247,6 +254,7 @@ impl FileHistoryView {
 
     fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
         self.selected_entry = ix;
+        self.selection_anchor = None;
         if let Some(ix) = ix {
             self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
         }
@@ -279,6 +287,107 @@ impl FileHistoryView {
         }
     }
 
+    fn selected_range(&self) -> Option<RangeInclusive<usize>> {
+        let selected = self.selected_entry?;
+        let anchor = self.selection_anchor.unwrap_or(selected);
+        Some(selected.min(anchor)..=selected.max(anchor))
+    }
+
+    /// The SHAs of the selected entries, newest first.
+    fn selected_commits(&self) -> Vec<String> {
+        let Some(range) = self.selected_range() else {
+            return Vec::new();
+        };
+        self.history.entries[range]
+            .iter()
+            .map(|entry| entry.sha.to_string())
+            .collect()
+    }
+
+    fn deploy_entry_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self
+            .selected_range()
+            .is_some_and(|range| range.contains(&ix))
+        {
+            self.select_ix(Some(ix), cx);
+        }
+        let newest_first = self.selected_commits();
+        let mut oldest_first = newest_first.clone();
+        oldest_first.reverse();
+        let suffix = if newest_first.len() > 1 {
+            format!(" {} Commits", newest_first.len())
+        } else {
+            String::new()
+        };
+
+        // Cherry-picks are applied oldest first so that each commit lands on top of its
+        // predecessors, while reverts undo the newest commit first.
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action_disabled_when(
+                    newest_first.len() > 1,
+                    "Open Commit",
+                    menu::Confirm.boxed_clone(),
+                )
+                .separator()
+                .action(
+                    format!("Cherry-pick{suffix}"),
+                    git::CherryPick {
+                        commits: oldest_first.clone(),
+                        no_commit: false,
+                    }
+                    .boxed_clone(),
+                )
+                .action(
+                    format!("Cherry-pick{suffix} Without Committing"),
+                    git::CherryPick {
+                        commits: oldest_first,
+                        no_commit: true,
+                    }
+                    .boxed_clone(),
+                )
+                .separator()
+                .action(
+                    format!("Revert{suffix}"),
+                    git::Revert {
+                        commits: newest_first.clone(),
+                        no_commit: false,
+                    }
+                    .boxed_clone(),
+                )
+                .action(
+                    format!("Revert{suffix} Without Committing"),
+                    git::Revert {
+                        commits: newest_first,
+                        no_commit: true,
+                    }
+                    .boxed_clone(),
+                )
+        });
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
     fn render_commit_avatar(
         &self,
         sha: &SharedString,
@@ -333,8 +442,12 @@ impl FileHistoryView {
             time_format::TimestampFormat::Relative,
         );
 
+        let is_selected = self
+            .selected_range()
+            .is_some_and(|range| range.contains(&ix));
+
         ListItem::new(("commit", ix))
-            .toggle_state(Some(ix) == self.selected_entry)
+            .toggle_state(is_selected)
             .child(
                 h_flex()
                     .h_8()
@@ -381,12 +494,26 @@ impl FileHistoryView {
                             ),
                     ),
             )
-            .on_click(cx.listener(move |this, _, window, cx| {
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                if event.modifiers().shift && this.selected_entry.is_some() {
+                    this.selection_anchor = this.selection_anchor.or(this.selected_entry);
+                    this.selected_entry = Some(ix);
+                    cx.notify();
+                    return;
+                }
+
                 this.selected_entry = Some(ix);
+                this.selection_anchor = None;
                 cx.notify();
 
                 this.open_commit_view(window, cx);
             }))
+            .on_secondary_mouse_down(cx.listener(
+                move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_entry_context_menu(event.position, ix, window, cx);
+                    cx.stop_propagation();
+                },
+            ))
             .into_any_element()
     }
 }
@@ -533,6 +660,15 @@ impl Render for FileHistoryView {
                     })
                     .vertical_scrollbar_for(&self.scroll_handle, window, cx),
             )
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
     }
 }
 
@@ -0,0 +1,678 @@
+use std::any::{Any, TypeId};
+use std::time::Duration;
+
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use git::log::{GraphLayout, GraphRow, LogEntry, LogRefKind, LogSearch};
+use gpui::{
+    AnyElement, AnyEntity, App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    PathBuilder, Pixels, Render, ScrollStrategy, Subscription, Task, UniformListScrollHandle,
+    WeakEntity, Window, actions, canvas, fill, point, size, uniform_list,
+};
+use project::{
+    Project, ProjectPath,
+    git_store::{GitStore, Repository, RepositoryEvent},
+};
+use time::OffsetDateTime;
+use ui::{Chip, ListItem, Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt;
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, SaveOptions},
+};
+
+use crate::commit_view::CommitView;
+use crate::git_panel::GitPanel;
+
+actions!(
+    git_log,
+    [
+        /// Switches the log search between messages, authors and commit SHAs.
+        CycleSearchMode,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::CommitLog, window, cx| {
+        let Some(repository) = workspace
+            .panel::<GitPanel>(cx)
+            .and_then(|panel| panel.read(cx).active_repository.clone())
+        else {
+            return;
+        };
+        GitLogView::open(repository, workspace, window, cx);
+    });
+}
+
+const PAGE_SIZE: usize = 200;
+/// How close to the end of the loaded entries the list may scroll before loading the next page.
+const LOAD_MORE_THRESHOLD: usize = 50;
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+const LANE_WIDTH: Pixels = px(14.);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SearchMode {
+    #[default]
+    Message,
+    Author,
+    Sha,
+}
+
+impl SearchMode {
+    fn label(&self) -> &'static str {
+        match self {
+            SearchMode::Message => "Message",
+            SearchMode::Author => "Author",
+            SearchMode::Sha => "SHA",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            SearchMode::Message => SearchMode::Author,
+            SearchMode::Author => SearchMode::Sha,
+            SearchMode::Sha => SearchMode::Message,
+        }
+    }
+
+    fn search(&self, query: String) -> LogSearch {
+        match self {
+            SearchMode::Message => LogSearch::Message(query),
+            SearchMode::Author => LogSearch::Author(query),
+            SearchMode::Sha => LogSearch::Sha(query),
+        }
+    }
+}
+
+pub struct GitLogView {
+    repository: Entity<Repository>,
+    git_store: WeakEntity<GitStore>,
+    workspace: WeakEntity<Workspace>,
+    entries: Vec<LogEntry>,
+    graph: GraphLayout,
+    search: Option<LogSearch>,
+    search_mode: SearchMode,
+    search_editor: Entity<Editor>,
+    selected_entry: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    has_more: bool,
+    load_task: Option<Task<()>>,
+    search_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitLogView {
+    pub fn open(
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .find(|view| view.read(cx).repository == repository);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let git_store = workspace.project().read(cx).git_store().downgrade();
+        let workspace_handle = workspace.weak_handle();
+        let view = cx.new(|cx| Self::new(repository, git_store, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        git_store: WeakEntity<GitStore>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search commits…", window, cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe_in(
+                &search_editor,
+                window,
+                |this, _, event: &EditorEvent, window, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        this.schedule_search(window, cx);
+                    }
+                },
+            ),
+            cx.subscribe_in(
+                &repository,
+                window,
+                |this, _, event: &RepositoryEvent, window, cx| {
+                    if let RepositoryEvent::BranchChanged = event {
+                        this.reload(window, cx);
+                    }
+                },
+            ),
+        ];
+
+        let mut this = Self {
+            repository,
+            git_store,
+            workspace,
+            entries: Vec::new(),
+            graph: GraphLayout::default(),
+            search: None,
+            search_mode: SearchMode::default(),
+            search_editor,
+            selected_entry: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            has_more: true,
+            load_task: None,
+            search_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.reload(window, cx);
+        this
+    }
+
+    /// Discards the loaded entries and loads the first page of the log again.
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.graph = GraphLayout::default();
+        self.selected_entry = None;
+        self.has_more = true;
+        self.load_task = None;
+        self.load_more(window, cx);
+    }
+
+    fn load_more(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.load_task.is_some() || !self.has_more {
+            return;
+        }
+
+        let skip = self.entries.len();
+        let search = self.search.clone();
+        let repository = self.repository.clone();
+        let git_store = self.git_store.clone();
+        self.load_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let task = git_store
+                .update(cx, |git_store, cx| {
+                    git_store.log(&repository, search, skip, Some(PAGE_SIZE), cx)
+                })
+                .ok();
+            let Some(entries) = task else {
+                return;
+            };
+            let entries = entries.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                this.has_more = entries.len() >= PAGE_SIZE;
+                // The parents of filtered entries are mostly filtered out too, so their lanes
+                // would never close. Search results are listed without a graph instead.
+                if this.search.is_none() {
+                    for entry in &entries {
+                        this.graph.push(entry);
+                    }
+                }
+                this.entries.extend(entries);
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn schedule_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_task = Some(cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+            this.update_in(cx, |this, window, cx| {
+                this.update_search(window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn update_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx).trim().to_string();
+        let search = (!query.is_empty()).then(|| self.search_mode.search(query));
+        if search != self.search {
+            self.search = search;
+            self.reload(window, cx);
+        }
+    }
+
+    fn cycle_search_mode(
+        &mut self,
+        _: &CycleSearchMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search_mode = self.search_mode.next();
+        self.update_search(window, cx);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_entry {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1).min(self.entries.len() - 1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_entry {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some(ix.saturating_sub(1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_first(&mut self, _: &menu::SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = (!self.entries.is_empty()).then_some(0);
+        self.select_ix(ix, cx);
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.entries.len().checked_sub(1);
+        self.select_ix(ix, cx);
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_entry = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+        }
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_commit_view(window, cx);
+    }
+
+    fn open_commit_view(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.selected_entry.and_then(|ix| self.entries.get(ix)) else {
+            return;
+        };
+        CommitView::open(
+            entry.sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn render_graph(&self, row: &GraphRow, width: usize, cx: &App) -> impl IntoElement {
+        let row = row.clone();
+        let accents = cx.theme().accents().clone();
+        canvas(
+            |_, _, _| {},
+            move |bounds, _, window, _| {
+                let lane_x = |lane: usize| bounds.origin.x + LANE_WIDTH * (lane as f32 + 0.5);
+                let (top, middle, bottom) = (bounds.top(), bounds.center().y, bounds.bottom());
+                let segments = row
+                    .upper
+                    .iter()
+                    .map(|&(from, to)| (from, top, to, middle, from))
+                    .chain(
+                        row.lower
+                            .iter()
+                            .map(|&(from, to)| (from, middle, to, bottom, to)),
+                    );
+                for (from, from_y, to, to_y, color_lane) in segments {
+                    let mut builder = PathBuilder::stroke(px(1.5));
+                    builder.move_to(point(lane_x(from), from_y));
+                    builder.line_to(point(lane_x(to), to_y));
+                    if let Ok(path) = builder.build() {
+                        window.paint_path(path, accents.color_for_index(color_lane as u32));
+                    }
+                }
+
+                let radius = px(3.5);
+                window.paint_quad(
+                    fill(
+                        gpui::Bounds::centered_at(
+                            point(lane_x(row.lane), middle),
+                            size(radius * 2., radius * 2.),
+                        ),
+                        accents.color_for_index(row.lane as u32),
+                    )
+                    .corner_radii(radius),
+                );
+            },
+        )
+        .flex_none()
+        .h_full()
+        .w(LANE_WIDTH * width as f32)
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &LogEntry,
+        graph_width: usize,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let commit_time = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::UNIX_EPOCH);
+        let relative_timestamp = time_format::format_localized_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC),
+            time_format::TimestampFormat::Relative,
+        );
+        let short_sha: String = entry.sha.chars().take(git::SHORT_SHA_LENGTH).collect();
+        let graph_row = self
+            .search
+            .is_none()
+            .then(|| self.graph.rows().get(ix))
+            .flatten();
+
+        ListItem::new(("commit", ix))
+            .toggle_state(Some(ix) == self.selected_entry)
+            .child(
+                h_flex()
+                    .h_7()
+                    .w_full()
+                    .pr_2p5()
+                    .gap_2()
+                    .children(graph_row.map(|row| self.render_graph(row, graph_width, cx)))
+                    .child(
+                        h_flex()
+                            .min_w_0()
+                            .w_full()
+                            .gap_1()
+                            .children(entry.refs.iter().map(|log_ref| {
+                                let color = match log_ref.kind {
+                                    LogRefKind::Head => Color::Accent,
+                                    LogRefKind::LocalBranch => Color::Success,
+                                    LogRefKind::RemoteBranch => Color::Info,
+                                    LogRefKind::Tag => Color::Warning,
+                                };
+                                Chip::new(log_ref.name.clone()).label_color(color)
+                            }))
+                            .child(
+                                Label::new(entry.subject.clone())
+                                    .size(LabelSize::Small)
+                                    .truncate(),
+                            ),
+                    )
+                    .child(
+                        h_flex()
+                            .flex_none()
+                            .gap_2()
+                            .child(
+                                Label::new(entry.author_name.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(short_sha)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .buffer_font(cx),
+                            )
+                            .child(
+                                Label::new(relative_timestamp)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.selected_entry = Some(ix);
+                cx.notify();
+
+                this.open_commit_view(window, cx);
+            }))
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<ItemEvent> for GitLogView {}
+
+impl Focusable for GitLogView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for GitLogView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        let loading = self.load_task.is_some();
+
+        v_flex()
+            .id("git_log_view")
+            .key_context("GitLogView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cycle_search_mode))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .h(rems_from_px(41.))
+                    .pl_3()
+                    .pr_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::MagnifyingGlass)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(div().flex_1().child(self.search_editor.clone()))
+                    .child(
+                        Button::new("search-mode", self.search_mode.label())
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::for_action_title(
+                                "Change Search Field",
+                                &CycleSearchMode,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cycle_search_mode(&CycleSearchMode, window, cx)
+                            })),
+                    )
+                    .child(
+                        Label::new(if loading {
+                            "Loading…".to_string()
+                        } else if self.has_more {
+                            format!("{entry_count}+ commits")
+                        } else {
+                            format!("{entry_count} commits")
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .size_full()
+                    .child({
+                        let view = cx.weak_entity();
+                        uniform_list("git-log-list", entry_count, move |range, window, cx| {
+                            let Some(view) = view.upgrade() else {
+                                return Vec::new();
+                            };
+                            view.update(cx, |this, cx| {
+                                if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                                    this.load_more(window, cx);
+                                }
+                                let graph_width = this
+                                    .graph
+                                    .rows()
+                                    .get(range.clone())
+                                    .unwrap_or_default()
+                                    .iter()
+                                    .map(|row| row.width)
+                                    .max()
+                                    .unwrap_or(0);
+                                let mut items = Vec::with_capacity(range.end - range.start);
+                                for ix in range {
+                                    if let Some(entry) = this.entries.get(ix) {
+                                        items.push(this.render_entry(ix, entry, graph_width, cx));
+                                    }
+                                }
+                                items
+                            })
+                        })
+                        .flex_1()
+                        .size_full()
+                        .track_scroll(&self.scroll_handle)
+                    })
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+    }
+}
+
+impl Item for GitLogView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        format!("Log: {}", self.repository.read(cx).display_name()).into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(
+            format!(
+                "Git log for {}",
+                self.repository.read(cx).work_directory_abs_path.display()
+            )
+            .into(),
+        )
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git log")
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>> {
+        Task::ready(None)
+    }
+
+    fn navigate(&mut self, _: Box<dyn Any>, _window: &mut Window, _: &mut Context<Self>) -> bool {
+        false
+    }
+
+    fn deactivated(&mut self, _window: &mut Window, _: &mut Context<Self>) {}
+
+    fn can_save(&self, _: &App) -> bool {
+        false
+    }
+
+    fn save(
+        &mut self,
+        _options: SaveOptions,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn save_as(
+        &mut self,
+        _project: Entity<Project>,
+        _path: ProjectPath,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        GitLogView::reload(self, window, cx);
+        Task::ready(Ok(()))
+    }
+
+    fn is_dirty(&self, _: &App) -> bool {
+        false
+    }
+
+    fn has_conflict(&self, _: &App) -> bool {
+        false
+    }
+
+    fn breadcrumbs(
+        &self,
+        _theme: &theme::Theme,
+        _cx: &App,
+    ) -> Option<Vec<workspace::item::BreadcrumbText>> {
+        None
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        _workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle, cx);
+    }
+
+    fn show_toolbar(&self) -> bool {
+        true
+    }
+
+    fn pixel_position_of_cursor(&self, _: &App) -> Option<gpui::Point<gpui::Pixels>> {
+        None
+    }
+
+    fn set_nav_history(
+        &mut self,
+        _: workspace::ItemNavHistory,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else {
+            None
+        }
+    }
+}
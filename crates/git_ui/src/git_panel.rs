@@ -2,9 +2,10 @@ use crate::askpass_modal::AskPassModal;
 use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
+use crate::merge_view::MergeView;
 use crate::project_diff::{self, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
-use crate::{branch_picker, picker_prompt, render_remote_button};
+use crate::{branch_picker, picker_prompt, render_remote_button, submodule_picker};
 use crate::{
     file_history_view::FileHistoryView, git_panel_settings::GitPanelSettings, git_status_icon,
     repository_selector::RepositorySelector,
@@ -20,19 +21,20 @@ use editor::{
     Direction, Editor, EditorElement, EditorMode, MultiBuffer, MultiBufferOffset,
     actions::ExpandAllDiffHunks,
 };
-use futures::StreamExt as _;
+use futures::{StreamExt as _, channel::oneshot};
 use git::commit::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions, GitCommitter,
-    PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
-    UpstreamTrackingStatus, get_git_committer,
+    PushOptions, Remote, RemoteCommandOutput, ResetMode, SequencerCommand, SequencerControl,
+    SequencerOutcome, Upstream, UpstreamTracking, UpstreamTrackingStatus, get_git_committer,
 };
 use git::stash::GitStash;
 use git::status::StageStatus;
-use git::{Amend, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
+use git::submodule::SubmoduleStatus;
+use git::{Amend, GpgSign, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
 use git::{
-    ExpandCommitEditor, GitHostingProviderRegistry, RestoreTrackedFiles, StageAll, StashAll,
-    StashApply, StashPop, TrashUntrackedFiles, UnstageAll,
+    ExpandCommitEditor, GitHostingProviderRegistry, PullRequestDetails, RestoreTrackedFiles,
+    StageAll, StashAll, StashApply, StashPop, TrashUntrackedFiles, UnstageAll,
 };
 use gpui::{
     Action, AsyncApp, AsyncWindowContext, Bounds, ClickEvent, Corner, DismissEvent, Entity,
@@ -171,8 +173,15 @@ fn git_panel_context_menu(
             )
             .action_disabled_when(!state.has_stash_items, "Stash Pop", StashPop.boxed_clone())
             .action("View Stash", zed_actions::git::ViewStash.boxed_clone())
+            .action("View Tags", zed_actions::git::ViewTags.boxed_clone())
+            .action(
+                "View Submodules",
+                zed_actions::git::ViewSubmodules.boxed_clone(),
+            )
             .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .action("View Commit Log", git::CommitLog.boxed_clone())
+            .action("Bisect", git::Bisect.boxed_clone())
             .separator()
             .action_disabled_when(
                 !state.has_tracked_changes,
@@ -235,6 +244,8 @@ struct SerializedGitPanel {
     amend_pending: bool,
     #[serde(default)]
     signoff_enabled: bool,
+    #[serde(default)]
+    gpg_sign_enabled: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -598,6 +609,7 @@ pub struct GitPanel {
     amend_pending: bool,
     original_commit_message: Option<String>,
     signoff_enabled: bool,
+    gpg_sign_enabled: bool,
     pending_serialization: Task<()>,
     pub(crate) project: Entity<Project>,
     scroll_handle: UniformListScrollHandle,
@@ -725,7 +737,10 @@ impl GitPanel {
                         _,
                         RepositoryEvent::StatusesChanged
                         | RepositoryEvent::BranchChanged
-                        | RepositoryEvent::MergeHeadsChanged,
+                        | RepositoryEvent::MergeHeadsChanged
+                        | RepositoryEvent::RebaseStatusChanged
+                        | RepositoryEvent::SubmodulesChanged
+                        | RepositoryEvent::BisectStatusChanged,
                         true,
                     )
                     | GitStoreEvent::RepositoryAdded
@@ -764,6 +779,7 @@ impl GitPanel {
                 amend_pending: false,
                 original_commit_message: None,
                 signoff_enabled: false,
+                gpg_sign_enabled: false,
                 pending_serialization: Task::ready(()),
                 single_staged_entry: None,
                 single_tracked_entry: None,
@@ -871,6 +887,7 @@ impl GitPanel {
         let width = self.width;
         let amend_pending = self.amend_pending;
         let signoff_enabled = self.signoff_enabled;
+        let gpg_sign_enabled = self.gpg_sign_enabled;
 
         self.pending_serialization = cx.spawn(async move |git_panel, cx| {
             cx.background_executor()
@@ -898,6 +915,7 @@ impl GitPanel {
                                 width,
                                 amend_pending,
                                 signoff_enabled,
+                                gpg_sign_enabled,
                             })?,
                         )
                         .await?;
@@ -2073,6 +2091,7 @@ impl GitPanel {
                 CommitOptions {
                     amend: false,
                     signoff: self.signoff_enabled,
+                    sign: self.gpg_sign_enabled,
                 },
                 window,
                 cx,
@@ -2113,6 +2132,7 @@ impl GitPanel {
                         CommitOptions {
                             amend: true,
                             signoff: self.signoff_enabled,
+                            sign: self.gpg_sign_enabled,
                         },
                         window,
                         cx,
@@ -2347,6 +2367,117 @@ impl GitPanel {
         self.pending_commit = Some(task);
     }
 
+    pub(crate) fn rebase_continue(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rebase_control("rebase continue", |repo| repo.rebase_continue(), window, cx);
+    }
+
+    pub(crate) fn rebase_skip(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rebase_control("rebase skip", |repo| repo.rebase_skip(), window, cx);
+    }
+
+    pub(crate) fn rebase_abort(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rebase_control("rebase abort", |repo| repo.rebase_abort(), window, cx);
+    }
+
+    fn rebase_control(
+        &mut self,
+        operation: &'static str,
+        f: impl FnOnce(&mut Repository) -> oneshot::Receiver<anyhow::Result<()>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let receiver = repo.update(cx, |repo, _| f(repo));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = receiver.await;
+            this.update(cx, |this, cx| match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => this.show_error_toast(operation, e, cx),
+                Err(e) => this.show_error_toast(operation, e.into(), cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub(crate) fn apply_commits(
+        &mut self,
+        command: SequencerCommand,
+        commits: Vec<String>,
+        no_commit: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Commits Applied", command = command.as_str());
+        let receiver = repo.update(cx, |repo, _| match command {
+            SequencerCommand::CherryPick => repo.cherry_pick(commits, no_commit),
+            SequencerCommand::Revert => repo.revert(commits, no_commit),
+        });
+        self.handle_sequencer_outcome(command, receiver, window, cx);
+    }
+
+    pub(crate) fn sequencer_control(
+        &mut self,
+        control: SequencerControl,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(command) = repo.read(cx).merge.sequencer else {
+            return;
+        };
+        let receiver = repo.update(cx, |repo, _| repo.sequencer_control(command, control));
+        self.handle_sequencer_outcome(command, receiver, window, cx);
+    }
+
+    fn handle_sequencer_outcome(
+        &mut self,
+        command: SequencerCommand,
+        receiver: oneshot::Receiver<anyhow::Result<SequencerOutcome>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            let result = receiver.await;
+            this.update(cx, |this, cx| match result {
+                Ok(Ok(SequencerOutcome::Completed)) => {}
+                Ok(Ok(SequencerOutcome::Conflicted)) => {
+                    this.show_sequencer_conflicts_toast(command, cx)
+                }
+                Ok(Err(e)) => this.show_error_toast(command.as_str(), e, cx),
+                Err(e) => this.show_error_toast(command.as_str(), e.into(), cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Points the user at the conflicts a cherry-pick or revert stopped on, which are resolved
+    /// in the project diff like any other merge conflict.
+    fn show_sequencer_conflicts_toast(&self, command: SequencerCommand, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let message = format!("git {} stopped due to conflicts", command.as_str());
+            let toast = StatusToast::new(message, cx, |this, _cx| {
+                this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+                    .action("Resolve Conflicts", |window, cx| {
+                        window.dispatch_action(crate::project_diff::Diff.boxed_clone(), cx)
+                    })
+                    .dismiss_button(true)
+            });
+            workspace.toggle_status_toast(toast, cx)
+        });
+    }
+
     fn check_for_pushed_commits(
         &mut self,
         window: &mut Window,
@@ -2715,6 +2846,7 @@ impl GitPanel {
                     stop: Vec::new(),
                     temperature,
                     thinking_allowed: false,
+                    max_output_tokens: None,
                 };
 
                 let stream = model.stream_completion_text(request, cx);
@@ -2830,7 +2962,9 @@ impl GitPanel {
                 this.update(cx, |this, cx| {
                     let action = match fetch_options {
                         FetchOptions::All => RemoteAction::Fetch(None),
-                        FetchOptions::Remote(remote) => RemoteAction::Fetch(Some(remote)),
+                        FetchOptions::Remote(remote) | FetchOptions::Refspec { remote, .. } => {
+                            RemoteAction::Fetch(Some(remote))
+                        }
                     };
                     match remote_message {
                         Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
@@ -3076,6 +3210,154 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        tag_name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let remote = self.get_remote(true, true, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let push = repo.update(cx, |repo, _| {
+                repo.push_tag(tag_name.clone(), remote.name.clone(), askpass_delegate)
+            })?;
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::PushTag(tag_name, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn update_submodule(
+        &mut self,
+        path: RepoPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let name: SharedString = path.as_unix_str().to_string().into();
+        let askpass_delegate =
+            self.askpass_delegate(format!("git submodule update {name}"), window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let update = repo.update(cx, |repo, _| {
+                repo.update_submodules(vec![path], askpass_delegate)
+            })?;
+
+            let remote_output = update.await?;
+
+            let action = RemoteAction::UpdateSubmodule(name);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while updating submodule {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn checkout_pull_request(
+        &mut self,
+        pull_request: PullRequestDetails,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let action = format!("checkout #{}", pull_request.number);
+        let askpass_delegate = self.askpass_delegate(format!("git {action}"), window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let checkout = repo.update(cx, |repo, cx| {
+                repo.checkout_pull_request(&pull_request, askpass_delegate, cx)
+            })?;
+            if let Err(e) = checkout.await {
+                log::error!("Error while checking out pull request {:?}", e);
+                this.update(cx, |this, cx| this.show_error_toast(action, e, cx))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn sync_submodule(&mut self, path: RepoPath, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        cx.spawn_in(window, async move |this, cx| {
+            let result = repo
+                .update(cx, |repo, _| repo.sync_submodules(vec![path.clone()]))?
+                .await?;
+            if let Err(e) = result {
+                log::error!("Error while syncing submodule {:?}", e);
+                this.update(cx, |this, cx| {
+                    this.show_error_toast(format!("submodule sync {}", path.as_unix_str()), e, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_submodule_repository(&mut self, path: &RepoPath, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        if let Err(e) = submodule_picker::open_submodule_repository(&self.project, &repo, path, cx)
+        {
+            self.show_error_toast("open submodule", e, cx);
+        }
+    }
+
+    fn open_merge_editor(&mut self, path: RepoPath, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        MergeView::open_conflict(repo, path, self.workspace.clone(), window, cx)
+            .detach_and_prompt_err("Failed to open merge editor", window, cx, |_, _, _| None);
+    }
+
     fn askpass_delegate(
         &self,
         operation: impl Into<SharedString>,
@@ -3957,6 +4239,7 @@ impl GitPanel {
                 let has_previous_commit = self.head_commit(cx).is_some();
                 let amend = self.amend_pending();
                 let signoff = self.signoff_enabled;
+                let gpg_sign = self.gpg_sign_enabled;
 
                 move |window, cx| {
                     Some(ContextMenu::build(window, cx, |context_menu, _, _| {
@@ -3989,6 +4272,13 @@ impl GitPanel {
                                 Some(Box::new(Signoff)),
                                 move |window, cx| window.dispatch_action(Box::new(Signoff), cx),
                             )
+                            .toggleable_entry(
+                                "Sign",
+                                gpg_sign,
+                                IconPosition::Start,
+                                Some(Box::new(GpgSign)),
+                                move |window, cx| window.dispatch_action(Box::new(GpgSign), cx),
+                            )
                     }))
                 }
             })
@@ -4261,6 +4551,7 @@ impl GitPanel {
         let commit_tooltip_focus_handle = self.commit_editor.focus_handle(cx);
         let amend = self.amend_pending();
         let signoff = self.signoff_enabled;
+        let sign = self.gpg_sign_enabled;
 
         let label_color = if self.pending_commit.is_some() {
             Color::Disabled
@@ -4294,7 +4585,11 @@ impl GitPanel {
                         git_panel
                             .update(cx, |git_panel, cx| {
                                 git_panel.commit_changes(
-                                    CommitOptions { amend, signoff },
+                                    CommitOptions {
+                                        amend,
+                                        signoff,
+                                        sign,
+                                    },
                                     window,
                                     cx,
                                 );
@@ -4311,9 +4606,10 @@ impl GitPanel {
                                 tooltip,
                                 Some(if amend { &git::Amend } else { &git::Commit }),
                                 format!(
-                                    "git commit{}{}",
+                                    "git commit{}{}{}",
                                     if amend { " --amend" } else { "" },
-                                    if signoff { " --signoff" } else { "" }
+                                    if signoff { " --signoff" } else { "" },
+                                    if sign { " --gpg-sign" } else { "" }
                                 ),
                                 &handle.clone(),
                                 cx,
@@ -4332,6 +4628,141 @@ impl GitPanel {
             ))
     }
 
+    fn render_rebase_in_progress(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let active_repository = self.active_repository.as_ref()?;
+        let rebase = active_repository.read(cx).rebase.as_ref()?;
+        let (done, total) = rebase.progress();
+        let mut title = match &rebase.head_name {
+            Some(head_name) => format!("Rebasing {head_name}"),
+            None => "Rebasing detached HEAD".to_string(),
+        };
+        if total > 0 {
+            title.push_str(&format!(" ({done}/{total})"));
+        }
+        if let Some(stopped_sha) = &rebase.stopped_sha {
+            let short_sha: String = stopped_sha.chars().take(git::SHORT_SHA_LENGTH).collect();
+            title.push_str(&format!(", stopped at {short_sha}"));
+        }
+        let interactive = rebase.interactive;
+
+        Some(
+            h_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(title).size(LabelSize::Small).truncate()),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .when(interactive, |this| {
+                            this.child(
+                                panel_icon_button("rebase-edit-todo", IconName::ListTodo)
+                                    .icon_size(IconSize::XSmall)
+                                    .icon_color(Color::Muted)
+                                    .tooltip(Tooltip::for_action_title(
+                                        "Edit Rebase Todo",
+                                        &git::InteractiveRebase,
+                                    ))
+                                    .on_click(|_, window, cx| {
+                                        window.dispatch_action(
+                                            git::InteractiveRebase.boxed_clone(),
+                                            cx,
+                                        )
+                                    }),
+                            )
+                        })
+                        .child(panel_button("Abort").size(ButtonSize::Default).on_click(
+                            cx.listener(|this, _, window, cx| this.rebase_abort(window, cx)),
+                        ))
+                        .child(panel_button("Skip").size(ButtonSize::Default).on_click(
+                            cx.listener(|this, _, window, cx| this.rebase_skip(window, cx)),
+                        ))
+                        .child(
+                            panel_filled_button("Continue")
+                                .size(ButtonSize::Default)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.rebase_continue(window, cx)
+                                })),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn render_sequencer_in_progress(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let active_repository = self.active_repository.as_ref()?;
+        let repository = active_repository.read(cx);
+        let command = repository.merge.sequencer?;
+        let title = match command {
+            SequencerCommand::CherryPick => "Cherry-pick in progress",
+            SequencerCommand::Revert => "Revert in progress",
+        };
+        let has_conflicts = !repository.merge.conflicted_paths.is_empty();
+
+        Some(
+            h_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(title).size(LabelSize::Small).truncate()),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .when(has_conflicts, |this| {
+                            this.child(
+                                panel_icon_button("sequencer-conflicts", IconName::Warning)
+                                    .icon_size(IconSize::XSmall)
+                                    .icon_color(Color::Warning)
+                                    .tooltip(Tooltip::for_action_title(
+                                        "Resolve Conflicts",
+                                        &crate::project_diff::Diff,
+                                    ))
+                                    .on_click(|_, window, cx| {
+                                        window.dispatch_action(
+                                            crate::project_diff::Diff.boxed_clone(),
+                                            cx,
+                                        )
+                                    }),
+                            )
+                        })
+                        .child(panel_button("Abort").size(ButtonSize::Default).on_click(
+                            cx.listener(|this, _, window, cx| {
+                                this.sequencer_control(SequencerControl::Abort, window, cx)
+                            }),
+                        ))
+                        .child(panel_button("Skip").size(ButtonSize::Default).on_click(
+                            cx.listener(|this, _, window, cx| {
+                                this.sequencer_control(SequencerControl::Skip, window, cx)
+                            }),
+                        ))
+                        .child(
+                            panel_filled_button("Continue")
+                                .size(ButtonSize::Default)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.sequencer_control(SequencerControl::Continue, window, cx)
+                                })),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         h_flex()
             .py_1p5()
@@ -4747,8 +5178,15 @@ impl GitPanel {
         } else {
             "Discard Changes"
         };
+        let submodule = self.active_repository.as_ref().and_then(|repo| {
+            repo.read(cx)
+                .submodule(&entry.repo_path)
+                .map(|submodule| (submodule.path.clone(), submodule.status))
+        });
+        let panel = cx.weak_entity();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             let is_created = entry.status.is_created();
+            let is_conflicted = entry.status.is_conflicted();
             context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
@@ -4763,6 +5201,57 @@ impl GitPanel {
                 .action("Open File", menu::SecondaryConfirm.boxed_clone())
                 .separator()
                 .action_disabled_when(is_created, "View File History", Box::new(git::FileHistory))
+                .when(is_conflicted, |context_menu| {
+                    context_menu.entry("Open Merge Editor", None, {
+                        let panel = panel.clone();
+                        let repo_path = entry.repo_path.clone();
+                        move |window, cx| {
+                            panel
+                                .update(cx, |panel, cx| {
+                                    panel.open_merge_editor(repo_path.clone(), window, cx)
+                                })
+                                .ok();
+                        }
+                    })
+                })
+                .when_some(submodule, |context_menu, (path, status)| {
+                    context_menu
+                        .separator()
+                        .entry("Update Submodule", None, {
+                            let panel = panel.clone();
+                            let path = path.clone();
+                            move |window, cx| {
+                                panel
+                                    .update(cx, |panel, cx| {
+                                        panel.update_submodule(path.clone(), window, cx)
+                                    })
+                                    .ok();
+                            }
+                        })
+                        .entry("Sync Submodule", None, {
+                            let panel = panel.clone();
+                            let path = path.clone();
+                            move |window, cx| {
+                                panel
+                                    .update(cx, |panel, cx| {
+                                        panel.sync_submodule(path.clone(), window, cx)
+                                    })
+                                    .ok();
+                            }
+                        })
+                        .when(status != SubmoduleStatus::Uninitialized, |context_menu| {
+                            context_menu.entry("Open Submodule Repository", None, {
+                                let panel = panel.clone();
+                                move |_, cx| {
+                                    panel
+                                        .update(cx, |panel, cx| {
+                                            panel.open_submodule_repository(&path, cx)
+                                        })
+                                        .ok();
+                                }
+                            })
+                        })
+                })
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -4911,6 +5400,13 @@ impl GitPanel {
             )
         };
 
+        let submodule_indicator = repo.submodule(&entry.repo_path).map(|submodule| {
+            submodule_picker::submodule_indicator(
+                ElementId::Name(format!("entry_{}_{}_submodule", display_name, ix).into()),
+                submodule,
+            )
+        });
+
         let name_row = h_flex()
             .min_w_0()
             .flex_1()
@@ -4934,7 +5430,8 @@ impl GitPanel {
                         status.is_deleted(),
                     ))
                 }
-            });
+            })
+            .children(submodule_indicator);
 
         h_flex()
             .id(id)
@@ -5261,6 +5758,25 @@ impl GitPanel {
         self.set_signoff_enabled(!self.signoff_enabled, cx);
     }
 
+    pub fn gpg_sign_enabled(&self) -> bool {
+        self.gpg_sign_enabled
+    }
+
+    pub fn set_gpg_sign_enabled(&mut self, value: bool, cx: &mut Context<Self>) {
+        self.gpg_sign_enabled = value;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    pub fn toggle_gpg_sign_enabled(
+        &mut self,
+        _: &GpgSign,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_gpg_sign_enabled(!self.gpg_sign_enabled, cx);
+    }
+
     pub async fn load(
         workspace: WeakEntity<Workspace>,
         mut cx: AsyncWindowContext,
@@ -5291,6 +5807,7 @@ impl GitPanel {
                     panel.width = serialized_panel.width;
                     panel.amend_pending = serialized_panel.amend_pending;
                     panel.signoff_enabled = serialized_panel.signoff_enabled;
+                    panel.gpg_sign_enabled = serialized_panel.gpg_sign_enabled;
                     cx.notify();
                 })
             }
@@ -5371,6 +5888,7 @@ impl Render for GitPanel {
                     .on_action(cx.listener(GitPanel::on_commit))
                     .on_action(cx.listener(GitPanel::on_amend))
                     .on_action(cx.listener(GitPanel::toggle_signoff_enabled))
+                    .on_action(cx.listener(GitPanel::toggle_gpg_sign_enabled))
                     .on_action(cx.listener(Self::stage_all))
                     .on_action(cx.listener(Self::unstage_all))
                     .on_action(cx.listener(Self::stage_selected))
@@ -5420,11 +5938,19 @@ impl Render for GitPanel {
                         }
                     })
                     .children(self.render_footer(window, cx))
-                    .when(self.amend_pending, |this| {
-                        this.child(self.render_pending_amend(cx))
-                    })
-                    .when(!self.amend_pending, |this| {
-                        this.children(self.render_previous_commit(cx))
+                    .map(|this| {
+                        if let Some(rebase) = self.render_rebase_in_progress(cx) {
+                            this.child(rebase)
+                        } else if let Some(sequencer) = self.render_sequencer_in_progress(cx) {
+                            this.child(sequencer)
+                        } else if self.amend_pending {
+                            this.child(self.render_pending_amend(cx).into_any_element())
+                        } else {
+                            this.children(
+                                self.render_previous_commit(cx)
+                                    .map(|commit| commit.into_any_element()),
+                            )
+                        }
                     })
                     .into_any_element(),
             )
@@ -13,7 +13,10 @@ mod blame_ui;
 pub mod clone;
 
 use git::{
-    repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
+    repository::{
+        Branch, SequencerCommand, SequencerControl, Upstream, UpstreamTracking,
+        UpstreamTrackingStatus,
+    },
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
 use gpui::{
@@ -31,6 +34,7 @@ use zed_actions;
 use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
+pub mod bisect_view;
 pub mod branch_picker;
 mod commit_modal;
 pub mod commit_tooltip;
@@ -38,14 +42,20 @@ pub mod commit_view;
 mod conflict_view;
 pub mod file_diff_view;
 pub mod file_history_view;
+pub mod git_log_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod merge_view;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod pull_requests;
+pub mod rebase_todo_editor;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
+pub mod submodule_picker;
+pub mod tag_picker;
 pub mod text_diff_view;
 pub mod worktree_picker;
 
@@ -75,6 +85,13 @@ pub fn init(cx: &mut App) {
         branch_picker::register(workspace);
         worktree_picker::register(workspace);
         stash_picker::register(workspace);
+        submodule_picker::register(workspace);
+        tag_picker::register(workspace);
+        rebase_todo_editor::register(workspace);
+        bisect_view::register(workspace);
+        merge_view::register(workspace);
+        pull_requests::register(workspace);
+        git_log_view::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
@@ -178,6 +195,74 @@ pub fn init(cx: &mut App) {
                 panel.unstage_all(action, window, cx);
             });
         });
+        workspace.register_action(|workspace, _: &git::RebaseContinue, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.rebase_continue(window, cx);
+            })
+        });
+        workspace.register_action(|workspace, _: &git::RebaseSkip, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.rebase_skip(window, cx);
+            })
+        });
+        workspace.register_action(|workspace, _: &git::RebaseAbort, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.rebase_abort(window, cx);
+            })
+        });
+        workspace.register_action(|workspace, action: &git::CherryPick, window, cx| {
+            apply_commits(
+                workspace,
+                SequencerCommand::CherryPick,
+                &action.commits,
+                action.no_commit,
+                window,
+                cx,
+            );
+        });
+        workspace.register_action(|workspace, action: &git::Revert, window, cx| {
+            apply_commits(
+                workspace,
+                SequencerCommand::Revert,
+                &action.commits,
+                action.no_commit,
+                window,
+                cx,
+            );
+        });
+        workspace.register_action(|workspace, _: &git::SequencerContinue, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.sequencer_control(SequencerControl::Continue, window, cx);
+            })
+        });
+        workspace.register_action(|workspace, _: &git::SequencerSkip, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.sequencer_control(SequencerControl::Skip, window, cx);
+            })
+        });
+        workspace.register_action(|workspace, _: &git::SequencerAbort, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.sequencer_control(SequencerControl::Abort, window, cx);
+            })
+        });
         workspace.register_action(|workspace, _: &git::Uncommit, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
@@ -418,6 +503,33 @@ fn rename_current_branch(
     });
 }
 
+fn apply_commits(
+    workspace: &mut Workspace,
+    command: SequencerCommand,
+    commits: &[String],
+    no_commit: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let commits = if commits.is_empty() {
+        let Some(commit_view) = workspace.active_item_as::<commit_view::CommitView>(cx) else {
+            return;
+        };
+        let Some(sha) = commit_view.read(cx).commit_sha() else {
+            return;
+        };
+        vec![sha.to_string()]
+    } else {
+        commits.to_vec()
+    };
+    let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+        return;
+    };
+    panel.update(cx, |panel, cx| {
+        panel.apply_commits(command, commits, no_commit, window, cx);
+    });
+}
+
 fn render_remote_button(
     id: impl Into<SharedString>,
     branch: &Branch,
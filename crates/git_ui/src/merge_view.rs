@@ -0,0 +1,806 @@
+//! MergeView resolves a conflicted file in four panes: the common base, both sides of the
+//! merge, and the editable result.
+
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use editor::{
+    Editor, EditorEvent, MultiBuffer, SelectionEffects, ToOffset as _, scroll::Autoscroll,
+};
+use git::repository::RepoPath;
+use gpui::{
+    AnyElement, App, AppContext as _, AsyncWindowContext, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, IntoElement, Render, Task, WeakEntity, Window, actions,
+};
+use language::{Anchor, Buffer, Capability, ToOffset as _};
+use project::{ConflictRegion, ConflictSet, ConflictSetSnapshot, Project, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Item, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::file_diff_view::build_buffer_diff;
+
+actions!(
+    merge_view,
+    [
+        /// Moves to the next conflict in the merge result.
+        NextConflict,
+        /// Moves to the previous conflict in the merge result.
+        PreviousConflict,
+        /// Resolves the conflict at the cursor with our side.
+        UseOurs,
+        /// Resolves the conflict at the cursor with their side.
+        UseTheirs,
+        /// Resolves the conflict at the cursor by keeping both sides.
+        UseBoth,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::OpenMergeEditor, window, cx| {
+        MergeView::deploy(workspace, window, cx);
+    });
+}
+
+/// Whether a merge result still has conflict markers, like when the user closed the merge editor
+/// without resolving every conflict.
+pub fn has_conflict_markers(text: &str) -> bool {
+    text.lines()
+        .any(|line| line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> "))
+}
+
+/// The buffers shown by a [`MergeView`].
+struct MergeBuffers {
+    base: Entity<Buffer>,
+    ours: Entity<Buffer>,
+    theirs: Entity<Buffer>,
+    result: Entity<Buffer>,
+}
+
+pub struct MergeView {
+    base_editor: Entity<Editor>,
+    ours_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    result_buffer: Entity<Buffer>,
+    /// The conflict markers left in the result, reparsed whenever it changes.
+    conflicts: ConflictSetSnapshot,
+}
+
+impl MergeView {
+    /// Opens the merge editor for the active editor's file, if it has merge conflicts.
+    fn deploy(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            return;
+        };
+        Self::open_conflict(repository, repo_path, workspace.weak_handle(), window, cx)
+            .detach_and_log_err(cx);
+    }
+
+    /// Opens the merge editor for the files passed to `zed --merge`, as used by `git mergetool`.
+    pub fn open(
+        base_path: PathBuf,
+        ours_path: PathBuf,
+        theirs_path: PathBuf,
+        result_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let mut buffers = Vec::new();
+            for path in [base_path, ours_path, theirs_path, result_path] {
+                let buffer = project
+                    .update(cx, |project, cx| project.open_local_buffer(&path, cx))?
+                    .await?;
+                buffers.push(buffer);
+            }
+            let [base, ours, theirs, result] = <[_; 4]>::try_from(buffers)
+                .ok()
+                .context("expected four merge buffers")?;
+
+            Self::add_to_workspace(
+                workspace,
+                project,
+                MergeBuffers {
+                    base,
+                    ours,
+                    theirs,
+                    result,
+                },
+                cx,
+            )
+            .await
+        })
+    }
+
+    /// Opens the merge editor for a file with merge conflicts, loading both sides and their
+    /// common base from the repository's index.
+    pub fn open_conflict(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let project_path = repository
+                .read_with(cx, |repository, cx| {
+                    repository.repo_path_to_project_path(&repo_path, cx)
+                })?
+                .context("conflicted file is not in the project")?;
+            let result = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                .await?;
+            let stages = repository
+                .update(cx, |repository, _| {
+                    repository.load_conflict_stages(repo_path)
+                })?
+                .await??;
+
+            let language = result.read_with(cx, |buffer, _| buffer.language().cloned())?;
+            let mut stage_buffer = |text: Option<String>| {
+                cx.new(|cx| {
+                    let mut buffer = Buffer::local(text.unwrap_or_default(), cx);
+                    buffer.set_language(language.clone(), cx);
+                    buffer.set_capability(Capability::ReadOnly, cx);
+                    buffer
+                })
+            };
+            let buffers = MergeBuffers {
+                base: stage_buffer(stages.base)?,
+                ours: stage_buffer(stages.ours)?,
+                theirs: stage_buffer(stages.theirs)?,
+                result,
+            };
+
+            Self::add_to_workspace(workspace, project, buffers, cx).await
+        })
+    }
+
+    async fn add_to_workspace(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        buffers: MergeBuffers,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        let ours_diff = build_buffer_diff(&buffers.base, &buffers.ours, cx).await?;
+        let theirs_diff = build_buffer_diff(&buffers.base, &buffers.theirs, cx).await?;
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let existing = workspace
+                .items_of_type::<Self>(cx)
+                .find(|view| view.read(cx).result_buffer.entity_id() == buffers.result.entity_id());
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+                return existing;
+            }
+
+            let merge_view =
+                cx.new(|cx| MergeView::new(buffers, ours_diff, theirs_diff, project, window, cx));
+            workspace.active_pane().update(cx, |pane, cx| {
+                pane.add_item(Box::new(merge_view.clone()), true, true, None, window, cx);
+            });
+            merge_view
+        })
+    }
+
+    fn new(
+        buffers: MergeBuffers,
+        ours_diff: Entity<BufferDiff>,
+        theirs_diff: Entity<BufferDiff>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base_editor = Self::side_editor(buffers.base, None, &project, window, cx);
+        let ours_editor = Self::side_editor(buffers.ours, Some(ours_diff), &project, window, cx);
+        let theirs_editor =
+            Self::side_editor(buffers.theirs, Some(theirs_diff), &project, window, cx);
+        let result_editor = cx.new(|cx| {
+            Editor::for_buffer(buffers.result.clone(), Some(project.clone()), window, cx)
+        });
+
+        cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone());
+        })
+        .detach();
+        cx.subscribe(&buffers.result, |this, buffer, event, cx| {
+            if matches!(event, language::BufferEvent::Edited) {
+                this.conflicts = ConflictSet::parse(&buffer.read(cx).snapshot());
+                cx.notify();
+            }
+        })
+        .detach();
+
+        let conflicts = ConflictSet::parse(&buffers.result.read(cx).snapshot());
+        Self {
+            base_editor,
+            ours_editor,
+            theirs_editor,
+            result_editor,
+            result_buffer: buffers.result,
+            conflicts,
+        }
+    }
+
+    /// Builds a read-only editor for one of the inputs, highlighting its changes from the base.
+    fn side_editor(
+        buffer: Entity<Buffer>,
+        diff: Option<Entity<BufferDiff>>,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+            if let Some(diff) = &diff {
+                multibuffer.add_diff(diff.clone(), cx);
+            }
+            multibuffer
+        });
+        cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx);
+            editor.set_read_only(true);
+            editor.disable_diagnostics(cx);
+            if diff.is_some() {
+                editor.start_temporary_diff_override();
+                editor.set_expand_all_diff_hunks(cx);
+                editor.set_render_diff_hunk_controls(
+                    Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+                    cx,
+                );
+            }
+            editor
+        })
+    }
+
+    /// Returns the index of the conflict containing the cursor, or of the first one after it.
+    fn conflict_at_cursor(&self, cx: &App) -> Option<usize> {
+        let editor = self.result_editor.read(cx);
+        let multibuffer = editor.buffer().read(cx);
+        let cursor = editor.selections.newest_anchor().head();
+        let cursor = cursor.to_offset(&multibuffer.snapshot(cx)).0;
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        self.conflicts
+            .conflicts
+            .iter()
+            .position(|conflict| conflict.range.end.to_offset(&snapshot) >= cursor)
+    }
+
+    fn select_conflict(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(conflict) = self.conflicts.conflicts.get(ix) else {
+            return;
+        };
+        let start = conflict.range.start;
+        self.result_editor.update(cx, |editor, cx| {
+            let Some(start) = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .as_singleton_anchor(start)
+            else {
+                return;
+            };
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_anchor_ranges([start..start]),
+            );
+        });
+        window.focus(&self.result_editor.focus_handle(cx));
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        let count = self.conflicts.conflicts.len();
+        if count == 0 {
+            return;
+        }
+        let ix = match self.conflict_at_cursor(cx) {
+            Some(ix) if self.cursor_is_in_conflict(ix, cx) => (ix + 1) % count,
+            Some(ix) => ix,
+            None => 0,
+        };
+        self.select_conflict(ix, window, cx);
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = self.conflicts.conflicts.len();
+        if count == 0 {
+            return;
+        }
+        let ix = self.conflict_at_cursor(cx).unwrap_or(count);
+        self.select_conflict((ix + count - 1) % count, window, cx);
+    }
+
+    fn cursor_is_in_conflict(&self, ix: usize, cx: &App) -> bool {
+        let Some(conflict) = self.conflicts.conflicts.get(ix) else {
+            return false;
+        };
+        let editor = self.result_editor.read(cx);
+        let cursor = editor.selections.newest_anchor().head();
+        let cursor = cursor.to_offset(&editor.buffer().read(cx).snapshot(cx)).0;
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        conflict.range.start.to_offset(&snapshot) <= cursor
+    }
+
+    /// Replaces the conflict at the cursor with the sides chosen by `ranges`, then moves to the
+    /// next remaining conflict.
+    fn resolve(
+        &mut self,
+        ranges: impl Fn(&ConflictRegion) -> Vec<Range<Anchor>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.conflict_at_cursor(cx) else {
+            return;
+        };
+        let conflict = self.conflicts.conflicts[ix].clone();
+        conflict.resolve(self.result_buffer.clone(), &ranges(&conflict), cx);
+        self.conflicts = ConflictSet::parse(&self.result_buffer.read(cx).snapshot());
+        cx.notify();
+        if !self.conflicts.conflicts.is_empty() {
+            self.select_conflict(ix.min(self.conflicts.conflicts.len() - 1), window, cx);
+        }
+    }
+
+    fn use_ours(&mut self, _: &UseOurs, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(|conflict| vec![conflict.ours.clone()], window, cx);
+    }
+
+    fn use_theirs(&mut self, _: &UseTheirs, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(|conflict| vec![conflict.theirs.clone()], window, cx);
+    }
+
+    fn use_both(&mut self, _: &UseBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(
+            |conflict| vec![conflict.ours.clone(), conflict.theirs.clone()],
+            window,
+            cx,
+        );
+    }
+
+    fn title(buffer: &Entity<Buffer>, cx: &App) -> SharedString {
+        buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string())
+            .unwrap_or_else(|| "untitled".into())
+            .into()
+    }
+
+    fn render_pane(
+        label: &'static str,
+        detail: Option<SharedString>,
+        editor: &Entity<Editor>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(label).size(LabelSize::Small))
+                    .children(detail.map(|detail| {
+                        Label::new(detail)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate()
+                    })),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+
+    fn render_result_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let conflict_count = self.conflicts.conflicts.len();
+        let status: SharedString = match conflict_count {
+            0 => "All conflicts resolved".into(),
+            1 => "1 conflict left".into(),
+            count => format!("{count} conflicts left").into(),
+        };
+        let has_conflicts = conflict_count > 0;
+        let focus_handle = self.result_editor.focus_handle(cx);
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_y_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new("Result").size(LabelSize::Small))
+            .child(
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(if has_conflicts {
+                        Color::Warning
+                    } else {
+                        Color::Success
+                    }),
+            )
+            .child(div().flex_1())
+            .child(
+                IconButton::new("previous-conflict", IconName::ChevronUp)
+                    .disabled(!has_conflicts)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Previous Conflict",
+                        &PreviousConflict,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.previous_conflict(&PreviousConflict, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("next-conflict", IconName::ChevronDown)
+                    .disabled(!has_conflicts)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Next Conflict",
+                        &NextConflict,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.next_conflict(&NextConflict, window, cx)
+                    })),
+            )
+            .child(
+                Button::new("use-ours", "Use Ours")
+                    .label_size(LabelSize::Small)
+                    .disabled(!has_conflicts)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.use_ours(&UseOurs, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("use-theirs", "Use Theirs")
+                    .label_size(LabelSize::Small)
+                    .disabled(!has_conflicts)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.use_theirs(&UseTheirs, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("use-both", "Use Both")
+                    .label_size(LabelSize::Small)
+                    .disabled(!has_conflicts)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.use_both(&UseBoth, window, cx)),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<EditorEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        format!("Merge {}", Self::title(&self.result_buffer, cx)).into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        self.result_buffer
+            .read(cx)
+            .file()
+            .map(|file| format!("Merge {}", file.full_path(cx).to_string_lossy()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.result_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let side_detail = |editor: &Entity<Editor>| {
+            let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+            buffer.read(cx).file()?;
+            Some(Self::title(&buffer, cx))
+        };
+
+        v_flex()
+            .key_context("MergeView")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .on_action(cx.listener(Self::use_ours))
+            .on_action(cx.listener(Self::use_theirs))
+            .on_action(cx.listener(Self::use_both))
+            .child(
+                h_flex()
+                    .h_1_2()
+                    .w_full()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .border_r_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(Self::render_pane(
+                                "Ours",
+                                side_detail(&self.ours_editor),
+                                &self.ours_editor,
+                                cx,
+                            )),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .border_r_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(Self::render_pane(
+                                "Base",
+                                side_detail(&self.base_editor),
+                                &self.base_editor,
+                                cx,
+                            )),
+                    )
+                    .child(Self::render_pane(
+                        "Theirs",
+                        side_detail(&self.theirs_editor),
+                        &self.theirs_editor,
+                        cx,
+                    )),
+            )
+            .child(self.render_result_toolbar(cx))
+            .child(div().flex_1().min_h_0().child(self.result_editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs, Project};
+    use settings::SettingsStore;
+    use unindent::unindent;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_resolve_conflicts_in_merge_view(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            serde_json::json!({
+                "base.txt": "one\ntwo\nthree\n",
+                "ours.txt": "one\nTWO\nthree\n",
+                "theirs.txt": "one\n2\nthree\n",
+                "result.txt": unindent(
+                    "
+                    one
+                    <<<<<<< ours
+                    TWO
+                    =======
+                    2
+                    >>>>>>> theirs
+                    three
+                    "
+                ),
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), [path!("/test").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open(
+                    path!("/test/base.txt").into(),
+                    path!("/test/ours.txt").into(),
+                    path!("/test/theirs.txt").into(),
+                    path!("/test/result.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        assert!(has_conflict_markers(
+            &fs.load(path!("/test/result.txt").as_ref()).await.unwrap()
+        ));
+        merge_view.read_with(cx, |merge_view, cx| {
+            assert_eq!(merge_view.conflicts.conflicts.len(), 1);
+            assert_eq!(merge_view.tab_content_text(0, cx), "Merge result.txt");
+            assert!(merge_view.ours_editor.read(cx).read_only(cx));
+        });
+
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.use_both(&UseBoth, window, cx);
+        });
+        merge_view.read_with(cx, |merge_view, cx| {
+            assert_eq!(merge_view.conflicts.conflicts.len(), 0);
+            assert_eq!(
+                merge_view.result_buffer.read(cx).text(),
+                "one\nTWO\n2\nthree\n"
+            );
+        });
+
+        let save_task = merge_view.update_in(cx, |merge_view, window, cx| {
+            Item::save(
+                merge_view,
+                SaveOptions::default(),
+                project.clone(),
+                window,
+                cx,
+            )
+        });
+        save_task.await.unwrap();
+        let result = fs.load(path!("/test/result.txt").as_ref()).await.unwrap();
+        assert_eq!(result, "one\nTWO\n2\nthree\n");
+        assert!(!has_conflict_markers(&result));
+    }
+}
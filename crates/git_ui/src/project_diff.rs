@@ -14,7 +14,8 @@ use editor::{
     scroll::Autoscroll,
 };
 use git::{
-    Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
+    Commit, StageAll, StageAndNext, StageSelectedLines, ToggleStaged, UnstageAll, UnstageAndNext,
+    UnstageSelectedLines,
     repository::{Branch, RepoPath, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::FileStatus,
 };
@@ -1254,6 +1255,30 @@ impl Render for ProjectDiffToolbar {
                                     this.dispatch_action(&ToggleStaged, window, cx)
                                 })),
                         )
+                        .child(
+                            Button::new("stage-lines", "Stage Lines")
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Stage selected lines",
+                                    &StageSelectedLines,
+                                    &focus_handle,
+                                ))
+                                .disabled(!button_states.stage)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dispatch_action(&StageSelectedLines, window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("unstage-lines", "Unstage Lines")
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Unstage selected lines",
+                                    &UnstageSelectedLines,
+                                    &focus_handle,
+                                ))
+                                .disabled(!button_states.unstage)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dispatch_action(&UnstageSelectedLines, window, cx)
+                                })),
+                        )
                     })
                     .when(!button_states.selection, |el| {
                         el.child(
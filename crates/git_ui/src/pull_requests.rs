@@ -0,0 +1,455 @@
+use collections::HashSet;
+use editor::{
+    Editor,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+};
+use fuzzy::StringMatchCandidate;
+use git::{PullRequestDetails, ReviewComment, repository::RepoPath};
+use gpui::{
+    AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, rems,
+};
+use language::{Buffer, Point};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::git_panel::GitPanel;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(toggle_review_comments);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::CheckoutPullRequest,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        PullRequestList::new(repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub struct PullRequestList {
+    width: Rems,
+    pub picker: Entity<Picker<PullRequestListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl PullRequestList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let pull_requests_request = repository
+            .as_ref()
+            .map(|repository| repository.read(cx).pull_requests(cx));
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(pull_requests_request) = pull_requests_request else {
+                return anyhow::Ok(());
+            };
+            let pull_requests = pull_requests_request.await?;
+
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.all_pull_requests = Some(pull_requests);
+                    picker.refresh(window, cx);
+                })
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to list pull requests", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+
+        let delegate = PullRequestListDelegate::new(workspace);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let picker_focus_handle = picker.focus_handle(cx);
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            picker_focus_handle,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for PullRequestList {}
+impl EventEmitter<DismissEvent> for PullRequestList {}
+
+impl Focusable for PullRequestList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for PullRequestList {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("PullRequestList")
+            .w(self.width)
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PullRequestEntry {
+    pull_request: PullRequestDetails,
+    positions: Vec<usize>,
+}
+
+pub struct PullRequestListDelegate {
+    matches: Vec<PullRequestEntry>,
+    all_pull_requests: Option<Vec<PullRequestDetails>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl PullRequestListDelegate {
+    fn new(workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: vec![],
+            all_pull_requests: None,
+            workspace,
+            selected_index: 0,
+        }
+    }
+}
+
+fn pull_request_label(pull_request: &PullRequestDetails) -> String {
+    format!("#{} {}", pull_request.number, pull_request.title)
+}
+
+impl PickerDelegate for PullRequestListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a pull request to check out…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.all_pull_requests.is_none() {
+            Some("Loading pull requests…".into())
+        } else {
+            Some("No open pull requests found".into())
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_pull_requests) = self.all_pull_requests.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches: Vec<PullRequestEntry> = if query.is_empty() {
+                all_pull_requests
+                    .into_iter()
+                    .map(|pull_request| PullRequestEntry {
+                        pull_request,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_pull_requests
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, pull_request)| {
+                        StringMatchCandidate::new(ix, &pull_request_label(pull_request))
+                    })
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| PullRequestEntry {
+                    pull_request: all_pull_requests[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let pull_request = entry.pull_request.clone();
+
+        if secondary {
+            cx.open_url(pull_request.url.as_str());
+        } else if let Some(panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        {
+            panel.update(cx, |panel, cx| {
+                panel.checkout_pull_request(pull_request, window, cx)
+            });
+        }
+
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+        let pull_request = &entry.pull_request;
+        let detail = format!(
+            "{} • {} • {}",
+            pull_request.author,
+            pull_request.head_branch,
+            pull_request.state.label()
+        );
+
+        Some(
+            ListItem::new(format!("pull-request-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::PullRequest)
+                        .color(Color::Muted)
+                        .size(IconSize::Small),
+                )
+                .child(
+                    v_flex()
+                        .w_full()
+                        .child(
+                            HighlightedLabel::new(
+                                pull_request_label(pull_request),
+                                entry.positions.clone(),
+                            )
+                            .truncate(),
+                        )
+                        .child(
+                            Label::new(detail)
+                                .truncate()
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                ),
+        )
+    }
+}
+
+/// Tracks the review comment blocks shown in an editor.
+struct ReviewCommentsAddon {
+    block_ids: HashSet<CustomBlockId>,
+}
+
+impl editor::Addon for ReviewCommentsAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+fn toggle_review_comments(
+    workspace: &mut Workspace,
+    _: &git::ToggleReviewComments,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+
+    let shown_block_ids = editor.update(cx, |editor, cx| {
+        let block_ids = editor.addon::<ReviewCommentsAddon>()?.block_ids.clone();
+        editor.unregister_addon::<ReviewCommentsAddon>();
+        editor.remove_blocks(block_ids.clone(), None, cx);
+        Some(block_ids)
+    });
+    if shown_block_ids.is_some() {
+        return;
+    }
+
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let git_store = workspace.project().read(cx).git_store().clone();
+    let Some((repository, repo_path)) = git_store
+        .read(cx)
+        .repository_and_path_for_buffer_id(buffer.read(cx).remote_id(), cx)
+    else {
+        return;
+    };
+    let Some(number) = repository
+        .read(cx)
+        .pull_request()
+        .map(|pull_request| pull_request.number)
+    else {
+        let toast = StatusToast::new("This branch has no open pull request", cx, |this, _| {
+            this.icon(ToastIcon::new(IconName::PullRequest).color(Color::Muted))
+                .dismiss_button(true)
+        });
+        workspace.toggle_status_toast(toast, cx);
+        return;
+    };
+
+    let comments = repository.read(cx).pull_request_review_comments(number, cx);
+    cx.spawn_in(window, async move |_, cx| {
+        let comments = comments.await?;
+        editor.update(cx, |editor, cx| {
+            insert_review_comments(editor, &buffer, &repo_path, comments, cx)
+        })?;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to load review comments", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
+fn insert_review_comments(
+    editor: &mut Editor,
+    buffer: &Entity<Buffer>,
+    repo_path: &RepoPath,
+    comments: Vec<ReviewComment>,
+    cx: &mut Context<Editor>,
+) {
+    if editor.buffer().read(cx).as_singleton().as_ref() != Some(buffer) {
+        return;
+    }
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let max_row = snapshot.max_point().row;
+
+    // Outdated comments no longer point at a line of the file, so they can't be placed.
+    let blocks = comments
+        .into_iter()
+        .filter(|comment| &comment.path == repo_path)
+        .filter_map(|comment| {
+            let row = comment.row?.min(max_row);
+            let anchor = snapshot.anchor_after(Point::new(row, 0));
+            let height = comment.body.lines().count().max(1) as u32 + 1;
+            Some(BlockProperties {
+                placement: BlockPlacement::Below(anchor),
+                height: Some(height),
+                style: BlockStyle::Flex,
+                render: Arc::new(move |cx| render_review_comment(&comment, cx)),
+                priority: 0,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+    editor.register_addon(ReviewCommentsAddon {
+        block_ids: block_ids.into_iter().collect(),
+    });
+    cx.notify();
+}
+
+fn render_review_comment(comment: &ReviewComment, cx: &mut BlockContext) -> AnyElement {
+    let url = comment.url.clone();
+    v_flex()
+        .id(cx.block_id)
+        .ml(cx.margins.gutter.width)
+        .pl_2()
+        .border_l_2()
+        .border_color(cx.theme().colors().border)
+        .bg(cx.theme().colors().editor_background)
+        .child(
+            h_flex()
+                .h(cx.line_height)
+                .gap_1()
+                .child(
+                    Icon::new(IconName::Chat)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(
+                    Label::new(comment.author.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .when_some(url, |this, url| {
+                    this.child(
+                        IconButton::new("open-review-comment", IconName::ArrowUpRight)
+                            .icon_size(IconSize::XSmall)
+                            .icon_color(Color::Muted)
+                            .on_click(move |_, _, cx| cx.open_url(url.as_str())),
+                    )
+                }),
+        )
+        .children(comment.body.lines().map(|line| {
+            div()
+                .h(cx.line_height)
+                .child(Label::new(line.to_string()).size(LabelSize::Small))
+        }))
+        .into_any_element()
+}
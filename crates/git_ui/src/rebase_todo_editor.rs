@@ -0,0 +1,514 @@
+use anyhow::Context as _;
+use editor::Editor;
+use git::rebase::{RebaseTodoAction, RebaseTodoEntry};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollStrategy, Task,
+    UniformListScrollHandle, Window, actions, uniform_list,
+};
+use project::git_store::Repository;
+use ui::{ListItem, Tooltip, WithScrollbar, prelude::*};
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+use crate::git_panel::GitPanel;
+
+actions!(
+    rebase_todo_editor,
+    [
+        /// Moves the selected commit one step earlier in the todo list.
+        MoveUp,
+        /// Moves the selected commit one step later in the todo list.
+        MoveDown,
+        /// Switches the selected commit to the next rebase action.
+        CycleAction,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::InteractiveRebase, window, cx| {
+        RebaseTodoEditor::toggle(workspace, window, cx);
+    });
+}
+
+enum Mode {
+    /// Editing the todo list of a rebase that has not started yet.
+    Start { upstream: Option<String> },
+    /// Editing the remaining commands of the rebase in progress.
+    EditRemaining,
+}
+
+pub struct RebaseTodoEditor {
+    repository: Entity<Repository>,
+    mode: Mode,
+    entries: Vec<RebaseTodoEntry>,
+    selected_ix: usize,
+    message_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    error: Option<SharedString>,
+    _load_task: Task<()>,
+}
+
+impl RebaseTodoEditor {
+    pub fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(panel) = workspace.panel::<GitPanel>(cx) else {
+            return;
+        };
+        let Some(repository) = panel.read(cx).active_repository.clone() else {
+            return;
+        };
+        workspace.toggle_modal(window, cx, |window, cx| {
+            RebaseTodoEditor::new(repository, window, cx)
+        });
+    }
+
+    fn new(repository: Entity<Repository>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(1, 6, window, cx);
+            editor.set_placeholder_text("New commit message", window, cx);
+            editor
+        });
+
+        let rebase = repository.read(cx).rebase.clone();
+        let (mode, entries, load_task) = match rebase {
+            Some(rebase) if rebase.interactive => {
+                (Mode::EditRemaining, rebase.remaining, Task::ready(()))
+            }
+            _ => {
+                let upstream = repository
+                    .read(cx)
+                    .branch
+                    .as_ref()
+                    .and_then(|branch| branch.upstream.as_ref())
+                    .map(|upstream| upstream.ref_name.to_string());
+                let task = Self::load_todo(repository.clone(), upstream, window, cx);
+                (Mode::Start { upstream: None }, Vec::new(), task)
+            }
+        };
+
+        Self {
+            repository,
+            mode,
+            entries,
+            selected_ix: 0,
+            message_editor,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            error: None,
+            _load_task: load_task,
+        }
+    }
+
+    fn load_todo(
+        repository: Entity<Repository>,
+        upstream: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let upstream = match upstream {
+                    Some(upstream) => upstream,
+                    None => repository
+                        .update(cx, |repository, _| repository.default_branch())?
+                        .await??
+                        .context("could not determine a branch to rebase onto")?
+                        .to_string(),
+                };
+                let entries = repository
+                    .update(cx, |repository, _| repository.rebase_todo(upstream.clone()))?
+                    .await??;
+                anyhow::Ok((upstream, entries))
+            }
+            .await;
+
+            this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok((upstream, entries)) => {
+                        if entries.is_empty() {
+                            this.error =
+                                Some(format!("No commits to rebase onto {upstream}").into());
+                        }
+                        this.mode = Mode::Start {
+                            upstream: Some(upstream),
+                        };
+                        this.entries = entries;
+                        this.select_entry(0, window, cx);
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn select_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.store_message(cx);
+        self.selected_ix = ix.min(self.entries.len().saturating_sub(1));
+        let message = self
+            .entries
+            .get(self.selected_ix)
+            .map(|entry| {
+                entry
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| entry.subject.clone())
+            })
+            .unwrap_or_default();
+        self.message_editor.update(cx, |editor, cx| {
+            editor.set_text(message, window, cx);
+        });
+        self.scroll_handle
+            .scroll_to_item(self.selected_ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    /// Copies the message editor's contents into the selected entry, if it is being reworded.
+    fn store_message(&mut self, cx: &mut Context<Self>) {
+        let text = self.message_editor.read(cx).text(cx);
+        if let Some(entry) = self.entries.get_mut(self.selected_ix)
+            && entry.action == RebaseTodoAction::Reword
+        {
+            entry.message =
+                (!text.trim().is_empty() && text != entry.subject.as_ref()).then(|| text.into());
+        }
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix + 1 < self.entries.len() {
+            self.select_entry(self.selected_ix + 1, window, cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_ix > 0 {
+            self.select_entry(self.selected_ix - 1, window, cx);
+        }
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix > 0 && self.selected_ix < self.entries.len() {
+            self.store_message(cx);
+            self.entries.swap(self.selected_ix, self.selected_ix - 1);
+            // Follow the moved entry before selecting it, so that the message editor's contents
+            // aren't stored into the entry it swapped places with.
+            self.selected_ix -= 1;
+            self.select_entry(self.selected_ix, window, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix + 1 < self.entries.len() {
+            self.store_message(cx);
+            self.entries.swap(self.selected_ix, self.selected_ix + 1);
+            self.selected_ix += 1;
+            self.select_entry(self.selected_ix, window, cx);
+        }
+    }
+
+    fn cycle_action(&mut self, _: &CycleAction, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_ix, None, window, cx);
+    }
+
+    /// Sets the action of the entry at `ix`, or advances it to the next one when `action` is `None`.
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: Option<RebaseTodoAction>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.store_message(cx);
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action.unwrap_or_else(|| entry.action.next());
+        if entry.action != RebaseTodoAction::Reword {
+            entry.message = None;
+        }
+        self.select_entry(ix, window, cx);
+    }
+
+    /// Squashing or fixing up the first commit has nothing to meld into.
+    fn validate(&self) -> Result<(), SharedString> {
+        let first_kept = self
+            .entries
+            .iter()
+            .find(|entry| entry.action != RebaseTodoAction::Drop);
+        match (&self.mode, first_kept) {
+            (Mode::Start { .. }, Some(entry)) if entry.action.melds_into_previous() => {
+                Err(format!("Cannot {} without a previous commit", entry.action.as_str()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.store_message(cx);
+        if let Err(error) = self.validate() {
+            self.error = Some(error);
+            cx.notify();
+            return;
+        }
+
+        let entries = self.entries.clone();
+        let task = match &self.mode {
+            Mode::Start {
+                upstream: Some(upstream),
+            } if !entries.is_empty() => {
+                let upstream = upstream.clone();
+                self.repository.update(cx, |repository, _| {
+                    repository.rebase_start(upstream, entries)
+                })
+            }
+            Mode::Start { .. } => return,
+            Mode::EditRemaining => self
+                .repository
+                .update(cx, |repository, _| repository.rebase_edit_todo(entries)),
+        };
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err("Failed to rebase", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(&self, ix: usize, entry: &RebaseTodoEntry, cx: &Context<Self>) -> AnyElement {
+        let action_color = match entry.action {
+            RebaseTodoAction::Pick => Color::Default,
+            RebaseTodoAction::Reword | RebaseTodoAction::Edit => Color::Accent,
+            RebaseTodoAction::Squash | RebaseTodoAction::Fixup => Color::Warning,
+            RebaseTodoAction::Drop => Color::Error,
+        };
+        let short_sha: String = entry.sha.chars().take(git::SHORT_SHA_LENGTH).collect();
+        let subject: SharedString = entry
+            .message
+            .as_ref()
+            .and_then(|message| message.lines().next())
+            .map(|line| line.to_string().into())
+            .unwrap_or_else(|| entry.subject.clone());
+
+        ListItem::new(("rebase-todo-entry", ix))
+            .toggle_state(ix == self.selected_ix)
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        Button::new(("rebase-action", ix), entry.action.as_str())
+                            .label_size(LabelSize::Small)
+                            .color(action_color)
+                            .tooltip(Tooltip::text("Change Action"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.set_action(ix, None, window, cx);
+                            })),
+                    )
+                    .child(
+                        Label::new(short_sha)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        Label::new(subject)
+                            .size(LabelSize::Small)
+                            .truncate()
+                            .when(entry.action == RebaseTodoAction::Drop, |label| {
+                                label.strikethrough()
+                            }),
+                    ),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select_entry(ix, window, cx);
+            }))
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<DismissEvent> for RebaseTodoEditor {}
+impl ModalView for RebaseTodoEditor {}
+impl Focusable for RebaseTodoEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RebaseTodoEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title: SharedString = match &self.mode {
+            Mode::Start {
+                upstream: Some(upstream),
+            } => format!("Interactive Rebase onto {upstream}").into(),
+            Mode::Start { upstream: None } => "Interactive Rebase".into(),
+            Mode::EditRemaining => "Edit Rebase Todo".into(),
+        };
+        let rewording = self
+            .entries
+            .get(self.selected_ix)
+            .is_some_and(|entry| entry.action == RebaseTodoAction::Reword);
+        let confirm_label = match self.mode {
+            Mode::Start { .. } => "Start Rebase",
+            Mode::EditRemaining => "Update Todo",
+        };
+        let entry_count = self.entries.len();
+
+        v_flex()
+            .key_context("RebaseTodoEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::cycle_action))
+            .elevation_2(cx)
+            .w(rems(40.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(
+                v_flex()
+                    .h(rems(20.))
+                    .px_1()
+                    .child({
+                        let view = cx.weak_entity();
+                        uniform_list("rebase-todo-list", entry_count, move |range, _, cx| {
+                            let Some(view) = view.upgrade() else {
+                                return Vec::new();
+                            };
+                            view.update(cx, |this, cx| {
+                                range
+                                    .filter_map(|ix| {
+                                        let entry = this.entries.get(ix)?;
+                                        Some(this.render_entry(ix, entry, cx))
+                                    })
+                                    .collect()
+                            })
+                        })
+                        .size_full()
+                        .track_scroll(&self.scroll_handle)
+                    })
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+            .when(rewording, |this| {
+                this.child(
+                    div()
+                        .px_3()
+                        .py_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .children(self.error.clone().map(|error| {
+                h_flex()
+                    .px_3()
+                    .py_1()
+                    .child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            }))
+            .child(
+                h_flex()
+                    .px_3()
+                    .py_2()
+                    .gap_1()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Button::new("cancel", "Cancel").on_click(
+                        cx.listener(|this, _, window, cx| this.cancel(&menu::Cancel, window, cx)),
+                    ))
+                    .child(
+                        Button::new("confirm", confirm_label)
+                            .style(ButtonStyle::Filled)
+                            .disabled(entry_count == 0)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(&menu::Confirm, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_moving_reworded_entry(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "" }))
+            .await;
+        fs.with_git_state(Path::new(path!("/project/.git")), true, |state| {
+            state.rebase_todo = vec![
+                RebaseTodoEntry::pick("aaa", "First"),
+                RebaseTodoEntry::pick("bbb", "Second"),
+                RebaseTodoEntry::pick("ccc", "Third"),
+            ];
+        })
+        .unwrap();
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let repository = project
+            .read_with(cx, |project, cx| project.active_repository(cx))
+            .unwrap();
+
+        let (todo_editor, cx) =
+            cx.add_window_view(|window, cx| RebaseTodoEditor::new(repository, window, cx));
+        cx.run_until_parked();
+
+        let message = "Better second\n\nWith a body";
+        todo_editor.update_in(cx, |todo_editor, window, cx| {
+            todo_editor.set_action(1, Some(RebaseTodoAction::Reword), window, cx);
+            todo_editor.message_editor.update(cx, |message_editor, cx| {
+                message_editor.set_text(message, window, cx)
+            });
+            todo_editor.move_down(&MoveDown, window, cx);
+        });
+        todo_editor.read_with(cx, |todo_editor, _| {
+            assert_eq!(todo_editor.selected_ix, 2);
+            assert_eq!(todo_editor.entries[1].sha.as_ref(), "ccc");
+            assert_eq!(todo_editor.entries[1].message, None);
+            assert_eq!(todo_editor.entries[2].sha.as_ref(), "bbb");
+            assert_eq!(todo_editor.entries[2].message.as_deref(), Some(message));
+        });
+
+        todo_editor.update_in(cx, |todo_editor, window, cx| {
+            todo_editor.move_up(&MoveUp, window, cx);
+            todo_editor.move_up(&MoveUp, window, cx);
+        });
+        todo_editor.read_with(cx, |todo_editor, cx| {
+            assert_eq!(todo_editor.selected_ix, 0);
+            assert_eq!(todo_editor.entries[0].sha.as_ref(), "bbb");
+            assert_eq!(todo_editor.entries[0].message.as_deref(), Some(message));
+            assert_eq!(todo_editor.entries[1].message, None);
+            assert_eq!(todo_editor.message_editor.read(cx).text(cx), message);
+        });
+    }
+}
@@ -10,6 +10,8 @@ pub enum RemoteAction {
     Fetch(Option<Remote>),
     Pull(Remote),
     Push(SharedString, Remote),
+    PushTag(SharedString, Remote),
+    UpdateSubmodule(SharedString),
 }
 
 impl RemoteAction {
@@ -17,7 +19,8 @@ impl RemoteAction {
         match self {
             RemoteAction::Fetch(_) => "fetch",
             RemoteAction::Pull(_) => "pull",
-            RemoteAction::Push(_, _) => "push",
+            RemoteAction::Push(_, _) | RemoteAction::PushTag(_, _) => "push",
+            RemoteAction::UpdateSubmodule(_) => "submodule update",
         }
     }
 }
@@ -157,6 +160,32 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 style: style.unwrap_or(SuccessStyle::ToastWithLog { output }),
             }
         }
+        RemoteAction::PushTag(tag_name, remote_ref) => {
+            if output.stderr.ends_with("Everything up-to-date\n") {
+                SuccessMessage {
+                    message: "Push: Everything is up-to-date".into(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: format!("Pushed tag {} to {}", tag_name, remote_ref.name),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
+        RemoteAction::UpdateSubmodule(path) => {
+            if output.is_empty() {
+                SuccessMessage {
+                    message: format!("Submodule {path} is up to date"),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: format!("Updated submodule {path}"),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
     }
 }
 
@@ -1,7 +1,10 @@
 use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity};
 use itertools::Itertools;
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
-use project::{Project, git_store::Repository};
+use project::{
+    Project,
+    git_store::{GitStore, Repository},
+};
 use std::sync::Arc;
 use ui::{ListItem, ListItemSpacing, prelude::*};
 use workspace::{ModalView, Workspace};
@@ -53,6 +56,7 @@ impl RepositorySelector {
 
         let delegate = RepositorySelectorDelegate {
             repository_selector: cx.entity().downgrade(),
+            git_store,
             repository_entries,
             filtered_repositories,
             selected_index: 0,
@@ -120,6 +124,7 @@ impl ModalView for RepositorySelector {}
 
 pub struct RepositorySelectorDelegate {
     repository_selector: WeakEntity<RepositorySelector>,
+    git_store: Entity<GitStore>,
     repository_entries: Vec<Entity<Repository>>,
     filtered_repositories: Vec<Entity<Repository>>,
     selected_index: usize,
@@ -227,12 +232,28 @@ impl PickerDelegate for RepositorySelectorDelegate {
     ) -> Option<Self::ListItem> {
         let repo_info = self.filtered_repositories.get(ix)?;
         let display_name = repo_info.read(cx).display_name();
+        let superproject = self
+            .git_store
+            .read(cx)
+            .superproject(repo_info.read(cx), cx)
+            .map(|superproject| superproject.read(cx).display_name());
         Some(
             ListItem::new(ix)
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .toggle_state(selected)
-                .child(Label::new(display_name)),
+                .child(
+                    h_flex()
+                        .gap_1p5()
+                        .child(Label::new(display_name))
+                        .when_some(superproject, |this, superproject| {
+                            this.child(
+                                Label::new(format!("submodule of {superproject}"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                ),
         )
     }
 }
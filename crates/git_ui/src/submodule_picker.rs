@@ -0,0 +1,577 @@
+use anyhow::anyhow;
+use fuzzy::StringMatchCandidate;
+
+use git::repository::RepoPath;
+use git::submodule::{Submodule, SubmoduleStatus};
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, ElementId, Entity, EventEmitter, FocusHandle,
+    Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled,
+    Subscription, Task, WeakEntity, Window, actions, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use project::git_store::{Repository, RepositoryEvent};
+use std::sync::Arc;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::git_panel::{GitPanel, show_error_toast};
+use crate::submodule_picker;
+
+actions!(
+    submodule_picker,
+    [
+        /// Initializes the selected submodule.
+        InitSubmodule,
+        /// Checks out the recorded commit of the selected submodule, cloning it if needed.
+        UpdateSubmodule,
+        /// Synchronizes the remote URL of the selected submodule with `.gitmodules`.
+        SyncSubmodule,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::ViewSubmodules,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let repository = project.read(cx).active_repository(cx);
+    let weak_workspace = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        SubmoduleList::new(project, repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub fn submodule_status_color(status: SubmoduleStatus) -> Color {
+    match status {
+        SubmoduleStatus::Uninitialized => Color::Disabled,
+        SubmoduleStatus::UpToDate => Color::Muted,
+        SubmoduleStatus::OutOfDate => Color::VersionControlModified,
+        SubmoduleStatus::Conflicted => Color::VersionControlConflict,
+    }
+}
+
+/// A small icon marking a path as a submodule, with its status in a tooltip.
+pub fn submodule_indicator(id: impl Into<ElementId>, submodule: &Submodule) -> impl IntoElement {
+    let tooltip = submodule_tooltip(submodule);
+    div()
+        .id(id.into())
+        .flex_none()
+        .child(
+            Icon::new(IconName::Box)
+                .size(IconSize::XSmall)
+                .color(submodule_status_color(submodule.status)),
+        )
+        .tooltip(move |_window, cx| Tooltip::simple(tooltip.clone(), cx))
+}
+
+fn submodule_tooltip(submodule: &Submodule) -> SharedString {
+    let recorded: String = submodule
+        .recorded_sha
+        .chars()
+        .take(git::SHORT_SHA_LENGTH)
+        .collect();
+    let mut tooltip = format!(
+        "Submodule {} • {}",
+        submodule.name,
+        submodule.status.label()
+    );
+    match &submodule.checked_out_sha {
+        Some(checked_out) if checked_out != &submodule.recorded_sha => {
+            let checked_out: String = checked_out.chars().take(git::SHORT_SHA_LENGTH).collect();
+            tooltip.push_str(&format!(
+                " (recorded {recorded}, checked out {checked_out})"
+            ));
+        }
+        _ => tooltip.push_str(&format!(" ({recorded})")),
+    }
+    tooltip.into()
+}
+
+/// Makes the repository checked out at the given submodule the active repository.
+pub fn open_submodule_repository(
+    project: &Entity<Project>,
+    repository: &Entity<Repository>,
+    path: &RepoPath,
+    cx: &mut App,
+) -> anyhow::Result<()> {
+    let git_store = project.read(cx).git_store().clone();
+    let repo = repository.read(cx);
+    let submodule = repo
+        .submodule(path)
+        .ok_or_else(|| anyhow!("{} is not a submodule", path.as_unix_str()))?;
+    let submodule_repository = git_store
+        .read(cx)
+        .submodule_repository(repo, submodule, cx)
+        .ok_or_else(|| anyhow!("submodule {} has not been cloned", submodule.name))?;
+    submodule_repository.update(cx, |repository, cx| repository.set_as_active_repository(cx));
+    Ok(())
+}
+
+pub struct SubmoduleList {
+    width: Rems,
+    pub picker: Entity<Picker<SubmoduleListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SubmoduleList {
+    fn new(
+        project: Entity<Project>,
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut _subscriptions = Vec::new();
+        if let Some(repo) = repository.clone() {
+            _subscriptions.push(
+                cx.subscribe_in(&repo, window, |this, repo, event, window, cx| {
+                    if matches!(event, RepositoryEvent::SubmodulesChanged) {
+                        let submodules = repo.read(cx).submodules.to_vec();
+                        this.picker.update(cx, |picker, cx| {
+                            picker.delegate.all_submodules = submodules;
+                            picker.refresh(window, cx);
+                        });
+                    }
+                }),
+            );
+        }
+
+        let mut delegate = SubmoduleListDelegate::new(project, repository, workspace, cx);
+        if let Some(repo) = &delegate.repo {
+            delegate.all_submodules = repo.read(cx).submodules.to_vec();
+        }
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, _| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+        });
+
+        _subscriptions.push(cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        }));
+
+        Self {
+            picker,
+            picker_focus_handle,
+            width,
+            _subscriptions,
+        }
+    }
+
+    fn handle_init(&mut self, _: &InitSubmodule, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .init_at(picker.delegate.selected_index, window, cx)
+        })
+    }
+
+    fn handle_update(&mut self, _: &UpdateSubmodule, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .update_at(picker.delegate.selected_index, window, cx)
+        })
+    }
+
+    fn handle_sync(&mut self, _: &SyncSubmodule, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .sync_at(picker.delegate.selected_index, window, cx)
+        })
+    }
+}
+
+impl ModalView for SubmoduleList {}
+impl EventEmitter<DismissEvent> for SubmoduleList {}
+
+impl Focusable for SubmoduleList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for SubmoduleList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SubmoduleList")
+            .w(self.width)
+            .on_action(cx.listener(Self::handle_init))
+            .on_action(cx.listener(Self::handle_update))
+            .on_action(cx.listener(Self::handle_sync))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SubmoduleEntry {
+    submodule: Submodule,
+    positions: Vec<usize>,
+}
+
+pub struct SubmoduleListDelegate {
+    matches: Vec<SubmoduleEntry>,
+    all_submodules: Vec<Submodule>,
+    project: Entity<Project>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    focus_handle: FocusHandle,
+}
+
+impl SubmoduleListDelegate {
+    fn new(
+        project: Entity<Project>,
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<SubmoduleList>,
+    ) -> Self {
+        Self {
+            matches: vec![],
+            all_submodules: Vec::new(),
+            project,
+            repo,
+            workspace,
+            selected_index: 0,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn init_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let path = entry.submodule.path.clone();
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let result = repo
+                .update(cx, |repo, _| repo.init_submodules(vec![path.clone()]))?
+                .await?;
+
+            if let Err(e) = result {
+                log::error!("Failed to initialize submodule: {}", e);
+                if let Some(workspace) = workspace.upgrade() {
+                    cx.update(|_window, cx| {
+                        show_error_toast(
+                            workspace,
+                            format!("submodule init {}", path.as_unix_str()),
+                            e,
+                            cx,
+                        )
+                    })?;
+                }
+            }
+
+            anyhow::Ok(())
+        })
+        .detach();
+    }
+
+    fn sync_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let path = entry.submodule.path.clone();
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let result = repo
+                .update(cx, |repo, _| repo.sync_submodules(vec![path.clone()]))?
+                .await?;
+
+            if let Err(e) = result {
+                log::error!("Failed to sync submodule: {}", e);
+                if let Some(workspace) = workspace.upgrade() {
+                    cx.update(|_window, cx| {
+                        show_error_toast(
+                            workspace,
+                            format!("submodule sync {}", path.as_unix_str()),
+                            e,
+                            cx,
+                        )
+                    })?;
+                }
+            }
+
+            anyhow::Ok(())
+        })
+        .detach();
+    }
+
+    fn update_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        let path = entry.submodule.path.clone();
+        panel.update(cx, |panel, cx| panel.update_submodule(path, window, cx));
+    }
+
+    fn open_at(&self, ix: usize, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.as_ref() else {
+            return;
+        };
+        if let Err(e) = open_submodule_repository(&self.project, repo, &entry.submodule.path, cx)
+            && let Some(workspace) = self.workspace.upgrade()
+        {
+            show_error_toast(workspace, "open submodule", e, cx);
+        }
+    }
+}
+
+impl PickerDelegate for SubmoduleListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a submodule…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No submodules found".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let all_submodules = self.all_submodules.clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches: Vec<SubmoduleEntry> = if query.is_empty() {
+                all_submodules
+                    .into_iter()
+                    .map(|submodule| SubmoduleEntry {
+                        submodule,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_submodules
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, submodule)| {
+                        StringMatchCandidate::new(ix, submodule.path.as_unix_str())
+                    })
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| SubmoduleEntry {
+                    submodule: all_submodules[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if self.matches.get(self.selected_index).is_none() {
+            return;
+        }
+        self.open_at(self.selected_index, cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let SubmoduleEntry {
+            submodule,
+            positions,
+        } = &self.matches[ix];
+
+        let short_sha =
+            |sha: &SharedString| -> String { sha.chars().take(git::SHORT_SHA_LENGTH).collect() };
+        let mut detail = format!(
+            "{} • {}",
+            submodule.status.label(),
+            short_sha(&submodule.recorded_sha)
+        );
+        if let Some(checked_out) = &submodule.checked_out_sha
+            && checked_out != &submodule.recorded_sha
+        {
+            detail.push_str(&format!(" → {}", short_sha(checked_out)));
+        }
+
+        Some(
+            ListItem::new(format!("submodule-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::Box)
+                        .color(submodule_status_color(submodule.status))
+                        .size(IconSize::Small),
+                )
+                .child(
+                    v_flex()
+                        .w_full()
+                        .child(
+                            HighlightedLabel::new(
+                                submodule.path.as_unix_str().to_string(),
+                                positions.clone(),
+                            )
+                            .truncate(),
+                        )
+                        .child(
+                            Label::new(detail)
+                                .truncate()
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                )
+                .when_some(submodule.url.clone(), |this, url| {
+                    this.tooltip(Tooltip::text(url))
+                }),
+        )
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let focus_handle = self.focus_handle.clone();
+        let entry = self.matches.get(self.selected_index)?;
+        let is_initialized = entry.submodule.status != SubmoduleStatus::Uninitialized;
+
+        let footer = h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_end()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .when(!is_initialized, |this| {
+                this.child(
+                    Button::new("init-submodule", "Init")
+                        .key_binding(
+                            KeyBinding::for_action_in(
+                                &submodule_picker::InitSubmodule,
+                                &focus_handle,
+                                cx,
+                            )
+                            .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window
+                                .dispatch_action(submodule_picker::InitSubmodule.boxed_clone(), cx)
+                        }),
+                )
+            })
+            .child(
+                Button::new("sync-submodule", "Sync")
+                    .key_binding(
+                        KeyBinding::for_action_in(
+                            &submodule_picker::SyncSubmodule,
+                            &focus_handle,
+                            cx,
+                        )
+                        .map(|kb| kb.size(rems_from_px(12.))),
+                    )
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(submodule_picker::SyncSubmodule.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                Button::new("update-submodule", "Update")
+                    .key_binding(
+                        KeyBinding::for_action_in(
+                            &submodule_picker::UpdateSubmodule,
+                            &focus_handle,
+                            cx,
+                        )
+                        .map(|kb| kb.size(rems_from_px(12.))),
+                    )
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(submodule_picker::UpdateSubmodule.boxed_clone(), cx)
+                    }),
+            )
+            .when(is_initialized, |this| {
+                this.child(
+                    Button::new("open-submodule", "Open Repository")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+            });
+
+        Some(footer.into_any())
+    }
+}
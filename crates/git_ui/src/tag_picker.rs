@@ -0,0 +1,768 @@
+use fuzzy::StringMatchCandidate;
+
+use git::repository::Tag;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, actions, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+use crate::git_panel::{GitPanel, show_error_toast};
+use crate::tag_picker;
+
+actions!(
+    tag_picker,
+    [
+        /// Deletes the selected tag.
+        DeleteTag,
+        /// Pushes the selected tag to a remote.
+        PushTag,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::ViewTags,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        TagList::new(repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl TagList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let tags_request = repository
+            .clone()
+            .map(|repository| repository.update(cx, |repository, _| repository.tags(None)));
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(tags_request) = tags_request else {
+                return anyhow::Ok(());
+            };
+            let tags = tags_request.await??;
+
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.all_tags = Some(tags);
+                    picker.refresh(window, cx);
+                })
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+
+        let delegate = TagListDelegate::new(repository, workspace, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, _| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            picker_focus_handle,
+            width,
+            _subscription,
+        }
+    }
+
+    fn handle_delete(&mut self, _: &DeleteTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_at(picker.delegate.selected_index, window, cx)
+        })
+    }
+
+    fn handle_push(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .push_at(picker.delegate.selected_index, window, cx)
+        })
+    }
+}
+
+impl ModalView for TagList {}
+impl EventEmitter<DismissEvent> for TagList {}
+
+impl Focusable for TagList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TagList")
+            .w(self.width)
+            .on_action(cx.listener(Self::handle_delete))
+            .on_action(cx.listener(Self::handle_push))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    Tag { tag: Tag, positions: Vec<usize> },
+    NewTag { name: String },
+    NewAnnotatedTag { name: String, message: String },
+}
+
+impl Entry {
+    fn as_tag(&self) -> Option<&Tag> {
+        match self {
+            Entry::Tag { tag, .. } => Some(tag),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Entry::Tag { tag, .. } => tag.name.as_ref(),
+            Entry::NewTag { name } | Entry::NewAnnotatedTag { name, .. } => name.as_str(),
+        }
+    }
+
+    #[cfg(test)]
+    fn is_new_tag(&self) -> bool {
+        matches!(self, Self::NewTag { .. })
+    }
+}
+
+#[derive(Debug)]
+enum PickerState {
+    /// When we display the list of tags
+    List,
+    /// When we enter the message of a new annotated tag
+    AnnotateTag(SharedString),
+}
+
+pub struct TagListDelegate {
+    matches: Vec<Entry>,
+    all_tags: Option<Vec<Tag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    state: PickerState,
+    focus_handle: FocusHandle,
+}
+
+impl TagListDelegate {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<TagList>,
+    ) -> Self {
+        Self {
+            matches: vec![],
+            all_tags: None,
+            repo,
+            workspace,
+            selected_index: 0,
+            state: PickerState::List,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        message: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.create_tag(name, None, message))?
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn delete_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag).cloned() else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let result = repo
+                .update(cx, |repo, _| repo.delete_tag(tag.name.to_string()))?
+                .await?;
+
+            if let Err(e) = result {
+                log::error!("Failed to delete tag: {}", e);
+                if let Some(workspace) = workspace.upgrade() {
+                    cx.update(|_window, cx| {
+                        show_error_toast(workspace, format!("tag -d {}", tag.name), e, cx)
+                    })?;
+                }
+                return Ok(());
+            }
+
+            picker.update_in(cx, |picker, _, cx| {
+                let delegate = &mut picker.delegate;
+                delegate
+                    .matches
+                    .retain(|entry| entry.as_tag() != Some(&tag));
+                if let Some(all_tags) = &mut delegate.all_tags {
+                    all_tags.retain(|existing| existing.name != tag.name);
+                }
+
+                if delegate.matches.is_empty() {
+                    delegate.selected_index = 0;
+                } else if delegate.selected_index >= delegate.matches.len() {
+                    delegate.selected_index = delegate.matches.len() - 1;
+                }
+
+                cx.notify();
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach();
+    }
+
+    fn push_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag) else {
+            return;
+        };
+        let Some(panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        let name = tag.name.clone();
+        panel.update(cx, |panel, cx| panel.push_tag(name, window, cx));
+        cx.emit(DismissEvent);
+    }
+
+    fn show_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        CommitView::open(
+            tag.sha.to_string(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.state {
+            PickerState::List => "Select or create a tag…",
+            PickerState::AnnotateTag(_) => "Enter a message for this tag…",
+        }
+        .into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        match self.state {
+            PickerState::List => Some("No tags found".into()),
+            PickerState::AnnotateTag(_) => Some("Tag message can't be empty".into()),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        if let PickerState::AnnotateTag(name) = &self.state {
+            self.matches = if query.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![Entry::NewAnnotatedTag {
+                    name: name.to_string(),
+                    message: query,
+                }]
+            };
+            self.selected_index = 0;
+            return Task::ready(());
+        }
+
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<Entry> = if query.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| Entry::Tag {
+                        tag,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| Entry::Tag {
+                    tag: all_tags[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            let name = query.trim().replace(' ', "-");
+            if !name.is_empty() && !matches.iter().any(|entry| entry.name() == name) {
+                matches.push(Entry::NewTag { name });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()).cloned() else {
+            return;
+        };
+
+        match entry {
+            Entry::Tag { .. } => self.show_at(self.selected_index, window, cx),
+            Entry::NewTag { name } if secondary => {
+                self.state = PickerState::AnnotateTag(name.into());
+                self.matches = Vec::new();
+                self.selected_index = 0;
+
+                cx.defer_in(window, |picker, window, cx| {
+                    picker.refresh_placeholder(window, cx);
+                    picker.set_query("", window, cx);
+                    cx.notify();
+                });
+
+                // Keep the modal open so the tag's message can be entered.
+                return;
+            }
+            Entry::NewTag { name } => self.create_tag(name, None, window, cx),
+            Entry::NewAnnotatedTag { name, message } => {
+                self.create_tag(name, Some(message), window, cx)
+            }
+        }
+
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.state = PickerState::List;
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+
+        let (icon, label, detail): (_, AnyElement, Option<SharedString>) = match entry {
+            Entry::Tag { tag, positions } => {
+                let short_sha: String = tag.sha.chars().take(git::SHORT_SHA_LENGTH).collect();
+                let detail = match &tag.message {
+                    Some(message) => format!("{short_sha} • {message}"),
+                    None => short_sha,
+                };
+                (
+                    IconName::Hash,
+                    HighlightedLabel::new(tag.name.clone(), positions.clone())
+                        .truncate()
+                        .into_any_element(),
+                    Some(detail.into()),
+                )
+            }
+            Entry::NewTag { name } => (
+                IconName::Plus,
+                Label::new(format!("Create Tag: \"{name}\""))
+                    .truncate()
+                    .into_any_element(),
+                None,
+            ),
+            Entry::NewAnnotatedTag { name, .. } => (
+                IconName::Plus,
+                Label::new(format!("Create Annotated Tag: \"{name}\""))
+                    .truncate()
+                    .into_any_element(),
+                None,
+            ),
+        };
+
+        Some(
+            ListItem::new(format!("tag-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(icon).color(Color::Muted).size(IconSize::Small))
+                .child(
+                    v_flex()
+                        .w_full()
+                        .child(label)
+                        .when_some(detail, |this, detail| {
+                            this.child(
+                                Label::new(detail)
+                                    .truncate()
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            )
+                        }),
+                )
+                .when_some(entry.as_tag(), |this, tag| {
+                    this.tooltip(Tooltip::text(format!("refs/tags/{}", tag.name)))
+                }),
+        )
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let focus_handle = self.focus_handle.clone();
+        let selected_entry = self.matches.get(self.selected_index);
+
+        let footer = h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_end()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant);
+
+        let footer = match selected_entry {
+            Some(Entry::Tag { .. }) => footer
+                .child(
+                    Button::new("delete-tag", "Delete")
+                        .key_binding(
+                            KeyBinding::for_action_in(&tag_picker::DeleteTag, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(tag_picker::DeleteTag.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("push-tag", "Push")
+                        .key_binding(
+                            KeyBinding::for_action_in(&tag_picker::PushTag, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(tag_picker::PushTag.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("view-tag", "View")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                ),
+            Some(Entry::NewTag { .. }) => footer
+                .child(
+                    Button::new("create-annotated-tag", "Create Annotated")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::SecondaryConfirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("create-tag", "Create")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                ),
+            Some(Entry::NewAnnotatedTag { .. }) | None => return None,
+        };
+
+        Some(footer.into_any())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    fn create_test_tag(name: &str, message: Option<&str>) -> Tag {
+        Tag {
+            name: name.to_string().into(),
+            sha: "deadbeef".into(),
+            message: message.map(|message| message.to_string().into()),
+        }
+    }
+
+    async fn init_tag_list_test(
+        repository: Option<Entity<Repository>>,
+        tags: Vec<Tag>,
+        cx: &mut TestAppContext,
+    ) -> (Entity<TagList>, VisualTestContext) {
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+
+        let workspace = cx.add_window(|window, cx| Workspace::test_new(project, window, cx));
+
+        let tag_list = workspace
+            .update(cx, |workspace, window, cx| {
+                cx.new(|cx| {
+                    let mut delegate =
+                        TagListDelegate::new(repository, workspace.weak_handle(), cx);
+                    delegate.all_tags = Some(tags);
+                    let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+                    let picker_focus_handle = picker.focus_handle(cx);
+                    picker.update(cx, |picker, _| {
+                        picker.delegate.focus_handle = picker_focus_handle.clone();
+                    });
+
+                    let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+                        cx.emit(DismissEvent);
+                    });
+
+                    TagList {
+                        picker,
+                        picker_focus_handle,
+                        width: rems(34.),
+                        _subscription,
+                    }
+                })
+            })
+            .unwrap();
+
+        let cx = VisualTestContext::from_window(*workspace, cx);
+
+        (tag_list, cx)
+    }
+
+    async fn update_tag_list_matches(
+        tag_list: &Entity<TagList>,
+        query: &str,
+        cx: &mut VisualTestContext,
+    ) {
+        tag_list
+            .update_in(cx, |tag_list, window, cx| {
+                tag_list.picker.update(cx, |picker, cx| {
+                    picker
+                        .delegate
+                        .update_matches(query.to_string(), window, cx)
+                })
+            })
+            .await;
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_update_tag_matches_with_query(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let tags = vec![
+            create_test_tag("v1.0", None),
+            create_test_tag("v1.1", Some("Release 1.1")),
+            create_test_tag("nightly", None),
+        ];
+        let (tag_list, mut ctx) = init_tag_list_test(None, tags, cx).await;
+        let cx = &mut ctx;
+
+        update_tag_list_matches(&tag_list, "v1", cx).await;
+        tag_list.update(cx, |tag_list, cx| {
+            tag_list.picker.update(cx, |picker, _| {
+                // Should have 2 existing tags + 1 "create tag" entry = 3 total
+                assert_eq!(picker.delegate.matches.len(), 3);
+                assert!(picker.delegate.matches.iter().any(|m| m.name() == "v1.0"));
+                assert!(picker.delegate.matches.iter().any(|m| m.name() == "v1.1"));
+                let last_match = picker.delegate.matches.last().unwrap();
+                assert!(last_match.is_new_tag());
+                assert_eq!(last_match.name(), "v1");
+            })
+        });
+
+        update_tag_list_matches(&tag_list, "v1.1", cx).await;
+        tag_list.update(cx, |tag_list, cx| {
+            tag_list.picker.update(cx, |picker, _| {
+                assert!(
+                    picker
+                        .delegate
+                        .matches
+                        .iter()
+                        .all(|entry| !entry.is_new_tag())
+                );
+            })
+        });
+    }
+
+    #[gpui::test]
+    async fn test_create_and_delete_tag(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ ".git": {}, "file.txt": "text" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let repository = cx
+            .read(|cx| project.read(cx).active_repository(cx))
+            .unwrap();
+
+        let (tag_list, mut ctx) =
+            init_tag_list_test(Some(repository.clone()), Vec::new(), cx).await;
+        let cx = &mut ctx;
+
+        update_tag_list_matches(&tag_list, "release one", cx).await;
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            tag_list.picker.update(cx, |picker, cx| {
+                picker.delegate.confirm(false, window, cx);
+            })
+        });
+        cx.run_until_parked();
+
+        let tags = repository
+            .update(cx, |repository, _| repository.tags(None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "release-one");
+        assert!(!tags[0].is_annotated());
+
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            tag_list.picker.update(cx, |picker, cx| {
+                picker.delegate.all_tags = Some(tags);
+                picker.delegate.matches = vec![Entry::Tag {
+                    tag: picker.delegate.all_tags.as_ref().unwrap()[0].clone(),
+                    positions: Vec::new(),
+                }];
+                picker.delegate.delete_at(0, window, cx);
+            })
+        });
+        cx.run_until_parked();
+
+        tag_list.update(cx, |tag_list, cx| {
+            tag_list.picker.update(cx, |picker, _| {
+                assert!(picker.delegate.matches.is_empty());
+                assert_eq!(picker.delegate.all_tags.as_deref(), Some(&[][..]));
+            })
+        });
+        let tags = repository
+            .update(cx, |repository, _| repository.tags(None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(tags.is_empty());
+    }
+}
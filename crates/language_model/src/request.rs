@@ -449,6 +449,9 @@ pub struct LanguageModelRequest {
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
     pub thinking_allowed: bool,
+    /// The most tokens the model may generate, below its own limit.
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
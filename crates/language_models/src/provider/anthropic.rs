@@ -753,7 +753,9 @@ pub fn into_anthropic(
     anthropic::Request {
         model,
         messages: new_messages,
-        max_tokens: max_output_tokens,
+        max_tokens: request
+            .max_output_tokens
+            .map_or(max_output_tokens, |max| max.min(max_output_tokens)),
         system: if system_message.is_empty() {
             None
         } else {
@@ -1171,6 +1173,7 @@ mod tests {
             tools: vec![],
             tool_choice: None,
             thinking_allowed: true,
+            max_output_tokens: None,
         };
 
         let anthropic_request = into_anthropic(
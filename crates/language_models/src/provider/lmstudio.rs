@@ -342,7 +342,10 @@ impl LmStudioLanguageModel {
             model: self.model.name.clone(),
             messages,
             stream: true,
-            max_tokens: Some(-1),
+            max_tokens: request
+                .max_output_tokens
+                .and_then(|max| i32::try_from(max).ok())
+                .or(Some(-1)),
             stop: Some(request.stop),
             // In LM Studio you can configure specific settings you'd like to use for your model.
             // For example Qwen3 is recommended to be used with 0.7 temperature.
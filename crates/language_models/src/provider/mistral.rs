@@ -539,7 +539,11 @@ pub fn into_mistral(
         model: model.id().to_string(),
         messages,
         stream,
-        max_tokens: max_output_tokens,
+        max_tokens: request
+            .max_output_tokens
+            .into_iter()
+            .chain(max_output_tokens)
+            .min(),
         temperature: request.temperature,
         response_format: None,
         tool_choice: match request.tool_choice {
@@ -908,6 +912,7 @@ mod tests {
             mode: None,
             stop: vec![],
             thinking_allowed: true,
+            max_output_tokens: None,
         };
 
         let mistral_request = into_mistral(request, mistral::Model::MistralSmallLatest, None);
@@ -942,6 +947,7 @@ mod tests {
             mode: None,
             stop: vec![],
             thinking_allowed: true,
+            max_output_tokens: None,
         };
 
         let mistral_request = into_mistral(request, mistral::Model::Pixtral12BLatest, None);
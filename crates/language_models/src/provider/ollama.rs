@@ -397,6 +397,9 @@ impl OllamaLanguageModel {
                 num_ctx: Some(self.model.max_tokens),
                 stop: Some(request.stop),
                 temperature: request.temperature.or(Some(1.0)),
+                num_predict: request
+                    .max_output_tokens
+                    .and_then(|max| isize::try_from(max).ok()),
                 ..Default::default()
             }),
             think: self
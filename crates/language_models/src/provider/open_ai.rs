@@ -428,7 +428,11 @@ pub fn into_open_ai(
         stream,
         stop: request.stop,
         temperature: request.temperature.or(Some(1.0)),
-        max_completion_tokens: max_output_tokens,
+        max_completion_tokens: request
+            .max_output_tokens
+            .into_iter()
+            .chain(max_output_tokens)
+            .min(),
         parallel_tool_calls: if supports_parallel_tool_calls && !request.tools.is_empty() {
             // Disable parallel tool calls, as the Agent currently expects a maximum of one per turn.
             Some(false)
@@ -881,6 +885,7 @@ mod tests {
             stop: vec![],
             temperature: None,
             thinking_allowed: true,
+            max_output_tokens: None,
         };
 
         // Validate that all models are supported by tiktoken-rs
@@ -477,7 +477,11 @@ pub fn into_open_router(
         stream: true,
         stop: request.stop,
         temperature: request.temperature.unwrap_or(0.4),
-        max_tokens: max_output_tokens,
+        max_tokens: request
+            .max_output_tokens
+            .into_iter()
+            .chain(max_output_tokens)
+            .min(),
         parallel_tool_calls: if model.supports_parallel_tool_calls() && !request.tools.is_empty() {
             Some(false)
         } else {
@@ -3,10 +3,25 @@ pub mod registry;
 
 use std::sync::Arc;
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
-use futures::{FutureExt as _, future::join_all};
+use context_server::oauth::{self, AuthorizationRequired, OAuthCredentials, OAuthSession};
+use context_server::{
+    ContextServer, ContextServerCommand, ContextServerId,
+    client::{self, NotificationSubscription},
+    protocol::{InitializedContextServerProtocol, ServerCapability},
+    types::{
+        Notification as _, PaginatedParams, Resource, ResourceTemplate,
+        notifications::ResourcesListChanged,
+        requests::{ListResourceTemplates, ResourcesList},
+    },
+};
+use credentials_provider::CredentialsProvider;
+use futures::{
+    FutureExt as _, StreamExt as _,
+    channel::{mpsc, oneshot},
+    future::{Shared, join_all},
+};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use registry::ContextServerDescriptorRegistry;
 use settings::{Settings as _, SettingsStore};
@@ -15,7 +30,7 @@ use util::{ResultExt as _, rel_path::RelPath};
 use crate::{
     Project,
     project_settings::{ContextServerSettings, ProjectSettings},
-    worktree_store::WorktreeStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
 pub fn init(cx: &mut App) {
@@ -36,6 +51,8 @@ pub enum ContextServerStatus {
     Running,
     Stopped,
     Error(Arc<str>),
+    /// The server requires the user to sign in before it can be used.
+    AuthRequired,
 }
 
 impl ContextServerStatus {
@@ -45,6 +62,7 @@ impl ContextServerStatus {
             ContextServerState::Running { .. } => ContextServerStatus::Running,
             ContextServerState::Stopped { .. } => ContextServerStatus::Stopped,
             ContextServerState::Error { error, .. } => ContextServerStatus::Error(error.clone()),
+            ContextServerState::AuthRequired { .. } => ContextServerStatus::AuthRequired,
         }
     }
 }
@@ -68,6 +86,11 @@ enum ContextServerState {
         configuration: Arc<ContextServerConfiguration>,
         error: Arc<str>,
     },
+    AuthRequired {
+        server: Arc<ContextServer>,
+        configuration: Arc<ContextServerConfiguration>,
+        authorization: AuthorizationRequired,
+    },
 }
 
 impl ContextServerState {
@@ -77,6 +100,7 @@ impl ContextServerState {
             ContextServerState::Running { server, .. } => server.clone(),
             ContextServerState::Stopped { server, .. } => server.clone(),
             ContextServerState::Error { server, .. } => server.clone(),
+            ContextServerState::AuthRequired { server, .. } => server.clone(),
         }
     }
 
@@ -86,6 +110,7 @@ impl ContextServerState {
             ContextServerState::Running { configuration, .. } => configuration.clone(),
             ContextServerState::Stopped { configuration, .. } => configuration.clone(),
             ContextServerState::Error { configuration, .. } => configuration.clone(),
+            ContextServerState::AuthRequired { configuration, .. } => configuration.clone(),
         }
     }
 }
@@ -171,9 +196,28 @@ pub struct ContextServerStore {
     update_servers_task: Option<Task<Result<()>>>,
     context_server_factory: Option<ContextServerFactory>,
     needs_server_update: bool,
+    resource_lists: HashMap<ContextServerId, ResourceLists>,
+    /// The sign-ins that are waiting for the user to approve access in their browser, which are
+    /// canceled when they're dropped.
+    pending_authorizations: HashMap<ContextServerId, Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
+/// The resources and resource templates that a running server offers.
+#[derive(Debug, Default)]
+pub struct ContextServerResources {
+    pub resources: Vec<Resource>,
+    pub templates: Vec<ResourceTemplate>,
+}
+
+/// What a running server last listed of its resources, kept until it says they changed.
+struct ResourceLists {
+    lists: Option<Shared<Task<Result<Arc<ContextServerResources>, Arc<str>>>>>,
+    /// Cleared once the server answers that it doesn't know how to list resource templates.
+    supports_templates: bool,
+    _list_changed_subscription: NotificationSubscription,
+}
+
 pub enum Event {
     ServerStatusChanged {
         server_id: ContextServerId,
@@ -244,8 +288,16 @@ impl ContextServerStore {
         weak_project: WeakEntity<Project>,
         cx: &mut Context<Self>,
     ) -> Self {
-        let subscriptions = if maintain_server_loop {
-            vec![
+        let mut subscriptions = vec![cx.subscribe(&worktree_store, |this, _, event, cx| {
+            if let WorktreeStoreEvent::WorktreeAdded(_)
+            | WorktreeStoreEvent::WorktreeRemoved(..)
+            | WorktreeStoreEvent::WorktreeOrderChanged = event
+            {
+                this.update_roots(cx);
+            }
+        })];
+        if maintain_server_loop {
+            subscriptions.extend([
                 cx.observe(&registry, |this, _registry, cx| {
                     this.available_context_servers_changed(cx);
                 }),
@@ -257,10 +309,8 @@ impl ContextServerStore {
                     this.context_server_settings = settings.clone();
                     this.available_context_servers_changed(cx);
                 }),
-            ]
-        } else {
-            Vec::new()
-        };
+            ]);
+        }
 
         let mut this = Self {
             _subscriptions: subscriptions,
@@ -271,6 +321,8 @@ impl ContextServerStore {
             registry,
             needs_server_update: false,
             servers: HashMap::default(),
+            resource_lists: HashMap::default(),
+            pending_authorizations: HashMap::default(),
             update_servers_task: None,
             context_server_factory,
         };
@@ -330,6 +382,136 @@ impl ContextServerStore {
             .collect()
     }
 
+    /// Lists the resources and resource templates of a running server, reusing what earlier
+    /// calls listed until the server says that its resources changed.
+    pub fn resources(
+        &mut self,
+        id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Arc<ContextServerResources>>> {
+        let Some(client) = self
+            .get_running_server(id)
+            .and_then(|server| server.client())
+        else {
+            return Task::ready(Err(anyhow!("Context server {id} is not running")));
+        };
+        if !client.capable(ServerCapability::Resources) {
+            return Task::ready(Ok(Arc::default()));
+        }
+
+        let resource_lists = self.resource_lists.entry(id.clone()).or_insert_with(|| {
+            let this = cx.weak_entity();
+            let id = id.clone();
+            ResourceLists {
+                lists: None,
+                supports_templates: true,
+                _list_changed_subscription: client.on_notification(
+                    ResourcesListChanged::METHOD,
+                    Box::new(move |_params, cx| {
+                        let this = this.clone();
+                        let id = id.clone();
+                        cx.spawn(async move |cx| {
+                            this.update(cx, |this, _| {
+                                if let Some(resource_lists) = this.resource_lists.get_mut(&id) {
+                                    resource_lists.lists = None;
+                                }
+                            })
+                        })
+                        .detach();
+                    }),
+                ),
+            }
+        });
+        // Listing again after a failure, which may have been temporary.
+        let lists = match &resource_lists.lists {
+            Some(lists) if !matches!(lists.peek(), Some(Err(_))) => lists.clone(),
+            _ => {
+                let lists =
+                    Self::list_resources(id.clone(), client, resource_lists.supports_templates, cx)
+                        .shared();
+                resource_lists.lists = Some(lists.clone());
+                lists
+            }
+        };
+        cx.background_spawn(async move { lists.await.map_err(|error| anyhow!(error)) })
+    }
+
+    fn list_resources(
+        id: ContextServerId,
+        client: Arc<InitializedContextServerProtocol>,
+        supports_templates: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Arc<ContextServerResources>, Arc<str>>> {
+        cx.spawn(async move |this, cx| {
+            let mut lists = ContextServerResources::default();
+            let mut cursor = None;
+            loop {
+                let response = client
+                    .request::<ResourcesList>(PaginatedParams { cursor, meta: None })
+                    .await
+                    .map_err(|error| Arc::<str>::from(error.to_string()))?;
+                lists.resources.extend(response.resources);
+                cursor = response.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            let mut cursor = None;
+            while supports_templates {
+                let response = match client
+                    .request::<ListResourceTemplates>(PaginatedParams { cursor, meta: None })
+                    .await
+                {
+                    Ok(response) => response,
+                    Err(error)
+                        if error
+                            .downcast_ref::<client::Error>()
+                            .is_some_and(|error| error.code == client::METHOD_NOT_FOUND) =>
+                    {
+                        this.update(cx, |this, _| {
+                            if let Some(resource_lists) = this.resource_lists.get_mut(&id) {
+                                resource_lists.supports_templates = false;
+                            }
+                        })
+                        .ok();
+                        break;
+                    }
+                    Err(error) => return Err(Arc::from(error.to_string())),
+                };
+                lists.templates.extend(response.resource_templates);
+                cursor = response.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            Ok(Arc::new(lists))
+        })
+    }
+
+    /// The project's visible worktrees, which servers are told they can operate on.
+    fn roots(&self, cx: &App) -> Vec<context_server::types::Root> {
+        self.worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                Some(context_server::types::Root {
+                    uri: url::Url::from_directory_path(worktree.abs_path()).ok()?,
+                    name: Some(worktree.root_name_str().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn update_roots(&mut self, cx: &mut Context<Self>) {
+        let roots = self.roots(cx);
+        for state in self.servers.values() {
+            state.server().set_roots(roots.clone()).log_err();
+        }
+    }
+
     pub fn start_server(&mut self, server: Arc<ContextServer>, cx: &mut Context<Self>) {
         cx.spawn(async move |this, cx| {
             let this = this.upgrade().context("Context server store dropped")?;
@@ -411,12 +593,28 @@ impl ContextServerStore {
         ) {
             self.stop_server(&id, cx).log_err();
         }
+        server.set_roots(self.roots(cx)).log_err();
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
         let task = cx.spawn({
             let id = server.id();
             let server = server.clone();
             let configuration = configuration.clone();
 
             async move |this, cx| {
+                if let ContextServerConfiguration::Http { url, .. } = configuration.as_ref()
+                    && server.oauth_session().is_none()
+                    && let Some(credentials) =
+                        read_oauth_credentials(credentials_provider.as_ref(), url, cx)
+                            .await
+                            .log_err()
+                            .flatten()
+                {
+                    this.update(cx, |_, cx| {
+                        server.set_oauth_session(Some(Self::oauth_session(url, credentials, cx)))
+                    })
+                    .ok();
+                }
+
                 match server.clone().start(cx).await {
                     Ok(_) => {
                         debug_assert!(server.client().is_some());
@@ -434,6 +632,24 @@ impl ContextServerStore {
                         .log_err()
                     }
                     Err(err) => {
+                        if let Some(authorization) = err.downcast_ref::<AuthorizationRequired>() {
+                            log::info!("{} context server requires authorization", id);
+                            let authorization = authorization.clone();
+                            this.update(cx, |this, cx| {
+                                this.update_server_state(
+                                    id.clone(),
+                                    ContextServerState::AuthRequired {
+                                        configuration,
+                                        server,
+                                        authorization,
+                                    },
+                                    cx,
+                                )
+                            })
+                            .log_err();
+                            return;
+                        }
+
                         log::error!("{} context server failed to start: {}", id, err);
                         this.update(cx, |this, cx| {
                             this.update_server_state(
@@ -463,6 +679,175 @@ impl ContextServerStore {
         );
     }
 
+    /// Lets the user sign in to a server that requires it in their browser, then restarts the
+    /// server with their credentials. The returned task succeeds without signing in if the
+    /// sign-in is canceled with [`Self::cancel_authentication`].
+    pub fn authenticate_server(
+        &mut self,
+        id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if self.pending_authorizations.contains_key(id) {
+            return Task::ready(Err(anyhow!(
+                "Already waiting for the sign-in to {id} context server"
+            )));
+        }
+        let Some(ContextServerState::AuthRequired {
+            configuration,
+            authorization,
+            ..
+        }) = self.servers.get(id)
+        else {
+            return Task::ready(Err(anyhow!(
+                "{id} context server doesn't require authorization"
+            )));
+        };
+        let ContextServerConfiguration::Http { url, .. } = configuration.as_ref() else {
+            return Task::ready(Err(anyhow!("{id} context server isn't an HTTP server")));
+        };
+
+        let id = id.clone();
+        let url = url.clone();
+        let www_authenticate = authorization.www_authenticate.clone();
+        let http_client = cx.http_client();
+        let executor = cx.background_executor().clone();
+        let (result_tx, result_rx) = oneshot::channel();
+        let authorization = cx.spawn({
+            let id = id.clone();
+            async move |this, cx| {
+                let result = async {
+                    let credentials = oauth::authorize(
+                        http_client,
+                        &url,
+                        www_authenticate.as_deref(),
+                        |authorization_url| {
+                            cx.update(|cx| cx.open_url(authorization_url.as_str()))
+                                .log_err();
+                        },
+                        executor,
+                    )
+                    .await?;
+                    this.update(cx, |this, cx| this.sign_in_server(&id, credentials, cx))?
+                        .await
+                }
+                .await;
+                result_tx.send(result).ok();
+                this.update(cx, |this, cx| {
+                    this.pending_authorizations.remove(&id);
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+        self.pending_authorizations.insert(id, authorization);
+        cx.notify();
+
+        cx.background_spawn(async move { result_rx.await.unwrap_or(Ok(())) })
+    }
+
+    /// Whether a sign-in to the server is waiting for the user to approve access in their
+    /// browser.
+    pub fn is_authenticating(&self, id: &ContextServerId) -> bool {
+        self.pending_authorizations.contains_key(id)
+    }
+
+    /// Stops waiting for the user to approve access to the server in their browser.
+    pub fn cancel_authentication(&mut self, id: &ContextServerId, cx: &mut Context<Self>) {
+        if self.pending_authorizations.remove(id).is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Whether the server's requests are signed with credentials the user signed in with.
+    pub fn is_signed_in(&self, id: &ContextServerId) -> bool {
+        self.servers
+            .get(id)
+            .is_some_and(|state| state.server().oauth_session().is_some())
+    }
+
+    /// Deletes the credentials that the user signed in to a server with, then restarts the
+    /// server, which asks them to sign in again.
+    pub fn sign_out_server(
+        &mut self,
+        id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(state) = self.servers.get(id) else {
+            return Task::ready(Err(anyhow!("Context server not found")));
+        };
+        let server = state.server();
+        let configuration = state.configuration();
+        let ContextServerConfiguration::Http { url, .. } = configuration.as_ref() else {
+            return Task::ready(Err(anyhow!("{id} context server isn't an HTTP server")));
+        };
+
+        let url = url.clone();
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .delete_credentials(url.as_str(), cx)
+                .await?;
+            this.update(cx, |this, cx| {
+                server.set_oauth_session(None);
+                this.run_server(server, configuration, cx);
+            })
+        })
+    }
+
+    /// Saves the credentials that the user authorized a server with and restarts it with them.
+    fn sign_in_server(
+        &mut self,
+        id: &ContextServerId,
+        credentials: OAuthCredentials,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(state) = self.servers.get(id) else {
+            return Task::ready(Err(anyhow!("Context server not found")));
+        };
+        let server = state.server();
+        let configuration = state.configuration();
+        let ContextServerConfiguration::Http { url, .. } = configuration.as_ref() else {
+            return Task::ready(Err(anyhow!("{id} context server isn't an HTTP server")));
+        };
+
+        let url = url.clone();
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        cx.spawn(async move |this, cx| {
+            write_oauth_credentials(credentials_provider.as_ref(), &url, &credentials, cx).await?;
+            this.update(cx, |this, cx| {
+                server.set_oauth_session(Some(Self::oauth_session(&url, credentials, cx)));
+                this.run_server(server, configuration, cx);
+            })
+        })
+    }
+
+    /// A session for the server at `url`, which saves its credentials whenever they're refreshed.
+    fn oauth_session(
+        url: &url::Url,
+        credentials: OAuthCredentials,
+        cx: &mut Context<Self>,
+    ) -> Arc<OAuthSession> {
+        let (refreshed_tx, mut refreshed_rx) = mpsc::unbounded();
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let url = url.clone();
+        cx.spawn(async move |_, cx| {
+            while let Some(credentials) = refreshed_rx.next().await {
+                write_oauth_credentials(credentials_provider.as_ref(), &url, &credentials, cx)
+                    .await
+                    .log_err();
+            }
+        })
+        .detach();
+
+        Arc::new(OAuthSession::new(
+            cx.http_client(),
+            credentials,
+            move |credentials| {
+                refreshed_tx.unbounded_send(credentials).ok();
+            },
+        ))
+    }
+
     fn remove_server(&mut self, id: &ContextServerId, cx: &mut Context<Self>) -> Result<()> {
         let state = self
             .servers
@@ -543,6 +928,7 @@ impl ContextServerStore {
     ) {
         let status = ContextServerStatus::from_state(&state);
         self.servers.insert(id.clone(), state);
+        self.resource_lists.remove(&id);
         cx.emit(Event::ServerStatusChanged {
             server_id: id,
             status,
@@ -658,6 +1044,36 @@ impl ContextServerStore {
     }
 }
 
+async fn read_oauth_credentials(
+    credentials_provider: &dyn CredentialsProvider,
+    url: &url::Url,
+    cx: &AsyncApp,
+) -> Result<Option<OAuthCredentials>> {
+    let Some((_, credentials)) = credentials_provider
+        .read_credentials(url.as_str(), cx)
+        .await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&credentials)?))
+}
+
+async fn write_oauth_credentials(
+    credentials_provider: &dyn CredentialsProvider,
+    url: &url::Url,
+    credentials: &OAuthCredentials,
+    cx: &AsyncApp,
+) -> Result<()> {
+    credentials_provider
+        .write_credentials(
+            url.as_str(),
+            &credentials.client_id,
+            &serde_json::to_vec(credentials)?,
+            cx,
+        )
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1309,6 +1725,303 @@ mod tests {
         cx.run_until_parked();
     }
 
+    #[gpui::test]
+    async fn test_remote_context_server_authorization(cx: &mut TestAppContext) {
+        use context_server::{test::FakeAuthorizationServer, types::requests::ListTools};
+        use http_client::HttpClient;
+
+        const SERVER_ID: &str = "remote-server";
+        let server_id = ContextServerId(SERVER_ID.into());
+        let server_url = url::Url::parse(FakeAuthorizationServer::MCP_URL).unwrap();
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({ "code.rs": "" }),
+            vec![(
+                SERVER_ID.into(),
+                ContextServerSettings::Http {
+                    enabled: true,
+                    url: server_url.to_string(),
+                    headers: Default::default(),
+                },
+            )],
+        )
+        .await;
+
+        let authorization_server = FakeAuthorizationServer::default();
+        let http_client: Arc<dyn HttpClient> = authorization_server.http_client();
+        cx.update(|cx| cx.set_http_client(http_client.clone()));
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test_maintain_server_loop(
+                None,
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        // The server rejects requests until the user signs in.
+        let server_events = assert_server_events(
+            &store,
+            vec![
+                (server_id.clone(), ContextServerStatus::Starting),
+                (server_id.clone(), ContextServerStatus::AuthRequired),
+            ],
+            cx,
+        );
+        cx.run_until_parked();
+        drop(server_events);
+
+        // Approve the authorization, as the user would in their browser.
+        let authorization = oauth::begin_authorization(
+            &http_client,
+            &server_url,
+            None,
+            "http://127.0.0.1:1234/callback",
+        )
+        .await
+        .unwrap();
+        let callback_query = authorization_server.approve(&authorization.url);
+        let credentials = authorization
+            .complete(&http_client, &callback_query)
+            .await
+            .unwrap();
+
+        let server_events = assert_server_events(
+            &store,
+            vec![
+                (server_id.clone(), ContextServerStatus::Starting),
+                (server_id.clone(), ContextServerStatus::Running),
+            ],
+            cx,
+        );
+        store
+            .update(cx, |store, cx| {
+                store.sign_in_server(&server_id, credentials, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        drop(server_events);
+
+        // Once the access token is rejected, it's refreshed and the request is retried.
+        authorization_server.revoke_access_tokens();
+        let server = store
+            .read_with(cx, |store, _| store.get_running_server(&server_id))
+            .unwrap();
+        server
+            .client()
+            .unwrap()
+            .request::<ListTools>(())
+            .await
+            .unwrap();
+        assert_eq!(
+            authorization_server.grant_types(),
+            ["authorization_code", "refresh_token"]
+        );
+
+        // Signing out forgets the credentials, so the server asks the user to sign in again.
+        assert!(store.read_with(cx, |store, _| store.is_signed_in(&server_id)));
+        let server_events = assert_server_events(
+            &store,
+            vec![
+                (server_id.clone(), ContextServerStatus::Stopped),
+                (server_id.clone(), ContextServerStatus::Starting),
+                (server_id.clone(), ContextServerStatus::AuthRequired),
+            ],
+            cx,
+        );
+        store
+            .update(cx, |store, cx| store.sign_out_server(&server_id, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        drop(server_events);
+        assert!(!store.read_with(cx, |store, _| store.is_signed_in(&server_id)));
+    }
+
+    #[gpui::test]
+    async fn test_context_server_roots(cx: &mut TestAppContext) {
+        use context_server::types::{notifications::RootsListChanged, requests::ListRoots};
+        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+        const SERVER_ID: &str = "mcp-1";
+
+        let (fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_ID.into(), dummy_server_settings())],
+        )
+        .await;
+        fs.insert_tree(path!("/other"), json!({"lib.rs": ""})).await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let roots_changed_count = Arc::new(AtomicUsize::new(0));
+        let transport = create_fake_transport(SERVER_ID, cx.executor())
+            .on_notification::<RootsListChanged>({
+                let roots_changed_count = roots_changed_count.clone();
+                move |_| {
+                    roots_changed_count.fetch_add(1, SeqCst);
+                }
+            });
+        let fake_server = transport.server();
+        let server = Arc::new(ContextServer::new(
+            ContextServerId(SERVER_ID.into()),
+            Arc::new(transport),
+        ));
+        store.update(cx, |store, cx| store.start_server(server, cx));
+        cx.run_until_parked();
+
+        let root_uris = async || {
+            fake_server
+                .request::<ListRoots>(())
+                .await
+                .unwrap()
+                .roots
+                .into_iter()
+                .map(|root| root.uri)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            root_uris().await,
+            vec![url::Url::from_directory_path(path!("/test")).unwrap()]
+        );
+
+        project
+            .update(cx, |project, cx| {
+                project.find_or_create_worktree(path!("/other"), true, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(roots_changed_count.load(SeqCst), 1);
+        assert_eq!(
+            root_uris().await,
+            vec![
+                url::Url::from_directory_path(path!("/test")).unwrap(),
+                url::Url::from_directory_path(path!("/other")).unwrap(),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_context_server_resources(cx: &mut TestAppContext) {
+        use context_server::types::{
+            Implementation, InitializeResponse, LATEST_PROTOCOL_VERSION, ProtocolVersion,
+            ResourcesCapabilities, ResourcesListResponse, ServerCapabilities, requests::Initialize,
+        };
+        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+        const SERVER_ID: &str = "mcp-1";
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_ID.into(), dummy_server_settings())],
+        )
+        .await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        // The server lists its resources over two pages, and doesn't know how to list templates.
+        let list_requests = Arc::new(AtomicUsize::new(0));
+        let transport = create_fake_transport(SERVER_ID, cx.executor())
+            .on_request::<Initialize, _>(|_| async {
+                InitializeResponse {
+                    protocol_version: ProtocolVersion(LATEST_PROTOCOL_VERSION.to_string()),
+                    server_info: Implementation {
+                        name: SERVER_ID.into(),
+                        version: "1.0.0".into(),
+                    },
+                    capabilities: ServerCapabilities {
+                        resources: Some(ResourcesCapabilities {
+                            subscribe: None,
+                            list_changed: Some(true),
+                        }),
+                        ..Default::default()
+                    },
+                    meta: None,
+                }
+            })
+            .on_request::<ResourcesList, _>({
+                let list_requests = list_requests.clone();
+                move |params| {
+                    list_requests.fetch_add(1, SeqCst);
+                    let (name, next_cursor) = match params.cursor.as_deref() {
+                        None => ("first", Some("2".to_string())),
+                        Some(cursor) => {
+                            assert_eq!(cursor, "2");
+                            ("second", None)
+                        }
+                    };
+                    async move {
+                        ResourcesListResponse {
+                            resources: vec![Resource {
+                                uri: format!("test://{name}").parse().unwrap(),
+                                name: name.into(),
+                                description: None,
+                                mime_type: None,
+                            }],
+                            next_cursor,
+                            meta: None,
+                        }
+                    }
+                }
+            });
+        let fake_server = transport.server();
+        let server_id = ContextServerId(SERVER_ID.into());
+        let server = Arc::new(ContextServer::new(server_id.clone(), Arc::new(transport)));
+        store.update(cx, |store, cx| store.start_server(server, cx));
+        cx.run_until_parked();
+
+        let resource_names = async |cx: &mut TestAppContext| {
+            let resources = store
+                .update(cx, |store, cx| store.resources(&server_id, cx))
+                .await
+                .unwrap();
+            assert!(resources.templates.is_empty());
+            resources
+                .resources
+                .iter()
+                .map(|resource| resource.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(resource_names(cx).await, ["first", "second"]);
+        assert_eq!(list_requests.load(SeqCst), 2);
+        store.read_with(cx, |store, _| {
+            assert!(!store.resource_lists[&server_id].supports_templates);
+        });
+
+        // The lists are reused until the server says they changed.
+        assert_eq!(resource_names(cx).await, ["first", "second"]);
+        assert_eq!(list_requests.load(SeqCst), 2);
+
+        fake_server.notify::<ResourcesListChanged>(()).unwrap();
+        cx.run_until_parked();
+        assert_eq!(resource_names(cx).await, ["first", "second"]);
+        assert_eq!(list_requests.load(SeqCst), 4);
+    }
+
     struct ServerEvents {
         received_event_count: Rc<RefCell<usize>>,
         expected_event_count: usize,
@@ -2,6 +2,7 @@ pub mod branch_diff;
 mod conflict_set;
 pub mod git_traversal;
 pub mod pending_op;
+mod pull_requests;
 
 use crate::{
     ProjectEnvironment, ProjectItem, ProjectPath,
@@ -25,19 +26,25 @@ use futures::{
     stream::FuturesOrdered,
 };
 use git::{
-    BuildPermalinkParams, GitHostingProviderRegistry, Oid, RunHook,
+    BuildPermalinkParams, GitHostingProviderRegistry, Oid, PullRequestDetails, RunHook,
+    bisect::{BisectMark, BisectRemaining, BisectStatus},
     blame::Blame,
+    log::{LogEntry, LogRef, LogRefKind, LogSearch},
     parse_git_remote_url,
+    rebase::{RebaseStatus, RebaseTodoAction, RebaseTodoEntry},
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus, Worktree as GitWorktree,
+        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, ConflictStages, DiffType,
+        FetchOptions, GitRepository, GitRepositoryCheckpoint, PushOptions, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, SequencerCommand, SequencerControl,
+        SequencerOutcome, Tag, UpstreamTrackingStatus, Worktree as GitWorktree,
     },
+    signing::{CommitSignature, SignatureStatus},
     stash::{GitStash, StashEntry},
     status::{
         DiffTreeType, FileStatus, GitSummary, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
         UnmergedStatus, UnmergedStatusCode,
     },
+    submodule::{Submodule, SubmoduleStatus},
 };
 use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
@@ -54,7 +61,7 @@ use rpc::{
     AnyProtoClient, TypedEnvelope,
     proto::{self, git_reset, split_repository_update},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use settings::WorktreeId;
 use smol::future::yield_now;
 use std::{
@@ -179,7 +186,7 @@ struct LocalDownstreamState {
     _task: Task<Result<()>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GitStoreCheckpoint {
     checkpoints_by_work_dir_abs_path: HashMap<Arc<Path>, GitRepositoryCheckpoint>,
 }
@@ -250,6 +257,8 @@ pub struct MergeDetails {
     pub conflicted_paths: TreeSet<RepoPath>,
     pub message: Option<SharedString>,
     pub heads: Vec<Option<SharedString>>,
+    /// The cherry-pick or revert that stopped midway, if any.
+    pub sequencer: Option<SequencerCommand>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -265,6 +274,9 @@ pub struct RepositorySnapshot {
     pub remote_origin_url: Option<String>,
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
+    pub rebase: Option<RebaseStatus>,
+    pub submodules: Arc<[Submodule]>,
+    pub bisect: Option<BisectStatus>,
 }
 
 type JobId = u64;
@@ -290,6 +302,8 @@ pub struct Repository {
     askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
     latest_askpass_id: u64,
     repository_state: Shared<Task<Result<RepositoryState, String>>>,
+    pull_request: Option<PullRequestDetails>,
+    pull_request_task: Task<()>,
 }
 
 impl std::ops::Deref for Repository {
@@ -365,7 +379,12 @@ pub enum RepositoryEvent {
     StatusesChanged,
     MergeHeadsChanged,
     BranchChanged,
+    RemotesChanged,
     StashEntriesChanged,
+    RebaseStatusChanged,
+    SubmodulesChanged,
+    BisectStatusChanged,
+    PullRequestChanged,
     PendingOpsChanged { pending_ops: SumTree<PendingOps> },
 }
 
@@ -475,6 +494,16 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_create_remote);
         client.add_entity_request_handler(Self::handle_remove_remote);
         client.add_entity_request_handler(Self::handle_delete_branch);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_push_tag);
+        client.add_entity_request_handler(Self::handle_init_submodules);
+        client.add_entity_request_handler(Self::handle_sync_submodules);
+        client.add_entity_request_handler(Self::handle_update_submodules);
+        client.add_entity_request_handler(Self::handle_bisect_start);
+        client.add_entity_request_handler(Self::handle_bisect_mark);
+        client.add_entity_request_handler(Self::handle_bisect_reset);
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
@@ -489,8 +518,11 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_get_commit_signature);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
+        client.add_entity_request_handler(Self::handle_load_conflict_stages);
         client.add_entity_request_handler(Self::handle_file_history);
+        client.add_entity_request_handler(Self::handle_log);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
@@ -509,6 +541,12 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_git_clone);
         client.add_entity_request_handler(Self::handle_get_worktrees);
         client.add_entity_request_handler(Self::handle_create_worktree);
+        client.add_entity_request_handler(Self::handle_rebase_todo);
+        client.add_entity_request_handler(Self::handle_rebase_start);
+        client.add_entity_request_handler(Self::handle_rebase_edit_todo);
+        client.add_entity_request_handler(Self::handle_rebase_control);
+        client.add_entity_request_handler(Self::handle_apply_commits);
+        client.add_entity_request_handler(Self::handle_sequencer_control);
     }
 
     pub fn is_local(&self) -> bool {
@@ -1014,6 +1052,36 @@ impl GitStore {
         })
     }
 
+    /// Returns a patch of the changes between two checkpoints, across every repository they both
+    /// cover.
+    pub fn diff_checkpoints(
+        &self,
+        base: GitStoreCheckpoint,
+        mut target: GitStoreCheckpoint,
+        cx: &mut App,
+    ) -> Task<Result<String>> {
+        let repositories_by_work_dir_abs_path = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).snapshot.work_directory_abs_path.clone(), repo))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = Vec::new();
+        for (work_dir_abs_path, base_checkpoint) in base.checkpoints_by_work_dir_abs_path {
+            if let Some(target_checkpoint) = target
+                .checkpoints_by_work_dir_abs_path
+                .remove(&work_dir_abs_path)
+                && let Some(repository) = repositories_by_work_dir_abs_path.get(&work_dir_abs_path)
+            {
+                let diff = repository.update(cx, |repository, _| {
+                    repository.diff_checkpoints(base_checkpoint, target_checkpoint)
+                });
+                tasks.push(async move { diff.await? });
+            }
+        }
+        cx.background_spawn(async move { Ok(future::try_join_all(tasks).await?.concat()) })
+    }
+
     /// Blames a buffer.
     pub fn blame_buffer(
         &self,
@@ -1085,6 +1153,19 @@ impl GitStore {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    pub fn log(
+        &self,
+        repo: &Entity<Repository>,
+        search: Option<LogSearch>,
+        skip: usize,
+        limit: Option<usize>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<LogEntry>>> {
+        let rx = repo.update(cx, |repo, _| repo.log(search, skip, limit));
+
+        cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
+    }
+
     pub fn get_permalink_to_line(
         &self,
         buffer: &Entity<Buffer>,
@@ -1334,6 +1415,12 @@ impl GitStore {
                 .ok();
             }
         }
+        if matches!(
+            event,
+            RepositoryEvent::BranchChanged | RepositoryEvent::RemotesChanged
+        ) {
+            repo.update(cx, |repo, cx| repo.refresh_pull_request(cx));
+        }
         cx.emit(GitStoreEvent::RepositoryUpdated(
             id,
             event.clone(),
@@ -1608,6 +1695,39 @@ impl GitStore {
         &self.repositories
     }
 
+    /// Returns the repository checked out at the given submodule of `repository`, which only
+    /// exists once the submodule has been initialized and cloned.
+    pub fn submodule_repository(
+        &self,
+        repository: &RepositorySnapshot,
+        submodule: &Submodule,
+        cx: &App,
+    ) -> Option<Entity<Repository>> {
+        let abs_path = repository.repo_path_to_abs_path(&submodule.path);
+        self.repositories
+            .values()
+            .find(|repo| repo.read(cx).work_directory_abs_path.as_ref() == abs_path)
+            .cloned()
+    }
+
+    /// Returns the repository that contains `repository` as a submodule, if any.
+    pub fn superproject(
+        &self,
+        repository: &RepositorySnapshot,
+        cx: &App,
+    ) -> Option<Entity<Repository>> {
+        self.repositories
+            .values()
+            .find(|repo| {
+                let repo = repo.read(cx);
+                repo.id != repository.id
+                    && repo
+                        .abs_path_to_repo_path(&repository.work_directory_abs_path)
+                        .is_some_and(|path| repo.submodule(&path).is_some())
+            })
+            .cloned()
+    }
+
     pub fn status_for_buffer_id(&self, buffer_id: BufferId, cx: &App) -> Option<FileStatus> {
         let (repo, path) = self.repository_and_path_for_buffer_id(buffer_id, cx)?;
         let status = repo.read(cx).snapshot.status_for_path(&path)?;
@@ -1820,7 +1940,8 @@ impl GitStore {
     ) -> Result<proto::RemoteMessageResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let fetch_options = FetchOptions::from_proto(envelope.payload.remote);
+        let fetch_options =
+            FetchOptions::from_proto(envelope.payload.remote, envelope.payload.refspec)?;
         let askpass_id = envelope.payload.askpass_id;
 
         let askpass = make_remote_delegate(
@@ -2108,6 +2229,7 @@ impl GitStore {
                     CommitOptions {
                         amend: options.amend,
                         signoff: options.signoff,
+                        sign: options.sign,
                     },
                     askpass,
                     cx,
@@ -2314,130 +2436,121 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
-    async fn handle_remove_remote(
+    async fn handle_get_tags(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitRemoveRemote>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::GitTagsResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let remote_name = envelope.payload.remote_name;
 
-        repository_handle
+        let tags = repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.remove_remote(remote_name)
+                repository_handle.tags(envelope.payload.points_at)
             })?
             .await??;
 
-        Ok(proto::Ack {})
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
     }
 
-    async fn handle_show(
+    async fn handle_create_tag(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitShow>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitCommitDetails> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let commit = repository_handle
+        repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.show(envelope.payload.commit)
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.target,
+                    envelope.payload.message,
+                )
             })?
             .await??;
-        Ok(proto::GitCommitDetails {
-            sha: commit.sha.into(),
-            message: commit.message.into(),
-            commit_timestamp: commit.commit_timestamp,
-            author_email: commit.author_email.into(),
-            author_name: commit.author_name.into(),
-        })
+
+        Ok(proto::Ack {})
     }
 
-    async fn handle_load_commit_diff(
+    async fn handle_delete_tag(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::LoadCommitDiff>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
         mut cx: AsyncApp,
-    ) -> Result<proto::LoadCommitDiffResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let commit_diff = repository_handle
+        repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.load_commit_diff(envelope.payload.commit)
+                repository_handle.delete_tag(envelope.payload.name)
             })?
             .await??;
-        Ok(proto::LoadCommitDiffResponse {
-            files: commit_diff
-                .files
-                .into_iter()
-                .map(|file| proto::CommitFile {
-                    path: file.path.to_proto(),
-                    old_text: file.old_text,
-                    new_text: file.new_text,
-                })
-                .collect(),
-        })
+
+        Ok(proto::Ack {})
     }
 
-    async fn handle_file_history(
+    async fn handle_push_tag(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitFileHistory>,
+        envelope: TypedEnvelope<proto::GitPushTag>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitFileHistoryResponse> {
+    ) -> Result<proto::RemoteMessageResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let path = RepoPath::from_proto(&envelope.payload.path)?;
-        let skip = envelope.payload.skip as usize;
-        let limit = envelope.payload.limit.map(|l| l as usize);
 
-        let file_history = repository_handle
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.file_history_paginated(path, skip, limit)
+                repository_handle.push_tag(
+                    envelope.payload.name.into(),
+                    envelope.payload.remote_name.into(),
+                    askpass,
+                )
             })?
             .await??;
-
-        Ok(proto::GitFileHistoryResponse {
-            entries: file_history
-                .entries
-                .into_iter()
-                .map(|entry| proto::FileHistoryEntry {
-                    sha: entry.sha.to_string(),
-                    subject: entry.subject.to_string(),
-                    message: entry.message.to_string(),
-                    commit_timestamp: entry.commit_timestamp,
-                    author_name: entry.author_name.to_string(),
-                    author_email: entry.author_email.to_string(),
-                })
-                .collect(),
-            path: file_history.path.to_proto(),
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
         })
     }
 
-    async fn handle_reset(
+    async fn handle_init_submodules(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitReset>,
+        envelope: TypedEnvelope<proto::GitInitSubmodules>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-
-        let mode = match envelope.payload.mode() {
-            git_reset::ResetMode::Soft => ResetMode::Soft,
-            git_reset::ResetMode::Mixed => ResetMode::Mixed,
-        };
+        let paths = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(|path| RepoPath::new(&path))
+            .collect::<Result<Vec<_>>>()?;
 
         repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.reset(envelope.payload.commit, mode, cx)
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.init_submodules(paths)
             })?
             .await??;
+
         Ok(proto::Ack {})
     }
 
-    async fn handle_checkout_files(
+    async fn handle_sync_submodules(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitCheckoutFiles>,
+        envelope: TypedEnvelope<proto::GitSyncSubmodules>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
@@ -2445,117 +2558,549 @@ impl GitStore {
         let paths = envelope
             .payload
             .paths
-            .iter()
-            .map(|s| RepoPath::from_proto(s))
+            .into_iter()
+            .map(|path| RepoPath::new(&path))
             .collect::<Result<Vec<_>>>()?;
 
         repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.checkout_files(&envelope.payload.commit, paths, cx)
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.sync_submodules(paths)
             })?
-            .await?;
+            .await??;
+
         Ok(proto::Ack {})
     }
 
-    async fn handle_open_commit_message_buffer(
+    async fn handle_update_submodules(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        envelope: TypedEnvelope<proto::GitUpdateSubmodules>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenBufferResponse> {
+    ) -> Result<proto::RemoteMessageResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let buffer = repository
-            .update(&mut cx, |repository, cx| {
-                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
-            })?
-            .await?;
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(|path| RepoPath::new(&path))
+            .collect::<Result<Vec<_>>>()?;
 
-        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
-        this.update(&mut cx, |this, cx| {
-            this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store
-                    .create_buffer_for_peer(
-                        &buffer,
-                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
-                        cx,
-                    )
-                    .detach_and_log_err(cx);
-            })
-        })?;
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
 
-        Ok(proto::OpenBufferResponse {
-            buffer_id: buffer_id.to_proto(),
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.update_submodules(paths, askpass)
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
         })
     }
 
-    async fn handle_askpass(
+    async fn handle_bisect_start(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::AskPassRequest>,
+        envelope: TypedEnvelope<proto::GitBisectStart>,
         mut cx: AsyncApp,
-    ) -> Result<proto::AskPassResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-
-        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
-        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
-            debug_panic!("no askpass found");
-            anyhow::bail!("no askpass found");
-        };
-
-        let response = askpass
-            .ask_password(envelope.payload.prompt)
-            .await
-            .ok_or_else(|| anyhow::anyhow!("askpass cancelled"))?;
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        delegates
-            .lock()
-            .insert(envelope.payload.askpass_id, askpass);
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_start(envelope.payload.bad, envelope.payload.good)
+            })?
+            .await??;
 
-        // In fact, we don't quite know what we're doing here, as we're sending askpass password unencrypted, but..
-        Ok(proto::AskPassResponse {
-            response: response.decrypt(IKnowWhatIAmDoingAndIHaveReadTheDocs)?,
-        })
+        Ok(proto::Ack {})
     }
 
-    async fn handle_check_for_pushed_commits(
+    async fn handle_bisect_mark(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
         mut cx: AsyncApp,
-    ) -> Result<proto::CheckForPushedCommitsResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let mark = match envelope.payload.mark() {
+            proto::git_bisect_mark::Mark::Good => BisectMark::Good,
+            proto::git_bisect_mark::Mark::Bad => BisectMark::Bad,
+            proto::git_bisect_mark::Mark::Skip => BisectMark::Skip,
+        };
 
-        let branches = repository_handle
+        repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.check_for_pushed_commits()
+                repository_handle.bisect_mark(mark, envelope.payload.commit)
             })?
             .await??;
-        Ok(proto::CheckForPushedCommitsResponse {
-            pushed_to: branches
-                .into_iter()
-                .map(|commit| commit.to_string())
-                .collect(),
-        })
+
+        Ok(proto::Ack {})
     }
 
-    async fn handle_git_diff(
+    async fn handle_bisect_reset(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitDiff>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitDiffResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let diff_type = match envelope.payload.diff_type() {
-            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
-            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
-        };
 
-        let mut diff = repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.diff(diff_type, cx)
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_reset()
             })?
             .await??;
-        const ONE_MB: usize = 1_000_000;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_remove_remote(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveRemote>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let remote_name = envelope.payload.remote_name;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.remove_remote(remote_name)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_show(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitShow>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitDetails> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let commit = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.show(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(commit_details_to_proto(&commit))
+    }
+
+    async fn handle_get_commit_signature(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetCommitSignature>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitSignatureResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let signature = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_signature(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::GitCommitSignatureResponse {
+            signature: signature.as_ref().map(commit_signature_to_proto),
+        })
+    }
+
+    async fn handle_load_commit_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadCommitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let commit_diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_commit_diff(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::LoadCommitDiffResponse {
+            files: commit_diff
+                .files
+                .into_iter()
+                .map(|file| proto::CommitFile {
+                    path: file.path.to_proto(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_load_conflict_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadConflictStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadConflictStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_conflict_stages(path)
+            })?
+            .await??;
+
+        Ok(proto::GitLoadConflictStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
+    async fn handle_file_history(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitFileHistory>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitFileHistoryResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+        let skip = envelope.payload.skip as usize;
+        let limit = envelope.payload.limit.map(|l| l as usize);
+
+        let file_history = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.file_history_paginated(path, skip, limit)
+            })?
+            .await??;
+
+        Ok(proto::GitFileHistoryResponse {
+            entries: file_history
+                .entries
+                .into_iter()
+                .map(|entry| proto::FileHistoryEntry {
+                    sha: entry.sha.to_string(),
+                    subject: entry.subject.to_string(),
+                    message: entry.message.to_string(),
+                    commit_timestamp: entry.commit_timestamp,
+                    author_name: entry.author_name.to_string(),
+                    author_email: entry.author_email.to_string(),
+                })
+                .collect(),
+            path: file_history.path.to_proto(),
+        })
+    }
+
+    async fn handle_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let search = envelope.payload.search.map(|search| match search {
+            proto::git_log::Search::Author(author) => LogSearch::Author(author),
+            proto::git_log::Search::Message(message) => LogSearch::Message(message),
+            proto::git_log::Search::Sha(sha) => LogSearch::Sha(sha),
+        });
+        let skip = envelope.payload.skip as usize;
+        let limit = envelope.payload.limit.map(|l| l as usize);
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(search, skip, limit)
+            })?
+            .await??;
+
+        Ok(proto::GitLogResponse {
+            entries: entries.iter().map(log_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let mode = match envelope.payload.mode() {
+            git_reset::ResetMode::Soft => ResetMode::Soft,
+            git_reset::ResetMode::Mixed => ResetMode::Mixed,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.reset(envelope.payload.commit, mode, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.upstream)
+            })?
+            .await??;
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_rebase_start(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseStart>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .entries
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect::<Result<_>>()?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_start(envelope.payload.upstream, todo)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_edit_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseEditTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .entries
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect::<Result<_>>()?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_edit_todo(todo)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_control(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseControl>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                match envelope.payload.operation() {
+                    proto::git_rebase_control::Operation::Continue => {
+                        repository_handle.rebase_continue()
+                    }
+                    proto::git_rebase_control::Operation::Skip => repository_handle.rebase_skip(),
+                    proto::git_rebase_control::Operation::Abort => repository_handle.rebase_abort(),
+                }
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_apply_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitApplyCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSequencerResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let command = proto_to_sequencer_command(envelope.payload.command());
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.apply_commits(
+                    command,
+                    envelope.payload.commits,
+                    envelope.payload.no_commit,
+                )
+            })?
+            .await??;
+        Ok(proto::GitSequencerResponse {
+            conflicted: outcome == SequencerOutcome::Conflicted,
+        })
+    }
+
+    async fn handle_sequencer_control(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSequencerControl>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSequencerResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let command = proto_to_sequencer_command(envelope.payload.command());
+        let control = match envelope.payload.control() {
+            proto::git_sequencer_control::Control::Continue => SequencerControl::Continue,
+            proto::git_sequencer_control::Control::Skip => SequencerControl::Skip,
+            proto::git_sequencer_control::Control::Abort => SequencerControl::Abort,
+        };
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.sequencer_control(command, control)
+            })?
+            .await??;
+        Ok(proto::GitSequencerResponse {
+            conflicted: outcome == SequencerOutcome::Conflicted,
+        })
+    }
+
+    async fn handle_checkout_files(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCheckoutFiles>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_proto(s))
+            .collect::<Result<Vec<_>>>()?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.checkout_files(&envelope.payload.commit, paths, cx)
+            })?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_open_commit_message_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenBufferResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let buffer = repository
+            .update(&mut cx, |repository, cx| {
+                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
+            })?
+            .await?;
+
+        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
+        this.update(&mut cx, |this, cx| {
+            this.buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store
+                    .create_buffer_for_peer(
+                        &buffer,
+                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
+                        cx,
+                    )
+                    .detach_and_log_err(cx);
+            })
+        })?;
+
+        Ok(proto::OpenBufferResponse {
+            buffer_id: buffer_id.to_proto(),
+        })
+    }
+
+    async fn handle_askpass(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::AskPassRequest>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::AskPassResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
+        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
+            debug_panic!("no askpass found");
+            anyhow::bail!("no askpass found");
+        };
+
+        let response = askpass
+            .ask_password(envelope.payload.prompt)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("askpass cancelled"))?;
+
+        delegates
+            .lock()
+            .insert(envelope.payload.askpass_id, askpass);
+
+        // In fact, we don't quite know what we're doing here, as we're sending askpass password unencrypted, but..
+        Ok(proto::AskPassResponse {
+            response: response.decrypt(IKnowWhatIAmDoingAndIHaveReadTheDocs)?,
+        })
+    }
+
+    async fn handle_check_for_pushed_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::CheckForPushedCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let branches = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.check_for_pushed_commits()
+            })?
+            .await??;
+        Ok(proto::CheckForPushedCommitsResponse {
+            pushed_to: branches
+                .into_iter()
+                .map(|commit| commit.to_string())
+                .collect(),
+        })
+    }
+
+    async fn handle_git_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let diff_type = match envelope.payload.diff_type() {
+            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
+            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
+            proto::git_diff::DiffType::MergeBaseToWorktree => DiffType::MergeBaseToWorktree {
+                base: envelope
+                    .payload
+                    .base
+                    .context("missing base for merge base diff")?
+                    .into(),
+            },
+        };
+
+        let mut diff = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.diff(diff_type, cx)
+            })?
+            .await??;
+        const ONE_MB: usize = 1_000_000;
         if diff.len() > ONE_MB {
             diff = diff.chars().take(ONE_MB).collect()
         }
@@ -3264,6 +3809,9 @@ impl RepositorySnapshot {
             remote_origin_url: None,
             remote_upstream_url: None,
             stash_entries: Default::default(),
+            rebase: None,
+            submodules: Vec::new().into(),
+            bisect: None,
             path_style,
         }
     }
@@ -3299,6 +3847,13 @@ impl RepositorySnapshot {
                 .collect(),
             remote_upstream_url: self.remote_upstream_url.clone(),
             remote_origin_url: self.remote_origin_url.clone(),
+            rebase_status: self.rebase.as_ref().map(rebase_status_to_proto),
+            sequencer_command: self
+                .merge
+                .sequencer
+                .map(|command| sequencer_command_to_proto(command).into()),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
+            bisect_status: self.bisect.as_ref().map(bisect_status_to_proto),
         }
     }
 
@@ -3370,6 +3925,13 @@ impl RepositorySnapshot {
                 .collect(),
             remote_upstream_url: self.remote_upstream_url.clone(),
             remote_origin_url: self.remote_origin_url.clone(),
+            rebase_status: self.rebase.as_ref().map(rebase_status_to_proto),
+            sequencer_command: self
+                .merge
+                .sequencer
+                .map(|command| sequencer_command_to_proto(command).into()),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
+            bisect_status: self.bisect.as_ref().map(bisect_status_to_proto),
         }
     }
 
@@ -3381,6 +3943,12 @@ impl RepositorySnapshot {
         self.statuses_by_path.summary().item_summary
     }
 
+    pub fn submodule(&self, path: &RepoPath) -> Option<&Submodule> {
+        self.submodules
+            .iter()
+            .find(|submodule| &submodule.path == path)
+    }
+
     pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
         self.statuses_by_path
             .get(&PathKey(path.as_ref().clone()), ())
@@ -3506,10 +4074,18 @@ impl MergeDetails {
         } else {
             prev_snapshot.merge.conflicted_paths.clone()
         };
+        let sequencer = if heads.get(1).is_some_and(Option::is_some) {
+            Some(SequencerCommand::CherryPick)
+        } else if heads.get(3).is_some_and(Option::is_some) {
+            Some(SequencerCommand::Revert)
+        } else {
+            None
+        };
         let details = MergeDetails {
             conflicted_paths,
             message: message.map(SharedString::from),
             heads,
+            sequencer,
         };
         Ok((details, merge_heads_changed))
     }
@@ -3579,6 +4155,8 @@ impl Repository {
             job_sender,
             job_id: 0,
             active_jobs: Default::default(),
+            pull_request: None,
+            pull_request_task: Task::ready(()),
         }
     }
 
@@ -3608,6 +4186,8 @@ impl Repository {
             latest_askpass_id: 0,
             active_jobs: Default::default(),
             job_id: 0,
+            pull_request: None,
+            pull_request_task: Task::ready(()),
         }
     }
 
@@ -4057,6 +4637,252 @@ impl Repository {
         })
     }
 
+    pub fn rebase_todo(
+        &mut self,
+        upstream: String,
+    ) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.rebase_todo(upstream).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            upstream,
+                        })
+                        .await?;
+
+                    response
+                        .entries
+                        .iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect()
+                }
+            }
+        })
+    }
+
+    pub fn rebase_start(
+        &mut self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git rebase --interactive {upstream}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.rebase_start(upstream, todo, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRebaseStart {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                upstream,
+                                entries: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn rebase_edit_todo(
+        &mut self,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.rebase_edit_todo(todo).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitRebaseEditTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            entries: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn cherry_pick(
+        &mut self,
+        commits: Vec<String>,
+        no_commit: bool,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        self.apply_commits(SequencerCommand::CherryPick, commits, no_commit)
+    }
+
+    pub fn revert(
+        &mut self,
+        commits: Vec<String>,
+        no_commit: bool,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        self.apply_commits(SequencerCommand::Revert, commits, no_commit)
+    }
+
+    fn apply_commits(
+        &mut self,
+        command: SequencerCommand,
+        commits: Vec<String>,
+        no_commit: bool,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        let id = self.id;
+        let job_status = format!(
+            "git {}{} {}",
+            command.as_str(),
+            if no_commit { " --no-commit" } else { "" },
+            commits.join(" ")
+        );
+        self.send_job(Some(job_status.into()), move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => match command {
+                    SequencerCommand::CherryPick => {
+                        backend.cherry_pick(commits, no_commit, environment).await
+                    }
+                    SequencerCommand::Revert => {
+                        backend.revert(commits, no_commit, environment).await
+                    }
+                },
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitApplyCommits {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            command: sequencer_command_to_proto(command).into(),
+                            commits,
+                            no_commit,
+                        })
+                        .await?;
+
+                    Ok(proto_to_sequencer_outcome(&response))
+                }
+            }
+        })
+    }
+
+    /// Continues, skips the current commit of, or aborts a cherry-pick or revert that stopped midway.
+    pub fn sequencer_control(
+        &mut self,
+        command: SequencerCommand,
+        control: SequencerControl,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        let id = self.id;
+        let flag = match control {
+            SequencerControl::Continue => "--continue",
+            SequencerControl::Skip => "--skip",
+            SequencerControl::Abort => "--abort",
+        };
+        self.send_job(
+            Some(format!("git {} {flag}", command.as_str()).into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .sequencer_control(command, control, environment)
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        use proto::git_sequencer_control::Control;
+                        let response = client
+                            .request(proto::GitSequencerControl {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                command: sequencer_command_to_proto(command).into(),
+                                control: match control {
+                                    SequencerControl::Continue => Control::Continue,
+                                    SequencerControl::Skip => Control::Skip,
+                                    SequencerControl::Abort => Control::Abort,
+                                }
+                                .into(),
+                            })
+                            .await?;
+
+                        Ok(proto_to_sequencer_outcome(&response))
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn rebase_continue(&mut self) -> oneshot::Receiver<Result<()>> {
+        self.rebase_control(proto::git_rebase_control::Operation::Continue)
+    }
+
+    pub fn rebase_skip(&mut self) -> oneshot::Receiver<Result<()>> {
+        self.rebase_control(proto::git_rebase_control::Operation::Skip)
+    }
+
+    pub fn rebase_abort(&mut self) -> oneshot::Receiver<Result<()>> {
+        self.rebase_control(proto::git_rebase_control::Operation::Abort)
+    }
+
+    fn rebase_control(
+        &mut self,
+        operation: proto::git_rebase_control::Operation,
+    ) -> oneshot::Receiver<Result<()>> {
+        use proto::git_rebase_control::Operation;
+        let id = self.id;
+        let flag = match operation {
+            Operation::Continue => "--continue",
+            Operation::Skip => "--skip",
+            Operation::Abort => "--abort",
+        };
+        self.send_job(
+            Some(format!("git rebase {flag}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => match operation {
+                        Operation::Continue => backend.rebase_continue(environment).await,
+                        Operation::Skip => backend.rebase_skip(environment).await,
+                        Operation::Abort => backend.rebase_abort(environment).await,
+                    },
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRebaseControl {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                operation: operation.into(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
         let id = self.id;
         self.send_job(None, move |git_repo, _cx| async move {
@@ -4069,16 +4895,65 @@ impl Repository {
                         .request(proto::GitShow {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(proto_to_commit_details(&resp))
+                }
+            }
+        })
+    }
+
+    /// Verifies the signature of `commit`, which is slower than [`Repository::show`].
+    pub fn commit_signature(
+        &mut self,
+        commit: String,
+    ) -> oneshot::Receiver<Result<Option<CommitSignature>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.commit_signature(commit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetCommitSignature {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(response.signature.as_ref().map(proto_to_commit_signature))
+                }
+            }
+        })
+    }
+
+    pub fn load_conflict_stages(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictStages>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_conflict_stages(path).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitLoadConflictStages {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
                         })
                         .await?;
 
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        author_email: resp.author_email.into(),
-                        author_name: resp.author_name.into(),
+                    Ok(ConflictStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
                     })
                 }
             }
@@ -4169,6 +5044,40 @@ impl Repository {
         })
     }
 
+    pub fn log(
+        &mut self,
+        search: Option<LogSearch>,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> oneshot::Receiver<Result<Vec<LogEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.log(search, skip, limit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            search: search.map(|search| match search {
+                                LogSearch::Author(author) => proto::git_log::Search::Author(author),
+                                LogSearch::Message(message) => {
+                                    proto::git_log::Search::Message(message)
+                                }
+                                LogSearch::Sha(sha) => proto::git_log::Search::Sha(sha),
+                            }),
+                            skip: skip as u64,
+                            limit: limit.map(|l| l as u64),
+                        })
+                        .await?;
+                    Ok(response.entries.iter().map(proto_to_log_entry).collect())
+                }
+            }
+        })
+    }
+
     fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
         Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
     }
@@ -4680,6 +5589,7 @@ impl Repository {
                             options: Some(proto::commit::CommitOptions {
                                 amend: options.amend,
                                 signoff: options.signoff,
+                                sign: options.sign,
                             }),
                             askpass_id,
                         })
@@ -4716,12 +5626,14 @@ impl Repository {
                         debug_assert!(askpass_delegate.is_some());
                     });
 
+                    let (remote, refspec) = fetch_options.to_proto();
                     let response = client
                         .request(proto::Fetch {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
                             askpass_id,
-                            remote: fetch_options.to_proto(),
+                            remote,
+                            refspec,
                         })
                         .await
                         .context("sending fetch request")?;
@@ -5242,87 +6154,427 @@ impl Repository {
                         })
                         .collect();
 
-                    Ok(TreeDiff { entries })
+                    Ok(TreeDiff { entries })
+                }
+            }
+        })
+    }
+
+    pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.diff(diff_type).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let (diff_type, base) = match diff_type {
+                        DiffType::HeadToIndex => (proto::git_diff::DiffType::HeadToIndex, None),
+                        DiffType::HeadToWorktree => {
+                            (proto::git_diff::DiffType::HeadToWorktree, None)
+                        }
+                        DiffType::MergeBaseToWorktree { base } => (
+                            proto::git_diff::DiffType::MergeBaseToWorktree,
+                            Some(base.to_string()),
+                        ),
+                    };
+                    let response = client
+                        .request(proto::GitDiff {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            diff_type: diff_type.into(),
+                            base,
+                        })
+                        .await?;
+
+                    Ok(response.diff)
+                }
+            }
+        })
+    }
+
+    pub fn create_branch(
+        &mut self,
+        branch_name: String,
+        base_branch: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let status_msg = if let Some(ref base) = base_branch {
+            format!("git switch -c {branch_name} {base}").into()
+        } else {
+            format!("git switch -c {branch_name}").into()
+        };
+        self.send_job(Some(status_msg), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.create_branch(branch_name, base_branch).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitCreateBranch {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch_name,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn change_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git switch {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.change_branch(branch_name).await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitChangeBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch_name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn delete_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git branch -d {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(state) => state.backend.delete_branch(branch_name).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch_name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Returns the repository's tags, or only those pointing at the commit `points_at`.
+    pub fn tags(&mut self, points_at: Option<String>) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.tags(points_at).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            points_at,
+                        })
+                        .await?;
+
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.create_tag(name, target, message, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                target,
+                                message,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag -d {name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(state) => state.backend.delete_tag(name).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn push_tag(
+        &mut self,
+        name: SharedString,
+        remote: SharedString,
+        askpass: AskPassDelegate,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            Some(format!("git push {remote} refs/tags/{name}").into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .push_tag(
+                                name.to_string(),
+                                remote.to_string(),
+                                askpass,
+                                environment.clone(),
+                                cx.clone(),
+                            )
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitPushTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name: name.to_string(),
+                                remote_name: remote.to_string(),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending push tag request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    /// Initializes the given submodules, or all of them when `paths` is empty.
+    pub fn init_submodules(&mut self, paths: Vec<RepoPath>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(submodule_job_description("init", &paths).into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.init_submodules(paths, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitInitSubmodules {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
                 }
-            }
-        })
+            },
+        )
     }
 
-    pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
+    /// Synchronizes the remote URLs of the given submodules, or all of them when `paths` is
+    /// empty, with `.gitmodules`.
+    pub fn sync_submodules(&mut self, paths: Vec<RepoPath>) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |repo, _cx| async move {
-            match repo {
-                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                    backend.diff(diff_type).await
+        self.send_job(
+            Some(submodule_job_description("sync", &paths).into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.sync_submodules(paths, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitSyncSubmodules {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
                 }
-                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
-                    let response = client
-                        .request(proto::GitDiff {
-                            project_id: project_id.0,
-                            repository_id: id.to_proto(),
-                            diff_type: match diff_type {
-                                DiffType::HeadToIndex => {
-                                    proto::git_diff::DiffType::HeadToIndex.into()
-                                }
-                                DiffType::HeadToWorktree => {
-                                    proto::git_diff::DiffType::HeadToWorktree.into()
-                                }
-                            },
-                        })
-                        .await?;
+            },
+        )
+    }
 
-                    Ok(response.diff)
+    /// Checks out the recorded commit of the given submodules, or all of them when `paths` is
+    /// empty, cloning them first if necessary.
+    pub fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            Some(submodule_job_description("update --init", &paths).into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .update_submodules(paths, askpass, environment.clone(), cx.clone())
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitUpdateSubmodules {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                paths: paths.iter().map(|path| path.to_proto()).collect(),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending update submodules request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
                 }
-            }
-        })
+            },
+        )
     }
 
-    pub fn create_branch(
+    pub fn bisect_start(
         &mut self,
-        branch_name: String,
-        base_branch: Option<String>,
+        bad: String,
+        good: Vec<String>,
     ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        let status_msg = if let Some(ref base) = base_branch {
-            format!("git switch -c {branch_name} {base}").into()
-        } else {
-            format!("git switch -c {branch_name}").into()
-        };
-        self.send_job(Some(status_msg), move |repo, _cx| async move {
-            match repo {
-                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                    backend.create_branch(branch_name, base_branch).await
-                }
-                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
-                    client
-                        .request(proto::GitCreateBranch {
-                            project_id: project_id.0,
-                            repository_id: id.to_proto(),
-                            branch_name,
-                        })
-                        .await?;
+        self.send_job(
+            Some(format!("git bisect start {bad} {}", good.join(" ")).into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_start(bad, good, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectStart {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                bad,
+                                good,
+                            })
+                            .await?;
 
-                    Ok(())
+                        Ok(())
+                    }
                 }
-            }
-        })
+            },
+        )
     }
 
-    pub fn change_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+    /// Marks the given commit, or the checked-out one when `commit` is `None`.
+    pub fn bisect_mark(
+        &mut self,
+        mark: BisectMark,
+        commit: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
         self.send_job(
-            Some(format!("git switch {branch_name}").into()),
+            Some(format!("git bisect {}", mark.as_str()).into()),
             move |repo, _cx| async move {
                 match repo {
-                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                        backend.change_branch(branch_name).await
-                    }
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_mark(mark, commit, environment).await,
                     RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let mark = match mark {
+                            BisectMark::Good => proto::git_bisect_mark::Mark::Good,
+                            BisectMark::Bad => proto::git_bisect_mark::Mark::Bad,
+                            BisectMark::Skip => proto::git_bisect_mark::Mark::Skip,
+                        };
                         client
-                            .request(proto::GitChangeBranch {
+                            .request(proto::GitBisectMark {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                branch_name,
+                                mark: mark.into(),
+                                commit,
                             })
                             .await?;
 
@@ -5333,19 +6585,22 @@ impl Repository {
         )
     }
 
-    pub fn delete_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+    pub fn bisect_reset(&mut self) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
         self.send_job(
-            Some(format!("git branch -d {branch_name}").into()),
+            Some("git bisect reset".into()),
             move |repo, _cx| async move {
                 match repo {
-                    RepositoryState::Local(state) => state.backend.delete_branch(branch_name).await,
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_reset(environment).await,
                     RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
                         client
-                            .request(proto::GitDeleteBranch {
+                            .request(proto::GitBisectReset {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                branch_name,
                             })
                             .await?;
 
@@ -5356,6 +6611,37 @@ impl Repository {
         )
     }
 
+    /// Runs `git bisect run` with a shell command, with `env` added to the repository's
+    /// environment, and returns its output.
+    ///
+    /// The run can take as long as the command takes on every candidate, so it doesn't wait
+    /// behind other git jobs and is stopped when the returned task is dropped. The command
+    /// only runs on the machine that owns the repository, never on behalf of collaborators.
+    pub fn bisect_run(
+        &mut self,
+        command: String,
+        env: HashMap<String, String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<String>> {
+        let repository_state = self.repository_state.clone();
+        cx.spawn(async move |_, _| {
+            match repository_state.await.map_err(|error| anyhow!(error))? {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => {
+                    let mut environment = HashMap::clone(&environment);
+                    environment.extend(env);
+                    backend.bisect_run(command, Arc::new(environment)).await
+                }
+                RepositoryState::Remote(_) => {
+                    bail!("Bisect tasks can only be run in local repositories")
+                }
+            }
+        })
+    }
+
     pub fn rename_branch(
         &mut self,
         branch: String,
@@ -5458,6 +6744,14 @@ impl Repository {
 
         self.snapshot.merge.conflicted_paths = conflicted_paths;
         self.snapshot.merge.message = update.merge_message.map(SharedString::from);
+        let new_sequencer = update
+            .sequencer_command
+            .and_then(proto::GitSequencerCommand::from_i32)
+            .map(proto_to_sequencer_command);
+        if self.snapshot.merge.sequencer != new_sequencer {
+            cx.emit(RepositoryEvent::MergeHeadsChanged)
+        }
+        self.snapshot.merge.sequencer = new_sequencer;
         let new_stash_entries = GitStash {
             entries: update
                 .stash_entries
@@ -5469,8 +6763,32 @@ impl Repository {
             cx.emit(RepositoryEvent::StashEntriesChanged)
         }
         self.snapshot.stash_entries = new_stash_entries;
+        if self.snapshot.remote_upstream_url != update.remote_upstream_url
+            || self.snapshot.remote_origin_url != update.remote_origin_url
+        {
+            cx.emit(RepositoryEvent::RemotesChanged)
+        }
         self.snapshot.remote_upstream_url = update.remote_upstream_url;
         self.snapshot.remote_origin_url = update.remote_origin_url;
+        let new_rebase = update.rebase_status.as_ref().map(proto_to_rebase_status);
+        if self.snapshot.rebase != new_rebase {
+            cx.emit(RepositoryEvent::RebaseStatusChanged)
+        }
+        self.snapshot.rebase = new_rebase;
+        let new_submodules: Arc<[Submodule]> = update
+            .submodules
+            .iter()
+            .filter_map(|submodule| proto_to_submodule(submodule).log_err())
+            .collect();
+        if self.snapshot.submodules != new_submodules {
+            cx.emit(RepositoryEvent::SubmodulesChanged)
+        }
+        self.snapshot.submodules = new_submodules;
+        let new_bisect = update.bisect_status.as_ref().map(proto_to_bisect_status);
+        if self.snapshot.bisect != new_bisect {
+            cx.emit(RepositoryEvent::BisectStatusChanged)
+        }
+        self.snapshot.bisect = new_bisect;
 
         let edits = update
             .removed_statuses
@@ -6174,6 +7492,294 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn commit_signature_to_proto(signature: &CommitSignature) -> proto::GitCommitSignature {
+    use proto::git_commit_signature::Status;
+    let status = match signature.status {
+        SignatureStatus::Good => Status::Good,
+        SignatureStatus::UnknownValidity => Status::UnknownValidity,
+        SignatureStatus::ExpiredSignature => Status::ExpiredSignature,
+        SignatureStatus::ExpiredKey => Status::ExpiredKey,
+        SignatureStatus::RevokedKey => Status::RevokedKey,
+        SignatureStatus::Bad => Status::Bad,
+        SignatureStatus::CannotCheck => Status::CannotCheck,
+    };
+    proto::GitCommitSignature {
+        status: status.into(),
+        signer: signature.signer.as_ref().map(|signer| signer.to_string()),
+        key: signature.key.as_ref().map(|key| key.to_string()),
+    }
+}
+
+fn proto_to_commit_signature(signature: &proto::GitCommitSignature) -> CommitSignature {
+    use proto::git_commit_signature::Status;
+    CommitSignature {
+        status: match signature.status() {
+            Status::Good => SignatureStatus::Good,
+            Status::UnknownValidity => SignatureStatus::UnknownValidity,
+            Status::ExpiredSignature => SignatureStatus::ExpiredSignature,
+            Status::ExpiredKey => SignatureStatus::ExpiredKey,
+            Status::RevokedKey => SignatureStatus::RevokedKey,
+            Status::Bad => SignatureStatus::Bad,
+            Status::CannotCheck => SignatureStatus::CannotCheck,
+        },
+        signer: signature.signer.clone().map(SharedString::from),
+        key: signature.key.clone().map(SharedString::from),
+    }
+}
+
+fn log_entry_to_proto(entry: &LogEntry) -> proto::GitLogEntry {
+    use proto::git_log_ref::Kind;
+    proto::GitLogEntry {
+        sha: entry.sha.to_string(),
+        parents: entry
+            .parents
+            .iter()
+            .map(|parent| parent.to_string())
+            .collect(),
+        subject: entry.subject.to_string(),
+        commit_timestamp: entry.commit_timestamp,
+        author_name: entry.author_name.to_string(),
+        author_email: entry.author_email.to_string(),
+        refs: entry
+            .refs
+            .iter()
+            .map(|log_ref| {
+                let kind = match log_ref.kind {
+                    LogRefKind::Head => Kind::Head,
+                    LogRefKind::LocalBranch => Kind::LocalBranch,
+                    LogRefKind::RemoteBranch => Kind::RemoteBranch,
+                    LogRefKind::Tag => Kind::Tag,
+                };
+                proto::GitLogRef {
+                    kind: kind.into(),
+                    name: log_ref.name.to_string(),
+                }
+            })
+            .collect(),
+    }
+}
+
+fn proto_to_log_entry(entry: &proto::GitLogEntry) -> LogEntry {
+    use proto::git_log_ref::Kind;
+    LogEntry {
+        sha: entry.sha.clone().into(),
+        parents: entry
+            .parents
+            .iter()
+            .map(|parent| parent.clone().into())
+            .collect(),
+        subject: entry.subject.clone().into(),
+        commit_timestamp: entry.commit_timestamp,
+        author_name: entry.author_name.clone().into(),
+        author_email: entry.author_email.clone().into(),
+        refs: entry
+            .refs
+            .iter()
+            .map(|log_ref| LogRef {
+                kind: match log_ref.kind() {
+                    Kind::Head => LogRefKind::Head,
+                    Kind::LocalBranch => LogRefKind::LocalBranch,
+                    Kind::RemoteBranch => LogRefKind::RemoteBranch,
+                    Kind::Tag => LogRefKind::Tag,
+                },
+                name: log_ref.name.clone().into(),
+            })
+            .collect(),
+    }
+}
+
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(|message| message.to_string()),
+    }
+}
+
+fn proto_to_tag(tag: &proto::GitTag) -> Tag {
+    Tag {
+        name: tag.name.clone().into(),
+        sha: tag.sha.clone().into(),
+        message: tag.message.clone().map(SharedString::from),
+    }
+}
+
+fn sequencer_command_to_proto(command: SequencerCommand) -> proto::GitSequencerCommand {
+    match command {
+        SequencerCommand::CherryPick => proto::GitSequencerCommand::CherryPick,
+        SequencerCommand::Revert => proto::GitSequencerCommand::Revert,
+    }
+}
+
+fn proto_to_sequencer_command(command: proto::GitSequencerCommand) -> SequencerCommand {
+    match command {
+        proto::GitSequencerCommand::CherryPick => SequencerCommand::CherryPick,
+        proto::GitSequencerCommand::Revert => SequencerCommand::Revert,
+    }
+}
+
+fn proto_to_sequencer_outcome(response: &proto::GitSequencerResponse) -> SequencerOutcome {
+    if response.conflicted {
+        SequencerOutcome::Conflicted
+    } else {
+        SequencerOutcome::Completed
+    }
+}
+
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::GitRebaseTodoEntry {
+    use proto::git_rebase_todo_entry::Action;
+    let action = match entry.action {
+        RebaseTodoAction::Pick => Action::Pick,
+        RebaseTodoAction::Reword => Action::Reword,
+        RebaseTodoAction::Edit => Action::Edit,
+        RebaseTodoAction::Squash => Action::Squash,
+        RebaseTodoAction::Fixup => Action::Fixup,
+        RebaseTodoAction::Drop => Action::Drop,
+    };
+    proto::GitRebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        message: entry.message.as_ref().map(|message| message.to_string()),
+    }
+}
+
+/// Reads a todo entry sent by a peer, rejecting ones that could add commands to the todo script.
+fn proto_to_rebase_todo_entry(entry: &proto::GitRebaseTodoEntry) -> Result<RebaseTodoEntry> {
+    use proto::git_rebase_todo_entry::Action;
+    let action = match entry.action() {
+        Action::Pick => RebaseTodoAction::Pick,
+        Action::Reword => RebaseTodoAction::Reword,
+        Action::Edit => RebaseTodoAction::Edit,
+        Action::Squash => RebaseTodoAction::Squash,
+        Action::Fixup => RebaseTodoAction::Fixup,
+        Action::Drop => RebaseTodoAction::Drop,
+    };
+    let entry = RebaseTodoEntry {
+        action,
+        sha: entry.sha.clone().into(),
+        subject: entry.subject.clone().into(),
+        message: entry.message.clone().map(SharedString::from),
+    };
+    entry.validate()?;
+    Ok(entry)
+}
+
+fn rebase_status_to_proto(status: &RebaseStatus) -> proto::GitRebaseStatus {
+    proto::GitRebaseStatus {
+        head_name: status.head_name.as_ref().map(|name| name.to_string()),
+        onto: status.onto.as_ref().map(|onto| onto.to_string()),
+        stopped_sha: status.stopped_sha.as_ref().map(|sha| sha.to_string()),
+        done: status.done.iter().map(rebase_todo_entry_to_proto).collect(),
+        remaining: status
+            .remaining
+            .iter()
+            .map(rebase_todo_entry_to_proto)
+            .collect(),
+        interactive: status.interactive,
+    }
+}
+
+fn proto_to_rebase_status(proto: &proto::GitRebaseStatus) -> RebaseStatus {
+    RebaseStatus {
+        head_name: proto.head_name.clone().map(SharedString::from),
+        onto: proto.onto.clone().map(SharedString::from),
+        stopped_sha: proto.stopped_sha.clone().map(SharedString::from),
+        done: proto
+            .done
+            .iter()
+            .filter_map(|entry| proto_to_rebase_todo_entry(entry).log_err())
+            .collect(),
+        remaining: proto
+            .remaining
+            .iter()
+            .filter_map(|entry| proto_to_rebase_todo_entry(entry).log_err())
+            .collect(),
+        interactive: proto.interactive,
+    }
+}
+
+fn bisect_status_to_proto(status: &BisectStatus) -> proto::GitBisectStatus {
+    proto::GitBisectStatus {
+        start: status.start.as_ref().map(|start| start.to_string()),
+        bad: status.bad.as_ref().map(|sha| sha.to_string()),
+        good: status.good.iter().map(|sha| sha.to_string()).collect(),
+        skipped: status.skipped.iter().map(|sha| sha.to_string()).collect(),
+        current: status.current.as_ref().map(|sha| sha.to_string()),
+        remaining_commits: status.remaining.map(|remaining| remaining.commits),
+        remaining_steps: status.remaining.map(|remaining| remaining.steps),
+        first_bad: status.first_bad.as_ref().map(|sha| sha.to_string()),
+    }
+}
+
+fn proto_to_bisect_status(proto: &proto::GitBisectStatus) -> BisectStatus {
+    BisectStatus {
+        start: proto.start.clone().map(SharedString::from),
+        bad: proto.bad.clone().map(SharedString::from),
+        good: proto.good.iter().cloned().map(SharedString::from).collect(),
+        skipped: proto
+            .skipped
+            .iter()
+            .cloned()
+            .map(SharedString::from)
+            .collect(),
+        current: proto.current.clone().map(SharedString::from),
+        remaining: proto
+            .remaining_commits
+            .zip(proto.remaining_steps)
+            .map(|(commits, steps)| BisectRemaining { commits, steps }),
+        first_bad: proto.first_bad.clone().map(SharedString::from),
+    }
+}
+
+fn submodule_job_description(command: &str, paths: &[RepoPath]) -> String {
+    let mut description = format!("git submodule {command}");
+    for path in paths {
+        description.push(' ');
+        description.push_str(path.as_unix_str());
+    }
+    description
+}
+
+fn submodule_to_proto(submodule: &Submodule) -> proto::GitSubmodule {
+    use proto::git_submodule::Status;
+    let status = match submodule.status {
+        SubmoduleStatus::Uninitialized => Status::Uninitialized,
+        SubmoduleStatus::UpToDate => Status::UpToDate,
+        SubmoduleStatus::OutOfDate => Status::OutOfDate,
+        SubmoduleStatus::Conflicted => Status::Conflicted,
+    };
+    proto::GitSubmodule {
+        name: submodule.name.to_string(),
+        path: submodule.path.to_proto(),
+        url: submodule.url.as_ref().map(|url| url.to_string()),
+        recorded_sha: submodule.recorded_sha.to_string(),
+        checked_out_sha: submodule
+            .checked_out_sha
+            .as_ref()
+            .map(|sha| sha.to_string()),
+        status: status.into(),
+    }
+}
+
+fn proto_to_submodule(proto: &proto::GitSubmodule) -> Result<Submodule> {
+    use proto::git_submodule::Status;
+    let status = match proto.status() {
+        Status::Uninitialized => SubmoduleStatus::Uninitialized,
+        Status::UpToDate => SubmoduleStatus::UpToDate,
+        Status::OutOfDate => SubmoduleStatus::OutOfDate,
+        Status::Conflicted => SubmoduleStatus::Conflicted,
+    };
+    Ok(Submodule {
+        name: proto.name.clone().into(),
+        path: RepoPath::from_proto(&proto.path)?,
+        url: proto.url.clone().map(SharedString::from),
+        recorded_sha: proto.recorded_sha.clone().into(),
+        checked_out_sha: proto.checked_out_sha.clone().map(SharedString::from),
+        status,
+    })
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
@@ -6223,6 +7829,31 @@ async fn compute_snapshot(
 
     let remote_origin_url = backend.remote_url("origin").await;
     let remote_upstream_url = backend.remote_url("upstream").await;
+    if remote_origin_url != prev_snapshot.remote_origin_url
+        || remote_upstream_url != prev_snapshot.remote_upstream_url
+    {
+        events.push(RepositoryEvent::RemotesChanged);
+    }
+
+    let rebase = backend.rebase_status().await.log_err().flatten();
+    if rebase != prev_snapshot.rebase {
+        events.push(RepositoryEvent::RebaseStatusChanged);
+    }
+
+    let submodules: Arc<[Submodule]> = backend
+        .submodules()
+        .await
+        .log_err()
+        .unwrap_or_default()
+        .into();
+    if submodules != prev_snapshot.submodules {
+        events.push(RepositoryEvent::SubmodulesChanged);
+    }
+
+    let bisect = backend.bisect_status().await.log_err().flatten();
+    if bisect != prev_snapshot.bisect {
+        events.push(RepositoryEvent::BisectStatusChanged);
+    }
 
     let snapshot = RepositorySnapshot {
         id,
@@ -6236,6 +7867,9 @@ async fn compute_snapshot(
         remote_origin_url,
         remote_upstream_url,
         stash_entries,
+        rebase,
+        submodules,
+        bisect,
     };
 
     Ok((snapshot, events))
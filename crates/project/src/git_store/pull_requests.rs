@@ -0,0 +1,185 @@
+use anyhow::{Context as _, Result};
+use askpass::AskPassDelegate;
+use git::{
+    GitHostingProviderRegistry, GitRemote, PullRequestDetails, ReviewComment, parse_git_remote_url,
+    repository::{FetchOptions, Remote},
+};
+use gpui::{App, AppContext as _, Context, SharedString, Task};
+use util::ResultExt as _;
+
+use super::{Repository, RepositoryEvent};
+
+impl Repository {
+    /// The remote that pull requests are opened against, preferring `upstream` over `origin` so
+    /// that forks show the pull requests of the repository they were forked from.
+    pub fn hosting_remote(&self, cx: &App) -> Option<(Remote, GitRemote)> {
+        let (name, url) = self
+            .snapshot
+            .remote_upstream_url
+            .as_ref()
+            .map(|url| ("upstream", url))
+            .or_else(|| {
+                self.snapshot
+                    .remote_origin_url
+                    .as_ref()
+                    .map(|url| ("origin", url))
+            })?;
+        let provider_registry = GitHostingProviderRegistry::try_global(cx)?;
+        let (host, parsed) = parse_git_remote_url(provider_registry, url)?;
+        let remote = GitRemote {
+            host,
+            owner: parsed.owner.into(),
+            repo: parsed.repo.into(),
+        };
+        Some((Remote { name: name.into() }, remote))
+    }
+
+    /// The owner of the repository that branches are pushed to. In forks, that's the owner of
+    /// `origin` rather than of the repository that pull requests are opened against.
+    fn push_owner(&self, cx: &App) -> Option<String> {
+        let url = self.snapshot.remote_origin_url.as_ref()?;
+        let provider_registry = GitHostingProviderRegistry::try_global(cx)?;
+        let (_, parsed) = parse_git_remote_url(provider_registry, url)?;
+        Some(parsed.owner.to_string())
+    }
+
+    fn pull_request_remote(&self, cx: &App) -> Option<(Remote, GitRemote)> {
+        self.hosting_remote(cx)
+            .filter(|(_, remote)| remote.host_supports_pull_requests())
+    }
+
+    /// The open pull request for the checked-out branch, if any.
+    pub fn pull_request(&self) -> Option<&PullRequestDetails> {
+        self.pull_request.as_ref()
+    }
+
+    /// Looks up the pull request for the checked-out branch on the hosting provider.
+    pub fn refresh_pull_request(&mut self, cx: &mut Context<Self>) {
+        let branch = self.branch.as_ref().map(|branch| branch.name().to_string());
+        let (Some(branch), Some((_, remote))) = (branch, self.pull_request_remote(cx)) else {
+            self.pull_request_task = Task::ready(());
+            self.set_pull_request(None, cx);
+            return;
+        };
+        let head_owner = self
+            .push_owner(cx)
+            .unwrap_or_else(|| remote.owner.to_string());
+        let http_client = cx.http_client();
+        self.pull_request_task = cx.spawn(async move |this, cx| {
+            let pull_request = remote
+                .pull_request_for_branch(&head_owner, &branch, http_client)
+                .await
+                .log_err()
+                .flatten();
+            this.update(cx, |this, cx| this.set_pull_request(pull_request, cx))
+                .ok();
+        });
+    }
+
+    fn set_pull_request(
+        &mut self,
+        pull_request: Option<PullRequestDetails>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.pull_request != pull_request {
+            self.pull_request = pull_request;
+            cx.emit(RepositoryEvent::PullRequestChanged);
+        }
+    }
+
+    /// Lists the open pull requests of the repository on its hosting provider.
+    pub fn pull_requests(&self, cx: &App) -> Task<Result<Vec<PullRequestDetails>>> {
+        let Some((_, remote)) = self.pull_request_remote(cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let http_client = cx.http_client();
+        cx.background_spawn(async move { remote.pull_requests(http_client).await })
+    }
+
+    /// Returns the review comments left on the given pull request.
+    pub fn pull_request_review_comments(
+        &self,
+        number: u32,
+        cx: &App,
+    ) -> Task<Result<Vec<ReviewComment>>> {
+        let Some((_, remote)) = self.pull_request_remote(cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let http_client = cx.http_client();
+        cx.background_spawn(async move { remote.review_comments(number, http_client).await })
+    }
+
+    /// Fetches the head of a pull request and checks it out on a local branch named after the
+    /// pull request's branch.
+    ///
+    /// A local branch with that name is only reused if it points at the pull request's head.
+    /// Otherwise the pull request is checked out on `pr/<number>`, so that unrelated local work
+    /// is left alone.
+    pub fn checkout_pull_request(
+        &mut self,
+        pull_request: &PullRequestDetails,
+        askpass: AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some((remote, git_remote)) = self.pull_request_remote(cx) else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "the repository's remote does not support pull requests"
+            )));
+        };
+        let Some(pull_request_ref) = git_remote.host.pull_request_ref(pull_request.number) else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "{} does not support checking out pull requests",
+                git_remote.host.name()
+            )));
+        };
+        // Keep fetched pull requests out of `refs/remotes`, so that the local branch doesn't
+        // track a branch that doesn't exist on the remote.
+        let number = pull_request.number;
+        let local_ref = format!("refs/pull/{}/{number}", remote.name);
+        let head_branch = pull_request.head_branch.to_string();
+        let head_sha = pull_request.head_sha.clone();
+        let fetch = self.fetch(
+            FetchOptions::Refspec {
+                remote,
+                refspec: SharedString::from(format!("+{pull_request_ref}:{local_ref}")),
+            },
+            askpass,
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            fetch
+                .await?
+                .with_context(|| format!("fetching pull request #{number}"))?;
+            let branches = this.update(cx, |this, _| this.branches())?.await??;
+            // Whether a local branch exists, and if so, whether it's at the pull request's head.
+            let branch_at_head = |name: &str| {
+                branches
+                    .iter()
+                    .find(|branch| !branch.is_remote() && branch.name() == name)
+                    .map(|branch| {
+                        branch
+                            .most_recent_commit
+                            .as_ref()
+                            .is_some_and(|commit| commit.sha == head_sha)
+                    })
+            };
+            let fallback_branch = format!("pr/{number}");
+            let checkout = this.update(cx, |this, _| {
+                [head_branch, fallback_branch]
+                    .into_iter()
+                    .find_map(|branch_name| match branch_at_head(&branch_name) {
+                        None => Some(this.create_branch(branch_name, Some(local_ref.clone()))),
+                        Some(true) => Some(this.change_branch(branch_name)),
+                        Some(false) => None,
+                    })
+            })?;
+            let Some(checkout) = checkout else {
+                anyhow::bail!(
+                    "local branches named after pull request #{number} already exist and point \
+                    elsewhere, delete or rename them to check it out"
+                );
+            };
+            checkout.await?
+        })
+    }
+}
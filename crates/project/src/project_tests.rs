@@ -16,6 +16,7 @@ use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
+    fake_hosting_provider::FakeGitHostingProvider,
     repository::{RepoPath, repo_path},
     status::{StatusCode, TrackedStatus},
 };
@@ -310,6 +311,70 @@ async fn test_git_provider_project_setting(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_pull_request_for_current_branch(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let provider = FakeGitHostingProvider::new("https://git.example.com");
+    cx.update(|cx| {
+        GitHostingProviderRegistry::default_global(cx).register_hosting_provider(provider.clone());
+    });
+    provider.set_pull_requests(vec![
+        provider.pull_request(1, "Add a feature", "feature"),
+        provider.pull_request(2, "Fix a bug", "bugfix"),
+    ]);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/root/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    project
+        .update(cx, |project, cx| project.git_scans_complete(cx))
+        .await;
+    let repo = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    repo.read_with(cx, |repo, _| assert_eq!(repo.pull_request(), None));
+
+    // The pull request is looked up once the repository has a remote on the provider.
+    fs.set_remote_for_repo(dot_git, "origin", "https://git.example.com/owner/repo.git");
+    cx.run_until_parked();
+    repo.read_with(cx, |repo, _| assert_eq!(repo.pull_request(), None));
+
+    fs.set_branch_name(dot_git, Some("bugfix"));
+    cx.run_until_parked();
+    repo.read_with(cx, |repo, _| {
+        assert_eq!(
+            repo.pull_request().map(|pull_request| pull_request.number),
+            Some(2)
+        );
+    });
+
+    let pull_requests = repo
+        .read_with(cx, |repo, cx| repo.pull_requests(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        pull_requests
+            .iter()
+            .map(|pull_request| pull_request.head_branch.as_ref())
+            .collect::<Vec<_>>(),
+        ["feature", "bugfix"]
+    );
+
+    fs.set_branch_name(dot_git, Some("main"));
+    cx.run_until_parked();
+    repo.read_with(cx, |repo, _| assert_eq!(repo.pull_request(), None));
+}
+
 #[gpui::test]
 async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -16,6 +16,7 @@ use editor::{
 use file_icons::FileIcons;
 use git;
 use git::status::GitSummary;
+use git::submodule::Submodule;
 use git_ui;
 use git_ui::file_diff_view::FileDiffView;
 use gpui::{
@@ -131,6 +132,7 @@ pub struct ProjectPanel {
     width: Option<Pixels>,
     pending_serialization: Task<Option<()>>,
     diagnostics: HashMap<(WorktreeId, Arc<RelPath>), DiagnosticSeverity>,
+    submodules: HashMap<(WorktreeId, Arc<RelPath>), Submodule>,
     diagnostic_summary_update: Task<()>,
     // We keep track of the mouse down state on entries so we don't flash the UI
     // in case a user clicks to open a file.
@@ -228,6 +230,7 @@ struct EntryDetails {
     filename_text_color: Color,
     diagnostic_severity: Option<DiagnosticSeverity>,
     git_status: GitSummary,
+    submodule: Option<Submodule>,
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Arc<Path>>,
@@ -588,12 +591,19 @@ impl ProjectPanel {
                 &git_store,
                 window,
                 |this, _, event, window, cx| match event {
-                    GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::StatusesChanged, _)
-                    | GitStoreEvent::RepositoryAdded
-                    | GitStoreEvent::RepositoryRemoved(_) => {
+                    GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::StatusesChanged, _) => {
                         this.update_visible_entries(None, false, false, window, cx);
                         cx.notify();
                     }
+                    GitStoreEvent::RepositoryAdded | GitStoreEvent::RepositoryRemoved(_) => {
+                        this.update_submodules(cx);
+                        this.update_visible_entries(None, false, false, window, cx);
+                        cx.notify();
+                    }
+                    GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::SubmodulesChanged, _) => {
+                        this.update_submodules(cx);
+                        cx.notify();
+                    }
                     _ => {}
                 },
             )
@@ -802,6 +812,7 @@ impl ProjectPanel {
                 width: None,
                 pending_serialization: Task::ready(None),
                 diagnostics: Default::default(),
+                submodules: Default::default(),
                 diagnostic_summary_update: Task::ready(()),
                 scroll_handle,
                 mouse_down: false,
@@ -821,6 +832,7 @@ impl ProjectPanel {
                 },
                 update_visible_entries_task: Default::default(),
             };
+            this.update_submodules(cx);
             this.update_visible_entries(None, false, false, window, cx);
 
             this
@@ -986,6 +998,25 @@ impl ProjectPanel {
         self.diagnostics = diagnostics;
     }
 
+    fn update_submodules(&mut self, cx: &mut Context<Self>) {
+        let mut submodules = HashMap::default();
+        let git_store = self.project.read(cx).git_store().read(cx);
+        for repository in git_store.repositories().values() {
+            let repository = repository.read(cx);
+            for submodule in repository.submodules.iter() {
+                if let Some(project_path) =
+                    repository.repo_path_to_project_path(&submodule.path, cx)
+                {
+                    submodules.insert(
+                        (project_path.worktree_id, project_path.path),
+                        submodule.clone(),
+                    );
+                }
+            }
+        }
+        self.submodules = submodules;
+    }
+
     fn update_strongest_diagnostic_severity(
         diagnostics: &mut HashMap<(WorktreeId, Arc<RelPath>), DiagnosticSeverity>,
         project_path: &ProjectPath,
@@ -4580,6 +4611,7 @@ impl ProjectPanel {
             .canonical_path
             .as_ref()
             .map(|f| f.to_string_lossy().into_owned());
+        let submodule = details.submodule.clone();
         let path_style = self.project.read(cx).path_style(cx);
         let path = details.path.clone();
         let path_for_external_paths = path.clone();
@@ -4961,24 +4993,36 @@ impl ProjectPanel {
                         }
                     })
                     .selectable(false)
-                    .when_some(canonical_path, |this, path| {
+                    .when(submodule.is_some() || canonical_path.is_some(), |this| {
                         this.end_slot::<AnyElement>(
-                            div()
-                                .id("symlink_icon")
+                            h_flex()
+                                .gap_1()
                                 .pr_3()
-                                .tooltip(move |_window, cx| {
-                                    Tooltip::with_meta(
-                                        path.to_string(),
-                                        None,
-                                        "Symbolic Link",
-                                        cx,
+                                .when_some(submodule, |this, submodule| {
+                                    this.child(git_ui::submodule_picker::submodule_indicator(
+                                        "submodule_icon",
+                                        &submodule,
+                                    ))
+                                })
+                                .when_some(canonical_path, |this, path| {
+                                    this.child(
+                                        div()
+                                            .id("symlink_icon")
+                                            .tooltip(move |_window, cx| {
+                                                Tooltip::with_meta(
+                                                    path.to_string(),
+                                                    None,
+                                                    "Symbolic Link",
+                                                    cx,
+                                                )
+                                            })
+                                            .child(
+                                                Icon::new(IconName::ArrowUpRight)
+                                                    .size(IconSize::Indicator)
+                                                    .color(filename_text_color),
+                                            ),
                                     )
                                 })
-                                .child(
-                                    Icon::new(IconName::ArrowUpRight)
-                                        .size(IconSize::Indicator)
-                                        .color(filename_text_color),
-                                )
                                 .into_any_element(),
                         )
                     })
@@ -5302,6 +5346,11 @@ impl ProjectPanel {
             .get(&(worktree_id, entry.path.clone()))
             .cloned();
 
+        let submodule = self
+            .submodules
+            .get(&(worktree_id, entry.path.clone()))
+            .cloned();
+
         let filename_text_color =
             entry_git_aware_label_color(git_status, entry.is_ignored, is_marked);
 
@@ -5327,6 +5376,7 @@ impl ProjectPanel {
             filename_text_color,
             diagnostic_severity,
             git_status,
+            submodule,
             is_private: entry.is_private,
             worktree_id,
             canonical_path: entry.canonical_path.clone(),
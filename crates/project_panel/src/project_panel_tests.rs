@@ -4287,6 +4287,77 @@ async fn test_drag_entries_between_different_worktrees(cx: &mut gpui::TestAppCon
     );
 }
 
+#[gpui::test]
+async fn test_submodules(cx: &mut gpui::TestAppContext) {
+    init_test_with_editor(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            ".git": {},
+            ".gitmodules": "",
+            "libs": {
+                "dep": {},
+                "other": {},
+            },
+        }),
+    )
+    .await;
+    let submodule = |path: &str| git::submodule::Submodule {
+        name: path.to_string().into(),
+        path: git::repository::RepoPath::new(path).unwrap(),
+        url: None,
+        recorded_sha: "abc".into(),
+        checked_out_sha: None,
+        status: git::submodule::SubmoduleStatus::Uninitialized,
+    };
+    fs.with_git_state(path!("/root/.git").as_ref(), false, |state| {
+        state.submodules = vec![submodule("libs/dep")];
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    cx.run_until_parked();
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+
+    // Repositories that were loaded before the panel was created are picked up.
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+    cx.run_until_parked();
+    let submodule_paths = |panel: &Entity<ProjectPanel>, cx: &mut VisualTestContext| {
+        panel.read_with(cx, |panel, _| {
+            let mut paths = panel
+                .submodules
+                .keys()
+                .map(|(worktree_id, path)| (*worktree_id, path.as_unix_str().to_string()))
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        })
+    };
+    assert_eq!(
+        submodule_paths(&panel, cx),
+        [(worktree_id, "libs/dep".to_string())]
+    );
+
+    fs.with_git_state(path!("/root/.git").as_ref(), true, |state| {
+        state.submodules = vec![submodule("libs/dep"), submodule("libs/other")];
+    })
+    .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        submodule_paths(&panel, cx),
+        [
+            (worktree_id, "libs/dep".to_string()),
+            (worktree_id, "libs/other".to_string()),
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_autoreveal_and_gitignored_files(cx: &mut gpui::TestAppContext) {
     init_test_with_editor(cx);
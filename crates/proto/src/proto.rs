@@ -345,7 +345,32 @@ messages!(
     (RemoteStarted, Background),
     (GitGetWorktrees, Background),
     (GitWorktreesResponse, Background),
-    (GitCreateWorktree, Background)
+    (GitCreateWorktree, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitRebaseStart, Background),
+    (GitRebaseEditTodo, Background),
+    (GitRebaseControl, Background),
+    (GitApplyCommits, Background),
+    (GitSequencerControl, Background),
+    (GitSequencerResponse, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTag, Background),
+    (GitInitSubmodules, Background),
+    (GitSyncSubmodules, Background),
+    (GitUpdateSubmodules, Background),
+    (GitBisectStart, Background),
+    (GitBisectMark, Background),
+    (GitBisectReset, Background),
+    (GitLoadConflictStages, Background),
+    (GitLoadConflictStagesResponse, Background),
+    (GitGetCommitSignature, Background),
+    (GitCommitSignatureResponse, Background),
 );
 
 request_messages!(
@@ -534,6 +559,25 @@ request_messages!(
     (GitCreateWorktree, Ack),
     (TrustWorktrees, Ack),
     (RestrictWorktrees, Ack),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitRebaseStart, Ack),
+    (GitRebaseEditTodo, Ack),
+    (GitRebaseControl, Ack),
+    (GitApplyCommits, GitSequencerResponse),
+    (GitSequencerControl, GitSequencerResponse),
+    (GitLog, GitLogResponse),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
+    (GitInitSubmodules, Ack),
+    (GitSyncSubmodules, Ack),
+    (GitUpdateSubmodules, RemoteMessageResponse),
+    (GitBisectStart, Ack),
+    (GitBisectMark, Ack),
+    (GitBisectReset, Ack),
+    (GitLoadConflictStages, GitLoadConflictStagesResponse),
+    (GitGetCommitSignature, GitCommitSignatureResponse),
 );
 
 lsp_messages!(
@@ -709,6 +753,25 @@ entity_messages!(
     GitCreateWorktree,
     TrustWorktrees,
     RestrictWorktrees,
+    GitRebaseTodo,
+    GitRebaseStart,
+    GitRebaseEditTodo,
+    GitRebaseControl,
+    GitApplyCommits,
+    GitSequencerControl,
+    GitLog,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTag,
+    GitInitSubmodules,
+    GitSyncSubmodules,
+    GitUpdateSubmodules,
+    GitBisectStart,
+    GitBisectMark,
+    GitBisectReset,
+    GitLoadConflictStages,
+    GitGetCommitSignature,
 );
 
 entity_messages!(
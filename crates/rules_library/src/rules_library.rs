@@ -1104,6 +1104,7 @@ impl RulesLibrary {
                                     stop: Vec::new(),
                                     temperature: None,
                                     thinking_allowed: true,
+                                    max_output_tokens: None,
                                 },
                                 cx,
                             )
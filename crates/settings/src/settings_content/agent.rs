@@ -119,6 +119,17 @@ pub struct AgentSettingsContent {
     ///
     /// Default: 4
     pub message_editor_min_lines: Option<usize>,
+    /// Whether to summarize the older turns of a thread when it gets close to the model's
+    /// context window.
+    ///
+    /// Default: true
+    pub auto_compact: Option<bool>,
+    /// Commands to run when the agent starts a thread, calls tools, or ends its turn. Each
+    /// command receives a JSON description of the event on stdin.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub hooks: Vec<AgentHookContent>,
 }
 
 impl AgentSettingsContent {
@@ -203,6 +214,73 @@ pub struct AgentProfileContent {
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
     /// The default language model selected when using this profile.
     pub default_model: Option<LanguageModelSelection>,
+    /// Rules that decide whether the agent's tool calls run right away, are denied, or wait for
+    /// your confirmation. The first rule that matches a tool call applies; tool calls that no rule
+    /// matches fall back to `always_allow_tool_actions`.
+    #[serde(default)]
+    pub tool_permissions: Vec<ToolPermissionRuleContent>,
+}
+
+#[with_fallible_options]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct ToolPermissionRuleContent {
+    /// The name of the tool the rule applies to, or `*` for every tool.
+    pub tool: Arc<str>,
+    /// A glob that the tool call's subject must match for the rule to apply, like `cargo test*`
+    /// for the terminal tool's command or `src/**` for the path edited by the edit file tool.
+    ///
+    /// When omitted, the rule applies to every call of the tool.
+    pub pattern: Option<String>,
+    /// What to do with the tool calls the rule applies to.
+    pub action: ToolPermissionAction,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermissionAction {
+    /// Run the tool call without asking for confirmation.
+    Allow,
+    /// Refuse to run the tool call.
+    Deny,
+    /// Ask for confirmation, even if `always_allow_tool_actions` is enabled.
+    Ask,
+}
+
+#[with_fallible_options]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The event that runs the hook.
+    pub event: AgentHookEvent,
+    /// The shell command to run. It runs in the root directory of the project's first worktree.
+    pub command: String,
+    /// The names of the tools whose calls run the hook. Only applies to the `pre_tool_call`,
+    /// `post_tool_call` and `post_edit` events.
+    ///
+    /// Default: every tool
+    pub tools: Option<Vec<Arc<str>>>,
+    /// How many seconds the command may run before it's stopped and treated as failed.
+    ///
+    /// Default: 60
+    pub timeout: Option<u64>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentHookEvent {
+    /// Before the first turn of a new thread. The output of hooks that succeed is added to the
+    /// thread for the model to read.
+    ThreadStart,
+    /// Before a tool call runs. A hook that fails blocks the tool call, and its output is sent to
+    /// the model instead of the tool's result.
+    PreToolCall,
+    /// After a tool call finishes. The output of hooks that fail is sent to the model along with
+    /// the tool's result.
+    PostToolCall,
+    /// After a tool call that edits, moves or deletes files succeeds. Works like `post_tool_call`.
+    PostEdit,
+    /// When the agent is about to end its turn. The output of hooks that fail is sent to the
+    /// model, which continues its turn to address it.
+    TurnEnd,
 }
 
 #[with_fallible_options]
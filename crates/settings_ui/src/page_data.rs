@@ -6195,6 +6195,21 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                         metadata: None,
                         files: USER,
                     }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Auto Compact",
+                        description: "Whether to summarize the older turns of a thread when it gets close to the model's context window.",
+                        field: Box::new(SettingField {
+                            json_path: Some("agent.auto_compact"),
+                            pick: |settings_content| {
+                                settings_content.agent.as_ref()?.auto_compact.as_ref()
+                            },
+                            write: |settings_content, value| {
+                                settings_content.agent.get_or_insert_default().auto_compact = value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
                 ];
                 items.extend(edit_prediction_language_settings_section());
                 items.extend(
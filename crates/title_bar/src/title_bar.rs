@@ -24,6 +24,7 @@ use auto_update::AutoUpdateStatus;
 use call::ActiveCall;
 use client::{Client, UserStore, zed_urls};
 use cloud_llm_client::{Plan, PlanV1, PlanV2};
+use git::PullRequestChecks;
 use gpui::{
     Action, AnyElement, App, Context, Corner, Element, Entity, Focusable, InteractiveElement,
     IntoElement, MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled,
@@ -189,7 +190,9 @@ impl Render for TitleBar {
                                         .child(self.render_project_name(window, cx))
                                 })
                                 .when(title_bar_settings.show_branch_name, |title_bar| {
-                                    title_bar.children(self.render_project_repo(window, cx))
+                                    title_bar
+                                        .children(self.render_project_repo(window, cx))
+                                        .children(self.render_pull_request(cx))
                                 })
                         })
                 })
@@ -713,6 +716,40 @@ impl TitleBar {
         )
     }
 
+    /// Shows the pull request opened from the checked-out branch, colored by its checks.
+    pub fn render_pull_request(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let repository = self.project.read(cx).active_repository(cx)?;
+        let pull_request = repository.read(cx).pull_request()?.clone();
+
+        let icon_color = match pull_request.checks {
+            Some(PullRequestChecks::Passing) => Color::Success,
+            Some(PullRequestChecks::Failing) => Color::Error,
+            Some(PullRequestChecks::Pending) => Color::Warning,
+            None => Color::Muted,
+        };
+        let title = pull_request.title.clone();
+        let meta: SharedString = match pull_request.checks {
+            Some(checks) => format!("{} · {}", pull_request.state.label(), checks.label()),
+            None => pull_request.state.label().to_string(),
+        }
+        .into();
+        let url = pull_request.url.to_string();
+
+        Some(
+            Button::new("project_pull_request", format!("#{}", pull_request.number))
+                .label_size(LabelSize::Small)
+                .color(Color::Muted)
+                .icon(IconName::PullRequest)
+                .icon_position(IconPosition::Start)
+                .icon_color(icon_color)
+                .icon_size(IconSize::Indicator)
+                .tooltip(move |_window, cx| {
+                    Tooltip::with_meta(title.clone(), None, meta.clone(), cx)
+                })
+                .on_click(move |_, _, cx| cx.open_url(&url)),
+        )
+    }
+
     fn window_activation_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if window.is_window_active() {
             ActiveCall::global(cx)
@@ -638,6 +638,7 @@ fn main() {
         project_panel::init(cx);
         outline_panel::init(cx);
         tasks_ui::init(cx);
+        editor_mcp_server::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
         search::init(cx);
@@ -744,10 +745,24 @@ fn main() {
         #[cfg(not(target_os = "windows"))]
         let wsl = None;
 
-        if !urls.is_empty() || !diff_paths.is_empty() {
+        let merge_paths: Vec<[String; 4]> = args
+            .merge
+            .chunks(4)
+            .map(|chunk| {
+                [
+                    chunk[0].clone(),
+                    chunk[1].clone(),
+                    chunk[2].clone(),
+                    chunk[3].clone(),
+                ]
+            })
+            .collect();
+
+        if !urls.is_empty() || !diff_paths.is_empty() || !merge_paths.is_empty() {
             open_listener.open(RawOpenRequest {
                 urls,
                 diff_paths,
+                merge_paths,
                 wsl,
             })
         }
@@ -956,6 +971,7 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
                     let (workspace, _results) = open_paths_with_positions(
                         &paths_with_position,
                         &[],
+                        &[],
                         app_state,
                         workspace::OpenOptions::default(),
                         cx,
@@ -1009,7 +1025,10 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
     }
 
     let mut task = None;
-    if !request.open_paths.is_empty() || !request.diff_paths.is_empty() {
+    if !request.open_paths.is_empty()
+        || !request.diff_paths.is_empty()
+        || !request.merge_paths.is_empty()
+    {
         let app_state = app_state.clone();
         task = Some(cx.spawn(async move |cx| {
             let paths_with_position =
@@ -1017,6 +1036,7 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
             let (_window, results) = open_paths_with_positions(
                 &paths_with_position,
                 &request.diff_paths,
+                &request.merge_paths,
                 app_state,
                 workspace::OpenOptions::default(),
                 cx,
@@ -1376,6 +1396,10 @@ struct Args {
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
 
+    /// Resolves a three-way merge, writing the result to OUTPUT_PATH. Can be specified multiple times.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 4, value_names = ["BASE_PATH", "LOCAL_PATH", "REMOTE_PATH", "OUTPUT_PATH"])]
+    merge: Vec<String>,
+
     /// Sets a custom directory for all user data (e.g., database, extensions, logs).
     ///
     /// This overrides the default platform-specific data directory location.
@@ -14,6 +14,7 @@ use futures::future;
 use futures::future::join_all;
 use futures::{FutureExt, SinkExt, StreamExt};
 use git_ui::file_diff_view::FileDiffView;
+use git_ui::merge_view::{MergeView, has_conflict_markers};
 use gpui::{App, AsyncApp, Global, WindowHandle};
 use language::Point;
 use onboarding::FIRST_OPEN;
@@ -37,6 +38,7 @@ pub struct OpenRequest {
     pub kind: Option<OpenRequestKind>,
     pub open_paths: Vec<String>,
     pub diff_paths: Vec<[String; 2]>,
+    pub merge_paths: Vec<[String; 4]>,
     pub open_channel_notes: Vec<(u64, Option<String>)>,
     pub join_channel: Option<u64>,
     pub remote_connection: Option<RemoteConnectionOptions>,
@@ -75,6 +77,7 @@ impl OpenRequest {
         let mut this = Self::default();
 
         this.diff_paths = request.diff_paths;
+        this.merge_paths = request.merge_paths;
         if let Some(wsl) = request.wsl {
             let (user, distro_name) = if let Some((user, distro)) = wsl.split_once('@') {
                 if user.is_empty() {
@@ -238,6 +241,7 @@ pub struct OpenListener(UnboundedSender<RawOpenRequest>);
 pub struct RawOpenRequest {
     pub urls: Vec<String>,
     pub diff_paths: Vec<[String; 2]>,
+    pub merge_paths: Vec<[String; 4]>,
     pub wsl: Option<String>,
 }
 
@@ -314,6 +318,7 @@ fn connect_to_cli(
 pub async fn open_paths_with_positions(
     path_positions: &[PathWithPosition],
     diff_paths: &[[String; 2]],
+    merge_paths: &[[String; 4]],
     app_state: Arc<AppState>,
     open_options: workspace::OpenOptions,
     cx: &mut AsyncApp,
@@ -353,6 +358,27 @@ pub async fn open_paths_with_positions(
         }
     }
 
+    for [base_path, ours_path, theirs_path, output_path] in merge_paths {
+        let base_path = Path::new(base_path).canonicalize()?;
+        let ours_path = Path::new(ours_path).canonicalize()?;
+        let theirs_path = Path::new(theirs_path).canonicalize()?;
+        let output_path = Path::new(output_path).canonicalize()?;
+        if let Ok(merge_view) = workspace.update(cx, |workspace, window, cx| {
+            MergeView::open(
+                base_path,
+                ours_path,
+                theirs_path,
+                output_path,
+                workspace,
+                window,
+                cx,
+            )
+        }) && let Some(merge_view) = merge_view.await.log_err()
+        {
+            items.push(Some(Ok(Box::new(merge_view))))
+        }
+    }
+
     for (item, path) in items.iter_mut().zip(&paths) {
         if let Some(Err(error)) = item {
             *error = anyhow!("error opening {path:?}: {error}");
@@ -389,6 +415,7 @@ pub async fn handle_cli_connection(
                 urls,
                 paths,
                 diff_paths,
+                merge_paths,
                 wait,
                 wsl,
                 open_new_workspace,
@@ -402,6 +429,7 @@ pub async fn handle_cli_connection(
                             RawOpenRequest {
                                 urls,
                                 diff_paths,
+                                merge_paths,
                                 wsl,
                             },
                             cx,
@@ -427,6 +455,7 @@ pub async fn handle_cli_connection(
                 let open_workspace_result = open_workspaces(
                     paths,
                     diff_paths,
+                    merge_paths,
                     open_new_workspace,
                     reuse,
                     &responses,
@@ -447,6 +476,7 @@ pub async fn handle_cli_connection(
 async fn open_workspaces(
     paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Vec<[String; 4]>,
     open_new_workspace: Option<bool>,
     reuse: bool,
     responses: &IpcSender<CliResponse>,
@@ -455,7 +485,7 @@ async fn open_workspaces(
     env: Option<collections::HashMap<String, String>>,
     cx: &mut AsyncApp,
 ) -> Result<()> {
-    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() {
+    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() && merge_paths.is_empty() {
         // If no paths are provided, restore from previous workspaces unless a new workspace is requested with -n
         if open_new_workspace == Some(true) {
             Vec::new()
@@ -507,6 +537,7 @@ async fn open_workspaces(
                     let workspace_failed_to_open = open_local_workspace(
                         workspace_paths,
                         diff_paths.clone(),
+                        merge_paths.clone(),
                         open_new_workspace,
                         reuse,
                         wait,
@@ -554,6 +585,7 @@ async fn open_workspaces(
 async fn open_local_workspace(
     workspace_paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Vec<[String; 4]>,
     open_new_workspace: Option<bool>,
     reuse: bool,
     wait: bool,
@@ -580,6 +612,7 @@ async fn open_local_workspace(
     let (workspace, items) = match open_paths_with_positions(
         &paths_with_position,
         &diff_paths,
+        &merge_paths,
         app_state.clone(),
         workspace::OpenOptions {
             open_new_workspace,
@@ -610,7 +643,8 @@ async fn open_local_workspace(
     // If --wait flag is used with no paths, or a directory, then wait until
     // the entire workspace is closed.
     if wait {
-        let mut wait_for_window_close = paths_with_position.is_empty() && diff_paths.is_empty();
+        let mut wait_for_window_close =
+            paths_with_position.is_empty() && diff_paths.is_empty() && merge_paths.is_empty();
         for path_with_position in &paths_with_position {
             if app_state.fs.is_dir(&path_with_position.path).await {
                 wait_for_window_close = true;
@@ -679,6 +713,23 @@ async fn open_local_workspace(
                 }
             }
         }
+
+        // `git mergetool` treats a non-zero exit status as the user aborting the merge.
+        for [_, _, _, output_path] in &merge_paths {
+            let is_resolved = app_state
+                .fs
+                .load(Path::new(output_path))
+                .await
+                .is_ok_and(|text| !has_conflict_markers(&text));
+            if !is_resolved {
+                responses
+                    .send(CliResponse::Stderr {
+                        message: format!("the conflicts in {output_path} weren't resolved"),
+                    })
+                    .log_err();
+                errored = true;
+            }
+        }
     }
 
     errored
@@ -919,6 +970,7 @@ mod tests {
                 let errored = open_local_workspace(
                     workspace_paths,
                     vec![],
+                    vec![],
                     None,
                     false,
                     true,
@@ -1010,6 +1062,7 @@ mod tests {
                 open_local_workspace(
                     workspace_paths,
                     vec![],
+                    vec![],
                     open_new_workspace,
                     false,
                     false,
@@ -1083,6 +1136,7 @@ mod tests {
                     open_local_workspace(
                         workspace_paths,
                         vec![],
+                        vec![],
                         None,
                         false,
                         false,
@@ -1107,6 +1161,7 @@ mod tests {
                     open_local_workspace(
                         workspace_paths_reuse,
                         vec![],
+                        vec![],
                         None, // open_new_workspace will be overridden by reuse logic
                         true, // reuse = true
                         false,
@@ -122,6 +122,7 @@ fn send_args_to_instance(args: &Args) -> anyhow::Result<()> {
         let mut paths = vec![];
         let mut urls = vec![];
         let mut diff_paths = vec![];
+        let mut merge_paths = vec![];
         for path in args.paths_or_urls.iter() {
             match std::fs::canonicalize(&path) {
                 Ok(path) => paths.push(path.to_string_lossy().into_owned()),
@@ -151,10 +152,26 @@ fn send_args_to_instance(args: &Args) -> anyhow::Result<()> {
             }
         }
 
+        for paths in args.merge.chunks(4) {
+            let paths = paths
+                .iter()
+                .map(|path| std::fs::canonicalize(path).log_err())
+                .collect::<Option<Vec<_>>>();
+            if let Some([base, local, remote, output]) = paths.as_deref() {
+                merge_paths.push([
+                    base.to_string_lossy().into_owned(),
+                    local.to_string_lossy().into_owned(),
+                    remote.to_string_lossy().into_owned(),
+                    output.to_string_lossy().into_owned(),
+                ]);
+            }
+        }
+
         CliRequest::Open {
             paths,
             urls,
             diff_paths,
+            merge_paths,
             wait: false,
             wsl: args.wsl.clone(),
             open_new_workspace: None,
@@ -230,6 +230,10 @@ pub mod git {
             Branch,
             /// Opens the git stash selector.
             ViewStash,
+            /// Opens the git tag selector.
+            ViewTags,
+            /// Opens the git submodule selector.
+            ViewSubmodules,
             /// Opens the git worktree selector.
             Worktree
         ]